@@ -0,0 +1,4 @@
+// マイグレーションファイルの追加・変更時に再ビルドする
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+}
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS command_audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    guild_id TEXT,
+    actor_discord_id TEXT NOT NULL,
+    actor_name TEXT NOT NULL,
+    command TEXT NOT NULL,
+    arguments TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_command_audit_log_actor ON command_audit_log (actor_discord_id);
@@ -0,0 +1,12 @@
+-- 時給設定（user_id が NULL の行はギルドの既定値）
+CREATE TABLE IF NOT EXISTS rates (
+    id BIGSERIAL PRIMARY KEY,
+    guild_id TEXT NOT NULL,
+    user_id BIGINT REFERENCES users (id),
+    hourly_rate BIGINT NOT NULL,
+    overtime_rate BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_rates_guild_user ON rates (guild_id, user_id);
@@ -0,0 +1,2 @@
+ALTER TABLE guild_settings ADD COLUMN standard_daily_minutes INTEGER NOT NULL DEFAULT 480;
+ALTER TABLE guild_settings ADD COLUMN standard_weekly_minutes INTEGER NOT NULL DEFAULT 2400;
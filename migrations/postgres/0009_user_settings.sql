@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS user_settings (
+    user_id BIGINT PRIMARY KEY REFERENCES users (id),
+    tutorial_completed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 既存ユーザーにはチュートリアルを表示しない
+INSERT INTO user_settings (user_id, tutorial_completed_at) SELECT id, CURRENT_TIMESTAMP FROM users;
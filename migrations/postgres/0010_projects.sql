@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS projects (
+    id BIGSERIAL PRIMARY KEY,
+    guild_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (guild_id, name)
+);
+
+-- セッション再計算時にプロジェクトなどを引き継ぐため、開始記録のIDを保持する
+ALTER TABLE work_sessions ADD COLUMN start_record_id BIGINT;
+ALTER TABLE work_sessions ADD COLUMN project_id BIGINT REFERENCES projects (id);
@@ -0,0 +1,2 @@
+ALTER TABLE guild_settings ADD COLUMN quality_digest_channel_id TEXT;
+ALTER TABLE guild_settings ADD COLUMN quality_digest_last_sent DATE;
@@ -0,0 +1,18 @@
+ALTER TABLE guild_settings ADD COLUMN approval_channel_id TEXT;
+
+CREATE TABLE IF NOT EXISTS pending_changes (
+    id BIGSERIAL PRIMARY KEY,
+    guild_id TEXT NOT NULL,
+    user_id BIGINT NOT NULL REFERENCES users (id),
+    requested_by TEXT NOT NULL,
+    action TEXT NOT NULL,
+    record_id BIGINT,
+    record_type TEXT NOT NULL,
+    old_timestamp TIMESTAMPTZ,
+    new_timestamp TIMESTAMPTZ NOT NULL,
+    source TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    reviewed_by TEXT,
+    reviewed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
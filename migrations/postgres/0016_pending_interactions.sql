@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS pending_interactions (
+    id BIGSERIAL PRIMARY KEY,
+    discord_user_id TEXT NOT NULL,
+    action TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_pending_interactions_created_at ON pending_interactions (created_at);
@@ -0,0 +1,9 @@
+-- ユーザーごと・サーバーごとの既定のプロジェクトと、前回使ったプロジェクト
+CREATE TABLE IF NOT EXISTS project_preferences (
+    user_id BIGINT NOT NULL REFERENCES users (id),
+    guild_id TEXT NOT NULL,
+    default_project_id BIGINT REFERENCES projects (id),
+    last_project_id BIGINT REFERENCES projects (id),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, guild_id)
+);
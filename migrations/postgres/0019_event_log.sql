@@ -0,0 +1,7 @@
+-- 記録を変更したサーバー（勤怠ログチャンネルへの投稿先の判定用、DM・APIからの操作は NULL）
+ALTER TABLE audit_log ADD COLUMN guild_id TEXT;
+CREATE INDEX IF NOT EXISTS idx_audit_log_guild_id ON audit_log (guild_id, id);
+
+ALTER TABLE guild_settings ADD COLUMN event_log_channel_id TEXT;
+-- 勤怠ログチャンネルに投稿済みの最後の監査ログID
+ALTER TABLE guild_settings ADD COLUMN event_log_last_id BIGINT;
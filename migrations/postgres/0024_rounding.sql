@@ -0,0 +1,6 @@
+-- `/rounding set` で設定する打刻の丸め単位（分、NULL で丸めない）
+ALTER TABLE user_settings ADD COLUMN rounding_minutes INTEGER;
+
+-- 丸める前の勤務時間（total_minutes には丸めた後の時間を保存する）
+ALTER TABLE work_sessions ADD COLUMN raw_minutes INTEGER;
+UPDATE work_sessions SET raw_minutes = total_minutes;
@@ -0,0 +1,13 @@
+-- `/schedule set` で登録する曜日ごとの開始予定時刻
+CREATE TABLE IF NOT EXISTS work_schedules (
+    user_id BIGINT NOT NULL REFERENCES users (id),
+    weekday INTEGER NOT NULL, -- 0 = 月曜 … 6 = 日曜
+    start_minutes INTEGER NOT NULL, -- JST 0時からの分数
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, weekday)
+);
+
+-- 開始予定時刻までに出勤がなければDMで知らせる
+ALTER TABLE user_settings ADD COLUMN schedule_nudge_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE user_settings ADD COLUMN schedule_nudge_last_sent DATE;
@@ -0,0 +1,13 @@
+-- `/shift add` で予定する勤務シフト（日をまたぐシフトは終了が翌日になる）
+CREATE TABLE IF NOT EXISTS shifts (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users (id),
+    guild_id TEXT,
+    start_time TIMESTAMPTZ NOT NULL,
+    end_time TIMESTAMPTZ NOT NULL,
+    created_by TEXT NOT NULL,
+    reminded BOOLEAN NOT NULL DEFAULT FALSE, -- 開始時刻を過ぎても出勤がないときのDMを送ったか
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_shifts_user_start ON shifts (user_id, start_time);
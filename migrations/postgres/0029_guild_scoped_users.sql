@@ -0,0 +1,30 @@
+-- 同じDiscordユーザーでもサーバーごとに勤怠を分けるため、ユーザーをサーバー単位にする（DMでの利用は guild_id = ''）
+ALTER TABLE users ADD COLUMN guild_id TEXT NOT NULL DEFAULT '';
+
+-- 既存のユーザーは操作の記録が最も多いサーバーに割り当てる
+-- （記録がなければ、登録済みのサーバーが1つだけの場合はそのサーバー、それ以外はDM扱い）
+UPDATE users SET guild_id = COALESCE(
+    (SELECT guild_id FROM (
+        SELECT guild_id FROM command_audit_log
+        WHERE actor_discord_id = users.discord_id AND guild_id IS NOT NULL
+        UNION ALL
+        SELECT guild_id FROM audit_log
+        WHERE audit_log.user_id = users.id AND guild_id IS NOT NULL
+    ) AS used GROUP BY guild_id ORDER BY COUNT(*) DESC, guild_id ASC LIMIT 1),
+    (SELECT MIN(guild_id) FROM guild_settings HAVING COUNT(*) = 1),
+    ''
+);
+
+ALTER TABLE users DROP CONSTRAINT IF EXISTS users_discord_id_key;
+ALTER TABLE users ADD CONSTRAINT users_discord_id_guild_id_key UNIQUE (discord_id, guild_id);
+
+-- サーバー単位の集計用（ユーザーのサーバーと同じ値を記録時に入れる）
+ALTER TABLE attendance_records ADD COLUMN guild_id TEXT NOT NULL DEFAULT '';
+UPDATE attendance_records SET guild_id = users.guild_id
+FROM users WHERE users.id = attendance_records.user_id;
+
+ALTER TABLE work_sessions ADD COLUMN guild_id TEXT NOT NULL DEFAULT '';
+UPDATE work_sessions SET guild_id = users.guild_id
+FROM users WHERE users.id = work_sessions.user_id;
+
+CREATE INDEX IF NOT EXISTS idx_work_sessions_guild_date ON work_sessions (guild_id, date);
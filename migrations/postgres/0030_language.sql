@@ -0,0 +1,3 @@
+-- `/language` と `/admin language` で選ぶ表示言語（`ja` / `en`。未設定なら Discord のロケール）
+ALTER TABLE user_settings ADD COLUMN language TEXT;
+ALTER TABLE guild_settings ADD COLUMN language TEXT;
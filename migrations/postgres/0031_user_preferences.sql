@@ -0,0 +1,5 @@
+-- `/settings` で選ぶ個人設定
+ALTER TABLE user_settings ADD COLUMN ephemeral_responses BOOLEAN NOT NULL DEFAULT FALSE; -- /start・/end・/status を本人にだけ表示
+ALTER TABLE user_settings ADD COLUMN utc_offset_minutes INTEGER; -- 時刻の表示に使うUTCからの差（分）。NULL = 日本時間
+ALTER TABLE user_settings ADD COLUMN clock_12h BOOLEAN NOT NULL DEFAULT FALSE; -- 12時間表示
+ALTER TABLE user_settings ADD COLUMN private_reports BOOLEAN NOT NULL DEFAULT FALSE; -- 自分のレポートを本人にだけ表示
@@ -0,0 +1,3 @@
+-- `/dashboard create` で投稿し、定期的に更新するダッシュボードのメッセージ
+ALTER TABLE guild_settings ADD COLUMN dashboard_channel_id TEXT;
+ALTER TABLE guild_settings ADD COLUMN dashboard_message_id TEXT;
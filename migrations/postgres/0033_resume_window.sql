@@ -0,0 +1,2 @@
+-- `/resume` で取り消せる終了記録の期限（終了からの分数）
+ALTER TABLE guild_settings ADD COLUMN resume_window_minutes INTEGER NOT NULL DEFAULT 10;
@@ -0,0 +1,4 @@
+-- 記録を追加した経路（slash_command / button / modal / api / import）。この列より前の記録は不明（NULL）
+ALTER TABLE attendance_records ADD COLUMN source TEXT;
+
+UPDATE attendance_records SET source = 'import' WHERE is_imported = TRUE;
@@ -0,0 +1,17 @@
+-- 記録の時刻を修正するたびに修正前の時刻を残す（`original_timestamp` は最初の時刻だけ）
+CREATE TABLE IF NOT EXISTS record_revisions (
+    id BIGSERIAL PRIMARY KEY,
+    record_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL REFERENCES users (id),
+    previous_timestamp TIMESTAMPTZ NOT NULL,
+    new_timestamp TIMESTAMPTZ NOT NULL,
+    editor_discord_id TEXT NOT NULL,
+    source TEXT NOT NULL,
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_record_revisions_record_id ON record_revisions (record_id);
+
+-- 承認制で申請された修正の理由（承認時に修正履歴へ引き継ぐ）
+ALTER TABLE pending_changes ADD COLUMN reason TEXT;
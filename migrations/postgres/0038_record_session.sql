@@ -0,0 +1,9 @@
+-- 記録がペアになった勤務セッション（SessionManager の再計算で設定）
+ALTER TABLE attendance_records ADD COLUMN session_id BIGINT REFERENCES work_sessions (id) ON DELETE SET NULL;
+
+-- 既存の開始記録はセッションの開始記録IDから紐づける（終了記録は次の再計算で紐づく）
+UPDATE attendance_records
+SET session_id = (SELECT MAX(id) FROM work_sessions WHERE work_sessions.start_record_id = attendance_records.id)
+WHERE record_type = 'start';
+
+CREATE INDEX IF NOT EXISTS idx_attendance_records_session_id ON attendance_records (session_id);
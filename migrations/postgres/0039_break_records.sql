@@ -0,0 +1,5 @@
+-- 休憩（break_start / break_end）と離席（away）の記録を追加できるようにする
+ALTER TABLE attendance_records DROP CONSTRAINT IF EXISTS attendance_records_record_type_check;
+
+ALTER TABLE attendance_records ADD CONSTRAINT attendance_records_record_type_check
+    CHECK (record_type IN ('start', 'end', 'break_start', 'break_end', 'away'));
@@ -0,0 +1,5 @@
+-- 長時間の勤務を知らせるまでの時間（`/admin long_session`、0で無効）
+ALTER TABLE guild_settings ADD COLUMN long_session_reminder_hours INTEGER NOT NULL DEFAULT 10;
+
+-- 長時間の勤務のDMを送ったセッション（再計算で作り直しても引き継ぐ）
+ALTER TABLE work_sessions ADD COLUMN long_session_reminded BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,13 @@
+-- `/focus` で始めた勤務中の集中時間（ポモドーロ）
+CREATE TABLE IF NOT EXISTS focus_sessions (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users (id),
+    channel_id TEXT NOT NULL, -- 終了を知らせるチャンネル
+    started_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL, -- 予定の終了時刻
+    ended_at TIMESTAMPTZ, -- 実際の終了時刻（集中中は NULL）
+    completed BOOLEAN NOT NULL DEFAULT FALSE, -- 予定の時間まで続けたか（中断したら FALSE）
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_focus_sessions_user_start ON focus_sessions (user_id, started_at);
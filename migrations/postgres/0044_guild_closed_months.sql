@@ -0,0 +1,16 @@
+-- 月次締めをサーバーごとにする（DMでの利用は guild_id = ''）。
+-- これまでの締めはすべてのサーバーに効いていたため、既存の締めはユーザーのいる各サーバーに引き継ぐ
+CREATE TABLE closed_months_new (
+    guild_id TEXT NOT NULL,
+    month DATE NOT NULL,
+    closed_by TEXT NOT NULL,
+    closed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, month)
+);
+
+INSERT INTO closed_months_new (guild_id, month, closed_by, closed_at)
+SELECT guilds.guild_id, closed_months.month, closed_months.closed_by, closed_months.closed_at
+FROM closed_months CROSS JOIN (SELECT DISTINCT guild_id FROM users) AS guilds;
+
+DROP TABLE closed_months;
+ALTER TABLE closed_months_new RENAME TO closed_months;
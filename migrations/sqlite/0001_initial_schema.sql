@@ -0,0 +1,51 @@
+CREATE TABLE IF NOT EXISTS users (
+    id INTEGER PRIMARY KEY,
+    discord_id TEXT UNIQUE NOT NULL,
+    username TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS attendance_records (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    record_type TEXT NOT NULL CHECK (record_type IN ('start', 'end')),
+    timestamp DATETIME NOT NULL,
+    is_modified BOOLEAN DEFAULT FALSE,
+    original_timestamp DATETIME,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (id)
+);
+
+CREATE TABLE IF NOT EXISTS work_sessions (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    start_time DATETIME NOT NULL,
+    end_time DATETIME,
+    total_minutes INTEGER,
+    date DATE NOT NULL,
+    is_completed BOOLEAN DEFAULT FALSE,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (id)
+);
+
+CREATE TABLE IF NOT EXISTS guild_settings (
+    guild_id TEXT PRIMARY KEY,
+    weekly_digest_channel_id TEXT,
+    weekly_digest_last_sent DATE,
+    timesheet_confirmation_enabled BOOLEAN DEFAULT FALSE,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS timesheet_confirmations (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    week_start DATE NOT NULL,
+    total_minutes INTEGER NOT NULL,
+    confirmed_at DATETIME,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (user_id, week_start),
+    FOREIGN KEY (user_id) REFERENCES users (id)
+);
@@ -0,0 +1 @@
+ALTER TABLE attendance_records ADD COLUMN is_imported BOOLEAN NOT NULL DEFAULT FALSE;
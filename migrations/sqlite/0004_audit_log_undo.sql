@@ -0,0 +1,3 @@
+-- /undo 用: 1回の操作で書き込まれた監査ログをまとめ、取り消し済みかを記録する
+ALTER TABLE audit_log ADD COLUMN operation_id INTEGER;
+ALTER TABLE audit_log ADD COLUMN undone_at DATETIME;
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS projects (
+    id INTEGER PRIMARY KEY,
+    guild_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (guild_id, name)
+);
+
+-- セッション再計算時にプロジェクトなどを引き継ぐため、開始記録のIDを保持する
+ALTER TABLE work_sessions ADD COLUMN start_record_id INTEGER;
+ALTER TABLE work_sessions ADD COLUMN project_id INTEGER REFERENCES projects (id);
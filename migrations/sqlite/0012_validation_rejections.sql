@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS validation_rejections (
+    guild_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    count INTEGER NOT NULL DEFAULT 0,
+    last_rejected_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, kind)
+);
@@ -0,0 +1,7 @@
+ALTER TABLE attendance_records ADD COLUMN locked BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS closed_months (
+    month DATE PRIMARY KEY,
+    closed_by TEXT NOT NULL,
+    closed_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
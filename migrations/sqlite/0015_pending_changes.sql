@@ -0,0 +1,18 @@
+ALTER TABLE guild_settings ADD COLUMN approval_channel_id TEXT;
+
+CREATE TABLE IF NOT EXISTS pending_changes (
+    id INTEGER PRIMARY KEY,
+    guild_id TEXT NOT NULL,
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    requested_by TEXT NOT NULL,
+    action TEXT NOT NULL,
+    record_id INTEGER,
+    record_type TEXT NOT NULL,
+    old_timestamp DATETIME,
+    new_timestamp DATETIME NOT NULL,
+    source TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    reviewed_by TEXT,
+    reviewed_at DATETIME,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
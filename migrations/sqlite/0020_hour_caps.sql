@@ -0,0 +1,12 @@
+-- 契約上の月間勤務時間の上限（ユーザーごと・サーバーごと）
+CREATE TABLE IF NOT EXISTS hour_caps (
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    guild_id TEXT NOT NULL,
+    monthly_minutes INTEGER NOT NULL,
+    notify_channel_id TEXT,
+    warned_month TEXT,
+    warned_level INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, guild_id)
+);
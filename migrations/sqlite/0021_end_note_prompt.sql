@@ -0,0 +1,2 @@
+-- `/end` で「今日やったこと」を入力するモーダルを表示する
+ALTER TABLE guild_settings ADD COLUMN end_note_prompt_enabled BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+-- `/goal set` で設定する週の目標勤務時間（分）
+ALTER TABLE user_settings ADD COLUMN weekly_goal_minutes INTEGER;
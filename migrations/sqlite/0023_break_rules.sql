@@ -0,0 +1,3 @@
+-- 1日の勤務が一定時間を超えて休憩の記録が足りないとき、レポートで休憩時間を自動控除する（NULL で無効）
+ALTER TABLE guild_settings ADD COLUMN auto_break_after_minutes INTEGER;
+ALTER TABLE guild_settings ADD COLUMN auto_break_minutes INTEGER NOT NULL DEFAULT 60;
@@ -0,0 +1,16 @@
+-- 管理者が日ごとに加減する勤務時間（打刻を作らずに集計へ含める）
+CREATE TABLE IF NOT EXISTS time_adjustments (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    date DATE NOT NULL,
+    minutes INTEGER NOT NULL,
+    reason TEXT,
+    guild_id TEXT,
+    created_by TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_time_adjustments_user_date ON time_adjustments (user_id, date);
+
+-- 監査ログの record_type = 'adjustment' の行で、加減した分数
+ALTER TABLE audit_log ADD COLUMN adjustment_minutes INTEGER;
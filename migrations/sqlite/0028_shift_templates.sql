@@ -0,0 +1,12 @@
+-- `/shift template` で登録するサーバーごとのシフトのひな形（「早番 07:00〜16:00」など）
+CREATE TABLE IF NOT EXISTS shift_templates (
+    id INTEGER PRIMARY KEY,
+    guild_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    start_minutes INTEGER NOT NULL, -- JST 0時からの分数
+    end_minutes INTEGER NOT NULL, -- 開始より前なら翌日の終了
+    created_by TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (guild_id, name)
+);
@@ -0,0 +1,46 @@
+-- 同じDiscordユーザーでもサーバーごとに勤怠を分けるため、ユーザーをサーバー単位にする（DMでの利用は guild_id = ''）。
+-- SQLiteでは discord_id の UNIQUE 制約を外せないため、外部キーの検査をコミット時まで遅らせて users を作り直す
+PRAGMA defer_foreign_keys = ON;
+
+-- 既存のユーザーは操作の記録が最も多いサーバーに割り当てる
+-- （記録がなければ、登録済みのサーバーが1つだけの場合はそのサーバー、それ以外はDM扱い）
+CREATE TEMP TABLE users_backup AS
+SELECT id, discord_id, COALESCE(
+    (SELECT guild_id FROM (
+        SELECT guild_id FROM command_audit_log
+        WHERE actor_discord_id = users.discord_id AND guild_id IS NOT NULL
+        UNION ALL
+        SELECT guild_id FROM audit_log
+        WHERE audit_log.user_id = users.id AND guild_id IS NOT NULL
+    ) AS used GROUP BY guild_id ORDER BY COUNT(*) DESC, guild_id ASC LIMIT 1),
+    (SELECT MIN(guild_id) FROM guild_settings HAVING COUNT(*) = 1),
+    ''
+) AS guild_id, username, created_at
+FROM users;
+
+DROP TABLE users;
+
+CREATE TABLE users (
+    id INTEGER PRIMARY KEY,
+    discord_id TEXT NOT NULL,
+    guild_id TEXT NOT NULL DEFAULT '',
+    username TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (discord_id, guild_id)
+);
+
+INSERT INTO users (id, discord_id, guild_id, username, created_at)
+SELECT id, discord_id, guild_id, username, created_at FROM users_backup;
+
+DROP TABLE users_backup;
+
+-- サーバー単位の集計用（ユーザーのサーバーと同じ値を記録時に入れる）
+ALTER TABLE attendance_records ADD COLUMN guild_id TEXT NOT NULL DEFAULT '';
+UPDATE attendance_records
+SET guild_id = (SELECT guild_id FROM users WHERE users.id = attendance_records.user_id);
+
+ALTER TABLE work_sessions ADD COLUMN guild_id TEXT NOT NULL DEFAULT '';
+UPDATE work_sessions
+SET guild_id = (SELECT guild_id FROM users WHERE users.id = work_sessions.user_id);
+
+CREATE INDEX IF NOT EXISTS idx_work_sessions_guild_date ON work_sessions (guild_id, date);
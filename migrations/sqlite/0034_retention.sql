@@ -0,0 +1,2 @@
+-- 記録を保持する月数（未設定で無期限。超えた勤怠記録とセッションは自動で削除する）
+ALTER TABLE guild_settings ADD COLUMN retention_months INTEGER;
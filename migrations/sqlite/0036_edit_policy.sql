@@ -0,0 +1,4 @@
+-- 記録の編集ルール（`/admin edit_policy`）: 追加・修正できる日数、過去の日付を管理者のみにするか、削除に承認が必要か
+ALTER TABLE guild_settings ADD COLUMN edit_max_age_days INTEGER NOT NULL DEFAULT 7;
+ALTER TABLE guild_settings ADD COLUMN backdate_admin_only BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE guild_settings ADD COLUMN delete_requires_approval BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,18 @@
+-- 記録の時刻を修正するたびに修正前の時刻を残す（`original_timestamp` は最初の時刻だけ）
+CREATE TABLE IF NOT EXISTS record_revisions (
+    id INTEGER PRIMARY KEY,
+    record_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    previous_timestamp DATETIME NOT NULL,
+    new_timestamp DATETIME NOT NULL,
+    editor_discord_id TEXT NOT NULL,
+    source TEXT NOT NULL,
+    reason TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_record_revisions_record_id ON record_revisions (record_id);
+
+-- 承認制で申請された修正の理由（承認時に修正履歴へ引き継ぐ）
+ALTER TABLE pending_changes ADD COLUMN reason TEXT;
@@ -0,0 +1,35 @@
+-- 休憩（break_start / break_end）と離席（away）の記録を追加できるようにする。
+-- SQLiteでは CHECK 制約を変更できないため、外部キーの検査をコミット時まで遅らせて attendance_records を作り直す
+PRAGMA defer_foreign_keys = ON;
+
+CREATE TABLE attendance_records_new (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    record_type TEXT NOT NULL CHECK (record_type IN ('start', 'end', 'break_start', 'break_end', 'away')),
+    timestamp DATETIME NOT NULL,
+    is_modified BOOLEAN DEFAULT FALSE,
+    original_timestamp DATETIME,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    is_imported BOOLEAN NOT NULL DEFAULT FALSE,
+    locked BOOLEAN NOT NULL DEFAULT FALSE,
+    guild_id TEXT NOT NULL DEFAULT '',
+    source TEXT,
+    session_id INTEGER REFERENCES work_sessions (id) ON DELETE SET NULL,
+    FOREIGN KEY (user_id) REFERENCES users (id)
+);
+
+INSERT INTO attendance_records_new (
+    id, user_id, record_type, timestamp, is_modified, original_timestamp, created_at, updated_at,
+    is_imported, locked, guild_id, source, session_id
+)
+SELECT
+    id, user_id, record_type, timestamp, is_modified, original_timestamp, created_at, updated_at,
+    is_imported, locked, guild_id, source, session_id
+FROM attendance_records;
+
+DROP TABLE attendance_records;
+
+ALTER TABLE attendance_records_new RENAME TO attendance_records;
+
+CREATE INDEX IF NOT EXISTS idx_attendance_records_session_id ON attendance_records (session_id);
@@ -0,0 +1,2 @@
+-- 勤務中のメンバーに付けるロール（`/admin working_role`、未設定で無効）
+ALTER TABLE guild_settings ADD COLUMN working_role_id TEXT;
@@ -0,0 +1,3 @@
+-- `/schedule checkin` の出勤リマインダーの時刻（JST 0時からの分数、NULLで無効）
+ALTER TABLE user_settings ADD COLUMN checkin_reminder_minutes INTEGER;
+ALTER TABLE user_settings ADD COLUMN checkin_reminder_last_sent DATE;
@@ -0,0 +1,141 @@
+// Unauthenticated liveness/readiness probes for Docker and Kubernetes. They are served on
+// their own `HEALTH_BIND_ADDR` so probes work without the API token, and without enabling
+// the REST API at all.
+
+use crate::database::{DbPool, queries};
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::get;
+use poise::serenity_prelude as serenity;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// シャードごとの接続状態（`ShardManager::runners` を共有する）
+pub type ShardRunners = Arc<Mutex<HashMap<serenity::ShardId, serenity::ShardRunnerInfo>>>;
+
+#[derive(Clone)]
+pub struct HealthState {
+    pub pool: DbPool,
+    pub runners: ShardRunners,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadinessReport {
+    pub status: &'static str,
+    pub database: String,
+    pub gateway: String,
+}
+
+pub fn router(state: HealthState) -> axum::Router {
+    axum::Router::new()
+        .route("/healthz", get(live))
+        .route("/readyz", get(ready))
+        .with_state(state)
+}
+
+/// 設定されていればヘルスチェックサーバーをバックグラウンドで起動する
+pub async fn spawn_if_configured(
+    pool: &DbPool,
+    runners: ShardRunners,
+    bind_addr: Option<&str>,
+) -> anyhow::Result<()> {
+    let Some(addr) = bind_addr else {
+        return Ok(());
+    };
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let app = router(HealthState {
+        pool: pool.clone(),
+        runners,
+    });
+    tracing::info!("Health check listening on {}", addr);
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            tracing::error!("Health check server error: {}", e);
+        }
+    });
+
+    Ok(())
+}
+
+/// プロセスが応答できれば常に成功する（liveness）
+async fn live() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+/// DBに接続でき、全シャードがゲートウェイに接続済みなら成功する（readiness）
+async fn ready(State(state): State<HealthState>) -> (StatusCode, Json<ReadinessReport>) {
+    let database = match queries::ping_database(&state.pool).await {
+        Ok(()) => None,
+        Err(e) => Some(e.to_string()),
+    };
+    let gateway = gateway_stage(&state.runners).await;
+
+    let is_ready = database.is_none() && gateway == serenity::ConnectionStage::Connected;
+    let report = ReadinessReport {
+        status: if is_ready { "ok" } else { "unavailable" },
+        database: database.unwrap_or_else(|| "ok".to_string()),
+        gateway: gateway.to_string(),
+    };
+    let status = if is_ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status, Json(report))
+}
+
+/// 接続済みでないシャードがあればその状態を返す（起動前は未接続扱い）
+async fn gateway_stage(runners: &ShardRunners) -> serenity::ConnectionStage {
+    let runners = runners.lock().await;
+    if runners.is_empty() {
+        return serenity::ConnectionStage::Disconnected;
+    }
+
+    runners
+        .values()
+        .map(|runner| runner.stage)
+        .find(|stage| *stage != serenity::ConnectionStage::Connected)
+        .unwrap_or(serenity::ConnectionStage::Connected)
+}
+
+#[cfg(all(test, not(feature = "postgres")))]
+mod tests {
+    use super::*;
+    use crate::database::create_test_pool;
+    use axum::body::{Body, to_bytes};
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn call(state: &HealthState, uri: &str) -> (StatusCode, String) {
+        let response = router(state.clone())
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_readiness_requires_gateway_connection() {
+        let state = HealthState {
+            pool: create_test_pool().await,
+            runners: Arc::new(Mutex::new(HashMap::new())),
+        };
+
+        let (status, _) = call(&state, "/healthz").await;
+        assert_eq!(status, StatusCode::OK);
+
+        // シャードが起動する前はDBが正常でも準備完了にしない
+        let (status, body) = call(&state, "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        let report: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(report["database"], "ok");
+        assert_eq!(report["gateway"], "disconnected");
+    }
+}
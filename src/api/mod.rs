@@ -0,0 +1,122 @@
+// Optional REST API served next to the Discord client. It shares the bot's database pool
+// and reuses the same clock-in/out path as shortcuts, so records created over HTTP go
+// through the same validation, audit log and session recalculation as slash commands.
+
+pub mod health;
+mod routes;
+
+use crate::bot::AppError;
+use crate::config::Config;
+use crate::database::DbPool;
+use axum::Json;
+use axum::extract::{Request, State};
+use axum::http::{StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use serde_json::json;
+
+#[derive(Clone)]
+pub struct ApiState {
+    pub pool: DbPool,
+    pub config: Config,
+}
+
+/// APIのエラー応答（`{"error": "..."}`）
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl ToString) -> Self {
+        Self {
+            status,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn internal(error: impl std::fmt::Display) -> Self {
+        tracing::error!("API error: {}", error);
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+    }
+}
+
+/// 打刻処理のエラー（状態や入力の矛盾は 409、内部エラーは原因をログに残して 500）
+impl From<AppError> for ApiError {
+    fn from(error: AppError) -> Self {
+        match error {
+            AppError::User(message) => Self::new(StatusCode::CONFLICT, message),
+            error => {
+                error.log();
+                Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+            }
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.message }))).into_response()
+    }
+}
+
+pub fn router(state: ApiState) -> axum::Router {
+    axum::Router::new()
+        .route("/api/users/{discord_id}/start", post(routes::start))
+        .route("/api/users/{discord_id}/end", post(routes::end))
+        .route("/api/users/{discord_id}/sessions", get(routes::sessions))
+        .route("/api/users/{discord_id}/export", get(routes::export))
+        .layer(middleware::from_fn_with_state(state.clone(), authenticate))
+        .with_state(state)
+}
+
+/// 設定されていればAPIサーバーをバックグラウンドで起動する
+pub async fn spawn_if_configured(pool: &DbPool, config: &Config) -> anyhow::Result<()> {
+    let Some(addr) = &config.api_bind_addr else {
+        return Ok(());
+    };
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let app = router(ApiState {
+        pool: pool.clone(),
+        config: config.clone(),
+    });
+    tracing::info!("REST API listening on {}", addr);
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            tracing::error!("REST API server error: {}", e);
+        }
+    });
+
+    Ok(())
+}
+
+async fn authenticate(
+    State(state): State<ApiState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match (provided, state.config.api_token.as_deref()) {
+        (Some(provided), Some(expected)) if constant_time_eq(provided, expected) => {
+            Ok(next.run(request).await)
+        }
+        _ => Err(ApiError::new(StatusCode::UNAUTHORIZED, "invalid API token")),
+    }
+}
+
+/// トークンの比較にかかる時間から内容を推測されないようにする
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
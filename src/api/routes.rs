@@ -0,0 +1,263 @@
+use super::{ApiError, ApiState};
+use crate::bot::edit_policy;
+use crate::bot::shortcuts::record_attendance;
+use crate::database::models::{AuditSource, RecordType, User, WorkSession};
+use crate::database::queries;
+use crate::utils::export::{ExportTemplate, format_sessions_csv, sign_export};
+use crate::utils::time::{get_current_date_jst, get_month_range};
+use crate::utils::validation::{validate_date_format, validate_month_format};
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::{StatusCode, header};
+use axum::response::IntoResponse;
+use chrono::{DateTime, Utc};
+use poise::serenity_prelude as serenity;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+pub struct RecordResponse {
+    pub record_type: &'static str,
+    pub timestamp: DateTime<Utc>,
+    /// 終了時のみ、終了したセッションの勤務時間（分）
+    pub worked_minutes: Option<i64>,
+}
+
+/// ユーザーはサーバーごとに別のため、対象のサーバーを指定する
+#[derive(Debug, Deserialize)]
+pub struct GuildQuery {
+    /// 対象サーバーのID（未指定でDMで使ったユーザー）
+    pub guild_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionsQuery {
+    pub guild_id: Option<String>,
+    /// 開始日 (YYYY-MM-DD、未指定で今月1日)
+    pub from: Option<String>,
+    /// 終了日 (YYYY-MM-DD、未指定で今月末日)
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    pub guild_id: Option<String>,
+    /// 対象月 (YYYY-MM、未指定で今月)
+    pub month: Option<String>,
+    #[serde(default)]
+    pub template: ExportTemplate,
+}
+
+pub async fn start(
+    State(state): State<ApiState>,
+    Path(discord_id): Path<String>,
+    Query(query): Query<GuildQuery>,
+) -> Result<Json<RecordResponse>, ApiError> {
+    add_record(&state, &discord_id, query.guild_id, RecordType::Start).await
+}
+
+pub async fn end(
+    State(state): State<ApiState>,
+    Path(discord_id): Path<String>,
+    Query(query): Query<GuildQuery>,
+) -> Result<Json<RecordResponse>, ApiError> {
+    add_record(&state, &discord_id, query.guild_id, RecordType::End).await
+}
+
+pub async fn sessions(
+    State(state): State<ApiState>,
+    Path(discord_id): Path<String>,
+    Query(query): Query<SessionsQuery>,
+) -> Result<Json<Vec<WorkSession>>, ApiError> {
+    let user = find_user(&state, &discord_id, query.guild_id.as_deref()).await?;
+    let (month_start, month_end) = get_month_range(get_current_date_jst());
+    let parse = |date: Option<&str>, default| match date {
+        Some(date) => {
+            validate_date_format(date).map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e))
+        }
+        None => Ok(default),
+    };
+    let from = parse(query.from.as_deref(), month_start)?;
+    let to = parse(query.to.as_deref(), month_end)?;
+
+    let sessions = queries::get_work_sessions_by_date_range(&state.pool, user.id, from, to)
+        .await
+        .map_err(ApiError::internal)?;
+    Ok(Json(sessions))
+}
+
+/// `/export csv` と同じ署名付きCSVを返す
+pub async fn export(
+    State(state): State<ApiState>,
+    Path(discord_id): Path<String>,
+    Query(query): Query<ExportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = find_user(&state, &discord_id, query.guild_id.as_deref()).await?;
+    let target_date = match query.month.as_deref() {
+        Some(month) => {
+            validate_month_format(month).map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e))?
+        }
+        None => get_current_date_jst(),
+    };
+    let (start_of_month, end_of_month) = get_month_range(target_date);
+
+    let sessions = queries::get_work_sessions_by_date_range(
+        &state.pool,
+        user.id,
+        start_of_month,
+        end_of_month,
+    )
+    .await
+    .map_err(ApiError::internal)?;
+    let signed_csv = sign_export(
+        &format_sessions_csv(&sessions, query.template, None),
+        state.config.export_signing_key.as_bytes(),
+    );
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+        signed_csv,
+    ))
+}
+
+async fn add_record(
+    state: &ApiState,
+    discord_id: &str,
+    guild_id: Option<String>,
+    record_type: RecordType,
+) -> Result<Json<RecordResponse>, ApiError> {
+    let user = find_user(state, discord_id, guild_id.as_deref()).await?;
+    let guild_id = guild_id
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(serenity::GuildId::new);
+    let audit = AuditSource::new(discord_id, "api").in_guild(guild_id);
+    let timestamp = Utc::now();
+    let policy = edit_policy::load(&state.pool, guild_id, false).await;
+
+    let started_at = record_attendance(
+        &state.pool,
+        &user,
+        record_type,
+        timestamp,
+        guild_id,
+        &audit,
+        &policy,
+    )
+    .await?;
+
+    Ok(Json(RecordResponse {
+        record_type: record_type.as_str(),
+        timestamp,
+        worked_minutes: started_at.map(|started_at| (timestamp - started_at).num_minutes()),
+    }))
+}
+
+/// APIではユーザー名が分からないため、Botを使ったことのあるユーザーのみ対象にする
+async fn find_user(
+    state: &ApiState,
+    discord_id: &str,
+    guild_id: Option<&str>,
+) -> Result<User, ApiError> {
+    queries::get_user_by_discord_id(&state.pool, discord_id, guild_id)
+        .await
+        .map_err(|_| ApiError::new(StatusCode::NOT_FOUND, "user not found"))
+}
+
+#[cfg(all(test, not(feature = "postgres")))]
+mod tests {
+    use super::super::router;
+    use super::*;
+    use crate::config::Config;
+    use crate::database::create_test_pool;
+    use axum::body::{Body, to_bytes};
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn test_state(pool: crate::database::DbPool) -> ApiState {
+        ApiState {
+            pool,
+            config: Config {
+                discord_token: "token".to_string(),
+                database_url: "sqlite::memory:".to_string(),
+                database: Default::default(),
+                admin_role_id: None,
+                export_signing_key: "key".to_string(),
+                select_menu_page_size: 25,
+                api_bind_addr: Some("127.0.0.1:0".to_string()),
+                api_token: Some("secret".to_string()),
+                health_bind_addr: None,
+                punch_journal_path: String::new(),
+            },
+        }
+    }
+
+    async fn call(state: &ApiState, method: &str, uri: &str, token: &str) -> (StatusCode, String) {
+        let response = router(state.clone())
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_api_clock_in_and_out() {
+        let pool = create_test_pool().await;
+        queries::create_or_get_user(&pool, "1234", Some("42"), "taro")
+            .await
+            .unwrap();
+        let state = test_state(pool);
+
+        let (status, _) = call(&state, "POST", "/api/users/1234/start", "wrong").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = call(&state, "POST", "/api/users/9999/start", "secret").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // 別のサーバー（未指定はDM）のユーザーとは区別する
+        let (status, _) = call(&state, "POST", "/api/users/1234/start", "secret").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let start = "/api/users/1234/start?guild_id=42";
+        let (status, body) = call(&state, "POST", start, "secret").await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        // 勤務中に再度開始はできない
+        let (status, _) = call(&state, "POST", start, "secret").await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        let (status, body) =
+            call(&state, "POST", "/api/users/1234/end?guild_id=42", "secret").await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert!(body.contains("\"worked_minutes\":0"));
+
+        let (status, body) = call(
+            &state,
+            "GET",
+            "/api/users/1234/sessions?guild_id=42",
+            "secret",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let sessions: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(sessions.as_array().unwrap().len(), 1);
+        assert_eq!(sessions[0]["is_completed"], true);
+
+        let (status, body) = call(
+            &state,
+            "GET",
+            "/api/users/1234/export?guild_id=42&template=payroll",
+            "secret",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("break_hours"));
+        assert!(body.contains("# kintai-signature"));
+    }
+}
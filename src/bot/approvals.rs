@@ -0,0 +1,187 @@
+// Approval workflow for manual record corrections. When a guild has an approval channel,
+// time edits and back-dated records from the /status buttons are stored as pending
+// changes and posted to that channel; `attendance_records` is only touched once an admin
+// approves the request (see `interactions::approval_buttons`). Members' deletions are
+// submitted the same way when the guild's edit policy requires it.
+
+use crate::bot::webhooks;
+use crate::database::models::{AuditSource, PendingChange, RecordType, User};
+use crate::database::{DbPool, queries};
+use crate::utils::format::{create_approval_request_embed, create_info_embed};
+use crate::utils::session_manager::SessionManager;
+use crate::utils::time::get_date_from_utc_timestamp;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use poise::serenity_prelude as serenity;
+use std::collections::BTreeSet;
+
+/// 承認が必要になりうる記録の変更（`record_id` がある場合は既存記録の時刻修正）
+#[derive(Debug, Clone, Copy)]
+pub struct RecordChange {
+    pub record_id: Option<i64>,
+    pub record_type: RecordType,
+    pub old_timestamp: Option<DateTime<Utc>>,
+    pub new_timestamp: DateTime<Utc>,
+    /// 既存記録の削除（`new_timestamp` には削除する記録の時刻を入れる）
+    pub delete: bool,
+}
+
+impl RecordChange {
+    /// 既存記録の削除
+    pub fn deletion(record_id: i64, record_type: RecordType, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            record_id: Some(record_id),
+            record_type,
+            old_timestamp: Some(timestamp),
+            new_timestamp: timestamp,
+            delete: true,
+        }
+    }
+
+    /// `pending_changes.action` に保存する操作の種類
+    pub fn action(&self) -> &'static str {
+        match (self.delete, self.record_id) {
+            (true, _) => "delete",
+            (false, Some(_)) => "update",
+            (false, None) => "create",
+        }
+    }
+}
+
+/// 承認チャンネルが設定されていれば承認依頼を投稿して true を返す（DMでの操作は対象外）
+pub async fn submit_if_required(
+    http: &serenity::Http,
+    pool: &DbPool,
+    guild_id: Option<serenity::GuildId>,
+    user: &User,
+    change: RecordChange,
+    audit: &AuditSource,
+) -> Result<bool> {
+    let Some(guild_id) = guild_id else {
+        return Ok(false);
+    };
+    let Some(channel_id) = queries::get_guild_settings(pool, &guild_id.to_string())
+        .await?
+        .and_then(|settings| settings.approval_channel_id)
+        .and_then(|id| id.parse::<u64>().ok())
+    else {
+        return Ok(false);
+    };
+
+    let pending = queries::create_pending_change(
+        pool,
+        &guild_id.to_string(),
+        user.id,
+        change.action(),
+        change.record_id,
+        change.record_type,
+        change.old_timestamp,
+        change.new_timestamp,
+        audit,
+    )
+    .await?;
+
+    let message = serenity::CreateMessage::new()
+        .embed(create_approval_request_embed(&pending, &user.discord_id))
+        .components(vec![review_buttons(pending.id)]);
+    if let Err(e) = serenity::ChannelId::new(channel_id)
+        .send_message(http, message)
+        .await
+    {
+        // 誰にも見えない承認依頼を残さない
+        queries::delete_pending_change(pool, pending.id).await?;
+        return Err(e.into());
+    }
+
+    Ok(true)
+}
+
+/// 承認・却下ボタン（custom_id: "approval_approve:id" / "approval_reject:id"）
+pub fn review_buttons(change_id: i64) -> serenity::CreateActionRow {
+    serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(format!("approval_approve:{}", change_id))
+            .label("✅ 承認")
+            .style(serenity::ButtonStyle::Success),
+        serenity::CreateButton::new(format!("approval_reject:{}", change_id))
+            .label("🚫 却下")
+            .style(serenity::ButtonStyle::Danger),
+    ])
+}
+
+/// 承認待ちになったことを依頼者に伝える
+pub fn pending_embed() -> serenity::CreateEmbed {
+    create_info_embed(
+        "承認待ち",
+        "記録の修正を管理者に申請しました。承認されると勤務記録に反映されます",
+    )
+}
+
+/// 削除の承認待ちになったことを依頼者に伝える
+pub fn pending_delete_embed() -> serenity::CreateEmbed {
+    create_info_embed(
+        "承認待ち",
+        "記録の削除を管理者に申請しました。承認されると勤務記録から削除されます",
+    )
+}
+
+/// 承認された変更を勤務記録に反映し、影響する日のセッションを再計算
+///
+/// 呼び出し側は検証から反映までその利用者の `punch_lock` を保持しておく
+pub async fn apply_change(pool: &DbPool, change: &PendingChange) -> Result<()> {
+    let audit = AuditSource::new(&change.requested_by, "approval")
+        .in_guild(Some(&change.guild_id))
+        .with_reason(change.reason.as_deref());
+    match change.record_id {
+        Some(record_id) if change.action == "delete" => {
+            if !queries::delete_attendance_record(pool, change.user_id, record_id, &audit).await? {
+                anyhow::bail!("削除する記録が見つかりません");
+            }
+        }
+        Some(record_id) => {
+            queries::update_attendance_record_time(pool, record_id, change.new_timestamp, &audit)
+                .await?
+        }
+        None => {
+            queries::create_attendance_record(
+                pool,
+                change.user_id,
+                change.record_type,
+                change.new_timestamp,
+                &audit,
+            )
+            .await?;
+            let user = queries::get_user_by_id(pool, change.user_id).await?;
+            webhooks::notify_record(
+                pool,
+                change
+                    .guild_id
+                    .parse::<u64>()
+                    .ok()
+                    .map(serenity::GuildId::new),
+                &user,
+                change.record_type,
+                change.new_timestamp,
+                &audit,
+            )
+            .await;
+        }
+    }
+
+    let dates: BTreeSet<_> = change
+        .old_timestamp
+        .into_iter()
+        .chain([change.new_timestamp])
+        .map(get_date_from_utc_timestamp)
+        .collect();
+    let session_manager = SessionManager::new(pool.clone());
+    for date in dates {
+        if let Err(e) = session_manager
+            .trigger_recalculation(change.user_id, date)
+            .await
+        {
+            tracing::error!("Failed to recalculate sessions: {}", e);
+        }
+    }
+
+    Ok(())
+}
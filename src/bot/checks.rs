@@ -0,0 +1,39 @@
+use crate::bot::{Context, Error};
+use crate::config::Config;
+use crate::utils::format::create_error_embed;
+use poise::serenity_prelude as serenity;
+
+/// 管理者コマンドの実行権限をチェック
+/// ADMIN_ROLE_ID が設定されていればそのロール、未設定なら管理者権限を要求する
+pub async fn is_admin(ctx: Context<'_>) -> Result<bool, Error> {
+    let member = match ctx.author_member().await {
+        Some(member) => member,
+        None => {
+            let embed =
+                create_error_embed("アクセス拒否", "このコマンドはサーバー内でのみ使用できます");
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(false);
+        }
+    };
+
+    let allowed = member_is_admin(&member, &ctx.data().config);
+
+    if !allowed {
+        let embed = create_error_embed("アクセス拒否", "このコマンドは管理者のみ使用できます");
+        ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+            .await?;
+    }
+
+    Ok(allowed)
+}
+
+/// メンバーが管理者かどうか（ボタン操作など、コマンド以外の権限チェック用）
+pub fn member_is_admin(member: &serenity::Member, config: &Config) -> bool {
+    match &config.admin_role_id {
+        Some(role_id) => member.roles.iter().any(|role| role.to_string() == *role_id),
+        None => member
+            .permissions
+            .is_some_and(|permissions| permissions.contains(serenity::Permissions::ADMINISTRATOR)),
+    }
+}
@@ -0,0 +1,61 @@
+// Japanese names for the everyday commands. Discord shows a command under its localized name
+// to users whose client is set to Japanese (`/出勤` for `/start`) and still sends the base name
+// in the interaction, so the handlers don't change. Applied to `FrameworkOptions::commands`
+// before registration, including the same commands under `/kintai`.
+
+use crate::bot::{Data, Error};
+
+/// 日本語クライアントで表示するコマンド名（コマンド名 → 日本語名）
+const JA_COMMAND_NAMES: &[(&str, &str)] = &[
+    ("start", "出勤"),
+    ("end", "退勤"),
+    ("punch", "打刻"),
+    ("resume", "勤務再開"),
+    ("status", "勤怠"),
+    ("undo", "取り消し"),
+    ("daily", "日報"),
+    ("weekly", "週報"),
+    ("monthly", "月報"),
+    ("leaderboard", "ランキング"),
+    ("working", "勤務中"),
+    ("calendar", "カレンダー"),
+    ("settings", "設定"),
+    ("kintai", "勤怠管理"),
+];
+
+/// コマンドとそのサブコマンドに日本語名を設定する
+pub fn localize(commands: &mut [poise::Command<Data, Error>]) {
+    for command in commands {
+        if let Some((_, name)) = JA_COMMAND_NAMES
+            .iter()
+            .find(|(base, _)| *base == command.name)
+        {
+            command
+                .name_localizations
+                .insert("ja".to_string(), name.to_string());
+        }
+        localize(&mut command.subcommands);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_names_are_valid_and_unique() {
+        let mut names = HashSet::new();
+        for (base, name) in JA_COMMAND_NAMES {
+            // Discord のコマンド名は32文字以内で、空白や大文字を含められない
+            assert!(name.chars().count() <= 32, "{}", base);
+            assert!(
+                name.chars()
+                    .all(|c| !c.is_whitespace() && !c.is_uppercase()),
+                "{}",
+                base
+            );
+            assert!(names.insert(*name), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,1892 @@
+use crate::bot::checks::is_admin;
+use crate::bot::commands::language::LanguageChoice;
+use crate::bot::diagnostics::{CheckStatus, format_report, run_diagnostics};
+use crate::bot::interactions::flow_state::{self, FlowState};
+use crate::bot::interactions::kiosk_buttons::kiosk_message;
+use crate::bot::shortcuts::record_attendance;
+use crate::bot::tasks::long_session_reminder::MAX_REMINDER_HOURS;
+use crate::bot::tasks::quality_digest::QUALITY_DIGEST_HOUR_JST;
+use crate::bot::{AppError, Context, Error};
+use crate::bot::{edit_policy, guild_reset, working_role};
+use crate::bot::{preferences, preflight};
+use crate::database::models::{AuditSource, RecordType, TimesheetConfirmation, User};
+use crate::database::queries;
+use crate::t;
+use crate::utils::csv_import::{parse_attendance_csv, validate_import_row};
+use crate::utils::export::{
+    ExportTemplate, ExportVerification, diff_sessions, format_guild_monthly_csv,
+    format_sessions_csv, parse_sessions_csv, sign_export, verify_export,
+};
+use crate::utils::format::{
+    create_error_embed, create_guild_monthly_embed, create_info_embed, create_success_embed,
+    format_adjustment_minutes, format_audit_logs, format_command_audit_logs, format_export_diff,
+    format_timesheet_confirmations, format_validation_rejections, format_yen,
+};
+use crate::utils::i18n::Lang;
+use crate::utils::message_parser::parse_attendance_message;
+use crate::utils::overtime::OvertimeThresholds;
+use crate::utils::record_validator::EditPolicy;
+use crate::utils::session_manager::SessionManager;
+use crate::utils::stats::{CAP_WARNING_PERCENT, MemberMonthSummary};
+use crate::utils::time::{
+    TimeDisplay, combine_date_time_jst, format_duration_minutes, format_time_jst,
+    get_current_date_jst, get_date_from_utc_timestamp, get_month_range, retention_cutoff,
+};
+use crate::utils::validation::{
+    validate_date_format, validate_month_format, validate_time_format, validate_webhook_url,
+};
+use chrono::DateTime;
+use poise::serenity_prelude as serenity;
+use std::collections::BTreeSet;
+
+/// 管理者用コマンド
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands(
+        "digest",
+        "quality_digest",
+        "approvals",
+        "webhook",
+        "event_log",
+        "working_role",
+        "long_session",
+        "timesheet",
+        "end_note",
+        "resume_window",
+        "retention",
+        "edit_policy",
+        "language",
+        "kiosk",
+        "rate",
+        "overtime",
+        "break_rule",
+        "hour_cap",
+        "adjust",
+        "force_end",
+        "confirmations",
+        "monthly",
+        "audit",
+        "export",
+        "backfill",
+        "import_csv",
+        "diagnostics",
+        "metrics",
+        "purge",
+        "reset_guild"
+    ),
+    check = "is_admin"
+)]
+pub async fn admin(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// 週次ダイジェストの投稿先チャンネルを設定します（未指定で停止）
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn digest(
+    ctx: Context<'_>,
+    #[description = "投稿先チャンネル（未指定で週次ダイジェストを停止）"]
+    #[channel_types("Text")]
+    channel: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    if let Some(channel) = &channel
+        && !preflight::ensure_channel_permissions(
+            ctx,
+            channel,
+            serenity::Permissions::VIEW_CHANNEL
+                | serenity::Permissions::SEND_MESSAGES
+                | serenity::Permissions::EMBED_LINKS,
+            "週次ダイジェスト",
+        )
+        .await?
+    {
+        return Ok(());
+    }
+    let pool = &ctx.data().pool;
+    let channel_id = channel.as_ref().map(|channel| channel.id.to_string());
+
+    match queries::set_weekly_digest_channel(pool, &guild_id.to_string(), channel_id.as_deref())
+        .await
+    {
+        Ok(()) => {
+            let description = match &channel {
+                Some(channel) => format!(
+                    "毎週月曜日に <#{}> へ週次ダイジェストを投稿します",
+                    channel.id
+                ),
+                None => "週次ダイジェストの投稿を停止しました".to_string(),
+            };
+            let embed = create_success_embed("週次ダイジェスト設定", &description);
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+        }
+        Err(e) => {
+            AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// データ品質ダイジェストの投稿先を設定します（未指定で停止）
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn quality_digest(
+    ctx: Context<'_>,
+    #[description = "投稿先チャンネル（未指定で停止）"]
+    #[channel_types("Text")]
+    channel: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    if let Some(channel) = &channel
+        && !preflight::ensure_channel_permissions(
+            ctx,
+            channel,
+            serenity::Permissions::VIEW_CHANNEL
+                | serenity::Permissions::SEND_MESSAGES
+                | serenity::Permissions::EMBED_LINKS,
+            "データ品質ダイジェスト",
+        )
+        .await?
+    {
+        return Ok(());
+    }
+    let pool = &ctx.data().pool;
+    let channel_id = channel.as_ref().map(|channel| channel.id.to_string());
+
+    match queries::set_quality_digest_channel(pool, &guild_id.to_string(), channel_id.as_deref())
+        .await
+    {
+        Ok(()) => {
+            let description = match &channel {
+                Some(channel) => format!(
+                    "毎日{}時に <#{}> へ前日の要確認項目（未終了・開始なしの終了・16時間以上の勤務・修正された記録）を投稿します",
+                    QUALITY_DIGEST_HOUR_JST, channel.id
+                ),
+                None => "データ品質ダイジェストの投稿を停止しました".to_string(),
+            };
+            let embed = create_success_embed("データ品質ダイジェスト設定", &description);
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+        }
+        Err(e) => {
+            AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 打刻時にJSONを送るWebhookのURLを設定します（未指定で停止）
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn webhook(
+    ctx: Context<'_>,
+    #[description = "送信先のURL（https://...、未指定で停止）"] url: Option<String>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let url = match url.as_deref().map(validate_webhook_url).transpose() {
+        Ok(url) => url,
+        Err(e) => {
+            let embed = create_error_embed("入力エラー", &e.to_string());
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+    };
+    let pool = &ctx.data().pool;
+
+    match queries::set_webhook_url(pool, &guild_id.to_string(), url.as_deref()).await {
+        Ok(()) => {
+            let description = match &url {
+                Some(url) => format!(
+                    "勤務の開始・終了を記録するたびに `{}` へJSONを送信します",
+                    url
+                ),
+                None => "Webhookの送信を停止しました".to_string(),
+            };
+            let embed = create_success_embed("Webhook設定", &description);
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+        }
+        Err(e) => {
+            AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 打刻や修正・削除を投稿するチャンネルを設定します（未指定で停止）
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn event_log(
+    ctx: Context<'_>,
+    #[description = "勤怠ログの投稿先チャンネル（未指定で停止）"]
+    #[channel_types("Text")]
+    channel: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    if let Some(channel) = &channel
+        && !preflight::ensure_channel_permissions(
+            ctx,
+            channel,
+            serenity::Permissions::VIEW_CHANNEL
+                | serenity::Permissions::SEND_MESSAGES
+                | serenity::Permissions::EMBED_LINKS,
+            "勤怠ログ",
+        )
+        .await?
+    {
+        return Ok(());
+    }
+    let pool = &ctx.data().pool;
+    let channel_id = channel.as_ref().map(|channel| channel.id.to_string());
+
+    match queries::set_event_log_channel(pool, &guild_id.to_string(), channel_id.as_deref()).await {
+        Ok(()) => {
+            let description = match &channel {
+                Some(channel) => format!(
+                    "このサーバーでの出勤・退勤・記録の修正・削除を <#{}> に投稿します（30秒ごとにまとめて投稿）",
+                    channel.id
+                ),
+                None => "勤怠ログの投稿を停止しました".to_string(),
+            };
+            let embed = create_success_embed("勤怠ログ設定", &description);
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+        }
+        Err(e) => {
+            AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 勤務中のメンバーに付けるロールを設定します（未指定で停止）
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn working_role(
+    ctx: Context<'_>,
+    #[description = "勤務中に付けるロール（未指定で停止）"] role: Option<serenity::Role>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    if let Some(role) = &role
+        && (role.managed || role.id.get() == guild_id.get())
+    {
+        return Err(AppError::user(
+            "@everyone や、Bot・連携サービスが管理するロールは指定できません",
+        ));
+    }
+    ctx.defer_ephemeral().await?;
+    let pool = &ctx.data().pool;
+    let old_role = queries::get_guild_settings(pool, &guild_id.to_string())
+        .await?
+        .and_then(|settings| settings.working_role_id)
+        .and_then(|role_id| role_id.parse::<u64>().ok())
+        .map(serenity::RoleId::new);
+    let new_role = role.as_ref().map(|role| role.id);
+    let role_id = new_role.map(|role_id| role_id.to_string());
+    if let Err(e) = queries::set_working_role(pool, &guild_id.to_string(), role_id.as_deref()).await
+    {
+        return AppError::internal("設定の保存に失敗しました", e)
+            .reply(ctx)
+            .await;
+    }
+    // 今勤務中のメンバーにもすぐ反映する
+    if let Err(e) = working_role::replace(ctx.http(), pool, guild_id, old_role, new_role).await {
+        tracing::warn!("Failed to apply working role to active members: {:?}", e);
+    }
+
+    let description = match new_role {
+        Some(role_id) => format!(
+            "出勤したメンバーに <@&{}> を付け、退勤したら外します。\n\
+             Botに「ロールの管理」権限を付与し、Botのロールをこのロールより上に置いてください",
+            role_id
+        ),
+        None => "勤務中ロールの付与を停止しました".to_string(),
+    };
+    let embed = create_success_embed("勤務中ロール設定", &description);
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 勤務が長時間続いたときにDMで知らせるまでの時間を設定します（0で停止）
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn long_session(
+    ctx: Context<'_>,
+    #[description = "勤務開始から何時間で知らせるか（既定 10時間、0で停止）"]
+    #[min = 0]
+    #[max = 24]
+    hours: u32,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let hours = (hours as i32).min(MAX_REMINDER_HOURS);
+    let pool = &ctx.data().pool;
+
+    if let Err(e) =
+        queries::set_long_session_reminder_hours(pool, &guild_id.to_string(), hours).await
+    {
+        return AppError::internal("設定の保存に失敗しました", e)
+            .reply(ctx)
+            .await;
+    }
+
+    let description = if hours == 0 {
+        "長時間の勤務のお知らせを停止しました".to_string()
+    } else {
+        format!(
+            "勤務開始から{}時間を過ぎても勤務中のメンバーに、終了を記録するボタン付きのDMを送ります（1回の勤務につき1回）",
+            hours
+        )
+    };
+    let embed = create_success_embed("長時間勤務のお知らせ設定", &description);
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 記録修正の承認依頼の投稿先を設定します（未指定で承認制を解除）
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn approvals(
+    ctx: Context<'_>,
+    #[description = "承認依頼の投稿先チャンネル（未指定で承認制を解除）"]
+    #[channel_types("Text")]
+    channel: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    if let Some(channel) = &channel
+        && !preflight::ensure_channel_permissions(
+            ctx,
+            channel,
+            serenity::Permissions::VIEW_CHANNEL
+                | serenity::Permissions::SEND_MESSAGES
+                | serenity::Permissions::EMBED_LINKS,
+            "承認依頼",
+        )
+        .await?
+    {
+        return Ok(());
+    }
+    let pool = &ctx.data().pool;
+    let channel_id = channel.as_ref().map(|channel| channel.id.to_string());
+
+    match queries::set_approval_channel(pool, &guild_id.to_string(), channel_id.as_deref()).await {
+        Ok(()) => {
+            let description = match &channel {
+                Some(channel) => format!(
+                    "`/status` からの時間修正・記録追加は <#{}> で管理者が承認してから反映されます",
+                    channel.id
+                ),
+                None => "承認制を解除しました。記録の修正はすぐに反映されます".to_string(),
+            };
+            let embed = create_success_embed("承認フロー設定", &description);
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+        }
+        Err(e) => {
+            AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 週次タイムシート確認フローの有効・無効を切り替えます
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn timesheet(
+    ctx: Context<'_>,
+    #[description = "毎週月曜日に前週のタイムシート確認依頼をDMで送信する"] enabled: bool,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let pool = &ctx.data().pool;
+
+    match queries::set_timesheet_confirmation_enabled(pool, &guild_id.to_string(), enabled).await {
+        Ok(()) => {
+            let description = if enabled {
+                "毎週月曜日に前週のタイムシート確認依頼をDMで送信します"
+            } else {
+                "タイムシート確認依頼の送信を停止しました"
+            };
+            let embed = create_success_embed("タイムシート確認設定", description);
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+        }
+        Err(e) => {
+            AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// `/end` で「今日やったこと」を入力するモーダルを表示するか切り替えます
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn end_note(
+    ctx: Context<'_>,
+    #[description = "`/end` のときに今日やったことを尋ねる（空欄でスキップ可）"] enabled: bool,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let pool = &ctx.data().pool;
+
+    match queries::set_end_note_prompt_enabled(pool, &guild_id.to_string(), enabled).await {
+        Ok(()) => {
+            let description = if enabled {
+                "`/end` のときに「今日やったこと」を入力するモーダルを表示します\n入力内容はその勤務のメモとして保存され、勤怠ログチャンネルと `/export csv`（日報付き）にも出力されます"
+            } else {
+                "「今日やったこと」の入力モーダルを表示しないようにしました"
+            };
+            let embed = create_success_embed("日報設定", description);
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+        }
+        Err(e) => {
+            AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// `/resume` で勤務終了を取り消せる期限を設定します
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn resume_window(
+    ctx: Context<'_>,
+    #[description = "終了から何分以内なら取り消せるか（既定 10分）"]
+    #[min = 1]
+    #[max = 120]
+    minutes: u32,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let pool = &ctx.data().pool;
+
+    let embed = match queries::set_resume_window(pool, &guild_id.to_string(), minutes as i32).await
+    {
+        Ok(()) => create_success_embed(
+            "勤務再開の設定",
+            &format!(
+                "勤務終了から{}分以内なら `/resume` で終了を取り消して勤務を再開できます",
+                minutes
+            ),
+        ),
+        Err(e) => {
+            return AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 記録の保持期間を設定します（期間を過ぎた記録は自動で削除）
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn retention(
+    ctx: Context<'_>,
+    #[description = "記録を保持する月数（未指定で無期限）"]
+    #[min = 1]
+    #[max = 120]
+    months: Option<u32>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let pool = &ctx.data().pool;
+
+    let result =
+        queries::set_retention_months(pool, &guild_id.to_string(), months.map(|m| m as i32)).await;
+    let embed = match (result, months) {
+        (Ok(()), Some(months)) => create_success_embed(
+            "保持期間の設定",
+            &format!(
+                "勤怠記録と勤務セッションを{}か月保持します。現在は {} より前の記録が1時間ごとに自動で削除されます（必要なら先に `/team report` のCSVなどで保存してください）",
+                months,
+                retention_cutoff(get_current_date_jst(), months).format("%Y-%m-%d")
+            ),
+        ),
+        (Ok(()), None) => create_success_embed(
+            "保持期間の設定",
+            "保持期間を解除しました。記録は自動で削除されません",
+        ),
+        (Err(e), _) => {
+            return AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 記録を追加・修正できる期間や過去日付の扱い、削除の承認を設定します（未指定の項目は現在の設定のまま）
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn edit_policy(
+    ctx: Context<'_>,
+    #[description = "何日前までの記録を追加・修正できるか（既定 7）"]
+    #[min = 1]
+    #[max = 90]
+    max_days: Option<u32>,
+    #[description = "今日より前の日付の記録の追加・修正を管理者のみに限定するか"]
+    admin_only_backdate: Option<bool>,
+    #[description = "メンバーの記録削除に承認を必要とするか（承認チャンネルが必要）"]
+    delete_approval: Option<bool>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let pool = &ctx.data().pool;
+
+    let current = edit_policy::load(pool, Some(guild_id), false).await;
+    let policy = EditPolicy {
+        max_age_days: max_days.map_or(current.max_age_days, i64::from),
+        backdate_admin_only: admin_only_backdate.unwrap_or(current.backdate_admin_only),
+        delete_requires_approval: delete_approval.unwrap_or(current.delete_requires_approval),
+    };
+    let summary = format!(
+        "追加・修正できる期間: 過去{}日以内\n過去の日付の記録: {}\n記録の削除: {}",
+        policy.max_age_days,
+        if policy.backdate_admin_only {
+            "管理者のみ"
+        } else {
+            "全員"
+        },
+        if policy.delete_requires_approval {
+            "承認が必要（承認チャンネル設定時）"
+        } else {
+            "承認不要"
+        }
+    );
+
+    let embed = if max_days.is_none() && admin_only_backdate.is_none() && delete_approval.is_none()
+    {
+        create_info_embed("編集ルール", &summary)
+    } else {
+        match queries::set_edit_policy(
+            pool,
+            &guild_id.to_string(),
+            policy.max_age_days as i32,
+            policy.backdate_admin_only,
+            policy.delete_requires_approval,
+        )
+        .await
+        {
+            Ok(()) => create_success_embed("編集ルールの設定", &summary),
+            Err(e) => {
+                return AppError::internal("設定の保存に失敗しました", e)
+                    .reply(ctx)
+                    .await;
+            }
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// サーバーの既定の表示言語を設定します（各メンバーの `/language` が優先）
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn language(
+    ctx: Context<'_>,
+    #[description = "表示言語"] language: LanguageChoice,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let pool = &ctx.data().pool;
+    let result =
+        queries::set_guild_language(pool, &guild_id.to_string(), language.lang().map(Lang::code))
+            .await;
+
+    let lang = preferences::load(ctx).await.lang;
+    let embed = match result {
+        Ok(()) => create_success_embed(
+            &t!(lang, "language.title"),
+            &match language.lang() {
+                Some(_) => t!(
+                    lang,
+                    "language.guild_set",
+                    language = poise::ChoiceParameter::name(&language)
+                ),
+                None => t!(lang, "language.guild_cleared"),
+            },
+        ),
+        Err(e) => {
+            return AppError::internal(t!(lang, "error.settings_save"), e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 時給を設定します（ユーザー未指定でギルドの既定値）
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn rate(
+    ctx: Context<'_>,
+    #[description = "時給（円）"]
+    #[min = 1]
+    hourly_rate: u32,
+    #[description = "時間外の時給（円、未指定で時給の1.25倍）"]
+    #[min = 1]
+    overtime_rate: Option<u32>,
+    #[description = "対象ユーザー（未指定でギルドの既定値）"] user: Option<serenity::User>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let pool = &ctx.data().pool;
+    let hourly_rate = hourly_rate as i64;
+    // 労働基準法の割増率（25%）を既定とする
+    let overtime_rate = overtime_rate.map_or(hourly_rate * 125 / 100, |rate| rate as i64);
+
+    let user_id = match &user {
+        Some(user) => {
+            match queries::create_or_get_user(
+                pool,
+                &user.id.to_string(),
+                ctx.guild_id(),
+                &user.name,
+            )
+            .await
+            {
+                Ok(db_user) => Some(db_user.id),
+                Err(e) => {
+                    return AppError::internal("ユーザー情報の取得に失敗しました", e)
+                        .reply(ctx)
+                        .await;
+                }
+            }
+        }
+        None => None,
+    };
+
+    match queries::set_rate(
+        pool,
+        &guild_id.to_string(),
+        user_id,
+        hourly_rate,
+        overtime_rate,
+    )
+    .await
+    {
+        Ok(()) => {
+            let target = match &user {
+                Some(user) => format!("<@{}>", user.id),
+                None => "ギルドの既定値".to_string(),
+            };
+            let embed = create_success_embed(
+                "時給設定",
+                &format!(
+                    "{} の時給を設定しました\n通常: {}/時\n時間外: {}/時",
+                    target,
+                    format_yen(hourly_rate),
+                    format_yen(overtime_rate)
+                ),
+            );
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+        }
+        Err(e) => {
+            AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 時間外勤務の判定に使う所定労働時間を設定します
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn overtime(
+    ctx: Context<'_>,
+    #[description = "1日の所定労働時間（時間）"]
+    #[min = 1]
+    #[max = 24]
+    daily_hours: u32,
+    #[description = "週の所定労働時間（時間）"]
+    #[min = 1]
+    #[max = 168]
+    weekly_hours: u32,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let pool = &ctx.data().pool;
+
+    match queries::set_overtime_thresholds(
+        pool,
+        &guild_id.to_string(),
+        daily_hours as i32 * 60,
+        weekly_hours as i32 * 60,
+    )
+    .await
+    {
+        Ok(()) => {
+            let embed = create_success_embed(
+                "所定労働時間設定",
+                &format!(
+                    "1日 {}時間・週 {}時間を超えた勤務を時間外として集計します",
+                    daily_hours, weekly_hours
+                ),
+            );
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+        }
+        Err(e) => {
+            AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 休憩の記録が足りない長時間勤務の日に休憩時間を自動控除します
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn break_rule(
+    ctx: Context<'_>,
+    #[description = "1日の勤務がこの時間を超えたら控除（時間、未指定で無効）"]
+    #[min = 1]
+    #[max = 24]
+    after_hours: Option<u32>,
+    #[description = "確保する休憩時間（分、既定 60）。記録された休憩が足りない分を控除"]
+    #[min = 1]
+    #[max = 240]
+    break_minutes: Option<u32>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let pool = &ctx.data().pool;
+    let break_minutes = break_minutes.unwrap_or(60);
+
+    match queries::set_break_rule(
+        pool,
+        &guild_id.to_string(),
+        after_hours.map(|hours| hours as i32 * 60),
+        break_minutes as i32,
+    )
+    .await
+    {
+        Ok(()) => {
+            let message = match after_hours {
+                Some(hours) => format!(
+                    "1日の勤務が {}時間を超え、記録された休憩（同じ日のセッションの間隔）が {}分に満たない日は、足りない分をレポートで控除します",
+                    hours, break_minutes
+                ),
+                None => "休憩の自動控除を無効にしました".to_string(),
+            };
+            let embed = create_success_embed("休憩の自動控除", &message);
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+        }
+        Err(e) => {
+            AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 打刻を作らずに、ユーザーのある日の勤務時間を分単位で加減します
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn adjust(
+    ctx: Context<'_>,
+    #[description = "対象ユーザー"] user: serenity::User,
+    #[description = "加減する分数（例: 30 で加算、-15 で減算）"]
+    #[min = -1440]
+    #[max = 1440]
+    minutes: i32,
+    #[description = "対象日 (YYYY-MM-DD、未指定で今日)"] date: Option<String>,
+    #[description = "理由（レポートと監査ログに表示）"] reason: Option<String>,
+) -> Result<(), Error> {
+    if minutes == 0 {
+        let embed = create_error_embed("入力エラー", "0分の調整は追加できません");
+        ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+    let target_date = match date.as_deref().map(validate_date_format) {
+        Some(Ok(date)) => date,
+        Some(Err(e)) => {
+            let embed = create_error_embed("入力エラー", &e.to_string());
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+        None => get_current_date_jst(),
+    };
+    let pool = &ctx.data().pool;
+    let audit = AuditSource::new(ctx.author().id, "/admin adjust").in_guild(ctx.guild_id());
+
+    let result =
+        match queries::create_or_get_user(pool, &user.id.to_string(), ctx.guild_id(), &user.name)
+            .await
+        {
+            Ok(db_user) => {
+                queries::create_time_adjustment(
+                    pool,
+                    db_user.id,
+                    target_date,
+                    minutes,
+                    reason.as_deref(),
+                    &audit,
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+
+    let embed = match result {
+        Ok(adjustment) => create_success_embed(
+            "勤務時間の調整",
+            &format!(
+                "<@{}> の {} の勤務時間を **{}** 調整しました{}\nレポートの合計に含まれます（取り消すときは逆の分数で調整してください）",
+                user.id,
+                adjustment.date.format("%Y-%m-%d"),
+                format_adjustment_minutes(adjustment.minutes),
+                adjustment
+                    .reason
+                    .as_deref()
+                    .map(|reason| format!("（{}）", reason))
+                    .unwrap_or_default()
+            ),
+        ),
+        Err(e) => {
+            return AppError::internal("調整の追加に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 帰宅などで終了されていないメンバーの勤務を、本人に代わって終了します
+#[poise::command(slash_command, guild_only, rename = "force-end", check = "is_admin")]
+pub async fn force_end(
+    ctx: Context<'_>,
+    #[description = "対象ユーザー"] user: serenity::User,
+    #[description = "終了時刻（HH:MM、勤務開始日の時刻。未指定で現在）"] time: Option<String>,
+) -> Result<(), Error> {
+    let time = match time.as_deref().map(validate_time_format).transpose() {
+        Ok(time) => time,
+        Err(e) => {
+            let embed = create_error_embed("入力エラー", &e.to_string());
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+    };
+    let pool = &ctx.data().pool;
+
+    let db_user =
+        match queries::create_or_get_user(pool, &user.id.to_string(), ctx.guild_id(), &user.name)
+            .await
+        {
+            Ok(db_user) => db_user,
+            Err(e) => {
+                return AppError::internal("ユーザー情報の取得に失敗しました", e)
+                    .reply(ctx)
+                    .await;
+            }
+        };
+    let session = match queries::get_active_work_session(pool, db_user.id).await {
+        Ok(Some(session)) => session,
+        Ok(None) => {
+            let embed = create_error_embed(
+                "勤務中ではありません",
+                &format!("<@{}> に終了していない勤務はありません", user.id),
+            );
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+        Err(e) => {
+            return AppError::internal("勤務記録の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    // 終了時刻は勤務を開始した日の記録として追加する（前日以前の勤務は時刻の指定が必要）
+    let timestamp = match time {
+        Some(time) => combine_date_time_jst(session.date, time),
+        None if session.date == get_current_date_jst() => chrono::Utc::now(),
+        None => {
+            let embed = create_error_embed(
+                "入力エラー",
+                &format!(
+                    "<@{}> の勤務は {} に開始されています。`time` で終了時刻を指定してください",
+                    user.id,
+                    session.date.format("%Y-%m-%d")
+                ),
+            );
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let audit = AuditSource::new(ctx.author().id, AuditSource::FORCE_END).in_guild(ctx.guild_id());
+    let policy = edit_policy::load(pool, ctx.guild_id(), true).await;
+    let started_at = match record_attendance(
+        pool,
+        &db_user,
+        RecordType::End,
+        timestamp,
+        ctx.guild_id(),
+        &audit,
+        &policy,
+    )
+    .await
+    {
+        Ok(started_at) => started_at.unwrap_or(session.start_time),
+        Err(error) => return error.reply(ctx).await,
+    };
+
+    let worked = format!(
+        "{} {} ～ {}（{}）",
+        session.date.format("%Y-%m-%d"),
+        format_time_jst(started_at),
+        format_time_jst(timestamp),
+        format_duration_minutes((timestamp - started_at).num_minutes() as i32)
+    );
+    let dm = create_info_embed(
+        "勤務が管理者によって終了されました",
+        &format!(
+            "<@{}> が終了していなかった勤務を終了しました\n{}\n時刻が違う場合は `/status` から修正するか、管理者に連絡してください",
+            ctx.author().id,
+            worked
+        ),
+    );
+    let notified = match user.create_dm_channel(ctx.http()).await {
+        Ok(channel) => channel
+            .send_message(ctx.http(), serenity::CreateMessage::new().embed(dm))
+            .await
+            .map(|_| ()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = &notified {
+        tracing::warn!("Failed to notify force-end to {}: {:?}", user.id, e);
+    }
+
+    let embed = create_success_embed(
+        "勤務を終了しました",
+        &format!(
+            "<@{}> の勤務を終了しました\n{}\n{}",
+            user.id,
+            worked,
+            if notified.is_ok() {
+                "本人にDMで通知しました"
+            } else {
+                "本人へのDMは送信できませんでした"
+            }
+        ),
+    );
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// ユーザーの月間勤務時間の上限を設定します（時間未指定で解除）
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn hour_cap(
+    ctx: Context<'_>,
+    #[description = "対象ユーザー"] user: serenity::User,
+    #[description = "月間の上限（時間、未指定で解除）"]
+    #[min = 1]
+    #[max = 744]
+    monthly_hours: Option<u32>,
+    #[description = "上限に近づいたときに管理者へ知らせるチャンネル"]
+    #[channel_types("Text")]
+    notify_channel: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    if let Some(channel) = &notify_channel
+        && !preflight::ensure_channel_permissions(
+            ctx,
+            channel,
+            serenity::Permissions::VIEW_CHANNEL
+                | serenity::Permissions::SEND_MESSAGES
+                | serenity::Permissions::EMBED_LINKS,
+            "月間上限の通知",
+        )
+        .await?
+    {
+        return Ok(());
+    }
+    let pool = &ctx.data().pool;
+
+    let db_user =
+        match queries::create_or_get_user(pool, &user.id.to_string(), ctx.guild_id(), &user.name)
+            .await
+        {
+            Ok(db_user) => db_user,
+            Err(e) => {
+                return AppError::internal("ユーザー情報の取得に失敗しました", e)
+                    .reply(ctx)
+                    .await;
+            }
+        };
+
+    let result = match monthly_hours {
+        Some(hours) => {
+            let channel_id = notify_channel
+                .as_ref()
+                .map(|channel| channel.id.to_string());
+            queries::set_hour_cap(
+                pool,
+                db_user.id,
+                &guild_id.to_string(),
+                hours as i32 * 60,
+                channel_id.as_deref(),
+            )
+            .await
+            .map(|()| {
+                let notify = match &notify_channel {
+                    Some(channel) => format!("\n上限に近づくと <#{}> にも通知します", channel.id),
+                    None => String::new(),
+                };
+                format!(
+                    "<@{}> の月間上限を {}時間 に設定しました\n実績が上限の{}%に達するか、月末の見込みが上限を超えると `/end` で警告します{}",
+                    user.id, hours, CAP_WARNING_PERCENT, notify
+                )
+            })
+        }
+        None => queries::delete_hour_cap(pool, db_user.id, &guild_id.to_string())
+            .await
+            .map(|deleted| {
+                if deleted {
+                    format!("<@{}> の月間上限を解除しました", user.id)
+                } else {
+                    format!("<@{}> には月間上限が設定されていません", user.id)
+                }
+            }),
+    };
+
+    let embed = match result {
+        Ok(description) => create_success_embed("月間上限設定", &description),
+        Err(e) => {
+            return AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 月ごとのタイムシート確認状況を表示します
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn confirmations(
+    ctx: Context<'_>,
+    #[description = "対象月 (YYYY-MM、未指定で今月)"] month: Option<String>,
+) -> Result<(), Error> {
+    let pool = &ctx.data().pool;
+
+    let target_date = match month.as_deref().map(validate_month_format) {
+        Some(Ok(date)) => date,
+        Some(Err(e)) => {
+            let embed = create_error_embed("入力エラー", &e.to_string());
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+        None => get_current_date_jst(),
+    };
+    let (start_of_month, end_of_month) = get_month_range(target_date);
+
+    let confirmations = match queries::get_timesheet_confirmations_by_date_range(
+        pool,
+        start_of_month,
+        end_of_month,
+    )
+    .await
+    {
+        Ok(confirmations) => confirmations,
+        Err(e) => {
+            return AppError::internal("確認状況の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    // ユーザーごとにまとめる（クエリは user_id 順）
+    let mut members: Vec<(User, Vec<TimesheetConfirmation>)> = Vec::new();
+    for confirmation in confirmations {
+        match members.last_mut() {
+            Some((user, list)) if user.id == confirmation.user_id => list.push(confirmation),
+            _ => {
+                let user = queries::get_user_by_id(pool, confirmation.user_id).await?;
+                members.push((user, vec![confirmation]));
+            }
+        }
+    }
+
+    let embed = create_info_embed(
+        &format!(
+            "📝 タイムシート確認状況 ({})",
+            start_of_month.format("%Y年%m月")
+        ),
+        &format_timesheet_confirmations(&members),
+    );
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// サーバー全員の月間の勤務時間・時間外・未終了セッションを集計します
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn monthly(
+    ctx: Context<'_>,
+    #[description = "対象月 (YYYY-MM、未指定で今月)"] month: Option<String>,
+    #[description = "CSVファイルで出力する"] csv: Option<bool>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let pool = &ctx.data().pool;
+
+    let target_date = match month.as_deref().map(validate_month_format) {
+        Some(Ok(date)) => date,
+        Some(Err(e)) => {
+            let embed = create_error_embed("入力エラー", &e.to_string());
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+        None => get_current_date_jst(),
+    };
+    let (start_of_month, end_of_month) = get_month_range(target_date);
+
+    let totals = match queries::get_guild_daily_totals(
+        pool,
+        &guild_id.to_string(),
+        start_of_month,
+        end_of_month,
+    )
+    .await
+    {
+        Ok(totals) => totals,
+        Err(e) => {
+            return AppError::internal("勤務記録の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    let thresholds = match queries::get_guild_settings(pool, &guild_id.to_string()).await {
+        Ok(settings) => settings
+            .as_ref()
+            .map(OvertimeThresholds::from)
+            .unwrap_or_default(),
+        Err(e) => {
+            tracing::warn!("Failed to load guild settings: {}", e);
+            OvertimeThresholds::default()
+        }
+    };
+    let summaries = MemberMonthSummary::from_daily_totals(&totals, thresholds);
+
+    let month_label = start_of_month.format("%Y年%m月").to_string();
+    let mut reply = poise::CreateReply::default()
+        .embed(create_guild_monthly_embed(&month_label, &summaries))
+        .ephemeral(true);
+
+    if csv.unwrap_or(false) {
+        let signed_csv = sign_export(
+            &format_guild_monthly_csv(&summaries),
+            ctx.data().config.export_signing_key.as_bytes(),
+        );
+        let filename = format!("kintai_monthly_{}.csv", start_of_month.format("%Y-%m"));
+        reply = reply.attachment(serenity::CreateAttachment::bytes(
+            signed_csv.into_bytes(),
+            filename,
+        ));
+    }
+
+    ctx.send(reply).await?;
+
+    Ok(())
+}
+
+/// 監査ログを表示します
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("audit_records", "audit_commands"),
+    check = "is_admin"
+)]
+pub async fn audit(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// ユーザーの勤務記録の変更履歴を表示します
+#[poise::command(slash_command, guild_only, rename = "records", check = "is_admin")]
+pub async fn audit_records(
+    ctx: Context<'_>,
+    #[description = "対象ユーザー"] user: serenity::User,
+    #[description = "対象日 (YYYY-MM-DD、未指定で今日)"] date: Option<String>,
+) -> Result<(), Error> {
+    let pool = &ctx.data().pool;
+
+    let target_date = match date.as_deref().map(validate_date_format) {
+        Some(Ok(date)) => date,
+        Some(Err(e)) => {
+            let embed = create_error_embed("入力エラー", &e.to_string());
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+        None => get_current_date_jst(),
+    };
+
+    let db_user =
+        match queries::get_user_by_discord_id(pool, &user.id.to_string(), ctx.guild_id()).await {
+            Ok(db_user) => db_user,
+            Err(_) => {
+                return AppError::user(format!("{} の勤務記録が見つかりません", user.name))
+                    .reply(ctx)
+                    .await;
+            }
+        };
+
+    match queries::get_audit_logs_for_date(pool, db_user.id, target_date).await {
+        Ok(logs) => {
+            let embed = create_info_embed(
+                &format!(
+                    "🔍 変更履歴 - {} ({})",
+                    db_user.username,
+                    target_date.format("%Y年%m月%d日")
+                ),
+                &format_audit_logs(&logs, Lang::Ja, TimeDisplay::default()),
+            );
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+        }
+        Err(e) => {
+            return AppError::internal("変更履歴の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    }
+
+    Ok(())
+}
+
+/// 管理者コマンドの実行履歴を表示します
+#[poise::command(slash_command, guild_only, rename = "commands", check = "is_admin")]
+pub async fn audit_commands(
+    ctx: Context<'_>,
+    #[description = "実行者で絞り込み"] user: Option<serenity::User>,
+    #[description = "表示件数（既定: 20）"]
+    #[min = 1]
+    #[max = 50]
+    limit: Option<u32>,
+) -> Result<(), Error> {
+    let pool = &ctx.data().pool;
+    let actor_id = user.as_ref().map(|user| user.id.to_string());
+    let limit = limit.unwrap_or(20).min(50);
+
+    match queries::get_command_audit_logs(pool, actor_id.as_deref(), limit as i64).await {
+        Ok(logs) => {
+            let embed = create_info_embed(
+                "🛡️ 管理者コマンド実行履歴",
+                &format_command_audit_logs(&logs),
+            );
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+        }
+        Err(e) => {
+            return AppError::internal("実行履歴の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    }
+
+    Ok(())
+}
+
+/// エクスポートを管理します
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("export_diff"),
+    check = "is_admin"
+)]
+pub async fn export(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// 署名付きエクスポート以降に変更された勤務記録を表示します
+#[poise::command(slash_command, guild_only, rename = "diff", check = "is_admin")]
+pub async fn export_diff(
+    ctx: Context<'_>,
+    #[description = "対象ユーザー"] user: serenity::User,
+    #[description = "以前に /export csv で出力したファイル"] file: serenity::Attachment,
+    #[description = "対象月 (YYYY-MM、未指定でファイルの内容から判定)"] month: Option<String>,
+) -> Result<(), Error> {
+    let pool = &ctx.data().pool;
+
+    let content = match file.download().await {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Err(e) => {
+            return AppError::internal("ファイルのダウンロードに失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    // 改変されたファイルとの差分は意味を持たないため、署名が一致するものだけ比較する
+    let previous = match verify_export(&content, ctx.data().config.export_signing_key.as_bytes()) {
+        ExportVerification::Valid => parse_sessions_csv(&content),
+        ExportVerification::Invalid => Err(anyhow::anyhow!(
+            "署名が一致しません。生成後に改変された可能性があります"
+        )),
+        ExportVerification::Unsigned => Err(anyhow::anyhow!("署名が含まれていません")),
+    };
+    let previous = match previous {
+        Ok(previous) => previous,
+        Err(e) => {
+            let embed =
+                create_error_embed("比較できません", &format!("`{}`: {}", file.filename, e));
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let target_month = match month.as_deref() {
+        Some(month) => validate_month_format(month),
+        None => previous
+            .first()
+            .and_then(|row| chrono::NaiveDate::parse_from_str(&row.date, "%Y-%m-%d").ok())
+            .ok_or_else(|| anyhow::anyhow!("ファイルに記録が無いため、対象月を指定してください")),
+    };
+    let (start_of_month, end_of_month) = match target_month {
+        Ok(date) => get_month_range(date),
+        Err(e) => {
+            let embed = create_error_embed("入力エラー", &e.to_string());
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let db_user =
+        match queries::get_user_by_discord_id(pool, &user.id.to_string(), ctx.guild_id()).await {
+            Ok(db_user) => db_user,
+            Err(_) => {
+                return AppError::user(format!("{} の勤務記録が見つかりません", user.name))
+                    .reply(ctx)
+                    .await;
+            }
+        };
+
+    // 現在の記録を同じ形式で出力し直して比較する
+    let current = match queries::get_work_sessions_by_date_range(
+        pool,
+        db_user.id,
+        start_of_month,
+        end_of_month,
+    )
+    .await
+    {
+        Ok(sessions) => parse_sessions_csv(&format_sessions_csv(
+            &sessions,
+            ExportTemplate::Standard,
+            None,
+        )),
+        Err(e) => Err(e),
+    };
+    let embed = match current {
+        Ok(current) => create_info_embed(
+            &format!(
+                "🔀 エクスポート差分 - {} ({})",
+                db_user.username,
+                start_of_month.format("%Y年%m月")
+            ),
+            &format_export_diff(&diff_sessions(&previous, &current)),
+        ),
+        Err(e) => {
+            return AppError::internal("勤務記録の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 1回の取り込みで遡るメッセージ数の上限
+const BACKFILL_MAX_MESSAGES: u32 = 10_000;
+
+/// チャンネルの「出勤/退勤」メッセージ履歴から勤務記録を取り込みます
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn backfill(
+    ctx: Context<'_>,
+    #[description = "取り込み元のチャンネル"]
+    #[channel_types("Text")]
+    channel: serenity::GuildChannel,
+    #[description = "遡るメッセージ数（既定: 1000）"]
+    #[min = 1]
+    #[max = 10000]
+    limit: Option<u32>,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    if !preflight::ensure_message_content_intent(ctx, "`/admin backfill`").await?
+        || !preflight::ensure_channel_permissions(
+            ctx,
+            &channel,
+            serenity::Permissions::VIEW_CHANNEL | serenity::Permissions::READ_MESSAGE_HISTORY,
+            "`/admin backfill`",
+        )
+        .await?
+    {
+        return Ok(());
+    }
+
+    let pool = &ctx.data().pool;
+    let limit = limit.unwrap_or(1000).min(BACKFILL_MAX_MESSAGES);
+    let audit = AuditSource::new(ctx.author().id, "/admin backfill");
+
+    let mut scanned = 0u32;
+    let mut imported = 0u32;
+    let mut duplicates = 0u32;
+    let mut affected: BTreeSet<(i64, chrono::NaiveDate)> = BTreeSet::new();
+    let mut before: Option<serenity::MessageId> = None;
+
+    while scanned < limit {
+        let mut request = serenity::GetMessages::new().limit((limit - scanned).min(100) as u8);
+        if let Some(before) = before {
+            request = request.before(before);
+        }
+
+        let messages = match channel.messages(ctx, request).await {
+            Ok(messages) => messages,
+            Err(e) => {
+                return AppError::internal("メッセージ履歴の取得に失敗しました", e)
+                    .reply(ctx)
+                    .await;
+            }
+        };
+        let Some(oldest) = messages.last() else {
+            break;
+        };
+        before = Some(oldest.id);
+        scanned += messages.len() as u32;
+
+        for message in &messages {
+            if message.author.bot {
+                continue;
+            }
+            let Some(record_type) = parse_attendance_message(&message.content) else {
+                continue;
+            };
+            let Some(timestamp) = DateTime::from_timestamp(message.timestamp.unix_timestamp(), 0)
+            else {
+                continue;
+            };
+
+            let user = queries::create_or_get_user(
+                pool,
+                &message.author.id.to_string(),
+                ctx.guild_id(),
+                &message.author.name,
+            )
+            .await?;
+
+            if queries::import_attendance_record(pool, user.id, record_type, timestamp, &audit)
+                .await?
+            {
+                imported += 1;
+                affected.insert((user.id, get_date_from_utc_timestamp(timestamp)));
+            } else {
+                duplicates += 1;
+            }
+        }
+    }
+
+    // 取り込んだ日のセッションを再計算
+    let session_manager = SessionManager::new(pool.clone());
+    for (user_id, date) in &affected {
+        if let Err(e) = session_manager.trigger_recalculation(*user_id, *date).await {
+            tracing::error!("Failed to recalculate sessions: {}", e);
+        }
+    }
+
+    let embed = create_success_embed(
+        "履歴の取り込み完了",
+        &format!(
+            "<#{}> のメッセージ {}件を確認し、{}件の記録を取り込みました\n（取り込み済みのためスキップ: {}件）",
+            channel.id, scanned, imported, duplicates
+        ),
+    );
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 取り込み結果に表示するエラー行数の上限
+const IMPORT_MAX_ERROR_LINES: usize = 10;
+
+/// CSV（date,start,end）から過去の勤務記録を取り込みます
+#[poise::command(slash_command, guild_only, rename = "import", check = "is_admin")]
+pub async fn import_csv(
+    ctx: Context<'_>,
+    #[description = "対象ユーザー"] user: serenity::User,
+    #[description = "date,start,end 形式のCSV（例: 2024-04-01,09:00,18:00）"]
+    file: serenity::Attachment,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let pool = &ctx.data().pool;
+    let content = match file.download().await {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Err(e) => {
+            return AppError::internal("ファイルのダウンロードに失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    let parsed = match parse_attendance_csv(&content) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            let embed =
+                create_error_embed("取り込めません", &format!("`{}`: {}", file.filename, e));
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let db_user =
+        queries::create_or_get_user(pool, &user.id.to_string(), ctx.guild_id(), &user.name).await?;
+    let audit = AuditSource::new(ctx.author().id, "/admin import");
+
+    let mut imported = 0usize;
+    let mut errors = parsed.errors;
+    let mut affected: BTreeSet<chrono::NaiveDate> = BTreeSet::new();
+
+    for row in &parsed.rows {
+        // 前の行で追加した記録も含めて検証するため、行ごとに取り直す
+        let records = queries::get_records_by_date(pool, db_user.id, row.date).await?;
+        if let Err(e) = validate_import_row(&records, row) {
+            errors.push((row.line, e.to_string()));
+            continue;
+        }
+
+        let result = async {
+            queries::import_attendance_record(
+                pool,
+                db_user.id,
+                RecordType::Start,
+                row.start,
+                &audit,
+            )
+            .await?;
+            if let Some(end) = row.end {
+                queries::import_attendance_record(pool, db_user.id, RecordType::End, end, &audit)
+                    .await?;
+            }
+            Ok::<_, anyhow::Error>(())
+        }
+        .await;
+        match result {
+            Ok(()) => {
+                imported += 1;
+                affected.insert(row.date);
+                affected.extend(row.end.map(get_date_from_utc_timestamp));
+            }
+            Err(e) => {
+                tracing::error!("Failed to import line {}: {:?}", row.line, e);
+                errors.push((row.line, "記録の保存に失敗しました".to_string()));
+            }
+        }
+    }
+
+    // 取り込んだ日のセッションを再計算
+    let session_manager = SessionManager::new(pool.clone());
+    for date in &affected {
+        if let Err(e) = session_manager
+            .trigger_recalculation(db_user.id, *date)
+            .await
+        {
+            tracing::error!("Failed to recalculate sessions: {}", e);
+        }
+    }
+
+    errors.sort_by_key(|(line, _)| *line);
+    let mut description = format!(
+        "{} の勤務記録を{}行取り込みました（スキップ: {}行）",
+        user.name,
+        imported,
+        errors.len()
+    );
+    if !errors.is_empty() {
+        description.push('\n');
+        for (line, message) in errors.iter().take(IMPORT_MAX_ERROR_LINES) {
+            description.push_str(&format!("\n{}行目: {}", line, message));
+        }
+        if errors.len() > IMPORT_MAX_ERROR_LINES {
+            description.push_str(&format!(
+                "\n…ほか{}行",
+                errors.len() - IMPORT_MAX_ERROR_LINES
+            ));
+        }
+    }
+    let embed = create_success_embed("CSVの取り込み完了", &description);
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 起動時と同じ自己診断を実行します
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn diagnostics(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_ids = ctx.cache().guilds();
+    let results = run_diagnostics(&ctx.serenity_context().http, ctx.data(), &guild_ids).await;
+    let report = format_report(&results);
+
+    let embed = if results
+        .iter()
+        .all(|result| result.status == CheckStatus::Ok)
+    {
+        create_success_embed("自己診断", &report)
+    } else {
+        create_error_embed("自己診断", &report)
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 全員が使える出勤・退勤ボタンをこのチャンネルに投稿します
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn kiosk(ctx: Context<'_>) -> Result<(), Error> {
+    let (embed, components) = kiosk_message();
+    let result = ctx
+        .channel_id()
+        .send_message(
+            ctx.http(),
+            serenity::CreateMessage::new()
+                .embed(embed)
+                .components(components),
+        )
+        .await;
+
+    let embed = match result {
+        Ok(_) => create_success_embed(
+            "出退勤ボタンを投稿しました",
+            "メンバーはボタンを押すだけで出勤・退勤を記録できます。不要になったらメッセージを削除してください",
+        ),
+        Err(e) => {
+            return AppError::internal(
+                "メッセージを投稿できませんでした（このチャンネルへの送信権限を確認してください）",
+                e,
+            )
+            .reply(ctx)
+            .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 記録のバリデーションエラーの種類別件数を表示します
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn metrics(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+
+    let embed =
+        match queries::get_validation_rejections(&ctx.data().pool, &guild_id.to_string()).await {
+            Ok(rejections) => create_info_embed(
+                "📈 記録のバリデーションエラー",
+                &format_validation_rejections(&rejections),
+            ),
+            Err(e) => {
+                return AppError::internal("集計の取得に失敗しました", e)
+                    .reply(ctx)
+                    .await;
+            }
+        };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 指定した月数より古い勤怠記録と勤務セッションを削除します
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn purge(
+    ctx: Context<'_>,
+    #[description = "この月数より古い記録を削除（今月の月初から数える）"]
+    #[min = 1]
+    #[max = 120]
+    months: u32,
+    #[description = "True で実際に削除する（未指定なら削除される件数を表示するだけ）"]
+    confirm: Option<bool>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let pool = &ctx.data().pool;
+    let confirm = confirm.unwrap_or(false);
+    let cutoff = retention_cutoff(get_current_date_jst(), months);
+
+    let embed = match queries::purge_records_before(pool, &guild_id.to_string(), cutoff, !confirm)
+        .await
+    {
+        Ok((records, sessions)) if confirm => create_success_embed(
+            "古い記録の削除",
+            &format!(
+                "{} より前の勤怠記録 {}件と勤務セッション {}件を削除しました",
+                cutoff.format("%Y-%m-%d"),
+                records,
+                sessions
+            ),
+        ),
+        Ok((records, sessions)) => create_info_embed(
+            "古い記録の削除（確認）",
+            &format!(
+                "{} より前の勤怠記録 {}件と勤務セッション {}件が削除されます。必要なら先に `/team report` のCSVなどで保存し、`confirm: True` を付けて実行してください",
+                cutoff.format("%Y-%m-%d"),
+                records,
+                sessions
+            ),
+        ),
+        Err(e) => {
+            return AppError::internal("記録の削除に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// エクスポート後、このサーバーの勤怠データを全て削除します
+#[poise::command(slash_command, guild_only, rename = "reset-guild", check = "is_admin")]
+pub async fn reset_guild(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    ctx.defer_ephemeral().await?;
+
+    let pool = &ctx.data().pool;
+    // 削除前のエクスポートを必ず渡し、その対象と同じユーザーだけを削除する
+    let prepared = async {
+        let members = guild_reset::collect_members(ctx.http(), pool, guild_id).await?;
+        let json = guild_reset::export_json(pool, guild_id, &members).await?;
+        let state_id = flow_state::save(
+            pool,
+            &ctx.author().id.to_string(),
+            FlowState::ResetGuild {
+                guild_id: guild_id.get(),
+                user_ids: members.iter().map(|user| user.id).collect(),
+            },
+        )
+        .await?;
+        Ok::<_, anyhow::Error>((members.len(), json, state_id))
+    }
+    .await;
+    let (member_count, json, state_id) = match prepared {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            return AppError::internal("削除前のエクスポートに失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    let embed = create_error_embed(
+        "⚠️ サーバーのデータ削除",
+        &format!(
+            "{}人分の打刻記録・勤務セッションと、このサーバーの設定（ダイジェスト・承認・時給・プロジェクトなど）を削除します。\n\
+             削除すると元に戻せません。添付のエクスポートを保存してから、ボタンを押してサーバー名を入力してください（24時間有効）",
+            member_count
+        ),
+    );
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(embed)
+            .attachment(serenity::CreateAttachment::bytes(
+                json.into_bytes(),
+                format!("kintai_guild_{}_backup.json", guild_id),
+            ))
+            .components(vec![guild_reset::reset_button(ctx.author().id, state_id)])
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -1,31 +1,149 @@
-use crate::bot::{Context, Error};
-use crate::database::models::RecordType;
+use crate::bot::interactions::note_buttons;
+use crate::bot::preferences::{self, Preferences};
+use crate::bot::punch_journal::{PendingPunch, is_database_unavailable};
+use crate::bot::{AppError, Context, Error, edit_policy, hour_caps, metrics, punch_lock, webhooks};
+use crate::database::models::{AttendanceRecord, AuditSource, ProjectPreference, RecordType};
 use crate::database::queries;
-use crate::utils::format::{create_error_embed, create_success_embed};
+use crate::t;
+use crate::utils::format::{create_error_embed, create_info_embed, create_success_embed};
+use crate::utils::i18n::Lang;
+use crate::utils::record_validator::RecordValidator;
 use crate::utils::session_manager::SessionManager;
-use crate::utils::time::{get_current_datetime_jst, get_date_from_utc_timestamp};
+use crate::utils::time::{
+    format_duration, get_current_date_jst, get_current_datetime_jst, get_date_from_utc_timestamp,
+};
+use crate::utils::validation::{
+    validate_project_name, validate_session_note, validate_time_format,
+};
+use chrono::{DateTime, Utc};
+use poise::serenity_prelude as serenity;
 
 /// 勤務を開始します
 #[poise::command(slash_command)]
-pub async fn start(ctx: Context<'_>) -> Result<(), Error> {
+pub async fn start(
+    ctx: Context<'_>,
+    #[description = "作業するプロジェクト（未指定で既定のもの、未登録の名前は新規作成）"]
+    #[autocomplete = "autocomplete_project"]
+    project: Option<String>,
+    #[description = "開始した時刻（HH:MM、未指定で現在時刻）"] time: Option<String>,
+) -> Result<(), Error> {
+    clock_in(ctx, project, time).await
+}
+
+/// 勤務中なら終了、そうでなければ開始します
+#[poise::command(slash_command)]
+pub async fn punch(ctx: Context<'_>) -> Result<(), Error> {
+    let user_id = ctx.author().id.to_string();
+    let guild_id = ctx.guild_id().map(|guild_id| guild_id.to_string());
+
+    // 保留中の打刻がある場合は、DBではなく最後に受け付けた打刻で判定する
+    let pending = ctx
+        .data()
+        .punch_journal
+        .pending()
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .find(|punch| punch.discord_id == user_id && punch.guild_id == guild_id);
+    let working = match pending {
+        Some(punch) => punch.record_type == RecordType::Start,
+        None => {
+            let pool = &ctx.data().pool;
+            let today = get_current_date_jst();
+            let username = &ctx.author().name;
+            let records =
+                match queries::create_or_get_user(pool, &user_id, ctx.guild_id(), username).await {
+                    Ok(user) => queries::get_today_records(pool, user.id, today).await,
+                    Err(e) => Err(e),
+                };
+            match records {
+                Ok(records) => records
+                    .iter()
+                    .rev()
+                    .find(|record| record.record_type.is_session_boundary())
+                    .is_some_and(|record| record.record_type == RecordType::Start),
+                Err(e) => {
+                    let lang = preferences::load(ctx).await.lang;
+                    let error = if is_database_unavailable(&e) {
+                        AppError::user(t!(lang, "punch.unknown_state"))
+                    } else {
+                        AppError::internal(t!(lang, "error.records_lookup"), e)
+                    };
+                    return error.reply(ctx).await;
+                }
+            }
+        }
+    };
+
+    if working {
+        clock_out(ctx, None, None).await
+    } else {
+        clock_in(ctx, None, None).await
+    }
+}
+
+async fn clock_in(
+    ctx: Context<'_>,
+    project: Option<String>,
+    time: Option<String>,
+) -> Result<(), Error> {
     let user_id = ctx.author().id.to_string();
     let username = ctx.author().name.clone();
     let pool = &ctx.data().pool;
+    // DBに接続できないときは既定の設定とDiscordのロケールになる
+    let prefs = preferences::load(ctx).await;
+    let lang = prefs.lang;
+    // DBに書き込めず保留にする場合も、受け付けた時刻（または指定した時刻）で記録する
+    let Some(current_datetime) = punch_time(ctx, &prefs, time.as_deref()).await? else {
+        return Ok(());
+    };
+
+    if ctx.data().punch_journal.has_pending(&user_id) {
+        return queue_punch(ctx, &prefs, RecordType::Start, current_datetime, None).await;
+    }
 
     // Create or get user
-    let user = match queries::create_or_get_user(pool, &user_id, &username).await {
+    let user = match queries::create_or_get_user(pool, &user_id, ctx.guild_id(), &username).await {
         Ok(user) => user,
+        Err(e) if is_database_unavailable(&e) => {
+            return queue_punch(ctx, &prefs, RecordType::Start, current_datetime, None).await;
+        }
         Err(e) => {
-            let embed = create_error_embed(
-                "エラー",
-                &format!("ユーザー情報の取得に失敗しました: {}", e),
-            );
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
-            return Ok(());
+            return AppError::internal(t!(lang, "error.user_lookup"), e)
+                .reply(ctx)
+                .await;
         }
     };
+    // 連打や同時実行で開始が二重に記録されないよう、記録するまで他の打刻を待たせる
+    let _punch_guard = punch_lock::lock(user.id).await;
+
+    let project = match project {
+        Some(name) => match resolve_project(ctx, &name).await {
+            Ok(project) => Some(project),
+            Err(e) => {
+                e.log();
+                let embed =
+                    create_error_embed(&t!(lang, "start.project_error_title"), &e.to_string());
+                ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                    .await?;
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+
+    let preference = match ctx.guild_id() {
+        Some(guild_id) => queries::get_project_preference(pool, user.id, &guild_id.to_string())
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to load project preference: {}", e);
+                ProjectPreference::default()
+            }),
+        None => ProjectPreference::default(),
+    };
+    // 指定したプロジェクトはこの勤務だけに使い、既定のプロジェクトは変えない
+    let project = project.or(preference.default_project);
 
-    let current_datetime = get_current_datetime_jst().to_utc();
     // Use the date from the actual timestamp being stored
     let current_date = get_date_from_utc_timestamp(current_datetime);
 
@@ -39,21 +157,22 @@ pub async fn start(ctx: Context<'_>) -> Result<(), Error> {
     // Check if there's already an unpaired start record
     let today_records = match queries::get_today_records(pool, user.id, current_date).await {
         Ok(records) => records,
+        Err(e) if is_database_unavailable(&e) => {
+            return queue_punch(ctx, &prefs, RecordType::Start, current_datetime, None).await;
+        }
         Err(e) => {
-            let embed =
-                create_error_embed("エラー", &format!("勤務記録の取得に失敗しました: {}", e));
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
-            return Ok(());
+            return AppError::internal(t!(lang, "error.records_lookup"), e)
+                .reply(ctx)
+                .await;
         }
     };
 
-    // Debug: Log all records
-    tracing::info!(
+    tracing::debug!(
         "Start command - Today's records count: {}",
         today_records.len()
     );
     for (i, record) in today_records.iter().enumerate() {
-        tracing::info!(
+        tracing::debug!(
             "Record {}: type={}, timestamp={:?}",
             i,
             record.record_type,
@@ -61,31 +180,69 @@ pub async fn start(ctx: Context<'_>) -> Result<(), Error> {
         );
     }
 
-    // Check if the last record is an unpaired start
-    if let Some(last_record) = today_records.last() {
+    // Check if the last record is an unpaired start (breaks and away records don't end a session)
+    let previous_record = today_records.iter().rev().find(|record| {
+        record.record_type.is_session_boundary() && record.timestamp < current_datetime
+    });
+    if let Some(last_record) = previous_record {
         tracing::info!("Last record type: {}", last_record.record_type);
-        if last_record.record_type == "start" {
+        if last_record.record_type == RecordType::Start {
             let embed = create_error_embed(
-                "既に勤務中です",
-                &format!(
-                    "開始時刻: {}\n先に `/end` で終了してください。",
-                    crate::utils::time::format_time_jst(last_record.timestamp)
+                &t!(lang, "start.already_working_title"),
+                &t!(
+                    lang,
+                    "start.already_working",
+                    time = prefs.clock.time(last_record.timestamp)
                 ),
             );
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+            ctx.send(
+                poise::CreateReply::default()
+                    .embed(embed)
+                    .ephemeral(prefs.ephemeral),
+            )
+            .await?;
             return Ok(());
         }
     } else {
         tracing::info!("No records found for today");
     }
 
+    if time.is_some()
+        && !validate_specified_time(
+            ctx,
+            &today_records,
+            RecordType::Start,
+            current_datetime,
+            lang,
+        )
+        .await?
+    {
+        return Ok(());
+    }
+
     // Create attendance record
     tracing::info!("Creating start record for user {}", user.id);
-    match queries::create_attendance_record(pool, user.id, RecordType::Start, current_datetime)
-        .await
+    let audit = AuditSource::new(ctx.author().id, "/start").in_guild(ctx.guild_id());
+    match queries::create_attendance_record(
+        pool,
+        user.id,
+        RecordType::Start,
+        current_datetime,
+        &audit,
+    )
+    .await
     {
-        Ok(_) => {
+        Ok(record) => {
             tracing::info!("Start record created successfully");
+            webhooks::notify_record(
+                pool,
+                ctx.guild_id(),
+                &user,
+                RecordType::Start,
+                current_datetime,
+                &audit,
+            )
+            .await;
             // Recalculate sessions after adding start record
             let session_manager = SessionManager::new(pool.clone());
             if let Err(e) = session_manager
@@ -95,46 +252,142 @@ pub async fn start(ctx: Context<'_>) -> Result<(), Error> {
                 tracing::error!("Failed to recalculate sessions: {}", e);
             }
 
-            let embed = create_success_embed(
-                "勤務開始",
-                &format!(
-                    "勤務を開始しました\n開始時刻: {}",
-                    crate::utils::time::format_time_jst(current_datetime)
-                ),
+            let mut description = t!(
+                lang,
+                "start.done",
+                time = prefs.clock.time(current_datetime)
             );
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+            if let Some((project_id, name)) = &project {
+                match queries::set_session_project(pool, record.id, Some(*project_id)).await {
+                    Ok(()) => {
+                        description.push('\n');
+                        description.push_str(&t!(lang, "start.project", name = name));
+                    }
+                    Err(e) => tracing::error!("Failed to set session project: {}", e),
+                }
+                if let Some(guild_id) = ctx.guild_id()
+                    && let Err(e) =
+                        queries::set_last_project(pool, user.id, &guild_id.to_string(), *project_id)
+                            .await
+                {
+                    tracing::error!("Failed to save last project: {}", e);
+                }
+            }
+
+            let embed = create_success_embed(&t!(lang, "start.title"), &description);
+            let mut reply = poise::CreateReply::default()
+                .embed(embed)
+                .ephemeral(prefs.ephemeral);
+            // 前回と違うプロジェクト（または未設定）で始めたときは前回のものをワンタップで選べるようにする
+            if let Some((last_id, last_name)) = preference.last_project
+                && project.as_ref().map(|(id, _)| *id) != Some(last_id)
+            {
+                reply = reply.components(vec![serenity::CreateActionRow::Buttons(vec![
+                    serenity::CreateButton::new(format!(
+                        "start_project:{}:{}:{}",
+                        ctx.author().id,
+                        record.id,
+                        last_id
+                    ))
+                    .label(t!(lang, "start.last_project_button", name = last_name))
+                    .style(serenity::ButtonStyle::Secondary),
+                ])]);
+            }
+            ctx.send(reply).await?;
+        }
+        Err(e) if is_database_unavailable(&e) => {
+            return queue_punch(ctx, &prefs, RecordType::Start, current_datetime, None).await;
         }
         Err(e) => {
-            let embed =
-                create_error_embed("エラー", &format!("勤務記録の作成に失敗しました: {}", e));
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+            return AppError::internal(t!(lang, "error.record_create"), e)
+                .reply(ctx)
+                .await;
         }
     }
 
     Ok(())
 }
 
+/// プロジェクト名を検証し、ギルドのプロジェクトIDと正規化した名前を返す
+pub async fn resolve_project(ctx: Context<'_>, name: &str) -> Result<(i64, String), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Err(AppError::user("プロジェクトはサーバー内でのみ指定できます"));
+    };
+    let name = validate_project_name(name).map_err(|e| AppError::user(e.to_string()))?;
+    let project_id =
+        queries::get_or_create_project(&ctx.data().pool, &guild_id.to_string(), &name).await?;
+    Ok((project_id, name))
+}
+
+pub async fn autocomplete_project(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Vec::new();
+    };
+
+    queries::get_projects(&ctx.data().pool, &guild_id.to_string())
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|project| project.name)
+        .filter(|name| name.contains(partial))
+        .collect()
+}
+
 /// 勤務を終了します
 #[poise::command(slash_command)]
-pub async fn end(ctx: Context<'_>) -> Result<(), Error> {
+pub async fn end(
+    ctx: Context<'_>,
+    #[description = "今回の勤務に残すメモ（200文字以内）"]
+    #[max_length = 200]
+    note: Option<String>,
+    #[description = "終了した時刻（HH:MM、未指定で現在時刻）"] time: Option<String>,
+) -> Result<(), Error> {
+    clock_out(ctx, note, time).await
+}
+
+async fn clock_out(
+    ctx: Context<'_>,
+    note: Option<String>,
+    time: Option<String>,
+) -> Result<(), Error> {
     let user_id = ctx.author().id.to_string();
     let username = ctx.author().name.clone();
     let pool = &ctx.data().pool;
+    // DBに接続できないときは既定の設定とDiscordのロケールになる
+    let prefs = preferences::load(ctx).await;
+    let lang = prefs.lang;
+    // DBに書き込めず保留にする場合も、受け付けた時刻（または指定した時刻）で記録する
+    let Some(current_datetime) = punch_time(ctx, &prefs, time.as_deref()).await? else {
+        return Ok(());
+    };
 
-    // Create or get user
-    let user = match queries::create_or_get_user(pool, &user_id, &username).await {
-        Ok(user) => user,
+    let note = match note.as_deref().map(validate_session_note).transpose() {
+        Ok(note) => note.flatten(),
         Err(e) => {
-            let embed = create_error_embed(
-                "エラー",
-                &format!("ユーザー情報の取得に失敗しました: {}", e),
-            );
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+            let embed = create_error_embed(&t!(lang, "error.input_title"), &e.to_string());
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
             return Ok(());
         }
     };
 
-    let current_datetime = get_current_datetime_jst().to_utc();
+    if ctx.data().punch_journal.has_pending(&user_id) {
+        return queue_punch(ctx, &prefs, RecordType::End, current_datetime, note).await;
+    }
+
+    // Create or get user
+    let user = match queries::create_or_get_user(pool, &user_id, ctx.guild_id(), &username).await {
+        Ok(user) => user,
+        Err(e) if is_database_unavailable(&e) => {
+            return queue_punch(ctx, &prefs, RecordType::End, current_datetime, note).await;
+        }
+        Err(e) => {
+            return AppError::internal(t!(lang, "error.user_lookup"), e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    let _punch_guard = punch_lock::lock(user.id).await;
 
     // Check if there's an unpaired start record
     let current_date = get_date_from_utc_timestamp(current_datetime);
@@ -148,21 +401,22 @@ pub async fn end(ctx: Context<'_>) -> Result<(), Error> {
 
     let today_records = match queries::get_today_records(pool, user.id, current_date).await {
         Ok(records) => records,
+        Err(e) if is_database_unavailable(&e) => {
+            return queue_punch(ctx, &prefs, RecordType::End, current_datetime, note).await;
+        }
         Err(e) => {
-            let embed =
-                create_error_embed("エラー", &format!("勤務記録の取得に失敗しました: {}", e));
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
-            return Ok(());
+            return AppError::internal(t!(lang, "error.records_lookup"), e)
+                .reply(ctx)
+                .await;
         }
     };
 
-    // Debug: Log all records
-    tracing::info!(
+    tracing::debug!(
         "End command - Today's records count: {}",
         today_records.len()
     );
     for (i, record) in today_records.iter().enumerate() {
-        tracing::info!(
+        tracing::debug!(
             "Record {}: type={}, timestamp={:?}",
             i,
             record.record_type,
@@ -170,32 +424,72 @@ pub async fn end(ctx: Context<'_>) -> Result<(), Error> {
         );
     }
 
-    // Check if the last record is an unpaired start
-    let start_record = match today_records.last() {
-        Some(record) if record.record_type == "start" => {
+    // Check if the last record is an unpaired start (breaks and away records don't end a session)
+    let start_record = match today_records.iter().rev().find(|record| {
+        record.record_type.is_session_boundary() && record.timestamp < current_datetime
+    }) {
+        Some(record) if record.record_type == RecordType::Start => {
             tracing::info!("Found unpaired start record");
             record
         }
         Some(record) => {
             tracing::info!("Last record is not start, it's: {}", record.record_type);
-            let embed =
-                create_error_embed("勤務中ではありません", "先に `/start` で開始してください。");
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+            let embed = create_error_embed(
+                &t!(lang, "end.not_working_title"),
+                &t!(lang, "end.not_working"),
+            );
+            ctx.send(
+                poise::CreateReply::default()
+                    .embed(embed)
+                    .ephemeral(prefs.ephemeral),
+            )
+            .await?;
             return Ok(());
         }
         None => {
             tracing::info!("No records found for today");
-            let embed =
-                create_error_embed("勤務中ではありません", "先に `/start` で開始してください。");
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+            let embed = create_error_embed(
+                &t!(lang, "end.not_working_title"),
+                &t!(lang, "end.not_working"),
+            );
+            ctx.send(
+                poise::CreateReply::default()
+                    .embed(embed)
+                    .ephemeral(prefs.ephemeral),
+            )
+            .await?;
             return Ok(());
         }
     };
 
+    if time.is_some()
+        && !validate_specified_time(ctx, &today_records, RecordType::End, current_datetime, lang)
+            .await?
+    {
+        return Ok(());
+    }
+
     // Create attendance record
-    match queries::create_attendance_record(pool, user.id, RecordType::End, current_datetime).await
+    let audit = AuditSource::new(ctx.author().id, "/end").in_guild(ctx.guild_id());
+    match queries::create_attendance_record(
+        pool,
+        user.id,
+        RecordType::End,
+        current_datetime,
+        &audit,
+    )
+    .await
     {
-        Ok(_) => {
+        Ok(end_record) => {
+            webhooks::notify_record(
+                pool,
+                ctx.guild_id(),
+                &user,
+                RecordType::End,
+                current_datetime,
+                &audit,
+            )
+            .await;
             // Recalculate sessions after adding end record
             let session_manager = SessionManager::new(pool.clone());
             if let Err(e) = session_manager
@@ -205,26 +499,191 @@ pub async fn end(ctx: Context<'_>) -> Result<(), Error> {
                 tracing::error!("Failed to recalculate sessions: {}", e);
             }
 
+            // 「今日やったこと」を尋ねる設定なら、結果はモーダルの送信時に表示する
+            if note.is_none()
+                && let poise::Context::Application(app_ctx) = ctx
+                && end_note_prompt_enabled(pool, ctx.guild_id()).await
+                && let Ok(Some(session)) =
+                    queries::get_work_session_for_record(pool, end_record.id).await
+            {
+                app_ctx
+                    .interaction
+                    .create_response(
+                        ctx.http(),
+                        serenity::CreateInteractionResponse::Modal(
+                            note_buttons::create_end_note_modal(session.id),
+                        ),
+                    )
+                    .await?;
+                return Ok(());
+            }
+
             let duration = current_datetime.signed_duration_since(start_record.timestamp);
             let duration_str =
-                crate::utils::time::format_duration_minutes(duration.num_minutes() as i32);
-
-            let embed = create_success_embed(
-                "勤務終了",
-                &format!(
-                    "勤務を終了しました\n終了時刻: {}\n勤務時間: {}",
-                    crate::utils::time::format_time_jst(current_datetime),
-                    duration_str
-                ),
+                format_duration(duration.num_minutes() as i32, lang.duration_format());
+
+            let mut description = t!(
+                lang,
+                "end.done",
+                time = prefs.clock.time(current_datetime),
+                duration = duration_str
             );
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+            if let Some(note) = &note {
+                match attach_note(pool, end_record.id, note).await {
+                    Ok(true) => {
+                        description.push('\n');
+                        description.push_str(&t!(lang, "end.note", note = note));
+                    }
+                    Ok(false) => tracing::warn!("No session found to attach the note to"),
+                    Err(e) => tracing::error!("Failed to set session note: {}", e),
+                }
+            }
+            if let Some(warning) =
+                hour_caps::check_after_clock_out(ctx.http(), pool, ctx.guild_id(), &user).await
+            {
+                description.push_str(&format!("\n\n{}", warning));
+            }
+
+            let embed = create_success_embed(&t!(lang, "end.title"), &description);
+            ctx.send(
+                poise::CreateReply::default()
+                    .embed(embed)
+                    .ephemeral(prefs.ephemeral),
+            )
+            .await?;
+        }
+        Err(e) if is_database_unavailable(&e) => {
+            return queue_punch(ctx, &prefs, RecordType::End, current_datetime, note).await;
+        }
+        Err(e) => {
+            return AppError::internal(t!(lang, "error.record_create"), e)
+                .reply(ctx)
+                .await;
         }
+    }
+
+    Ok(())
+}
+
+/// 打刻の時刻。`time` を指定した場合は利用者のタイムゾーンでの今日のその時刻（不正な形式ならエラーを返信して `None`）
+async fn punch_time(
+    ctx: Context<'_>,
+    prefs: &Preferences,
+    time: Option<&str>,
+) -> Result<Option<DateTime<Utc>>, Error> {
+    let now = get_current_datetime_jst().to_utc();
+    let Some(time) = time else {
+        return Ok(Some(now));
+    };
+    match validate_time_format(time) {
+        Ok(time) => Ok(Some(prefs.clock.today_at(time, now))),
         Err(e) => {
-            let embed =
-                create_error_embed("エラー", &format!("勤務記録の作成に失敗しました: {}", e));
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+            let embed = create_error_embed(&t!(prefs.lang, "error.input_title"), &e.to_string());
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            Ok(None)
         }
     }
+}
+
+/// 指定された時刻の記録が今日の記録と矛盾しないか検証する（不正ならエラーを返信して `false`）
+async fn validate_specified_time(
+    ctx: Context<'_>,
+    records: &[AttendanceRecord],
+    record_type: RecordType,
+    timestamp: DateTime<Utc>,
+    lang: Lang,
+) -> Result<bool, Error> {
+    let date = get_date_from_utc_timestamp(timestamp);
+    let policy = edit_policy::for_command(ctx).await;
+    let Err(e) =
+        RecordValidator::validate_new_record(records, record_type, timestamp, date, None, &policy)
+    else {
+        return Ok(true);
+    };
+
+    metrics::record_validation_rejection(&ctx.data().pool, ctx.guild_id(), &e).await;
+    let embed = create_error_embed(&t!(lang, "error.input_title"), &e.to_string());
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+    Ok(false)
+}
+
+/// DBに書き込めなかった打刻を保留にし、復旧後に記録することを伝える
+async fn queue_punch(
+    ctx: Context<'_>,
+    prefs: &Preferences,
+    record_type: RecordType,
+    timestamp: chrono::DateTime<chrono::Utc>,
+    note: Option<String>,
+) -> Result<(), Error> {
+    let punch = PendingPunch {
+        discord_id: ctx.author().id.to_string(),
+        username: ctx.author().name.clone(),
+        record_type,
+        timestamp,
+        guild_id: ctx.guild_id().map(|guild_id| guild_id.to_string()),
+        note,
+    };
+    let lang = prefs.lang;
+    let embed = match ctx.data().punch_journal.append(&punch) {
+        Ok(()) => {
+            tracing::warn!(
+                "Queued {} punch for {} while the database is unavailable",
+                punch.record_type,
+                punch.discord_id
+            );
+            let time = prefs.clock.time(timestamp);
+            create_info_embed(
+                &t!(lang, "punch.queued_title"),
+                &if record_type == RecordType::Start {
+                    t!(lang, "punch.queued_start", time = time)
+                } else {
+                    t!(lang, "punch.queued_end", time = time)
+                },
+            )
+        }
+        Err(e) => {
+            return AppError::internal(t!(lang, "punch.queue_failed"), e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(embed)
+            .ephemeral(prefs.ephemeral),
+    )
+    .await?;
 
     Ok(())
 }
+
+/// 記録がペアになっているセッションにメモを付ける（セッションが無い場合は false）
+async fn attach_note(
+    pool: &crate::database::DbPool,
+    record_id: i64,
+    note: &str,
+) -> anyhow::Result<bool> {
+    match queries::get_work_session_for_record(pool, record_id).await? {
+        Some(session) => queries::set_session_note(pool, session.id, Some(note)).await,
+        None => Ok(false),
+    }
+}
+
+/// ギルドで `/admin end_note` が有効か（DMでは常に無効）
+async fn end_note_prompt_enabled(
+    pool: &crate::database::DbPool,
+    guild_id: Option<serenity::GuildId>,
+) -> bool {
+    let Some(guild_id) = guild_id else {
+        return false;
+    };
+    match queries::get_guild_settings(pool, &guild_id.to_string()).await {
+        Ok(settings) => settings.is_some_and(|settings| settings.end_note_prompt_enabled),
+        Err(e) => {
+            tracing::warn!("Failed to load guild settings: {}", e);
+            false
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::bot::checks::is_admin;
+use crate::bot::{AppError, Context, Error};
+use crate::database::queries;
+use crate::utils::format::{create_error_embed, create_info_embed, create_success_embed};
+use crate::utils::time::{get_current_date_jst, get_month_range};
+use crate::utils::validation::validate_month_format;
+
+/// 勤務記録を締めます（管理者のみ）
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("close_month"),
+    subcommand_required,
+    check = "is_admin"
+)]
+pub async fn close(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// 月を締め、その月の記録の追加・修正・削除を禁止します
+#[poise::command(slash_command, guild_only, rename = "month", check = "is_admin")]
+pub async fn close_month(
+    ctx: Context<'_>,
+    #[description = "締める月（YYYY-MM）"] month: String,
+) -> Result<(), Error> {
+    let month_start = match validate_month_format(&month) {
+        Ok(month_start) => month_start,
+        Err(e) => {
+            let embed = create_error_embed("入力エラー", &e.to_string());
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+    };
+    let month_label = month_start.format("%Y年%m月").to_string();
+
+    // 勤務中の記録が締めた月に入らないよう、終わった月のみ締められる
+    if month_start >= get_month_range(get_current_date_jst()).0 {
+        let embed = create_error_embed(
+            "締められません",
+            &format!("{}はまだ終わっていないため締められません", month_label),
+        );
+        ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+
+    let pool = &ctx.data().pool;
+    let guild_id = ctx.guild_id().map(|id| id.to_string()).unwrap_or_default();
+    let already_closed = match queries::is_month_closed(pool, &guild_id, month_start).await {
+        Ok(closed) => closed,
+        Err(e) => {
+            return AppError::internal("締め状況の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    let closed_by = ctx.author().id.to_string();
+    let embed = match queries::close_month(pool, &guild_id, month_start, &closed_by).await {
+        Ok(_) if already_closed => {
+            create_info_embed("月次締め", &format!("{}は既に締め済みです", month_label))
+        }
+        Ok(locked) => create_success_embed(
+            "月次締め",
+            &format!(
+                "{}を締めました（{}件の記録をロック）\n以降、このサーバーのこの月の記録は追加・修正・削除できません",
+                month_label, locked
+            ),
+        ),
+        Err(e) => {
+            return AppError::internal("月次締めに失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+// `/dashboard` posts a message showing who in the guild is clocked in and today's totals,
+// which `tasks/dashboard.rs` keeps editing in place. A guild has one dashboard: creating a new
+// one deletes the previous message, and a deleted message stops the updates.
+
+use crate::bot::checks::is_admin;
+use crate::bot::tasks::dashboard::{REFRESH_INTERVAL, build_embed, dashboard_message};
+use crate::bot::{AppError, Context, Error};
+use crate::database::queries;
+use crate::utils::format::{create_info_embed, create_success_embed};
+use poise::serenity_prelude as serenity;
+
+/// 自動更新される勤務状況のダッシュボードを管理します（管理者のみ）
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("create", "remove"),
+    subcommand_required,
+    check = "is_admin"
+)]
+pub async fn dashboard(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// このチャンネルに勤務状況のダッシュボードを投稿し自動更新します
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn create(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let guild_id = guild_id.to_string();
+    let pool = &ctx.data().pool;
+
+    let embed = match build_embed(pool, &guild_id).await {
+        Ok(embed) => embed,
+        Err(e) => {
+            return AppError::internal("勤務記録の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    let message = match ctx
+        .channel_id()
+        .send_message(ctx.http(), serenity::CreateMessage::new().embed(embed))
+        .await
+    {
+        Ok(message) => message,
+        Err(e) => {
+            return AppError::internal(
+                "ダッシュボードを投稿できませんでした（このチャンネルへの送信権限を確認してください）",
+                e,
+            )
+            .reply(ctx)
+            .await;
+        }
+    };
+
+    // 以前のダッシュボードは更新されなくなるため削除する
+    if let Ok(Some(previous)) = queries::get_guild_settings(pool, &guild_id).await
+        && let Some((channel_id, message_id)) = dashboard_message(&previous)
+        && let Err(e) = channel_id.delete_message(ctx.http(), message_id).await
+    {
+        tracing::debug!("Failed to delete previous dashboard: {}", e);
+    }
+
+    let channel_id = message.channel_id.to_string();
+    let message_id = message.id.to_string();
+    if let Err(e) =
+        queries::set_dashboard_message(pool, &guild_id, Some((&channel_id, &message_id))).await
+    {
+        return AppError::internal("設定の保存に失敗しました", e)
+            .reply(ctx)
+            .await;
+    }
+
+    let embed = create_success_embed(
+        "ダッシュボードを作成しました",
+        &format!(
+            "{}分ごとに最新の勤務状況へ更新されます。停止するには `/dashboard remove` を実行するか、メッセージを削除してください",
+            REFRESH_INTERVAL.as_secs() / 60
+        ),
+    );
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// ダッシュボードの自動更新を停止し、メッセージを削除します
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn remove(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let guild_id = guild_id.to_string();
+    let pool = &ctx.data().pool;
+
+    let current = match queries::get_guild_settings(pool, &guild_id).await {
+        Ok(settings) => settings.as_ref().and_then(dashboard_message),
+        Err(e) => {
+            return AppError::internal("設定の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    let Some((channel_id, message_id)) = current else {
+        let embed = create_info_embed("ダッシュボード", "ダッシュボードは作成されていません");
+        ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+            .await?;
+        return Ok(());
+    };
+
+    if let Err(e) = queries::set_dashboard_message(pool, &guild_id, None).await {
+        return AppError::internal("設定の保存に失敗しました", e)
+            .reply(ctx)
+            .await;
+    }
+    // 既に削除されている場合もあるため、削除の失敗は無視する
+    if let Err(e) = channel_id.delete_message(ctx.http(), message_id).await {
+        tracing::debug!("Failed to delete dashboard: {}", e);
+    }
+
+    let embed = create_success_embed("ダッシュボードを削除しました", "自動更新を停止しました");
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use crate::bot::{AppError, Context, Error, hour_caps};
+use crate::database::queries;
+use crate::utils::export::{
+    ExportTemplate, ExportVerification, PersonalDataExport, format_personal_data_json,
+    format_sessions_csv, sign_export, verify_export,
+};
+use crate::utils::format::{create_error_embed, create_success_embed};
+use crate::utils::time::{get_current_date_jst, get_month_range};
+use crate::utils::validation::validate_month_format;
+use poise::serenity_prelude as serenity;
+
+/// 勤務記録をエクスポートします
+#[poise::command(slash_command, subcommands("csv", "json"))]
+pub async fn export(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// 月ごとの勤務セッションを署名付きCSVでエクスポートします
+#[poise::command(slash_command)]
+pub async fn csv(
+    ctx: Context<'_>,
+    #[description = "対象月 (YYYY-MM、未指定で今月)"] month: Option<String>,
+    #[description = "列の構成（未指定で標準）"] template: Option<ExportTemplate>,
+) -> Result<(), Error> {
+    let user_id = ctx.author().id.to_string();
+    let username = ctx.author().name.clone();
+    let pool = &ctx.data().pool;
+
+    let target_date = match month.as_deref().map(validate_month_format) {
+        Some(Ok(date)) => date,
+        Some(Err(e)) => {
+            let embed = create_error_embed("入力エラー", &e.to_string());
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+        None => get_current_date_jst(),
+    };
+    let (start_of_month, end_of_month) = get_month_range(target_date);
+
+    // Create or get user
+    let user = match queries::create_or_get_user(pool, &user_id, ctx.guild_id(), &username).await {
+        Ok(user) => user,
+        Err(e) => {
+            return AppError::internal("ユーザー情報の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    match queries::get_work_sessions_by_date_range(pool, user.id, start_of_month, end_of_month)
+        .await
+    {
+        Ok(sessions) => {
+            // 月間上限が設定されていれば超過分の列を追加する
+            let monthly_cap = hour_caps::get_cap(pool, ctx.guild_id(), user.id)
+                .await
+                .map(|cap| cap.monthly_minutes);
+            let signed_csv = sign_export(
+                &format_sessions_csv(&sessions, template.unwrap_or_default(), monthly_cap),
+                ctx.data().config.export_signing_key.as_bytes(),
+            );
+            let filename = format!("kintai_{}_{}.csv", username, start_of_month.format("%Y-%m"));
+
+            let embed = create_success_embed(
+                "エクスポート完了",
+                &format!(
+                    "{}の勤務記録をエクスポートしました（{}件）\nファイルには改ざん検知用の署名が含まれています。`/verify-export` で検証できます。",
+                    start_of_month.format("%Y年%m月"),
+                    sessions.len()
+                ),
+            );
+            ctx.send(
+                poise::CreateReply::default()
+                    .embed(embed)
+                    .attachment(serenity::CreateAttachment::bytes(
+                        signed_csv.into_bytes(),
+                        filename,
+                    ))
+                    .ephemeral(true),
+            )
+            .await?;
+        }
+        Err(e) => {
+            return AppError::internal("勤務記録の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    }
+
+    Ok(())
+}
+
+/// 自分の勤怠データをすべてJSONでエクスポートします（バックアップ用）
+#[poise::command(slash_command)]
+pub async fn json(ctx: Context<'_>) -> Result<(), Error> {
+    let user_id = ctx.author().id.to_string();
+    let username = ctx.author().name.clone();
+    let pool = &ctx.data().pool;
+
+    let result = async {
+        let user = queries::create_or_get_user(pool, &user_id, ctx.guild_id(), &username).await?;
+        let records = queries::get_all_attendance_records(pool, user.id).await?;
+        let sessions = queries::get_all_work_sessions(pool, user.id).await?;
+        let adjustments = queries::get_all_time_adjustments(pool, user.id).await?;
+        let json = format_personal_data_json(&PersonalDataExport {
+            exported_at: chrono::Utc::now(),
+            user: &user,
+            attendance_records: &records,
+            work_sessions: &sessions,
+            time_adjustments: &adjustments,
+        })?;
+        Ok::<_, anyhow::Error>((json, records.len(), sessions.len()))
+    }
+    .await;
+
+    match result {
+        Ok((json, record_count, session_count)) => {
+            let embed = create_success_embed(
+                "エクスポート完了",
+                &format!(
+                    "すべての勤怠データをエクスポートしました（打刻 {}件、勤務セッション {}件）",
+                    record_count, session_count
+                ),
+            );
+            ctx.send(
+                poise::CreateReply::default()
+                    .embed(embed)
+                    .attachment(serenity::CreateAttachment::bytes(
+                        json.into_bytes(),
+                        format!("kintai_{}_data.json", username),
+                    ))
+                    .ephemeral(true),
+            )
+            .await?;
+        }
+        Err(e) => {
+            AppError::internal("勤怠データのエクスポートに失敗しました", e)
+                .reply(ctx)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// エクスポートファイルが生成後に改変されていないか検証します
+#[poise::command(slash_command, rename = "verify-export")]
+pub async fn verify_export_file(
+    ctx: Context<'_>,
+    #[description = "検証するエクスポートファイル"] file: serenity::Attachment,
+) -> Result<(), Error> {
+    let content = match file.download().await {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Err(e) => {
+            return AppError::internal("ファイルのダウンロードに失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    let embed = match verify_export(&content, ctx.data().config.export_signing_key.as_bytes()) {
+        ExportVerification::Valid => create_success_embed(
+            "✅ 検証成功",
+            &format!("`{}` は生成後に改変されていません", file.filename),
+        ),
+        ExportVerification::Invalid => create_error_embed(
+            "❌ 検証失敗",
+            &format!(
+                "`{}` の署名が一致しません。生成後に改変された可能性があります",
+                file.filename
+            ),
+        ),
+        ExportVerification::Unsigned => create_error_embed(
+            "❌ 署名なし",
+            &format!("`{}` に署名が含まれていません", file.filename),
+        ),
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
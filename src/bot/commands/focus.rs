@@ -0,0 +1,80 @@
+// `/focus` starts a timed focus block (a pomodoro) inside the user's open work session. The
+// block is stored in `focus_sessions`; `tasks/focus_timer.rs` ends it at `ends_at` and pings the
+// user in the channel where it was started, and the "中断" button on the reply ends it early.
+// Finished blocks are summarized in the daily, weekly and monthly reports.
+
+use crate::bot::interactions::focus_buttons::stop_button;
+use crate::bot::{AppError, Context, Error};
+use crate::database::queries;
+use crate::utils::format::create_success_embed;
+use crate::utils::time::format_time_jst;
+use chrono::{Duration, Utc};
+
+/// 集中時間の既定の長さ（分）
+const DEFAULT_FOCUS_MINUTES: u32 = 25;
+
+/// 勤務中に集中時間（ポモドーロ）を始め、終わったらお知らせします
+#[poise::command(slash_command)]
+pub async fn focus(
+    ctx: Context<'_>,
+    #[description = "集中する分数（既定 25分）"]
+    #[min = 1]
+    #[max = 180]
+    minutes: Option<u32>,
+) -> Result<(), Error> {
+    let minutes = minutes.unwrap_or(DEFAULT_FOCUS_MINUTES);
+    let pool = &ctx.data().pool;
+    let user = queries::create_or_get_user(
+        pool,
+        &ctx.author().id.to_string(),
+        ctx.guild_id(),
+        &ctx.author().name,
+    )
+    .await
+    .map_err(|e| AppError::internal("ユーザー情報の取得に失敗しました", e))?;
+
+    if queries::get_active_work_session(pool, user.id)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::user(
+            "勤務中ではありません。`/start` で勤務を開始してから集中モードを始めてください",
+        ));
+    }
+    if let Some(running) = queries::get_running_focus_session(pool, user.id).await? {
+        return Err(AppError::user(format!(
+            "集中モード中です（{} まで）。先に「中断」で終了してください",
+            format_time_jst(running.ends_at)
+        )));
+    }
+
+    let started_at = Utc::now();
+    let ends_at = started_at + Duration::minutes(minutes as i64);
+    let focus = queries::create_focus_session(
+        pool,
+        user.id,
+        &ctx.channel_id().to_string(),
+        started_at,
+        ends_at,
+    )
+    .await
+    .map_err(|e| AppError::internal("集中モードの開始に失敗しました", e))?;
+
+    let embed = create_success_embed(
+        "🍅 集中モード",
+        &format!(
+            "{}分の集中を始めました（{} まで）。終わったらこのチャンネルでお知らせします",
+            minutes,
+            format_time_jst(ends_at)
+        ),
+    );
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(embed)
+            .components(stop_button(&ctx.author().id.to_string(), focus.id))
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
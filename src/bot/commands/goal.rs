@@ -0,0 +1,83 @@
+use crate::bot::{AppError, Context, Error};
+use crate::database::{DbPool, queries};
+use crate::utils::format::{create_success_embed, format_goal_progress};
+use crate::utils::i18n::Lang;
+use crate::utils::time::{format_duration_minutes, get_week_start};
+use chrono::NaiveDate;
+
+/// 勤務時間の目標を設定します
+#[poise::command(slash_command, subcommands("set"), subcommand_required)]
+pub async fn goal(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// 週の目標勤務時間を設定します（`/status` と `/weekly` に進捗を表示）
+#[poise::command(slash_command)]
+pub async fn set(
+    ctx: Context<'_>,
+    #[description = "週の目標時間（未指定で解除）"]
+    #[min = 1]
+    #[max = 168]
+    weekly: Option<u32>,
+) -> Result<(), Error> {
+    let pool = &ctx.data().pool;
+    let goal_minutes = weekly.map(|hours| hours as i32 * 60);
+    let result = match queries::create_or_get_user(
+        pool,
+        &ctx.author().id.to_string(),
+        ctx.guild_id(),
+        &ctx.author().name,
+    )
+    .await
+    {
+        Ok(user) => queries::set_weekly_goal(pool, user.id, goal_minutes).await,
+        Err(e) => Err(e),
+    };
+
+    let embed = match (result, goal_minutes) {
+        (Ok(()), Some(minutes)) => create_success_embed(
+            "週の目標",
+            &format!(
+                "週の目標を **{}** に設定しました\n`/status` と `/weekly` に今週の進捗を表示します",
+                format_duration_minutes(minutes)
+            ),
+        ),
+        (Ok(()), None) => create_success_embed("週の目標", "週の目標を解除しました"),
+        (Err(e), _) => {
+            return AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 週の目標が設定されていれば、今週（月曜〜`today`）の進捗を返す
+pub async fn weekly_progress(
+    pool: &DbPool,
+    user_id: i64,
+    today: NaiveDate,
+    lang: Lang,
+) -> Option<String> {
+    let result = async {
+        let Some(goal_minutes) = queries::get_weekly_goal(pool, user_id).await? else {
+            return Ok(None);
+        };
+        let sessions =
+            queries::get_work_sessions_by_date_range(pool, user_id, get_week_start(today), today)
+                .await?;
+        let worked_minutes: i32 = sessions.iter().filter_map(|s| s.total_minutes).sum();
+        anyhow::Ok(Some(format_goal_progress(
+            worked_minutes,
+            goal_minutes,
+            lang,
+        )))
+    };
+    result.await.unwrap_or_else(|e| {
+        tracing::warn!("Failed to get weekly goal progress for {}: {}", user_id, e);
+        None
+    })
+}
@@ -0,0 +1,85 @@
+// `/kintai` groups the bot's commands under one parent so they do not collide with
+// other bots' global commands. The flat commands stay registered as aliases during the
+// transition; each subcommand here reuses the same implementation.
+
+use crate::bot::checks::is_admin;
+use crate::bot::commands::{
+    admin, attendance, export, focus, goal, language, project, reports, rounding, schedule,
+    settings, shift, shortcut, status,
+};
+use crate::bot::{Context, Error};
+
+/// 勤怠管理コマンド
+#[poise::command(
+    slash_command,
+    subcommands(
+        "attendance::start",
+        "attendance::end",
+        "attendance::punch",
+        "status::status",
+        "focus::focus",
+        "report",
+        "export::export",
+        "shortcut::shortcut",
+        "project::project",
+        "goal::goal",
+        "rounding::rounding",
+        "schedule::schedule",
+        "shift::shift",
+        "language::language",
+        "settings::settings",
+        "config"
+    ),
+    subcommand_required
+)]
+pub async fn kintai(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// 勤務レポートを表示します
+#[poise::command(
+    slash_command,
+    subcommands(
+        "reports::daily",
+        "reports::weekly",
+        "reports::monthly",
+        "reports::leaderboard",
+        "reports::forecast",
+        "reports::calendar",
+        "reports::stats"
+    ),
+    subcommand_required
+)]
+pub async fn report(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// ギルドの設定を変更します（管理者のみ）
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands(
+        "admin::digest",
+        "admin::quality_digest",
+        "admin::approvals",
+        "admin::webhook",
+        "admin::event_log",
+        "admin::working_role",
+        "admin::long_session",
+        "admin::timesheet",
+        "admin::end_note",
+        "admin::resume_window",
+        "admin::retention",
+        "admin::edit_policy",
+        "admin::language",
+        "admin::rate",
+        "admin::overtime",
+        "admin::break_rule",
+        "admin::hour_cap"
+    ),
+    subcommand_required,
+    check = "is_admin"
+)]
+pub async fn config(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
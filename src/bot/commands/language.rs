@@ -0,0 +1,74 @@
+use crate::bot::{AppError, Context, Error, preferences};
+use crate::database::queries;
+use crate::t;
+use crate::utils::format::create_success_embed;
+use crate::utils::i18n::Lang;
+
+/// 表示言語の選択肢
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum LanguageChoice {
+    #[name = "日本語"]
+    Japanese,
+    #[name = "English"]
+    English,
+    #[name = "自動（Discordの言語設定に従う）"]
+    Auto,
+}
+
+impl LanguageChoice {
+    /// 保存する言語（自動なら `None`）
+    pub fn lang(self) -> Option<Lang> {
+        match self {
+            LanguageChoice::Japanese => Some(Lang::Ja),
+            LanguageChoice::English => Some(Lang::En),
+            LanguageChoice::Auto => None,
+        }
+    }
+}
+
+/// ボットの表示言語を設定します（自動ならDiscordの言語設定に従う）
+#[poise::command(slash_command)]
+pub async fn language(
+    ctx: Context<'_>,
+    #[description = "表示言語"] language: LanguageChoice,
+) -> Result<(), Error> {
+    let pool = &ctx.data().pool;
+    let result = match queries::create_or_get_user(
+        pool,
+        &ctx.author().id.to_string(),
+        ctx.guild_id(),
+        &ctx.author().name,
+    )
+    .await
+    {
+        Ok(user) => {
+            queries::set_user_language(pool, user.id, language.lang().map(Lang::code)).await
+        }
+        Err(e) => Err(e),
+    };
+
+    // 新しい設定の言語で返す
+    let lang = preferences::load(ctx).await.lang;
+    let embed = match result {
+        Ok(()) => create_success_embed(
+            &t!(lang, "language.title"),
+            &match language.lang() {
+                Some(_) => t!(
+                    lang,
+                    "language.set",
+                    language = poise::ChoiceParameter::name(&language)
+                ),
+                None => t!(lang, "language.cleared"),
+            },
+        ),
+        Err(e) => {
+            return AppError::internal(t!(lang, "error.settings_save"), e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
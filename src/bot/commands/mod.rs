@@ -1,3 +1,22 @@
+pub mod admin;
 pub mod attendance;
+pub mod close;
+pub mod dashboard;
+pub mod export;
+pub mod focus;
+pub mod goal;
+pub mod kintai;
+pub mod language;
+pub mod project;
+pub mod registration;
 pub mod reports;
+pub mod resume;
+pub mod rounding;
+pub mod schedule;
+pub mod settings;
+pub mod shift;
+pub mod shortcut;
+pub mod standup;
 pub mod status;
+pub mod team;
+pub mod undo;
@@ -0,0 +1,84 @@
+use crate::bot::commands::attendance::{autocomplete_project, resolve_project};
+use crate::bot::{AppError, Context, Error};
+use crate::database::queries;
+use crate::utils::format::{create_error_embed, create_success_embed};
+
+/// プロジェクトの設定を変更します
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("set_default"),
+    subcommand_required
+)]
+pub async fn project(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// `/start` でプロジェクトを省略したときに使うプロジェクトを設定します
+#[poise::command(slash_command, guild_only, rename = "default")]
+pub async fn set_default(
+    ctx: Context<'_>,
+    #[description = "既定のプロジェクト（未指定で解除、未登録の名前は新規作成）"]
+    #[autocomplete = "autocomplete_project"]
+    name: Option<String>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let project = match name {
+        Some(name) => match resolve_project(ctx, &name).await {
+            Ok(project) => Some(project),
+            Err(e) => {
+                e.log();
+                let embed = create_error_embed("プロジェクトを指定できません", &e.to_string());
+                ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                    .await?;
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+
+    let pool = &ctx.data().pool;
+    let result = match queries::create_or_get_user(
+        pool,
+        &ctx.author().id.to_string(),
+        ctx.guild_id(),
+        &ctx.author().name,
+    )
+    .await
+    {
+        Ok(user) => {
+            queries::set_default_project(
+                pool,
+                user.id,
+                &guild_id.to_string(),
+                project.as_ref().map(|(id, _)| *id),
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    let embed = match (result, &project) {
+        (Ok(()), Some((_, name))) => create_success_embed(
+            "既定のプロジェクト",
+            &format!(
+                "`/start` でプロジェクトを省略すると **{}** で記録します\n別のプロジェクトで働くときは `/start project:<名前>` で指定してください（既定は変わりません）",
+                name
+            ),
+        ),
+        (Ok(()), None) => {
+            create_success_embed("既定のプロジェクト", "既定のプロジェクトを解除しました")
+        }
+        (Err(e), _) => {
+            return AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+// Owner-only maintenance for Discord's application command registrations. Commands that
+// were renamed or removed in code, or registered per guild during development, keep
+// showing up for users until they are deleted explicitly.
+
+use crate::bot::{AppError, Context, Data, Error};
+use crate::utils::format::{create_info_embed, create_success_embed};
+use poise::serenity_prelude as serenity;
+use std::collections::BTreeSet;
+
+/// 登録済みのスラッシュコマンドとコードの差分
+struct RegistrationDiff {
+    /// コードにあるが未登録のコマンド
+    missing: Vec<String>,
+    /// コードに無いグローバルコマンド
+    stale_global: Vec<serenity::Command>,
+    /// サーバー単位で登録されたコマンド（このBotはグローバルにのみ登録する）
+    guild: Vec<serenity::Command>,
+}
+
+/// スラッシュコマンドの登録を管理します（Botオーナーのみ）
+#[poise::command(
+    slash_command,
+    owners_only,
+    default_member_permissions = "ADMINISTRATOR",
+    subcommands("registration_diff", "registration_cleanup"),
+    subcommand_required
+)]
+pub async fn registration(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// 登録済みのコマンドとコードのコマンド一覧の差分を表示します
+#[poise::command(slash_command, owners_only, rename = "diff")]
+pub async fn registration_diff(ctx: Context<'_>) -> Result<(), Error> {
+    let diff = match fetch_diff(ctx).await {
+        Ok(diff) => diff,
+        Err(e) => {
+            return AppError::internal("登録済みコマンドの取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    let list = |names: Vec<&str>| {
+        if names.is_empty() {
+            "なし".to_string()
+        } else {
+            names
+                .iter()
+                .map(|name| format!("`/{}`", name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    };
+    let description = format!(
+        "**未登録**: {}\n**不要（グローバル）**: {}\n**不要（このサーバー）**: {}{}",
+        list(diff.missing.iter().map(String::as_str).collect()),
+        list(diff.stale_global.iter().map(|c| c.name.as_str()).collect()),
+        list(diff.guild.iter().map(|c| c.name.as_str()).collect()),
+        if diff.missing.is_empty() && diff.stale_global.is_empty() && diff.guild.is_empty() {
+            ""
+        } else {
+            "\n\n`/registration cleanup` で不要なコマンドを削除し、未登録のコマンドを登録できます"
+        }
+    );
+    let embed = create_info_embed("🧹 コマンド登録の差分", &description);
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 不要なコマンドを削除し、コードのコマンド一覧を登録し直します
+#[poise::command(slash_command, owners_only, rename = "cleanup")]
+pub async fn registration_cleanup(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let result = async {
+        let diff = fetch_diff(ctx).await?;
+        let http = ctx.http();
+
+        for command in &diff.stale_global {
+            serenity::Command::delete_global_command(http, command.id).await?;
+        }
+        if let Some(guild_id) = ctx.guild_id() {
+            for command in &diff.guild {
+                guild_id.delete_command(http, command.id).await?;
+            }
+        }
+        if !diff.missing.is_empty() {
+            register_commands(http, &ctx.framework().options().commands).await?;
+        }
+        Ok::<_, Error>(diff)
+    }
+    .await;
+
+    let embed = match result {
+        Ok(diff) => create_success_embed(
+            "コマンド登録を整理しました",
+            &format!(
+                "削除（グローバル）: {}件\n削除（このサーバー）: {}件\n登録: {}件\n\nクライアントに反映されるまで時間がかかる場合があります",
+                diff.stale_global.len(),
+                diff.guild.len(),
+                diff.missing.len()
+            ),
+        ),
+        Err(e) => {
+            return AppError::internal("コマンド登録の整理に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+async fn fetch_diff(ctx: Context<'_>) -> Result<RegistrationDiff, Error> {
+    let expected: BTreeSet<String> = ctx
+        .framework()
+        .options()
+        .commands
+        .iter()
+        .filter(|command| command.create_as_slash_command().is_some())
+        .map(|command| command.name.clone())
+        .collect();
+
+    let global = serenity::Command::get_global_commands(ctx.http()).await?;
+    let guild = match ctx.guild_id() {
+        Some(guild_id) => guild_id.get_commands(ctx.http()).await?,
+        None => Vec::new(),
+    };
+
+    let registered: BTreeSet<&str> = global.iter().map(|c| c.name.as_str()).collect();
+    let missing = expected
+        .iter()
+        .filter(|name| !registered.contains(name.as_str()))
+        .cloned()
+        .collect();
+    let stale_global = global
+        .into_iter()
+        .filter(|command| !expected.contains(&command.name))
+        .collect();
+
+    Ok(RegistrationDiff {
+        missing,
+        stale_global,
+        guild,
+    })
+}
+
+/// コマンドをグローバルに登録する。`guild_only` でないコマンドはBotとのDMでも使えるようにする
+pub async fn register_commands(
+    http: impl AsRef<serenity::Http>,
+    commands: &[poise::Command<Data, Error>],
+) -> Result<(), serenity::Error> {
+    let builders = commands
+        .iter()
+        .filter_map(|command| {
+            let contexts = if command.guild_only {
+                vec![serenity::InteractionContext::Guild]
+            } else {
+                vec![
+                    serenity::InteractionContext::Guild,
+                    serenity::InteractionContext::BotDm,
+                ]
+            };
+            command
+                .create_as_slash_command()
+                .map(|builder| builder.contexts(contexts))
+        })
+        .collect();
+    serenity::Command::set_global_commands(http, builders).await?;
+    Ok(())
+}
@@ -1,46 +1,298 @@
-use crate::bot::{Context, Error};
+use crate::bot::commands::goal::weekly_progress;
+use crate::bot::commands::team::ReportPeriod;
+use crate::bot::{AppError, Context, Error, hour_caps, preferences};
+use crate::database::models::{GuildSettings, WorkSession};
 use crate::database::queries;
-use crate::utils::format::{create_error_embed, create_report_embed};
-use crate::utils::time::get_current_date_jst;
-use chrono::{Datelike, Days};
+use crate::t;
+use crate::utils::breaks::{BreakDeduction, BreakPolicy, apply_break_deductions};
+use crate::utils::chart::render_daily_hours_chart;
+use crate::utils::format::{
+    create_error_embed, create_info_embed, create_report_embed, format_break_deductions,
+    format_calendar, format_cap_warning, format_focus_summary, format_forecast, format_hour_cap,
+    format_late_starts, format_leaderboard, format_overtime_split, format_pay_estimate,
+    format_project_breakdown, format_shift_report, format_time_adjustments,
+    format_work_pattern_stats, format_working_members,
+};
+use crate::utils::overtime::{OvertimeThresholds, split_overtime};
+use crate::utils::schedule::find_late_starts;
+use crate::utils::stats::{
+    CapLevel, MonthForecast, PayEstimate, WorkPatternStats, merge_close_sessions,
+    standard_month_minutes,
+};
+use crate::utils::time::{
+    TimeDisplay, combine_date_time_jst, get_current_date_jst, get_month_range, get_week_start,
+};
+use crate::utils::validation::{validate_month_format, validate_time_format};
+use chrono::Datelike;
+use poise::serenity_prelude as serenity;
+
+/// `merge_gap` が指定されていれば短い間隔のセッションを表示用にまとめる
+fn merge_for_display(sessions: Vec<WorkSession>, merge_gap: Option<u32>) -> Vec<WorkSession> {
+    match merge_gap {
+        Some(gap) => merge_close_sessions(&sessions, gap as i64),
+        None => sessions,
+    }
+}
+
+/// ギルドの設定（DMや未設定、取得に失敗した場合は `None`）
+async fn guild_settings(ctx: Context<'_>) -> Option<GuildSettings> {
+    let guild_id = ctx.guild_id()?;
+    match queries::get_guild_settings(&ctx.data().pool, &guild_id.to_string()).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::warn!("Failed to get guild settings for {}: {:?}", guild_id, e);
+            None
+        }
+    }
+}
+
+/// ギルドの所定労働時間（DMや未設定の場合は法定労働時間）
+async fn overtime_thresholds(ctx: Context<'_>) -> OvertimeThresholds {
+    guild_settings(ctx)
+        .await
+        .as_ref()
+        .map(OvertimeThresholds::from)
+        .unwrap_or_default()
+}
+
+/// 記録された休憩を差し引き、ギルドに休憩の自動控除ルールがあれば適用する
+async fn apply_break_rule(
+    ctx: Context<'_>,
+    user_id: i64,
+    start_date: chrono::NaiveDate,
+    end_date: chrono::NaiveDate,
+    sessions: Vec<WorkSession>,
+) -> (Vec<WorkSession>, Vec<BreakDeduction>) {
+    let policy = guild_settings(ctx)
+        .await
+        .as_ref()
+        .and_then(BreakPolicy::from_settings);
+    let records =
+        match queries::get_records_by_date_range(&ctx.data().pool, user_id, start_date, end_date)
+            .await
+        {
+            Ok(records) => records,
+            Err(e) => {
+                tracing::warn!("Failed to get break records for {}: {:?}", user_id, e);
+                Vec::new()
+            }
+        };
+    apply_break_deductions(&sessions, &records, policy)
+}
+
+/// 期間内に勤務時間の調整があれば、一覧と調整後の合計を添える
+async fn with_adjustment_field(
+    embed: serenity::CreateEmbed,
+    ctx: Context<'_>,
+    user_id: i64,
+    start_date: chrono::NaiveDate,
+    end_date: chrono::NaiveDate,
+    sessions: &[WorkSession],
+) -> serenity::CreateEmbed {
+    match queries::get_time_adjustments_by_date_range(
+        &ctx.data().pool,
+        user_id,
+        start_date,
+        end_date,
+    )
+    .await
+    {
+        Ok(adjustments) if !adjustments.is_empty() => {
+            let session_minutes = sessions.iter().filter_map(|s| s.total_minutes).sum();
+            embed.field(
+                "勤務時間の調整",
+                format_time_adjustments(&adjustments, session_minutes),
+                false,
+            )
+        }
+        Ok(_) => embed,
+        Err(e) => {
+            tracing::warn!("Failed to get time adjustments for {}: {:?}", user_id, e);
+            embed
+        }
+    }
+}
+
+/// 開始予定時刻より遅く始めた日があれば ⚠️ を付けて並べる
+async fn with_late_start_field(
+    embed: serenity::CreateEmbed,
+    ctx: Context<'_>,
+    user_id: i64,
+    sessions: &[WorkSession],
+) -> serenity::CreateEmbed {
+    match queries::get_work_schedules(&ctx.data().pool, user_id).await {
+        Ok(schedules) => {
+            let late_starts = find_late_starts(sessions, &schedules);
+            if late_starts.is_empty() {
+                return embed;
+            }
+            embed.field("予定より遅い開始", format_late_starts(&late_starts), false)
+        }
+        Err(e) => {
+            tracing::warn!("Failed to get work schedules for {}: {:?}", user_id, e);
+            embed
+        }
+    }
+}
+
+/// 期間内にシフトがあれば実際の勤務との比較を添える
+async fn with_shift_field(
+    embed: serenity::CreateEmbed,
+    ctx: Context<'_>,
+    user_id: i64,
+    start_date: chrono::NaiveDate,
+    end_date: chrono::NaiveDate,
+    sessions: &[WorkSession],
+    clock: TimeDisplay,
+) -> serenity::CreateEmbed {
+    let from = combine_date_time_jst(start_date, chrono::NaiveTime::MIN);
+    let to = combine_date_time_jst(end_date, chrono::NaiveTime::MIN) + chrono::Duration::days(1);
+    match queries::get_shifts_in_range(&ctx.data().pool, user_id, from, to).await {
+        Ok(shifts) if shifts.is_empty() => embed,
+        Ok(shifts) => embed.field(
+            "シフトとの比較",
+            format_shift_report(&shifts, sessions, chrono::Utc::now(), clock),
+            false,
+        ),
+        Err(e) => {
+            tracing::warn!("Failed to get shifts for {}: {:?}", user_id, e);
+            embed
+        }
+    }
+}
+
+/// 休憩を自動控除した日があれば内訳を添える
+fn with_break_field(
+    embed: serenity::CreateEmbed,
+    deductions: &[BreakDeduction],
+) -> serenity::CreateEmbed {
+    if deductions.is_empty() {
+        return embed;
+    }
+    embed.field("休憩の自動控除", format_break_deductions(deductions), false)
+}
+
+/// 通常・時間外の内訳をレポートに添える
+fn with_overtime_field(
+    embed: serenity::CreateEmbed,
+    sessions: &[WorkSession],
+    thresholds: OvertimeThresholds,
+) -> serenity::CreateEmbed {
+    let split = split_overtime(sessions, thresholds);
+    embed.field("勤務時間の内訳", format_overtime_split(&split), false)
+}
+
+/// プロジェクトが設定されたセッションがあればプロジェクト別の内訳を添える
+async fn with_project_field(
+    embed: serenity::CreateEmbed,
+    ctx: Context<'_>,
+    user_id: i64,
+    start_date: chrono::NaiveDate,
+    end_date: chrono::NaiveDate,
+) -> serenity::CreateEmbed {
+    match queries::get_project_minutes_by_date_range(
+        &ctx.data().pool,
+        user_id,
+        start_date,
+        end_date,
+    )
+    .await
+    {
+        Ok(projects) if projects.iter().any(|(name, _)| name.is_some()) => {
+            embed.field("プロジェクト別", format_project_breakdown(&projects), false)
+        }
+        Ok(_) => embed,
+        Err(e) => {
+            tracing::warn!("Failed to get project breakdown for {}: {:?}", user_id, e);
+            embed
+        }
+    }
+}
+
+/// 集中モードを使った日があれば、集中時間の回数と合計を添える
+async fn with_focus_field(
+    embed: serenity::CreateEmbed,
+    ctx: Context<'_>,
+    user_id: i64,
+    start_date: chrono::NaiveDate,
+    end_date: chrono::NaiveDate,
+) -> serenity::CreateEmbed {
+    let from = combine_date_time_jst(start_date, chrono::NaiveTime::MIN);
+    let to = combine_date_time_jst(end_date, chrono::NaiveTime::MIN) + chrono::Duration::days(1);
+    match queries::get_focus_sessions_in_range(&ctx.data().pool, user_id, from, to).await {
+        Ok(focus_sessions) if focus_sessions.iter().any(|focus| focus.ended_at.is_some()) => {
+            embed.field("集中モード", format_focus_summary(&focus_sessions), false)
+        }
+        Ok(_) => embed,
+        Err(e) => {
+            tracing::warn!("Failed to get focus sessions for {}: {:?}", user_id, e);
+            embed
+        }
+    }
+}
 
 /// 今日の勤務レポートを表示します
 #[poise::command(slash_command)]
-pub async fn daily(ctx: Context<'_>) -> Result<(), Error> {
+pub async fn daily(
+    ctx: Context<'_>,
+    #[description = "この分数未満の間隔のセッションを1行にまとめて表示"]
+    #[min = 1]
+    #[max = 240]
+    merge_gap: Option<u32>,
+
+    #[description = "自分だけに表示する（未指定で /settings の設定に従う）"] private: Option<bool>,
+) -> Result<(), Error> {
     let user_id = ctx.author().id.to_string();
     let username = ctx.author().name.clone();
     let pool = &ctx.data().pool;
 
     // Create or get user
-    let user = match queries::create_or_get_user(pool, &user_id, &username).await {
+    let user = match queries::create_or_get_user(pool, &user_id, ctx.guild_id(), &username).await {
         Ok(user) => user,
         Err(e) => {
-            let embed = create_error_embed(
-                "エラー",
-                &format!("ユーザー情報の取得に失敗しました: {}", e),
-            );
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
-            return Ok(());
+            return AppError::internal("ユーザー情報の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
         }
     };
+    let prefs = preferences::load(ctx).await;
+    let private = private.unwrap_or(prefs.private_reports);
 
     let today = get_current_date_jst();
 
     match queries::get_work_sessions_by_date_range(pool, user.id, today, today).await {
         Ok(sessions) => {
+            let (sessions, deductions) =
+                apply_break_rule(ctx, user.id, today, today, sessions).await;
+            let sessions = merge_for_display(sessions, merge_gap);
             let embed = create_report_embed(
                 &username,
-                "日次レポート",
-                &today.format("%Y年%m月%d日").to_string(),
+                &t!(prefs.lang, "report.daily_title"),
+                &today.format(&t!(prefs.lang, "format.date")).to_string(),
                 &sessions,
+                prefs.lang,
+                prefs.clock,
             );
+            let embed = with_break_field(embed, &deductions);
+            let embed = with_adjustment_field(embed, ctx, user.id, today, today, &sessions).await;
+            let embed = with_late_start_field(embed, ctx, user.id, &sessions).await;
+            let embed =
+                with_shift_field(embed, ctx, user.id, today, today, &sessions, prefs.clock).await;
+            let embed = with_overtime_field(embed, &sessions, overtime_thresholds(ctx).await);
+            let embed = with_project_field(embed, ctx, user.id, today, today).await;
+            let embed = with_focus_field(embed, ctx, user.id, today, today).await;
 
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+            ctx.send(
+                poise::CreateReply::default()
+                    .embed(embed)
+                    .ephemeral(private),
+            )
+            .await?;
         }
         Err(e) => {
-            let embed =
-                create_error_embed("エラー", &format!("勤務記録の取得に失敗しました: {}", e));
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+            return AppError::internal("勤務記録の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
         }
     }
 
@@ -49,46 +301,87 @@ pub async fn daily(ctx: Context<'_>) -> Result<(), Error> {
 
 /// 今週の勤務レポートを表示します
 #[poise::command(slash_command)]
-pub async fn weekly(ctx: Context<'_>) -> Result<(), Error> {
+pub async fn weekly(
+    ctx: Context<'_>,
+    #[description = "この分数未満の間隔のセッションを1行にまとめて表示"]
+    #[min = 1]
+    #[max = 240]
+    merge_gap: Option<u32>,
+
+    #[description = "自分だけに表示する（未指定で /settings の設定に従う）"] private: Option<bool>,
+) -> Result<(), Error> {
     let user_id = ctx.author().id.to_string();
     let username = ctx.author().name.clone();
     let pool = &ctx.data().pool;
 
     // Create or get user
-    let user = match queries::create_or_get_user(pool, &user_id, &username).await {
+    let user = match queries::create_or_get_user(pool, &user_id, ctx.guild_id(), &username).await {
         Ok(user) => user,
         Err(e) => {
-            let embed = create_error_embed(
-                "エラー",
-                &format!("ユーザー情報の取得に失敗しました: {}", e),
-            );
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
-            return Ok(());
+            return AppError::internal("ユーザー情報の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
         }
     };
+    let prefs = preferences::load(ctx).await;
+    let private = private.unwrap_or(prefs.private_reports);
 
     let today = get_current_date_jst();
-    let days_since_monday = today.weekday().num_days_from_monday() as u64;
-    let start_of_week = today
-        .checked_sub_days(Days::new(days_since_monday))
-        .unwrap_or(today);
+    let start_of_week = get_week_start(today);
 
     match queries::get_work_sessions_by_date_range(pool, user.id, start_of_week, today).await {
         Ok(sessions) => {
-            let date_range = format!(
-                "{} ～ {}",
-                start_of_week.format("%Y年%m月%d日"),
-                today.format("%Y年%m月%d日")
+            let (sessions, deductions) =
+                apply_break_rule(ctx, user.id, start_of_week, today, sessions).await;
+            let sessions = merge_for_display(sessions, merge_gap);
+            let date_format = t!(prefs.lang, "format.date");
+            let date_range = t!(
+                prefs.lang,
+                "format.date_range",
+                start = start_of_week.format(&date_format),
+                end = today.format(&date_format)
             );
 
-            let embed = create_report_embed(&username, "週次レポート", &date_range, &sessions);
+            let embed = create_report_embed(
+                &username,
+                &t!(prefs.lang, "report.weekly_title"),
+                &date_range,
+                &sessions,
+                prefs.lang,
+                prefs.clock,
+            );
+            let embed = with_break_field(embed, &deductions);
+            let embed =
+                with_adjustment_field(embed, ctx, user.id, start_of_week, today, &sessions).await;
+            let embed = with_late_start_field(embed, ctx, user.id, &sessions).await;
+            let embed = with_shift_field(
+                embed,
+                ctx,
+                user.id,
+                start_of_week,
+                today,
+                &sessions,
+                prefs.clock,
+            )
+            .await;
+            let embed = with_overtime_field(embed, &sessions, overtime_thresholds(ctx).await);
+            let embed = with_project_field(embed, ctx, user.id, start_of_week, today).await;
+            let mut embed = with_focus_field(embed, ctx, user.id, start_of_week, today).await;
+            if let Some(progress) = weekly_progress(pool, user.id, today, prefs.lang).await {
+                embed = embed.field("今週の目標", progress, false);
+            }
 
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+            ctx.send(
+                poise::CreateReply::default()
+                    .embed(embed)
+                    .ephemeral(private),
+            )
+            .await?;
         }
         Err(e) => {
-            let embed =
-                create_error_embed("エラー", &format!("勤務記録の取得に失敗しました: {}", e));
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+            return AppError::internal("勤務記録の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
         }
     }
 
@@ -97,23 +390,30 @@ pub async fn weekly(ctx: Context<'_>) -> Result<(), Error> {
 
 /// 今月の勤務レポートを表示します
 #[poise::command(slash_command)]
-pub async fn monthly(ctx: Context<'_>) -> Result<(), Error> {
+pub async fn monthly(
+    ctx: Context<'_>,
+    #[description = "この分数未満の間隔のセッションを1行にまとめて表示"]
+    #[min = 1]
+    #[max = 240]
+    merge_gap: Option<u32>,
+
+    #[description = "自分だけに表示する（未指定で /settings の設定に従う）"] private: Option<bool>,
+) -> Result<(), Error> {
     let user_id = ctx.author().id.to_string();
     let username = ctx.author().name.clone();
     let pool = &ctx.data().pool;
 
     // Create or get user
-    let user = match queries::create_or_get_user(pool, &user_id, &username).await {
+    let user = match queries::create_or_get_user(pool, &user_id, ctx.guild_id(), &username).await {
         Ok(user) => user,
         Err(e) => {
-            let embed = create_error_embed(
-                "エラー",
-                &format!("ユーザー情報の取得に失敗しました: {}", e),
-            );
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
-            return Ok(());
+            return AppError::internal("ユーザー情報の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
         }
     };
+    let prefs = preferences::load(ctx).await;
+    let private = private.unwrap_or(prefs.private_reports);
 
     let today = get_current_date_jst();
     let start_of_month =
@@ -121,22 +421,399 @@ pub async fn monthly(ctx: Context<'_>) -> Result<(), Error> {
 
     match queries::get_work_sessions_by_date_range(pool, user.id, start_of_month, today).await {
         Ok(sessions) => {
-            let date_range = format!(
-                "{} ～ {}",
-                start_of_month.format("%Y年%m月%d日"),
-                today.format("%Y年%m月%d日")
+            let (sessions, deductions) =
+                apply_break_rule(ctx, user.id, start_of_month, today, sessions).await;
+            let sessions = merge_for_display(sessions, merge_gap);
+            let date_format = t!(prefs.lang, "format.date");
+            let date_range = t!(
+                prefs.lang,
+                "format.date_range",
+                start = start_of_month.format(&date_format),
+                end = today.format(&date_format)
             );
 
-            let embed = create_report_embed(&username, "月次レポート", &date_range, &sessions);
+            let thresholds = overtime_thresholds(ctx).await;
+            let embed = create_report_embed(
+                &username,
+                &t!(prefs.lang, "report.monthly_title"),
+                &date_range,
+                &sessions,
+                prefs.lang,
+                prefs.clock,
+            );
+            let embed = with_break_field(embed, &deductions);
+            let embed =
+                with_adjustment_field(embed, ctx, user.id, start_of_month, today, &sessions).await;
+            let embed = with_late_start_field(embed, ctx, user.id, &sessions).await;
+            let embed = with_shift_field(
+                embed,
+                ctx,
+                user.id,
+                start_of_month,
+                today,
+                &sessions,
+                prefs.clock,
+            )
+            .await;
+            let embed = with_overtime_field(embed, &sessions, thresholds);
+            let embed = with_project_field(embed, ctx, user.id, start_of_month, today).await;
+            let mut embed = with_focus_field(embed, ctx, user.id, start_of_month, today).await;
+
+            // 時給が設定されていれば給与見積もりを添える
+            if let Some(guild_id) = ctx.guild_id() {
+                match queries::get_effective_rate(pool, &guild_id.to_string(), user.id).await {
+                    Ok(Some(rate)) => {
+                        let estimate = PayEstimate::calculate(&sessions, &rate, thresholds);
+                        embed = embed.field(
+                            "給与見積もり",
+                            format_pay_estimate(&estimate, &rate),
+                            false,
+                        );
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("Failed to get rate for user {}: {:?}", user.id, e),
+                }
+            }
+            if let Some(cap) = hour_caps::get_cap(pool, ctx.guild_id(), user.id).await {
+                let forecast = MonthForecast::calculate(&sessions, today, cap.monthly_minutes);
+                embed = embed.field("月間上限", format_hour_cap(&forecast), false);
+            }
 
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+            // 日ごとの勤務時間のグラフを添付する（生成に失敗した場合は埋め込みのみ）
+            let mut reply = poise::CreateReply::default();
+            match render_daily_hours_chart(today, &sessions, thresholds.daily_minutes) {
+                Ok(png) => {
+                    embed = embed.image("attachment://monthly.png");
+                    reply = reply.attachment(serenity::CreateAttachment::bytes(png, "monthly.png"));
+                }
+                Err(e) => tracing::warn!("Failed to render monthly chart: {:?}", e),
+            }
+            ctx.send(reply.embed(embed).ephemeral(private)).await?;
         }
         Err(e) => {
-            let embed =
-                create_error_embed("エラー", &format!("勤務記録の取得に失敗しました: {}", e));
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+            return AppError::internal("勤務記録の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    }
+
+    Ok(())
+}
+
+/// 遅い開始とみなす既定の時刻
+const DEFAULT_LATE_AFTER: &str = "09:00";
+
+/// 平均の開始・終了時刻や最長の勤務など、勤務の傾向を表示します
+#[poise::command(slash_command)]
+pub async fn stats(
+    ctx: Context<'_>,
+    #[description = "集計期間（未指定で今月）"] period: Option<ReportPeriod>,
+    #[description = "この時刻より後の開始を遅い開始として数える (HH:MM、既定 09:00)"]
+    late_after: Option<String>,
+
+    #[description = "自分だけに表示する（未指定で /settings の設定に従う）"] private: Option<bool>,
+) -> Result<(), Error> {
+    let pool = &ctx.data().pool;
+
+    let late_after = match validate_time_format(late_after.as_deref().unwrap_or(DEFAULT_LATE_AFTER))
+    {
+        Ok(time) => time,
+        Err(e) => {
+            let embed = create_error_embed("入力エラー", &e.to_string());
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let user = match queries::create_or_get_user(
+        pool,
+        &ctx.author().id.to_string(),
+        ctx.guild_id(),
+        &ctx.author().name,
+    )
+    .await
+    {
+        Ok(user) => user,
+        Err(e) => {
+            return AppError::internal("ユーザー情報の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    let prefs = preferences::load(ctx).await;
+    let private = private.unwrap_or(prefs.private_reports);
+
+    let period = period.unwrap_or(ReportPeriod::ThisMonth);
+    let (start_date, end_date) = period.date_range(get_current_date_jst());
+    let result = match queries::get_session_aggregates(pool, user.id, start_date, end_date).await {
+        Ok(aggregates) => queries::get_daily_spans(pool, user.id, start_date, end_date)
+            .await
+            .map(|spans| WorkPatternStats::calculate(aggregates, &spans, late_after)),
+        Err(e) => Err(e),
+    };
+
+    let embed = match result {
+        Ok(stats) => create_info_embed(
+            &format!("📈 勤務の傾向（{}）", poise::ChoiceParameter::name(&period)),
+            &format_work_pattern_stats(&stats),
+        )
+        .footer(serenity::CreateEmbedFooter::new(format!(
+            "{} ～ {}・完了した勤務のみ集計",
+            start_date.format("%Y/%m/%d"),
+            end_date.format("%Y/%m/%d")
+        ))),
+        Err(e) => {
+            return AppError::internal("勤務記録の集計に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(embed)
+            .ephemeral(private),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// 月のカレンダーで勤務日・休み・未終了の日を表示します
+#[poise::command(slash_command)]
+pub async fn calendar(
+    ctx: Context<'_>,
+    #[description = "対象月 (YYYY-MM、未指定で今月)"] month: Option<String>,
+
+    #[description = "自分だけに表示する（未指定で /settings の設定に従う）"] private: Option<bool>,
+) -> Result<(), Error> {
+    let pool = &ctx.data().pool;
+
+    let today = get_current_date_jst();
+    let target_date = match month.as_deref().map(validate_month_format) {
+        Some(Ok(date)) => date,
+        Some(Err(e)) => {
+            let embed = create_error_embed("入力エラー", &e.to_string());
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+        None => today,
+    };
+    let (start_of_month, end_of_month) = get_month_range(target_date);
+
+    let user = match queries::create_or_get_user(
+        pool,
+        &ctx.author().id.to_string(),
+        ctx.guild_id(),
+        &ctx.author().name,
+    )
+    .await
+    {
+        Ok(user) => user,
+        Err(e) => {
+            return AppError::internal("ユーザー情報の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    let prefs = preferences::load(ctx).await;
+    let private = private.unwrap_or(prefs.private_reports);
+
+    match queries::get_work_sessions_by_date_range(pool, user.id, start_of_month, end_of_month)
+        .await
+    {
+        Ok(sessions) => {
+            let embed = create_info_embed(
+                &format!("📅 {}のカレンダー", start_of_month.format("%Y年%m月")),
+                &format_calendar(target_date, &sessions, today),
+            )
+            .footer(serenity::CreateEmbedFooter::new(
+                "各行は月曜日〜日曜日 🟩 勤務 🟨 未終了あり ⬜ 休み ➖ 未来の日",
+            ));
+            ctx.send(
+                poise::CreateReply::default()
+                    .embed(embed)
+                    .ephemeral(private),
+            )
+            .await?;
+        }
+        Err(e) => {
+            return AppError::internal("勤務記録の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    }
+
+    Ok(())
+}
+
+/// 今月のペースから月末の勤務時間を予測します
+#[poise::command(slash_command)]
+pub async fn forecast(
+    ctx: Context<'_>,
+    #[description = "今月の目標時間（未指定で所定労働時間×平日数）"]
+    #[min = 1]
+    #[max = 744]
+    target_hours: Option<u32>,
+
+    #[description = "自分だけに表示する（未指定で /settings の設定に従う）"] private: Option<bool>,
+) -> Result<(), Error> {
+    let pool = &ctx.data().pool;
+
+    let user = match queries::create_or_get_user(
+        pool,
+        &ctx.author().id.to_string(),
+        ctx.guild_id(),
+        &ctx.author().name,
+    )
+    .await
+    {
+        Ok(user) => user,
+        Err(e) => {
+            return AppError::internal("ユーザー情報の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    let prefs = preferences::load(ctx).await;
+    let private = private.unwrap_or(prefs.private_reports);
+
+    let today = get_current_date_jst();
+    let (start_of_month, _) = get_month_range(today);
+    let sessions = match queries::get_work_sessions_by_date_range(
+        pool,
+        user.id,
+        start_of_month,
+        today,
+    )
+    .await
+    {
+        Ok(sessions) => sessions,
+        Err(e) => {
+            return AppError::internal("勤務記録の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    let target_minutes = match target_hours {
+        Some(hours) => hours as i32 * 60,
+        None => standard_month_minutes(today, overtime_thresholds(ctx).await.daily_minutes),
+    };
+    let forecast = MonthForecast::calculate(&sessions, today, target_minutes);
+    let mut embed = create_info_embed(
+        &format!("勤務時間の予測（{}）", today.format("%Y年%m月")),
+        &format_forecast(&forecast),
+    );
+    if let Some(cap) = hour_caps::get_cap(pool, ctx.guild_id(), user.id).await {
+        let forecast = MonthForecast::calculate(&sessions, today, cap.monthly_minutes);
+        let mut text = format_hour_cap(&forecast);
+        if let Some(warning) = format_cap_warning(CapLevel::evaluate(&forecast), &forecast) {
+            text.push_str(&format!("\n{}", warning));
+        }
+        embed = embed.field("月間上限", text, false);
+    }
+    let embed = embed.footer(serenity::CreateEmbedFooter::new(
+        "営業日は土日を除いた平日です。勤務中のセッションは含みません",
+    ));
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(embed)
+            .ephemeral(private),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// 今月の勤務時間ランキングを表示します
+#[poise::command(slash_command, guild_only)]
+pub async fn leaderboard(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let pool = &ctx.data().pool;
+
+    // メンバー確認に時間がかかる場合があるため先に応答を保留する
+    ctx.defer().await?;
+
+    let today = get_current_date_jst();
+    let (start_of_month, _) = get_month_range(today);
+
+    let totals = match queries::get_total_minutes_by_user(
+        pool,
+        &guild_id.to_string(),
+        start_of_month,
+        today,
+    )
+    .await
+    {
+        Ok(totals) => totals,
+        Err(e) => {
+            return AppError::internal("勤務記録の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    // このギルドのメンバーのみを対象にする
+    let mut ranking = Vec::new();
+    for (user, total_minutes) in totals {
+        let Ok(discord_id) = user.discord_id.parse::<u64>() else {
+            continue;
+        };
+        if guild_id
+            .member(ctx, serenity::UserId::new(discord_id))
+            .await
+            .is_ok()
+        {
+            ranking.push((user, total_minutes));
         }
     }
 
+    let embed = serenity::CreateEmbed::new()
+        .title(format!(
+            "🏆 {}の勤務時間ランキング",
+            today.format("%Y年%m月")
+        ))
+        .description(format_leaderboard(&ranking, &ctx.author().id.to_string()))
+        .color(0xf1c40f) // Gold
+        .footer(serenity::CreateEmbedFooter::new(format!(
+            "{} ～ {}",
+            start_of_month.format("%Y年%m月%d日"),
+            today.format("%Y年%m月%d日")
+        )))
+        .timestamp(chrono::Utc::now());
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// いま勤務中のメンバーと開始時刻・経過時間を表示します
+#[poise::command(slash_command, guild_only)]
+pub async fn working(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let pool = &ctx.data().pool;
+
+    let active = match queries::get_active_sessions_by_guild(pool, &guild_id.to_string()).await {
+        Ok(active) => active,
+        Err(e) => {
+            return AppError::internal("勤務記録の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    let now = chrono::Utc::now();
+    let embed = serenity::CreateEmbed::new()
+        .title("🟢 勤務中のメンバー")
+        .description(format_working_members(&active, get_current_date_jst(), now))
+        .color(0x00ff00) // Green
+        .timestamp(now);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
     Ok(())
 }
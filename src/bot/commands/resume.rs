@@ -0,0 +1,149 @@
+// `/resume` is for users who clocked out by mistake: it deletes their latest end record if it
+// is recent enough (guild_settings.resume_window_minutes, set with `/admin resume_window`), which
+// reopens the session. The deletion goes through the audit log like any other, so `/undo` can
+// restore it.
+
+use crate::bot::preferences::{self, Preferences};
+use crate::bot::{AppError, Context, Error};
+use crate::database::models::{AttendanceRecord, AuditSource, RecordType};
+use crate::database::queries;
+use crate::t;
+use crate::utils::format::{create_error_embed, create_success_embed};
+use crate::utils::session_manager::SessionManager;
+use crate::utils::time::get_date_from_utc_timestamp;
+use chrono::{Duration, Utc};
+
+/// ギルドの設定が無い場合（DMなど）の取り消し期限
+const DEFAULT_RESUME_WINDOW_MINUTES: i32 = 10;
+
+/// 間違えて勤務を終了したとき、直前の終了を取り消して勤務を再開します
+#[poise::command(slash_command)]
+pub async fn resume(ctx: Context<'_>) -> Result<(), Error> {
+    let prefs = preferences::load(ctx).await;
+    let lang = prefs.lang;
+
+    let reply = match reopen_session(ctx, &prefs).await {
+        Ok(description) => poise::CreateReply::default()
+            .embed(create_success_embed(
+                &t!(lang, "resume.title"),
+                &description,
+            ))
+            .ephemeral(prefs.ephemeral),
+        Err(Failure::Rejected(title, description)) => poise::CreateReply::default()
+            .embed(create_error_embed(&title, &description))
+            .ephemeral(true),
+        Err(Failure::Error(error)) => return error.reply(ctx).await,
+    };
+    ctx.send(reply).await?;
+
+    Ok(())
+}
+
+/// 再開できなかった理由
+enum Failure {
+    /// 取り消せる終了記録が無い（タイトルと説明）
+    Rejected(String, String),
+    Error(AppError),
+}
+
+impl From<AppError> for Failure {
+    fn from(error: AppError) -> Self {
+        Failure::Error(error)
+    }
+}
+
+/// 期限内の最後の終了記録を削除し、結果の説明を返す
+async fn reopen_session(ctx: Context<'_>, prefs: &Preferences) -> Result<String, Failure> {
+    let pool = &ctx.data().pool;
+    let lang = prefs.lang;
+    let user = queries::create_or_get_user(
+        pool,
+        &ctx.author().id.to_string(),
+        ctx.guild_id(),
+        &ctx.author().name,
+    )
+    .await
+    .map_err(|e| AppError::internal(t!(lang, "error.user_lookup"), e))?;
+
+    let window = match ctx.guild_id() {
+        Some(guild_id) => match queries::get_guild_settings(pool, &guild_id.to_string()).await {
+            Ok(settings) => settings.map_or(DEFAULT_RESUME_WINDOW_MINUTES, |settings| {
+                settings.resume_window_minutes
+            }),
+            Err(e) => {
+                tracing::warn!("Failed to load guild settings: {}", e);
+                DEFAULT_RESUME_WINDOW_MINUTES
+            }
+        },
+        None => DEFAULT_RESUME_WINDOW_MINUTES,
+    };
+    let nothing = || {
+        Failure::Rejected(
+            t!(lang, "resume.nothing_title"),
+            t!(lang, "resume.nothing", minutes = window),
+        )
+    };
+
+    // 期限が日付をまたぐ場合に備えて、期限の始まりの日から今日までの記録を見る
+    let now = Utc::now();
+    let since = now - Duration::minutes(window.into());
+    let mut records: Vec<AttendanceRecord> = Vec::new();
+    let mut date = get_date_from_utc_timestamp(since);
+    while date <= get_date_from_utc_timestamp(now) {
+        let day = queries::get_records_by_date(pool, user.id, date)
+            .await
+            .map_err(|e| AppError::internal(t!(lang, "error.records_lookup"), e))?;
+        records.extend(day);
+        let Some(next) = date.succ_opt() else { break };
+        date = next;
+    }
+    records.sort_by_key(|record| record.timestamp);
+
+    let end_record = match records
+        .iter()
+        .rev()
+        .find(|record| record.record_type.is_session_boundary())
+    {
+        Some(record) if record.record_type == RecordType::Start => {
+            return Err(Failure::Rejected(
+                t!(lang, "resume.nothing_title"),
+                t!(lang, "resume.already_working"),
+            ));
+        }
+        Some(record) if record.timestamp >= since && record.timestamp <= now => record,
+        _ => return Err(nothing()),
+    };
+
+    let audit = AuditSource::new(ctx.author().id, "/resume").in_guild(ctx.guild_id());
+    let deleted = queries::delete_attendance_record(pool, user.id, end_record.id, &audit)
+        .await
+        .map_err(|e| AppError::internal(t!(lang, "resume.failed"), e))?;
+    if !deleted {
+        return Err(nothing());
+    }
+
+    let session_manager = SessionManager::new(pool.clone());
+    if let Err(e) = session_manager
+        .trigger_recalculation(user.id, get_date_from_utc_timestamp(end_record.timestamp))
+        .await
+    {
+        tracing::error!("Failed to recalculate sessions: {}", e);
+    }
+
+    let mut description = t!(
+        lang,
+        "resume.done",
+        time = prefs.clock.time(end_record.timestamp)
+    );
+    if let Some(start) = records.iter().rev().find(|record| {
+        record.record_type == RecordType::Start && record.timestamp < end_record.timestamp
+    }) {
+        description.push('\n');
+        description.push_str(&t!(
+            lang,
+            "resume.started_at",
+            time = prefs.clock.time(start.timestamp)
+        ));
+    }
+    Ok(description)
+}
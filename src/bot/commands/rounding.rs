@@ -0,0 +1,67 @@
+use crate::bot::{AppError, Context, Error};
+use crate::database::queries;
+use crate::utils::format::{create_error_embed, create_success_embed};
+
+/// 勤務時間の丸めを設定します
+#[poise::command(slash_command, subcommands("set"), subcommand_required)]
+pub async fn rounding(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// 開始を切り上げ・終了を切り捨てる丸めの単位を設定します
+#[poise::command(slash_command)]
+pub async fn set(
+    ctx: Context<'_>,
+    #[description = "丸める単位（分、60の約数。未指定で丸めない）"]
+    #[min = 1]
+    #[max = 60]
+    minutes: Option<u32>,
+) -> Result<(), Error> {
+    if let Some(minutes) = minutes
+        && 60 % minutes != 0
+    {
+        let embed = create_error_embed(
+            "入力エラー",
+            "丸める単位は 5・10・15・30 分など60の約数で指定してください",
+        );
+        ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+
+    let pool = &ctx.data().pool;
+    let result = match queries::create_or_get_user(
+        pool,
+        &ctx.author().id.to_string(),
+        ctx.guild_id(),
+        &ctx.author().name,
+    )
+    .await
+    {
+        Ok(user) => queries::set_rounding_minutes(pool, user.id, minutes.map(|m| m as i32)).await,
+        Err(e) => Err(e),
+    };
+
+    let embed = match (result, minutes) {
+        (Ok(()), Some(minutes)) => create_success_embed(
+            "勤務時間の丸め",
+            &format!(
+                "開始を{0}分単位で切り上げ、終了を{0}分単位で切り捨てて集計します\nこれから記録・修正する日の勤務に適用されます（丸める前の時間も保存します）",
+                minutes
+            ),
+        ),
+        (Ok(()), None) => create_success_embed(
+            "勤務時間の丸め",
+            "丸めを解除しました。これから記録・修正する日の勤務は打刻どおりに集計します",
+        ),
+        (Err(e), _) => {
+            return AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,248 @@
+use crate::bot::{AppError, Context, Error};
+use crate::database::models::User;
+use crate::database::queries;
+use crate::utils::format::{
+    create_error_embed, create_info_embed, create_success_embed, format_work_schedules,
+};
+use crate::utils::schedule::WEEKDAY_NAMES;
+use crate::utils::validation::validate_time_format;
+use chrono::Timelike;
+
+/// 開始予定時刻を登録する曜日
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum ScheduleDay {
+    #[name = "平日（月〜金）"]
+    Weekdays,
+    #[name = "月曜日"]
+    Monday,
+    #[name = "火曜日"]
+    Tuesday,
+    #[name = "水曜日"]
+    Wednesday,
+    #[name = "木曜日"]
+    Thursday,
+    #[name = "金曜日"]
+    Friday,
+    #[name = "土曜日"]
+    Saturday,
+    #[name = "日曜日"]
+    Sunday,
+}
+
+impl ScheduleDay {
+    /// 対象の曜日（0 = 月曜）
+    fn weekdays(self) -> Vec<i32> {
+        match self {
+            ScheduleDay::Weekdays => (0..5).collect(),
+            day => vec![day as i32 - 1],
+        }
+    }
+}
+
+/// 曜日ごとの開始予定時刻を管理します
+#[poise::command(
+    slash_command,
+    subcommands("set", "clear", "show", "nudge", "checkin"),
+    subcommand_required
+)]
+pub async fn schedule(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// コマンドを実行したユーザー（取得に失敗した場合はエラーを表示して `None`）
+async fn author(ctx: Context<'_>) -> Result<Option<User>, Error> {
+    match queries::create_or_get_user(
+        &ctx.data().pool,
+        &ctx.author().id.to_string(),
+        ctx.guild_id(),
+        &ctx.author().name,
+    )
+    .await
+    {
+        Ok(user) => Ok(Some(user)),
+        Err(e) => {
+            AppError::internal("ユーザー情報の取得に失敗しました", e)
+                .reply(ctx)
+                .await?;
+            Ok(None)
+        }
+    }
+}
+
+/// 開始予定時刻を登録します（遅れて始めた日はレポートに ⚠️ で表示）
+#[poise::command(slash_command)]
+pub async fn set(
+    ctx: Context<'_>,
+    #[description = "曜日"] day: ScheduleDay,
+    #[description = "開始予定時刻 (HH:MM)"] start: String,
+) -> Result<(), Error> {
+    let start = match validate_time_format(&start) {
+        Ok(time) => time,
+        Err(e) => {
+            let embed = create_error_embed("入力エラー", &e.to_string());
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+    };
+    let Some(user) = author(ctx).await? else {
+        return Ok(());
+    };
+
+    let start_minutes = (start.hour() * 60 + start.minute()) as i32;
+    let embed = match queries::set_work_schedule(
+        &ctx.data().pool,
+        user.id,
+        &day.weekdays(),
+        start_minutes,
+    )
+    .await
+    {
+        Ok(()) => create_success_embed(
+            "開始予定時刻",
+            &format!(
+                "{} の開始予定時刻を {} に設定しました\n予定より遅く始めた日はレポートに ⚠️ で表示します",
+                poise::ChoiceParameter::name(&day),
+                start.format("%H:%M")
+            ),
+        ),
+        Err(e) => {
+            return AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 開始予定時刻の登録を解除します
+#[poise::command(slash_command)]
+pub async fn clear(
+    ctx: Context<'_>,
+    #[description = "曜日"] day: ScheduleDay,
+) -> Result<(), Error> {
+    let Some(user) = author(ctx).await? else {
+        return Ok(());
+    };
+
+    let weekdays = day.weekdays();
+    let embed = match queries::delete_work_schedule(&ctx.data().pool, user.id, &weekdays).await {
+        Ok(0) => create_info_embed(
+            "開始予定時刻",
+            &format!(
+                "{} には開始予定時刻が登録されていません",
+                poise::ChoiceParameter::name(&day)
+            ),
+        ),
+        Ok(_) => create_success_embed(
+            "開始予定時刻",
+            &format!(
+                "{} の開始予定時刻を解除しました",
+                weekdays
+                    .iter()
+                    .map(|weekday| WEEKDAY_NAMES[*weekday as usize])
+                    .collect::<Vec<_>>()
+                    .join("・")
+            ),
+        ),
+        Err(e) => {
+            return AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 登録済みの開始予定時刻を表示します
+#[poise::command(slash_command)]
+pub async fn show(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(user) = author(ctx).await? else {
+        return Ok(());
+    };
+
+    let embed = match queries::get_work_schedules(&ctx.data().pool, user.id).await {
+        Ok(schedules) => create_info_embed("開始予定時刻", &format_work_schedules(&schedules)),
+        Err(e) => {
+            return AppError::internal("設定の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 開始予定時刻を過ぎても出勤がないときにDMで知らせるかを設定します
+#[poise::command(slash_command)]
+pub async fn nudge(
+    ctx: Context<'_>,
+    #[description = "DMで知らせる"] enabled: bool,
+) -> Result<(), Error> {
+    let Some(user) = author(ctx).await? else {
+        return Ok(());
+    };
+
+    let embed = match queries::set_schedule_nudge_enabled(&ctx.data().pool, user.id, enabled).await
+    {
+        Ok(()) if enabled => create_success_embed(
+            "出勤の通知",
+            "開始予定時刻やシフトの開始時刻を過ぎても出勤の記録がないときは、DMでお知らせします",
+        ),
+        Ok(()) => create_success_embed("出勤の通知", "出勤の通知を停止しました"),
+        Err(e) => {
+            return AppError::internal("設定の保存に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 勤務日の決まった時刻に、まだ出勤していなければDMで知らせます（未指定で停止）
+#[poise::command(slash_command)]
+pub async fn checkin(
+    ctx: Context<'_>,
+    #[description = "知らせる時刻 (HH:MM、未指定で停止)"] time: Option<String>,
+) -> Result<(), Error> {
+    let time = time
+        .as_deref()
+        .map(validate_time_format)
+        .transpose()
+        .map_err(|e| AppError::user(e.to_string()))?;
+    let Some(user) = author(ctx).await? else {
+        return Ok(());
+    };
+
+    let minutes = time.map(|time| (time.hour() * 60 + time.minute()) as i32);
+    if let Err(e) = queries::set_checkin_reminder(&ctx.data().pool, user.id, minutes).await {
+        return AppError::internal("設定の保存に失敗しました", e)
+            .reply(ctx)
+            .await;
+    }
+
+    let description = match time {
+        Some(time) => format!(
+            "勤務日の {} までに出勤していなければ、出勤ボタン付きのDMでお知らせします\n\
+             勤務日は開始予定時刻を登録した曜日（登録がなければ月〜金）で、祝日は除きます",
+            time.format("%H:%M")
+        ),
+        None => "出勤リマインダーを停止しました".to_string(),
+    };
+    let embed = create_success_embed("出勤リマインダー", &description);
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use crate::bot::commands::language::LanguageChoice;
+use crate::bot::preferences::Preferences;
+use crate::bot::{AppError, Context, Error};
+use crate::database::queries;
+use crate::t;
+use crate::utils::format::{create_error_embed, create_info_embed, create_success_embed};
+use crate::utils::i18n::Lang;
+use crate::utils::validation::validate_utc_offset;
+
+/// 応答を表示する範囲
+#[derive(Debug, Clone, Copy, PartialEq, poise::ChoiceParameter)]
+pub enum Visibility {
+    #[name = "チャンネルに表示"]
+    Public,
+    #[name = "自分だけに表示"]
+    Private,
+}
+
+/// 時刻の表示形式
+#[derive(Debug, Clone, Copy, PartialEq, poise::ChoiceParameter)]
+pub enum TimeFormatChoice {
+    #[name = "24時間（13:00）"]
+    Hour24,
+    #[name = "12時間（1:00 PM）"]
+    Hour12,
+}
+
+/// 個人設定を表示・変更します（指定した項目だけ変更）
+#[poise::command(slash_command)]
+pub async fn settings(
+    ctx: Context<'_>,
+    #[description = "/start・/end・/status の応答"] responses: Option<Visibility>,
+    #[description = "表示言語"] language: Option<LanguageChoice>,
+    #[description = "タイムゾーン（例: +09:00, UTC-5, JST）"] timezone: Option<String>,
+    #[description = "時刻の表示形式"] time_format: Option<TimeFormatChoice>,
+    #[description = "自分のレポート（/daily・/weekly・/monthly など）"] reports: Option<Visibility>,
+) -> Result<(), Error> {
+    let pool = &ctx.data().pool;
+    let discord_id = ctx.author().id.to_string();
+    let user =
+        match queries::create_or_get_user(pool, &discord_id, ctx.guild_id(), &ctx.author().name)
+            .await
+        {
+            Ok(user) => user,
+            Err(e) => {
+                let lang = Lang::from_locale(ctx.locale());
+                return AppError::internal(t!(lang, "error.user_lookup"), e)
+                    .reply(ctx)
+                    .await;
+            }
+        };
+    let mut settings = match queries::get_user_preferences(pool, &discord_id, ctx.guild_id()).await
+    {
+        Ok(settings) => settings,
+        Err(e) => {
+            let lang = Lang::from_locale(ctx.locale());
+            return AppError::internal(t!(lang, "settings.load_error"), e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    let lang = Preferences::from_settings(&settings, ctx.locale()).lang;
+
+    let utc_offset_minutes = match timezone.as_deref().map(validate_utc_offset).transpose() {
+        Ok(offset) => offset,
+        Err(e) => {
+            let embed = create_error_embed(&t!(lang, "error.input_title"), &e.to_string());
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let changed = responses.is_some()
+        || language.is_some()
+        || utc_offset_minutes.is_some()
+        || time_format.is_some()
+        || reports.is_some();
+    if let Some(responses) = responses {
+        settings.ephemeral_responses = responses == Visibility::Private;
+    }
+    if let Some(language) = language {
+        settings.language = language.lang().map(|lang| lang.code().to_string());
+    }
+    if let Some(offset) = utc_offset_minutes {
+        settings.utc_offset_minutes = Some(offset);
+    }
+    if let Some(time_format) = time_format {
+        settings.clock_12h = time_format == TimeFormatChoice::Hour12;
+    }
+    if let Some(reports) = reports {
+        settings.private_reports = reports == Visibility::Private;
+    }
+
+    if changed && let Err(e) = queries::set_user_preferences(pool, user.id, &settings).await {
+        return AppError::internal(t!(lang, "error.settings_save"), e)
+            .reply(ctx)
+            .await;
+    }
+
+    // 変更後の言語で表示する
+    let prefs = Preferences::from_settings(&settings, ctx.locale());
+    let lang = prefs.lang;
+    let visibility = |private: bool| {
+        if private {
+            t!(lang, "settings.visibility_private")
+        } else {
+            t!(lang, "settings.visibility_public")
+        }
+    };
+    let summary = t!(
+        lang,
+        "settings.summary",
+        language = match settings.language {
+            Some(_) => lang.native_name().to_string(),
+            None => t!(lang, "settings.language_auto", current = lang.native_name()),
+        },
+        timezone = prefs.clock.zone_name(),
+        time_format = if prefs.clock.clock_12h {
+            t!(lang, "settings.clock_12h")
+        } else {
+            t!(lang, "settings.clock_24h")
+        },
+        responses = visibility(prefs.ephemeral),
+        reports = visibility(prefs.private_reports)
+    );
+    let embed = if changed {
+        create_success_embed(
+            &t!(lang, "settings.title"),
+            &format!("{}\n\n{}", t!(lang, "settings.saved"), summary),
+        )
+    } else {
+        create_info_embed(
+            &t!(lang, "settings.title"),
+            &format!("{}\n\n{}", summary, t!(lang, "settings.hint")),
+        )
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
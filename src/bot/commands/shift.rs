@@ -0,0 +1,533 @@
+use crate::bot::checks::{is_admin, member_is_admin};
+use crate::bot::{AppError, Context, Error};
+use crate::database::models::User;
+use crate::database::queries;
+use crate::utils::format::{
+    create_error_embed, create_info_embed, create_success_embed, format_shift_templates,
+    format_shifts,
+};
+use crate::utils::schedule::plan_template_shifts;
+use crate::utils::time::{combine_date_time_jst, get_current_date_jst};
+use crate::utils::validation::{
+    validate_date_format, validate_shift_template_name, validate_time_format,
+};
+use chrono::{Timelike, Utc};
+use poise::serenity_prelude as serenity;
+
+/// `/shift list` で表示する日数
+const LIST_DAYS: i64 = 31;
+
+/// `/shift apply` でひな形を当てはめる期間
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum ShiftPeriod {
+    #[name = "1週間"]
+    Week,
+    #[name = "1か月"]
+    Month,
+}
+
+/// `/shift apply` でひな形を当てはめる曜日
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum ShiftDays {
+    #[name = "平日のみ"]
+    Weekdays,
+    #[name = "毎日"]
+    Everyday,
+}
+
+/// 勤務シフトを予定・確認します
+#[poise::command(
+    slash_command,
+    subcommands(
+        "add",
+        "list",
+        "remove",
+        "apply",
+        "template",
+        "templates",
+        "template_delete"
+    ),
+    subcommand_required
+)]
+pub async fn shift(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+async fn send_error(ctx: Context<'_>, title: &str, description: &str) -> Result<(), Error> {
+    let embed = create_error_embed(title, description);
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// 実行者が管理者か（DMでは常に false）
+async fn author_is_admin(ctx: Context<'_>) -> bool {
+    ctx.author_member()
+        .await
+        .is_some_and(|member| member_is_admin(&member, &ctx.data().config))
+}
+
+/// 操作対象のユーザー。他のユーザーを指定できるのは管理者のみ（使えない場合はエラーを表示して `None`）
+async fn target_user(
+    ctx: Context<'_>,
+    user: Option<serenity::User>,
+) -> Result<Option<User>, Error> {
+    let target = user.unwrap_or_else(|| ctx.author().clone());
+    if target.id != ctx.author().id && !author_is_admin(ctx).await {
+        send_error(
+            ctx,
+            "アクセス拒否",
+            "他のユーザーのシフトは管理者のみ操作できます",
+        )
+        .await?;
+        return Ok(None);
+    }
+
+    match queries::create_or_get_user(
+        &ctx.data().pool,
+        &target.id.to_string(),
+        ctx.guild_id(),
+        &target.name,
+    )
+    .await
+    {
+        Ok(user) => Ok(Some(user)),
+        Err(e) => {
+            AppError::internal("ユーザー情報の取得に失敗しました", e)
+                .reply(ctx)
+                .await?;
+            Ok(None)
+        }
+    }
+}
+
+/// シフトを予定します（終了が開始より前なら翌日の終了として扱います）
+#[poise::command(slash_command)]
+pub async fn add(
+    ctx: Context<'_>,
+    #[description = "日付 (YYYY-MM-DD)"] date: String,
+    #[description = "開始時刻 (HH:MM)"] start: String,
+    #[description = "終了時刻 (HH:MM)"] end: String,
+    #[description = "対象ユーザー（他のユーザーは管理者のみ）"] user: Option<serenity::User>,
+) -> Result<(), Error> {
+    let parsed = validate_date_format(&date).and_then(|date| {
+        Ok((
+            date,
+            validate_time_format(&start)?,
+            validate_time_format(&end)?,
+        ))
+    });
+    let (date, start, end) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => return AppError::user(e.to_string()).reply(ctx).await,
+    };
+    let start_time = combine_date_time_jst(date, start);
+    let mut end_time = combine_date_time_jst(date, end);
+    if end_time <= start_time {
+        end_time += chrono::Duration::days(1);
+    }
+    if start_time < Utc::now() {
+        return AppError::user("開始時刻を過ぎたシフトは追加できません")
+            .reply(ctx)
+            .await;
+    }
+
+    let Some(target) = target_user(ctx, user).await? else {
+        return Ok(());
+    };
+    let pool = &ctx.data().pool;
+
+    match queries::get_shifts_in_range(pool, target.id, start_time, end_time).await {
+        Ok(overlapping) if !overlapping.is_empty() => {
+            return send_error(
+                ctx,
+                "シフトが重なっています",
+                &format!(
+                    "次のシフトと時間が重なっています\n{}",
+                    format_shifts(&overlapping)
+                ),
+            )
+            .await;
+        }
+        Ok(_) => {}
+        Err(e) => {
+            return AppError::internal("シフトの取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    }
+
+    let guild_id = ctx.guild_id().map(|guild_id| guild_id.to_string());
+    let embed = match queries::create_shift(
+        pool,
+        target.id,
+        guild_id.as_deref(),
+        start_time,
+        end_time,
+        &ctx.author().id.to_string(),
+    )
+    .await
+    {
+        Ok(shift) => create_success_embed(
+            "シフト",
+            &format!(
+                "<@{}> のシフトを追加しました\n{}",
+                target.discord_id,
+                format_shifts(&[shift])
+            ),
+        ),
+        Err(e) => {
+            return AppError::internal("シフトの追加に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 今日から31日分のシフトを表示します
+#[poise::command(slash_command)]
+pub async fn list(
+    ctx: Context<'_>,
+    #[description = "対象ユーザー（他のユーザーは管理者のみ）"] user: Option<serenity::User>,
+) -> Result<(), Error> {
+    let Some(target) = target_user(ctx, user).await? else {
+        return Ok(());
+    };
+
+    let from = combine_date_time_jst(get_current_date_jst(), chrono::NaiveTime::MIN);
+    let to = from + chrono::Duration::days(LIST_DAYS);
+    let embed = match queries::get_shifts_in_range(&ctx.data().pool, target.id, from, to).await {
+        Ok(shifts) => create_info_embed(
+            &format!("{} のシフト", target.username),
+            &format_shifts(&shifts),
+        ),
+        Err(e) => {
+            return AppError::internal("シフトの取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// シフトを取り消します（IDは `/shift list` で確認できます）
+#[poise::command(slash_command)]
+pub async fn remove(
+    ctx: Context<'_>, #[description = "シフトのID"] id: i64
+) -> Result<(), Error> {
+    let pool = &ctx.data().pool;
+    let author = match queries::create_or_get_user(
+        pool,
+        &ctx.author().id.to_string(),
+        ctx.guild_id(),
+        &ctx.author().name,
+    )
+    .await
+    {
+        Ok(author) => author,
+        Err(e) => {
+            return AppError::internal("ユーザー情報の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    let shift = match queries::get_shift(pool, id).await {
+        Ok(shift) => shift,
+        Err(e) => {
+            return AppError::internal("シフトの取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    // 他のユーザーのシフトは、同じサーバーで登録されたものだけ管理者が取り消せる
+    let guild_id = ctx.guild_id().map(|guild_id| guild_id.to_string());
+    let shift = match shift {
+        Some(shift) if shift.user_id == author.id => shift,
+        Some(shift)
+            if shift.guild_id.is_some()
+                && shift.guild_id == guild_id
+                && author_is_admin(ctx).await =>
+        {
+            shift
+        }
+        _ => {
+            return send_error(
+                ctx,
+                "シフトが見つかりません",
+                &format!("ID {} のシフトは見つかりませんでした", id),
+            )
+            .await;
+        }
+    };
+
+    let embed = match queries::delete_shift(pool, shift.id).await {
+        Ok(_) => create_success_embed(
+            "シフト",
+            &format!("シフトを取り消しました\n{}", format_shifts(&[shift])),
+        ),
+        Err(e) => {
+            return AppError::internal("シフトの取り消しに失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// ひな形を期間内の各日にまとめて当てはめ、シフトを追加します
+#[poise::command(slash_command, guild_only)]
+pub async fn apply(
+    ctx: Context<'_>,
+    #[description = "ひな形の名前"]
+    #[autocomplete = "autocomplete_template"]
+    template: String,
+    #[description = "期間"] period: ShiftPeriod,
+    #[description = "開始日 (YYYY-MM-DD、未指定で明日)"] from: Option<String>,
+    #[description = "曜日（未指定で平日のみ）"] days: Option<ShiftDays>,
+    #[description = "対象ユーザー（他のユーザーは管理者のみ）"] user: Option<serenity::User>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id().map(|guild_id| guild_id.to_string()) else {
+        return Ok(());
+    };
+    let from = match from.as_deref().map(validate_date_format).transpose() {
+        Ok(from) => from.unwrap_or_else(|| get_current_date_jst() + chrono::Duration::days(1)),
+        Err(e) => return AppError::user(e.to_string()).reply(ctx).await,
+    };
+    let to = match period {
+        ShiftPeriod::Week => from + chrono::Duration::days(6),
+        ShiftPeriod::Month => from
+            .checked_add_months(chrono::Months::new(1))
+            .and_then(|date| date.pred_opt())
+            .unwrap_or(from),
+    };
+
+    let pool = &ctx.data().pool;
+    let template = match queries::get_shift_template(pool, &guild_id, template.trim()).await {
+        Ok(Some(template)) => template,
+        Ok(None) => {
+            return send_error(
+                ctx,
+                "ひな形が見つかりません",
+                &format!(
+                    "「{}」というひな形はありません。`/shift templates` で確認してください",
+                    template.trim()
+                ),
+            )
+            .await;
+        }
+        Err(e) => {
+            return AppError::internal("ひな形の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    let Some(target) = target_user(ctx, user).await? else {
+        return Ok(());
+    };
+
+    let days = days.unwrap_or(ShiftDays::Weekdays);
+    let now = Utc::now();
+    let mut created = Vec::new();
+    let mut skipped = 0;
+    for (start_time, end_time) in
+        plan_template_shifts(&template, from, to, matches!(days, ShiftDays::Weekdays))
+    {
+        if start_time < now {
+            skipped += 1;
+            continue;
+        }
+        match queries::get_shifts_in_range(pool, target.id, start_time, end_time).await {
+            Ok(overlapping) if !overlapping.is_empty() => {
+                skipped += 1;
+                continue;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                return AppError::internal("シフトの取得に失敗しました", e)
+                    .reply(ctx)
+                    .await;
+            }
+        }
+        match queries::create_shift(
+            pool,
+            target.id,
+            Some(&guild_id),
+            start_time,
+            end_time,
+            &ctx.author().id.to_string(),
+        )
+        .await
+        {
+            Ok(shift) => created.push(shift),
+            Err(e) => {
+                return AppError::internal("シフトの追加に失敗しました", e)
+                    .reply(ctx)
+                    .await;
+            }
+        }
+    }
+
+    let mut description = format!(
+        "<@{}> に「{}」を {}〜{}（{}）で当てはめ、{}件のシフトを追加しました",
+        target.discord_id,
+        template.name,
+        from.format("%m/%d"),
+        to.format("%m/%d"),
+        poise::ChoiceParameter::name(&days),
+        created.len()
+    );
+    if skipped > 0 {
+        description.push_str(&format!(
+            "\n重なるシフトがある日・開始時刻を過ぎた日の {}件は追加していません",
+            skipped
+        ));
+    }
+    if !created.is_empty() {
+        description.push_str(&format!("\n\n{}", format_shifts(&created)));
+    }
+    let embed = create_success_embed("シフト", &description);
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+pub async fn autocomplete_template(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Vec::new();
+    };
+
+    queries::get_shift_templates(&ctx.data().pool, &guild_id.to_string())
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|template| template.name)
+        .filter(|name| name.contains(partial))
+        .collect()
+}
+
+/// シフトのひな形を登録します（同じ名前は上書き、管理者のみ）
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn template(
+    ctx: Context<'_>,
+    #[description = "ひな形の名前（例: 早番）"] name: String,
+    #[description = "開始時刻 (HH:MM)"] start: String,
+    #[description = "終了時刻 (HH:MM、開始より前なら翌日)"] end: String,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id().map(|guild_id| guild_id.to_string()) else {
+        return Ok(());
+    };
+    let parsed = validate_shift_template_name(&name).and_then(|name| {
+        Ok((
+            name,
+            validate_time_format(&start)?,
+            validate_time_format(&end)?,
+        ))
+    });
+    let (name, start, end) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => return AppError::user(e.to_string()).reply(ctx).await,
+    };
+    if start == end {
+        return AppError::user("開始時刻と終了時刻は別の時刻にしてください")
+            .reply(ctx)
+            .await;
+    }
+
+    let minutes = |time: chrono::NaiveTime| (time.hour() * 60 + time.minute()) as i32;
+    let embed = match queries::upsert_shift_template(
+        &ctx.data().pool,
+        &guild_id,
+        &name,
+        minutes(start),
+        minutes(end),
+        &ctx.author().id.to_string(),
+    )
+    .await
+    {
+        Ok(()) => create_success_embed(
+            "シフトのひな形",
+            &format!(
+                "ひな形「{}」（{}〜{}）を登録しました\n`/shift apply` で期間内の各日にシフトを追加できます",
+                name,
+                start.format("%H:%M"),
+                end.format("%H:%M")
+            ),
+        ),
+        Err(e) => {
+            return AppError::internal("ひな形の保存に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// サーバーのシフトのひな形を表示します
+#[poise::command(slash_command, guild_only)]
+pub async fn templates(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id().map(|guild_id| guild_id.to_string()) else {
+        return Ok(());
+    };
+
+    let embed = match queries::get_shift_templates(&ctx.data().pool, &guild_id).await {
+        Ok(templates) => create_info_embed("シフトのひな形", &format_shift_templates(&templates)),
+        Err(e) => {
+            return AppError::internal("ひな形の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// シフトのひな形を削除します（追加済みのシフトは残ります）
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn template_delete(
+    ctx: Context<'_>,
+    #[description = "ひな形の名前"]
+    #[autocomplete = "autocomplete_template"]
+    name: String,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id().map(|guild_id| guild_id.to_string()) else {
+        return Ok(());
+    };
+
+    let embed = match queries::delete_shift_template(&ctx.data().pool, &guild_id, name.trim()).await
+    {
+        Ok(true) => create_success_embed(
+            "シフトのひな形",
+            &format!("ひな形「{}」を削除しました", name.trim()),
+        ),
+        Ok(false) => create_error_embed(
+            "ひな形が見つかりません",
+            &format!("「{}」というひな形はありません", name.trim()),
+        ),
+        Err(e) => {
+            return AppError::internal("ひな形の削除に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,251 @@
+use crate::bot::shortcuts::execute_shortcut;
+use crate::bot::{AppError, Context, Error};
+use crate::database::queries;
+use crate::utils::format::{create_error_embed, create_info_embed, create_success_embed};
+use crate::utils::shortcut::{format_shortcut, parse_shortcut, validate_shortcut_name};
+use poise::serenity_prelude as serenity;
+
+/// 1メッセージに表示できるボタンの上限（5行 × 5個）
+const MAX_SHORTCUT_BUTTONS: usize = 25;
+
+/// よく使う操作をショートカットとして登録・実行します
+#[poise::command(
+    slash_command,
+    subcommands("set", "run", "list", "delete"),
+    subcommand_required
+)]
+pub async fn shortcut(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// ショートカットを登録します（同名のものは上書き）
+#[poise::command(slash_command)]
+pub async fn set(
+    ctx: Context<'_>,
+    #[description = "ショートカット名"] name: String,
+    #[description = "手順を ; 区切りで指定（例: end 18:00; daily）"] action: String,
+) -> Result<(), Error> {
+    let actions = match validate_shortcut_name(&name).and_then(|_| parse_shortcut(&action)) {
+        Ok(actions) => actions,
+        Err(e) => {
+            let embed = create_error_embed("入力エラー", &e.to_string());
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+    };
+    let action = format_shortcut(&actions);
+
+    let pool = &ctx.data().pool;
+    let result = match queries::create_or_get_user(
+        pool,
+        &ctx.author().id.to_string(),
+        ctx.guild_id(),
+        &ctx.author().name,
+    )
+    .await
+    {
+        Ok(user) => queries::upsert_shortcut(pool, user.id, &name, &action).await,
+        Err(e) => Err(e),
+    };
+
+    let embed = match result {
+        Ok(()) => create_success_embed(
+            "ショートカット登録",
+            &format!(
+                "**{}** を登録しました\n手順: `{}`\n`/shortcut run name:{}` で実行できます",
+                name, action, name
+            ),
+        ),
+        Err(e) => {
+            return AppError::internal("ショートカットの保存に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+/// 登録したショートカットを実行します
+#[poise::command(slash_command)]
+pub async fn run(
+    ctx: Context<'_>,
+    #[description = "ショートカット名"]
+    #[autocomplete = "autocomplete_shortcut_name"]
+    name: String,
+) -> Result<(), Error> {
+    let pool = &ctx.data().pool;
+
+    let shortcut = match queries::create_or_get_user(
+        pool,
+        &ctx.author().id.to_string(),
+        ctx.guild_id(),
+        &ctx.author().name,
+    )
+    .await
+    {
+        Ok(user) => queries::get_shortcut(pool, user.id, &name).await,
+        Err(e) => Err(e),
+    };
+    let shortcut = match shortcut {
+        Ok(Some(shortcut)) => shortcut,
+        Ok(None) => {
+            let embed = create_error_embed(
+                "ショートカットが見つかりません",
+                &format!(
+                    "**{}** は登録されていません。`/shortcut list` で確認してください。",
+                    name
+                ),
+            );
+            ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+        Err(e) => {
+            return AppError::internal("ショートカットの取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    let embeds = match parse_shortcut(&shortcut.action) {
+        Ok(actions) => execute_shortcut(pool, ctx.author(), ctx.guild_id(), &actions).await,
+        Err(e) => vec![create_error_embed(
+            "ショートカットを実行できません",
+            &e.to_string(),
+        )],
+    };
+
+    ctx.send(poise::CreateReply {
+        embeds,
+        ..Default::default()
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// 登録したショートカットを一覧表示します（ボタンからも実行できます）
+#[poise::command(slash_command)]
+pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    let pool = &ctx.data().pool;
+    let user_id = ctx.author().id.to_string();
+
+    let shortcuts =
+        match queries::create_or_get_user(pool, &user_id, ctx.guild_id(), &ctx.author().name).await
+        {
+            Ok(user) => queries::get_shortcuts(pool, user.id).await,
+            Err(e) => Err(e),
+        };
+    let shortcuts = match shortcuts {
+        Ok(shortcuts) => shortcuts,
+        Err(e) => {
+            return AppError::internal("ショートカットの取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    if shortcuts.is_empty() {
+        let embed = create_info_embed(
+            "ショートカット",
+            "ショートカットは登録されていません\n`/shortcut set` で登録できます",
+        );
+        ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+
+    let description = shortcuts
+        .iter()
+        .map(|shortcut| format!("**{}**: `{}`", shortcut.name, shortcut.action))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let buttons: Vec<_> = shortcuts
+        .iter()
+        .take(MAX_SHORTCUT_BUTTONS)
+        .map(|shortcut| {
+            serenity::CreateButton::new(format!("shortcut_run:{}:{}", user_id, shortcut.name))
+                .label(&shortcut.name)
+                .style(serenity::ButtonStyle::Primary)
+        })
+        .collect();
+    let components = buttons
+        .chunks(5)
+        .map(|row| serenity::CreateActionRow::Buttons(row.to_vec()))
+        .collect();
+
+    let embed = create_info_embed("ショートカット", &description);
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(embed)
+            .components(components)
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// ショートカットを削除します
+#[poise::command(slash_command)]
+pub async fn delete(
+    ctx: Context<'_>,
+    #[description = "ショートカット名"]
+    #[autocomplete = "autocomplete_shortcut_name"]
+    name: String,
+) -> Result<(), Error> {
+    let pool = &ctx.data().pool;
+
+    let result = match queries::create_or_get_user(
+        pool,
+        &ctx.author().id.to_string(),
+        ctx.guild_id(),
+        &ctx.author().name,
+    )
+    .await
+    {
+        Ok(user) => queries::delete_shortcut(pool, user.id, &name).await,
+        Err(e) => Err(e),
+    };
+
+    let embed = match result {
+        Ok(true) => create_success_embed(
+            "ショートカット削除",
+            &format!("**{}** を削除しました", name),
+        ),
+        Ok(false) => create_error_embed(
+            "ショートカットが見つかりません",
+            &format!("**{}** は登録されていません", name),
+        ),
+        Err(e) => {
+            return AppError::internal("ショートカットの削除に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+async fn autocomplete_shortcut_name(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    let pool = &ctx.data().pool;
+    let Ok(user) =
+        queries::get_user_by_discord_id(pool, &ctx.author().id.to_string(), ctx.guild_id()).await
+    else {
+        return Vec::new();
+    };
+
+    queries::get_shortcuts(pool, user.id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|shortcut| shortcut.name)
+        .filter(|name| name.starts_with(partial))
+        .collect()
+}
@@ -0,0 +1,71 @@
+// `/standup` posts the morning roll call to the channel: for each registered member, today's
+// clock-in status and yesterday's hours and work notes, built from the records the bot
+// already has so nobody needs to report them by hand.
+
+use crate::bot::commands::team::collect_role_members;
+use crate::bot::guild_reset::collect_members;
+use crate::bot::{AppError, Context, Error};
+use crate::database::models::{AttendanceRecord, User, WorkSession};
+use crate::database::queries;
+use crate::utils::format::{create_info_embed, format_standup};
+use crate::utils::time::get_current_date_jst;
+use chrono::Days;
+use poise::serenity_prelude as serenity;
+
+/// メンバーの前日の勤務時間・メモと今日の出勤状況を投稿します
+#[poise::command(slash_command, guild_only)]
+pub async fn standup(
+    ctx: Context<'_>,
+    #[description = "対象のロール（未指定でサーバーの登録済みメンバー全員）"] role: Option<
+        serenity::Role,
+    >,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let pool = &ctx.data().pool;
+
+    // メンバー情報の取得に時間がかかるため先に応答を保留する（結果はチャンネルに公開する）
+    ctx.defer().await?;
+
+    let members = match &role {
+        Some(role) => collect_role_members(ctx, guild_id, role.id).await,
+        None => collect_members(ctx.http(), pool, guild_id)
+            .await
+            .map_err(Into::into),
+    };
+    let members = match members {
+        Ok(members) => members,
+        Err(e) => {
+            return AppError::internal("メンバー情報の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    let today = get_current_date_jst();
+    let yesterday = today - Days::new(1);
+    let mut entries: Vec<(User, Vec<WorkSession>, Vec<AttendanceRecord>)> = Vec::new();
+    for user in members {
+        let sessions =
+            queries::get_work_sessions_by_date_range(pool, user.id, yesterday, yesterday).await;
+        let records = queries::get_records_by_date(pool, user.id, today).await;
+        match (sessions, records) {
+            (Ok(sessions), Ok(records)) => entries.push((user, sessions, records)),
+            (Err(e), _) | (_, Err(e)) => {
+                return AppError::internal("勤務記録の取得に失敗しました", e)
+                    .reply(ctx)
+                    .await;
+            }
+        }
+    }
+
+    let title = match &role {
+        Some(role) => format!("朝会 {}（{}）", today.format("%m/%d"), role.name),
+        None => format!("朝会 {}", today.format("%m/%d")),
+    };
+    let embed = create_info_embed(&title, &format_standup(&entries));
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
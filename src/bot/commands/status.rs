@@ -1,27 +1,33 @@
-use crate::bot::{Context, Error};
+use crate::bot::commands::goal::weekly_progress;
+use crate::bot::interactions::status_buttons::record_changes_button;
+use crate::bot::{AppError, Context, Error, preferences};
 use crate::database::queries;
-use crate::utils::format::{create_error_embed, create_status_embed};
+use crate::t;
+use crate::utils::format::{create_status_embed, format_shift_comparison};
 use crate::utils::record_selector::RecordSelector;
-use crate::utils::time::get_current_date_jst;
+use crate::utils::time::{combine_date_time_jst, get_current_date_jst};
 use poise::serenity_prelude as serenity;
 
 /// 現在の勤務状況を確認します
 #[poise::command(slash_command)]
-pub async fn status(ctx: Context<'_>) -> Result<(), Error> {
+pub async fn status(
+    ctx: Context<'_>,
+    #[description = "自分だけに表示する（未指定で /settings の設定に従う）"] private: Option<bool>,
+) -> Result<(), Error> {
     let user_id = ctx.author().id.to_string();
     let username = ctx.author().name.clone();
     let pool = &ctx.data().pool;
+    let prefs = preferences::load(ctx).await;
+    let lang = prefs.lang;
+    let private = private.unwrap_or(prefs.ephemeral);
 
     // Create or get user
-    let user = match queries::create_or_get_user(pool, &user_id, &username).await {
+    let user = match queries::create_or_get_user(pool, &user_id, ctx.guild_id(), &username).await {
         Ok(user) => user,
         Err(e) => {
-            let embed = create_error_embed(
-                "エラー",
-                &format!("ユーザー情報の取得に失敗しました: {}", e),
-            );
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
-            return Ok(());
+            return AppError::internal(t!(lang, "error.user_lookup"), e)
+                .reply(ctx)
+                .await;
         }
     };
 
@@ -36,10 +42,10 @@ pub async fn status(ctx: Context<'_>) -> Result<(), Error> {
             // Create interactive buttons with user ID embedded
             let mut buttons = vec![
                 serenity::CreateButton::new(format!("record_add:{}", user_id))
-                    .label("✅ 記録追加")
+                    .label(t!(lang, "status.button.add"))
                     .style(serenity::ButtonStyle::Success),
                 serenity::CreateButton::new(format!("history_view:{}", user_id))
-                    .label("📋 履歴")
+                    .label(t!(lang, "status.button.history"))
                     .style(serenity::ButtonStyle::Secondary),
             ];
 
@@ -48,31 +54,72 @@ pub async fn status(ctx: Context<'_>) -> Result<(), Error> {
                 buttons.insert(
                     0,
                     serenity::CreateButton::new(format!("time_edit:{}", user_id))
-                        .label("🕐 時間修正")
+                        .label(t!(lang, "status.button.edit"))
                         .style(serenity::ButtonStyle::Primary),
                 );
                 buttons.insert(
                     2,
                     serenity::CreateButton::new(format!("delete_record:{}", user_id))
-                        .label("🗑️ 削除")
+                        .label(t!(lang, "status.button.delete"))
                         .style(serenity::ButtonStyle::Danger),
                 );
+                buttons.push(
+                    serenity::CreateButton::new(format!("note_add:{}", user_id))
+                        .label(t!(lang, "status.button.note"))
+                        .style(serenity::ButtonStyle::Secondary),
+                );
             }
 
-            let components = vec![serenity::CreateActionRow::Buttons(buttons)];
+            let mut components = vec![serenity::CreateActionRow::Buttons(buttons)];
+            if records.iter().any(|record| record.is_modified) {
+                components.push(serenity::CreateActionRow::Buttons(vec![
+                    record_changes_button(&user_id, current_date)
+                        .label(t!(lang, "status.button.changes")),
+                ]));
+            }
 
-            let embed = create_status_embed(&username, current_date, &records);
+            let mut embed =
+                create_status_embed(&username, current_date, &records, lang, prefs.clock);
+            if let Some(progress) = weekly_progress(pool, user.id, current_date, lang).await {
+                embed = embed.field(t!(lang, "status.weekly_goal"), progress, false);
+            }
+            let day_start = combine_date_time_jst(current_date, chrono::NaiveTime::MIN);
+            match queries::get_shifts_in_range(
+                pool,
+                user.id,
+                day_start,
+                day_start + chrono::Duration::days(1),
+            )
+            .await
+            {
+                Ok(shifts) if !shifts.is_empty() => {
+                    embed = embed.field(
+                        t!(lang, "status.shifts_today"),
+                        format_shift_comparison(
+                            &shifts,
+                            &records,
+                            chrono::Utc::now(),
+                            lang,
+                            prefs.clock,
+                        ),
+                        false,
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to load shifts: {}", e),
+            }
 
             let builder = poise::CreateReply::default()
                 .embed(embed)
-                .components(components);
+                .components(components)
+                .ephemeral(private);
 
             ctx.send(builder).await?;
         }
         Err(e) => {
-            let embed =
-                create_error_embed("エラー", &format!("勤務記録の取得に失敗しました: {}", e));
-            ctx.send(poise::CreateReply::default().embed(embed)).await?;
+            return AppError::internal(t!(lang, "error.records_lookup"), e)
+                .reply(ctx)
+                .await;
         }
     }
 
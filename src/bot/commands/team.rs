@@ -0,0 +1,139 @@
+use crate::bot::checks::is_admin;
+use crate::bot::{AppError, Context, Error};
+use crate::database::models::{User, WorkSession};
+use crate::database::queries;
+use crate::utils::export::{format_team_report_csv, sign_export};
+use crate::utils::format::create_team_report_embed;
+use crate::utils::time::{get_current_date_jst, get_month_range, get_week_start};
+use chrono::{Days, Months, NaiveDate};
+use poise::serenity_prelude as serenity;
+
+/// レポートの集計期間
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum ReportPeriod {
+    #[name = "今週"]
+    ThisWeek,
+    #[name = "先週"]
+    LastWeek,
+    #[name = "今月"]
+    ThisMonth,
+    #[name = "先月"]
+    LastMonth,
+}
+
+impl ReportPeriod {
+    /// 基準日から集計期間の開始日と終了日を求める
+    pub fn date_range(self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            ReportPeriod::ThisWeek => (get_week_start(today), today),
+            ReportPeriod::LastWeek => {
+                let last_week_start = get_week_start(today) - Days::new(7);
+                (last_week_start, last_week_start + Days::new(6))
+            }
+            ReportPeriod::ThisMonth => (get_month_range(today).0, today),
+            ReportPeriod::LastMonth => {
+                get_month_range(today.checked_sub_months(Months::new(1)).unwrap_or(today))
+            }
+        }
+    }
+}
+
+/// チーム管理用コマンド
+#[poise::command(slash_command, guild_only, subcommands("report"), check = "is_admin")]
+pub async fn team(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// 指定ロールのメンバー全員の勤務時間を集計します
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn report(
+    ctx: Context<'_>,
+    #[description = "集計対象のロール"] role: serenity::Role,
+    #[description = "集計期間"] period: ReportPeriod,
+    #[description = "CSVファイルで出力する"] csv: Option<bool>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let pool = &ctx.data().pool;
+
+    // メンバー情報の取得に時間がかかるため先に応答を保留する
+    ctx.defer_ephemeral().await?;
+
+    let (start_date, end_date) = period.date_range(get_current_date_jst());
+
+    let members = match collect_role_members(ctx, guild_id, role.id).await {
+        Ok(members) => members,
+        Err(e) => {
+            return AppError::internal("メンバー情報の取得に失敗しました", e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    let mut member_sessions: Vec<(User, Vec<WorkSession>)> = Vec::new();
+    for user in members {
+        match queries::get_work_sessions_by_date_range(pool, user.id, start_date, end_date).await {
+            Ok(sessions) => member_sessions.push((user, sessions)),
+            Err(e) => {
+                return AppError::internal("勤務記録の取得に失敗しました", e)
+                    .reply(ctx)
+                    .await;
+            }
+        }
+    }
+
+    let date_range = format!(
+        "{} ～ {}",
+        start_date.format("%Y年%m月%d日"),
+        end_date.format("%Y年%m月%d日")
+    );
+    let embed = create_team_report_embed(&role.name, &date_range, &member_sessions);
+    let mut reply = poise::CreateReply::default().embed(embed).ephemeral(true);
+
+    if csv.unwrap_or(false) {
+        let signed_csv = sign_export(
+            &format_team_report_csv(&member_sessions),
+            ctx.data().config.export_signing_key.as_bytes(),
+        );
+        let filename = format!(
+            "kintai_team_{}_{}_{}.csv",
+            role.name,
+            start_date.format("%Y-%m-%d"),
+            end_date.format("%Y-%m-%d")
+        );
+        reply = reply.attachment(serenity::CreateAttachment::bytes(
+            signed_csv.into_bytes(),
+            filename,
+        ));
+    }
+
+    ctx.send(reply).await?;
+
+    Ok(())
+}
+
+/// このサーバーで記録したユーザーのうち、指定ロールを持つギルドメンバーを取得
+pub async fn collect_role_members(
+    ctx: Context<'_>,
+    guild_id: serenity::GuildId,
+    role_id: serenity::RoleId,
+) -> Result<Vec<User>, Error> {
+    let mut members = Vec::new();
+
+    for user in queries::get_guild_users(&ctx.data().pool, &guild_id.to_string()).await? {
+        let Ok(discord_id) = user.discord_id.parse::<u64>() else {
+            continue;
+        };
+        // ギルドを抜けたユーザーは取得に失敗するため対象外とする
+        if let Ok(member) = guild_id
+            .member(ctx.http(), serenity::UserId::new(discord_id))
+            .await
+            && member.roles.contains(&role_id)
+        {
+            members.push(user);
+        }
+    }
+
+    Ok(members)
+}
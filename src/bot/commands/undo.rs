@@ -0,0 +1,78 @@
+use crate::bot::{AppError, Context, Error, preferences};
+use crate::database::models::AuditSource;
+use crate::database::queries;
+use crate::t;
+use crate::utils::format::{create_error_embed, create_success_embed, format_audit_logs};
+use crate::utils::session_manager::SessionManager;
+use crate::utils::time::get_date_from_utc_timestamp;
+use std::collections::BTreeSet;
+
+/// 直前の記録の追加・修正・削除を取り消します
+#[poise::command(slash_command)]
+pub async fn undo(ctx: Context<'_>) -> Result<(), Error> {
+    let user_id = ctx.author().id.to_string();
+    let username = ctx.author().name.clone();
+    let pool = &ctx.data().pool;
+    let prefs = preferences::load(ctx).await;
+    let lang = prefs.lang;
+
+    // Create or get user
+    let user = match queries::create_or_get_user(pool, &user_id, ctx.guild_id(), &username).await {
+        Ok(user) => user,
+        Err(e) => {
+            return AppError::internal(t!(lang, "error.user_lookup"), e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    let operation = match queries::get_last_undoable_operation(pool, user.id, &user_id).await {
+        Ok(operation) => operation,
+        Err(e) => {
+            return AppError::internal(t!(lang, "undo.history_error"), e)
+                .reply(ctx)
+                .await;
+        }
+    };
+
+    if operation.is_empty() {
+        let embed = create_error_embed(&t!(lang, "undo.nothing_title"), &t!(lang, "undo.nothing"));
+        ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+
+    let audit = AuditSource::new(ctx.author().id, AuditSource::UNDO).in_guild(ctx.guild_id());
+    if let Err(e) = queries::undo_operation(pool, &operation, &audit).await {
+        return AppError::internal(t!(lang, "undo.failed"), e)
+            .reply(ctx)
+            .await;
+    }
+
+    // 変更前後の日付のセッションを再計算
+    let affected_dates: BTreeSet<_> = operation
+        .iter()
+        .flat_map(|log| [log.old_timestamp, log.new_timestamp])
+        .flatten()
+        .map(get_date_from_utc_timestamp)
+        .collect();
+    let session_manager = SessionManager::new(pool.clone());
+    for date in affected_dates {
+        if let Err(e) = session_manager.trigger_recalculation(user.id, date).await {
+            tracing::error!("Failed to recalculate sessions: {}", e);
+        }
+    }
+
+    let embed = create_success_embed(
+        &t!(lang, "undo.title"),
+        &t!(
+            lang,
+            "undo.done",
+            changes = format_audit_logs(&operation, lang, prefs.clock)
+        ),
+    );
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
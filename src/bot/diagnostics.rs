@@ -0,0 +1,213 @@
+// Startup self-check: verifies the database, gateway intents and per-guild configuration
+// up front so misconfiguration is reported at boot instead of at first use
+
+use crate::bot::{Data, preflight};
+use crate::database::{migrations, queries};
+use poise::serenity_prelude as serenity;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl CheckResult {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// 全ての診断を実行する
+pub async fn run_diagnostics(
+    http: &serenity::Http,
+    data: &Data,
+    guild_ids: &[serenity::GuildId],
+) -> Vec<CheckResult> {
+    let mut results = vec![
+        check_database_writable(data).await,
+        check_migrations(data).await,
+        check_message_content_intent(http).await,
+    ];
+    results.extend(check_digest_channels(http, data).await);
+    if let Some(result) = check_admin_role(http, data, guild_ids).await {
+        results.push(result);
+    }
+    results
+}
+
+/// 診断結果をログに出力する
+pub fn log_report(results: &[CheckResult]) {
+    for result in results {
+        match result.status {
+            CheckStatus::Ok => tracing::info!("[diagnostics] {}: {}", result.name, result.detail),
+            CheckStatus::Warning => {
+                tracing::warn!("[diagnostics] {}: {}", result.name, result.detail)
+            }
+            CheckStatus::Error => {
+                tracing::error!("[diagnostics] {}: {}", result.name, result.detail)
+            }
+        }
+    }
+}
+
+/// 診断結果をメッセージ用に整形する
+pub fn format_report(results: &[CheckResult]) -> String {
+    results
+        .iter()
+        .map(|result| {
+            let icon = match result.status {
+                CheckStatus::Ok => "✅",
+                CheckStatus::Warning => "⚠️",
+                CheckStatus::Error => "❌",
+            };
+            format!("{} **{}**: {}", icon, result.name, result.detail)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+async fn check_database_writable(data: &Data) -> CheckResult {
+    const NAME: &str = "データベース書き込み";
+    match queries::check_database_writable(&data.pool).await {
+        Ok(()) => CheckResult::new(NAME, CheckStatus::Ok, "書き込み可能"),
+        Err(e) => CheckResult::new(
+            NAME,
+            CheckStatus::Error,
+            format!("書き込みに失敗しました: {}", e),
+        ),
+    }
+}
+
+async fn check_migrations(data: &Data) -> CheckResult {
+    const NAME: &str = "マイグレーション";
+    match migrations::schema_status(&data.pool).await {
+        Ok((Some(current), expected)) if current == expected => CheckResult::new(
+            NAME,
+            CheckStatus::Ok,
+            format!("スキーマバージョン {}", current),
+        ),
+        Ok((current, expected)) => CheckResult::new(
+            NAME,
+            CheckStatus::Error,
+            format!(
+                "スキーマバージョン {} が想定 ({}) と一致しません",
+                current.map_or("未適用".to_string(), |v| v.to_string()),
+                expected
+            ),
+        ),
+        Err(e) => CheckResult::new(
+            NAME,
+            CheckStatus::Error,
+            format!("スキーマバージョンを取得できません: {}", e),
+        ),
+    }
+}
+
+async fn check_message_content_intent(http: &serenity::Http) -> CheckResult {
+    const NAME: &str = "Message Content Intent";
+    match preflight::message_content_intent_enabled(http).await {
+        Ok(true) => CheckResult::new(NAME, CheckStatus::Ok, "有効"),
+        Ok(false) => CheckResult::new(
+            NAME,
+            CheckStatus::Warning,
+            "Developer Portal で有効化されていません（`/admin backfill` が本文を読めません）",
+        ),
+        Err(e) => CheckResult::new(
+            NAME,
+            CheckStatus::Warning,
+            format!("アプリケーション情報を取得できません: {}", e),
+        ),
+    }
+}
+
+/// 週次ダイジェストの投稿先チャンネルが存在するか確認する
+async fn check_digest_channels(http: &serenity::Http, data: &Data) -> Vec<CheckResult> {
+    const NAME: &str = "週次ダイジェストチャンネル";
+    let settings = match queries::get_guilds_with_weekly_digest(&data.pool).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            return vec![CheckResult::new(
+                NAME,
+                CheckStatus::Error,
+                format!("設定を取得できません: {}", e),
+            )];
+        }
+    };
+
+    let mut results = Vec::new();
+    for setting in settings {
+        let Some(channel_id) = setting
+            .weekly_digest_channel_id
+            .as_deref()
+            .and_then(|id| serenity::ChannelId::from_str(id).ok())
+        else {
+            continue;
+        };
+        if http.get_channel(channel_id).await.is_err() {
+            results.push(CheckResult::new(
+                NAME,
+                CheckStatus::Error,
+                format!(
+                    "ギルド {} の投稿先 <#{}> にアクセスできません（`/admin digest` で再設定してください）",
+                    setting.guild_id, channel_id
+                ),
+            ));
+        }
+    }
+
+    if results.is_empty() {
+        results.push(CheckResult::new(NAME, CheckStatus::Ok, "問題なし"));
+    }
+    results
+}
+
+/// ADMIN_ROLE_ID が参加中のいずれかのギルドに存在するか確認する
+async fn check_admin_role(
+    http: &serenity::Http,
+    data: &Data,
+    guild_ids: &[serenity::GuildId],
+) -> Option<CheckResult> {
+    const NAME: &str = "管理者ロール";
+    let role_id = data.config.admin_role_id.as_deref()?;
+    let Ok(role_id) = serenity::RoleId::from_str(role_id) else {
+        return Some(CheckResult::new(
+            NAME,
+            CheckStatus::Error,
+            format!("ADMIN_ROLE_ID ({}) はロールIDではありません", role_id),
+        ));
+    };
+
+    for guild_id in guild_ids {
+        if let Ok(roles) = http.get_guild_roles(*guild_id).await
+            && roles.iter().any(|role| role.id == role_id)
+        {
+            return Some(CheckResult::new(
+                NAME,
+                CheckStatus::Ok,
+                format!("ギルド {} に存在します", guild_id),
+            ));
+        }
+    }
+
+    Some(CheckResult::new(
+        NAME,
+        CheckStatus::Error,
+        format!(
+            "ADMIN_ROLE_ID ({}) のロールが見つかりません（管理者コマンドを誰も使用できません）",
+            role_id
+        ),
+    ))
+}
@@ -0,0 +1,38 @@
+// The guild's record edit policy from `/admin edit_policy`: how many days back records can be
+// added or changed, whether dates before today are reserved for admins, and whether members'
+// deletions go through the approval channel. `RecordValidator` enforces the first two; the
+// `/status` delete confirmations check the last one.
+
+use crate::bot::Context;
+use crate::bot::checks::member_is_admin;
+use crate::database::{DbPool, queries};
+use crate::utils::record_validator::EditPolicy;
+use poise::serenity_prelude as serenity;
+
+/// ギルドの編集ルールを操作する人に合わせて読み込む（DMや取得に失敗した場合は既定値）
+pub async fn load(
+    pool: &DbPool,
+    guild_id: Option<serenity::GuildId>,
+    is_admin: bool,
+) -> EditPolicy {
+    let Some(guild_id) = guild_id else {
+        return EditPolicy::default();
+    };
+    let policy = match queries::get_guild_settings(pool, &guild_id.to_string()).await {
+        Ok(settings) => settings.as_ref().map(EditPolicy::from).unwrap_or_default(),
+        Err(e) => {
+            tracing::warn!("Failed to load edit policy: {}", e);
+            EditPolicy::default()
+        }
+    };
+    policy.for_actor(is_admin)
+}
+
+/// コマンドの実行者に適用する編集ルール
+pub async fn for_command(ctx: Context<'_>) -> EditPolicy {
+    let is_admin = match ctx.author_member().await {
+        Some(member) => member_is_admin(&member, &ctx.data().config),
+        None => false,
+    };
+    load(&ctx.data().pool, ctx.guild_id(), is_admin).await
+}
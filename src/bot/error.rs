@@ -0,0 +1,223 @@
+// The error type of every command, interaction handler and event handler (`bot::Error`).
+// `User` errors are mistakes the user can fix and are shown as they are; `Internal` errors
+// show only their Japanese summary and keep the cause for the log. Any error that converts
+// into `anyhow::Error` can be returned with `?` and becomes an internal error. Errors and
+// panics that escape a command end up in `on_error`, the framework's error handler.
+
+use crate::bot::{Context, Data, preferences};
+use crate::t;
+use crate::utils::format::create_error_embed;
+use crate::utils::i18n::Lang;
+use poise::serenity_prelude as serenity;
+use std::fmt;
+
+/// 内部エラーの原因を利用者に見せないときの既定の説明
+const INTERNAL_ERROR_MESSAGE: &str =
+    "処理中にエラーが発生しました。しばらくしてからもう一度お試しください";
+
+pub enum AppError {
+    /// 利用者の操作で解決できるエラー（メッセージをそのまま表示する）
+    User(String),
+    /// 内部のエラー（利用者には `message` だけを表示し、`cause` はログに残す）
+    Internal {
+        message: String,
+        cause: anyhow::Error,
+    },
+}
+
+impl AppError {
+    pub fn user(message: impl Into<String>) -> Self {
+        AppError::User(message.into())
+    }
+
+    pub fn internal(message: impl Into<String>, cause: impl Into<anyhow::Error>) -> Self {
+        AppError::Internal {
+            message: message.into(),
+            cause: cause.into(),
+        }
+    }
+
+    /// 利用者に表示する説明
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::User(message) => message,
+            AppError::Internal { message, .. } => message,
+        }
+    }
+
+    /// 内部エラーの原因をログに残す（利用者のエラーは残さない）
+    pub fn log(&self) {
+        if let AppError::Internal { message, cause } = self {
+            tracing::error!("{}: {:?}", message, cause);
+        }
+    }
+
+    /// 原因をログに残してから、利用者に表示する説明を取り出す
+    pub fn into_message(self) -> String {
+        self.log();
+        match self {
+            AppError::User(message) => message,
+            AppError::Internal { message, .. } => message,
+        }
+    }
+
+    pub fn embed(&self, lang: Lang) -> serenity::CreateEmbed {
+        create_error_embed(&t!(lang, "error.title"), self.message())
+    }
+
+    /// コマンドの実行者だけに見えるエラーの埋め込みで返信する（タイトルは実行者の言語）
+    pub async fn reply(self, ctx: Context<'_>) -> Result<(), AppError> {
+        self.log();
+        let lang = preferences::load(ctx).await.lang;
+        ctx.send(
+            poise::CreateReply::default()
+                .embed(self.embed(lang))
+                .ephemeral(true),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// コマンドから返されたエラーやパニックを処理する（`FrameworkOptions::on_error`）
+pub async fn on_error(error: poise::FrameworkError<'_, Data, AppError>) {
+    match error {
+        poise::FrameworkError::Command { error, ctx, .. }
+        | poise::FrameworkError::CommandCheckFailed {
+            error: Some(error),
+            ctx,
+            ..
+        } => report(ctx, error).await,
+        poise::FrameworkError::CommandPanic { payload, ctx, .. } => {
+            let payload = payload.unwrap_or_else(|| "(no payload)".to_string());
+            let error = AppError::from(anyhow::anyhow!("command panicked: {}", payload));
+            report(ctx, error).await;
+        }
+        poise::FrameworkError::ArgumentParse { error, ctx, .. } => {
+            tracing::warn!(
+                "Failed to parse arguments of {}: {}",
+                describe_invocation(ctx),
+                error
+            );
+            let error =
+                AppError::user("コマンドの入力を読み取れませんでした。入力内容を確認してください");
+            report(ctx, error).await;
+        }
+        other => {
+            if let Err(e) = poise::builtins::on_error(other).await {
+                tracing::error!("Failed to handle framework error: {:?}", e);
+            }
+        }
+    }
+}
+
+/// エラーを実行したコマンドと一緒にログに残し、実行者だけに見える埋め込みで伝える
+async fn report(ctx: Context<'_>, error: AppError) {
+    match &error {
+        AppError::User(message) => {
+            tracing::debug!("{} was rejected: {}", describe_invocation(ctx), message);
+        }
+        AppError::Internal { message, cause } => {
+            tracing::error!(
+                "{} failed: {}: {:?}",
+                describe_invocation(ctx),
+                message,
+                cause
+            );
+        }
+    }
+    let lang = preferences::load(ctx).await.lang;
+    let reply = poise::CreateReply::default()
+        .embed(error.embed(lang))
+        .ephemeral(true);
+    if let Err(e) = ctx.send(reply).await {
+        tracing::warn!(
+            "Failed to send error message for {}: {:?}",
+            describe_invocation(ctx),
+            e
+        );
+    }
+}
+
+/// ログ用のコマンド・実行者・場所の説明
+fn describe_invocation(ctx: Context<'_>) -> String {
+    let guild = ctx
+        .guild_id()
+        .map_or_else(|| "DM".to_string(), |id| format!("guild {}", id));
+    format!(
+        "`{}` by user {} in {} (channel {})",
+        ctx.invocation_string(),
+        ctx.author().id,
+        guild,
+        ctx.channel_id()
+    )
+}
+
+impl<E> From<E> for AppError
+where
+    E: Into<anyhow::Error>,
+{
+    fn from(cause: E) -> Self {
+        AppError::internal(INTERNAL_ERROR_MESSAGE, cause)
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl fmt::Debug for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::User(message) => f.debug_tuple("User").field(message).finish(),
+            AppError::Internal { message, cause } => f
+                .debug_struct("Internal")
+                .field("message", message)
+                .field("cause", cause)
+                .finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::interactions::responder::{InteractionResponder, MockResponder};
+
+    #[tokio::test]
+    async fn test_respond_error_hides_internal_cause() {
+        let responder = MockResponder::default();
+
+        responder
+            .respond_error(AppError::user("無効な操作です"))
+            .await
+            .unwrap();
+        responder
+            .respond_error(AppError::internal(
+                "記録の取得に失敗しました",
+                anyhow::anyhow!("database is locked"),
+            ))
+            .await
+            .unwrap();
+
+        let responses = responder.responses();
+        assert_eq!(
+            responses[0]["data"]["content"],
+            "❌ **エラー**: 無効な操作です"
+        );
+        assert_eq!(responses[0]["data"]["flags"], 64);
+        assert_eq!(
+            responses[1]["data"]["content"],
+            "❌ **エラー**: 記録の取得に失敗しました"
+        );
+    }
+
+    #[test]
+    fn test_question_mark_becomes_internal_error() {
+        let error = AppError::from(anyhow::anyhow!("connection refused"));
+        assert_eq!(error.message(), INTERNAL_ERROR_MESSAGE);
+        assert!(format!("{:?}", error).contains("connection refused"));
+    }
+}
@@ -0,0 +1,97 @@
+// Guild-level data reset for servers that restart their attendance tracking. `/admin
+// reset-guild` always attaches a full JSON export first; the wipe only happens after an
+// admin types the server name into the confirmation modal (see
+// `interactions::reset_buttons`), and only for the users included in that export.
+
+use crate::database::models::User;
+use crate::database::{DbPool, queries};
+use crate::utils::export::{GuildDataExport, PersonalDataExport, format_guild_data_json};
+use anyhow::Result;
+use poise::serenity_prelude as serenity;
+
+/// このサーバーで記録したユーザーのうち、現在もメンバーであるユーザー
+pub async fn collect_members(
+    http: &serenity::Http,
+    pool: &DbPool,
+    guild_id: serenity::GuildId,
+) -> Result<Vec<User>> {
+    let mut members = Vec::new();
+
+    for user in queries::get_guild_users(pool, &guild_id.to_string()).await? {
+        let Ok(discord_id) = user.discord_id.parse::<u64>() else {
+            continue;
+        };
+        // サーバーを抜けたユーザーは取得に失敗するため対象外とする
+        if guild_id
+            .member(http, serenity::UserId::new(discord_id))
+            .await
+            .is_ok()
+        {
+            members.push(user);
+        }
+    }
+
+    Ok(members)
+}
+
+/// 削除対象のデータ一式をJSONで出力する
+pub async fn export_json(
+    pool: &DbPool,
+    guild_id: serenity::GuildId,
+    members: &[User],
+) -> Result<String> {
+    let mut data = Vec::new();
+    for user in members {
+        data.push((
+            user,
+            queries::get_all_attendance_records(pool, user.id).await?,
+            queries::get_all_work_sessions(pool, user.id).await?,
+            queries::get_all_time_adjustments(pool, user.id).await?,
+        ));
+    }
+
+    let exported_at = chrono::Utc::now();
+    let guild_id = guild_id.to_string();
+    format_guild_data_json(&GuildDataExport {
+        exported_at,
+        guild_id: &guild_id,
+        members: data
+            .iter()
+            .map(
+                |(user, records, sessions, adjustments)| PersonalDataExport {
+                    exported_at,
+                    user,
+                    attendance_records: records,
+                    work_sessions: sessions,
+                    time_adjustments: adjustments,
+                },
+            )
+            .collect(),
+    })
+}
+
+/// 確認モーダルを開くボタン（custom_id: "guild_reset:user_id:state_id"）
+pub fn reset_button(user_id: serenity::UserId, state_id: i64) -> serenity::CreateActionRow {
+    serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(format!("guild_reset:{}:{}", user_id, state_id))
+            .label("サーバー名を入力して削除")
+            .style(serenity::ButtonStyle::Danger),
+    ])
+}
+
+/// サーバー名を入力させる確認モーダル（custom_id: "guild_reset_modal:user_id:state_id"）
+pub fn confirmation_modal(user_id: serenity::UserId, state_id: i64) -> serenity::CreateModal {
+    serenity::CreateModal::new(
+        format!("guild_reset_modal:{}:{}", user_id, state_id),
+        "サーバーのデータを削除",
+    )
+    .components(vec![serenity::CreateActionRow::InputText(
+        serenity::CreateInputText::new(
+            serenity::InputTextStyle::Short,
+            "確認のためサーバー名を入力してください",
+            "guild_name",
+        )
+        .required(true)
+        .max_length(100),
+    )])
+}
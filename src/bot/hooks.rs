@@ -0,0 +1,94 @@
+use crate::bot::Context;
+use crate::bot::interactions::tutorial_buttons::tutorial_message;
+use crate::database::queries;
+use poise::serenity_prelude as serenity;
+
+/// コマンド実行前フック（権限チェック通過後に呼ばれる）
+pub async fn pre_command(ctx: Context<'_>) {
+    let name = &ctx.command().qualified_name;
+    if name.starts_with("admin")
+        || name.starts_with("close")
+        || name.starts_with("registration")
+        || name.starts_with("kintai config")
+    {
+        record_admin_command(ctx).await;
+    }
+}
+
+/// コマンド実行後フック
+pub async fn post_command(ctx: Context<'_>) {
+    offer_tutorial(ctx).await;
+}
+
+/// 初めてコマンドを使ったユーザーにチュートリアルを表示する（1回のみ）
+async fn offer_tutorial(ctx: Context<'_>) {
+    let pool = &ctx.data().pool;
+    let user_id = ctx.author().id.to_string();
+
+    let first_time =
+        match queries::create_or_get_user(pool, &user_id, ctx.guild_id(), &ctx.author().name).await
+        {
+            Ok(user) => queries::mark_tutorial_completed(pool, user.id).await,
+            Err(e) => Err(e),
+        };
+    match first_time {
+        Ok(true) => {
+            let (embed, components) = tutorial_message(&user_id, 0);
+            if let Err(e) = ctx
+                .send(
+                    poise::CreateReply::default()
+                        .embed(embed)
+                        .components(components)
+                        .ephemeral(true),
+                )
+                .await
+            {
+                tracing::warn!("Failed to send tutorial: {:?}", e);
+            }
+        }
+        Ok(false) => {}
+        Err(e) => tracing::error!("Failed to update tutorial status: {:?}", e),
+    }
+}
+
+/// 管理者コマンドの実行者・コマンド・引数を記録
+async fn record_admin_command(ctx: Context<'_>) {
+    let arguments = match ctx {
+        poise::Context::Application(app_ctx) => format_arguments(app_ctx.args),
+        poise::Context::Prefix(prefix_ctx) => prefix_ctx.args.to_string(),
+    };
+    let guild_id = ctx.guild_id().map(|id| id.to_string());
+
+    if let Err(e) = queries::create_command_audit_log(
+        &ctx.data().pool,
+        guild_id.as_deref(),
+        &ctx.author().id.to_string(),
+        &ctx.author().name,
+        &format!("/{}", ctx.command().qualified_name),
+        &arguments,
+    )
+    .await
+    {
+        tracing::error!("Failed to record admin command execution: {:?}", e);
+    }
+}
+
+fn format_arguments(args: &[serenity::ResolvedOption<'_>]) -> String {
+    args.iter()
+        .map(|option| {
+            let value = match &option.value {
+                serenity::ResolvedValue::Boolean(value) => value.to_string(),
+                serenity::ResolvedValue::Integer(value) => value.to_string(),
+                serenity::ResolvedValue::Number(value) => value.to_string(),
+                serenity::ResolvedValue::String(value) => value.to_string(),
+                serenity::ResolvedValue::Channel(channel) => format!("<#{}>", channel.id),
+                serenity::ResolvedValue::User(user, _) => format!("<@{}>", user.id),
+                serenity::ResolvedValue::Role(role) => format!("<@&{}>", role.id),
+                serenity::ResolvedValue::Attachment(attachment) => attachment.filename.clone(),
+                other => format!("{:?}", other),
+            };
+            format!("{}={}", option.name, value)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
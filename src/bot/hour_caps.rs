@@ -0,0 +1,83 @@
+// Contracted monthly hour caps set with `/admin hour_cap`. After each `/end` the month's
+// actual and projected totals are compared with the user's cap: the user sees a warning in
+// the response, and when the cap has a notification channel, admins are told once per
+// level (approaching / exceeded) per month.
+
+use crate::database::models::{HourCap, User};
+use crate::database::{DbPool, queries};
+use crate::utils::format::{create_info_embed, format_cap_warning};
+use crate::utils::stats::{CapLevel, MonthForecast};
+use crate::utils::time::{get_current_date_jst, get_month_range};
+use anyhow::Result;
+use poise::serenity_prelude as serenity;
+
+/// ユーザーの月間上限（DMでの操作や取得に失敗した場合は `None`）
+pub async fn get_cap(
+    pool: &DbPool,
+    guild_id: Option<serenity::GuildId>,
+    user_id: i64,
+) -> Option<HourCap> {
+    let guild_id = guild_id?;
+    queries::get_hour_cap(pool, user_id, &guild_id.to_string())
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to get hour cap for user {}: {}", user_id, e);
+            None
+        })
+}
+
+/// 今月の実績と見込みを上限と比べ、上限に近づいていれば警告文を返す
+pub async fn check_after_clock_out(
+    http: &serenity::Http,
+    pool: &DbPool,
+    guild_id: Option<serenity::GuildId>,
+    user: &User,
+) -> Option<String> {
+    let cap = get_cap(pool, guild_id, user.id).await?;
+    match check(http, pool, &cap, user).await {
+        Ok(warning) => warning,
+        Err(e) => {
+            tracing::warn!("Failed to check hour cap for user {}: {}", user.id, e);
+            None
+        }
+    }
+}
+
+async fn check(
+    http: &serenity::Http,
+    pool: &DbPool,
+    cap: &HourCap,
+    user: &User,
+) -> Result<Option<String>> {
+    let today = get_current_date_jst();
+    let (start_of_month, _) = get_month_range(today);
+    let sessions =
+        queries::get_work_sessions_by_date_range(pool, user.id, start_of_month, today).await?;
+    let forecast = MonthForecast::calculate(&sessions, today, cap.monthly_minutes);
+    let level = CapLevel::evaluate(&forecast);
+    let Some(warning) = format_cap_warning(level, &forecast) else {
+        return Ok(None);
+    };
+
+    // 管理者への通知は同じ月の同じ段階では1回だけ
+    let month = today.format("%Y-%m").to_string();
+    let already_warned =
+        cap.warned_month.as_deref() == Some(month.as_str()) && cap.warned_level >= level as i32;
+    if let Some(channel_id) = cap
+        .notify_channel_id
+        .as_deref()
+        .and_then(|id| id.parse::<u64>().ok())
+        .filter(|_| !already_warned)
+    {
+        let embed = create_info_embed(
+            "月間上限の通知",
+            &format!("<@{}>\n{}", user.discord_id, warning),
+        );
+        serenity::ChannelId::new(channel_id)
+            .send_message(http, serenity::CreateMessage::new().embed(embed))
+            .await?;
+        queries::set_hour_cap_warning(pool, user.id, &cap.guild_id, &month, level as i32).await?;
+    }
+
+    Ok(Some(warning))
+}
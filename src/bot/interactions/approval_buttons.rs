@@ -0,0 +1,162 @@
+use crate::bot::approvals;
+use crate::bot::checks::member_is_admin;
+use crate::bot::edit_policy;
+use crate::bot::interactions::responder::{ComponentResponder, InteractionResponder};
+use crate::bot::{AppError, Data, Error, punch_lock};
+use crate::database::queries;
+use crate::utils::format::{create_approval_request_embed, create_info_embed};
+use crate::utils::record_validator::RecordValidator;
+use crate::utils::time::get_date_from_utc_timestamp;
+use poise::serenity_prelude as serenity;
+
+/// 承認依頼の承認・却下ボタン（custom_id: "approval_approve:id" / "approval_reject:id"）
+pub async fn handle_review(
+    ctx: &serenity::Context,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+    approve: bool,
+) -> Result<(), Error> {
+    let responder = ComponentResponder::new(ctx, interaction);
+    let is_admin = interaction
+        .member
+        .as_ref()
+        .is_some_and(|member| member_is_admin(member, &data.config));
+    if !is_admin {
+        return responder
+            .respond_error(AppError::user("承認・却下は管理者のみ行えます"))
+            .await;
+    }
+
+    let Some(change_id) = interaction
+        .data
+        .custom_id
+        .split(':')
+        .nth(1)
+        .and_then(|id| id.parse::<i64>().ok())
+    else {
+        return responder
+            .respond_error(AppError::user("無効な承認依頼です"))
+            .await;
+    };
+
+    let pool = &data.pool;
+    let change = match queries::get_pending_change(pool, change_id).await {
+        Ok(Some(change)) if change.status == "pending" => change,
+        Ok(_) => {
+            return responder
+                .respond_error(AppError::user("この承認依頼は既に処理されています"))
+                .await;
+        }
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("承認依頼の取得に失敗しました", e))
+                .await;
+        }
+    };
+
+    let _punch_guard = punch_lock::lock(change.user_id).await;
+    // 申請後に記録が変わっている可能性があるため、承認時点の記録で検証し直す（削除は不要）
+    if approve && change.action != "delete" {
+        let date = get_date_from_utc_timestamp(change.new_timestamp);
+        let records = match queries::get_records_by_date(pool, change.user_id, date).await {
+            Ok(records) => records,
+            Err(e) => {
+                return responder
+                    .respond_error(AppError::internal("記録の取得に失敗しました", e))
+                    .await;
+            }
+        };
+        if let Err(e) = RecordValidator::validate_new_record(
+            &records,
+            change.record_type,
+            change.new_timestamp,
+            date,
+            change.record_id,
+            &edit_policy::load(pool, interaction.guild_id, true).await,
+        ) {
+            return responder
+                .respond_error(AppError::user(format!(
+                    "現在の記録と矛盾するため承認できません: {}",
+                    e
+                )))
+                .await;
+        }
+    }
+
+    let reviewer = interaction.user.id.to_string();
+    let status = if approve { "approved" } else { "rejected" };
+    match queries::resolve_pending_change(pool, change_id, status, &reviewer).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return responder
+                .respond_error(AppError::user("この承認依頼は既に処理されています"))
+                .await;
+        }
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("承認依頼の更新に失敗しました", e))
+                .await;
+        }
+    }
+
+    if approve && let Err(e) = approvals::apply_change(pool, &change).await {
+        if let Err(e) = queries::reopen_pending_change(pool, change_id).await {
+            tracing::error!("Failed to reopen pending change {}: {}", change_id, e);
+        }
+        return responder
+            .respond_error(AppError::internal("勤務記録への反映に失敗しました", e))
+            .await;
+    }
+
+    let resolved = queries::get_pending_change(pool, change_id)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(change);
+    let requester = resolved.requested_by.clone();
+    interaction
+        .create_response(
+            &ctx.http,
+            serenity::CreateInteractionResponse::UpdateMessage(
+                serenity::CreateInteractionResponseMessage::new()
+                    .embed(create_approval_request_embed(&resolved, &requester))
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+
+    notify_requester(ctx, &requester, approve).await;
+    Ok(())
+}
+
+/// 依頼者に結果をDMで伝える（失敗しても処理は続ける）
+async fn notify_requester(ctx: &serenity::Context, requester: &str, approved: bool) {
+    let Ok(discord_id) = requester.parse::<u64>() else {
+        return;
+    };
+    let embed = if approved {
+        create_info_embed(
+            "記録修正が承認されました",
+            "申請した記録の修正が勤務記録に反映されました。`/status` で確認できます",
+        )
+    } else {
+        create_info_embed(
+            "記録修正が却下されました",
+            "申請した記録の修正は反映されませんでした。詳しくは管理者に確認してください",
+        )
+    };
+
+    let result = match serenity::UserId::new(discord_id)
+        .create_dm_channel(&ctx.http)
+        .await
+    {
+        Ok(channel) => channel
+            .send_message(&ctx.http, serenity::CreateMessage::new().embed(embed))
+            .await
+            .map(|_| ()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        tracing::warn!("Failed to notify approval result to {}: {:?}", requester, e);
+    }
+}
@@ -0,0 +1,86 @@
+// The "出勤する" button on the morning check-in reminder DM (`/schedule checkin`). Like the long
+// session reminder, the custom id carries the discord id for the owner check and `users.id`
+// to find the user, because a DM can't tell which guild the user belongs to.
+
+use crate::bot::interactions::responder::InteractionResponder;
+use crate::bot::shortcuts::record_attendance;
+use crate::bot::{AppError, Data, Error, edit_policy};
+use crate::database::models::{AuditSource, RecordType, User};
+use crate::database::queries;
+use crate::utils::format::create_success_embed;
+use crate::utils::time::format_time_jst;
+use chrono::Utc;
+use poise::serenity_prelude as serenity;
+
+/// 今の時刻で勤務開始を記録するボタン（custom_id: "checkin_start:discord_id:user_id"）
+pub const CHECKIN_START: &str = "checkin_start";
+
+/// 出勤リマインダーのDMに付けるボタン
+pub fn checkin_buttons(user: &User) -> Vec<serenity::CreateActionRow> {
+    vec![serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(format!("{}:{}:{}", CHECKIN_START, user.discord_id, user.id))
+            .label("出勤する")
+            .emoji('🟢')
+            .style(serenity::ButtonStyle::Success),
+    ])]
+}
+
+/// 「出勤する」: 今の時刻で勤務開始を記録する
+pub async fn handle_checkin_start(
+    responder: &impl InteractionResponder,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let pool = &data.pool;
+    let user = match interaction
+        .data
+        .custom_id
+        .split(':')
+        .nth(2)
+        .and_then(|value| value.parse::<i64>().ok())
+    {
+        Some(user_id) => queries::get_user_by_id(pool, user_id).await.ok(),
+        None => None,
+    };
+    let Some(user) = user.filter(|user| user.discord_id == interaction.user.id.to_string()) else {
+        return responder
+            .respond_error(AppError::user("このリマインダーは操作できません"))
+            .await;
+    };
+
+    // ユーザーのサーバー（DMで登録したユーザーは空文字）
+    let guild_id = user
+        .guild_id
+        .parse::<u64>()
+        .ok()
+        .map(serenity::GuildId::new);
+    let timestamp = Utc::now();
+    let audit = AuditSource::new(interaction.user.id, "checkin_reminder").in_guild(guild_id);
+    let policy = edit_policy::load(pool, guild_id, false).await;
+
+    if let Err(error) = record_attendance(
+        pool,
+        &user,
+        RecordType::Start,
+        timestamp,
+        guild_id,
+        &audit,
+        &policy,
+    )
+    .await
+    {
+        return responder.respond_error(error).await;
+    }
+
+    let embed = create_success_embed(
+        "勤務開始",
+        &format!("開始時刻: {}", format_time_jst(timestamp)),
+    );
+    responder
+        .respond(serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new()
+                .embed(embed)
+                .components(vec![]),
+        ))
+        .await
+}
@@ -0,0 +1,143 @@
+// State for multi-step interactions that must survive a restart. Select menus carry what
+// they need in their custom_id; confirmation buttons only carry the id of a row in
+// `pending_interactions`, so a click after a redeploy (or after midnight) still acts on
+// exactly what the user was shown.
+
+use crate::database::{DbPool, queries};
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, Utc};
+
+/// 途中状態の有効期限（時間）
+pub const FLOW_STATE_TTL_HOURS: i64 = 24;
+
+/// 操作の途中状態
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlowState {
+    /// 記録削除の確認待ち（`record_id` が None なら `date` のすべての記録）
+    DeleteRecords {
+        date: NaiveDate,
+        record_id: Option<i64>,
+    },
+    /// サーバーのデータ削除の確認待ち（`user_ids` はエクスポートに含めたユーザー）
+    ResetGuild { guild_id: u64, user_ids: Vec<i64> },
+}
+
+impl FlowState {
+    fn action(&self) -> &'static str {
+        match self {
+            FlowState::DeleteRecords { .. } => "delete_records",
+            FlowState::ResetGuild { .. } => "reset_guild",
+        }
+    }
+
+    fn payload(&self) -> String {
+        match self {
+            FlowState::DeleteRecords { date, record_id } => format!(
+                "{}:{}",
+                date.format("%Y-%m-%d"),
+                record_id.map_or("all".to_string(), |id| id.to_string())
+            ),
+            FlowState::ResetGuild { guild_id, user_ids } => format!(
+                "{}:{}",
+                guild_id,
+                user_ids
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        }
+    }
+
+    fn parse(action: &str, payload: &str) -> Option<Self> {
+        match action {
+            "delete_records" => {
+                let (date, target) = payload.split_once(':')?;
+                Some(FlowState::DeleteRecords {
+                    date: NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?,
+                    record_id: match target {
+                        "all" => None,
+                        id => Some(id.parse().ok()?),
+                    },
+                })
+            }
+            "reset_guild" => {
+                let (guild_id, user_ids) = payload.split_once(':')?;
+                Some(FlowState::ResetGuild {
+                    guild_id: guild_id.parse().ok()?,
+                    user_ids: user_ids
+                        .split(',')
+                        .filter(|id| !id.is_empty())
+                        .map(|id| id.parse().ok())
+                        .collect::<Option<_>>()?,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 途中状態を保存し、custom_id に載せるIDを返す
+pub async fn save(pool: &DbPool, discord_user_id: &str, state: FlowState) -> Result<i64> {
+    queries::create_pending_interaction(
+        pool,
+        discord_user_id,
+        state.action(),
+        &state.payload(),
+        expires_before(),
+    )
+    .await
+}
+
+/// 本人の期限内の途中状態を読み込み、使用済みとして削除する
+pub async fn take(pool: &DbPool, id: i64, discord_user_id: &str) -> Result<Option<FlowState>> {
+    let Some(pending) =
+        queries::get_pending_interaction(pool, id, discord_user_id, expires_before()).await?
+    else {
+        return Ok(None);
+    };
+    queries::delete_pending_interaction(pool, pending.id).await?;
+
+    Ok(FlowState::parse(&pending.action, &pending.payload))
+}
+
+fn expires_before() -> chrono::DateTime<Utc> {
+    Utc::now() - Duration::hours(FLOW_STATE_TTL_HOURS)
+}
+
+#[cfg(all(test, not(feature = "postgres")))]
+mod tests {
+    use super::*;
+    use crate::database::create_test_pool;
+
+    #[tokio::test]
+    async fn test_flow_state_survives_round_trip_once() {
+        let pool = create_test_pool().await;
+        let date = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
+        let single = FlowState::DeleteRecords {
+            date,
+            record_id: Some(42),
+        };
+        let all = FlowState::DeleteRecords {
+            date,
+            record_id: None,
+        };
+
+        let reset = FlowState::ResetGuild {
+            guild_id: 99,
+            user_ids: vec![1, 2],
+        };
+
+        let single_id = save(&pool, "1234", single.clone()).await.unwrap();
+        let all_id = save(&pool, "1234", all.clone()).await.unwrap();
+        let reset_id = save(&pool, "1234", reset.clone()).await.unwrap();
+
+        // 他のユーザーは使えない
+        assert_eq!(take(&pool, single_id, "5678").await.unwrap(), None);
+        assert_eq!(take(&pool, single_id, "1234").await.unwrap(), Some(single));
+        assert_eq!(take(&pool, all_id, "1234").await.unwrap(), Some(all));
+        assert_eq!(take(&pool, reset_id, "1234").await.unwrap(), Some(reset));
+        // 一度使った状態は残らない
+        assert_eq!(take(&pool, single_id, "1234").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,94 @@
+use crate::bot::interactions::responder::InteractionResponder;
+use crate::bot::{AppError, Data, Error};
+use crate::database::queries;
+use crate::utils::format::create_info_embed;
+use chrono::Utc;
+use poise::serenity_prelude as serenity;
+
+/// `/focus` の返信に付ける中断ボタン（custom_id: "focus_stop:discord_id:focus_id"）
+pub fn stop_button(discord_id: &str, focus_id: i64) -> Vec<serenity::CreateActionRow> {
+    vec![serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(format!("focus_stop:{}:{}", discord_id, focus_id))
+            .label("中断")
+            .emoji('⏹')
+            .style(serenity::ButtonStyle::Secondary),
+    ])]
+}
+
+/// 「中断」: 集中時間を今の時刻で終了する
+pub async fn handle_focus_stop(
+    responder: &impl InteractionResponder,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let pool = &data.pool;
+    let Some(focus_id) = interaction
+        .data
+        .custom_id
+        .split(':')
+        .nth(2)
+        .and_then(|id| id.parse::<i64>().ok())
+    else {
+        return responder
+            .respond_error(AppError::user("無効な操作です"))
+            .await;
+    };
+    let user = match queries::create_or_get_user(
+        pool,
+        &interaction.user.id.to_string(),
+        interaction.guild_id,
+        &interaction.user.name,
+    )
+    .await
+    {
+        Ok(user) => user,
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("ユーザー情報の取得に失敗しました", e))
+                .await;
+        }
+    };
+    let focus = match queries::get_running_focus_session(pool, user.id).await {
+        Ok(Some(focus)) if focus.id == focus_id => focus,
+        Ok(_) => {
+            return responder
+                .respond_error(AppError::user("この集中モードは終了しています"))
+                .await;
+        }
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("集中モードの取得に失敗しました", e))
+                .await;
+        }
+    };
+
+    let now = Utc::now();
+    match queries::finish_focus_session(pool, focus.id, now, false).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return responder
+                .respond_error(AppError::user("この集中モードは終了しています"))
+                .await;
+        }
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("集中モードの終了に失敗しました", e))
+                .await;
+        }
+    }
+
+    let embed = create_info_embed(
+        "🍅 集中モード",
+        &format!(
+            "集中モードを中断しました（{}分）",
+            (now - focus.started_at).num_minutes()
+        ),
+    );
+    responder
+        .respond(serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new()
+                .embed(embed)
+                .components(vec![]),
+        ))
+        .await
+}
@@ -0,0 +1,119 @@
+// Shared clock-in/out buttons posted by `/admin kiosk`. Unlike the status buttons, the custom
+// ids carry no owner: whoever presses a button gets the record, in the guild the message is in,
+// and the result is shown only to them.
+
+use crate::bot::interactions::responder::InteractionResponder;
+use crate::bot::shortcuts::record_attendance;
+use crate::bot::{AppError, Data, Error, edit_policy, hour_caps, preferences};
+use crate::database::models::{AuditSource, RecordType};
+use crate::database::queries;
+use crate::t;
+use crate::utils::format::{create_info_embed, create_success_embed};
+use crate::utils::time::format_duration;
+use poise::serenity_prelude as serenity;
+
+pub const KIOSK_START: &str = "kiosk_start";
+pub const KIOSK_END: &str = "kiosk_end";
+
+/// 出勤・退勤ボタン付きの常設メッセージ
+pub fn kiosk_message() -> (serenity::CreateEmbed, Vec<serenity::CreateActionRow>) {
+    let embed = create_info_embed(
+        "🕒 出退勤",
+        "ボタンを押すと、押した人の出勤・退勤を今の時刻で記録します（結果は本人にだけ表示されます）",
+    );
+    let buttons = vec![serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(KIOSK_START)
+            .label("出勤")
+            .emoji('🟢')
+            .style(serenity::ButtonStyle::Success),
+        serenity::CreateButton::new(KIOSK_END)
+            .label("退勤")
+            .emoji('🔴')
+            .style(serenity::ButtonStyle::Danger),
+    ])];
+    (embed, buttons)
+}
+
+/// 押したユーザーの開始・終了を記録する
+pub async fn handle_kiosk(
+    responder: &impl InteractionResponder,
+    http: &serenity::Http,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+    record_type: RecordType,
+) -> Result<(), Error> {
+    let pool = &data.pool;
+    let discord_id = interaction.user.id.to_string();
+    let prefs = preferences::resolve(
+        pool,
+        &discord_id,
+        interaction.guild_id,
+        Some(&interaction.locale),
+    )
+    .await;
+    let lang = prefs.lang;
+
+    let embed = match queries::create_or_get_user(
+        pool,
+        &discord_id,
+        interaction.guild_id,
+        &interaction.user.name,
+    )
+    .await
+    {
+        Ok(user) => {
+            let timestamp = chrono::Utc::now();
+            let audit =
+                AuditSource::new(interaction.user.id, "kiosk").in_guild(interaction.guild_id);
+            let policy = edit_policy::load(pool, interaction.guild_id, false).await;
+            match record_attendance(
+                pool,
+                &user,
+                record_type,
+                timestamp,
+                interaction.guild_id,
+                &audit,
+                &policy,
+            )
+            .await
+            {
+                Ok(Some(started_at)) => {
+                    let mut description = t!(
+                        lang,
+                        "end.done",
+                        time = prefs.clock.time(timestamp),
+                        duration = format_duration(
+                            (timestamp - started_at).num_minutes() as i32,
+                            lang.duration_format()
+                        )
+                    );
+                    if let Some(warning) =
+                        hour_caps::check_after_clock_out(http, pool, interaction.guild_id, &user)
+                            .await
+                    {
+                        description.push_str(&format!("\n\n{}", warning));
+                    }
+                    create_success_embed(&t!(lang, "end.title"), &description)
+                }
+                Ok(None) => create_success_embed(
+                    &t!(lang, "start.title"),
+                    &t!(lang, "start.done", time = prefs.clock.time(timestamp)),
+                ),
+                Err(error) => return responder.respond_error(error).await,
+            }
+        }
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal(t!(lang, "error.user_lookup"), e))
+                .await;
+        }
+    };
+
+    responder
+        .respond(serenity::CreateInteractionResponse::Message(
+            serenity::CreateInteractionResponseMessage::new()
+                .embed(embed)
+                .ephemeral(true),
+        ))
+        .await
+}
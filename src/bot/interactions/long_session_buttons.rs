@@ -0,0 +1,241 @@
+// Buttons on the DM from `tasks::long_session_reminder`. The DM can't tell which guild a user
+// belongs to, so the custom ids carry the discord id for the owner check and `users.id` to find
+// the user, like the timesheet confirmation. "今すぐ終了" records the end at the current time
+// and is only offered while the session's date is today; "終了時刻を入力" asks for a time on
+// the session's date.
+
+use crate::bot::interactions::responder::InteractionResponder;
+use crate::bot::shortcuts::record_attendance;
+use crate::bot::{AppError, Data, Error, edit_policy, hour_caps};
+use crate::database::models::{AuditSource, RecordType, User, WorkSession};
+use crate::database::{DbPool, queries};
+use crate::utils::format::create_success_embed;
+use crate::utils::time::{
+    combine_date_time_jst, format_duration_minutes, format_time_jst, get_current_date_jst,
+};
+use crate::utils::validation::validate_time_format;
+use chrono::{DateTime, Utc};
+use poise::serenity_prelude as serenity;
+
+/// 勤務を今の時刻で終了するボタン（custom_id: "long_session_end:discord_id:user_id"）
+pub const END_NOW: &str = "long_session_end";
+/// 終了時刻を入力するモーダルを開くボタン（custom_id: "long_session_end_at:discord_id:user_id"）
+pub const END_AT: &str = "long_session_end_at";
+/// 終了時刻の入力モーダル（custom_id: "long_session_end_modal:user_id"）
+pub const END_AT_MODAL: &str = "long_session_end_modal";
+
+/// リマインダーのDMに付けるボタン（日付が変わったセッションは今の時刻では終了できない）
+pub fn reminder_buttons(user: &User, can_end_now: bool) -> Vec<serenity::CreateActionRow> {
+    let mut buttons = Vec::new();
+    if can_end_now {
+        buttons.push(
+            serenity::CreateButton::new(format!("{}:{}:{}", END_NOW, user.discord_id, user.id))
+                .label("今すぐ終了")
+                .emoji('🔴')
+                .style(serenity::ButtonStyle::Danger),
+        );
+    }
+    buttons.push(
+        serenity::CreateButton::new(format!("{}:{}:{}", END_AT, user.discord_id, user.id))
+            .label("終了時刻を入力")
+            .emoji('🕒')
+            .style(serenity::ButtonStyle::Secondary),
+    );
+    vec![serenity::CreateActionRow::Buttons(buttons)]
+}
+
+/// 「今すぐ終了」: 今の時刻で勤務終了を記録する
+pub async fn handle_end_now(
+    responder: &impl InteractionResponder,
+    http: &serenity::Http,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let pool = &data.pool;
+    let (user, session) =
+        match load_open_session(pool, &interaction.data.custom_id, 2, interaction.user.id).await {
+            Ok(found) => found,
+            Err(e) => return responder.respond_error(e).await,
+        };
+    if session.date != get_current_date_jst() {
+        return responder
+            .respond_error(AppError::user(
+                "日付が変わっているため、「終了時刻を入力」から終了時刻を指定してください",
+            ))
+            .await;
+    }
+
+    let timestamp = Utc::now();
+    match end_session(
+        http,
+        pool,
+        &user,
+        interaction.user.id,
+        timestamp,
+        "long_session",
+    )
+    .await
+    {
+        Ok(embed) => {
+            responder
+                .respond(serenity::CreateInteractionResponse::UpdateMessage(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .embed(embed)
+                        .components(vec![]),
+                ))
+                .await
+        }
+        Err(e) => responder.respond_error(e).await,
+    }
+}
+
+/// 「終了時刻を入力」: 時刻の入力モーダルを開く
+pub async fn handle_end_at(
+    responder: &impl InteractionResponder,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let pool = &data.pool;
+    let user =
+        match load_open_session(pool, &interaction.data.custom_id, 2, interaction.user.id).await {
+            Ok((user, _)) => user,
+            Err(e) => return responder.respond_error(e).await,
+        };
+
+    let modal = serenity::CreateModal::new(format!("{}:{}", END_AT_MODAL, user.id), "勤務終了")
+        .components(vec![serenity::CreateActionRow::InputText(
+            serenity::CreateInputText::new(
+                serenity::InputTextStyle::Short,
+                "終了時刻（勤務開始の日の HH:MM）",
+                "end_time",
+            )
+            .placeholder("例: 18:00")
+            .required(true)
+            .max_length(5),
+        )]);
+    responder
+        .respond(serenity::CreateInteractionResponse::Modal(modal))
+        .await
+}
+
+/// 終了時刻のモーダルの送信: 勤務開始の日の指定時刻で勤務終了を記録する
+pub async fn handle_end_at_modal(
+    responder: &impl InteractionResponder,
+    http: &serenity::Http,
+    interaction: &serenity::ModalInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let pool = &data.pool;
+    let (user, session) =
+        match load_open_session(pool, &interaction.data.custom_id, 1, interaction.user.id).await {
+            Ok(found) => found,
+            Err(e) => return responder.respond_error(e).await,
+        };
+    let input = interaction
+        .data
+        .components
+        .first()
+        .and_then(|row| row.components.first())
+        .and_then(|component| {
+            if let serenity::ActionRowComponent::InputText(input) = component {
+                input.value.as_deref()
+            } else {
+                None
+            }
+        })
+        .unwrap_or("");
+    let time = match validate_time_format(input) {
+        Ok(time) => time,
+        Err(e) => return responder.respond_error(AppError::user(e.to_string())).await,
+    };
+    let timestamp = combine_date_time_jst(session.date, time);
+
+    match end_session(
+        http,
+        pool,
+        &user,
+        interaction.user.id,
+        timestamp,
+        "long_session:time",
+    )
+    .await
+    {
+        Ok(embed) => {
+            responder
+                .respond(serenity::CreateInteractionResponse::UpdateMessage(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .embed(embed)
+                        .components(vec![]),
+                ))
+                .await
+        }
+        Err(e) => responder.respond_error(e).await,
+    }
+}
+
+/// custom_id の `index` 番目の users.id から、押した本人のユーザーと未終了のセッションを取得する
+async fn load_open_session(
+    pool: &DbPool,
+    custom_id: &str,
+    index: usize,
+    actor: serenity::UserId,
+) -> Result<(User, WorkSession), AppError> {
+    let user = match custom_id
+        .split(':')
+        .nth(index)
+        .and_then(|value| value.parse::<i64>().ok())
+    {
+        Some(user_id) => queries::get_user_by_id(pool, user_id).await.ok(),
+        None => None,
+    };
+    let Some(user) = user.filter(|user| user.discord_id == actor.to_string()) else {
+        return Err(AppError::user("このリマインダーは操作できません"));
+    };
+
+    match queries::get_active_work_session(pool, user.id).await {
+        Ok(Some(session)) => Ok((user, session)),
+        Ok(None) => Err(AppError::user("勤務中ではありません（終了は記録済みです）")),
+        Err(e) => Err(AppError::internal("勤務状況の取得に失敗しました", e)),
+    }
+}
+
+/// 勤務終了を記録し、結果の埋め込みを作る
+async fn end_session(
+    http: &serenity::Http,
+    pool: &DbPool,
+    user: &User,
+    actor: serenity::UserId,
+    timestamp: DateTime<Utc>,
+    source: &str,
+) -> Result<serenity::CreateEmbed, AppError> {
+    // ユーザーのサーバー（DMで登録したユーザーは空文字）
+    let guild_id = user
+        .guild_id
+        .parse::<u64>()
+        .ok()
+        .map(serenity::GuildId::new);
+    let audit = AuditSource::new(actor, source).in_guild(guild_id);
+    let policy = edit_policy::load(pool, guild_id, false).await;
+
+    let started_at = record_attendance(
+        pool,
+        user,
+        RecordType::End,
+        timestamp,
+        guild_id,
+        &audit,
+        &policy,
+    )
+    .await?
+    .ok_or_else(|| AppError::user("勤務中ではありません"))?;
+
+    let mut description = format!(
+        "終了時刻: {}\n勤務時間: {}",
+        format_time_jst(timestamp),
+        format_duration_minutes((timestamp - started_at).num_minutes() as i32)
+    );
+    if let Some(warning) = hour_caps::check_after_clock_out(http, pool, guild_id, user).await {
+        description.push_str(&format!("\n\n{}", warning));
+    }
+    Ok(create_success_embed("勤務終了", &description))
+}
@@ -1,4 +1,19 @@
 // Interaction handlers for buttons, modals, and select menus
 // This module will be implemented when status command interactive features are added
 
+pub mod approval_buttons;
+pub mod checkin_buttons;
+pub mod flow_state;
+pub mod focus_buttons;
+pub mod kiosk_buttons;
+pub mod long_session_buttons;
+pub mod note_buttons;
+pub mod project_buttons;
+pub mod quality_buttons;
+pub mod reset_buttons;
+pub mod responder;
+pub mod shortcut_buttons;
 pub mod status_buttons;
+pub mod suggestion_buttons;
+pub mod timesheet_buttons;
+pub mod tutorial_buttons;
@@ -0,0 +1,321 @@
+use crate::bot::hour_caps;
+use crate::bot::interactions::responder::InteractionResponder;
+use crate::bot::{AppError, Data, Error};
+use crate::database::queries;
+use crate::utils::format::{create_info_embed, create_success_embed};
+use crate::utils::time::{DurationFormat, format_duration, format_time_jst, get_current_date_jst};
+use crate::utils::validation::{MAX_SESSION_NOTE_CHARS, validate_session_note};
+use poise::serenity_prelude as serenity;
+
+/// 「メモ追加」ボタン: 今日の最新のセッションのメモを編集するモーダルを開く
+pub async fn handle_note_add(
+    responder: &impl InteractionResponder,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let pool = &data.pool;
+    let today = get_current_date_jst();
+
+    let sessions = match queries::create_or_get_user(
+        pool,
+        &interaction.user.id.to_string(),
+        interaction.guild_id,
+        &interaction.user.name,
+    )
+    .await
+    {
+        Ok(user) => queries::get_work_sessions_by_date_range(pool, user.id, today, today).await,
+        Err(e) => Err(e),
+    };
+
+    let session = match sessions {
+        Ok(sessions) => sessions.into_iter().last(),
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("勤務セッションの取得に失敗しました", e))
+                .await;
+        }
+    };
+    let Some(session) = session else {
+        return responder
+            .respond_error(AppError::user("今日の勤務セッションがありません"))
+            .await;
+    };
+
+    responder
+        .respond(serenity::CreateInteractionResponse::Modal(
+            create_note_modal(
+                session.id,
+                &format_time_jst(session.start_time),
+                session.note.as_deref(),
+            ),
+        ))
+        .await
+}
+
+/// セッションIDを埋め込んだメモ編集モーダルを作成
+pub fn create_note_modal(
+    session_id: i64,
+    start_time: &str,
+    current_note: Option<&str>,
+) -> serenity::CreateModal {
+    let mut note_input = serenity::CreateInputText::new(
+        serenity::InputTextStyle::Paragraph,
+        "メモ（空にすると削除）",
+        "note",
+    )
+    .placeholder("例: 〇〇の資料作成")
+    .required(false)
+    .max_length(MAX_SESSION_NOTE_CHARS as u16);
+    if let Some(note) = current_note {
+        note_input = note_input.value(note);
+    }
+
+    serenity::CreateModal::new("note_modal", format!("メモ（{} 開始の勤務）", start_time))
+        .components(vec![
+            serenity::CreateActionRow::InputText(note_input),
+            serenity::CreateActionRow::InputText(
+                serenity::CreateInputText::new(
+                    serenity::InputTextStyle::Short,
+                    "セッションID (変更不可)",
+                    "session_id",
+                )
+                .value(session_id.to_string())
+                .required(false)
+                .max_length(10),
+            ),
+        ])
+}
+
+/// `/end` で表示する「今日やったこと」の入力モーダル（custom_id: "end_note_modal:session_id"）
+pub fn create_end_note_modal(session_id: i64) -> serenity::CreateModal {
+    serenity::CreateModal::new(
+        format!("end_note_modal:{}", session_id),
+        "勤務終了 - 今日やったこと",
+    )
+    .components(vec![serenity::CreateActionRow::InputText(
+        serenity::CreateInputText::new(
+            serenity::InputTextStyle::Paragraph,
+            "今日やったこと（空欄のまま送信でスキップ）",
+            "note",
+        )
+        .placeholder("例: 〇〇の資料作成、△△のレビュー")
+        .required(false)
+        .max_length(MAX_SESSION_NOTE_CHARS as u16),
+    )])
+}
+
+/// 「今日やったこと」モーダルの送信: メモを保存して勤務終了の結果を表示する
+pub async fn handle_end_note_modal(
+    responder: &impl InteractionResponder,
+    http: &serenity::Http,
+    interaction: &serenity::ModalInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let Some(session_id) = interaction
+        .data
+        .custom_id
+        .split(':')
+        .nth(1)
+        .and_then(|id| id.parse::<i64>().ok())
+    else {
+        return responder
+            .respond_error(AppError::user("無効な操作です"))
+            .await;
+    };
+    let input = interaction
+        .data
+        .components
+        .first()
+        .and_then(|row| row.components.first())
+        .and_then(|component| {
+            if let serenity::ActionRowComponent::InputText(input) = component {
+                input.value.as_deref()
+            } else {
+                None
+            }
+        })
+        .unwrap_or("");
+    let note = match validate_session_note(input) {
+        Ok(note) => note,
+        Err(e) => return responder.respond_error(AppError::user(e.to_string())).await,
+    };
+
+    let pool = &data.pool;
+    let user = match queries::get_user_by_discord_id(
+        pool,
+        &interaction.user.id.to_string(),
+        interaction.guild_id,
+    )
+    .await
+    {
+        Ok(user) => user,
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("ユーザー情報の取得に失敗しました", e))
+                .await;
+        }
+    };
+    let session = match queries::get_work_session_by_id(pool, session_id).await {
+        Ok(session) if session.user_id == user.id => session,
+        _ => {
+            return responder
+                .respond_error(AppError::user(
+                    "勤務セッションが見つかりません。退勤は記録済みです。メモは `/status` から追加してください",
+                ))
+                .await;
+        }
+    };
+
+    let mut description = format!(
+        "勤務を終了しました\n終了時刻: {}\n勤務時間: {}",
+        session.end_time.map(format_time_jst).unwrap_or_default(),
+        format_duration(
+            session.total_minutes.unwrap_or(0),
+            DurationFormat::from_locale(Some(&interaction.locale)),
+        )
+    );
+    if let Some(note) = &note {
+        match queries::set_session_note(pool, session.id, Some(note)).await {
+            Ok(_) => {
+                description.push_str(&format!("\n📝 今日やったこと: {}", note));
+                post_to_event_log(http, data, interaction.guild_id, &user.discord_id, note).await;
+            }
+            Err(e) => tracing::error!("Failed to set session note: {}", e),
+        }
+    }
+    if let Some(warning) =
+        hour_caps::check_after_clock_out(http, pool, interaction.guild_id, &user).await
+    {
+        description.push_str(&format!("\n\n{}", warning));
+    }
+
+    responder
+        .respond(serenity::CreateInteractionResponse::Message(
+            serenity::CreateInteractionResponseMessage::new()
+                .embed(create_success_embed("勤務終了", &description)),
+        ))
+        .await
+}
+
+/// 勤怠ログチャンネルが設定されていれば「今日やったこと」を投稿する
+async fn post_to_event_log(
+    http: &serenity::Http,
+    data: &Data,
+    guild_id: Option<serenity::GuildId>,
+    discord_id: &str,
+    note: &str,
+) {
+    let Some(guild_id) = guild_id else {
+        return;
+    };
+    let channel_id = match queries::get_guild_settings(&data.pool, &guild_id.to_string()).await {
+        Ok(settings) => settings
+            .and_then(|settings| settings.event_log_channel_id)
+            .and_then(|id| id.parse::<u64>().ok()),
+        Err(e) => {
+            tracing::warn!("Failed to load guild settings: {}", e);
+            None
+        }
+    };
+    let Some(channel_id) = channel_id else {
+        return;
+    };
+
+    let embed = create_info_embed(
+        "📝 今日やったこと",
+        &format!("👤 <@{}>\n{}", discord_id, note),
+    );
+    if let Err(e) = serenity::ChannelId::new(channel_id)
+        .send_message(http, serenity::CreateMessage::new().embed(embed))
+        .await
+    {
+        tracing::warn!("Failed to post daily note for guild {}: {}", guild_id, e);
+    }
+}
+
+/// メモ編集モーダルの送信
+pub async fn handle_note_modal(
+    responder: &impl InteractionResponder,
+    interaction: &serenity::ModalInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let input = |index: usize| {
+        interaction
+            .data
+            .components
+            .get(index)
+            .and_then(|row| row.components.first())
+            .and_then(|component| {
+                if let serenity::ActionRowComponent::InputText(input) = component {
+                    input.value.as_deref()
+                } else {
+                    None
+                }
+            })
+            .unwrap_or("")
+    };
+
+    let Ok(session_id) = input(1).parse::<i64>() else {
+        return responder
+            .respond_error(AppError::user("無効なセッションIDです"))
+            .await;
+    };
+    let note = match validate_session_note(input(0)) {
+        Ok(note) => note,
+        Err(e) => return responder.respond_error(AppError::user(e.to_string())).await,
+    };
+
+    let pool = &data.pool;
+    let user = match queries::create_or_get_user(
+        pool,
+        &interaction.user.id.to_string(),
+        interaction.guild_id,
+        &interaction.user.name,
+    )
+    .await
+    {
+        Ok(user) => user,
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("ユーザー情報の取得に失敗しました", e))
+                .await;
+        }
+    };
+
+    // モーダルを開いた後に記録が修正されるとセッションは作り直されている
+    let updated = match queries::get_work_session_by_id(pool, session_id).await {
+        Ok(session) if session.user_id == user.id => {
+            queries::set_session_note(pool, session_id, note.as_deref()).await
+        }
+        _ => Ok(false),
+    };
+
+    match updated {
+        Ok(true) => {
+            let embed = match &note {
+                Some(note) => create_success_embed("メモ保存", &format!("📝 {}", note)),
+                None => create_success_embed("メモ削除", "メモを削除しました"),
+            };
+            responder
+                .respond(serenity::CreateInteractionResponse::Message(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .embed(embed)
+                        .ephemeral(true),
+                ))
+                .await
+        }
+        Ok(false) => {
+            responder
+                .respond_error(AppError::user(
+                    "勤務セッションが見つかりません。記録が変更された可能性があるため、もう一度 `/status` から操作してください",
+                ))
+                .await
+        }
+        Err(e) => {
+            responder
+                .respond_error(AppError::internal("メモの保存に失敗しました", e))
+                .await
+        }
+    }
+}
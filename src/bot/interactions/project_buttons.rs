@@ -0,0 +1,92 @@
+use crate::bot::interactions::responder::InteractionResponder;
+use crate::bot::{AppError, Data, Error};
+use crate::database::models::RecordType;
+use crate::database::queries;
+use poise::serenity_prelude as serenity;
+
+/// `/start` の「前回のプロジェクト」ボタン（custom_id: "start_project:user_id:record_id:project_id"）
+pub async fn handle_start_project(
+    responder: &impl InteractionResponder,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let ids: Vec<i64> = interaction
+        .data
+        .custom_id
+        .split(':')
+        .skip(2)
+        .filter_map(|id| id.parse().ok())
+        .collect();
+    let [record_id, project_id] = ids[..] else {
+        return responder
+            .respond_error(AppError::user("無効な操作です"))
+            .await;
+    };
+    let pool = &data.pool;
+
+    let user = match queries::get_user_by_discord_id(
+        pool,
+        &interaction.user.id.to_string(),
+        interaction.guild_id,
+    )
+    .await
+    {
+        Ok(user) => user,
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("ユーザー情報の取得に失敗しました", e))
+                .await;
+        }
+    };
+    let record = queries::get_attendance_record_by_id(pool, record_id).await;
+    if !record
+        .is_ok_and(|record| record.user_id == user.id && record.record_type == RecordType::Start)
+    {
+        return responder
+            .respond_error(AppError::user("対象の開始記録が見つかりません"))
+            .await;
+    }
+    let project = match queries::get_project(pool, project_id).await {
+        Ok(Some(project)) => project,
+        Ok(None) => {
+            return responder
+                .respond_error(AppError::user("プロジェクトが見つかりません"))
+                .await;
+        }
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("プロジェクトの取得に失敗しました", e))
+                .await;
+        }
+    };
+
+    if let Err(e) = queries::set_session_project(pool, record_id, Some(project.id)).await {
+        return responder
+            .respond_error(AppError::internal("プロジェクトの設定に失敗しました", e))
+            .await;
+    }
+    if let Err(e) = queries::set_last_project(pool, user.id, &project.guild_id, project.id).await {
+        tracing::error!("Failed to save last project: {}", e);
+    }
+
+    // 元の「勤務開始」の表示にプロジェクトを追記し、ボタンを消す
+    let embeds = interaction
+        .message
+        .embeds
+        .iter()
+        .map(|embed| {
+            serenity::CreateEmbed::from(embed.clone()).description(format!(
+                "{}\nプロジェクト: {}",
+                embed.description.as_deref().unwrap_or_default(),
+                project.name
+            ))
+        })
+        .collect();
+    responder
+        .respond(serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new()
+                .embeds(embeds)
+                .components(vec![]),
+        ))
+        .await
+}
@@ -0,0 +1,74 @@
+use crate::bot::interactions::responder::InteractionResponder;
+use crate::bot::{AppError, Data, Error};
+use crate::database::queries;
+use crate::utils::format::create_status_embed;
+use crate::utils::i18n::Lang;
+use crate::utils::time::TimeDisplay;
+use poise::serenity_prelude as serenity;
+
+/// データ品質ダイジェストの修正ボタン: 対象日の記録と修正用のボタンを本人にだけ表示する
+pub async fn handle_quality_fix(
+    responder: &impl InteractionResponder,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    // custom_id: "quality_fix:user_id:YYYY-MM-DD"
+    let date = interaction
+        .data
+        .custom_id
+        .split(':')
+        .nth(2)
+        .and_then(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+    let Some(date) = date else {
+        return responder
+            .respond_error(AppError::user("無効な日付です"))
+            .await;
+    };
+
+    let pool = &data.pool;
+    let user_id = interaction.user.id.to_string();
+    let records = match queries::create_or_get_user(
+        pool,
+        &user_id,
+        interaction.guild_id,
+        &interaction.user.name,
+    )
+    .await
+    {
+        Ok(user) => queries::get_records_by_date(pool, user.id, date).await,
+        Err(e) => Err(e),
+    };
+    let records = match records {
+        Ok(records) => records,
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("勤務記録の取得に失敗しました", e))
+                .await;
+        }
+    };
+
+    // ステータス画面の時間修正・記録追加（過去日付に対応）を、ダイジェストを書き換えないよう本人用のメッセージで提供する
+    let components = vec![serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(format!("time_edit:{}", user_id))
+            .label("🕐 時間修正")
+            .style(serenity::ButtonStyle::Primary),
+        serenity::CreateButton::new(format!("record_add:{}", user_id))
+            .label("✅ 記録追加")
+            .style(serenity::ButtonStyle::Success),
+    ])];
+
+    responder
+        .respond(serenity::CreateInteractionResponse::Message(
+            serenity::CreateInteractionResponseMessage::new()
+                .embed(create_status_embed(
+                    &interaction.user.name,
+                    date,
+                    &records,
+                    Lang::Ja,
+                    TimeDisplay::default(),
+                ))
+                .components(components)
+                .ephemeral(true),
+        ))
+        .await
+}
@@ -0,0 +1,161 @@
+use crate::bot::checks::member_is_admin;
+use crate::bot::guild_reset;
+use crate::bot::interactions::flow_state::{self, FlowState};
+use crate::bot::interactions::responder::InteractionResponder;
+use crate::bot::{AppError, Data, Error};
+use crate::database::queries;
+use crate::utils::format::create_success_embed;
+use poise::serenity_prelude as serenity;
+
+/// 「サーバー名を入力して削除」ボタン（custom_id: "guild_reset:user_id:state_id"）
+pub async fn handle_reset_button(
+    responder: &impl InteractionResponder,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    if !interaction
+        .member
+        .as_ref()
+        .is_some_and(|member| member_is_admin(member, &data.config))
+    {
+        return responder
+            .respond_error(AppError::user("サーバーのデータ削除は管理者のみ行えます"))
+            .await;
+    }
+    let Some(state_id) = interaction
+        .data
+        .custom_id
+        .split(':')
+        .nth(2)
+        .and_then(|id| id.parse::<i64>().ok())
+    else {
+        return responder
+            .respond_error(AppError::user("無効な操作です"))
+            .await;
+    };
+
+    responder
+        .respond(serenity::CreateInteractionResponse::Modal(
+            guild_reset::confirmation_modal(interaction.user.id, state_id),
+        ))
+        .await
+}
+
+/// 確認モーダルの送信（サーバー名が一致したときだけ削除する）
+pub async fn handle_reset_modal(
+    responder: &impl InteractionResponder,
+    http: &serenity::Http,
+    interaction: &serenity::ModalInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let (Some(guild_id), Some(member)) = (interaction.guild_id, interaction.member.as_ref()) else {
+        return responder
+            .respond_error(AppError::user("サーバー内で操作してください"))
+            .await;
+    };
+    if !member_is_admin(member, &data.config) {
+        return responder
+            .respond_error(AppError::user("サーバーのデータ削除は管理者のみ行えます"))
+            .await;
+    }
+    let Some(state_id) = interaction
+        .data
+        .custom_id
+        .split(':')
+        .nth(2)
+        .and_then(|id| id.parse::<i64>().ok())
+    else {
+        return responder
+            .respond_error(AppError::user("無効な操作です"))
+            .await;
+    };
+
+    let input = interaction
+        .data
+        .components
+        .first()
+        .and_then(|row| row.components.first())
+        .and_then(|component| {
+            if let serenity::ActionRowComponent::InputText(input) = component {
+                input.value.as_deref()
+            } else {
+                None
+            }
+        })
+        .unwrap_or("")
+        .trim();
+    let guild_name = match guild_id.to_partial_guild(http).await {
+        Ok(guild) => guild.name,
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("サーバー情報の取得に失敗しました", e))
+                .await;
+        }
+    };
+    // 入力を間違えた場合はもう一度ボタンから入力できるよう、状態は読み込まない
+    if input != guild_name {
+        return responder
+            .respond_error(AppError::user(
+                "サーバー名が一致しません。削除は行っていません",
+            ))
+            .await;
+    }
+
+    let pool = &data.pool;
+    let state = flow_state::take(pool, state_id, &interaction.user.id.to_string())
+        .await
+        .unwrap_or_else(|e| {
+            tracing::error!("Failed to load interaction state: {}", e);
+            None
+        });
+    let Some(FlowState::ResetGuild {
+        guild_id: target_guild_id,
+        user_ids,
+    }) = state
+    else {
+        return responder
+            .respond_error(AppError::user(
+                "この操作は期限切れです。もう一度 `/admin reset-guild` から操作してください",
+            ))
+            .await;
+    };
+    if target_guild_id != guild_id.get() {
+        return responder
+            .respond_error(AppError::user("別のサーバーの操作です"))
+            .await;
+    }
+
+    let embed = match queries::reset_guild_data(pool, &guild_id.to_string(), &user_ids).await {
+        Ok((records, sessions)) => {
+            tracing::warn!(
+                "Guild {} data was reset by {} ({} records, {} sessions)",
+                guild_id,
+                interaction.user.id,
+                records,
+                sessions
+            );
+            create_success_embed(
+                "サーバーのデータを削除しました",
+                &format!(
+                    "{}人分の打刻記録 {}件・勤務セッション {}件と、サーバーの設定を削除しました",
+                    user_ids.len(),
+                    records,
+                    sessions
+                ),
+            )
+        }
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("削除に失敗しました", e))
+                .await;
+        }
+    };
+
+    responder
+        .respond(serenity::CreateInteractionResponse::Message(
+            serenity::CreateInteractionResponseMessage::new()
+                .embed(embed)
+                .ephemeral(true),
+        ))
+        .await
+}
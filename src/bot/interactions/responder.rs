@@ -0,0 +1,104 @@
+use crate::bot::{AppError, Error};
+use crate::utils::format::format_error_message;
+use crate::utils::i18n::Lang;
+use poise::serenity_prelude as serenity;
+
+/// インタラクションへの応答送信を抽象化（テストではモックに差し替える）
+pub trait InteractionResponder {
+    async fn respond(&self, response: serenity::CreateInteractionResponse) -> Result<(), Error>;
+
+    /// 操作した人のDiscordのロケール
+    fn locale(&self) -> Option<&str> {
+        None
+    }
+
+    /// 操作した人だけに見えるメッセージでエラーを返す（内部エラーの原因はログに残す）
+    async fn respond_error(&self, error: AppError) -> Result<(), Error> {
+        error.log();
+        let lang = Lang::from_locale(self.locale());
+        self.respond(serenity::CreateInteractionResponse::Message(
+            serenity::CreateInteractionResponseMessage::new()
+                .content(format_error_message(error.message(), lang))
+                .ephemeral(true),
+        ))
+        .await
+    }
+}
+
+/// ボタン・セレクトメニューのインタラクションに応答する
+pub struct ComponentResponder<'a> {
+    ctx: &'a serenity::Context,
+    interaction: &'a serenity::ComponentInteraction,
+}
+
+impl<'a> ComponentResponder<'a> {
+    pub fn new(
+        ctx: &'a serenity::Context,
+        interaction: &'a serenity::ComponentInteraction,
+    ) -> Self {
+        Self { ctx, interaction }
+    }
+}
+
+impl InteractionResponder for ComponentResponder<'_> {
+    async fn respond(&self, response: serenity::CreateInteractionResponse) -> Result<(), Error> {
+        self.interaction
+            .create_response(&self.ctx.http, response)
+            .await?;
+        Ok(())
+    }
+
+    fn locale(&self) -> Option<&str> {
+        Some(&self.interaction.locale)
+    }
+}
+
+/// モーダル送信のインタラクションに応答する
+pub struct ModalResponder<'a> {
+    ctx: &'a serenity::Context,
+    interaction: &'a serenity::ModalInteraction,
+}
+
+impl<'a> ModalResponder<'a> {
+    pub fn new(ctx: &'a serenity::Context, interaction: &'a serenity::ModalInteraction) -> Self {
+        Self { ctx, interaction }
+    }
+}
+
+impl InteractionResponder for ModalResponder<'_> {
+    async fn respond(&self, response: serenity::CreateInteractionResponse) -> Result<(), Error> {
+        self.interaction
+            .create_response(&self.ctx.http, response)
+            .await?;
+        Ok(())
+    }
+
+    fn locale(&self) -> Option<&str> {
+        Some(&self.interaction.locale)
+    }
+}
+
+/// 送信された応答をJSONとして記録するテスト用モック
+#[cfg(test)]
+#[derive(Default)]
+pub struct MockResponder {
+    responses: std::sync::Mutex<Vec<serde_json::Value>>,
+}
+
+#[cfg(test)]
+impl MockResponder {
+    pub fn responses(&self) -> Vec<serde_json::Value> {
+        self.responses.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl InteractionResponder for MockResponder {
+    async fn respond(&self, response: serenity::CreateInteractionResponse) -> Result<(), Error> {
+        self.responses
+            .lock()
+            .unwrap()
+            .push(serde_json::to_value(&response)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use crate::bot::interactions::responder::InteractionResponder;
+use crate::bot::shortcuts::execute_shortcut;
+use crate::bot::{AppError, Data, Error};
+use crate::database::queries;
+use crate::utils::format::create_error_embed;
+use crate::utils::shortcut::parse_shortcut;
+use poise::serenity_prelude as serenity;
+
+/// `/shortcut list` のボタンからショートカットを実行する
+pub async fn handle_shortcut_run(
+    responder: &impl InteractionResponder,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    // custom_id: "shortcut_run:user_id:name"
+    let name = interaction
+        .data
+        .custom_id
+        .splitn(3, ':')
+        .nth(2)
+        .unwrap_or_default();
+    let pool = &data.pool;
+
+    let shortcut = match queries::create_or_get_user(
+        pool,
+        &interaction.user.id.to_string(),
+        interaction.guild_id,
+        &interaction.user.name,
+    )
+    .await
+    {
+        Ok(user) => queries::get_shortcut(pool, user.id, name).await,
+        Err(e) => Err(e),
+    };
+
+    let embeds = match shortcut {
+        Ok(Some(shortcut)) => match parse_shortcut(&shortcut.action) {
+            Ok(actions) => {
+                execute_shortcut(pool, &interaction.user, interaction.guild_id, &actions).await
+            }
+            Err(e) => vec![create_error_embed(
+                "ショートカットを実行できません",
+                &e.to_string(),
+            )],
+        },
+        Ok(None) => vec![create_error_embed(
+            "ショートカットが見つかりません",
+            &format!("**{}** は削除されています", name),
+        )],
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("ショートカットの取得に失敗しました", e))
+                .await;
+        }
+    };
+
+    responder
+        .respond(serenity::CreateInteractionResponse::Message(
+            serenity::CreateInteractionResponseMessage::new()
+                .embeds(embeds)
+                .ephemeral(true),
+        ))
+        .await
+}
@@ -1,12 +1,28 @@
-use crate::bot::{Data, Error};
-use crate::database::models::RecordType;
+use crate::bot::approvals::{self, RecordChange};
+use crate::bot::checks::member_is_admin;
+use crate::bot::interactions::flow_state::{self, FlowState};
+use crate::bot::interactions::responder::{
+    ComponentResponder, InteractionResponder, ModalResponder,
+};
+use crate::bot::interactions::{
+    approval_buttons, checkin_buttons, focus_buttons, kiosk_buttons, long_session_buttons,
+    note_buttons, project_buttons, quality_buttons, reset_buttons, shortcut_buttons,
+    suggestion_buttons, timesheet_buttons, tutorial_buttons,
+};
+use crate::bot::{AppError, Data, Error, edit_policy, metrics, preferences, punch_lock, webhooks};
+use crate::database::models::{AttendanceRecord, AuditSource, RecordType};
 use crate::database::queries;
-use crate::utils::format::{create_error_embed, create_success_embed, format_error_message};
+use crate::utils::format::{
+    create_error_embed, create_info_embed, create_success_embed, format_audit_logs,
+    format_record_revisions, record_type_label,
+};
 use crate::utils::record_selector::RecordSelector;
 use crate::utils::record_validator::RecordValidator;
 use crate::utils::session_manager::SessionManager;
-use crate::utils::time::{combine_date_time_jst, get_current_date_jst};
-use crate::utils::validation::validate_time_format;
+use crate::utils::time::{
+    combine_date_time_jst, get_current_date_jst, get_date_from_utc_timestamp,
+};
+use crate::utils::validation::{validate_date_format, validate_time_format};
 use chrono::{Datelike, NaiveDate};
 use poise::serenity_prelude as serenity;
 
@@ -15,73 +31,140 @@ pub async fn handle_status_interaction(
     interaction: &serenity::ComponentInteraction,
     data: &Data,
 ) -> Result<(), Error> {
+    let responder = ComponentResponder::new(ctx, interaction);
     let custom_id = &interaction.data.custom_id;
 
     // Extract action and user ID from custom_id (format: "action:user_id" or "action:user_id:extra")
     let parts: Vec<&str> = custom_id.split(':').collect();
+
+    // 承認依頼のボタンは依頼者ではなく管理者が押す
+    if let Some(approve) = match parts[0] {
+        "approval_approve" => Some(true),
+        "approval_reject" => Some(false),
+        _ => None,
+    } {
+        return approval_buttons::handle_review(ctx, interaction, data, approve).await;
+    }
+
+    // 出退勤ボタンは誰でも押せ、押したユーザーの記録を作る
+    if let Some(record_type) = match custom_id.as_str() {
+        kiosk_buttons::KIOSK_START => Some(RecordType::Start),
+        kiosk_buttons::KIOSK_END => Some(RecordType::End),
+        _ => None,
+    } {
+        return kiosk_buttons::handle_kiosk(&responder, &ctx.http, interaction, data, record_type)
+            .await;
+    }
+
     if parts.len() >= 2 {
         let action = parts[0];
-        let original_user_id = parts[1];
 
         // Verify user has permission to interact with this status message
-        if interaction.user.id.to_string() != original_user_id {
+        if !pressed_by_owner(interaction) {
             let embed =
                 create_error_embed("アクセス拒否", "他のユーザーの勤務状況は操作できません");
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .embed(embed)
-                            .ephemeral(true),
-                    ),
-                )
+            responder
+                .respond(serenity::CreateInteractionResponse::Message(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .embed(embed)
+                        .ephemeral(true),
+                ))
                 .await?;
             return Ok(());
         }
 
         match action {
-            "time_edit" => handle_time_edit_selection(ctx, interaction, data).await,
-            "record_add" => handle_record_add(ctx, interaction, data).await,
-            "delete_record" => handle_delete_record_selection(ctx, interaction, data).await,
-            "history_view" => handle_history_view(ctx, interaction, data).await,
-            "add_start_record" => handle_add_start_record(ctx, interaction, data).await,
-            "add_end_record" => handle_add_end_record(ctx, interaction, data).await,
-            "cancel_add" => handle_cancel_action(ctx, interaction, data).await,
-            "confirm_delete_single" => handle_confirm_delete_single(ctx, interaction, data).await,
-            "confirm_delete_all" => handle_confirm_delete_all(ctx, interaction, data).await,
-            "cancel_delete" => handle_cancel_action(ctx, interaction, data).await,
+            "time_edit" => handle_time_edit_selection(&responder, interaction, data).await,
+            "record_add" => handle_record_add(&responder, interaction, data).await,
+            "delete_record" => handle_delete_record_selection(&responder, interaction, data).await,
+            "history_view" | "history_page" => {
+                handle_history_view(&responder, interaction, data).await
+            }
+            "record_changes" => handle_record_changes(&responder, interaction, data).await,
+            "record_revisions" => handle_record_revisions(&responder, interaction, data).await,
+            "note_add" => note_buttons::handle_note_add(&responder, interaction, data).await,
+            "add_start_record" => handle_add_start_record(&responder, interaction, data).await,
+            "add_end_record" => handle_add_end_record(&responder, interaction, data).await,
+            "cancel_add" => handle_cancel_action(&responder, interaction, data).await,
+            "edit_date_select" => handle_edit_date_selected(&responder, interaction, data).await,
+            "edit_record_select" => {
+                handle_edit_record_selected(&responder, interaction, data).await
+            }
+            "delete_record_select" => {
+                handle_delete_record_selected(&responder, interaction, data).await
+            }
+            "history_date_select" => {
+                handle_history_date_selected(&responder, interaction, data).await
+            }
+            "confirm_delete" | "confirm_delete_single" | "confirm_delete_all" => {
+                handle_confirm_delete(&responder, &ctx.http, interaction, data).await
+            }
+            "cancel_delete" => handle_cancel_action(&responder, interaction, data).await,
+            timesheet_buttons::TIMESHEET_CONFIRM => {
+                timesheet_buttons::handle_timesheet_confirm(&responder, interaction, data).await
+            }
+            "focus_stop" => focus_buttons::handle_focus_stop(&responder, interaction, data).await,
+            checkin_buttons::CHECKIN_START => {
+                checkin_buttons::handle_checkin_start(&responder, interaction, data).await
+            }
+            long_session_buttons::END_NOW => {
+                long_session_buttons::handle_end_now(&responder, &ctx.http, interaction, data).await
+            }
+            long_session_buttons::END_AT => {
+                long_session_buttons::handle_end_at(&responder, interaction, data).await
+            }
+            "suggest_previous_day" => {
+                suggestion_buttons::handle_previous_day(ctx, interaction, data).await
+            }
+            "suggest_edit_record" => suggestion_buttons::handle_edit_record(ctx, interaction).await,
+            "quality_fix" => {
+                quality_buttons::handle_quality_fix(&responder, interaction, data).await
+            }
+            "shortcut_run" => {
+                shortcut_buttons::handle_shortcut_run(&responder, interaction, data).await
+            }
+            "start_project" => {
+                project_buttons::handle_start_project(&responder, interaction, data).await
+            }
+            "tutorial_page" => {
+                tutorial_buttons::handle_tutorial_page(&responder, interaction).await
+            }
+            "tutorial_close" => tutorial_buttons::handle_tutorial_close(&responder).await,
+            "guild_reset" => {
+                reset_buttons::handle_reset_button(&responder, interaction, data).await
+            }
             _ => {
-                interaction
-                    .create_response(
-                        &ctx.http,
-                        serenity::CreateInteractionResponse::Message(
-                            serenity::CreateInteractionResponseMessage::new()
-                                .content("未実装の機能です")
-                                .ephemeral(true),
-                        ),
-                    )
+                responder
+                    .respond(serenity::CreateInteractionResponse::Message(
+                        serenity::CreateInteractionResponseMessage::new()
+                            .content("未実装の機能です")
+                            .ephemeral(true),
+                    ))
                     .await?;
                 Ok(())
             }
         }
     } else {
-        // Handle cases without user ID (select menus, etc.)
+        // Handle cases without user ID
         match custom_id.as_str() {
-            // Select menu interactions
-            "edit_record_select" => handle_edit_record_selected(ctx, interaction, data).await,
-            "delete_record_select" => handle_delete_record_selected(ctx, interaction, data).await,
-            "history_date_select" => handle_history_date_selected(ctx, interaction, data).await,
+            // 所有者を載せていない旧形式のセレクトメニューは誰でも操作できてしまうため受け付けない
+            "edit_date_select"
+            | "edit_record_select"
+            | "delete_record_select"
+            | "history_date_select" => {
+                responder
+                    .respond_error(AppError::user(
+                        "この操作は期限切れです。もう一度 `/status` から操作してください",
+                    ))
+                    .await
+            }
             _ => {
-                interaction
-                    .create_response(
-                        &ctx.http,
-                        serenity::CreateInteractionResponse::Message(
-                            serenity::CreateInteractionResponseMessage::new()
-                                .content("未実装の機能です")
-                                .ephemeral(true),
-                        ),
-                    )
+                responder
+                    .respond(serenity::CreateInteractionResponse::Message(
+                        serenity::CreateInteractionResponseMessage::new()
+                            .content("未実装の機能です")
+                            .ephemeral(true),
+                    ))
                     .await?;
                 Ok(())
             }
@@ -90,7 +173,7 @@ pub async fn handle_status_interaction(
 }
 
 async fn handle_time_edit_selection(
-    ctx: &serenity::Context,
+    responder: &impl InteractionResponder,
     interaction: &serenity::ComponentInteraction,
     data: &Data,
 ) -> Result<(), Error> {
@@ -100,99 +183,147 @@ async fn handle_time_edit_selection(
     let pool = &data.pool;
 
     // Get user from database
-    let user = match queries::create_or_get_user(pool, &user_id, &username).await {
-        Ok(user) => user,
+    let user =
+        match queries::create_or_get_user(pool, &user_id, interaction.guild_id, &username).await {
+            Ok(user) => user,
+            Err(e) => {
+                return responder
+                    .respond_error(AppError::internal("ユーザー情報の取得に失敗しました", e))
+                    .await;
+            }
+        };
+
+    let available_dates = match queries::get_user_available_dates(pool, user.id).await {
+        Ok(dates) => dates,
         Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "ユーザー情報の取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
+            return responder
+                .respond_error(AppError::internal("勤務記録の取得に失敗しました", e))
+                .await;
         }
     };
 
+    // Only dates that can still be modified are offered
+    let is_admin = interaction
+        .member
+        .as_ref()
+        .is_some_and(|member| member_is_admin(member, &data.config));
+    let policy = edit_policy::load(pool, interaction.guild_id, is_admin).await;
     let current_date = get_current_date_jst();
-
-    // Get today's records
-    let records = match queries::get_today_records(pool, user.id, current_date).await {
-        Ok(records) => records,
-        Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "勤務記録の取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
+    let options: Vec<_> = available_dates
+        .iter()
+        .filter(|date| {
+            let age = (current_date - **date).num_days();
+            age <= policy.max_age_days && (age <= 0 || !policy.backdate_admin_only)
+        })
+        .map(|date| {
+            let label = if *date == current_date {
+                format!(
+                    "{} ({}) 今日",
+                    date.format("%Y/%m/%d"),
+                    get_weekday_jp(*date)
                 )
-                .await?;
-            return Ok(());
-        }
-    };
-
-    let record_selector = RecordSelector::new(records);
+            } else {
+                format!("{} ({})", date.format("%Y/%m/%d"), get_weekday_jp(*date))
+            };
+            serenity::CreateSelectMenuOption::new(label, date.format("%Y-%m-%d").to_string())
+        })
+        .collect();
 
-    if record_selector.is_empty() {
-        interaction
-            .create_response(
-                &ctx.http,
-                serenity::CreateInteractionResponse::Message(
-                    serenity::CreateInteractionResponseMessage::new()
-                        .content("修正できる記録がありません")
-                        .ephemeral(true),
-                ),
-            )
+    if options.is_empty() {
+        responder
+            .respond(serenity::CreateInteractionResponse::Message(
+                serenity::CreateInteractionResponseMessage::new()
+                    .content("修正できる記録がありません")
+                    .ephemeral(true),
+            ))
             .await?;
         return Ok(());
     }
 
-    // Create select menu for record selection
-    if let Some(select_menu) =
-        record_selector.create_select_menu("edit_record_select", "修正する記録を選択してください")
-    {
-        let components = vec![serenity::CreateActionRow::SelectMenu(select_menu)];
+    let select_menu = serenity::CreateSelectMenu::new(
+        format!("edit_date_select:{}", user_id),
+        serenity::CreateSelectMenuKind::String { options },
+    )
+    .placeholder("修正する日付を選択してください");
+
+    responder
+        .respond(serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new()
+                .content(format!(
+                    "🕐 **時間修正**: 修正する日付を選択してください（過去{}日以内）",
+                    policy.max_age_days
+                ))
+                .components(vec![serenity::CreateActionRow::SelectMenu(select_menu)]),
+        ))
+        .await?;
 
-        interaction
-            .create_response(
-                &ctx.http,
-                serenity::CreateInteractionResponse::UpdateMessage(
-                    serenity::CreateInteractionResponseMessage::new()
-                        .content("🕐 **時間修正**: 修正する記録を選択してください")
-                        .components(components),
-                ),
-            )
-            .await?;
+    Ok(())
+}
+
+async fn handle_edit_date_selected(
+    responder: &impl InteractionResponder,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let selected_date_str = if let serenity::ComponentInteractionDataKind::StringSelect { values } =
+        &interaction.data.kind
+    {
+        values.first().cloned().unwrap_or_default()
     } else {
-        interaction
-            .create_response(
-                &ctx.http,
-                serenity::CreateInteractionResponse::Message(
-                    serenity::CreateInteractionResponseMessage::new()
-                        .content("記録の選択メニューの作成に失敗しました")
-                        .ephemeral(true),
-                ),
-            )
-            .await?;
-    }
+        String::new()
+    };
+
+    let selected_date = match NaiveDate::parse_from_str(&selected_date_str, "%Y-%m-%d") {
+        Ok(date) => date,
+        Err(_) => {
+            return responder
+                .respond_error(AppError::user("無効な日付が選択されました"))
+                .await;
+        }
+    };
+
+    // Get user information
+    let user_id = interaction.user.id.to_string();
+    let username = interaction.user.name.clone();
+    let pool = &data.pool;
+
+    let user =
+        match queries::create_or_get_user(pool, &user_id, interaction.guild_id, &username).await {
+            Ok(user) => user,
+            Err(e) => {
+                return responder
+                    .respond_error(AppError::internal("ユーザー情報の取得に失敗しました", e))
+                    .await;
+            }
+        };
+
+    // Get records for the selected date
+    let records = match queries::get_records_by_date(pool, user.id, selected_date).await {
+        Ok(records) => records,
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("勤務記録の取得に失敗しました", e))
+                .await;
+        }
+    };
+
+    responder
+        .respond(serenity::CreateInteractionResponse::UpdateMessage(
+            edit_record_menu_message(
+                data.config.select_menu_page_size,
+                &user_id,
+                selected_date,
+                records,
+                0,
+            ),
+        ))
+        .await?;
 
     Ok(())
 }
 
 async fn handle_record_add(
-    ctx: &serenity::Context,
+    responder: &impl InteractionResponder,
     interaction: &serenity::ComponentInteraction,
     _data: &Data,
 ) -> Result<(), Error> {
@@ -211,22 +342,19 @@ async fn handle_record_add(
             .style(serenity::ButtonStyle::Secondary),
     ])];
 
-    interaction
-        .create_response(
-            &ctx.http,
-            serenity::CreateInteractionResponse::UpdateMessage(
-                serenity::CreateInteractionResponseMessage::new()
-                    .content("✅ **記録追加**: 追加する記録の種類を選択してください")
-                    .components(components),
-            ),
-        )
+    responder
+        .respond(serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new()
+                .content("✅ **記録追加**: 追加する記録の種類を選択してください")
+                .components(components),
+        ))
         .await?;
 
     Ok(())
 }
 
 async fn handle_delete_record_selection(
-    ctx: &serenity::Context,
+    responder: &impl InteractionResponder,
     interaction: &serenity::ComponentInteraction,
     data: &Data,
 ) -> Result<(), Error> {
@@ -236,25 +364,15 @@ async fn handle_delete_record_selection(
     let pool = &data.pool;
 
     // Get user from database
-    let user = match queries::create_or_get_user(pool, &user_id, &username).await {
-        Ok(user) => user,
-        Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "ユーザー情報の取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
-        }
-    };
+    let user =
+        match queries::create_or_get_user(pool, &user_id, interaction.guild_id, &username).await {
+            Ok(user) => user,
+            Err(e) => {
+                return responder
+                    .respond_error(AppError::internal("ユーザー情報の取得に失敗しました", e))
+                    .await;
+            }
+        };
 
     let current_date = get_current_date_jst();
 
@@ -262,170 +380,149 @@ async fn handle_delete_record_selection(
     let records = match queries::get_today_records(pool, user.id, current_date).await {
         Ok(records) => records,
         Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "勤務記録の取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
+            return responder
+                .respond_error(AppError::internal("勤務記録の取得に失敗しました", e))
+                .await;
         }
     };
 
-    let record_selector = RecordSelector::new(records);
-
-    if record_selector.is_empty() {
-        interaction
-            .create_response(
-                &ctx.http,
-                serenity::CreateInteractionResponse::Message(
+    match delete_record_menu_message(
+        data.config.select_menu_page_size,
+        &user_id,
+        current_date,
+        records,
+        0,
+    ) {
+        Some(message) => {
+            responder
+                .respond(serenity::CreateInteractionResponse::UpdateMessage(message))
+                .await?;
+        }
+        None => {
+            responder
+                .respond(serenity::CreateInteractionResponse::Message(
                     serenity::CreateInteractionResponseMessage::new()
                         .content("削除できる記録がありません")
                         .ephemeral(true),
-                ),
-            )
-            .await?;
-        return Ok(());
-    }
-
-    // Create select menu for record deletion
-    if let Some(select_menu) = record_selector.create_delete_select_menu("delete_record_select") {
-        let components = vec![serenity::CreateActionRow::SelectMenu(select_menu)];
-
-        interaction
-            .create_response(
-                &ctx.http,
-                serenity::CreateInteractionResponse::UpdateMessage(
-                    serenity::CreateInteractionResponseMessage::new()
-                        .content("🗑️ **記録削除**: 削除する記録を選択してください")
-                        .components(components),
-                ),
-            )
-            .await?;
-    } else {
-        interaction
-            .create_response(
-                &ctx.http,
-                serenity::CreateInteractionResponse::Message(
-                    serenity::CreateInteractionResponseMessage::new()
-                        .content("削除選択メニューの作成に失敗しました")
-                        .ephemeral(true),
-                ),
-            )
-            .await?;
+                ))
+                .await?;
+        }
     }
 
     Ok(())
 }
 
+/// 履歴の日付選択メニュー1ページあたりの件数（セレクトメニューの上限は25件）
+const HISTORY_PAGE_SIZE: usize = 20;
+
 async fn handle_history_view(
-    ctx: &serenity::Context,
+    responder: &impl InteractionResponder,
     interaction: &serenity::ComponentInteraction,
     data: &Data,
 ) -> Result<(), Error> {
+    // "history_view:user_id" or "history_page:user_id:page"
+    let page = interaction
+        .data
+        .custom_id
+        .split(':')
+        .nth(2)
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(0);
+
     // Get user information
     let user_id = interaction.user.id.to_string();
     let username = interaction.user.name.clone();
     let pool = &data.pool;
 
     // Get user from database
-    let user = match queries::create_or_get_user(pool, &user_id, &username).await {
-        Ok(user) => user,
-        Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "ユーザー情報の取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
-        }
-    };
+    let user =
+        match queries::create_or_get_user(pool, &user_id, interaction.guild_id, &username).await {
+            Ok(user) => user,
+            Err(e) => {
+                return responder
+                    .respond_error(AppError::internal("ユーザー情報の取得に失敗しました", e))
+                    .await;
+            }
+        };
 
     // Get available dates for history
     let available_dates = match queries::get_user_available_dates(pool, user.id).await {
         Ok(dates) => dates,
         Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "履歴データの取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
+            return responder
+                .respond_error(AppError::internal("履歴データの取得に失敗しました", e))
+                .await;
         }
     };
 
     if available_dates.is_empty() {
-        interaction
-            .create_response(
-                &ctx.http,
-                serenity::CreateInteractionResponse::Message(
-                    serenity::CreateInteractionResponseMessage::new()
-                        .content("📋 過去30日間に勤務記録がありません")
-                        .ephemeral(true),
-                ),
-            )
+        responder
+            .respond(serenity::CreateInteractionResponse::Message(
+                serenity::CreateInteractionResponseMessage::new()
+                    .content("📋 勤務記録がありません")
+                    .ephemeral(true),
+            ))
             .await?;
         return Ok(());
     }
 
-    // Create date selection menu
+    // Create date selection menu for the requested page
+    let total_pages = available_dates.len().div_ceil(HISTORY_PAGE_SIZE);
+    let page = page.min(total_pages - 1);
     let mut options = Vec::new();
 
-    for date in available_dates.iter().take(20) {
-        // Limit to 20 dates to avoid Discord limits
+    for date in available_dates
+        .iter()
+        .skip(page * HISTORY_PAGE_SIZE)
+        .take(HISTORY_PAGE_SIZE)
+    {
         let date_str = date.format("%Y-%m-%d").to_string();
         let display_str = format!("{} ({})", date.format("%Y/%m/%d"), get_weekday_jp(*date));
         options.push(serenity::CreateSelectMenuOption::new(display_str, date_str));
     }
 
     let select_menu = serenity::CreateSelectMenu::new(
-        "history_date_select",
+        format!("history_date_select:{}", user_id),
         serenity::CreateSelectMenuKind::String { options },
     )
     .placeholder("日付を選択してください");
 
-    let components = vec![serenity::CreateActionRow::SelectMenu(select_menu)];
+    let mut components = vec![serenity::CreateActionRow::SelectMenu(select_menu)];
+    if total_pages > 1 {
+        components.push(serenity::CreateActionRow::Buttons(vec![
+            serenity::CreateButton::new(format!(
+                "history_page:{}:{}",
+                user_id,
+                page.saturating_sub(1)
+            ))
+            .label("◀ 新しい日付")
+            .style(serenity::ButtonStyle::Secondary)
+            .disabled(page == 0),
+            serenity::CreateButton::new(format!("history_page:{}:{}", user_id, page + 1))
+                .label("古い日付 ▶")
+                .style(serenity::ButtonStyle::Secondary)
+                .disabled(page + 1 >= total_pages),
+        ]));
+    }
 
-    interaction
-        .create_response(
-            &ctx.http,
-            serenity::CreateInteractionResponse::UpdateMessage(
-                serenity::CreateInteractionResponseMessage::new()
-                    .content("📋 **履歴表示**: 表示する日付を選択してください")
-                    .components(components),
-            ),
-        )
+    responder
+        .respond(serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new()
+                .content(format!(
+                    "📋 **履歴表示**: 表示する日付を選択してください（{}/{}ページ）",
+                    page + 1,
+                    total_pages
+                ))
+                .components(components),
+        ))
         .await?;
 
     Ok(())
 }
 
 async fn handle_add_start_record(
-    ctx: &serenity::Context,
-    interaction: &serenity::ComponentInteraction,
+    responder: &impl InteractionResponder,
+    _interaction: &serenity::ComponentInteraction,
     _data: &Data,
 ) -> Result<(), Error> {
     let modal = serenity::CreateModal::new("add_start_modal", "開始記録追加").components(vec![
@@ -439,18 +536,19 @@ async fn handle_add_start_record(
             .required(true)
             .max_length(5),
         ),
+        create_record_date_input(),
     ]);
 
-    interaction
-        .create_response(&ctx.http, serenity::CreateInteractionResponse::Modal(modal))
+    responder
+        .respond(serenity::CreateInteractionResponse::Modal(modal))
         .await?;
 
     Ok(())
 }
 
 async fn handle_add_end_record(
-    ctx: &serenity::Context,
-    interaction: &serenity::ComponentInteraction,
+    responder: &impl InteractionResponder,
+    _interaction: &serenity::ComponentInteraction,
     _data: &Data,
 ) -> Result<(), Error> {
     let modal = serenity::CreateModal::new("add_end_modal", "終了記録追加").components(vec![
@@ -460,38 +558,177 @@ async fn handle_add_end_record(
                 .required(true)
                 .max_length(5),
         ),
+        create_record_date_input(),
     ]);
 
-    interaction
-        .create_response(&ctx.http, serenity::CreateInteractionResponse::Modal(modal))
+    responder
+        .respond(serenity::CreateInteractionResponse::Modal(modal))
         .await?;
 
     Ok(())
 }
 
+/// 記録追加モーダルの日付入力欄（今日の日付を初期値にする）
+fn create_record_date_input() -> serenity::CreateActionRow {
+    serenity::CreateActionRow::InputText(
+        serenity::CreateInputText::new(serenity::InputTextStyle::Short, "日付", "record_date")
+            .placeholder("YYYY-MM-DD 形式で入力 (例: 2024-04-01)")
+            .value(get_current_date_jst().format("%Y-%m-%d").to_string())
+            .required(true)
+            .max_length(10),
+    )
+}
+
 async fn handle_cancel_action(
-    ctx: &serenity::Context,
-    interaction: &serenity::ComponentInteraction,
+    responder: &impl InteractionResponder,
+    _interaction: &serenity::ComponentInteraction,
     _data: &Data,
 ) -> Result<(), Error> {
-    interaction
-        .create_response(
-            &ctx.http,
-            serenity::CreateInteractionResponse::UpdateMessage(
-                serenity::CreateInteractionResponseMessage::new()
-                    .content("操作をキャンセルしました")
-                    .components(vec![]),
-            ),
-        )
+    responder
+        .respond(serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new()
+                .content("操作をキャンセルしました")
+                .components(vec![]),
+        ))
         .await?;
 
     Ok(())
 }
 
+/// 時間修正の記録選択メニュー（custom_id に日付を載せ、ページを移動しても同じ日を表示する）
+fn edit_record_menu_message(
+    page_size: usize,
+    user_id: &str,
+    date: NaiveDate,
+    records: Vec<AttendanceRecord>,
+    page: usize,
+) -> serenity::CreateInteractionResponseMessage {
+    let record_selector = RecordSelector::new(records).with_page_size(page_size);
+    let select_id = format!("edit_record_select:{}:{}", user_id, date.format("%Y-%m-%d"));
+
+    match record_selector.create_select_menu(&select_id, "修正する記録を選択してください", page)
+    {
+        Some(select_menu) => serenity::CreateInteractionResponseMessage::new()
+            .content(format!(
+                "🕐 **時間修正**: {} ({}) の修正する記録を選択してください{}",
+                date.format("%Y/%m/%d"),
+                get_weekday_jp(date),
+                page_suffix(record_selector.page_summary(page, 0))
+            ))
+            .components(vec![serenity::CreateActionRow::SelectMenu(select_menu)]),
+        None => serenity::CreateInteractionResponseMessage::new()
+            .content(format!(
+                "🕐 {} ({}) の記録はありません",
+                date.format("%Y/%m/%d"),
+                get_weekday_jp(date)
+            ))
+            .components(vec![]),
+    }
+}
+
+/// 削除の記録選択メニュー（custom_id に日付を載せ、確認ボタンまで同じ日を対象にする）
+fn delete_record_menu_message(
+    page_size: usize,
+    user_id: &str,
+    date: NaiveDate,
+    records: Vec<AttendanceRecord>,
+    page: usize,
+) -> Option<serenity::CreateInteractionResponseMessage> {
+    let record_selector = RecordSelector::new(records).with_page_size(page_size);
+    let select_id = format!(
+        "delete_record_select:{}:{}",
+        user_id,
+        date.format("%Y-%m-%d")
+    );
+    let select_menu = record_selector.create_delete_select_menu(&select_id, page)?;
+
+    Some(
+        serenity::CreateInteractionResponseMessage::new()
+            .content(format!(
+                "🗑️ **記録削除**: 削除する記録を選択してください{}",
+                page_suffix(
+                    record_selector.page_summary(page, record_selector.delete_reserved_options())
+                )
+            ))
+            .components(vec![serenity::CreateActionRow::SelectMenu(select_menu)]),
+    )
+}
+
+fn page_suffix(summary: Option<String>) -> String {
+    summary
+        .map(|summary| format!("（{}）", summary))
+        .unwrap_or_default()
+}
+
+/// 選択メニューのページ移動（"page:N"）なら同じ日のメニューを表示し直す
+async fn handle_record_menu_page(
+    responder: &impl InteractionResponder,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+    page: usize,
+    delete: bool,
+) -> Result<(), Error> {
+    let user_id = interaction.user.id.to_string();
+    let pool = &data.pool;
+    let date = interaction
+        .data
+        .custom_id
+        .split(':')
+        .nth(2)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .unwrap_or_else(get_current_date_jst);
+
+    let records = match queries::create_or_get_user(
+        pool,
+        &user_id,
+        interaction.guild_id,
+        &interaction.user.name,
+    )
+    .await
+    {
+        Ok(user) => queries::get_records_by_date(pool, user.id, date).await,
+        Err(e) => Err(e),
+    };
+    let records = match records {
+        Ok(records) => records,
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("勤務記録の取得に失敗しました", e))
+                .await;
+        }
+    };
+
+    let message = if delete {
+        delete_record_menu_message(
+            data.config.select_menu_page_size,
+            &user_id,
+            date,
+            records,
+            page,
+        )
+        .unwrap_or_else(|| {
+            serenity::CreateInteractionResponseMessage::new()
+                .content("削除できる記録がありません")
+                .components(vec![])
+        })
+    } else {
+        edit_record_menu_message(
+            data.config.select_menu_page_size,
+            &user_id,
+            date,
+            records,
+            page,
+        )
+    };
+    responder
+        .respond(serenity::CreateInteractionResponse::UpdateMessage(message))
+        .await
+}
+
 async fn handle_edit_record_selected(
-    ctx: &serenity::Context,
+    responder: &impl InteractionResponder,
     interaction: &serenity::ComponentInteraction,
-    _data: &Data,
+    data: &Data,
 ) -> Result<(), Error> {
     let selected_record_id =
         if let serenity::ComponentInteractionDataKind::StringSelect { values } =
@@ -502,7 +739,22 @@ async fn handle_edit_record_selected(
             String::new()
         };
 
-    let modal = serenity::CreateModal::new("time_edit_modal", "時間修正").components(vec![
+    if let Some(page) = RecordSelector::parse_page_option(&selected_record_id) {
+        return handle_record_menu_page(responder, interaction, data, page, false).await;
+    }
+
+    let modal = create_time_edit_modal(&selected_record_id);
+
+    responder
+        .respond(serenity::CreateInteractionResponse::Modal(modal))
+        .await?;
+
+    Ok(())
+}
+
+/// 記録IDを埋め込んだ時間修正モーダルを作成
+pub fn create_time_edit_modal(record_id: &str) -> serenity::CreateModal {
+    serenity::CreateModal::new("time_edit_modal", "時間修正").components(vec![
         serenity::CreateActionRow::InputText(
             serenity::CreateInputText::new(
                 serenity::InputTextStyle::Short,
@@ -519,23 +771,27 @@ async fn handle_edit_record_selected(
                 "記録ID (変更不可)",
                 "record_id",
             )
-            .value(&selected_record_id)
+            .value(record_id)
             .required(false)
             .max_length(10),
         ),
-    ]);
-
-    interaction
-        .create_response(&ctx.http, serenity::CreateInteractionResponse::Modal(modal))
-        .await?;
-
-    Ok(())
+        serenity::CreateActionRow::InputText(
+            serenity::CreateInputText::new(
+                serenity::InputTextStyle::Paragraph,
+                "修正理由（任意）",
+                "reason",
+            )
+            .placeholder("例: 打刻を忘れて後から出勤したため")
+            .required(false)
+            .max_length(200),
+        ),
+    ])
 }
 
 async fn handle_delete_record_selected(
-    ctx: &serenity::Context,
+    responder: &impl InteractionResponder,
     interaction: &serenity::ComponentInteraction,
-    _data: &Data,
+    data: &Data,
 ) -> Result<(), Error> {
     let user_id = interaction.user.id.to_string();
 
@@ -547,18 +803,46 @@ async fn handle_delete_record_selected(
         String::new()
     };
 
-    let (content, button_id) = if selected_value == "delete_all" {
-        (
-            "すべての記録を削除しますか？",
-            format!("confirm_delete_all:{}", user_id),
-        )
+    if let Some(page) = RecordSelector::parse_page_option(&selected_value) {
+        return handle_record_menu_page(responder, interaction, data, page, true).await;
+    }
+
+    // 日付を読めない場合は今日とみなす
+    let date = interaction
+        .data
+        .custom_id
+        .split(':')
+        .nth(2)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .unwrap_or_else(get_current_date_jst);
+    let (content, record_id) = if selected_value == "delete_all" {
+        ("すべての記録を削除しますか？", None)
     } else {
-        // Include the record_id in the button for individual deletion
-        (
-            "選択した記録を削除しますか？",
-            format!("confirm_delete_single:{}:{}", user_id, selected_value),
-        )
+        match selected_value.parse::<i64>() {
+            Ok(record_id) => ("選択した記録を削除しますか？", Some(record_id)),
+            Err(_) => {
+                return responder
+                    .respond_error(AppError::user("無効な記録IDです"))
+                    .await;
+            }
+        }
+    };
+
+    let state_id = match flow_state::save(
+        &data.pool,
+        &user_id,
+        FlowState::DeleteRecords { date, record_id },
+    )
+    .await
+    {
+        Ok(state_id) => state_id,
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("操作の準備に失敗しました", e))
+                .await;
+        }
     };
+    let button_id = format!("confirm_delete:{}:{}", user_id, state_id);
 
     let components = vec![serenity::CreateActionRow::Buttons(vec![
         serenity::CreateButton::new(&button_id)
@@ -569,15 +853,12 @@ async fn handle_delete_record_selected(
             .style(serenity::ButtonStyle::Secondary),
     ])];
 
-    interaction
-        .create_response(
-            &ctx.http,
-            serenity::CreateInteractionResponse::UpdateMessage(
-                serenity::CreateInteractionResponseMessage::new()
-                    .content(format!("⚠️ **確認**: {}", content))
-                    .components(components),
-            ),
-        )
+    responder
+        .respond(serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new()
+                .content(format!("⚠️ **確認**: {}", content))
+                .components(components),
+        ))
         .await?;
 
     Ok(())
@@ -588,22 +869,31 @@ pub async fn handle_status_modal(
     interaction: &serenity::ModalInteraction,
     data: &Data,
 ) -> Result<(), Error> {
+    let responder = ModalResponder::new(ctx, interaction);
     let custom_id = &interaction.data.custom_id;
 
     match custom_id.as_str() {
-        "time_edit_modal" => handle_time_edit_modal(ctx, interaction, data).await,
-        "add_start_modal" => handle_add_start_modal(ctx, interaction, data).await,
-        "add_end_modal" => handle_add_end_modal(ctx, interaction, data).await,
+        "time_edit_modal" => handle_time_edit_modal(&responder, &ctx.http, interaction, data).await,
+        "add_start_modal" => handle_add_start_modal(&responder, &ctx.http, interaction, data).await,
+        "add_end_modal" => handle_add_end_modal(&responder, &ctx.http, interaction, data).await,
+        "note_modal" => note_buttons::handle_note_modal(&responder, interaction, data).await,
+        id if id.starts_with("end_note_modal:") => {
+            note_buttons::handle_end_note_modal(&responder, &ctx.http, interaction, data).await
+        }
+        id if id.starts_with("long_session_end_modal:") => {
+            long_session_buttons::handle_end_at_modal(&responder, &ctx.http, interaction, data)
+                .await
+        }
+        id if id.starts_with("guild_reset_modal:") => {
+            reset_buttons::handle_reset_modal(&responder, &ctx.http, interaction, data).await
+        }
         _ => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content("未実装のモーダルです")
-                            .ephemeral(true),
-                    ),
-                )
+            responder
+                .respond(serenity::CreateInteractionResponse::Message(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .content("未実装のモーダルです")
+                        .ephemeral(true),
+                ))
                 .await?;
             Ok(())
         }
@@ -611,7 +901,8 @@ pub async fn handle_status_modal(
 }
 
 async fn handle_time_edit_modal(
-    ctx: &serenity::Context,
+    responder: &impl InteractionResponder,
+    http: &serenity::Http,
     interaction: &serenity::ModalInteraction,
     data: &Data,
 ) -> Result<(), Error> {
@@ -649,17 +940,9 @@ async fn handle_time_edit_modal(
     let record_id = match record_id_str.parse::<i64>() {
         Ok(id) => id,
         Err(_) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message("無効な記録IDです"))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
+            return responder
+                .respond_error(AppError::user("無効な記録IDです"))
+                .await;
         }
     };
 
@@ -667,142 +950,144 @@ async fn handle_time_edit_modal(
     let new_time = match validate_time_format(time_input) {
         Ok(time) => time,
         Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&e.to_string()))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
+            return responder.respond_error(AppError::user(e.to_string())).await;
         }
     };
 
-    // Combine with current date in JST
-    let current_date = get_current_date_jst();
-    let new_datetime = combine_date_time_jst(current_date, new_time);
-
     // Get current records for validation
     let user_id = interaction.user.id.to_string();
     let username = interaction.user.name.clone();
     let pool = &data.pool;
 
-    let user = match queries::create_or_get_user(pool, &user_id, &username).await {
-        Ok(user) => user,
-        Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "ユーザー情報の取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
+    let user =
+        match queries::create_or_get_user(pool, &user_id, interaction.guild_id, &username).await {
+            Ok(user) => user,
+            Err(e) => {
+                return responder
+                    .respond_error(AppError::internal("ユーザー情報の取得に失敗しました", e))
+                    .await;
+            }
+        };
+
+    // The record keeps its own date; only the time of day changes
+    let record_being_modified = match queries::get_attendance_record_by_id(pool, record_id).await {
+        Ok(record) if record.user_id == user.id => record,
+        _ => {
+            return responder
+                .respond_error(AppError::user("指定された記録が見つかりません"))
+                .await;
         }
     };
+    let target_date = get_date_from_utc_timestamp(record_being_modified.timestamp);
+    let new_datetime = combine_date_time_jst(target_date, new_time);
 
-    let existing_records = match queries::get_today_records(pool, user.id, current_date).await {
+    let existing_records = match queries::get_records_by_date(pool, user.id, target_date).await {
         Ok(records) => records,
         Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "記録の取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
+            return responder
+                .respond_error(AppError::internal("記録の取得に失敗しました", e))
+                .await;
         }
     };
 
-    // Get the record being modified to determine its type
-    let record_being_modified = existing_records.iter().find(|r| r.id == record_id);
-
-    if let Some(record) = record_being_modified {
-        let record_type = RecordType::from(record.record_type.clone());
-
-        // Validate the modification
-        if let Err(e) = RecordValidator::validate_new_record(
-            &existing_records,
-            record_type,
-            new_datetime,
-            current_date,
-            Some(record_id),
-        ) {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&e.to_string()))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
-        }
+    // Validate the modification
+    let is_admin = interaction
+        .member
+        .as_ref()
+        .is_some_and(|member| member_is_admin(member, &data.config));
+    let policy = edit_policy::load(pool, interaction.guild_id, is_admin).await;
+    let record_type = record_being_modified.record_type;
+    if let Err(e) = RecordValidator::validate_new_record(
+        &existing_records,
+        record_type,
+        new_datetime,
+        target_date,
+        Some(record_id),
+        &policy,
+    ) {
+        metrics::record_validation_rejection(pool, interaction.guild_id, &e).await;
+        suggestion_buttons::respond_validation_error(
+            responder,
+            interaction.user.id,
+            &e,
+            &record_id.to_string(),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    // Optional reason kept in the record's revision history
+    let reason = interaction
+        .data
+        .components
+        .get(2)
+        .and_then(|row| row.components.first())
+        .and_then(|component| {
+            if let serenity::ActionRowComponent::InputText(input) = component {
+                input.value.as_deref()
+            } else {
+                None
+            }
+        });
+
+    let audit = AuditSource::new(interaction.user.id, "status:edit_time")
+        .in_guild(interaction.guild_id)
+        .with_reason(reason);
+    let change = RecordChange {
+        record_id: Some(record_id),
+        record_type,
+        old_timestamp: Some(record_being_modified.timestamp),
+        new_timestamp: new_datetime,
+        delete: false,
+    };
+    if submit_for_approval(
+        responder,
+        http,
+        pool,
+        interaction.guild_id,
+        &user,
+        change,
+        &audit,
+    )
+    .await?
+    {
+        return Ok(());
     }
 
     // Update the record
-    match queries::update_attendance_record_time(pool, record_id, new_datetime).await {
+    match queries::update_attendance_record_time(pool, record_id, new_datetime, &audit).await {
         Ok(()) => {
-            // Recalculate sessions after modification
+            // Recalculate sessions for the modified date
             let session_manager = SessionManager::new(pool.clone());
-            let user_discord_id = interaction.user.id.to_string();
-            let username = interaction.user.name.clone();
-
-            if let Ok(user) = queries::create_or_get_user(pool, &user_discord_id, &username).await {
-                if let Err(e) = session_manager
-                    .trigger_recalculation(user.id, current_date)
-                    .await
-                {
-                    tracing::error!("Failed to recalculate sessions: {}", e);
-                }
+            if let Err(e) = session_manager
+                .trigger_recalculation(user.id, target_date)
+                .await
+            {
+                tracing::error!("Failed to recalculate sessions: {}", e);
             }
 
             let embed = create_success_embed(
                 "時間修正完了",
-                &format!("記録の時間を{}に修正しました", time_input),
+                &format!(
+                    "{}の記録の時間を{}に修正しました",
+                    target_date.format("%Y年%m月%d日"),
+                    time_input
+                ),
             );
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .embed(embed)
-                            .ephemeral(true),
-                    ),
-                )
+            responder
+                .respond(serenity::CreateInteractionResponse::Message(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .embed(embed)
+                        .components(vec![serenity::CreateActionRow::Buttons(vec![
+                            record_revisions_button(&interaction.user.id.to_string(), record_id),
+                        ])])
+                        .ephemeral(true),
+                ))
                 .await?;
         }
         Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "時間修正に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
+            responder
+                .respond_error(AppError::internal("時間修正に失敗しました", e))
                 .await?;
         }
     }
@@ -811,7 +1096,8 @@ async fn handle_time_edit_modal(
 }
 
 async fn handle_add_start_modal(
-    ctx: &serenity::Context,
+    responder: &impl InteractionResponder,
+    http: &serenity::Http,
     interaction: &serenity::ModalInteraction,
     data: &Data,
 ) -> Result<(), Error> {
@@ -829,21 +1115,33 @@ async fn handle_add_start_modal(
         })
         .unwrap_or("");
 
+    let date_input = interaction
+        .data
+        .components
+        .get(1)
+        .and_then(|row| row.components.first())
+        .and_then(|component| {
+            if let serenity::ActionRowComponent::InputText(input) = component {
+                input.value.as_deref()
+            } else {
+                None
+            }
+        })
+        .unwrap_or("");
+
     // Validate time format
     let new_time = match validate_time_format(time_input) {
         Ok(time) => time,
         Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&e.to_string()))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
+            return responder.respond_error(AppError::user(e.to_string())).await;
+        }
+    };
+
+    // Validate date format
+    let target_date = match validate_date_format(date_input) {
+        Ok(date) => date,
+        Err(e) => {
+            return responder.respond_error(AppError::user(e.to_string())).await;
         }
     };
 
@@ -853,79 +1151,91 @@ async fn handle_add_start_modal(
     let pool = &data.pool;
 
     // Get user from database
-    let user = match queries::create_or_get_user(pool, &user_id, &username).await {
-        Ok(user) => user,
-        Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "ユーザー情報の取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
-        }
-    };
+    let user =
+        match queries::create_or_get_user(pool, &user_id, interaction.guild_id, &username).await {
+            Ok(user) => user,
+            Err(e) => {
+                return responder
+                    .respond_error(AppError::internal("ユーザー情報の取得に失敗しました", e))
+                    .await;
+            }
+        };
+    let _punch_guard = punch_lock::lock(user.id).await;
 
-    // Combine with current date in JST
-    let current_date = get_current_date_jst();
-    let new_datetime = combine_date_time_jst(current_date, new_time);
+    // Combine with the entered date in JST
+    let new_datetime = combine_date_time_jst(target_date, new_time);
 
     // Get existing records for validation
-    let existing_records = match queries::get_today_records(pool, user.id, current_date).await {
+    let existing_records = match queries::get_records_by_date(pool, user.id, target_date).await {
         Ok(records) => records,
         Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "記録の取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
+            return responder
+                .respond_error(AppError::internal("記録の取得に失敗しました", e))
+                .await;
         }
     };
 
     // Validate the new start record
+    let is_admin = interaction
+        .member
+        .as_ref()
+        .is_some_and(|member| member_is_admin(member, &data.config));
+    let policy = edit_policy::load(pool, interaction.guild_id, is_admin).await;
     if let Err(e) = RecordValidator::validate_new_record(
         &existing_records,
         RecordType::Start,
         new_datetime,
-        current_date,
+        target_date,
         None,
+        &policy,
     ) {
-        interaction
-            .create_response(
-                &ctx.http,
-                serenity::CreateInteractionResponse::Message(
-                    serenity::CreateInteractionResponseMessage::new()
-                        .content(format_error_message(&e.to_string()))
-                        .ephemeral(true),
-                ),
-            )
+        metrics::record_validation_rejection(pool, interaction.guild_id, &e).await;
+        suggestion_buttons::respond_validation_error(responder, interaction.user.id, &e, "start")
             .await?;
         return Ok(());
     }
 
+    let audit =
+        AuditSource::new(interaction.user.id, "status:add_start").in_guild(interaction.guild_id);
+    let change = RecordChange {
+        record_id: None,
+        record_type: RecordType::Start,
+        old_timestamp: None,
+        new_timestamp: new_datetime,
+        delete: false,
+    };
+    if submit_for_approval(
+        responder,
+        http,
+        pool,
+        interaction.guild_id,
+        &user,
+        change,
+        &audit,
+    )
+    .await?
+    {
+        return Ok(());
+    }
+
     // Create attendance record
-    match queries::create_attendance_record(pool, user.id, RecordType::Start, new_datetime).await {
+    match queries::create_attendance_record(pool, user.id, RecordType::Start, new_datetime, &audit)
+        .await
+    {
         Ok(_) => {
+            webhooks::notify_record(
+                pool,
+                interaction.guild_id,
+                &user,
+                RecordType::Start,
+                new_datetime,
+                &audit,
+            )
+            .await;
             // Recalculate sessions after adding record
             let session_manager = SessionManager::new(pool.clone());
             if let Err(e) = session_manager
-                .trigger_recalculation(user.id, current_date)
+                .trigger_recalculation(user.id, target_date)
                 .await
             {
                 tracing::error!("Failed to recalculate sessions: {}", e);
@@ -933,32 +1243,23 @@ async fn handle_add_start_modal(
 
             let embed = create_success_embed(
                 "記録追加完了",
-                &format!("開始記録を{}に追加しました", time_input),
+                &format!(
+                    "開始記録を{} {}に追加しました",
+                    target_date.format("%Y年%m月%d日"),
+                    time_input
+                ),
             );
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .embed(embed)
-                            .ephemeral(true),
-                    ),
-                )
+            responder
+                .respond(serenity::CreateInteractionResponse::Message(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .embed(embed)
+                        .ephemeral(true),
+                ))
                 .await?;
         }
         Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "開始記録の追加に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
+            responder
+                .respond_error(AppError::internal("開始記録の追加に失敗しました", e))
                 .await?;
         }
     }
@@ -967,7 +1268,8 @@ async fn handle_add_start_modal(
 }
 
 async fn handle_add_end_modal(
-    ctx: &serenity::Context,
+    responder: &impl InteractionResponder,
+    http: &serenity::Http,
     interaction: &serenity::ModalInteraction,
     data: &Data,
 ) -> Result<(), Error> {
@@ -985,21 +1287,33 @@ async fn handle_add_end_modal(
         })
         .unwrap_or("");
 
+    let date_input = interaction
+        .data
+        .components
+        .get(1)
+        .and_then(|row| row.components.first())
+        .and_then(|component| {
+            if let serenity::ActionRowComponent::InputText(input) = component {
+                input.value.as_deref()
+            } else {
+                None
+            }
+        })
+        .unwrap_or("");
+
     // Validate time format
     let new_time = match validate_time_format(time_input) {
         Ok(time) => time,
         Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&e.to_string()))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
+            return responder.respond_error(AppError::user(e.to_string())).await;
+        }
+    };
+
+    // Validate date format
+    let target_date = match validate_date_format(date_input) {
+        Ok(date) => date,
+        Err(e) => {
+            return responder.respond_error(AppError::user(e.to_string())).await;
         }
     };
 
@@ -1009,79 +1323,91 @@ async fn handle_add_end_modal(
     let pool = &data.pool;
 
     // Get user from database
-    let user = match queries::create_or_get_user(pool, &user_id, &username).await {
-        Ok(user) => user,
-        Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "ユーザー情報の取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
-        }
-    };
+    let user =
+        match queries::create_or_get_user(pool, &user_id, interaction.guild_id, &username).await {
+            Ok(user) => user,
+            Err(e) => {
+                return responder
+                    .respond_error(AppError::internal("ユーザー情報の取得に失敗しました", e))
+                    .await;
+            }
+        };
+    let _punch_guard = punch_lock::lock(user.id).await;
 
-    // Combine with current date in JST
-    let current_date = get_current_date_jst();
-    let new_datetime = combine_date_time_jst(current_date, new_time);
+    // Combine with the entered date in JST
+    let new_datetime = combine_date_time_jst(target_date, new_time);
 
     // Get existing records for validation
-    let existing_records = match queries::get_today_records(pool, user.id, current_date).await {
+    let existing_records = match queries::get_records_by_date(pool, user.id, target_date).await {
         Ok(records) => records,
         Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "記録の取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
+            return responder
+                .respond_error(AppError::internal("記録の取得に失敗しました", e))
+                .await;
         }
     };
 
     // Validate the new end record
+    let is_admin = interaction
+        .member
+        .as_ref()
+        .is_some_and(|member| member_is_admin(member, &data.config));
+    let policy = edit_policy::load(pool, interaction.guild_id, is_admin).await;
     if let Err(e) = RecordValidator::validate_new_record(
         &existing_records,
         RecordType::End,
         new_datetime,
-        current_date,
+        target_date,
         None,
+        &policy,
     ) {
-        interaction
-            .create_response(
-                &ctx.http,
-                serenity::CreateInteractionResponse::Message(
-                    serenity::CreateInteractionResponseMessage::new()
-                        .content(format_error_message(&e.to_string()))
-                        .ephemeral(true),
-                ),
-            )
+        metrics::record_validation_rejection(pool, interaction.guild_id, &e).await;
+        suggestion_buttons::respond_validation_error(responder, interaction.user.id, &e, "end")
             .await?;
         return Ok(());
     }
 
+    let audit =
+        AuditSource::new(interaction.user.id, "status:add_end").in_guild(interaction.guild_id);
+    let change = RecordChange {
+        record_id: None,
+        record_type: RecordType::End,
+        old_timestamp: None,
+        new_timestamp: new_datetime,
+        delete: false,
+    };
+    if submit_for_approval(
+        responder,
+        http,
+        pool,
+        interaction.guild_id,
+        &user,
+        change,
+        &audit,
+    )
+    .await?
+    {
+        return Ok(());
+    }
+
     // Create attendance record
-    match queries::create_attendance_record(pool, user.id, RecordType::End, new_datetime).await {
+    match queries::create_attendance_record(pool, user.id, RecordType::End, new_datetime, &audit)
+        .await
+    {
         Ok(_) => {
+            webhooks::notify_record(
+                pool,
+                interaction.guild_id,
+                &user,
+                RecordType::End,
+                new_datetime,
+                &audit,
+            )
+            .await;
             // Recalculate sessions after adding record
             let session_manager = SessionManager::new(pool.clone());
             if let Err(e) = session_manager
-                .trigger_recalculation(user.id, current_date)
+                .trigger_recalculation(user.id, target_date)
                 .await
             {
                 tracing::error!("Failed to recalculate sessions: {}", e);
@@ -1089,32 +1415,23 @@ async fn handle_add_end_modal(
 
             let embed = create_success_embed(
                 "記録追加完了",
-                &format!("終了記録を{}に追加しました", time_input),
+                &format!(
+                    "終了記録を{} {}に追加しました",
+                    target_date.format("%Y年%m月%d日"),
+                    time_input
+                ),
             );
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .embed(embed)
-                            .ephemeral(true),
-                    ),
-                )
+            responder
+                .respond(serenity::CreateInteractionResponse::Message(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .embed(embed)
+                        .ephemeral(true),
+                ))
                 .await?;
         }
         Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "終了記録の追加に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
+            responder
+                .respond_error(AppError::internal("終了記録の追加に失敗しました", e))
                 .await?;
         }
     }
@@ -1122,168 +1439,225 @@ async fn handle_add_end_modal(
     Ok(())
 }
 
-async fn handle_confirm_delete_single(
-    ctx: &serenity::Context,
-    interaction: &serenity::ComponentInteraction,
-    data: &Data,
-) -> Result<(), Error> {
-    // Parse custom_id to get record_id: "confirm_delete_single:user_id:record_id"
-    let custom_id = &interaction.data.custom_id;
-    let parts: Vec<&str> = custom_id.split(':').collect();
+/// 承認制のギルドでは承認依頼を投稿して応答する（応答済みなら true）
+async fn submit_for_approval(
+    responder: &impl InteractionResponder,
+    http: &serenity::Http,
+    pool: &crate::database::DbPool,
+    guild_id: Option<serenity::GuildId>,
+    user: &crate::database::models::User,
+    change: RecordChange,
+    audit: &AuditSource,
+) -> Result<bool, Error> {
+    let message =
+        match approvals::submit_if_required(http, pool, guild_id, user, change, audit).await {
+            Ok(false) => return Ok(false),
+            Ok(true) => {
+                serenity::CreateInteractionResponseMessage::new().embed(approvals::pending_embed())
+            }
+            Err(e) => {
+                responder
+                    .respond_error(AppError::internal("承認依頼の送信に失敗しました", e))
+                    .await?;
+                return Ok(true);
+            }
+        };
+    responder
+        .respond(serenity::CreateInteractionResponse::Message(
+            message.ephemeral(true),
+        ))
+        .await?;
+    Ok(true)
+}
 
-    let record_id = if parts.len() >= 3 {
-        match parts[2].parse::<i64>() {
-            Ok(id) => id,
-            Err(_) => {
-                interaction
-                    .create_response(
-                        &ctx.http,
-                        serenity::CreateInteractionResponse::Message(
-                            serenity::CreateInteractionResponseMessage::new()
-                                .content(format_error_message("無効な記録IDです"))
-                                .ephemeral(true),
-                        ),
-                    )
+/// 削除に承認が必要なギルドでは記録ごとに削除を申請して応答する（応答済みなら true）
+async fn submit_deletions(
+    responder: &impl InteractionResponder,
+    http: &serenity::Http,
+    pool: &crate::database::DbPool,
+    guild_id: Option<serenity::GuildId>,
+    user: &crate::database::models::User,
+    changes: &[RecordChange],
+    audit: &AuditSource,
+) -> Result<bool, Error> {
+    let mut submitted = false;
+    for change in changes {
+        match approvals::submit_if_required(http, pool, guild_id, user, *change, audit).await {
+            Ok(true) => submitted = true,
+            // 承認チャンネルがなければそのまま削除する
+            Ok(false) => return Ok(false),
+            Err(e) => {
+                responder
+                    .respond_error(AppError::internal("承認依頼の送信に失敗しました", e))
                     .await?;
-                return Ok(());
+                return Ok(true);
             }
         }
-    } else {
-        interaction
-            .create_response(
-                &ctx.http,
-                serenity::CreateInteractionResponse::Message(
-                    serenity::CreateInteractionResponseMessage::new()
-                        .content(format_error_message("記録IDが指定されていません"))
-                        .ephemeral(true),
-                ),
-            )
-            .await?;
-        return Ok(());
+    }
+    if !submitted {
+        return Ok(false);
+    }
+    responder
+        .respond(serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new()
+                .embed(approvals::pending_delete_embed())
+                .components(vec![]),
+        ))
+        .await?;
+    Ok(true)
+}
+
+/// 削除の確認ボタン（custom_id: "confirm_delete:user_id:state_id"）
+///
+/// 旧形式の "confirm_delete_single:user_id:record_id" / "confirm_delete_all:user_id" は今日の記録として扱う
+async fn handle_confirm_delete(
+    responder: &impl InteractionResponder,
+    http: &serenity::Http,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let parts: Vec<&str> = interaction.data.custom_id.split(':').collect();
+    let state = match parts[0] {
+        "confirm_delete_all" => Some(FlowState::DeleteRecords {
+            date: get_current_date_jst(),
+            record_id: None,
+        }),
+        "confirm_delete_single" => {
+            parts
+                .get(2)
+                .and_then(|id| id.parse::<i64>().ok())
+                .map(|record_id| FlowState::DeleteRecords {
+                    date: get_current_date_jst(),
+                    record_id: Some(record_id),
+                })
+        }
+        _ => match parts.get(2).and_then(|id| id.parse::<i64>().ok()) {
+            Some(state_id) => {
+                flow_state::take(&data.pool, state_id, &interaction.user.id.to_string())
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::error!("Failed to load interaction state: {}", e);
+                        None
+                    })
+            }
+            None => None,
+        },
     };
 
+    match state {
+        Some(FlowState::DeleteRecords {
+            date,
+            record_id: Some(record_id),
+        }) => confirm_delete_single(responder, http, interaction, data, date, record_id).await,
+        Some(FlowState::DeleteRecords {
+            date,
+            record_id: None,
+        }) => confirm_delete_all(responder, http, interaction, data, date).await,
+        // 削除確認のIDで他の操作の状態は読めない（`take` 済みなので期限切れとして扱う）
+        Some(FlowState::ResetGuild { .. }) | None => {
+            responder
+                .respond_error(AppError::user(
+                    "この操作は期限切れです。もう一度 `/status` から操作してください",
+                ))
+                .await
+        }
+    }
+}
+
+/// 1件の記録を削除（`date` は選択メニューを表示した日）
+async fn confirm_delete_single(
+    responder: &impl InteractionResponder,
+    http: &serenity::Http,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+    date: NaiveDate,
+    record_id: i64,
+) -> Result<(), Error> {
     // Get user information
     let user_id = interaction.user.id.to_string();
     let username = interaction.user.name.clone();
     let pool = &data.pool;
 
     // Get user from database
-    let user = match queries::create_or_get_user(pool, &user_id, &username).await {
-        Ok(user) => user,
-        Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "ユーザー情報の取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
-        }
-    };
-
-    let current_date = get_current_date_jst();
+    let user =
+        match queries::create_or_get_user(pool, &user_id, interaction.guild_id, &username).await {
+            Ok(user) => user,
+            Err(e) => {
+                return responder
+                    .respond_error(AppError::internal("ユーザー情報の取得に失敗しました", e))
+                    .await;
+            }
+        };
 
     // Get the specific record to verify it belongs to this user
-    let records = match queries::get_today_records(pool, user.id, current_date).await {
+    let records = match queries::get_today_records(pool, user.id, date).await {
         Ok(records) => records,
         Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "勤務記録の取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
+            return responder
+                .respond_error(AppError::internal("勤務記録の取得に失敗しました", e))
+                .await;
         }
     };
 
     // Verify the record exists and belongs to this user
-    let record_exists = records.iter().any(|record| record.id == record_id);
-    if !record_exists {
-        interaction
-            .create_response(
-                &ctx.http,
-                serenity::CreateInteractionResponse::Message(
-                    serenity::CreateInteractionResponseMessage::new()
-                        .content(format_error_message("指定された記録が見つかりません"))
-                        .ephemeral(true),
-                ),
-            )
-            .await?;
-        return Ok(());
-    }
+    let Some(record) = records.iter().find(|record| record.id == record_id) else {
+        return responder
+            .respond_error(AppError::user("指定された記録が見つかりません"))
+            .await;
+    };
 
-    // Delete the specific record using the simple queries
-    match sqlx::query("DELETE FROM attendance_records WHERE id = ? AND user_id = ?")
-        .bind(record_id)
-        .bind(user.id)
-        .execute(pool)
-        .await
-    {
-        Ok(result) => {
-            if result.rows_affected() > 0 {
+    // Delete the specific record
+    let audit = AuditSource::new(interaction.user.id, "status:delete_single")
+        .in_guild(interaction.guild_id);
+    let is_admin = interaction
+        .member
+        .as_ref()
+        .is_some_and(|member| member_is_admin(member, &data.config));
+    let policy = edit_policy::load(pool, interaction.guild_id, is_admin).await;
+    if policy.delete_requires_approval {
+        let change = RecordChange::deletion(record.id, record.record_type, record.timestamp);
+        if submit_deletions(
+            responder,
+            http,
+            pool,
+            interaction.guild_id,
+            &user,
+            &[change],
+            &audit,
+        )
+        .await?
+        {
+            return Ok(());
+        }
+    }
+    match queries::delete_attendance_record(pool, user.id, record_id, &audit).await {
+        Ok(deleted) => {
+            if deleted {
                 // Recalculate sessions after deletion
                 let session_manager = SessionManager::new(pool.clone());
-                if let Err(e) = session_manager
-                    .trigger_recalculation(user.id, current_date)
-                    .await
-                {
+                if let Err(e) = session_manager.trigger_recalculation(user.id, date).await {
                     tracing::error!("Failed to recalculate sessions: {}", e);
                 }
 
                 let embed = create_success_embed("削除完了", "選択した記録を削除しました");
-                interaction
-                    .create_response(
-                        &ctx.http,
-                        serenity::CreateInteractionResponse::UpdateMessage(
-                            serenity::CreateInteractionResponseMessage::new()
-                                .embed(embed)
-                                .components(vec![]),
-                        ),
-                    )
+                responder
+                    .respond(serenity::CreateInteractionResponse::UpdateMessage(
+                        serenity::CreateInteractionResponseMessage::new()
+                            .embed(embed)
+                            .components(vec![]),
+                    ))
                     .await?;
             } else {
-                interaction
-                    .create_response(
-                        &ctx.http,
-                        serenity::CreateInteractionResponse::Message(
-                            serenity::CreateInteractionResponseMessage::new()
-                                .content(format_error_message(
-                                    "記録の削除に失敗しました（記録が見つかりません）",
-                                ))
-                                .ephemeral(true),
-                        ),
-                    )
+                responder
+                    .respond_error(AppError::user(
+                        "記録の削除に失敗しました（記録が見つかりません）",
+                    ))
                     .await?;
             }
         }
         Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "記録の削除に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
+            responder
+                .respond_error(AppError::internal("記録の削除に失敗しました", e))
                 .await?;
         }
     }
@@ -1291,10 +1665,13 @@ async fn handle_confirm_delete_single(
     Ok(())
 }
 
-async fn handle_confirm_delete_all(
-    ctx: &serenity::Context,
+/// 指定日のすべての記録を削除
+async fn confirm_delete_all(
+    responder: &impl InteractionResponder,
+    http: &serenity::Http,
     interaction: &serenity::ComponentInteraction,
     data: &Data,
+    date: NaiveDate,
 ) -> Result<(), Error> {
     // Get user information
     let user_id = interaction.user.id.to_string();
@@ -1302,65 +1679,77 @@ async fn handle_confirm_delete_all(
     let pool = &data.pool;
 
     // Get user from database
-    let user = match queries::create_or_get_user(pool, &user_id, &username).await {
-        Ok(user) => user,
-        Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "ユーザー情報の取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
+    let user =
+        match queries::create_or_get_user(pool, &user_id, interaction.guild_id, &username).await {
+            Ok(user) => user,
+            Err(e) => {
+                return responder
+                    .respond_error(AppError::internal("ユーザー情報の取得に失敗しました", e))
+                    .await;
+            }
+        };
+
+    // Delete all records for the date
+    let audit =
+        AuditSource::new(interaction.user.id, "status:delete_all").in_guild(interaction.guild_id);
+    let is_admin = interaction
+        .member
+        .as_ref()
+        .is_some_and(|member| member_is_admin(member, &data.config));
+    let policy = edit_policy::load(pool, interaction.guild_id, is_admin).await;
+    if policy.delete_requires_approval {
+        let records = match queries::get_records_by_date(pool, user.id, date).await {
+            Ok(records) => records,
+            Err(e) => {
+                return responder
+                    .respond_error(AppError::internal("勤務記録の取得に失敗しました", e))
+                    .await;
+            }
+        };
+        let changes: Vec<_> = records
+            .iter()
+            .map(|record| RecordChange::deletion(record.id, record.record_type, record.timestamp))
+            .collect();
+        if submit_deletions(
+            responder,
+            http,
+            pool,
+            interaction.guild_id,
+            &user,
+            &changes,
+            &audit,
+        )
+        .await?
+        {
             return Ok(());
         }
-    };
-
-    let current_date = get_current_date_jst();
-
-    // Delete all records for today
-    match queries::delete_all_user_records_for_date(pool, user.id, current_date).await {
+    }
+    match queries::delete_all_user_records_for_date(pool, user.id, date, &audit).await {
         Ok(()) => {
             // Recalculate sessions after deletion
             let session_manager = SessionManager::new(pool.clone());
-            if let Err(e) = session_manager
-                .trigger_recalculation(user.id, current_date)
-                .await
-            {
+            if let Err(e) = session_manager.trigger_recalculation(user.id, date).await {
                 tracing::error!("Failed to recalculate sessions: {}", e);
             }
 
-            let embed = create_success_embed("削除完了", "当日のすべての記録を削除しました");
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::UpdateMessage(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .embed(embed)
-                            .components(vec![]),
-                    ),
-                )
+            let embed = create_success_embed(
+                "削除完了",
+                &format!(
+                    "{}のすべての記録を削除しました",
+                    date.format("%Y年%m月%d日")
+                ),
+            );
+            responder
+                .respond(serenity::CreateInteractionResponse::UpdateMessage(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .embed(embed)
+                        .components(vec![]),
+                ))
                 .await?;
         }
         Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "記録の削除に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
+            responder
+                .respond_error(AppError::internal("記録の削除に失敗しました", e))
                 .await?;
         }
     }
@@ -1369,7 +1758,7 @@ async fn handle_confirm_delete_all(
 }
 
 async fn handle_history_date_selected(
-    ctx: &serenity::Context,
+    responder: &impl InteractionResponder,
     interaction: &serenity::ComponentInteraction,
     data: &Data,
 ) -> Result<(), Error> {
@@ -1385,17 +1774,9 @@ async fn handle_history_date_selected(
     let selected_date = match chrono::NaiveDate::parse_from_str(&selected_date_str, "%Y-%m-%d") {
         Ok(date) => date,
         Err(_) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message("無効な日付が選択されました"))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
+            return responder
+                .respond_error(AppError::user("無効な日付が選択されました"))
+                .await;
         }
     };
 
@@ -1404,82 +1785,234 @@ async fn handle_history_date_selected(
     let username = interaction.user.name.clone();
     let pool = &data.pool;
 
-    let user = match queries::create_or_get_user(pool, &user_id, &username).await {
-        Ok(user) => user,
-        Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "ユーザー情報の取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
-        }
-    };
+    let user =
+        match queries::create_or_get_user(pool, &user_id, interaction.guild_id, &username).await {
+            Ok(user) => user,
+            Err(e) => {
+                return responder
+                    .respond_error(AppError::internal("ユーザー情報の取得に失敗しました", e))
+                    .await;
+            }
+        };
 
     // Get records for the selected date
     let records = match queries::get_records_by_date(pool, user.id, selected_date).await {
         Ok(records) => records,
         Err(e) => {
-            interaction
-                .create_response(
-                    &ctx.http,
-                    serenity::CreateInteractionResponse::Message(
-                        serenity::CreateInteractionResponseMessage::new()
-                            .content(format_error_message(&format!(
-                                "記録の取得に失敗しました: {}",
-                                e
-                            )))
-                            .ephemeral(true),
-                    ),
-                )
-                .await?;
-            return Ok(());
+            return responder
+                .respond_error(AppError::internal("記録の取得に失敗しました", e))
+                .await;
         }
     };
 
     if records.is_empty() {
-        interaction
-            .create_response(
-                &ctx.http,
-                serenity::CreateInteractionResponse::UpdateMessage(
-                    serenity::CreateInteractionResponseMessage::new()
-                        .content(format!(
-                            "📋 {} ({}) の記録はありません",
-                            selected_date.format("%Y/%m/%d"),
-                            get_weekday_jp(selected_date)
-                        ))
-                        .components(vec![]),
-                ),
-            )
+        responder
+            .respond(serenity::CreateInteractionResponse::UpdateMessage(
+                serenity::CreateInteractionResponseMessage::new()
+                    .content(format!(
+                        "📋 {} ({}) の記録はありません",
+                        selected_date.format("%Y/%m/%d"),
+                        get_weekday_jp(selected_date)
+                    ))
+                    .components(vec![]),
+            ))
             .await?;
         return Ok(());
     }
 
     // Format the historical records
+    let prefs = preferences::resolve(
+        pool,
+        &user_id,
+        interaction.guild_id,
+        Some(&interaction.locale),
+    )
+    .await;
     let content = format!(
         "📋 **{} ({}) の勤務記録**\n\n{}",
         selected_date.format("%Y/%m/%d"),
         get_weekday_jp(selected_date),
-        crate::utils::format::format_attendance_status(&records)
+        crate::utils::format::format_attendance_status(&records, prefs.lang, prefs.clock)
     );
 
-    interaction
-        .create_response(
-            &ctx.http,
-            serenity::CreateInteractionResponse::UpdateMessage(
-                serenity::CreateInteractionResponseMessage::new()
-                    .content(&content)
-                    .components(vec![]),
-            ),
-        )
+    // 修正された記録がある日は変更履歴の詳細を開けるようにする
+    let components = if records.iter().any(|record| record.is_modified) {
+        vec![serenity::CreateActionRow::Buttons(vec![
+            record_changes_button(&user_id, selected_date),
+        ])]
+    } else {
+        vec![]
+    };
+
+    responder
+        .respond(serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new()
+                .content(&content)
+                .components(components),
+        ))
+        .await?;
+
+    Ok(())
+}
+
+/// custom_id の2番目（"action:discord_id:..."）が押したユーザーか
+fn pressed_by_owner(interaction: &serenity::ComponentInteraction) -> bool {
+    interaction.data.custom_id.split(':').nth(1) == Some(interaction.user.id.to_string().as_str())
+}
+
+/// 指定日の記録の変更履歴を開くボタン（custom_id: "record_changes:user_id:date"）
+pub fn record_changes_button(user_id: &str, date: NaiveDate) -> serenity::CreateButton {
+    serenity::CreateButton::new(format!(
+        "record_changes:{}:{}",
+        user_id,
+        date.format("%Y-%m-%d")
+    ))
+    .label("🔍 変更履歴")
+    .style(serenity::ButtonStyle::Secondary)
+}
+
+/// 1件の記録の修正履歴を開くボタン（custom_id: "record_revisions:user_id:record_id"）
+pub fn record_revisions_button(user_id: &str, record_id: i64) -> serenity::CreateButton {
+    serenity::CreateButton::new(format!("record_revisions:{}:{}", user_id, record_id))
+        .label("🕓 修正履歴")
+        .style(serenity::ButtonStyle::Secondary)
+}
+
+/// 1件の記録の修正履歴（`record_revisions`）を一覧表示
+async fn handle_record_revisions(
+    responder: &impl InteractionResponder,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let user_id = interaction.user.id.to_string();
+    let username = interaction.user.name.clone();
+    let pool = &data.pool;
+
+    let record_id = interaction
+        .data
+        .custom_id
+        .split(':')
+        .nth(2)
+        .and_then(|id| id.parse::<i64>().ok());
+    let user = queries::create_or_get_user(pool, &user_id, interaction.guild_id, &username).await;
+    let record = match (record_id, user) {
+        (Some(record_id), Ok(user)) => queries::get_attendance_record_by_id(pool, record_id)
+            .await
+            .ok()
+            .filter(|record| record.user_id == user.id),
+        _ => None,
+    };
+    let Some(record) = record else {
+        return responder
+            .respond_error(AppError::user("指定された記録が見つかりません"))
+            .await;
+    };
+
+    let prefs = preferences::resolve(
+        pool,
+        &user_id,
+        interaction.guild_id,
+        Some(&interaction.locale),
+    )
+    .await;
+    let message = match queries::get_record_revisions(pool, record.id).await {
+        Ok(revisions) => {
+            serenity::CreateInteractionResponseMessage::new().embed(create_info_embed(
+                "🕓 修正履歴",
+                &format_record_revisions(&record, &revisions, prefs.clock),
+            ))
+        }
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("修正履歴の取得に失敗しました", e))
+                .await;
+        }
+    };
+    responder
+        .respond(serenity::CreateInteractionResponse::Message(
+            message.ephemeral(true),
+        ))
+        .await?;
+
+    Ok(())
+}
+
+/// 指定日の記録に対するすべての変更を監査ログから一覧表示
+async fn handle_record_changes(
+    responder: &impl InteractionResponder,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let Some(date) = interaction
+        .data
+        .custom_id
+        .split(':')
+        .nth(2)
+        .and_then(|date| validate_date_format(date).ok())
+    else {
+        return responder
+            .respond_error(AppError::user("無効な日付です"))
+            .await;
+    };
+
+    let user_id = interaction.user.id.to_string();
+    let username = interaction.user.name.clone();
+    let pool = &data.pool;
+
+    let prefs = preferences::resolve(
+        pool,
+        &user_id,
+        interaction.guild_id,
+        Some(&interaction.locale),
+    )
+    .await;
+    let result =
+        match queries::create_or_get_user(pool, &user_id, interaction.guild_id, &username).await {
+            Ok(user) => match queries::get_audit_logs_for_date(pool, user.id, date).await {
+                Ok(logs) => queries::get_records_by_date(pool, user.id, date)
+                    .await
+                    .map(|records| (logs, records)),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+    let message = match result {
+        Ok((logs, records)) => {
+            // 修正された記録ごとに修正履歴を開くボタン（1行5個まで）
+            let buttons: Vec<_> = records
+                .iter()
+                .filter(|record| record.is_modified)
+                .take(25)
+                .map(|record| {
+                    record_revisions_button(&user_id, record.id).label(format!(
+                        "🕓 {} {}",
+                        record_type_label(record.record_type, prefs.lang),
+                        prefs.clock.time(record.timestamp)
+                    ))
+                })
+                .collect();
+            serenity::CreateInteractionResponseMessage::new()
+                .embed(create_info_embed(
+                    &format!("🔍 変更履歴 - {}", date.format("%Y年%m月%d日")),
+                    &format_audit_logs(&logs, prefs.lang, prefs.clock),
+                ))
+                .components(
+                    buttons
+                        .chunks(5)
+                        .map(|row| serenity::CreateActionRow::Buttons(row.to_vec()))
+                        .collect(),
+                )
+        }
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("変更履歴の取得に失敗しました", e))
+                .await;
+        }
+    };
+    responder
+        .respond(serenity::CreateInteractionResponse::Message(
+            message.ephemeral(true),
+        ))
         .await?;
 
     Ok(())
@@ -1496,3 +2029,442 @@ fn get_weekday_jp(date: NaiveDate) -> &'static str {
         chrono::Weekday::Sun => "日",
     }
 }
+
+#[cfg(all(test, not(feature = "postgres")))]
+mod tests {
+    use super::*;
+    use crate::bot::interactions::responder::MockResponder;
+    use crate::bot::punch_journal::PunchJournal;
+    use crate::config::Config;
+    use crate::database::create_test_pool;
+    use crate::utils::i18n::Lang;
+    use crate::utils::time::TimeDisplay;
+    use std::sync::Arc;
+
+    const USER_ID: &str = "1234";
+
+    async fn create_test_data() -> Data {
+        Data {
+            pool: create_test_pool().await,
+            punch_journal: Arc::new(PunchJournal::new("")),
+            config: Config {
+                discord_token: String::new(),
+                database_url: "sqlite::memory:".to_string(),
+                database: Default::default(),
+                admin_role_id: None,
+                export_signing_key: "test".to_string(),
+                select_menu_page_size: 25,
+                api_bind_addr: None,
+                api_token: None,
+                health_bind_addr: None,
+                punch_journal_path: String::new(),
+            },
+        }
+    }
+
+    /// テスト用のコンポーネントインタラクションを作成
+    fn create_component_interaction(
+        custom_id: &str,
+        values: &[&str],
+    ) -> serenity::ComponentInteraction {
+        let user = serde_json::json!({
+            "id": USER_ID,
+            "username": "tester",
+            "discriminator": "0",
+            "avatar": null,
+        });
+        let data = if values.is_empty() {
+            serde_json::json!({ "custom_id": custom_id, "component_type": 2 })
+        } else {
+            serde_json::json!({ "custom_id": custom_id, "component_type": 3, "values": values })
+        };
+        serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "application_id": "2",
+            "type": 3,
+            "data": data,
+            "channel_id": "3",
+            "user": user,
+            "token": "token",
+            "version": 1,
+            "message": {
+                "id": "4",
+                "channel_id": "3",
+                "author": user,
+                "content": "",
+                "timestamp": "2024-01-01T00:00:00Z",
+                "edited_timestamp": null,
+                "tts": false,
+                "mention_everyone": false,
+                "mentions": [],
+                "mention_roles": [],
+                "attachments": [],
+                "embeds": [],
+                "pinned": false,
+                "type": 0,
+            },
+            "locale": "ja",
+            "entitlements": [],
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_record_add_shows_start_and_end_buttons() {
+        let data = create_test_data().await;
+        let interaction = create_component_interaction(&format!("record_add:{}", USER_ID), &[]);
+        let responder = MockResponder::default();
+
+        handle_record_add(&responder, &interaction, &data)
+            .await
+            .unwrap();
+
+        let responses = responder.responses();
+        assert_eq!(responses.len(), 1);
+        let buttons = &responses[0]["data"]["components"][0]["components"];
+        assert_eq!(
+            buttons[0]["custom_id"],
+            format!("add_start_record:{}", USER_ID)
+        );
+        assert_eq!(
+            buttons[1]["custom_id"],
+            format!("add_end_record:{}", USER_ID)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_time_edit_without_records_is_rejected() {
+        let data = create_test_data().await;
+        let interaction = create_component_interaction(&format!("time_edit:{}", USER_ID), &[]);
+        let responder = MockResponder::default();
+
+        handle_time_edit_selection(&responder, &interaction, &data)
+            .await
+            .unwrap();
+
+        let responses = responder.responses();
+        assert_eq!(
+            responses[0]["data"]["content"],
+            "修正できる記録がありません"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_time_edit_offers_dates_with_records() {
+        let data = create_test_data().await;
+        let user = queries::create_or_get_user(&data.pool, USER_ID, None::<&str>, "tester")
+            .await
+            .unwrap();
+        let audit = AuditSource::new(serenity::UserId::new(1234), "test");
+        let timestamp = chrono::Utc::now() - chrono::Duration::minutes(1);
+        queries::create_attendance_record(
+            &data.pool,
+            user.id,
+            RecordType::Start,
+            timestamp,
+            &audit,
+        )
+        .await
+        .unwrap();
+        let interaction = create_component_interaction(&format!("time_edit:{}", USER_ID), &[]);
+        let responder = MockResponder::default();
+
+        handle_time_edit_selection(&responder, &interaction, &data)
+            .await
+            .unwrap();
+
+        let responses = responder.responses();
+        let select_menu = &responses[0]["data"]["components"][0]["components"][0];
+        // 所有者を載せ、ボタンと同じく本人以外の操作を拒否できるようにする
+        assert_eq!(
+            select_menu["custom_id"],
+            format!("edit_date_select:{}", USER_ID)
+        );
+        assert_eq!(
+            select_menu["options"][0]["value"],
+            get_date_from_utc_timestamp(timestamp)
+                .format("%Y-%m-%d")
+                .to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_changes_lists_audit_trail() {
+        let data = create_test_data().await;
+        let user = queries::create_or_get_user(&data.pool, USER_ID, None::<&str>, "tester")
+            .await
+            .unwrap();
+        let audit = AuditSource::new(serenity::UserId::new(1234), "test");
+        let timestamp = chrono::Utc::now() - chrono::Duration::hours(1);
+        let record = queries::create_attendance_record(
+            &data.pool,
+            user.id,
+            RecordType::Start,
+            timestamp,
+            &audit,
+        )
+        .await
+        .unwrap();
+        queries::update_attendance_record_time(
+            &data.pool,
+            record.id,
+            timestamp - chrono::Duration::minutes(30),
+            &audit,
+        )
+        .await
+        .unwrap();
+        let date = get_date_from_utc_timestamp(timestamp);
+        let interaction = create_component_interaction(
+            &format!("record_changes:{}:{}", USER_ID, date.format("%Y-%m-%d")),
+            &[],
+        );
+        let responder = MockResponder::default();
+
+        handle_record_changes(&responder, &interaction, &data)
+            .await
+            .unwrap();
+
+        let description = responder.responses()[0]["data"]["embeds"][0]["description"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(description.contains("開始を追加"));
+        assert!(description.contains("開始を修正"));
+
+        // 修正された記録の修正履歴を開ける
+        let revisions_id =
+            responder.responses()[0]["data"]["components"][0]["components"][0]["custom_id"]
+                .as_str()
+                .unwrap()
+                .to_string();
+        assert_eq!(
+            revisions_id,
+            format!("record_revisions:{}:{}", USER_ID, record.id)
+        );
+        let interaction = create_component_interaction(&revisions_id, &[]);
+        let responder = MockResponder::default();
+        handle_record_revisions(&responder, &interaction, &data)
+            .await
+            .unwrap();
+        let description = responder.responses()[0]["data"]["embeds"][0]["description"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(description.contains(&format!(
+            "{} → **{}**",
+            TimeDisplay::default().time(timestamp),
+            TimeDisplay::default().time(timestamp - chrono::Duration::minutes(30))
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_edit_record_selected_opens_modal_with_record_id() {
+        let data = create_test_data().await;
+        let interaction = create_component_interaction("edit_record_select", &["42"]);
+        let responder = MockResponder::default();
+
+        handle_edit_record_selected(&responder, &interaction, &data)
+            .await
+            .unwrap();
+
+        let responses = responder.responses();
+        assert_eq!(responses[0]["data"]["custom_id"], "time_edit_modal");
+        assert_eq!(
+            responses[0]["data"]["components"][1]["components"][0]["value"],
+            "42"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_confirmation_uses_saved_state() {
+        let data = create_test_data().await;
+        let user = queries::create_or_get_user(&data.pool, USER_ID, None::<&str>, "tester")
+            .await
+            .unwrap();
+        let audit = AuditSource::new(serenity::UserId::new(1234), "test");
+        let timestamp = chrono::Utc::now() - chrono::Duration::minutes(1);
+        let record = queries::create_attendance_record(
+            &data.pool,
+            user.id,
+            RecordType::Start,
+            timestamp,
+            &audit,
+        )
+        .await
+        .unwrap();
+        let date = get_date_from_utc_timestamp(timestamp);
+
+        let interaction = create_component_interaction(
+            &format!(
+                "delete_record_select:{}:{}",
+                USER_ID,
+                date.format("%Y-%m-%d")
+            ),
+            &[&record.id.to_string()],
+        );
+        let responder = MockResponder::default();
+        handle_delete_record_selected(&responder, &interaction, &data)
+            .await
+            .unwrap();
+        let confirm_id =
+            responder.responses()[0]["data"]["components"][0]["components"][0]["custom_id"]
+                .as_str()
+                .unwrap()
+                .to_string();
+        assert!(confirm_id.starts_with(&format!("confirm_delete:{}:", USER_ID)));
+
+        // 確認ボタンには記録IDを載せず、保存した状態から削除対象を復元する
+        let interaction = create_component_interaction(&confirm_id, &[]);
+        let responder = MockResponder::default();
+        handle_confirm_delete(&responder, &serenity::Http::new(""), &interaction, &data)
+            .await
+            .unwrap();
+        assert_eq!(
+            responder.responses()[0]["data"]["embeds"][0]["title"],
+            "削除完了"
+        );
+        assert!(
+            queries::get_records_by_date(&data.pool, user.id, date)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // 使用済みの確認ボタンは期限切れとして扱う
+        let responder = MockResponder::default();
+        handle_confirm_delete(&responder, &serenity::Http::new(""), &interaction, &data)
+            .await
+            .unwrap();
+        assert!(
+            responder.responses()[0]["data"]["content"]
+                .as_str()
+                .unwrap()
+                .contains("期限切れ")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_start_project_button_sets_last_project() {
+        let data = create_test_data().await;
+        let pool = &data.pool;
+        let user = queries::create_or_get_user(pool, USER_ID, None::<&str>, "tester")
+            .await
+            .unwrap();
+        let project_id = queries::get_or_create_project(pool, "99", "開発")
+            .await
+            .unwrap();
+        let audit = AuditSource::new(serenity::UserId::new(1234), "test");
+        let timestamp = chrono::Utc::now() - chrono::Duration::minutes(1);
+        let record =
+            queries::create_attendance_record(pool, user.id, RecordType::Start, timestamp, &audit)
+                .await
+                .unwrap();
+        let date = get_date_from_utc_timestamp(timestamp);
+        SessionManager::new(pool.clone())
+            .trigger_recalculation(user.id, date)
+            .await
+            .unwrap();
+
+        let interaction = create_component_interaction(
+            &format!("start_project:{}:{}:{}", USER_ID, record.id, project_id),
+            &[],
+        );
+        let responder = MockResponder::default();
+        project_buttons::handle_start_project(&responder, &interaction, &data)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            responder.responses()[0]["data"]["components"],
+            serde_json::json!([])
+        );
+        assert_eq!(
+            queries::get_project_preference(pool, user.id, "99")
+                .await
+                .unwrap()
+                .last_project,
+            Some((project_id, "開発".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_kiosk_buttons_record_for_presser() {
+        let data = create_test_data().await;
+        let http = serenity::Http::new("");
+        let interaction = create_component_interaction(kiosk_buttons::KIOSK_START, &[]);
+
+        for _ in 0..2 {
+            kiosk_buttons::handle_kiosk(
+                &MockResponder::default(),
+                &http,
+                &interaction,
+                &data,
+                RecordType::Start,
+            )
+            .await
+            .unwrap();
+        }
+
+        // 押したユーザーの記録だけが作られ、勤務中の2回目は記録されない
+        let user = queries::get_user_by_discord_id(&data.pool, USER_ID, None::<&str>)
+            .await
+            .unwrap();
+        let records = queries::get_records_by_date(&data.pool, user.id, get_current_date_jst())
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].record_type, RecordType::Start);
+
+        let responder = MockResponder::default();
+        kiosk_buttons::handle_kiosk(&responder, &http, &interaction, &data, RecordType::End)
+            .await
+            .unwrap();
+        let responses = responder.responses();
+        assert_eq!(responses[0]["data"]["flags"], 64);
+        assert_eq!(
+            queries::get_records_by_date(&data.pool, user.id, get_current_date_jst())
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_timesheet_confirm_button_is_routed_for_its_owner() {
+        let data = create_test_data().await;
+        let user = queries::create_or_get_user(&data.pool, USER_ID, None::<&str>, "tester")
+            .await
+            .unwrap();
+        let week_start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        queries::create_timesheet_confirmation(&data.pool, user.id, week_start, 60)
+            .await
+            .unwrap();
+
+        let buttons = serde_json::to_value(timesheet_buttons::confirm_buttons(
+            &user,
+            week_start,
+            Lang::Ja,
+        ))
+        .unwrap();
+        let custom_id = buttons[0]["components"][0]["custom_id"].as_str().unwrap();
+        let interaction = create_component_interaction(custom_id, &[]);
+        // DMのボタンも他の所有者付きボタンと同じ確認を通る
+        assert!(pressed_by_owner(&interaction));
+
+        let responder = MockResponder::default();
+        timesheet_buttons::handle_timesheet_confirm(&responder, &interaction, &data)
+            .await
+            .unwrap();
+        assert_eq!(responder.responses()[0]["type"], 7);
+        // 確認済みになっているため、もう一度は更新されない
+        assert!(
+            !queries::confirm_timesheet(&data.pool, user.id, week_start)
+                .await
+                .unwrap()
+        );
+
+        let other = create_component_interaction(&custom_id.replacen(USER_ID, "5678", 1), &[]);
+        assert!(!pressed_by_owner(&other));
+    }
+}
@@ -0,0 +1,292 @@
+use crate::bot::approvals::{self, RecordChange};
+use crate::bot::checks::member_is_admin;
+use crate::bot::interactions::responder::{ComponentResponder, InteractionResponder};
+use crate::bot::interactions::status_buttons::create_time_edit_modal;
+use crate::bot::{AppError, Data, Error, edit_policy, metrics, punch_lock, webhooks};
+use crate::database::models::{AuditSource, RecordType};
+use crate::database::queries;
+use crate::utils::format::{create_success_embed, format_error_message};
+use crate::utils::i18n::Lang;
+use crate::utils::record_validator::{CorrectionSuggestion, RecordValidator, ValidationError};
+use crate::utils::session_manager::SessionManager;
+use crate::utils::time::{format_time_jst, get_current_date_jst, get_date_from_utc_timestamp};
+use chrono::DateTime;
+use poise::serenity_prelude as serenity;
+
+/// バリデーションエラーを修正候補ボタン付きで返信する
+///
+/// `target` は追加時は "start" / "end"、修正時は対象の記録ID
+pub async fn respond_validation_error(
+    responder: &impl InteractionResponder,
+    user_id: serenity::UserId,
+    error: &ValidationError,
+    target: &str,
+) -> Result<(), Error> {
+    let buttons: Vec<serenity::CreateButton> = error
+        .suggestions()
+        .iter()
+        .map(|suggestion| {
+            let custom_id = match suggestion {
+                CorrectionSuggestion::PreviousDay { timestamp } => format!(
+                    "suggest_previous_day:{}:{}:{}",
+                    user_id,
+                    target,
+                    timestamp.timestamp()
+                ),
+                CorrectionSuggestion::EditRecord { record_id, .. } => {
+                    format!("suggest_edit_record:{}:{}", user_id, record_id)
+                }
+            };
+            serenity::CreateButton::new(custom_id)
+                .label(suggestion.label())
+                .style(serenity::ButtonStyle::Primary)
+        })
+        .collect();
+
+    let mut message = serenity::CreateInteractionResponseMessage::new()
+        .content(format_error_message(
+            &error.to_string(),
+            Lang::from_locale(responder.locale()),
+        ))
+        .ephemeral(true);
+    if !buttons.is_empty() {
+        message = message.components(vec![serenity::CreateActionRow::Buttons(buttons)]);
+    }
+
+    responder
+        .respond(serenity::CreateInteractionResponse::Message(message))
+        .await?;
+
+    Ok(())
+}
+
+/// 「既存のHH:MM記録を修正する」: 対象記録の時間修正モーダルを開く
+pub async fn handle_edit_record(
+    ctx: &serenity::Context,
+    interaction: &serenity::ComponentInteraction,
+) -> Result<(), Error> {
+    // "suggest_edit_record:user_id:record_id"
+    let record_id = interaction
+        .data
+        .custom_id
+        .split(':')
+        .nth(2)
+        .unwrap_or_default();
+
+    interaction
+        .create_response(
+            &ctx.http,
+            serenity::CreateInteractionResponse::Modal(create_time_edit_modal(record_id)),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// 「HH:MMではなく前日扱いにする」: 入力時刻を前日の記録として追加・修正する
+pub async fn handle_previous_day(
+    ctx: &serenity::Context,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let responder = ComponentResponder::new(ctx, interaction);
+    // "suggest_previous_day:user_id:target:unix_timestamp"
+    let custom_id = &interaction.data.custom_id;
+    let parts: Vec<&str> = custom_id.split(':').collect();
+
+    let (Some(target), Some(timestamp)) = (
+        parts.get(2).copied(),
+        parts
+            .get(3)
+            .and_then(|value| value.parse::<i64>().ok())
+            .and_then(|secs| DateTime::from_timestamp(secs, 0)),
+    ) else {
+        return responder
+            .respond_error(AppError::user("無効な修正候補です"))
+            .await;
+    };
+
+    let user_id = interaction.user.id.to_string();
+    let username = interaction.user.name.clone();
+    let pool = &data.pool;
+
+    let user =
+        match queries::create_or_get_user(pool, &user_id, interaction.guild_id, &username).await {
+            Ok(user) => user,
+            Err(e) => {
+                return responder
+                    .respond_error(AppError::internal("ユーザー情報の取得に失敗しました", e))
+                    .await;
+            }
+        };
+    let _punch_guard = punch_lock::lock(user.id).await;
+
+    let target_date = get_date_from_utc_timestamp(timestamp);
+    let existing_records = match queries::get_today_records(pool, user.id, target_date).await {
+        Ok(records) => records,
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("記録の取得に失敗しました", e))
+                .await;
+        }
+    };
+
+    let is_admin = interaction
+        .member
+        .as_ref()
+        .is_some_and(|member| member_is_admin(member, &data.config));
+    let policy = edit_policy::load(pool, interaction.guild_id, is_admin).await;
+
+    let change = match target.parse::<RecordType>() {
+        Ok(record_type) => {
+            if let Err(e) = RecordValidator::validate_new_record(
+                &existing_records,
+                record_type,
+                timestamp,
+                target_date,
+                None,
+                &policy,
+            ) {
+                metrics::record_validation_rejection(pool, interaction.guild_id, &e).await;
+                return responder.respond_error(AppError::user(e.to_string())).await;
+            }
+            RecordChange {
+                record_id: None,
+                record_type,
+                old_timestamp: None,
+                new_timestamp: timestamp,
+                delete: false,
+            }
+        }
+        Err(_) => {
+            let Ok(record_id) = target.parse::<i64>() else {
+                return responder
+                    .respond_error(AppError::user("無効な記録IDです"))
+                    .await;
+            };
+            let record = match queries::get_attendance_record_by_id(pool, record_id).await {
+                Ok(record) if record.user_id == user.id => record,
+                _ => {
+                    return responder
+                        .respond_error(AppError::user("指定された記録が見つかりません"))
+                        .await;
+                }
+            };
+            let record_type = record.record_type;
+            if let Err(e) = RecordValidator::validate_new_record(
+                &existing_records,
+                record_type,
+                timestamp,
+                target_date,
+                Some(record_id),
+                &policy,
+            ) {
+                metrics::record_validation_rejection(pool, interaction.guild_id, &e).await;
+                return responder.respond_error(AppError::user(e.to_string())).await;
+            }
+            RecordChange {
+                record_id: Some(record_id),
+                record_type,
+                old_timestamp: Some(record.timestamp),
+                new_timestamp: timestamp,
+                delete: false,
+            }
+        }
+    };
+    let record_type = change.record_type;
+
+    let audit =
+        AuditSource::new(interaction.user.id, "status:suggestion").in_guild(interaction.guild_id);
+    match approvals::submit_if_required(
+        &ctx.http,
+        pool,
+        interaction.guild_id,
+        &user,
+        change,
+        &audit,
+    )
+    .await
+    {
+        Ok(false) => {}
+        Ok(true) => {
+            interaction
+                .create_response(
+                    &ctx.http,
+                    serenity::CreateInteractionResponse::UpdateMessage(
+                        serenity::CreateInteractionResponseMessage::new()
+                            .content("")
+                            .embed(approvals::pending_embed())
+                            .components(vec![]),
+                    ),
+                )
+                .await?;
+            return Ok(());
+        }
+        Err(e) => {
+            return responder
+                .respond_error(AppError::internal("承認依頼の送信に失敗しました", e))
+                .await;
+        }
+    }
+
+    let result = match change.record_id {
+        Some(record_id) => {
+            queries::update_attendance_record_time(pool, record_id, timestamp, &audit).await
+        }
+        None => queries::create_attendance_record(pool, user.id, record_type, timestamp, &audit)
+            .await
+            .map(|_| ()),
+    };
+
+    if let Err(e) = result {
+        return responder
+            .respond_error(AppError::internal("記録の保存に失敗しました", e))
+            .await;
+    }
+    if change.record_id.is_none() {
+        webhooks::notify_record(
+            pool,
+            interaction.guild_id,
+            &user,
+            record_type,
+            timestamp,
+            &audit,
+        )
+        .await;
+    }
+
+    // 前日と当日の両方のセッションを再計算
+    let session_manager = SessionManager::new(pool.clone());
+    for date in [target_date, get_current_date_jst()] {
+        if let Err(e) = session_manager.trigger_recalculation(user.id, date).await {
+            tracing::error!("Failed to recalculate sessions: {}", e);
+        }
+    }
+
+    let embed = create_success_embed(
+        "修正完了",
+        &format!(
+            "{}記録を{} {}に保存しました",
+            if record_type == RecordType::Start {
+                "開始"
+            } else {
+                "終了"
+            },
+            target_date.format("%Y年%m月%d日"),
+            format_time_jst(timestamp)
+        ),
+    );
+    interaction
+        .create_response(
+            &ctx.http,
+            serenity::CreateInteractionResponse::UpdateMessage(
+                serenity::CreateInteractionResponseMessage::new()
+                    .content("")
+                    .embed(embed)
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use crate::bot::interactions::responder::InteractionResponder;
+use crate::bot::{AppError, Data, Error, preferences};
+use crate::database::models::User;
+use crate::database::queries;
+use crate::t;
+use crate::utils::format::create_success_embed;
+use crate::utils::i18n::Lang;
+use chrono::NaiveDate;
+use poise::serenity_prelude as serenity;
+
+/// タイムシートを確認済みにするボタン（custom_id: "timesheet_confirm:discord_id:user_id:YYYY-MM-DD"）
+pub const TIMESHEET_CONFIRM: &str = "timesheet_confirm";
+
+/// 確認依頼のDMに付けるボタン
+pub fn confirm_buttons(
+    user: &User,
+    week_start: NaiveDate,
+    lang: Lang,
+) -> Vec<serenity::CreateActionRow> {
+    vec![serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(format!(
+            "{}:{}:{}:{}",
+            TIMESHEET_CONFIRM,
+            user.discord_id,
+            user.id,
+            week_start.format("%Y-%m-%d")
+        ))
+        .label(t!(lang, "timesheet.confirm_button"))
+        .style(serenity::ButtonStyle::Success),
+    ])]
+}
+
+pub async fn handle_timesheet_confirm(
+    responder: &impl InteractionResponder,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    // ユーザーはサーバーごとに別のため、DMで押されたボタンは users.id で対象を特定する
+    let parts: Vec<&str> = interaction.data.custom_id.split(':').collect();
+    let pool = &data.pool;
+    let user = match parts.get(2).and_then(|value| value.parse::<i64>().ok()) {
+        Some(user_id) => queries::get_user_by_id(pool, user_id).await.ok(),
+        None => None,
+    };
+    let user = user.filter(|user| user.discord_id == interaction.user.id.to_string());
+
+    // DMで押されるため、確認依頼を送ったサーバーの設定の言語で応答する
+    let guild_id = user
+        .as_ref()
+        .and_then(|user| user.guild_id.parse::<u64>().ok())
+        .map(serenity::GuildId::new);
+    let lang = preferences::resolve(
+        pool,
+        &interaction.user.id.to_string(),
+        guild_id,
+        Some(&interaction.locale),
+    )
+    .await
+    .lang;
+
+    let Some(user) = user else {
+        return responder
+            .respond_error(AppError::user(t!(lang, "timesheet.not_yours")))
+            .await;
+    };
+    let week_start = match parts
+        .get(3)
+        .and_then(|value| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok())
+    {
+        Some(date) => date,
+        None => {
+            return responder
+                .respond_error(AppError::user(t!(lang, "timesheet.invalid_week")))
+                .await;
+        }
+    };
+
+    if let Err(e) = queries::confirm_timesheet(pool, user.id, week_start).await {
+        return responder
+            .respond_error(AppError::internal(t!(lang, "timesheet.save_error"), e))
+            .await;
+    }
+
+    let embed = create_success_embed(
+        &t!(lang, "timesheet.done_title"),
+        &t!(
+            lang,
+            "timesheet.done",
+            week = week_start.format(&t!(lang, "format.date"))
+        ),
+    );
+    responder
+        .respond(serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new()
+                .embed(embed)
+                .components(vec![]),
+        ))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use crate::bot::Error;
+use crate::bot::interactions::responder::InteractionResponder;
+use poise::serenity_prelude as serenity;
+
+/// チュートリアルの各ページ（タイトル, 本文）
+const TUTORIAL_PAGES: [(&str, &str); 4] = [
+    (
+        "👋 ようこそ！ 勤務の開始と終了",
+        "このBotでは Discord 上で勤怠を記録できます。\n\n\
+         🟢 `/start` で勤務開始、🔴 `/end` で勤務終了を記録します。\n\
+         休憩を挟む場合も `/end` → `/start` で何度でも記録できます。\n\n\
+         ※ 下のボタンは見本です（押しても動作しません）",
+    ),
+    (
+        "🔧 記録の確認と修正",
+        "`/status` で今日の記録を確認できます。表示されるボタンから\n\
+         • 🕐 **時間修正**: 過去の記録の時刻を修正（既定は7日以内）\n\
+         • ✅ **記録追加**: 押し忘れた開始・終了を後から追加\n\
+         • 🗑️ **削除**: 誤った記録を削除\n\n\
+         操作を間違えたときは `/undo` で直前の変更を取り消せます。\n\n\
+         ※ 下のボタンは見本です（押しても動作しません）",
+    ),
+    (
+        "📅 レポートとエクスポート",
+        "• `/daily` `/weekly` `/monthly`: 日・週・月ごとの勤務時間\n\
+         • `/leaderboard`: 今月の勤務時間ランキング\n\
+         • `/export csv`: 月ごとの勤務記録を署名付きCSVで出力",
+    ),
+    (
+        "⚡ 便利な機能",
+        "• `/shortcut set`: よく使う手順をまとめて登録（例: `end 18:00; daily`）\n\
+         • `/kintai`: すべてのコマンドを `/kintai` 以下からも呼び出せます\n\n\
+         これでチュートリアルは終わりです。お疲れさまでした！",
+    ),
+];
+
+/// チュートリアルのページを組み立てる（`page` は 0 始まり）
+pub fn tutorial_message(
+    user_id: &str,
+    page: usize,
+) -> (serenity::CreateEmbed, Vec<serenity::CreateActionRow>) {
+    let page = page.min(TUTORIAL_PAGES.len() - 1);
+    let (title, description) = TUTORIAL_PAGES[page];
+
+    let embed = serenity::CreateEmbed::new()
+        .title(title)
+        .description(description)
+        .color(0x3498db) // Blue
+        .footer(serenity::CreateEmbedFooter::new(format!(
+            "チュートリアル {}/{}",
+            page + 1,
+            TUTORIAL_PAGES.len()
+        )));
+
+    let mut components = Vec::new();
+    if let Some(examples) = example_buttons(page) {
+        components.push(serenity::CreateActionRow::Buttons(examples));
+    }
+
+    let mut navigation = Vec::new();
+    if page > 0 {
+        navigation.push(
+            serenity::CreateButton::new(format!("tutorial_page:{}:{}", user_id, page - 1))
+                .label("◀ 前へ")
+                .style(serenity::ButtonStyle::Secondary),
+        );
+    }
+    if page + 1 < TUTORIAL_PAGES.len() {
+        navigation.push(
+            serenity::CreateButton::new(format!("tutorial_page:{}:{}", user_id, page + 1))
+                .label("次へ ▶")
+                .style(serenity::ButtonStyle::Primary),
+        );
+    }
+    navigation.push(
+        serenity::CreateButton::new(format!("tutorial_close:{}", user_id))
+            .label(if page + 1 < TUTORIAL_PAGES.len() {
+                "スキップ"
+            } else {
+                "閉じる"
+            })
+            .style(serenity::ButtonStyle::Secondary),
+    );
+    components.push(serenity::CreateActionRow::Buttons(navigation));
+
+    (embed, components)
+}
+
+/// 実際のコマンドで表示されるボタンの見本（無効化して表示）
+fn example_buttons(page: usize) -> Option<Vec<serenity::CreateButton>> {
+    let buttons = match page {
+        0 => vec![
+            serenity::CreateButton::new("tutorial_example:start")
+                .label("🟢 /start")
+                .style(serenity::ButtonStyle::Success),
+            serenity::CreateButton::new("tutorial_example:end")
+                .label("🔴 /end")
+                .style(serenity::ButtonStyle::Danger),
+        ],
+        1 => vec![
+            serenity::CreateButton::new("tutorial_example:time_edit")
+                .label("🕐 時間修正")
+                .style(serenity::ButtonStyle::Primary),
+            serenity::CreateButton::new("tutorial_example:record_add")
+                .label("✅ 記録追加")
+                .style(serenity::ButtonStyle::Success),
+            serenity::CreateButton::new("tutorial_example:delete_record")
+                .label("🗑️ 削除")
+                .style(serenity::ButtonStyle::Danger),
+        ],
+        _ => return None,
+    };
+    Some(
+        buttons
+            .into_iter()
+            .map(|button| button.disabled(true))
+            .collect(),
+    )
+}
+
+/// ページ移動ボタン（custom_id: "tutorial_page:user_id:page"）
+pub async fn handle_tutorial_page(
+    responder: &impl InteractionResponder,
+    interaction: &serenity::ComponentInteraction,
+) -> Result<(), Error> {
+    let parts: Vec<&str> = interaction.data.custom_id.split(':').collect();
+    let page = parts.get(2).and_then(|page| page.parse().ok()).unwrap_or(0);
+    let (embed, components) = tutorial_message(parts[1], page);
+
+    responder
+        .respond(serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new()
+                .embed(embed)
+                .components(components),
+        ))
+        .await
+}
+
+/// チュートリアルを閉じる
+pub async fn handle_tutorial_close(responder: &impl InteractionResponder) -> Result<(), Error> {
+    responder
+        .respond(serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new()
+                .content("チュートリアルを閉じました。`/kintai` からいつでもコマンドを確認できます")
+                .embeds(vec![])
+                .components(vec![]),
+        ))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn button_ids(row: &serenity::CreateActionRow) -> Vec<String> {
+        serde_json::to_value(row).unwrap()["components"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|button| button["custom_id"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_tutorial_navigation() {
+        let (_, components) = tutorial_message("42", 0);
+        assert_eq!(components.len(), 2);
+        assert_eq!(
+            button_ids(&components[1]),
+            vec!["tutorial_page:42:1", "tutorial_close:42"]
+        );
+
+        let (_, components) = tutorial_message("42", 2);
+        assert_eq!(components.len(), 1);
+        assert_eq!(
+            button_ids(&components[0]),
+            vec![
+                "tutorial_page:42:1",
+                "tutorial_page:42:3",
+                "tutorial_close:42"
+            ]
+        );
+
+        // 範囲外のページは最後のページとして表示する
+        let (embed, components) = tutorial_message("42", 10);
+        assert_eq!(
+            button_ids(&components[0]),
+            vec!["tutorial_page:42:2", "tutorial_close:42"]
+        );
+        assert_eq!(
+            serde_json::to_value(embed).unwrap()["footer"]["text"],
+            "チュートリアル 4/4"
+        );
+    }
+}
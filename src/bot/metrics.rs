@@ -0,0 +1,23 @@
+// Usage metrics recorded per guild so maintainers can see which validation rules cause
+// the most friction. Recording is best-effort: failures are logged and never surface to
+// the user whose input was rejected.
+
+use crate::database::{DbPool, queries};
+use crate::utils::record_validator::ValidationError;
+use poise::serenity_prelude as serenity;
+
+/// 記録のバリデーションエラーを種類ごとに数える（DMでの操作は対象外）
+pub async fn record_validation_rejection(
+    pool: &DbPool,
+    guild_id: Option<serenity::GuildId>,
+    error: &ValidationError,
+) {
+    let Some(guild_id) = guild_id else {
+        return;
+    };
+    if let Err(e) =
+        queries::increment_validation_rejection(pool, &guild_id.to_string(), error.kind()).await
+    {
+        tracing::warn!("Failed to record validation rejection: {}", e);
+    }
+}
@@ -1,50 +1,119 @@
+pub mod approvals;
+pub mod checks;
+pub mod command_names;
 pub mod commands;
+pub mod diagnostics;
+pub mod edit_policy;
+pub mod error;
+pub mod guild_reset;
 pub mod handlers;
+pub mod hooks;
+pub mod hour_caps;
 pub mod interactions;
+pub mod metrics;
+pub mod preferences;
+pub mod preflight;
+pub mod punch_journal;
+pub mod punch_lock;
+pub mod shortcuts;
+pub mod tasks;
+pub mod webhooks;
+pub mod working_role;
 
 use crate::config::Config;
-use crate::database;
+use crate::database::{self, DbPool};
 use anyhow::Result;
 use poise::serenity_prelude as serenity;
-use sqlx::SqlitePool;
+use punch_journal::PunchJournal;
+use std::sync::Arc;
 
-pub type Error = Box<dyn std::error::Error + Send + Sync>;
+pub use error::AppError;
+pub type Error = AppError;
 pub type Context<'a> = poise::Context<'a, Data, Error>;
 
 #[derive(Clone)]
 pub struct Data {
-    pub pool: SqlitePool,
+    pub pool: DbPool,
     pub config: Config,
+    pub punch_journal: Arc<PunchJournal>,
 }
 
 pub async fn create_bot(config: Config) -> Result<serenity::Client> {
-    let pool = database::create_connection(&config.database_url).await?;
+    let pool = database::create_connection(&config.database_url, &config.database).await?;
+    crate::api::spawn_if_configured(&pool, &config).await?;
 
     let data = Data {
-        pool,
+        pool: pool.clone(),
         config: config.clone(),
+        punch_journal: Arc::new(PunchJournal::new(&config.punch_journal_path)),
     };
 
-    let intents = serenity::GatewayIntents::non_privileged();
+    // `/admin backfill` で過去メッセージの本文を読むため MESSAGE_CONTENT が必要
+    let intents =
+        serenity::GatewayIntents::non_privileged() | serenity::GatewayIntents::MESSAGE_CONTENT;
+
+    let mut commands = vec![
+        commands::kintai::kintai(),
+        // 以下は `/kintai` 導入前のコマンド（移行期間中の別名）
+        commands::attendance::start(),
+        commands::attendance::end(),
+        commands::attendance::punch(),
+        commands::status::status(),
+        commands::reports::daily(),
+        commands::reports::weekly(),
+        commands::reports::monthly(),
+        commands::reports::leaderboard(),
+        commands::reports::working(),
+        commands::reports::forecast(),
+        commands::reports::calendar(),
+        commands::reports::stats(),
+        commands::undo::undo(),
+        commands::resume::resume(),
+        commands::focus::focus(),
+        commands::language::language(),
+        commands::settings::settings(),
+        commands::shortcut::shortcut(),
+        commands::project::project(),
+        commands::goal::goal(),
+        commands::rounding::rounding(),
+        commands::schedule::schedule(),
+        commands::shift::shift(),
+        commands::export::export(),
+        commands::export::verify_export_file(),
+        commands::team::team(),
+        commands::standup::standup(),
+        commands::dashboard::dashboard(),
+        commands::admin::admin(),
+        commands::close::close(),
+        commands::registration::registration(),
+    ];
+    command_names::localize(&mut commands);
 
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions {
-            commands: vec![
-                commands::attendance::start(),
-                commands::attendance::end(),
-                commands::status::status(),
-                commands::reports::daily(),
-                commands::reports::weekly(),
-                commands::reports::monthly(),
-            ],
+            commands,
+            pre_command: |ctx| Box::pin(hooks::pre_command(ctx)),
+            post_command: |ctx| Box::pin(hooks::post_command(ctx)),
+            on_error: |error| Box::pin(error::on_error(error)),
             event_handler: |ctx, event, framework, data| {
                 Box::pin(handlers::event_handler(ctx, event, framework, data))
             },
             ..Default::default()
         })
-        .setup(|ctx, _ready, framework| {
+        .setup(|ctx, ready, framework| {
             Box::pin(async move {
-                poise::builtins::register_globally(ctx, &framework.options().commands).await?;
+                commands::registration::register_commands(ctx, &framework.options().commands)
+                    .await?;
+                let guild_ids: Vec<_> = ready.guilds.iter().map(|guild| guild.id).collect();
+                diagnostics::log_report(
+                    &diagnostics::run_diagnostics(&ctx.http, &data, &guild_ids).await,
+                );
+                working_role::init(ctx.http.clone());
+                tasks::spawn_background_tasks(
+                    ctx.http.clone(),
+                    framework.shard_manager().runners.clone(),
+                    &data,
+                );
                 Ok(data)
             })
         })
@@ -53,6 +122,12 @@ pub async fn create_bot(config: Config) -> Result<serenity::Client> {
     let client = serenity::ClientBuilder::new(&config.discord_token, intents)
         .framework(framework)
         .await?;
+    crate::api::health::spawn_if_configured(
+        &pool,
+        client.shard_manager.runners.clone(),
+        config.health_bind_addr.as_deref(),
+    )
+    .await?;
 
     Ok(client)
 }
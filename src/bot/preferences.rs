@@ -0,0 +1,102 @@
+// Per-user display preferences for a command or interaction, from `/settings`. The language is
+// the user's choice, then the server default from `/admin language`, then the Discord client
+// locale, then Japanese; times default to JST in 24-hour format.
+
+use crate::bot::Context;
+use crate::database::models::UserPreferences;
+use crate::database::{DbPool, queries};
+use crate::utils::i18n::Lang;
+use crate::utils::time::{JST_OFFSET_MINUTES, TimeDisplay};
+use poise::serenity_prelude as serenity;
+
+/// 応答の表示に使う設定
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Preferences {
+    pub lang: Lang,
+    pub clock: TimeDisplay,
+    /// `/start`・`/end`・`/status` の応答を本人にだけ表示する
+    pub ephemeral: bool,
+    /// 自分のレポートを本人にだけ表示する
+    pub private_reports: bool,
+}
+
+impl Preferences {
+    pub fn from_settings(settings: &UserPreferences, locale: Option<&str>) -> Self {
+        Self {
+            lang: settings
+                .language
+                .as_deref()
+                .or(settings.guild_language.as_deref())
+                .and_then(Lang::from_code)
+                .unwrap_or_else(|| Lang::from_locale(locale)),
+            clock: TimeDisplay {
+                utc_offset_minutes: settings.utc_offset_minutes.unwrap_or(JST_OFFSET_MINUTES),
+                clock_12h: settings.clock_12h,
+            },
+            ephemeral: settings.ephemeral_responses,
+            private_reports: settings.private_reports,
+        }
+    }
+}
+
+/// コマンドを実行したユーザーの設定
+pub async fn load(ctx: Context<'_>) -> Preferences {
+    resolve(
+        &ctx.data().pool,
+        &ctx.author().id.to_string(),
+        ctx.guild_id(),
+        ctx.locale(),
+    )
+    .await
+}
+
+/// ユーザーの設定を読む（読めなければ既定の設定とロケールに従う）
+pub async fn resolve(
+    pool: &DbPool,
+    discord_id: &str,
+    guild_id: Option<serenity::GuildId>,
+    locale: Option<&str>,
+) -> Preferences {
+    let settings = queries::get_user_preferences(pool, discord_id, guild_id)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to load user preferences: {}", e);
+            UserPreferences::default()
+        });
+    Preferences::from_settings(&settings, locale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_settings() {
+        let defaults = Preferences::from_settings(&UserPreferences::default(), Some("ja"));
+        assert_eq!(defaults.lang, Lang::Ja);
+        assert_eq!(defaults.clock, TimeDisplay::default());
+        assert!(!defaults.ephemeral);
+        assert_eq!(
+            Preferences::from_settings(&UserPreferences::default(), Some("en-US")).lang,
+            Lang::En
+        );
+
+        // 本人の設定 → サーバーの設定 → ロケールの順
+        let guild_only = UserPreferences {
+            guild_language: Some("en".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            Preferences::from_settings(&guild_only, Some("ja")).lang,
+            Lang::En
+        );
+        let both = UserPreferences {
+            language: Some("ja".to_string()),
+            ..guild_only
+        };
+        assert_eq!(
+            Preferences::from_settings(&both, Some("en-US")).lang,
+            Lang::Ja
+        );
+    }
+}
@@ -0,0 +1,91 @@
+// Capability checks for features that depend on privileged intents or channel permissions.
+// Commands call these before doing any work so a missing prerequisite is reported with
+// setup instructions instead of the feature silently doing nothing.
+
+use crate::bot::{Context, Error};
+use crate::utils::format::create_error_embed;
+use poise::serenity_prelude as serenity;
+
+/// Developer Portal で Message Content Intent が有効になっているか
+pub async fn message_content_intent_enabled(http: &serenity::Http) -> Result<bool, Error> {
+    let granted = serenity::ApplicationFlags::GATEWAY_MESSAGE_CONTENT
+        | serenity::ApplicationFlags::GATEWAY_MESSAGE_CONTENT_LIMITED;
+    let info = http.get_current_application_info().await?;
+    Ok(info.flags.is_some_and(|flags| flags.intersects(granted)))
+}
+
+/// Message Content Intent が無効なら設定手順を返信して `false` を返す
+pub async fn ensure_message_content_intent(ctx: Context<'_>, feature: &str) -> Result<bool, Error> {
+    match message_content_intent_enabled(ctx.http()).await {
+        Ok(true) => Ok(true),
+        Ok(false) => {
+            respond_missing(
+                ctx,
+                feature,
+                "Message Content Intent が有効になっていません。\n\
+                 1. [Discord Developer Portal](https://discord.com/developers/applications) でこのBotのアプリケーションを開く\n\
+                 2. **Bot** → **Privileged Gateway Intents** の **Message Content Intent** を有効化\n\
+                 3. Botを再起動",
+            )
+            .await?;
+            Ok(false)
+        }
+        Err(e) => {
+            // 確認できない場合は実行を妨げない
+            tracing::warn!("Failed to check Message Content Intent: {:?}", e);
+            Ok(true)
+        }
+    }
+}
+
+/// Botに指定チャンネルでの必要な権限がなければ不足分を返信して `false` を返す
+pub async fn ensure_channel_permissions(
+    ctx: Context<'_>,
+    channel: &serenity::GuildChannel,
+    required: serenity::Permissions,
+    feature: &str,
+) -> Result<bool, Error> {
+    let bot_id = ctx.cache().current_user().id;
+    let permissions = ctx.guild().and_then(|guild| {
+        guild
+            .members
+            .get(&bot_id)
+            .map(|member| guild.user_permissions_in(channel, member))
+    });
+    let Some(permissions) = permissions else {
+        // キャッシュに無い場合は確認できないため実行を妨げない
+        tracing::warn!("Failed to resolve channel permissions for {}", channel.id);
+        return Ok(true);
+    };
+    let missing = required - permissions;
+
+    if missing.is_empty() {
+        return Ok(true);
+    }
+
+    respond_missing(
+        ctx,
+        feature,
+        &format!(
+            "Botに <#{}> での次の権限がありません: **{}**\n\
+             サーバー設定 → ロール、またはチャンネルの権限設定でBotのロールに付与してください。",
+            channel.id,
+            missing.get_permission_names().join(", ")
+        ),
+    )
+    .await?;
+    Ok(false)
+}
+
+async fn respond_missing(ctx: Context<'_>, feature: &str, instructions: &str) -> Result<(), Error> {
+    let embed = create_error_embed(
+        "前提条件が不足しています",
+        &format!(
+            "{} を利用するには設定が必要です。\n\n{}",
+            feature, instructions
+        ),
+    );
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,288 @@
+// Local journal for `/start` and `/end` punches that could not be written because the database
+// was unreachable. The punch is appended to a JSON Lines file (`PUNCH_JOURNAL_PATH`) with the
+// time of the request, the user is told it was accepted as pending, and `tasks/punch_replay.rs`
+// writes the entries in order through `shortcuts::record_attendance` once the database is back.
+
+use crate::bot::edit_policy;
+use crate::bot::shortcuts::record_attendance;
+use crate::database::models::{AuditSource, RecordType};
+use crate::database::{DbPool, queries};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use poise::serenity_prelude as serenity;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// DBに書き込めず保留している打刻
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingPunch {
+    pub discord_id: String,
+    pub username: String,
+    pub record_type: RecordType,
+    /// コマンドを受け付けた時刻（この時刻で記録する）
+    pub timestamp: DateTime<Utc>,
+    pub guild_id: Option<String>,
+    /// `/end note:` のメモ
+    pub note: Option<String>,
+}
+
+/// 保留中の打刻を1行1件のJSONで保存するファイル
+pub struct PunchJournal {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl PunchJournal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// 打刻を末尾に追加する
+    pub fn append(&self, punch: &PendingPunch) -> Result<()> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(punch)?)?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    /// 保留中の打刻（古い順）
+    pub fn pending(&self) -> Result<Vec<PendingPunch>> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.read()
+    }
+
+    /// ユーザーに保留中の打刻があるか（あれば新しい打刻も順番を保つため保留にする）
+    pub fn has_pending(&self, discord_id: &str) -> bool {
+        match self.pending() {
+            Ok(punches) => punches.iter().any(|punch| punch.discord_id == discord_id),
+            Err(e) => {
+                tracing::warn!("Failed to read punch journal: {}", e);
+                false
+            }
+        }
+    }
+
+    /// 先頭の1件を取り除く（再生中に追加された打刻は残す）
+    pub fn remove_first(&self) -> Result<()> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let punches = self.read()?;
+        if punches.is_empty() {
+            return Ok(());
+        }
+
+        let mut contents = String::new();
+        for punch in &punches[1..] {
+            contents.push_str(&serde_json::to_string(punch)?);
+            contents.push('\n');
+        }
+        let temp_path = self.path.with_extension("tmp");
+        std::fs::write(&temp_path, contents)?;
+        std::fs::rename(&temp_path, &self.path)?;
+        Ok(())
+    }
+
+    fn read(&self) -> Result<Vec<PendingPunch>> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(serde_json::from_str(line)?))
+            .collect()
+    }
+}
+
+/// DBに接続できないことによる失敗か（入力や状態によるエラーは含まない）
+pub fn is_database_unavailable(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<sqlx::Error>(),
+        Some(
+            sqlx::Error::Io(_)
+                | sqlx::Error::Tls(_)
+                | sqlx::Error::Protocol(_)
+                | sqlx::Error::PoolTimedOut
+                | sqlx::Error::PoolClosed
+                | sqlx::Error::WorkerCrashed
+        )
+    )
+}
+
+/// 再生の結果
+pub enum ReplayOutcome {
+    Recorded,
+    /// 記録できなかった（理由）
+    Rejected(String),
+    /// DBにまだ接続できない
+    Unavailable,
+}
+
+/// 保留中の打刻を1件記録する
+pub async fn replay(pool: &DbPool, punch: &PendingPunch) -> ReplayOutcome {
+    let user = match queries::create_or_get_user(
+        pool,
+        &punch.discord_id,
+        punch.guild_id.as_deref(),
+        &punch.username,
+    )
+    .await
+    {
+        Ok(user) => user,
+        Err(e) if is_database_unavailable(&e) => return ReplayOutcome::Unavailable,
+        Err(e) => {
+            tracing::error!(
+                "Failed to get user {} for replay: {:?}",
+                punch.discord_id,
+                e
+            );
+            return ReplayOutcome::Rejected("ユーザー情報の取得に失敗しました".to_string());
+        }
+    };
+
+    // 記録した後、ジャーナルから消す前に止まった打刻は書き込み済みなので二重に記録しない
+    match already_recorded(pool, user.id, punch).await {
+        Ok(true) => return ReplayOutcome::Recorded,
+        Ok(false) => {}
+        Err(e) if is_database_unavailable(&e) => return ReplayOutcome::Unavailable,
+        Err(e) => tracing::warn!("Failed to check replayed punch for duplicates: {}", e),
+    }
+
+    let record_type = punch.record_type;
+    let guild_id = punch
+        .guild_id
+        .as_deref()
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(serenity::GuildId::new);
+    let audit = AuditSource::new(&punch.discord_id, &format!("/{}", record_type.as_str()))
+        .in_guild(guild_id);
+    // 保留中の打刻は打刻した時点の操作なので、日付が変わっていても過去の日付の制限はかけない
+    let policy = edit_policy::load(pool, guild_id, true).await;
+    match record_attendance(
+        pool,
+        &user,
+        record_type,
+        punch.timestamp,
+        guild_id,
+        &audit,
+        &policy,
+    )
+    .await
+    {
+        Ok(started_at) => {
+            if let (Some(note), Some(started_at)) = (&punch.note, started_at) {
+                let date = crate::utils::time::get_date_from_utc_timestamp(started_at);
+                if let Err(e) = attach_note(pool, user.id, date, started_at, note).await {
+                    tracing::warn!("Failed to attach note to replayed punch: {}", e);
+                }
+            }
+            ReplayOutcome::Recorded
+        }
+        // 途中で接続が切れた場合は保留のまま次の機会に回す
+        Err(_) if queries::ping_database(pool).await.is_err() => ReplayOutcome::Unavailable,
+        Err(error) => ReplayOutcome::Rejected(error.into_message()),
+    }
+}
+
+/// 同じ種別・同じ時刻の記録がすでにあるか（DBの精度に合わせてマイクロ秒で比べる）
+async fn already_recorded(pool: &DbPool, user_id: i64, punch: &PendingPunch) -> Result<bool> {
+    let date = crate::utils::time::get_date_from_utc_timestamp(punch.timestamp);
+    let records = queries::get_records_by_date(pool, user_id, date).await?;
+    Ok(records.iter().any(|record| {
+        record.record_type == punch.record_type
+            && record.timestamp.timestamp_micros() == punch.timestamp.timestamp_micros()
+    }))
+}
+
+async fn attach_note(
+    pool: &DbPool,
+    user_id: i64,
+    date: chrono::NaiveDate,
+    start_time: DateTime<Utc>,
+    note: &str,
+) -> Result<()> {
+    let sessions = queries::get_work_sessions_by_date_range(pool, user_id, date, date).await?;
+    if let Some(session) = sessions
+        .into_iter()
+        .find(|session| session.start_time == start_time)
+    {
+        queries::set_session_note(pool, session.id, Some(note)).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn punch(discord_id: &str, record_type: RecordType) -> PendingPunch {
+        PendingPunch {
+            discord_id: discord_id.to_string(),
+            username: "tester".to_string(),
+            record_type,
+            timestamp: Utc::now(),
+            guild_id: None,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_punch_journal() {
+        let path =
+            std::env::temp_dir().join(format!("punch_journal_test_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let journal = PunchJournal::new(&path);
+        assert!(journal.pending().unwrap().is_empty());
+
+        let first = punch("1", RecordType::Start);
+        let second = punch("2", RecordType::End);
+        journal.append(&first).unwrap();
+        journal.append(&second).unwrap();
+        assert_eq!(journal.pending().unwrap(), vec![first, second.clone()]);
+        assert!(journal.has_pending("1"));
+
+        journal.remove_first().unwrap();
+        assert_eq!(journal.pending().unwrap(), vec![second]);
+        assert!(!journal.has_pending("1"));
+
+        journal.remove_first().unwrap();
+        assert!(journal.pending().unwrap().is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    #[tokio::test]
+    async fn test_replay_skips_punch_already_recorded() {
+        let pool = crate::database::create_test_pool().await;
+        let pending = punch("1", RecordType::Start);
+
+        assert!(matches!(
+            replay(&pool, &pending).await,
+            ReplayOutcome::Recorded
+        ));
+        // ジャーナルから消す前に止まって同じ打刻をもう一度再生しても記録は増えない
+        assert!(matches!(
+            replay(&pool, &pending).await,
+            ReplayOutcome::Recorded
+        ));
+
+        let user = queries::create_or_get_user(&pool, "1", None::<&str>, "tester")
+            .await
+            .unwrap();
+        let date = crate::utils::time::get_date_from_utc_timestamp(pending.timestamp);
+        let records = queries::get_records_by_date(&pool, user.id, date)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+    }
+}
@@ -0,0 +1,55 @@
+// Per-user lock around punches. Recording a start or end reads the day's records, checks the
+// last one and then inserts, so a double-clicked kiosk button or two `/start` invocations
+// arriving together could both pass the check and insert twice. Every path that adds a punch
+// (`/start`, `/end`, `shortcuts::record_attendance`, the status add-record modals, correction
+// suggestions and approved changes) holds this lock for the user from the check until the
+// record is written.
+//
+// The lock lives in this process only: it serializes punches handled by one running bot, and
+// does nothing for a second bot process sharing the database. Journal replay goes through
+// `shortcuts::record_attendance` and so takes the lock too, and additionally skips a pending
+// punch whose record is already in the database (see `punch_journal::replay`). Run a single bot
+// process per database.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use tokio::sync::OwnedMutexGuard;
+
+type UserLocks = HashMap<i64, Arc<tokio::sync::Mutex<()>>>;
+
+static LOCKS: LazyLock<Mutex<UserLocks>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// ユーザーの打刻を直列にするロックを取得する（ガードを破棄するまで保持）
+pub async fn lock(user_id: i64) -> OwnedMutexGuard<()> {
+    let user_lock = {
+        let mut locks = LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+        // 誰も使っていないロックは捨てて、マップが増え続けないようにする
+        locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+        locks.entry(user_id).or_default().clone()
+    };
+    user_lock.lock_owned().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_lock_serializes_same_user_only() {
+        let guard = lock(-1).await;
+
+        // 別のユーザーは待たされない
+        let other = tokio::time::timeout(Duration::from_millis(100), lock(-2)).await;
+        assert!(other.is_ok());
+
+        // 同じユーザーはガードを破棄するまで待たされる
+        let waiting = tokio::spawn(lock(-1));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        drop(guard);
+        let second = tokio::time::timeout(Duration::from_secs(1), waiting).await;
+        assert!(second.is_ok());
+    }
+}
@@ -0,0 +1,217 @@
+// Interpreter for user-defined shortcuts. Runs the parsed steps in order for the invoking
+// user and stops at the first failing step, so `/shortcut run` and the shortcut buttons
+// share the same behaviour.
+
+use crate::bot::{AppError, edit_policy, metrics, preferences, punch_lock, webhooks};
+use crate::database::DbPool;
+use crate::database::models::{AuditSource, RecordType, User};
+use crate::database::queries;
+use crate::t;
+use crate::utils::format::{create_error_embed, create_report_embed, create_success_embed};
+use crate::utils::record_validator::{EditPolicy, RecordValidator};
+use crate::utils::session_manager::SessionManager;
+use crate::utils::shortcut::{ReportKind, ShortcutAction};
+use crate::utils::time::{
+    combine_date_time_jst, format_duration_minutes, format_time_jst, get_current_date_jst,
+    get_date_from_utc_timestamp, get_month_range, get_week_start,
+};
+use chrono::{DateTime, NaiveTime, Utc};
+use poise::serenity_prelude as serenity;
+
+/// 手順を順に実行し、各手順の結果を埋め込みで返す（失敗した手順で中断する）
+pub async fn execute_shortcut(
+    pool: &DbPool,
+    author: &serenity::User,
+    guild_id: Option<serenity::GuildId>,
+    actions: &[ShortcutAction],
+) -> Vec<serenity::CreateEmbed> {
+    let user =
+        match queries::create_or_get_user(pool, &author.id.to_string(), guild_id, &author.name)
+            .await
+        {
+            Ok(user) => user,
+            Err(e) => {
+                let prefs =
+                    preferences::resolve(pool, &author.id.to_string(), guild_id, None).await;
+                let error = AppError::internal(t!(prefs.lang, "error.user_lookup"), e);
+                error.log();
+                return vec![error.embed(prefs.lang)];
+            }
+        };
+    let audit = AuditSource::new(author.id, "/shortcut").in_guild(guild_id);
+    let policy = edit_policy::load(pool, guild_id, false).await;
+
+    let mut embeds = Vec::new();
+    for action in actions {
+        let result = match action {
+            ShortcutAction::Start(time) => {
+                add_record(
+                    pool,
+                    &user,
+                    RecordType::Start,
+                    *time,
+                    guild_id,
+                    &audit,
+                    &policy,
+                )
+                .await
+            }
+            ShortcutAction::End(time) => {
+                add_record(
+                    pool,
+                    &user,
+                    RecordType::End,
+                    *time,
+                    guild_id,
+                    &audit,
+                    &policy,
+                )
+                .await
+            }
+            ShortcutAction::Report(kind) => report(pool, &user, guild_id, *kind).await,
+        };
+
+        match result {
+            Ok(embed) => embeds.push(embed),
+            Err(error) => {
+                embeds.push(create_error_embed(
+                    &format!("「{}」を実行できませんでした", action),
+                    &error.into_message(),
+                ));
+                break;
+            }
+        }
+    }
+    embeds
+}
+
+async fn add_record(
+    pool: &DbPool,
+    user: &User,
+    record_type: RecordType,
+    time: Option<NaiveTime>,
+    guild_id: Option<serenity::GuildId>,
+    audit: &AuditSource,
+    policy: &EditPolicy,
+) -> Result<serenity::CreateEmbed, AppError> {
+    let timestamp = match time {
+        Some(time) => combine_date_time_jst(get_current_date_jst(), time),
+        None => chrono::Utc::now(),
+    };
+
+    let started_at =
+        record_attendance(pool, user, record_type, timestamp, guild_id, audit, policy).await?;
+
+    let embed = match (record_type, started_at) {
+        (RecordType::End, Some(started_at)) => create_success_embed(
+            "勤務終了",
+            &format!(
+                "終了時刻: {}\n勤務時間: {}",
+                format_time_jst(timestamp),
+                format_duration_minutes((timestamp - started_at).num_minutes() as i32)
+            ),
+        ),
+        _ => create_success_embed(
+            "勤務開始",
+            &format!("開始時刻: {}", format_time_jst(timestamp)),
+        ),
+    };
+    Ok(embed)
+}
+
+/// 開始・終了の記録を追加してセッションを再計算する（終了時は勤務開始の時刻を返す）
+///
+/// ショートカットとREST APIで共通の処理。/start・/end と同じく直前の記録から勤務中かどうかを判定する
+/// （`policy` は操作した人に適用する編集ルール）
+pub async fn record_attendance(
+    pool: &DbPool,
+    user: &User,
+    record_type: RecordType,
+    timestamp: DateTime<Utc>,
+    guild_id: Option<serenity::GuildId>,
+    audit: &AuditSource,
+    policy: &EditPolicy,
+) -> Result<Option<DateTime<Utc>>, AppError> {
+    let _punch_guard = punch_lock::lock(user.id).await;
+    let date = get_date_from_utc_timestamp(timestamp);
+
+    let records = queries::get_records_by_date(pool, user.id, date)
+        .await
+        .map_err(|e| AppError::internal("勤務記録の取得に失敗しました", e))?;
+
+    let last_start = records
+        .iter()
+        .filter(|record| record.record_type.is_session_boundary() && record.timestamp < timestamp)
+        .max_by_key(|record| record.timestamp)
+        .filter(|record| record.record_type == RecordType::Start)
+        .map(|record| record.timestamp);
+    match (record_type, last_start) {
+        (RecordType::Start, Some(start)) => {
+            return Err(AppError::user(format!(
+                "既に勤務中です（開始時刻: {}）",
+                format_time_jst(start)
+            )));
+        }
+        (RecordType::End, None) => return Err(AppError::user("勤務中ではありません")),
+        _ => {}
+    }
+
+    if let Err(e) =
+        RecordValidator::validate_new_record(&records, record_type, timestamp, date, None, policy)
+    {
+        metrics::record_validation_rejection(pool, guild_id, &e).await;
+        return Err(AppError::user(e.to_string()));
+    }
+
+    queries::create_attendance_record(pool, user.id, record_type, timestamp, audit)
+        .await
+        .map_err(|e| AppError::internal("勤務記録の作成に失敗しました", e))?;
+    webhooks::notify_record(pool, guild_id, user, record_type, timestamp, audit).await;
+
+    let session_manager = SessionManager::new(pool.clone());
+    if let Err(e) = session_manager.trigger_recalculation(user.id, date).await {
+        tracing::error!("Failed to recalculate sessions: {}", e);
+    }
+
+    Ok(last_start)
+}
+
+async fn report(
+    pool: &DbPool,
+    user: &User,
+    guild_id: Option<serenity::GuildId>,
+    kind: ReportKind,
+) -> Result<serenity::CreateEmbed, AppError> {
+    let today = get_current_date_jst();
+    let (title, start_date) = match kind {
+        ReportKind::Daily => ("report.daily_title", today),
+        ReportKind::Weekly => ("report.weekly_title", get_week_start(today)),
+        ReportKind::Monthly => ("report.monthly_title", get_month_range(today).0),
+    };
+
+    let sessions = queries::get_work_sessions_by_date_range(pool, user.id, start_date, today)
+        .await
+        .map_err(|e| AppError::internal("勤務記録の取得に失敗しました", e))?;
+
+    let prefs = preferences::resolve(pool, &user.discord_id, guild_id, None).await;
+    let lang = prefs.lang;
+    let date_format = t!(lang, "format.date");
+    let date_range = if start_date == today {
+        today.format(&date_format).to_string()
+    } else {
+        t!(
+            lang,
+            "format.date_range",
+            start = start_date.format(&date_format),
+            end = today.format(&date_format)
+        )
+    };
+    Ok(create_report_embed(
+        &user.username,
+        &t!(lang, title),
+        &date_range,
+        &sessions,
+        lang,
+        prefs.clock,
+    ))
+}
@@ -0,0 +1,136 @@
+use crate::database::models::GuildSettings;
+use crate::database::{DbPool, queries};
+use crate::utils::format::{create_info_embed, format_dashboard};
+use crate::utils::time::get_current_date_jst;
+use anyhow::Result;
+use poise::serenity_prelude as serenity;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// ダッシュボードを更新する間隔
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(180);
+
+pub async fn run(http: Arc<serenity::Http>, pool: DbPool) {
+    let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+    // 最後に書き込んだ内容（変わっていないときは編集しない）
+    let mut rendered = HashMap::new();
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = refresh_dashboards(&http, &pool, &mut rendered).await {
+            tracing::error!("Failed to refresh dashboards: {:?}", e);
+        }
+    }
+}
+
+/// サーバーの勤務中のメンバーと今日の勤務時間の埋め込みを作る
+pub async fn build_embed(pool: &DbPool, guild_id: &str) -> Result<serenity::CreateEmbed> {
+    // 日付をまたぐ勤務も表示できるよう、前日以降のセッションを対象にする
+    let today = get_current_date_jst();
+    let since = today.pred_opt().unwrap_or(today);
+
+    let mut members = Vec::new();
+    for user in queries::get_guild_users(pool, guild_id).await? {
+        let sessions =
+            queries::get_work_sessions_by_date_range(pool, user.id, since, today).await?;
+        members.push((user, sessions));
+    }
+
+    let title = format!("📋 勤務状況ダッシュボード {}", today.format("%m/%d"));
+    Ok(create_info_embed(
+        &title,
+        &format_dashboard(&members, today, chrono::Utc::now()),
+    )
+    .footer(serenity::CreateEmbedFooter::new(format!(
+        "{}分ごとに自動更新",
+        REFRESH_INTERVAL.as_secs() / 60
+    ))))
+}
+
+/// 設定されているダッシュボードのメッセージ
+pub fn dashboard_message(
+    settings: &GuildSettings,
+) -> Option<(serenity::ChannelId, serenity::MessageId)> {
+    let channel_id = settings.dashboard_channel_id.as_deref()?.parse().ok()?;
+    let message_id = settings.dashboard_message_id.as_deref()?.parse().ok()?;
+    Some((
+        serenity::ChannelId::new(channel_id),
+        serenity::MessageId::new(message_id),
+    ))
+}
+
+/// 設定されたダッシュボードのメッセージを最新の内容に書き換える
+async fn refresh_dashboards(
+    http: &serenity::Http,
+    pool: &DbPool,
+    rendered: &mut HashMap<serenity::MessageId, serde_json::Value>,
+) -> Result<()> {
+    let dashboards: Vec<_> = queries::get_guilds_with_dashboard(pool)
+        .await?
+        .into_iter()
+        .filter_map(|settings| {
+            let (channel_id, message_id) = dashboard_message(&settings)?;
+            Some((settings.guild_id, channel_id, message_id))
+        })
+        .collect();
+    // 削除や作り直しで使われなくなったメッセージの内容は捨てる
+    rendered.retain(|message_id, _| dashboards.iter().any(|(_, _, id)| id == message_id));
+
+    // 1つのサーバーで失敗しても他のサーバーの更新は続ける
+    for (guild_id, channel_id, message_id) in dashboards {
+        if let Err(e) =
+            refresh_dashboard(http, pool, rendered, &guild_id, channel_id, message_id).await
+        {
+            tracing::error!(
+                "Failed to refresh dashboard for guild {}: {:?}",
+                guild_id,
+                e
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn refresh_dashboard(
+    http: &serenity::Http,
+    pool: &DbPool,
+    rendered: &mut HashMap<serenity::MessageId, serde_json::Value>,
+    guild_id: &str,
+    channel_id: serenity::ChannelId,
+    message_id: serenity::MessageId,
+) -> Result<()> {
+    let embed = build_embed(pool, guild_id).await?;
+    // 埋め込みの時刻は毎回変わるため比較に含めない
+    let mut content = serde_json::to_value(&embed)?;
+    if let Some(fields) = content.as_object_mut() {
+        fields.remove("timestamp");
+    }
+    if rendered.get(&message_id) == Some(&content) {
+        return Ok(());
+    }
+
+    let result = channel_id
+        .edit_message(http, message_id, serenity::EditMessage::new().embed(embed))
+        .await;
+    match result {
+        Ok(_) => {
+            rendered.insert(message_id, content);
+        }
+        // メッセージやチャンネルが削除された場合は更新をやめる
+        Err(serenity::Error::Http(e)) if e.status_code().map(|code| code.as_u16()) == Some(404) => {
+            tracing::info!(
+                "Dashboard message for guild {} was deleted; stopping updates",
+                guild_id
+            );
+            queries::set_dashboard_message(pool, guild_id, None).await?;
+        }
+        Err(e) => {
+            tracing::warn!("Failed to update dashboard for guild {}: {}", guild_id, e);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use crate::database::{DbPool, queries};
+use crate::utils::format::create_event_log_embed;
+use anyhow::Result;
+use poise::serenity_prelude as serenity;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 新しい変更履歴を確認する間隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// 1メッセージに載せられる埋め込みの上限
+const MAX_EMBEDS_PER_MESSAGE: usize = 10;
+/// 1回の確認で1ギルドに投稿する変更履歴の上限（残りは次回に回す）
+const MAX_EVENTS_PER_CHECK: i64 = 50;
+
+pub async fn run(http: Arc<serenity::Http>, pool: DbPool) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = post_new_events(&http, &pool).await {
+            tracing::error!("Failed to post attendance log events: {:?}", e);
+        }
+    }
+}
+
+/// 監査ログのうち未投稿のものを、操作したサーバーの勤怠ログチャンネルに投稿
+async fn post_new_events(http: &serenity::Http, pool: &DbPool) -> Result<()> {
+    for settings in queries::get_guilds_with_event_log(pool).await? {
+        let Some(channel_id) = settings
+            .event_log_channel_id
+            .as_deref()
+            .and_then(|id| id.parse::<u64>().ok())
+            .map(serenity::ChannelId::new)
+        else {
+            continue;
+        };
+
+        let events = queries::get_guild_audit_logs_after(
+            pool,
+            &settings.guild_id,
+            settings.event_log_last_id.unwrap_or(0),
+            MAX_EVENTS_PER_CHECK,
+        )
+        .await?;
+
+        for chunk in events.chunks(MAX_EMBEDS_PER_MESSAGE) {
+            let embeds = chunk
+                .iter()
+                .map(|(log, discord_id)| create_event_log_embed(log, discord_id))
+                .collect();
+            if let Err(e) = channel_id
+                .send_message(http, serenity::CreateMessage::new().embeds(embeds))
+                .await
+            {
+                // 投稿できなかった分は次回に再送する
+                tracing::warn!(
+                    "Failed to post attendance log for guild {}: {}",
+                    settings.guild_id,
+                    e
+                );
+                break;
+            }
+            if let Some((last, _)) = chunk.last() {
+                queries::set_event_log_last_id(pool, &settings.guild_id, last.id).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+// Ends `/focus` blocks when their time is up and pings the user in the channel where the block
+// was started. The block is marked finished before the message is sent, so a restart or a
+// failed send never pings twice, and a block stopped with the "中断" button is never pinged.
+
+use crate::database::{DbPool, queries};
+use anyhow::Result;
+use chrono::Utc;
+use poise::serenity_prelude as serenity;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 終了時刻を確認する間隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+pub async fn run(http: Arc<serenity::Http>, pool: DbPool) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = finish_due_focus_sessions(&http, &pool).await {
+            tracing::error!("Failed to finish focus sessions: {:?}", e);
+        }
+    }
+}
+
+/// 予定の時刻を過ぎた集中時間を終了し、ユーザーに知らせる
+async fn finish_due_focus_sessions(http: &serenity::Http, pool: &DbPool) -> Result<()> {
+    for (discord_id, focus) in queries::get_due_focus_sessions(pool, Utc::now()).await? {
+        if !queries::finish_focus_session(pool, focus.id, focus.ends_at, true).await? {
+            continue;
+        }
+        let Ok(channel_id) = focus.channel_id.parse::<u64>() else {
+            continue;
+        };
+
+        let content = format!(
+            "<@{}> 🍅 {}分の集中時間が終わりました。少し休憩しましょう",
+            discord_id,
+            (focus.ends_at - focus.started_at).num_minutes()
+        );
+        if let Err(e) = serenity::ChannelId::new(channel_id)
+            .send_message(http, serenity::CreateMessage::new().content(content))
+            .await
+        {
+            tracing::warn!(
+                "Failed to notify the end of focus session {} in channel {}: {:?}",
+                focus.id,
+                channel_id,
+                e
+            );
+        }
+    }
+
+    Ok(())
+}
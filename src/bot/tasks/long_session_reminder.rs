@@ -0,0 +1,99 @@
+// Reminders for sessions that are still open long after clock-in, usually a forgotten `/end`.
+// Once a session has been open for the guild's `/admin long_session` hours (10 by default, 0
+// turns it off), the user gets one DM with buttons to end the session now or at a time they
+// enter. The session's `long_session_reminded` flag is stored so restarts don't send it twice.
+
+use crate::bot::interactions::long_session_buttons::reminder_buttons;
+use crate::database::{DbPool, queries};
+use crate::utils::format::create_info_embed;
+use crate::utils::time::{format_time_jst, get_current_date_jst};
+use anyhow::Result;
+use chrono::Utc;
+use poise::serenity_prelude as serenity;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 送信タイミングを確認する間隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// ギルド設定がない場合（DMのユーザーなど）に知らせるまでの時間
+pub const DEFAULT_REMINDER_HOURS: i32 = 10;
+
+/// `/admin long_session` で設定できる最大の時間
+pub const MAX_REMINDER_HOURS: i32 = 24;
+
+/// 知らせる時間からこの時間数を過ぎたセッションには送らない（Botの停止中に過ぎた分への通知を防ぐ）
+const REMINDER_WINDOW_HOURS: i64 = 24;
+
+pub async fn run(http: Arc<serenity::Http>, pool: DbPool) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = send_due_reminders(&http, &pool).await {
+            tracing::error!("Failed to send long session reminders: {:?}", e);
+        }
+    }
+}
+
+/// 設定した時間を過ぎても続いている勤務のユーザーにDMを送る
+async fn send_due_reminders(http: &serenity::Http, pool: &DbPool) -> Result<()> {
+    let now = Utc::now();
+    let started_after =
+        now - chrono::Duration::hours(MAX_REMINDER_HOURS as i64 + REMINDER_WINDOW_HOURS);
+
+    for (user, session, hours) in
+        queries::get_unreminded_open_sessions(pool, started_after, DEFAULT_REMINDER_HOURS).await?
+    {
+        if hours <= 0 {
+            continue;
+        }
+        let elapsed = now - session.start_time;
+        let due = chrono::Duration::hours(hours as i64);
+        if elapsed < due {
+            continue;
+        }
+        // 期間を過ぎたセッションや送信に失敗したセッションも、もう一度送らないよう記録する
+        queries::mark_long_session_reminded(pool, session.id).await?;
+        if elapsed >= due + chrono::Duration::hours(REMINDER_WINDOW_HOURS) {
+            continue;
+        }
+        let Ok(discord_id) = user.discord_id.parse::<u64>() else {
+            continue;
+        };
+
+        let embed = create_info_embed(
+            "⏰ 勤務が続いています",
+            &format!(
+                "勤務開始（{}）から{}時間以上経過しています。退勤の記録を忘れていませんか？\n勤務を終えている場合は、下のボタンから終了を記録してください",
+                format_time_jst(session.start_time),
+                hours
+            ),
+        );
+        let message = serenity::CreateMessage::new()
+            .embed(embed)
+            .components(reminder_buttons(
+                &user,
+                session.date == get_current_date_jst(),
+            ));
+
+        let result = match serenity::UserId::new(discord_id)
+            .create_dm_channel(http)
+            .await
+        {
+            Ok(channel) => channel.send_message(http, message).await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = result {
+            tracing::warn!(
+                "Failed to send long session reminder to user {}: {:?}",
+                user.id,
+                e
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+// Background tasks spawned once the bot is ready
+
+pub mod dashboard;
+pub mod event_log;
+pub mod focus_timer;
+pub mod long_session_reminder;
+pub mod presence;
+pub mod punch_replay;
+pub mod quality_digest;
+pub mod retention;
+pub mod schedule_nudge;
+pub mod timesheet_confirmation;
+pub mod weekly_digest;
+
+use crate::api::health::ShardRunners;
+use crate::bot::Data;
+use poise::serenity_prelude as serenity;
+use std::sync::Arc;
+
+pub fn spawn_background_tasks(http: Arc<serenity::Http>, runners: ShardRunners, data: &Data) {
+    tokio::spawn(weekly_digest::run(http.clone(), data.pool.clone()));
+    tokio::spawn(quality_digest::run(http.clone(), data.pool.clone()));
+    tokio::spawn(event_log::run(http.clone(), data.pool.clone()));
+    tokio::spawn(dashboard::run(http.clone(), data.pool.clone()));
+    tokio::spawn(presence::run(runners, data.pool.clone()));
+    tokio::spawn(retention::run(data.pool.clone()));
+    tokio::spawn(schedule_nudge::run(http.clone(), data.pool.clone()));
+    tokio::spawn(long_session_reminder::run(http.clone(), data.pool.clone()));
+    tokio::spawn(focus_timer::run(http.clone(), data.pool.clone()));
+    tokio::spawn(punch_replay::run(
+        http.clone(),
+        data.pool.clone(),
+        data.punch_journal.clone(),
+    ));
+    tokio::spawn(timesheet_confirmation::run(http, data.pool.clone()));
+}
@@ -0,0 +1,98 @@
+use crate::api::health::ShardRunners;
+use crate::database::{DbPool, queries};
+use crate::utils::time::get_current_date_jst;
+use poise::serenity_prelude as serenity;
+use std::time::Duration;
+
+/// 勤務中の人数を確認する間隔
+const UPDATE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Botのステータスに勤務中の人数を表示する（人数が変わったときだけ更新）
+pub async fn run(runners: ShardRunners, pool: DbPool) {
+    let mut interval = tokio::time::interval(UPDATE_INTERVAL);
+    let mut current = None;
+
+    loop {
+        interval.tick().await;
+
+        // 日付をまたぐ勤務も数えられるよう、前日以降に始まったセッションを対象にする
+        let today = get_current_date_jst();
+        let since = today.pred_opt().unwrap_or(today);
+        let count = match queries::count_active_workers(&pool, since).await {
+            Ok(count) => count,
+            Err(e) => {
+                tracing::error!("Failed to count active workers: {:?}", e);
+                continue;
+            }
+        };
+        if current == Some(count) {
+            continue;
+        }
+
+        let runners = runners.lock().await;
+        // 起動直後でシャードが未接続の場合は次回に更新する
+        if runners.is_empty() {
+            continue;
+        }
+        for runner in runners.values() {
+            runner
+                .runner_tx
+                .set_activity(Some(serenity::ActivityData::custom(activity_text(count))));
+        }
+        current = Some(count);
+    }
+}
+
+fn activity_text(count: i64) -> String {
+    format!("勤務中: {}人", count)
+}
+
+#[cfg(all(test, not(feature = "postgres")))]
+mod tests {
+    use super::*;
+    use crate::database::create_test_pool;
+    use crate::database::models::{AuditSource, RecordType};
+    use crate::utils::session_manager::SessionManager;
+    use crate::utils::time::get_date_from_utc_timestamp;
+
+    #[tokio::test]
+    async fn test_count_active_workers() {
+        let pool = create_test_pool().await;
+        let audit = AuditSource::new("1", "/start");
+        let start = chrono::Utc::now() - chrono::Duration::minutes(30);
+        let date = get_date_from_utc_timestamp(start);
+
+        for (discord_id, ended) in [("1", false), ("2", false), ("3", true)] {
+            let user = queries::create_or_get_user(&pool, discord_id, None::<&str>, "user")
+                .await
+                .unwrap();
+            queries::create_attendance_record(&pool, user.id, RecordType::Start, start, &audit)
+                .await
+                .unwrap();
+            if ended {
+                queries::create_attendance_record(
+                    &pool,
+                    user.id,
+                    RecordType::End,
+                    start + chrono::Duration::minutes(1),
+                    &audit,
+                )
+                .await
+                .unwrap();
+            }
+            SessionManager::new(pool.clone())
+                .trigger_recalculation(user.id, date)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(queries::count_active_workers(&pool, date).await.unwrap(), 2);
+        assert_eq!(
+            queries::count_active_workers(&pool, date.succ_opt().unwrap())
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(activity_text(2), "勤務中: 2人");
+    }
+}
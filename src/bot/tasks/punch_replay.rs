@@ -0,0 +1,93 @@
+// Writes punches queued in the punch journal once the database is reachable again. Entries are
+// replayed oldest first and removed one at a time, stopping at the first one that still can't
+// reach the database so a user's start/end order is preserved.
+
+use crate::bot::preferences;
+use crate::bot::punch_journal::{PendingPunch, PunchJournal, ReplayOutcome, replay};
+use crate::database::DbPool;
+use crate::t;
+use anyhow::Result;
+use poise::serenity_prelude as serenity;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 保留中の打刻を確認する間隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+pub async fn run(http: Arc<serenity::Http>, pool: DbPool, journal: Arc<PunchJournal>) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = replay_pending(&http, &pool, &journal).await {
+            tracing::error!("Failed to replay pending punches: {:?}", e);
+        }
+    }
+}
+
+async fn replay_pending(
+    http: &serenity::Http,
+    pool: &DbPool,
+    journal: &PunchJournal,
+) -> Result<()> {
+    for punch in journal.pending()? {
+        match replay(pool, &punch).await {
+            ReplayOutcome::Recorded => {
+                tracing::info!(
+                    "Replayed pending {} punch for {} at {}",
+                    punch.record_type,
+                    punch.discord_id,
+                    punch.timestamp
+                );
+            }
+            ReplayOutcome::Rejected(reason) => {
+                tracing::warn!(
+                    "Dropped pending {} punch for {}: {}",
+                    punch.record_type,
+                    punch.discord_id,
+                    reason
+                );
+                notify_rejected(http, pool, &punch, &reason).await;
+            }
+            ReplayOutcome::Unavailable => return Ok(()),
+        }
+        journal.remove_first()?;
+    }
+
+    Ok(())
+}
+
+/// 保留として受け付けた打刻を記録できなかったことを本人にDMで伝える
+async fn notify_rejected(http: &serenity::Http, pool: &DbPool, punch: &PendingPunch, reason: &str) {
+    let Ok(discord_id) = punch.discord_id.parse::<u64>() else {
+        return;
+    };
+    let guild_id = punch
+        .guild_id
+        .as_deref()
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(serenity::GuildId::new);
+    let prefs = preferences::resolve(pool, &punch.discord_id, guild_id, None).await;
+    let message = serenity::CreateMessage::new().content(t!(
+        prefs.lang,
+        "punch.replay_rejected",
+        command = punch.record_type.as_str(),
+        time = prefs.clock.datetime(punch.timestamp),
+        reason = reason
+    ));
+    let result = match serenity::UserId::new(discord_id)
+        .create_dm_channel(http)
+        .await
+    {
+        Ok(channel) => channel.send_message(http, message).await.map(|_| ()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        tracing::warn!(
+            "Failed to notify user {} of a dropped punch: {:?}",
+            discord_id,
+            e
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use crate::database::models::User;
+use crate::database::{DbPool, queries};
+use crate::utils::data_quality::{Anomaly, detect_anomalies};
+use crate::utils::format::create_quality_digest_embed;
+use crate::utils::time::get_current_datetime_jst;
+use anyhow::Result;
+use chrono::{Days, NaiveDate, Timelike};
+use poise::serenity_prelude as serenity;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 投稿タイミングを確認する間隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// 毎日何時（JST）以降に前日分を投稿するか
+pub const QUALITY_DIGEST_HOUR_JST: u32 = 8;
+/// 1メッセージに表示できるボタンの上限（5行 × 5個）
+const MAX_FIX_BUTTONS: usize = 25;
+
+pub async fn run(http: Arc<serenity::Http>, pool: DbPool) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = post_due_digests(&http, &pool).await {
+            tracing::error!("Failed to post data quality digests: {:?}", e);
+        }
+    }
+}
+
+/// 今日分が未投稿のギルドに前日の要確認項目を投稿
+async fn post_due_digests(http: &serenity::Http, pool: &DbPool) -> Result<()> {
+    let now = get_current_datetime_jst();
+    let today = now.date_naive();
+
+    if now.hour() < QUALITY_DIGEST_HOUR_JST {
+        return Ok(());
+    }
+
+    let pending: Vec<_> = queries::get_guilds_with_quality_digest(pool)
+        .await?
+        .into_iter()
+        .filter(|settings| {
+            settings
+                .quality_digest_last_sent
+                .is_none_or(|last_sent| last_sent < today)
+        })
+        .collect();
+
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let yesterday = today - Days::new(1);
+
+    for settings in pending {
+        let Some(channel_id) = settings
+            .quality_digest_channel_id
+            .as_deref()
+            .and_then(|id| id.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        let members = collect_anomalies(pool, &settings.guild_id, yesterday).await?;
+
+        let mut message =
+            serenity::CreateMessage::new().embed(create_quality_digest_embed(yesterday, &members));
+        let buttons = fix_buttons(&members, yesterday);
+        if !buttons.is_empty() {
+            message = message.components(
+                buttons
+                    .chunks(5)
+                    .map(|row| serenity::CreateActionRow::Buttons(row.to_vec()))
+                    .collect(),
+            );
+        }
+
+        match serenity::ChannelId::new(channel_id)
+            .send_message(http, message)
+            .await
+        {
+            Ok(_) => {
+                tracing::info!(
+                    "Posted data quality digest for guild {} ({} members)",
+                    settings.guild_id,
+                    members.len()
+                );
+                queries::mark_quality_digest_sent(pool, &settings.guild_id, today).await?;
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to post data quality digest for guild {}: {:?}",
+                    settings.guild_id,
+                    e
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// サーバーのユーザーのうち、指定日に要確認の項目があるユーザーとその項目を取得
+async fn collect_anomalies(
+    pool: &DbPool,
+    guild_id: &str,
+    date: NaiveDate,
+) -> Result<Vec<(User, Vec<Anomaly>)>> {
+    let mut members = Vec::new();
+
+    for user in queries::get_guild_users(pool, guild_id).await? {
+        let records = queries::get_records_by_date(pool, user.id, date).await?;
+        let anomalies = detect_anomalies(&records);
+        if !anomalies.is_empty() {
+            members.push((user, anomalies));
+        }
+    }
+
+    Ok(members)
+}
+
+/// 本人だけが押せる修正ボタン（custom_id: "quality_fix:discord_id:YYYY-MM-DD"）
+fn fix_buttons(members: &[(User, Vec<Anomaly>)], date: NaiveDate) -> Vec<serenity::CreateButton> {
+    members
+        .iter()
+        .take(MAX_FIX_BUTTONS)
+        .map(|(user, _)| {
+            serenity::CreateButton::new(format!(
+                "quality_fix:{}:{}",
+                user.discord_id,
+                date.format("%Y-%m-%d")
+            ))
+            .label(format!("🔧 {}", user.username))
+            .style(serenity::ButtonStyle::Secondary)
+        })
+        .collect()
+}
@@ -0,0 +1,44 @@
+use crate::database::{DbPool, queries};
+use crate::utils::time::{get_current_date_jst, retention_cutoff};
+use anyhow::Result;
+use std::time::Duration;
+
+/// 保持期間を過ぎた記録を確認する間隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+pub async fn run(pool: DbPool) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = purge_expired_records(&pool).await {
+            tracing::error!("Failed to purge expired records: {:?}", e);
+        }
+    }
+}
+
+/// 保持期間が設定されたギルドの、期間を過ぎた勤怠記録と勤務セッションを削除
+async fn purge_expired_records(pool: &DbPool) -> Result<()> {
+    let today = get_current_date_jst();
+
+    for settings in queries::get_guilds_with_retention(pool).await? {
+        let Some(months) = settings.retention_months else {
+            continue;
+        };
+        let cutoff = retention_cutoff(today, months.max(1) as u32);
+        let (records, sessions) =
+            queries::purge_records_before(pool, &settings.guild_id, cutoff, false).await?;
+        if records > 0 || sessions > 0 {
+            tracing::info!(
+                "Purged {} records and {} sessions before {} for guild {}",
+                records,
+                sessions,
+                cutoff,
+                settings.guild_id
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,210 @@
+// Clock-in reminders for users who registered an expected start time with `/schedule set` or
+// planned a shift with `/shift add`, and turned on `/schedule nudge`. Once the start time has
+// passed without a clock-in, the user gets one DM; the date (or the shift's `reminded` flag) is
+// stored so restarts don't send it twice. Users who chose a time with `/schedule checkin` also
+// get a "まだ出勤していません" DM with a clock-in button at that time on their workdays (the
+// weekdays with an expected start time, or Monday to Friday), except on national holidays and
+// days they already clocked in.
+
+use crate::bot::interactions::checkin_buttons::checkin_buttons;
+use crate::database::models::RecordType;
+use crate::database::{DbPool, queries};
+use crate::utils::format::create_info_embed;
+use crate::utils::holidays::holiday_name;
+use crate::utils::schedule::match_shift_records;
+use crate::utils::time::{format_time_jst, get_current_datetime_jst, get_date_from_utc_timestamp};
+use anyhow::Result;
+use chrono::{Datelike, Timelike};
+use poise::serenity_prelude as serenity;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 送信タイミングを確認する間隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 開始予定時刻からこの分数を過ぎた分は知らせない（Botの停止中に過ぎた予定への通知を防ぐ）
+const NUDGE_WINDOW_MINUTES: i32 = 60;
+
+pub async fn run(http: Arc<serenity::Http>, pool: DbPool) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = send_due_nudges(&http, &pool).await {
+            tracing::error!("Failed to send schedule nudges: {:?}", e);
+        }
+        if let Err(e) = send_due_shift_reminders(&http, &pool).await {
+            tracing::error!("Failed to send shift reminders: {:?}", e);
+        }
+        if let Err(e) = send_due_checkin_reminders(&http, &pool).await {
+            tracing::error!("Failed to send check-in reminders: {:?}", e);
+        }
+    }
+}
+
+/// 開始予定時刻を過ぎても今日の記録がないユーザーにDMを送る
+async fn send_due_nudges(http: &serenity::Http, pool: &DbPool) -> Result<()> {
+    let now = get_current_datetime_jst();
+    let today = now.date_naive();
+    let weekday = today.weekday().num_days_from_monday() as i32;
+    let now_minutes = (now.hour() * 60 + now.minute()) as i32;
+
+    for (user, start_minutes) in queries::get_due_schedule_nudges(
+        pool,
+        weekday,
+        now_minutes - NUDGE_WINDOW_MINUTES,
+        now_minutes,
+        today,
+    )
+    .await?
+    {
+        // 出勤済みの日や送信に失敗した日も、同じ日にもう一度送らないよう記録する
+        queries::mark_schedule_nudge_sent(pool, user.id, today).await?;
+        if !queries::get_today_records(pool, user.id, today)
+            .await?
+            .is_empty()
+        {
+            continue;
+        }
+        let Ok(discord_id) = user.discord_id.parse::<u64>() else {
+            continue;
+        };
+
+        send_dm(
+            http,
+            discord_id,
+            format!(
+                "⏰ 開始予定時刻（{:02}:{:02}）を過ぎましたが、今日の出勤の記録がありません。勤務を始めている場合は `/start` で記録してください",
+                start_minutes / 60,
+                start_minutes % 60
+            ),
+        )
+        .await;
+    }
+
+    Ok(())
+}
+
+/// 開始時刻を過ぎても出勤がないシフトについてDMを送る
+async fn send_due_shift_reminders(http: &serenity::Http, pool: &DbPool) -> Result<()> {
+    let now = chrono::Utc::now();
+    let window = chrono::Duration::minutes(NUDGE_WINDOW_MINUTES as i64);
+
+    for (user, shift) in queries::get_due_shift_reminders(pool, now - window, now).await? {
+        queries::mark_shift_reminded(pool, shift.id).await?;
+        let date = get_date_from_utc_timestamp(shift.start_time);
+        let records = queries::get_today_records(pool, user.id, date).await?;
+        // シフトに対応する出勤があるか、前から勤務を続けている場合は知らせない
+        let working = records
+            .iter()
+            .filter(|record| {
+                record.record_type.is_session_boundary() && record.timestamp <= shift.start_time
+            })
+            .max_by_key(|record| record.timestamp)
+            .is_some_and(|record| record.record_type == RecordType::Start);
+        if working || match_shift_records(&shift, &records).actual_start.is_some() {
+            continue;
+        }
+        let Ok(discord_id) = user.discord_id.parse::<u64>() else {
+            continue;
+        };
+
+        send_dm(
+            http,
+            discord_id,
+            format!(
+                "⏰ シフト（{}〜{}）の開始時刻を過ぎましたが、出勤の記録がありません。勤務を始めている場合は `/start` で記録してください",
+                format_time_jst(shift.start_time),
+                format_time_jst(shift.end_time)
+            ),
+        )
+        .await;
+    }
+
+    Ok(())
+}
+
+/// 出勤リマインダーの時刻を過ぎても出勤していないユーザーに、出勤ボタン付きのDMを送る
+async fn send_due_checkin_reminders(http: &serenity::Http, pool: &DbPool) -> Result<()> {
+    let now = get_current_datetime_jst();
+    let today = now.date_naive();
+    if holiday_name(today).is_some() {
+        return Ok(());
+    }
+    let weekday = today.weekday().num_days_from_monday() as i32;
+    let now_minutes = (now.hour() * 60 + now.minute()) as i32;
+
+    for (user, reminder_minutes) in queries::get_due_checkin_reminders(
+        pool,
+        weekday,
+        now_minutes - NUDGE_WINDOW_MINUTES,
+        now_minutes,
+        today,
+    )
+    .await?
+    {
+        queries::mark_checkin_reminder_sent(pool, user.id, today).await?;
+        // 今日の出勤があるか、前日から勤務を続けている場合は知らせない
+        let started = queries::get_today_records(pool, user.id, today)
+            .await?
+            .iter()
+            .any(|record| record.record_type == RecordType::Start);
+        if started
+            || queries::get_active_work_session(pool, user.id)
+                .await?
+                .is_some()
+        {
+            continue;
+        }
+        let Ok(discord_id) = user.discord_id.parse::<u64>() else {
+            continue;
+        };
+
+        let embed = create_info_embed(
+            "🌅 まだ出勤していません",
+            &format!(
+                "{:02}:{:02} になりました。勤務を始める場合は「出勤する」を押してください",
+                reminder_minutes / 60,
+                reminder_minutes % 60
+            ),
+        );
+        send_message(
+            http,
+            discord_id,
+            serenity::CreateMessage::new()
+                .embed(embed)
+                .components(checkin_buttons(&user)),
+        )
+        .await;
+    }
+
+    Ok(())
+}
+
+async fn send_dm(http: &serenity::Http, discord_id: u64, content: String) {
+    send_message(
+        http,
+        discord_id,
+        serenity::CreateMessage::new().content(content),
+    )
+    .await;
+}
+
+async fn send_message(http: &serenity::Http, discord_id: u64, message: serenity::CreateMessage) {
+    let result = match serenity::UserId::new(discord_id)
+        .create_dm_channel(http)
+        .await
+    {
+        Ok(channel) => channel.send_message(http, message).await.map(|_| ()),
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = result {
+        tracing::warn!(
+            "Failed to send clock-in reminder to {}: {:?}",
+            discord_id,
+            e
+        );
+    }
+}
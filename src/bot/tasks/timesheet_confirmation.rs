@@ -0,0 +1,130 @@
+use crate::bot::interactions::timesheet_buttons::confirm_buttons;
+use crate::bot::preferences;
+use crate::bot::tasks::weekly_digest::{DIGEST_HOUR_JST, collect_member_sessions};
+use crate::database::{DbPool, queries};
+use crate::t;
+use crate::utils::format::create_report_embed;
+use crate::utils::time::{get_current_datetime_jst, get_week_start};
+use anyhow::Result;
+use chrono::{Days, NaiveDate, Timelike};
+use poise::serenity_prelude as serenity;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 送信タイミングを確認する間隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+pub async fn run(http: Arc<serenity::Http>, pool: DbPool) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = send_due_confirmations(&http, &pool).await {
+            tracing::error!("Failed to send timesheet confirmations: {:?}", e);
+        }
+    }
+}
+
+/// 確認依頼を有効にしたサーバーで前週に勤務記録のあるユーザーへ、未送信のタイムシート確認依頼をDMで送信
+async fn send_due_confirmations(http: &serenity::Http, pool: &DbPool) -> Result<()> {
+    let guilds = queries::get_guilds_with_timesheet_confirmation(pool).await?;
+    if guilds.is_empty() {
+        return Ok(());
+    }
+
+    let now = get_current_datetime_jst();
+    let today = now.date_naive();
+    let this_monday = get_week_start(today);
+
+    if today == this_monday && now.hour() < DIGEST_HOUR_JST {
+        return Ok(());
+    }
+
+    let week_start = this_monday - Days::new(7);
+    let week_end = this_monday - Days::new(1);
+
+    // 1つのサーバーの失敗で他のサーバーの送信を止めない
+    for guild in guilds {
+        if let Err(e) =
+            send_guild_confirmations(http, pool, &guild.guild_id, week_start, week_end).await
+        {
+            tracing::error!(
+                "Failed to send timesheet confirmations for guild {}: {:?}",
+                guild.guild_id,
+                e
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn send_guild_confirmations(
+    http: &serenity::Http,
+    pool: &DbPool,
+    guild_id: &str,
+    week_start: NaiveDate,
+    week_end: NaiveDate,
+) -> Result<()> {
+    let guild = guild_id.parse::<u64>().ok().map(serenity::GuildId::new);
+
+    for (user, sessions) in
+        collect_member_sessions(pool, Some(guild_id), week_start, week_end).await?
+    {
+        let total_minutes: i32 = sessions.iter().filter_map(|s| s.total_minutes).sum();
+
+        // 送信済みの週は作成されないため、重複送信は発生しない
+        if !queries::create_timesheet_confirmation(pool, user.id, week_start, total_minutes).await?
+        {
+            continue;
+        }
+
+        let Ok(discord_id) = user.discord_id.parse::<u64>() else {
+            continue;
+        };
+
+        // DMにはロケールが無いため、本人とサーバーの設定の言語で送る
+        let prefs = preferences::resolve(pool, &user.discord_id, guild, None).await;
+        let lang = prefs.lang;
+        let date_format = t!(lang, "format.date");
+        let date_range = t!(
+            lang,
+            "format.date_range",
+            start = week_start.format(&date_format),
+            end = week_end.format(&date_format)
+        );
+        let embed = create_report_embed(
+            &user.username,
+            &t!(lang, "timesheet.title"),
+            &date_range,
+            &sessions,
+            lang,
+            prefs.clock,
+        );
+        let components = confirm_buttons(&user, week_start, lang);
+
+        let message = serenity::CreateMessage::new()
+            .content(t!(lang, "timesheet.request"))
+            .embed(embed)
+            .components(components);
+
+        let result = match serenity::UserId::new(discord_id)
+            .create_dm_channel(http)
+            .await
+        {
+            Ok(channel) => channel.send_message(http, message).await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = result {
+            tracing::warn!(
+                "Failed to send timesheet confirmation to user {}: {:?}",
+                user.id,
+                e
+            );
+        }
+    }
+
+    Ok(())
+}
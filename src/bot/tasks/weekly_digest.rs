@@ -0,0 +1,121 @@
+use crate::database::models::{User, WorkSession};
+use crate::database::{DbPool, queries};
+use crate::utils::format::create_weekly_digest_embed;
+use crate::utils::time::{get_current_datetime_jst, get_week_start};
+use anyhow::Result;
+use chrono::{Days, NaiveDate, Timelike};
+use poise::serenity_prelude as serenity;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 投稿タイミングを確認する間隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// 月曜日の何時（JST）以降に投稿するか
+pub const DIGEST_HOUR_JST: u32 = 9;
+
+pub async fn run(http: Arc<serenity::Http>, pool: DbPool) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = post_due_digests(&http, &pool).await {
+            tracing::error!("Failed to post weekly digests: {:?}", e);
+        }
+    }
+}
+
+/// 今週分のダイジェストが未投稿のギルドに前週のサマリーを投稿
+async fn post_due_digests(http: &serenity::Http, pool: &DbPool) -> Result<()> {
+    let now = get_current_datetime_jst();
+    let today = now.date_naive();
+    let this_monday = get_week_start(today);
+
+    // 月曜日は指定時刻になるまで待つ（ボット停止中に月曜を過ぎた場合は即投稿）
+    if today == this_monday && now.hour() < DIGEST_HOUR_JST {
+        return Ok(());
+    }
+
+    let guilds = queries::get_guilds_with_weekly_digest(pool).await?;
+    let pending: Vec<_> = guilds
+        .into_iter()
+        .filter(|settings| {
+            settings
+                .weekly_digest_last_sent
+                .is_none_or(|last_sent| last_sent < this_monday)
+        })
+        .collect();
+
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let week_start = this_monday - Days::new(7);
+    let week_end = this_monday - Days::new(1);
+
+    for settings in pending {
+        let Some(channel_id) = settings
+            .weekly_digest_channel_id
+            .as_deref()
+            .and_then(|id| id.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        let members =
+            collect_member_sessions(pool, Some(&settings.guild_id), week_start, week_end).await?;
+
+        let date_range = format!(
+            "{} ～ {}",
+            week_start.format("%Y年%m月%d日"),
+            week_end.format("%Y年%m月%d日")
+        );
+        let embed = create_weekly_digest_embed(&date_range, &members);
+
+        match serenity::ChannelId::new(channel_id)
+            .send_message(http, serenity::CreateMessage::new().embed(embed))
+            .await
+        {
+            Ok(_) => {
+                tracing::info!(
+                    "Posted weekly digest for guild {} ({} members)",
+                    settings.guild_id,
+                    members.len()
+                );
+                queries::mark_weekly_digest_sent(pool, &settings.guild_id, this_monday).await?;
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to post weekly digest for guild {}: {:?}",
+                    settings.guild_id,
+                    e
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 期間内に勤務記録のあるメンバーとそのセッション一覧を取得（`guild_id` が `None` なら全サーバー）
+pub async fn collect_member_sessions(
+    pool: &DbPool,
+    guild_id: Option<&str>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<(User, Vec<WorkSession>)>> {
+    let mut members = Vec::new();
+
+    let users = match guild_id {
+        Some(guild_id) => queries::get_guild_users(pool, guild_id).await?,
+        None => queries::get_all_users(pool).await?,
+    };
+    for user in users {
+        let sessions =
+            queries::get_work_sessions_by_date_range(pool, user.id, start_date, end_date).await?;
+        if !sessions.is_empty() {
+            members.push((user, sessions));
+        }
+    }
+
+    Ok(members)
+}
@@ -0,0 +1,177 @@
+// Outgoing webhooks for clock-in/clock-out events. When a guild configures `/admin webhook`,
+// every start/end record created from a command, button or shortcut in that guild is POSTed
+// as JSON. Delivery is best-effort and runs in a background task, so a slow or failing
+// receiver never delays or breaks the Discord response. The same record event also updates
+// the guild's working role (`working_role.rs`).
+
+use crate::bot::working_role;
+use crate::database::models::{AuditSource, RecordType, User};
+use crate::database::{DbPool, queries};
+use chrono::{DateTime, Utc};
+use poise::serenity_prelude as serenity;
+use serde::Serialize;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_default()
+});
+
+/// Webhookで送る打刻イベント
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordEvent {
+    /// "clock_in"・"clock_out"・"break_start"・"break_end"・"away"
+    pub event: &'static str,
+    pub guild_id: String,
+    pub discord_id: String,
+    pub username: String,
+    pub timestamp: DateTime<Utc>,
+    /// 記録の操作元（"/start"、"status:add_end" など監査ログと同じ値）
+    pub source: String,
+}
+
+impl RecordEvent {
+    pub fn new(
+        guild_id: serenity::GuildId,
+        user: &User,
+        record_type: RecordType,
+        timestamp: DateTime<Utc>,
+        audit: &AuditSource,
+    ) -> Self {
+        Self {
+            event: match record_type {
+                RecordType::Start => "clock_in",
+                RecordType::End => "clock_out",
+                RecordType::BreakStart => "break_start",
+                RecordType::BreakEnd => "break_end",
+                RecordType::Away => "away",
+            },
+            guild_id: guild_id.to_string(),
+            discord_id: user.discord_id.clone(),
+            username: user.username.clone(),
+            timestamp,
+            source: audit.source.clone(),
+        }
+    }
+}
+
+/// ギルドの設定に応じて打刻イベントをWebhookに送り、勤務中ロールを更新する（DMでの操作は対象外）
+pub async fn notify_record(
+    pool: &DbPool,
+    guild_id: Option<serenity::GuildId>,
+    user: &User,
+    record_type: RecordType,
+    timestamp: DateTime<Utc>,
+    audit: &AuditSource,
+) {
+    let Some(guild_id) = guild_id else {
+        return;
+    };
+    let settings = match queries::get_guild_settings(pool, &guild_id.to_string()).await {
+        Ok(Some(settings)) => settings,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!("Failed to load webhook settings: {}", e);
+            return;
+        }
+    };
+    if let Some(role_id) = &settings.working_role_id {
+        working_role::update(guild_id, role_id, user, record_type, timestamp);
+    }
+    let Some(url) = settings.webhook_url else {
+        return;
+    };
+
+    let event = RecordEvent::new(guild_id, user, record_type, timestamp, audit);
+    tokio::spawn(async move {
+        if let Err(e) = deliver(&url, &event).await {
+            tracing::warn!(
+                "Failed to deliver webhook for guild {}: {}",
+                event.guild_id,
+                e
+            );
+        }
+    });
+}
+
+async fn deliver(url: &str, event: &RecordEvent) -> Result<(), reqwest::Error> {
+    CLIENT
+        .post(url)
+        .json(event)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+#[cfg(all(test, not(feature = "postgres")))]
+mod tests {
+    use super::*;
+    use crate::database::create_test_pool;
+    use axum::Json;
+    use axum::extract::State;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_notify_record_posts_event() {
+        let (tx, mut rx) = mpsc::unbounded_channel::<serde_json::Value>();
+        let app = axum::Router::new()
+            .route(
+                "/hook",
+                axum::routing::post(
+                    |State(tx): State<mpsc::UnboundedSender<serde_json::Value>>,
+                     Json(body): Json<serde_json::Value>| async move {
+                        tx.send(body).unwrap();
+                    },
+                ),
+            )
+            .with_state(tx);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let pool = create_test_pool().await;
+        let user = queries::create_or_get_user(&pool, "1234", Some("42"), "taro")
+            .await
+            .unwrap();
+        let audit = AuditSource::new("1234", "/start");
+        let guild_id = serenity::GuildId::new(42);
+        let timestamp = Utc::now();
+
+        // 未設定のギルドには送らない
+        notify_record(
+            &pool,
+            Some(guild_id),
+            &user,
+            RecordType::Start,
+            timestamp,
+            &audit,
+        )
+        .await;
+        queries::set_webhook_url(&pool, "42", Some(&format!("http://{}/hook", addr)))
+            .await
+            .unwrap();
+        notify_record(
+            &pool,
+            Some(guild_id),
+            &user,
+            RecordType::End,
+            timestamp,
+            &audit,
+        )
+        .await;
+
+        let body = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(body["event"], "clock_out");
+        assert_eq!(body["guild_id"], "42");
+        assert_eq!(body["discord_id"], "1234");
+        assert_eq!(body["source"], "/start");
+        assert!(rx.try_recv().is_err());
+    }
+}
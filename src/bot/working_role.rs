@@ -0,0 +1,108 @@
+// The optional "勤務中" role from `/admin working_role`. `webhooks::notify_record` calls
+// `update` for every record event in a guild with the role set, so the role is added on
+// clock-in and removed on clock-out wherever the record came from (commands, buttons,
+// shortcuts, the kiosk, the REST API). Records dated before today are corrections and leave
+// the role alone. Role changes need Manage Roles and a bot role above the working role;
+// failures are only logged.
+
+use crate::database::models::{RecordType, User};
+use crate::database::{DbPool, queries};
+use crate::utils::time::{get_current_date_jst, get_date_from_utc_timestamp};
+use chrono::{DateTime, Utc};
+use poise::serenity_prelude as serenity;
+use std::sync::{Arc, OnceLock};
+
+/// ロールの付け外しに使うクライアント（起動時に `init` で設定。未設定の間は何もしない）
+static HTTP: OnceLock<Arc<serenity::Http>> = OnceLock::new();
+
+pub fn init(http: Arc<serenity::Http>) {
+    let _ = HTTP.set(http);
+}
+
+/// 記録の種類に応じて勤務中ロールを付ける・外す（開始・終了以外と過去の日付は対象外）
+pub fn update(
+    guild_id: serenity::GuildId,
+    role_id: &str,
+    user: &User,
+    record_type: RecordType,
+    timestamp: DateTime<Utc>,
+) {
+    let working = match record_type {
+        RecordType::Start => true,
+        RecordType::End => false,
+        RecordType::BreakStart | RecordType::BreakEnd | RecordType::Away => return,
+    };
+    if get_date_from_utc_timestamp(timestamp) < get_current_date_jst() {
+        return;
+    }
+    let Some(http) = HTTP.get().cloned() else {
+        return;
+    };
+    let (Ok(role_id), Ok(user_id)) = (role_id.parse::<u64>(), user.discord_id.parse::<u64>())
+    else {
+        return;
+    };
+    let role_id = serenity::RoleId::new(role_id);
+    let user_id = serenity::UserId::new(user_id);
+
+    tokio::spawn(async move {
+        if let Err(e) = set_role(&http, guild_id, user_id, role_id, working).await {
+            tracing::warn!(
+                "Failed to update working role for user {} in guild {}: {:?}",
+                user_id,
+                guild_id,
+                e
+            );
+        }
+    });
+}
+
+/// ロールを変更したとき、勤務中のメンバーを新しいロールに付け替える
+pub async fn replace(
+    http: &serenity::Http,
+    pool: &DbPool,
+    guild_id: serenity::GuildId,
+    old_role: Option<serenity::RoleId>,
+    new_role: Option<serenity::RoleId>,
+) -> anyhow::Result<()> {
+    if old_role == new_role {
+        return Ok(());
+    }
+    let sessions = queries::get_active_sessions_by_guild(pool, &guild_id.to_string()).await?;
+    for (user, _) in sessions {
+        let Ok(user_id) = user.discord_id.parse::<u64>() else {
+            continue;
+        };
+        let user_id = serenity::UserId::new(user_id);
+        for (role_id, working) in [(old_role, false), (new_role, true)] {
+            let Some(role_id) = role_id else {
+                continue;
+            };
+            if let Err(e) = set_role(http, guild_id, user_id, role_id, working).await {
+                tracing::warn!(
+                    "Failed to update working role for user {} in guild {}: {:?}",
+                    user_id,
+                    guild_id,
+                    e
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn set_role(
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+    role_id: serenity::RoleId,
+    working: bool,
+) -> serenity::Result<()> {
+    if working {
+        http.add_member_role(guild_id, user_id, role_id, Some("勤務開始"))
+            .await
+    } else {
+        http.remove_member_role(guild_id, user_id, role_id, Some("勤務終了"))
+            .await
+    }
+}
@@ -1,3 +1,4 @@
+use crate::utils::record_selector::MAX_SELECT_OPTIONS;
 use anyhow::Result;
 use std::env;
 
@@ -5,7 +6,65 @@ use std::env;
 pub struct Config {
     pub discord_token: String,
     pub database_url: String,
+    /// DB接続プールとSQLiteのプラグマの設定
+    pub database: DatabaseOptions,
     pub admin_role_id: Option<String>,
+    pub export_signing_key: String,
+    /// 記録選択メニュー1ページの選択肢数（ページ移動の選択肢を含む）
+    pub select_menu_page_size: usize,
+    /// REST APIの待ち受けアドレス（未設定ならAPIを起動しない）
+    pub api_bind_addr: Option<String>,
+    /// REST APIの認証トークン（`Authorization: Bearer ...`）
+    pub api_token: Option<String>,
+    /// ヘルスチェック（`/healthz`・`/readyz`）の待ち受けアドレス（未設定なら起動しない）
+    pub health_bind_addr: Option<String>,
+    /// DBに書き込めなかった打刻を保留するファイル
+    pub punch_journal_path: String,
+}
+
+/// DB接続プールとSQLiteのプラグマ（同時の操作で "database is locked" にならないよう既定はWAL）
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatabaseOptions {
+    /// 接続プールの最大接続数
+    pub max_connections: u32,
+    /// SQLiteの `journal_mode`（PostgreSQLでは無視）
+    pub sqlite_journal_mode: String,
+    /// SQLiteの `synchronous`（PostgreSQLでは無視）
+    pub sqlite_synchronous: String,
+    /// SQLiteがロックの解除を待つ時間（ミリ秒、PostgreSQLでは無視）
+    pub sqlite_busy_timeout_ms: u64,
+}
+
+impl Default for DatabaseOptions {
+    fn default() -> Self {
+        Self {
+            max_connections: 10,
+            sqlite_journal_mode: "WAL".to_string(),
+            sqlite_synchronous: "NORMAL".to_string(),
+            sqlite_busy_timeout_ms: 5000,
+        }
+    }
+}
+
+impl DatabaseOptions {
+    fn from_env() -> Self {
+        let defaults = Self::default();
+        let non_empty = |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+
+        Self {
+            max_connections: non_empty("DATABASE_MAX_CONNECTIONS")
+                .and_then(|value| value.parse().ok())
+                .filter(|&connections| connections > 0)
+                .unwrap_or(defaults.max_connections),
+            sqlite_journal_mode: non_empty("SQLITE_JOURNAL_MODE")
+                .unwrap_or(defaults.sqlite_journal_mode),
+            sqlite_synchronous: non_empty("SQLITE_SYNCHRONOUS")
+                .unwrap_or(defaults.sqlite_synchronous),
+            sqlite_busy_timeout_ms: non_empty("SQLITE_BUSY_TIMEOUT_MS")
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(defaults.sqlite_busy_timeout_ms),
+        }
+    }
 }
 
 impl Config {
@@ -20,10 +79,45 @@ impl Config {
 
         let admin_role_id = env::var("ADMIN_ROLE_ID").ok();
 
+        // Falls back to the bot token so exports are always signed with a bot-only secret
+        let export_signing_key =
+            env::var("EXPORT_SIGNING_KEY").unwrap_or_else(|_| discord_token.clone());
+
+        let select_menu_page_size = env::var("SELECT_MENU_PAGE_SIZE")
+            .ok()
+            .and_then(|size| size.parse().ok())
+            .unwrap_or(MAX_SELECT_OPTIONS);
+
+        let api_bind_addr = env::var("API_BIND_ADDR")
+            .ok()
+            .filter(|addr| !addr.is_empty());
+        let api_token = env::var("API_TOKEN").ok().filter(|token| !token.is_empty());
+        if api_bind_addr.is_some() && api_token.is_none() {
+            return Err(anyhow::anyhow!(
+                "API_TOKEN environment variable is required when API_BIND_ADDR is set"
+            ));
+        }
+
+        let health_bind_addr = env::var("HEALTH_BIND_ADDR")
+            .ok()
+            .filter(|addr| !addr.is_empty());
+
+        let punch_journal_path = env::var("PUNCH_JOURNAL_PATH")
+            .ok()
+            .filter(|path| !path.is_empty())
+            .unwrap_or_else(|| "punch_journal.jsonl".to_string());
+
         Ok(Config {
             discord_token,
             database_url,
+            database: DatabaseOptions::from_env(),
             admin_role_id,
+            export_signing_key,
+            select_menu_page_size,
+            api_bind_addr,
+            api_token,
+            health_bind_addr,
+            punch_journal_path,
         })
     }
 }
@@ -1,76 +1,64 @@
-use anyhow::Result;
-use sqlx::SqlitePool;
+use crate::database::DbPool;
+use anyhow::{Context, Result, bail};
+use sqlx::migrate::{MigrateError, Migrator};
 use tracing::info;
 
-pub async fn run_migrations(pool: &SqlitePool) -> Result<()> {
+/// バイナリに埋め込まれたマイグレーション（`migrations/<backend>/NNNN_description.sql`）
+#[cfg(not(feature = "postgres"))]
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+#[cfg(feature = "postgres")]
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+
+pub async fn run_migrations(pool: &DbPool) -> Result<()> {
     info!("Running database migrations...");
 
-    create_users_table(pool).await?;
-    create_attendance_records_table(pool).await?;
-    create_work_sessions_table(pool).await?;
+    let expected = latest_version();
+    if let Some(current) = schema_version(pool).await?
+        && current > expected
+    {
+        bail!(
+            "Database schema version {} is newer than this binary supports ({}). Please upgrade the bot.",
+            current,
+            expected
+        );
+    }
 
-    info!("Database migrations completed successfully");
-    Ok(())
-}
-
-async fn create_users_table(pool: &SqlitePool) -> Result<()> {
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS users (
-            id INTEGER PRIMARY KEY,
-            discord_id TEXT UNIQUE NOT NULL,
-            username TEXT NOT NULL,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
+    MIGRATOR.run(pool).await.map_err(|e| match e {
+        MigrateError::VersionMismatch(version) => anyhow::anyhow!(
+            "Migration {} was modified after it was applied. Add a new migration file instead of editing an existing one.",
+            version
+        ),
+        e => e.into(),
+    })?;
 
+    info!(
+        "Database migrations completed successfully (schema version {})",
+        latest_version()
+    );
     Ok(())
 }
 
-async fn create_attendance_records_table(pool: &SqlitePool) -> Result<()> {
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS attendance_records (
-            id INTEGER PRIMARY KEY,
-            user_id INTEGER NOT NULL,
-            record_type TEXT NOT NULL CHECK (record_type IN ('start', 'end')),
-            timestamp DATETIME NOT NULL,
-            is_modified BOOLEAN DEFAULT FALSE,
-            original_timestamp DATETIME,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            FOREIGN KEY (user_id) REFERENCES users (id)
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
+/// 適用済みのスキーマバージョンとバイナリが想定するバージョン
+pub async fn schema_status(pool: &DbPool) -> Result<(Option<i64>, i64)> {
+    Ok((schema_version(pool).await?, latest_version()))
+}
 
-    Ok(())
+/// バイナリが想定するスキーマバージョン
+fn latest_version() -> i64 {
+    MIGRATOR.iter().map(|m| m.version).max().unwrap_or(0)
 }
 
-async fn create_work_sessions_table(pool: &SqlitePool) -> Result<()> {
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS work_sessions (
-            id INTEGER PRIMARY KEY,
-            user_id INTEGER NOT NULL,
-            start_time DATETIME NOT NULL,
-            end_time DATETIME,
-            total_minutes INTEGER,
-            date DATE NOT NULL,
-            is_completed BOOLEAN DEFAULT FALSE,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            FOREIGN KEY (user_id) REFERENCES users (id)
-        )
-        "#,
+/// データベースに適用済みの最新スキーマバージョン（未初期化の場合は `None`）
+async fn schema_version(pool: &DbPool) -> Result<Option<i64>> {
+    // マイグレーション管理テーブルが無い場合は新規データベースとして扱う
+    match sqlx::query_scalar::<_, Option<i64>>(
+        "SELECT MAX(version) FROM _sqlx_migrations WHERE success = TRUE",
     )
-    .execute(pool)
-    .await?;
-
-    Ok(())
+    .fetch_one(pool)
+    .await
+    {
+        Ok(version) => Ok(version),
+        Err(sqlx::Error::Database(_)) => Ok(None),
+        Err(e) => Err(e).context("Failed to read schema version"),
+    }
 }
@@ -4,17 +4,68 @@ pub mod queries_simple;
 
 pub use queries_simple as queries;
 
+use crate::config::DatabaseOptions;
 use anyhow::Result;
-use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
-use std::str::FromStr;
 
-pub async fn create_connection(database_url: &str) -> Result<SqlitePool> {
-    let connect_options = SqliteConnectOptions::from_str(database_url)?.create_if_missing(true);
+/// Database backend selected at compile time (SQLite by default, PostgreSQL with `--features postgres`)
+#[cfg(not(feature = "postgres"))]
+pub type Db = sqlx::Sqlite;
+#[cfg(feature = "postgres")]
+pub type Db = sqlx::Postgres;
 
-    let pool = SqlitePool::connect_with(connect_options).await?;
+pub type DbPool = sqlx::Pool<Db>;
+pub type DbRow = <Db as sqlx::Database>::Row;
+
+#[cfg(not(feature = "postgres"))]
+pub async fn create_connection(database_url: &str, options: &DatabaseOptions) -> Result<DbPool> {
+    use sqlx::sqlite::{
+        SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous,
+    };
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    let journal_mode = SqliteJournalMode::from_str(&options.sqlite_journal_mode)
+        .map_err(|e| anyhow::anyhow!("Invalid SQLITE_JOURNAL_MODE: {}", e))?;
+    let synchronous = SqliteSynchronous::from_str(&options.sqlite_synchronous)
+        .map_err(|e| anyhow::anyhow!("Invalid SQLITE_SYNCHRONOUS: {}", e))?;
+    let connect_options = SqliteConnectOptions::from_str(database_url)?
+        .create_if_missing(true)
+        .journal_mode(journal_mode)
+        .synchronous(synchronous)
+        .busy_timeout(Duration::from_millis(options.sqlite_busy_timeout_ms));
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(options.max_connections)
+        .connect_with(connect_options)
+        .await?;
+
+    // Run migrations
+    migrations::run_migrations(&pool).await?;
+
+    Ok(pool)
+}
+
+#[cfg(feature = "postgres")]
+pub async fn create_connection(database_url: &str, options: &DatabaseOptions) -> Result<DbPool> {
+    let pool = sqlx::postgres::PgPoolOptions::new()
+        .max_connections(options.max_connections)
+        .connect(database_url)
+        .await?;
 
     // Run migrations
     migrations::run_migrations(&pool).await?;
 
     Ok(pool)
 }
+
+/// テスト用のインメモリSQLiteプール（マイグレーション適用済み）
+#[cfg(all(test, not(feature = "postgres")))]
+pub async fn create_test_pool() -> DbPool {
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    migrations::run_migrations(&pool).await.unwrap();
+    pool
+}
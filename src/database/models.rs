@@ -1,3 +1,4 @@
+use crate::database::Db;
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -6,6 +7,8 @@ use sqlx::FromRow;
 pub struct User {
     pub id: i64,
     pub discord_id: String,
+    /// 記録したサーバー（DMでの利用は空文字）
+    pub guild_id: String,
     pub username: String,
     pub created_at: DateTime<Utc>,
 }
@@ -14,10 +17,14 @@ pub struct User {
 pub struct AttendanceRecord {
     pub id: i64,
     pub user_id: i64,
-    pub record_type: String, // "start" or "end"
+    pub record_type: RecordType,
     pub timestamp: DateTime<Utc>,
     pub is_modified: bool,
     pub original_timestamp: Option<DateTime<Utc>>,
+    pub is_imported: bool,       // チャンネル履歴から取り込んだ記録
+    pub source: Option<String>,  // 記録を追加した経路（`RecordSource`。列の追加前の記録は `None`）
+    pub locked: bool,            // 月次締め済み（変更不可）
+    pub session_id: Option<i64>, // 再計算でペアになった勤務セッション（ペアのない記録は None）
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -31,31 +38,490 @@ pub struct WorkSession {
     pub total_minutes: Option<i32>,
     pub date: NaiveDate,
     pub is_completed: bool,
+    pub note: Option<String>, // ユーザーが付けたメモ
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct GuildSettings {
+    pub guild_id: String,
+    pub weekly_digest_channel_id: Option<String>,
+    pub weekly_digest_last_sent: Option<NaiveDate>,
+    pub timesheet_confirmation_enabled: bool,
+    pub standard_daily_minutes: i32, // 時間外の判定に使う1日の所定労働時間
+    pub standard_weekly_minutes: i32, // 時間外の判定に使う週の所定労働時間
+    pub quality_digest_channel_id: Option<String>, // データ品質ダイジェストの投稿先
+    pub quality_digest_last_sent: Option<NaiveDate>,
+    pub approval_channel_id: Option<String>, // 記録修正の承認依頼の投稿先（設定時のみ承認制）
+    pub webhook_url: Option<String>,         // 打刻イベントのJSONを送るWebhookのURL
+    pub event_log_channel_id: Option<String>, // 記録の追加・修正・削除を投稿する勤怠ログチャンネル
+    pub event_log_last_id: Option<i64>,      // 勤怠ログに投稿済みの最後の監査ログID
+    pub end_note_prompt_enabled: bool,       // `/end` で「今日やったこと」を尋ねる
+    pub auto_break_after_minutes: Option<i32>, // この分数を超えた日に休憩を自動控除する（未設定で無効）
+    pub auto_break_minutes: i32,               // 自動控除で確保する休憩時間（分）
+    pub dashboard_channel_id: Option<String>,  // 定期的に更新するダッシュボードの投稿先
+    pub dashboard_message_id: Option<String>,
+    pub resume_window_minutes: i32, // `/resume` で終了を取り消せる、終了からの分数
+    pub retention_months: Option<i32>, // 記録を保持する月数（未設定で無期限）
+    pub edit_max_age_days: i32,     // 記録を追加・修正できる日数（今日から遡る）
+    pub backdate_admin_only: bool,  // 今日より前の日付の追加・修正を管理者のみに制限する
+    pub delete_requires_approval: bool, // 記録の削除も承認制にする（承認チャンネルが必要）
+    pub working_role_id: Option<String>, // 勤務中のメンバーに付けるロール
+    pub long_session_reminder_hours: i32, // 勤務がこの時間を超えたらDMで知らせる（0で無効）
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct TimesheetConfirmation {
+    pub id: i64,
+    pub user_id: i64,
+    pub week_start: NaiveDate,
+    pub total_minutes: i32,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AuditLog {
+    pub id: i64,
+    pub operation_id: Option<i64>, // 複数記録にまたがる操作の最初のログID
+    pub user_id: i64,
+    pub actor_discord_id: String,
+    pub action: String, // "create", "update" or "delete"
+    pub record_id: i64,
+    pub record_type: String,
+    pub old_timestamp: Option<DateTime<Utc>>,
+    pub new_timestamp: Option<DateTime<Utc>>,
+    pub source: String,
+    pub undone_at: Option<DateTime<Utc>>,
+    pub guild_id: Option<String>, // 操作したサーバー（DM・APIからの操作は None）
+    pub adjustment_minutes: Option<i32>, // 勤務時間の調整（record_type が "adjustment"）で加減した分数
+    pub created_at: DateTime<Utc>,
+}
+
+/// 記録の時刻を修正したときの修正前後の時刻（修正のたびに1行）
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct RecordRevision {
+    pub id: i64,
+    pub record_id: i64,
+    pub user_id: i64,
+    pub previous_timestamp: DateTime<Utc>,
+    pub new_timestamp: DateTime<Utc>,
+    pub editor_discord_id: String,
+    pub source: String,
+    pub reason: Option<String>, // 修正モーダルなどで入力された理由
+    pub created_at: DateTime<Utc>,
+}
+
+/// 管理者コマンドの実行履歴
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct CommandAuditLog {
+    pub id: i64,
+    pub guild_id: Option<String>,
+    pub actor_discord_id: String,
+    pub actor_name: String,
+    pub command: String,
+    pub arguments: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 時給設定（`user_id` が `None` の場合はギルドの既定値）
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Rate {
+    pub id: i64,
+    pub guild_id: String,
+    pub user_id: Option<i64>,
+    pub hourly_rate: i64,   // 円/時
+    pub overtime_rate: i64, // 円/時（時間外）
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 管理者が日ごとに加減した勤務時間（打刻を伴わない調整）
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct TimeAdjustment {
+    pub id: i64,
+    pub user_id: i64,
+    pub date: NaiveDate,
+    pub minutes: i32, // 加算は正、減算は負
+    pub reason: Option<String>,
+    pub guild_id: Option<String>,
+    pub created_by: String, // 調整した管理者のDiscord ID
+    pub created_at: DateTime<Utc>,
+}
+
+/// 曜日ごとの開始予定時刻
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct WorkSchedule {
+    pub user_id: i64,
+    pub weekday: i32,       // 0 = 月曜 … 6 = 日曜
+    pub start_minutes: i32, // JST 0時からの分数
+}
+
+/// `/shift add` で予定した勤務シフト
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Shift {
+    pub id: i64,
+    pub user_id: i64,
+    pub guild_id: Option<String>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub created_by: String, // 登録したユーザーのDiscord ID
+    pub reminded: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// サーバーごとのシフトのひな形（`/shift apply` で期間内の各日にシフトを作る）
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ShiftTemplate {
+    pub id: i64,
+    pub guild_id: String,
+    pub name: String,
+    pub start_minutes: i32, // JST 0時からの分数
+    pub end_minutes: i32,   // 開始より前なら翌日の終了
+}
+
+/// `/focus` で始めた勤務中の集中時間
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct FocusSession {
+    pub id: i64,
+    pub user_id: i64,
+    pub channel_id: String, // 終了を知らせるチャンネル
+    pub started_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,          // 予定の終了時刻
+    pub ended_at: Option<DateTime<Utc>>, // 集中中は None
+    pub completed: bool,                 // 予定の時間まで続けたか
+    pub created_at: DateTime<Utc>,
+}
+
+/// 契約上の月間勤務時間の上限（ユーザーごと・ギルドごと）
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct HourCap {
+    pub user_id: i64,
+    pub guild_id: String,
+    pub monthly_minutes: i32,
+    pub notify_channel_id: Option<String>, // 上限に近づいたときに管理者へ知らせるチャンネル
+    pub warned_month: Option<String>,      // 最後に管理者へ知らせた月（YYYY-MM）
+    pub warned_level: i32,                 // その月に知らせた段階（`CapLevel` の値）
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 勤務セッションに紐付けるプロジェクト（ギルドごと）
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Project {
+    pub id: i64,
+    pub guild_id: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// ユーザーのサーバーごとのプロジェクト設定（プロジェクトIDと名前の組）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectPreference {
+    /// `/start` でプロジェクトを省略したときに使うプロジェクト
+    pub default_project: Option<(i64, String)>,
+    /// 前回の勤務で使ったプロジェクト
+    pub last_project: Option<(i64, String)>,
+}
+
+/// `/settings` の個人設定（`user_settings` の行が無ければ既定値）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UserPreferences {
+    /// `ja` / `en`（`None` で自動）
+    pub language: Option<String>,
+    /// サーバーの既定の表示言語（`/admin language`。保存時は使わない）
+    pub guild_language: Option<String>,
+    pub ephemeral_responses: bool,
+    /// 時刻の表示に使うUTCからの差（分、`None` で日本時間）
+    pub utc_offset_minutes: Option<i32>,
+    pub clock_12h: bool,
+    pub private_reports: bool,
+}
+
+/// 期間内の完了済みセッションの集計（`/stats`）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SessionAggregates {
+    pub session_count: i64,
+    pub working_days: i64,
+    pub total_minutes: i64,
+    pub longest_minutes: i64,
+}
+
+/// 1日の最初の開始時刻と最後の終了時刻（`/stats`）
 #[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DailySpan {
+    pub date: NaiveDate,
+    pub first_start: DateTime<Utc>,
+    pub last_end: Option<DateTime<Utc>>,
+}
+
+/// メンバーの1日分のセッションの集計（`/admin monthly`）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DailyTotal {
+    pub date: NaiveDate,
+    pub total_minutes: i64,
+    pub incomplete_sessions: i64,
+}
+
+/// ユーザーが登録した手順のショートカット（`action` は `utils::shortcut` の書式）
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Shortcut {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub action: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 管理者の承認待ちの記録変更（`action` は "create" / "update" / "delete"、`status` は "pending" / "approved" / "rejected"）
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PendingChange {
+    pub id: i64,
+    pub guild_id: String,
+    pub user_id: i64,
+    pub requested_by: String,
+    pub action: String,
+    pub record_id: Option<i64>,
+    pub record_type: RecordType,
+    pub old_timestamp: Option<DateTime<Utc>>,
+    pub new_timestamp: DateTime<Utc>,
+    pub source: String,
+    pub reason: Option<String>,
+    pub status: String,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 再起動をまたいで続けられる複数ステップの操作の途中状態（custom_id には `id` だけを載せる）
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PendingInteraction {
+    pub id: i64,
+    pub discord_user_id: String,
+    pub action: String,
+    pub payload: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 勤務記録を変更した操作者と操作元（監査ログ用）
+#[derive(Debug, Clone)]
+pub struct AuditSource {
+    pub actor_discord_id: String,
+    pub source: String,
+    /// 操作したサーバー（勤怠ログチャンネルへの投稿先）
+    pub guild_id: Option<String>,
+    /// 修正の理由（時刻を修正したときに修正履歴へ残す）
+    pub reason: Option<String>,
+}
+
+impl AuditSource {
+    /// `/undo` による打ち消し操作の操作元（取り消し対象から除外する）
+    pub const UNDO: &'static str = "/undo";
+    /// `/admin force-end` で管理者が代わりに終了した操作元
+    pub const FORCE_END: &'static str = "/admin force-end";
+
+    /// 操作元から、追加する記録の経路を決める
+    pub fn record_source(&self) -> RecordSource {
+        match self.source.as_str() {
+            "api" => RecordSource::Api,
+            "kiosk" | "status:suggestion" | "long_session" | "checkin_reminder" => {
+                RecordSource::Button
+            }
+            "/admin backfill" | "/admin import" => RecordSource::Import,
+            Self::FORCE_END => RecordSource::Admin,
+            // `/status` の記録追加・時間修正のモーダルと、その承認依頼
+            "approval" | "long_session:time" => RecordSource::Modal,
+            source if source.starts_with("status:") => RecordSource::Modal,
+            _ => RecordSource::SlashCommand,
+        }
+    }
+
+    pub fn new(actor_discord_id: impl ToString, source: &str) -> Self {
+        Self {
+            actor_discord_id: actor_discord_id.to_string(),
+            source: source.to_string(),
+            guild_id: None,
+            reason: None,
+        }
+    }
+
+    /// サーバー内での操作として記録する（DMでの操作は `None` のまま）
+    pub fn in_guild(mut self, guild_id: Option<impl ToString>) -> Self {
+        self.guild_id = guild_id.map(|guild_id| guild_id.to_string());
+        self
+    }
+
+    /// 修正の理由を添える（空欄は理由なしとして扱う）
+    pub fn with_reason(mut self, reason: Option<&str>) -> Self {
+        self.reason = reason
+            .map(str::trim)
+            .filter(|reason| !reason.is_empty())
+            .map(str::to_string);
+        self
+    }
+}
+
+/// 記録を追加した経路（`attendance_records.source`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordSource {
+    SlashCommand,
+    Button,
+    Modal,
+    Api,
+    Import,
+    Admin, // 管理者が本人の代わりに終了した記録
+}
+
+impl RecordSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecordSource::SlashCommand => "slash_command",
+            RecordSource::Button => "button",
+            RecordSource::Modal => "modal",
+            RecordSource::Api => "api",
+            RecordSource::Import => "import",
+            RecordSource::Admin => "admin",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        [
+            RecordSource::SlashCommand,
+            RecordSource::Button,
+            RecordSource::Modal,
+            RecordSource::Api,
+            RecordSource::Import,
+            RecordSource::Admin,
+        ]
+        .into_iter()
+        .find(|source| source.as_str() == s)
+    }
+}
+
+/// 打刻の種類（`attendance_records.record_type` / `pending_changes.record_type`）
+///
+/// 種類を増やすときは `as_str` と `ALL` に加える（DBには `as_str` の文字列で保存する）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum RecordType {
     Start,
     End,
+    /// 休憩の開始（勤務中のみ）
+    BreakStart,
+    /// 休憩の終了
+    BreakEnd,
+    /// 離席（勤務中の一時的な不在の記録）
+    Away,
 }
 
 impl RecordType {
+    pub const ALL: [RecordType; 5] = [
+        RecordType::Start,
+        RecordType::End,
+        RecordType::BreakStart,
+        RecordType::BreakEnd,
+        RecordType::Away,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             RecordType::Start => "start",
             RecordType::End => "end",
+            RecordType::BreakStart => "break_start",
+            RecordType::BreakEnd => "break_end",
+            RecordType::Away => "away",
         }
     }
+
+    /// 勤務セッションを区切る記録か（休憩・離席は勤務中の出来事で、勤務中かどうかの判定には使わない）
+    pub fn is_session_boundary(&self) -> bool {
+        matches!(self, RecordType::Start | RecordType::End)
+    }
+}
+
+/// 記録の種類として解釈できない文字列
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownRecordType(pub String);
+
+impl std::fmt::Display for UnknownRecordType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "不明な記録の種類です: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownRecordType {}
+
+impl std::str::FromStr for RecordType {
+    type Err = UnknownRecordType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RecordType::ALL
+            .into_iter()
+            .find(|record_type| record_type.as_str() == s)
+            .ok_or_else(|| UnknownRecordType(s.to_string()))
+    }
 }
 
-impl From<String> for RecordType {
-    fn from(s: String) -> Self {
-        match s.as_str() {
-            "start" => RecordType::Start,
-            "end" => RecordType::End,
-            _ => panic!("Invalid record type: {}", s),
+impl TryFrom<String> for RecordType {
+    type Error = UnknownRecordType;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl std::fmt::Display for RecordType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// DBではTEXT列として読み書きし、未知の値はパニックせずデコードエラーにする
+impl sqlx::Type<Db> for RecordType {
+    fn type_info() -> <Db as sqlx::Database>::TypeInfo {
+        <String as sqlx::Type<Db>>::type_info()
+    }
+
+    fn compatible(ty: &<Db as sqlx::Database>::TypeInfo) -> bool {
+        <String as sqlx::Type<Db>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, Db> for RecordType {
+    fn decode(
+        value: <Db as sqlx::Database>::ValueRef<'r>,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let s = <String as sqlx::Decode<'r, Db>>::decode(value)?;
+        Ok(s.parse()?)
+    }
+}
+
+impl<'q> sqlx::Encode<'q, Db> for RecordType {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Db as sqlx::Database>::ArgumentBuffer<'q>,
+    ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+        <&str as sqlx::Encode<'q, Db>>::encode(self.as_str(), buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_type_round_trip() {
+        for record_type in RecordType::ALL {
+            assert_eq!(record_type.as_str().parse(), Ok(record_type));
+            let json = serde_json::to_string(&record_type).unwrap();
+            assert_eq!(json, format!("\"{}\"", record_type.as_str()));
         }
+        assert_eq!(
+            RecordType::try_from("lunch".to_string()),
+            Err(UnknownRecordType("lunch".to_string()))
+        );
     }
 }
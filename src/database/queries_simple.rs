@@ -1,65 +1,119 @@
-use crate::database::models::{AttendanceRecord, RecordType, User, WorkSession};
+use crate::database::models::{
+    AttendanceRecord, AuditLog, AuditSource, CommandAuditLog, DailySpan, DailyTotal, FocusSession,
+    GuildSettings, HourCap, PendingChange, PendingInteraction, Project, ProjectPreference, Rate,
+    RecordRevision, RecordType, SessionAggregates, Shift, ShiftTemplate, Shortcut, TimeAdjustment,
+    TimesheetConfirmation, User, UserPreferences, WorkSchedule, WorkSession,
+};
+use crate::database::{Db, DbPool, DbRow};
 use anyhow::Result;
-use chrono::{DateTime, NaiveDate, TimeZone, Utc};
-use sqlx::{Row, SqlitePool};
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use sqlx::Row;
 
 // User queries using simpler API without macros
+/// サーバー単位のユーザーのキー（DMでの利用は空文字）
+fn guild_key(guild_id: Option<impl ToString>) -> String {
+    guild_id
+        .map(|guild_id| guild_id.to_string())
+        .unwrap_or_default()
+}
+
+fn user_from_row(row: &DbRow) -> User {
+    User {
+        id: row.get("id"),
+        discord_id: row.get("discord_id"),
+        guild_id: row.get("guild_id"),
+        username: row.get("username"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// ユーザーを取得（なければ作成）。同じDiscordユーザーでもサーバーごとに別のユーザーになる
 pub async fn create_or_get_user(
-    pool: &SqlitePool,
+    pool: &DbPool,
     discord_id: &str,
+    guild_id: Option<impl ToString>,
     username: &str,
 ) -> Result<User> {
+    let guild_id = guild_key(guild_id);
+
     // Try to get existing user first
-    if let Ok(user) = get_user_by_discord_id(pool, discord_id).await {
+    if let Ok(user) = get_user_by_discord_id(pool, discord_id, Some(&guild_id)).await {
         return Ok(user);
     }
 
     // Create new user if not exists
-    let result = sqlx::query("INSERT INTO users (discord_id, username) VALUES (?, ?)")
-        .bind(discord_id)
-        .bind(username)
-        .execute(pool)
-        .await?;
+    let row = sqlx::query(
+        "INSERT INTO users (discord_id, guild_id, username) VALUES ($1, $2, $3) RETURNING id",
+    )
+    .bind(discord_id)
+    .bind(&guild_id)
+    .bind(username)
+    .fetch_one(pool)
+    .await?;
 
-    let user_id = result.last_insert_rowid();
+    let user_id: i64 = row.get("id");
     get_user_by_id(pool, user_id).await
 }
 
-pub async fn get_user_by_discord_id(pool: &SqlitePool, discord_id: &str) -> Result<User> {
-    let row =
-        sqlx::query("SELECT id, discord_id, username, created_at FROM users WHERE discord_id = ?")
-            .bind(discord_id)
-            .fetch_one(pool)
-            .await?;
+pub async fn get_user_by_discord_id(
+    pool: &DbPool,
+    discord_id: &str,
+    guild_id: Option<impl ToString>,
+) -> Result<User> {
+    let row = sqlx::query(
+        "SELECT id, discord_id, guild_id, username, created_at FROM users
+         WHERE discord_id = $1 AND guild_id = $2",
+    )
+    .bind(discord_id)
+    .bind(guild_key(guild_id))
+    .fetch_one(pool)
+    .await?;
 
-    Ok(User {
-        id: row.get("id"),
-        discord_id: row.get("discord_id"),
-        username: row.get("username"),
-        created_at: row.get("created_at"),
-    })
+    Ok(user_from_row(&row))
 }
 
-pub async fn get_user_by_id(pool: &SqlitePool, user_id: i64) -> Result<User> {
-    let row = sqlx::query("SELECT id, discord_id, username, created_at FROM users WHERE id = ?")
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+pub async fn get_user_by_id(pool: &DbPool, user_id: i64) -> Result<User> {
+    let row = sqlx::query(
+        "SELECT id, discord_id, guild_id, username, created_at FROM users WHERE id = $1",
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
 
-    Ok(User {
-        id: row.get("id"),
-        discord_id: row.get("discord_id"),
-        username: row.get("username"),
-        created_at: row.get("created_at"),
-    })
+    Ok(user_from_row(&row))
+}
+
+/// 全サーバーのユーザー（同じDiscordユーザーでもサーバーごとに別の行）
+pub async fn get_all_users(pool: &DbPool) -> Result<Vec<User>> {
+    let rows = sqlx::query(
+        "SELECT id, discord_id, guild_id, username, created_at FROM users ORDER BY id ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(user_from_row).collect())
+}
+
+/// サーバーで勤怠を記録したことのあるユーザー
+pub async fn get_guild_users(pool: &DbPool, guild_id: &str) -> Result<Vec<User>> {
+    let rows = sqlx::query(
+        "SELECT id, discord_id, guild_id, username, created_at FROM users
+         WHERE guild_id = $1 ORDER BY id ASC",
+    )
+    .bind(guild_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(user_from_row).collect())
 }
 
 // Attendance record queries
 pub async fn create_attendance_record(
-    pool: &SqlitePool,
+    pool: &DbPool,
     user_id: i64,
     record_type: RecordType,
     timestamp: DateTime<Utc>,
+    audit: &AuditSource,
 ) -> Result<AttendanceRecord> {
     let record_type_str = record_type.as_str();
 
@@ -70,16 +124,39 @@ pub async fn create_attendance_record(
         timestamp
     );
 
-    let result = sqlx::query(
-        "INSERT INTO attendance_records (user_id, record_type, timestamp) VALUES (?, ?, ?)",
+    let mut tx = pool.begin().await?;
+    ensure_month_open(&mut tx, user_id, timestamp).await?;
+
+    let row = sqlx::query(
+        "INSERT INTO attendance_records (user_id, record_type, timestamp, source, guild_id)
+         VALUES ($1, $2, $3, $4, (SELECT guild_id FROM users WHERE id = $1))
+         RETURNING id",
     )
     .bind(user_id)
     .bind(record_type_str)
     .bind(timestamp)
-    .execute(pool)
+    .bind(audit.record_source().as_str())
+    .fetch_one(&mut *tx)
     .await?;
 
-    let record_id = result.last_insert_rowid();
+    let record_id: i64 = row.get("id");
+    insert_audit_log(
+        &mut tx,
+        audit,
+        AuditEntry {
+            operation_id: None,
+            action: "create",
+            user_id,
+            record_id,
+            record_type: record_type_str,
+            old_timestamp: None,
+            new_timestamp: Some(timestamp),
+            adjustment_minutes: None,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
     tracing::info!("Record inserted with ID: {}", record_id);
 
     let record = get_attendance_record_by_id(pool, record_id).await?;
@@ -93,32 +170,98 @@ pub async fn create_attendance_record(
     Ok(record)
 }
 
-pub async fn get_attendance_record_by_id(
-    pool: &SqlitePool,
-    record_id: i64,
-) -> Result<AttendanceRecord> {
+/// 過去のメッセージ履歴から記録を取り込む（同一時刻・同一種別の記録が既にある場合は何もしない）
+pub async fn import_attendance_record(
+    pool: &DbPool,
+    user_id: i64,
+    record_type: RecordType,
+    timestamp: DateTime<Utc>,
+    audit: &AuditSource,
+) -> Result<bool> {
+    let record_type_str = record_type.as_str();
+    let mut tx = pool.begin().await?;
+    ensure_month_open(&mut tx, user_id, timestamp).await?;
+
+    let existing: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM attendance_records
+         WHERE user_id = $1 AND record_type = $2 AND timestamp = $3",
+    )
+    .bind(user_id)
+    .bind(record_type_str)
+    .bind(timestamp)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if existing > 0 {
+        return Ok(false);
+    }
+
     let row = sqlx::query(
-        "SELECT id, user_id, record_type, timestamp, is_modified, original_timestamp, created_at, updated_at 
-         FROM attendance_records WHERE id = ?"
+        "INSERT INTO attendance_records (user_id, record_type, timestamp, is_imported, source, guild_id)
+         VALUES ($1, $2, $3, TRUE, 'import', (SELECT guild_id FROM users WHERE id = $1))
+         RETURNING id",
     )
-    .bind(record_id)
-    .fetch_one(pool)
+    .bind(user_id)
+    .bind(record_type_str)
+    .bind(timestamp)
+    .fetch_one(&mut *tx)
     .await?;
 
+    insert_audit_log(
+        &mut tx,
+        audit,
+        AuditEntry {
+            operation_id: None,
+            action: "create",
+            user_id,
+            record_id: row.get("id"),
+            record_type: record_type_str,
+            old_timestamp: None,
+            new_timestamp: Some(timestamp),
+            adjustment_minutes: None,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// 記録の行を読み込む（`record_type` が未知の値なら、パニックせずエラーを返す）
+fn attendance_record_from_row(row: &DbRow) -> Result<AttendanceRecord> {
     Ok(AttendanceRecord {
         id: row.get("id"),
         user_id: row.get("user_id"),
-        record_type: row.get("record_type"),
+        record_type: row.try_get("record_type")?,
         timestamp: row.get("timestamp"),
         is_modified: row.get("is_modified"),
         original_timestamp: row.get("original_timestamp"),
+        is_imported: row.get("is_imported"),
+        source: row.get("source"),
+        locked: row.get("locked"),
+        session_id: row.get("session_id"),
         created_at: row.get("created_at"),
         updated_at: row.get("updated_at"),
     })
 }
 
+pub async fn get_attendance_record_by_id(
+    pool: &DbPool,
+    record_id: i64,
+) -> Result<AttendanceRecord> {
+    let row = sqlx::query(
+        "SELECT id, user_id, record_type, timestamp, is_modified, original_timestamp, is_imported, source, locked, session_id, created_at, updated_at 
+         FROM attendance_records WHERE id = $1"
+    )
+    .bind(record_id)
+    .fetch_one(pool)
+    .await?;
+
+    attendance_record_from_row(&row)
+}
+
 pub async fn get_today_records(
-    pool: &SqlitePool,
+    pool: &DbPool,
     user_id: i64,
     date: NaiveDate,
 ) -> Result<Vec<AttendanceRecord>> {
@@ -130,7 +273,7 @@ pub async fn get_today_records(
     let start_of_day = jst_offset.from_local_datetime(&jst_start).unwrap().to_utc();
     let end_of_day = jst_offset.from_local_datetime(&jst_end).unwrap().to_utc();
 
-    tracing::info!(
+    tracing::debug!(
         "get_today_records - user_id: {}, date: {}, start_of_day: {:?}, end_of_day: {:?}",
         user_id,
         date,
@@ -138,93 +281,54 @@ pub async fn get_today_records(
         end_of_day
     );
 
-    let sql = "SELECT id, user_id, record_type, timestamp, is_modified, original_timestamp, created_at, updated_at 
+    let rows = sqlx::query(
+        "SELECT id, user_id, record_type, timestamp, is_modified, original_timestamp, is_imported, source, locked, session_id, created_at, updated_at 
          FROM attendance_records 
-         WHERE user_id = ? AND timestamp >= ? AND timestamp < ?
-         ORDER BY timestamp ASC";
-
-    tracing::info!("Executing SQL: {}", sql);
-    tracing::info!(
-        "With parameters: user_id={}, start={:?}, end={:?}",
-        user_id,
-        start_of_day,
-        end_of_day
-    );
-
-    let rows = sqlx::query(sql)
-        .bind(user_id)
-        .bind(start_of_day)
-        .bind(end_of_day)
-        .fetch_all(pool)
-        .await?;
-
-    let records: Vec<AttendanceRecord> = rows
-        .into_iter()
-        .map(|row| AttendanceRecord {
-            id: row.get("id"),
-            user_id: row.get("user_id"),
-            record_type: row.get("record_type"),
-            timestamp: row.get("timestamp"),
-            is_modified: row.get("is_modified"),
-            original_timestamp: row.get("original_timestamp"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-        })
-        .collect();
-
-    tracing::info!("get_today_records - Found {} records", records.len());
-
-    // Debug: Get all records for this user to see what's actually in the database
-    let all_user_records = sqlx::query(
-        "SELECT id, user_id, record_type, timestamp FROM attendance_records WHERE user_id = ? ORDER BY timestamp DESC LIMIT 10"
+         WHERE user_id = $1 AND timestamp >= $2 AND timestamp < $3
+         ORDER BY timestamp ASC",
     )
     .bind(user_id)
+    .bind(start_of_day)
+    .bind(end_of_day)
     .fetch_all(pool)
     .await?;
 
-    tracing::info!(
-        "All recent records for user {}: count={}",
-        user_id,
-        all_user_records.len()
-    );
-    for row in all_user_records {
-        let id: i64 = row.get("id");
-        let timestamp: DateTime<Utc> = row.get("timestamp");
-        let record_type: String = row.get("record_type");
-        tracing::info!(
-            "  Record ID={}, type={}, timestamp={:?}",
-            id,
-            record_type,
-            timestamp
-        );
-    }
+    let records: Vec<AttendanceRecord> = rows
+        .iter()
+        .map(attendance_record_from_row)
+        .collect::<Result<Vec<_>>>()?;
+
+    tracing::debug!("get_today_records - Found {} records", records.len());
 
     Ok(records)
 }
 
 // Work session queries
 pub async fn create_work_session(
-    pool: &SqlitePool,
+    pool: &DbPool,
     user_id: i64,
     start_time: DateTime<Utc>,
     date: NaiveDate,
 ) -> Result<WorkSession> {
-    let result =
-        sqlx::query("INSERT INTO work_sessions (user_id, start_time, date) VALUES (?, ?, ?)")
-            .bind(user_id)
-            .bind(start_time)
-            .bind(date)
-            .execute(pool)
-            .await?;
+    let row = sqlx::query(
+        "INSERT INTO work_sessions (user_id, start_time, date, guild_id)
+         VALUES ($1, $2, $3, (SELECT guild_id FROM users WHERE id = $1))
+         RETURNING id",
+    )
+    .bind(user_id)
+    .bind(start_time)
+    .bind(date)
+    .fetch_one(pool)
+    .await?;
 
-    let session_id = result.last_insert_rowid();
+    let session_id: i64 = row.get("id");
     get_work_session_by_id(pool, session_id).await
 }
 
-pub async fn get_work_session_by_id(pool: &SqlitePool, session_id: i64) -> Result<WorkSession> {
+pub async fn get_work_session_by_id(pool: &DbPool, session_id: i64) -> Result<WorkSession> {
     let row = sqlx::query(
-        "SELECT id, user_id, start_time, end_time, total_minutes, date, is_completed, created_at, updated_at 
-         FROM work_sessions WHERE id = ?"
+        "SELECT id, user_id, start_time, end_time, total_minutes, date, is_completed, note, created_at, updated_at 
+         FROM work_sessions WHERE id = $1"
     )
     .bind(session_id)
     .fetch_one(pool)
@@ -238,19 +342,47 @@ pub async fn get_work_session_by_id(pool: &SqlitePool, session_id: i64) -> Resul
         total_minutes: row.get("total_minutes"),
         date: row.get("date"),
         is_completed: row.get("is_completed"),
+        note: row.get("note"),
         created_at: row.get("created_at"),
         updated_at: row.get("updated_at"),
     })
 }
 
-pub async fn get_active_work_session(
-    pool: &SqlitePool,
-    user_id: i64,
+/// 記録がペアになっている勤務セッション（再計算でペアにならなかった記録は None）
+pub async fn get_work_session_for_record(
+    pool: &DbPool,
+    record_id: i64,
 ) -> Result<Option<WorkSession>> {
+    let session_id: Option<Option<i64>> =
+        sqlx::query_scalar("SELECT session_id FROM attendance_records WHERE id = $1")
+            .bind(record_id)
+            .fetch_optional(pool)
+            .await?;
+
+    match session_id.flatten() {
+        Some(session_id) => Ok(Some(get_work_session_by_id(pool, session_id).await?)),
+        None => Ok(None),
+    }
+}
+
+/// 未終了のセッションがあるユーザーの数（`since` より前の日付の未終了セッションは打刻忘れとみなして数えない）
+pub async fn count_active_workers(pool: &DbPool, since: NaiveDate) -> Result<i64> {
+    let row = sqlx::query(
+        "SELECT COUNT(DISTINCT user_id) AS count FROM work_sessions
+         WHERE is_completed = FALSE AND date >= $1",
+    )
+    .bind(since)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.get("count"))
+}
+
+pub async fn get_active_work_session(pool: &DbPool, user_id: i64) -> Result<Option<WorkSession>> {
     let row_opt = sqlx::query(
-        "SELECT id, user_id, start_time, end_time, total_minutes, date, is_completed, created_at, updated_at 
+        "SELECT id, user_id, start_time, end_time, total_minutes, date, is_completed, note, created_at, updated_at 
          FROM work_sessions 
-         WHERE user_id = ? AND is_completed = FALSE 
+         WHERE user_id = $1 AND is_completed = FALSE 
          ORDER BY start_time DESC 
          LIMIT 1"
     )
@@ -267,6 +399,7 @@ pub async fn get_active_work_session(
             total_minutes: row.get("total_minutes"),
             date: row.get("date"),
             is_completed: row.get("is_completed"),
+            note: row.get("note"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
         })),
@@ -275,7 +408,7 @@ pub async fn get_active_work_session(
 }
 
 pub async fn complete_work_session(
-    pool: &SqlitePool,
+    pool: &DbPool,
     session_id: i64,
     end_time: DateTime<Utc>,
 ) -> Result<()> {
@@ -286,8 +419,8 @@ pub async fn complete_work_session(
 
     sqlx::query(
         "UPDATE work_sessions 
-         SET end_time = ?, total_minutes = ?, is_completed = TRUE, updated_at = CURRENT_TIMESTAMP 
-         WHERE id = ?",
+         SET end_time = $1, total_minutes = $2, is_completed = TRUE, updated_at = CURRENT_TIMESTAMP 
+         WHERE id = $3",
     )
     .bind(end_time)
     .bind(total_minutes)
@@ -299,26 +432,26 @@ pub async fn complete_work_session(
 }
 
 // Get user's available dates for history (past 30 days)
-pub async fn get_user_available_dates(pool: &SqlitePool, user_id: i64) -> Result<Vec<NaiveDate>> {
-    let thirty_days_ago = chrono::Utc::now().date_naive() - chrono::Duration::days(30);
-    let today = chrono::Utc::now().date_naive();
+/// 勤務記録のある日付（JST）を新しい順にすべて取得
+pub async fn get_user_available_dates(pool: &DbPool, user_id: i64) -> Result<Vec<NaiveDate>> {
+    // Dates are derived in JST on our side so the query stays portable across database backends
+    let jst_offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
 
     let rows = sqlx::query(
-        "SELECT DISTINCT DATE(timestamp) as record_date 
-         FROM attendance_records 
-         WHERE user_id = ? AND DATE(timestamp) >= ? AND DATE(timestamp) <= ?
-         ORDER BY record_date DESC",
+        "SELECT timestamp
+         FROM attendance_records
+         WHERE user_id = $1
+         ORDER BY timestamp DESC",
     )
     .bind(user_id)
-    .bind(thirty_days_ago)
-    .bind(today)
     .fetch_all(pool)
     .await?;
 
-    let mut dates = Vec::new();
+    let mut dates: Vec<NaiveDate> = Vec::new();
     for row in rows {
-        let date_str: String = row.get("record_date");
-        if let Ok(date) = chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
+        let timestamp: DateTime<Utc> = row.get("timestamp");
+        let date = timestamp.with_timezone(&jst_offset).date_naive();
+        if dates.last() != Some(&date) {
             dates.push(date);
         }
     }
@@ -326,127 +459,260 @@ pub async fn get_user_available_dates(pool: &SqlitePool, user_id: i64) -> Result
     Ok(dates)
 }
 
-// Get records for a specific date (not just today)
-pub async fn get_records_by_date(
-    pool: &SqlitePool,
-    user_id: i64,
-    date: NaiveDate,
-) -> Result<Vec<AttendanceRecord>> {
-    // Convert JST date to UTC range
-    let jst_offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
-    let jst_start = date.and_hms_opt(0, 0, 0).unwrap();
-    let jst_end = date.succ_opt().unwrap().and_hms_opt(0, 0, 0).unwrap();
-
-    let start_of_day = jst_offset.from_local_datetime(&jst_start).unwrap().to_utc();
-    let end_of_day = jst_offset.from_local_datetime(&jst_end).unwrap().to_utc();
-
+/// 期間内の合計勤務時間（分）をユーザーごとに集計し、多い順に返す
+pub async fn get_total_minutes_by_user(
+    pool: &DbPool,
+    guild_id: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<(User, i64)>> {
     let rows = sqlx::query(
-        "SELECT id, user_id, record_type, timestamp, is_modified, original_timestamp, created_at, updated_at 
-         FROM attendance_records 
-         WHERE user_id = ? AND timestamp >= ? AND timestamp < ?
-         ORDER BY timestamp ASC"
+        "SELECT u.id, u.discord_id, u.guild_id, u.username, u.created_at,
+                SUM(ws.total_minutes) AS total_minutes
+         FROM work_sessions ws
+         JOIN users u ON u.id = ws.user_id
+         WHERE ws.guild_id = $1 AND ws.date >= $2 AND ws.date <= $3
+           AND ws.total_minutes IS NOT NULL
+         GROUP BY u.id, u.discord_id, u.guild_id, u.username, u.created_at
+         ORDER BY total_minutes DESC, u.id ASC",
     )
-    .bind(user_id)
-    .bind(start_of_day)
-    .bind(end_of_day)
+    .bind(guild_id)
+    .bind(start_date)
+    .bind(end_date)
     .fetch_all(pool)
     .await?;
 
-    let mut records = Vec::new();
-    for row in rows {
-        records.push(AttendanceRecord {
-            id: row.get("id"),
-            user_id: row.get("user_id"),
-            record_type: row.get("record_type"),
-            timestamp: row.get("timestamp"),
-            is_modified: row.get("is_modified"),
-            original_timestamp: row.get("original_timestamp"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-        });
-    }
-
-    Ok(records)
+    Ok(rows
+        .iter()
+        .map(|row| (user_from_row(row), row.get("total_minutes")))
+        .collect())
 }
 
-pub async fn get_work_sessions_by_date_range(
-    pool: &SqlitePool,
-    user_id: i64,
+/// 期間内のサーバーのセッションをメンバー・日付ごとに集計する（ユーザー、日付の順）
+pub async fn get_guild_daily_totals(
+    pool: &DbPool,
+    guild_id: &str,
     start_date: NaiveDate,
     end_date: NaiveDate,
-) -> Result<Vec<WorkSession>> {
+) -> Result<Vec<(User, DailyTotal)>> {
     let rows = sqlx::query(
-        "SELECT id, user_id, start_time, end_time, total_minutes, date, is_completed, created_at, updated_at 
-         FROM work_sessions 
-         WHERE user_id = ? AND date >= ? AND date <= ?
-         ORDER BY date ASC, start_time ASC"
+        "SELECT u.id, u.discord_id, u.guild_id, u.username, u.created_at, ws.date,
+                CAST(COALESCE(SUM(ws.total_minutes), 0) AS BIGINT) AS total_minutes,
+                CAST(SUM(CASE WHEN ws.is_completed THEN 0 ELSE 1 END) AS BIGINT) AS incomplete_sessions
+         FROM work_sessions ws
+         JOIN users u ON u.id = ws.user_id
+         WHERE ws.guild_id = $1 AND ws.date >= $2 AND ws.date <= $3
+         GROUP BY u.id, u.discord_id, u.guild_id, u.username, u.created_at, ws.date
+         ORDER BY u.id ASC, ws.date ASC",
     )
-    .bind(user_id)
+    .bind(guild_id)
     .bind(start_date)
     .bind(end_date)
     .fetch_all(pool)
     .await?;
 
-    let sessions = rows
-        .into_iter()
-        .map(|row| WorkSession {
-            id: row.get("id"),
-            user_id: row.get("user_id"),
-            start_time: row.get("start_time"),
-            end_time: row.get("end_time"),
-            total_minutes: row.get("total_minutes"),
-            date: row.get("date"),
-            is_completed: row.get("is_completed"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let total = DailyTotal {
+                date: row.get("date"),
+                total_minutes: row.get("total_minutes"),
+                incomplete_sessions: row.get("incomplete_sessions"),
+            };
+            (user_from_row(row), total)
         })
-        .collect();
+        .collect())
+}
 
-    Ok(sessions)
+/// サーバーで勤務中（未完了のセッションがある）のユーザーと、そのセッション。開始が早い順
+pub async fn get_active_sessions_by_guild(
+    pool: &DbPool,
+    guild_id: &str,
+) -> Result<Vec<(User, WorkSession)>> {
+    let rows = sqlx::query(
+        "SELECT u.id, u.discord_id, u.guild_id, u.username, u.created_at,
+                ws.id AS session_id, ws.start_time, ws.end_time, ws.total_minutes, ws.date,
+                ws.is_completed, ws.note, ws.created_at AS session_created_at, ws.updated_at
+         FROM work_sessions ws
+         JOIN users u ON u.id = ws.user_id
+         WHERE u.guild_id = $1 AND ws.is_completed = FALSE
+         ORDER BY ws.start_time ASC, u.id ASC",
+    )
+    .bind(guild_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let user = user_from_row(row);
+            let session = WorkSession {
+                id: row.get("session_id"),
+                user_id: user.id,
+                start_time: row.get("start_time"),
+                end_time: row.get("end_time"),
+                total_minutes: row.get("total_minutes"),
+                date: row.get("date"),
+                is_completed: row.get("is_completed"),
+                note: row.get("note"),
+                created_at: row.get("session_created_at"),
+                updated_at: row.get("updated_at"),
+            };
+            (user, session)
+        })
+        .collect())
 }
 
-// Additional functions for record modification
-pub async fn update_attendance_record_time(
-    pool: &SqlitePool,
-    record_id: i64,
-    new_timestamp: DateTime<Utc>,
-) -> Result<()> {
-    // First get the current record to preserve original timestamp
-    let current_record = get_attendance_record_by_id(pool, record_id).await?;
-    let original_timestamp = if current_record.is_modified {
-        current_record.original_timestamp
-    } else {
-        Some(current_record.timestamp)
-    };
+/// `started_after` 以降に始まり、長時間の勤務をまだ知らせていない未完了のセッションと、
+/// ユーザーのサーバーで知らせるまでの時間（ギルド設定が無ければ `default_hours`）
+pub async fn get_unreminded_open_sessions(
+    pool: &DbPool,
+    started_after: DateTime<Utc>,
+    default_hours: i32,
+) -> Result<Vec<(User, WorkSession, i32)>> {
+    let rows = sqlx::query(
+        "SELECT u.id, u.discord_id, u.guild_id, u.username, u.created_at,
+                ws.id AS session_id, ws.start_time, ws.end_time, ws.total_minutes, ws.date,
+                ws.is_completed, ws.note, ws.created_at AS session_created_at, ws.updated_at,
+                COALESCE(gs.long_session_reminder_hours, $2) AS reminder_hours
+         FROM work_sessions ws
+         JOIN users u ON u.id = ws.user_id
+         LEFT JOIN guild_settings gs ON gs.guild_id = u.guild_id
+         WHERE ws.is_completed = FALSE AND ws.long_session_reminded = FALSE
+           AND ws.start_time >= $1
+         ORDER BY ws.start_time ASC",
+    )
+    .bind(started_after)
+    .bind(default_hours)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let user = user_from_row(row);
+            let session = WorkSession {
+                id: row.get("session_id"),
+                user_id: user.id,
+                start_time: row.get("start_time"),
+                end_time: row.get("end_time"),
+                total_minutes: row.get("total_minutes"),
+                date: row.get("date"),
+                is_completed: row.get("is_completed"),
+                note: row.get("note"),
+                created_at: row.get("session_created_at"),
+                updated_at: row.get("updated_at"),
+            };
+            (user, session, row.get("reminder_hours"))
+        })
+        .collect())
+}
 
+/// 長時間の勤務をDMで知らせた（または知らせる必要がなかった）ことを記録する
+pub async fn mark_long_session_reminded(pool: &DbPool, session_id: i64) -> Result<()> {
     sqlx::query(
-        "UPDATE attendance_records 
-         SET timestamp = ?, is_modified = TRUE, original_timestamp = ?, updated_at = CURRENT_TIMESTAMP 
-         WHERE id = ?"
+        "UPDATE work_sessions SET long_session_reminded = TRUE, updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1",
     )
-    .bind(new_timestamp)
-    .bind(original_timestamp)
-    .bind(record_id)
+    .bind(session_id)
     .execute(pool)
     .await?;
 
     Ok(())
 }
 
-pub async fn delete_attendance_record(pool: &SqlitePool, record_id: i64) -> Result<()> {
-    sqlx::query("DELETE FROM attendance_records WHERE id = ?")
-        .bind(record_id)
-        .execute(pool)
-        .await?;
+/// 期間内のプロジェクト別の勤務時間（分）。プロジェクト未設定の分は `None`
+pub async fn get_project_minutes_by_date_range(
+    pool: &DbPool,
+    user_id: i64,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<(Option<String>, i64)>> {
+    let rows = sqlx::query(
+        "SELECT p.name AS name, SUM(ws.total_minutes) AS total_minutes
+         FROM work_sessions ws
+         LEFT JOIN projects p ON p.id = ws.project_id
+         WHERE ws.user_id = $1 AND ws.date >= $2 AND ws.date <= $3 AND ws.total_minutes IS NOT NULL
+         GROUP BY p.name
+         ORDER BY total_minutes DESC",
+    )
+    .bind(user_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(pool)
+    .await?;
 
-    Ok(())
+    Ok(rows
+        .iter()
+        .map(|row| (row.get("name"), row.get("total_minutes")))
+        .collect())
 }
 
-pub async fn delete_all_user_records_for_date(
-    pool: &SqlitePool,
+/// 期間内の完了済みセッションの件数・勤務日数・合計・最長（分）
+pub async fn get_session_aggregates(
+    pool: &DbPool,
     user_id: i64,
-    date: chrono::NaiveDate,
-) -> Result<()> {
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<SessionAggregates> {
+    let row = sqlx::query(
+        "SELECT COUNT(*) AS session_count,
+                COUNT(DISTINCT date) AS working_days,
+                CAST(COALESCE(SUM(total_minutes), 0) AS BIGINT) AS total_minutes,
+                CAST(COALESCE(MAX(total_minutes), 0) AS BIGINT) AS longest_minutes
+         FROM work_sessions
+         WHERE user_id = $1 AND date >= $2 AND date <= $3 AND is_completed = TRUE",
+    )
+    .bind(user_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(SessionAggregates {
+        session_count: row.get("session_count"),
+        working_days: row.get("working_days"),
+        total_minutes: row.get("total_minutes"),
+        longest_minutes: row.get("longest_minutes"),
+    })
+}
+
+/// 期間内の日ごとの最初の開始時刻と最後の終了時刻（完了済みのセッションのみ）
+pub async fn get_daily_spans(
+    pool: &DbPool,
+    user_id: i64,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<DailySpan>> {
+    let rows = sqlx::query(
+        "SELECT date, MIN(start_time) AS first_start, MAX(end_time) AS last_end
+         FROM work_sessions
+         WHERE user_id = $1 AND date >= $2 AND date <= $3 AND is_completed = TRUE
+         GROUP BY date
+         ORDER BY date",
+    )
+    .bind(user_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| DailySpan {
+            date: row.get("date"),
+            first_start: row.get("first_start"),
+            last_end: row.get("last_end"),
+        })
+        .collect())
+}
+
+// Get records for a specific date (not just today)
+pub async fn get_records_by_date(
+    pool: &DbPool,
+    user_id: i64,
+    date: NaiveDate,
+) -> Result<Vec<AttendanceRecord>> {
     // Convert JST date to UTC range
     let jst_offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
     let jst_start = date.and_hms_opt(0, 0, 0).unwrap();
@@ -455,15 +721,2831 @@ pub async fn delete_all_user_records_for_date(
     let start_of_day = jst_offset.from_local_datetime(&jst_start).unwrap().to_utc();
     let end_of_day = jst_offset.from_local_datetime(&jst_end).unwrap().to_utc();
 
+    let rows = sqlx::query(
+        "SELECT id, user_id, record_type, timestamp, is_modified, original_timestamp, is_imported, source, locked, session_id, created_at, updated_at 
+         FROM attendance_records 
+         WHERE user_id = $1 AND timestamp >= $2 AND timestamp < $3
+         ORDER BY timestamp ASC"
+    )
+    .bind(user_id)
+    .bind(start_of_day)
+    .bind(end_of_day)
+    .fetch_all(pool)
+    .await?;
+
+    let records = rows
+        .iter()
+        .map(attendance_record_from_row)
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(records)
+}
+
+// Get records for a range of dates (JST, both ends inclusive)
+pub async fn get_records_by_date_range(
+    pool: &DbPool,
+    user_id: i64,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<AttendanceRecord>> {
+    let jst_offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+    let jst_start = start_date.and_hms_opt(0, 0, 0).unwrap();
+    let jst_end = end_date.succ_opt().unwrap().and_hms_opt(0, 0, 0).unwrap();
+
+    let start = jst_offset.from_local_datetime(&jst_start).unwrap().to_utc();
+    let end = jst_offset.from_local_datetime(&jst_end).unwrap().to_utc();
+
+    let rows = sqlx::query(
+        "SELECT id, user_id, record_type, timestamp, is_modified, original_timestamp, is_imported, source, locked, session_id, created_at, updated_at 
+         FROM attendance_records 
+         WHERE user_id = $1 AND timestamp >= $2 AND timestamp < $3
+         ORDER BY timestamp ASC"
+    )
+    .bind(user_id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await?;
+
+    let records = rows
+        .iter()
+        .map(attendance_record_from_row)
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(records)
+}
+
+pub async fn get_work_sessions_by_date_range(
+    pool: &DbPool,
+    user_id: i64,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<WorkSession>> {
+    let rows = sqlx::query(
+        "SELECT id, user_id, start_time, end_time, total_minutes, date, is_completed, note, created_at, updated_at 
+         FROM work_sessions 
+         WHERE user_id = $1 AND date >= $2 AND date <= $3
+         ORDER BY date ASC, start_time ASC"
+    )
+    .bind(user_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(pool)
+    .await?;
+
+    let sessions = rows
+        .into_iter()
+        .map(|row| WorkSession {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            start_time: row.get("start_time"),
+            end_time: row.get("end_time"),
+            total_minutes: row.get("total_minutes"),
+            date: row.get("date"),
+            is_completed: row.get("is_completed"),
+            note: row.get("note"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+        .collect();
+
+    Ok(sessions)
+}
+
+// Get every record of a user (personal data export)
+pub async fn get_all_attendance_records(
+    pool: &DbPool,
+    user_id: i64,
+) -> Result<Vec<AttendanceRecord>> {
+    let rows = sqlx::query(
+        "SELECT id, user_id, record_type, timestamp, is_modified, original_timestamp, is_imported, source, locked, session_id, created_at, updated_at 
+         FROM attendance_records 
+         WHERE user_id = $1
+         ORDER BY timestamp ASC"
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let records = rows
+        .iter()
+        .map(attendance_record_from_row)
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(records)
+}
+
+// Get every work session of a user (personal data export)
+pub async fn get_all_work_sessions(pool: &DbPool, user_id: i64) -> Result<Vec<WorkSession>> {
+    let rows = sqlx::query(
+        "SELECT id, user_id, start_time, end_time, total_minutes, date, is_completed, note, created_at, updated_at 
+         FROM work_sessions 
+         WHERE user_id = $1
+         ORDER BY date ASC, start_time ASC"
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let sessions = rows
+        .into_iter()
+        .map(|row| WorkSession {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            start_time: row.get("start_time"),
+            end_time: row.get("end_time"),
+            total_minutes: row.get("total_minutes"),
+            date: row.get("date"),
+            is_completed: row.get("is_completed"),
+            note: row.get("note"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+        .collect();
+
+    Ok(sessions)
+}
+
+// Additional functions for record modification
+pub async fn update_attendance_record_time(
+    pool: &DbPool,
+    record_id: i64,
+    new_timestamp: DateTime<Utc>,
+    audit: &AuditSource,
+) -> Result<()> {
+    // First get the current record to preserve original timestamp
+    let current_record = get_attendance_record_by_id(pool, record_id).await?;
+    if current_record.locked {
+        return Err(anyhow::anyhow!(MONTH_CLOSED_MESSAGE));
+    }
+    let original_timestamp = if current_record.is_modified {
+        current_record.original_timestamp
+    } else {
+        Some(current_record.timestamp)
+    };
+
+    let mut tx = pool.begin().await?;
+    ensure_month_open(&mut tx, current_record.user_id, new_timestamp).await?;
+
     sqlx::query(
+        "UPDATE attendance_records 
+         SET timestamp = $1, is_modified = TRUE, original_timestamp = $2, updated_at = CURRENT_TIMESTAMP 
+         WHERE id = $3"
+    )
+    .bind(new_timestamp)
+    .bind(original_timestamp)
+    .bind(record_id)
+    .execute(&mut *tx)
+    .await?;
+
+    insert_audit_log(
+        &mut tx,
+        audit,
+        AuditEntry {
+            operation_id: None,
+            action: "update",
+            user_id: current_record.user_id,
+            record_id,
+            record_type: current_record.record_type.as_str(),
+            old_timestamp: Some(current_record.timestamp),
+            new_timestamp: Some(new_timestamp),
+            adjustment_minutes: None,
+        },
+    )
+    .await?;
+    insert_record_revision(
+        &mut tx,
+        audit,
+        current_record.user_id,
+        record_id,
+        current_record.timestamp,
+        new_timestamp,
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// 指定ユーザーの記録を1件削除し、削除できたかを返す
+pub async fn delete_attendance_record(
+    pool: &DbPool,
+    user_id: i64,
+    record_id: i64,
+    audit: &AuditSource,
+) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+
+    let locked: Option<bool> =
+        sqlx::query_scalar("SELECT locked FROM attendance_records WHERE id = $1 AND user_id = $2")
+            .bind(record_id)
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+    if locked == Some(true) {
+        return Err(anyhow::anyhow!(MONTH_CLOSED_MESSAGE));
+    }
+
+    let row = sqlx::query(
+        "DELETE FROM attendance_records WHERE id = $1 AND user_id = $2
+         RETURNING record_type, timestamp",
+    )
+    .bind(record_id)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(row) = row else {
+        return Ok(false);
+    };
+
+    let record_type: String = row.get("record_type");
+    insert_audit_log(
+        &mut tx,
+        audit,
+        AuditEntry {
+            operation_id: None,
+            action: "delete",
+            user_id,
+            record_id,
+            record_type: &record_type,
+            old_timestamp: Some(row.get("timestamp")),
+            new_timestamp: None,
+            adjustment_minutes: None,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+pub async fn delete_all_user_records_for_date(
+    pool: &DbPool,
+    user_id: i64,
+    date: chrono::NaiveDate,
+    audit: &AuditSource,
+) -> Result<()> {
+    let (start_of_day, end_of_day) = jst_day_range_utc(date);
+
+    let mut tx = pool.begin().await?;
+
+    let locked: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM attendance_records
+         WHERE user_id = $1 AND timestamp >= $2 AND timestamp < $3 AND locked = TRUE",
+    )
+    .bind(user_id)
+    .bind(start_of_day)
+    .bind(end_of_day)
+    .fetch_one(&mut *tx)
+    .await?;
+    if locked > 0 {
+        return Err(anyhow::anyhow!(MONTH_CLOSED_MESSAGE));
+    }
+
+    let rows = sqlx::query(
         "DELETE FROM attendance_records 
-         WHERE user_id = ? AND timestamp >= ? AND timestamp < ?",
+         WHERE user_id = $1 AND timestamp >= $2 AND timestamp < $3
+         RETURNING id, record_type, timestamp",
     )
     .bind(user_id)
     .bind(start_of_day)
     .bind(end_of_day)
-    .execute(pool)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut operation_id = None;
+    for row in rows {
+        let record_type: String = row.get("record_type");
+        let audit_id = insert_audit_log(
+            &mut tx,
+            audit,
+            AuditEntry {
+                operation_id,
+                action: "delete",
+                user_id,
+                record_id: row.get("id"),
+                record_type: &record_type,
+                old_timestamp: Some(row.get("timestamp")),
+                new_timestamp: None,
+                adjustment_minutes: None,
+            },
+        )
+        .await?;
+        operation_id.get_or_insert(audit_id);
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// JSTの日付をUTCの [開始, 終了) 範囲に変換
+fn jst_day_range_utc(date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let jst_offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+    let jst_start = date.and_hms_opt(0, 0, 0).unwrap();
+    let jst_end = date.succ_opt().unwrap().and_hms_opt(0, 0, 0).unwrap();
+
+    (
+        jst_offset.from_local_datetime(&jst_start).unwrap().to_utc(),
+        jst_offset.from_local_datetime(&jst_end).unwrap().to_utc(),
+    )
+}
+
+/// 締め済みの月の記録を変更しようとしたときのエラーメッセージ
+pub const MONTH_CLOSED_MESSAGE: &str = "締め済みの月の記録は変更できません";
+
+/// タイムスタンプ（JST）の月がユーザーのサーバーで締め済みならエラーを返す
+async fn ensure_month_open(
+    tx: &mut sqlx::Transaction<'_, Db>,
+    user_id: i64,
+    timestamp: DateTime<Utc>,
+) -> Result<()> {
+    let closed: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM closed_months
+         JOIN users ON users.guild_id = closed_months.guild_id
+         WHERE users.id = $1 AND closed_months.month = $2",
+    )
+    .bind(user_id)
+    .bind(jst_month_start(timestamp))
+    .fetch_one(&mut **tx)
     .await?;
 
+    if closed > 0 {
+        return Err(anyhow::anyhow!(MONTH_CLOSED_MESSAGE));
+    }
     Ok(())
 }
+
+/// タイムスタンプが属するJSTの月の1日
+fn jst_month_start(timestamp: DateTime<Utc>) -> NaiveDate {
+    let jst_offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+    let date = timestamp.with_timezone(&jst_offset).date_naive();
+    date.with_day(1).unwrap()
+}
+
+// Time adjustment queries
+const TIME_ADJUSTMENT_COLUMNS: &str =
+    "id, user_id, date, minutes, reason, guild_id, created_by, created_at";
+
+fn time_adjustment_from_row(row: &DbRow) -> TimeAdjustment {
+    TimeAdjustment {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        date: row.get("date"),
+        minutes: row.get("minutes"),
+        reason: row.get("reason"),
+        guild_id: row.get("guild_id"),
+        created_by: row.get("created_by"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// 勤務時間の調整を追加し、監査ログに記録する（締め済みの月には追加できない）
+pub async fn create_time_adjustment(
+    pool: &DbPool,
+    user_id: i64,
+    date: NaiveDate,
+    minutes: i32,
+    reason: Option<&str>,
+    audit: &AuditSource,
+) -> Result<TimeAdjustment> {
+    // 監査ログでは対象日のJSTの0時を時刻として扱う
+    let (start_of_day, _) = jst_day_range_utc(date);
+    let mut tx = pool.begin().await?;
+    ensure_month_open(&mut tx, user_id, start_of_day).await?;
+
+    let row = sqlx::query(&format!(
+        "INSERT INTO time_adjustments (user_id, date, minutes, reason, guild_id, created_by)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         RETURNING {}",
+        TIME_ADJUSTMENT_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(date)
+    .bind(minutes)
+    .bind(reason)
+    .bind(&audit.guild_id)
+    .bind(&audit.actor_discord_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    let adjustment = time_adjustment_from_row(&row);
+
+    insert_audit_log(
+        &mut tx,
+        audit,
+        AuditEntry {
+            operation_id: None,
+            action: "create",
+            user_id,
+            record_id: adjustment.id,
+            record_type: "adjustment",
+            old_timestamp: None,
+            new_timestamp: Some(start_of_day),
+            adjustment_minutes: Some(minutes),
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(adjustment)
+}
+
+/// 期間内（両端を含む）の勤務時間の調整を日付順に取得
+pub async fn get_time_adjustments_by_date_range(
+    pool: &DbPool,
+    user_id: i64,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<TimeAdjustment>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM time_adjustments
+         WHERE user_id = $1 AND date >= $2 AND date <= $3
+         ORDER BY date ASC, id ASC",
+        TIME_ADJUSTMENT_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(time_adjustment_from_row).collect())
+}
+
+/// ユーザーの勤務時間の調整をすべて取得（エクスポート用）
+pub async fn get_all_time_adjustments(pool: &DbPool, user_id: i64) -> Result<Vec<TimeAdjustment>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM time_adjustments WHERE user_id = $1 ORDER BY date ASC, id ASC",
+        TIME_ADJUSTMENT_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(time_adjustment_from_row).collect())
+}
+
+// Audit log queries
+struct AuditEntry<'a> {
+    /// 複数記録にまたがる操作で、最初の監査ログのID（単独の操作は None）
+    operation_id: Option<i64>,
+    action: &'a str,
+    user_id: i64,
+    record_id: i64,
+    record_type: &'a str,
+    old_timestamp: Option<DateTime<Utc>>,
+    new_timestamp: Option<DateTime<Utc>>,
+    /// 勤務時間の調整で加減した分数（打刻の変更は None）
+    adjustment_minutes: Option<i32>,
+}
+
+async fn insert_audit_log(
+    tx: &mut sqlx::Transaction<'_, Db>,
+    audit: &AuditSource,
+    entry: AuditEntry<'_>,
+) -> Result<i64> {
+    let row = sqlx::query(
+        "INSERT INTO audit_log
+         (operation_id, user_id, actor_discord_id, action, record_id, record_type, old_timestamp, new_timestamp, source, guild_id, adjustment_minutes)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+         RETURNING id",
+    )
+    .bind(entry.operation_id)
+    .bind(entry.user_id)
+    .bind(&audit.actor_discord_id)
+    .bind(entry.action)
+    .bind(entry.record_id)
+    .bind(entry.record_type)
+    .bind(entry.old_timestamp)
+    .bind(entry.new_timestamp)
+    .bind(&audit.source)
+    .bind(&audit.guild_id)
+    .bind(entry.adjustment_minutes)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok(row.get("id"))
+}
+
+/// 記録の時刻の修正を修正履歴に残す
+async fn insert_record_revision(
+    tx: &mut sqlx::Transaction<'_, Db>,
+    audit: &AuditSource,
+    user_id: i64,
+    record_id: i64,
+    previous_timestamp: DateTime<Utc>,
+    new_timestamp: DateTime<Utc>,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO record_revisions
+         (record_id, user_id, previous_timestamp, new_timestamp, editor_discord_id, source, reason)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)",
+    )
+    .bind(record_id)
+    .bind(user_id)
+    .bind(previous_timestamp)
+    .bind(new_timestamp)
+    .bind(&audit.actor_discord_id)
+    .bind(&audit.source)
+    .bind(&audit.reason)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// 記録の修正履歴を古い順に取得
+pub async fn get_record_revisions(pool: &DbPool, record_id: i64) -> Result<Vec<RecordRevision>> {
+    let rows = sqlx::query(
+        "SELECT id, record_id, user_id, previous_timestamp, new_timestamp, editor_discord_id,
+                source, reason, created_at
+         FROM record_revisions
+         WHERE record_id = $1
+         ORDER BY created_at ASC, id ASC",
+    )
+    .bind(record_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| RecordRevision {
+            id: row.get("id"),
+            record_id: row.get("record_id"),
+            user_id: row.get("user_id"),
+            previous_timestamp: row.get("previous_timestamp"),
+            new_timestamp: row.get("new_timestamp"),
+            editor_discord_id: row.get("editor_discord_id"),
+            source: row.get("source"),
+            reason: row.get("reason"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+const AUDIT_LOG_COLUMNS: &str =
+    "id, operation_id, user_id, actor_discord_id, action, record_id, record_type,
+     old_timestamp, new_timestamp, source, undone_at, guild_id, adjustment_minutes, created_at";
+
+fn audit_log_from_row(row: &DbRow) -> AuditLog {
+    AuditLog {
+        id: row.get("id"),
+        operation_id: row.get("operation_id"),
+        user_id: row.get("user_id"),
+        actor_discord_id: row.get("actor_discord_id"),
+        action: row.get("action"),
+        record_id: row.get("record_id"),
+        record_type: row.get("record_type"),
+        old_timestamp: row.get("old_timestamp"),
+        new_timestamp: row.get("new_timestamp"),
+        source: row.get("source"),
+        undone_at: row.get("undone_at"),
+        guild_id: row.get("guild_id"),
+        adjustment_minutes: row.get("adjustment_minutes"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// 指定日（JST）の記録に対する変更履歴を取得
+pub async fn get_audit_logs_for_date(
+    pool: &DbPool,
+    user_id: i64,
+    date: NaiveDate,
+) -> Result<Vec<AuditLog>> {
+    let (start_of_day, end_of_day) = jst_day_range_utc(date);
+
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM audit_log
+         WHERE user_id = $1
+           AND ((old_timestamp >= $2 AND old_timestamp < $3)
+             OR (new_timestamp >= $2 AND new_timestamp < $3))
+         ORDER BY created_at ASC, id ASC",
+        AUDIT_LOG_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(start_of_day)
+    .bind(end_of_day)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(audit_log_from_row).collect())
+}
+
+/// サーバー内で行われた `after_id` より後の変更履歴を、対象ユーザーのDiscord IDと合わせて古い順に取得
+pub async fn get_guild_audit_logs_after(
+    pool: &DbPool,
+    guild_id: &str,
+    after_id: i64,
+    limit: i64,
+) -> Result<Vec<(AuditLog, String)>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {}, (SELECT discord_id FROM users WHERE users.id = audit_log.user_id) AS discord_id
+         FROM audit_log
+         WHERE guild_id = $1 AND id > $2
+         ORDER BY id ASC
+         LIMIT $3",
+        AUDIT_LOG_COLUMNS
+    ))
+    .bind(guild_id)
+    .bind(after_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| (audit_log_from_row(row), row.get("discord_id")))
+        .collect())
+}
+
+/// ユーザー自身が最後に行った記録の変更操作を取得（取り消し済み・該当なしの場合は空）
+pub async fn get_last_undoable_operation(
+    pool: &DbPool,
+    user_id: i64,
+    actor_discord_id: &str,
+) -> Result<Vec<AuditLog>> {
+    let latest = sqlx::query(
+        "SELECT id, operation_id, undone_at FROM audit_log
+         WHERE user_id = $1 AND actor_discord_id = $2 AND source <> $3 AND record_type <> 'adjustment'
+         ORDER BY id DESC
+         LIMIT 1",
+    )
+    .bind(user_id)
+    .bind(actor_discord_id)
+    .bind(AuditSource::UNDO)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(latest) = latest else {
+        return Ok(Vec::new());
+    };
+    let undone_at: Option<DateTime<Utc>> = latest.get("undone_at");
+    if undone_at.is_some() {
+        return Ok(Vec::new());
+    }
+
+    let operation_id: Option<i64> = latest.get("operation_id");
+    let operation_id = operation_id.unwrap_or_else(|| latest.get("id"));
+
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM audit_log WHERE id = $1 OR operation_id = $1 ORDER BY id ASC",
+        AUDIT_LOG_COLUMNS
+    ))
+    .bind(operation_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(audit_log_from_row).collect())
+}
+
+/// 操作を逆順に打ち消し、元の監査ログを取り消し済みにする
+pub async fn undo_operation(pool: &DbPool, logs: &[AuditLog], audit: &AuditSource) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let mut operation_id = None;
+
+    let timestamps: Vec<(i64, DateTime<Utc>)> = logs
+        .iter()
+        .flat_map(|log| {
+            [log.old_timestamp, log.new_timestamp]
+                .into_iter()
+                .flatten()
+                .map(|timestamp| (log.user_id, timestamp))
+        })
+        .collect();
+    for (user_id, timestamp) in timestamps {
+        ensure_month_open(&mut tx, user_id, timestamp).await?;
+    }
+
+    for log in logs.iter().rev() {
+        let (action, old_timestamp, new_timestamp) = match log.action.as_str() {
+            // 追加 → 削除
+            "create" => {
+                let result =
+                    sqlx::query("DELETE FROM attendance_records WHERE id = $1 AND user_id = $2")
+                        .bind(log.record_id)
+                        .bind(log.user_id)
+                        .execute(&mut *tx)
+                        .await?;
+                if result.rows_affected() == 0 {
+                    continue;
+                }
+                ("delete", log.new_timestamp, None)
+            }
+            // 修正 → 元の時刻に戻す（最初の時刻に戻った場合は修正済みフラグも外す）
+            "update" => {
+                let result = sqlx::query(
+                    "UPDATE attendance_records
+                     SET timestamp = $1,
+                         is_modified = CASE WHEN original_timestamp = $1 THEN FALSE ELSE TRUE END,
+                         original_timestamp = CASE WHEN original_timestamp = $1 THEN NULL ELSE original_timestamp END,
+                         updated_at = CURRENT_TIMESTAMP
+                     WHERE id = $2 AND user_id = $3",
+                )
+                .bind(log.old_timestamp)
+                .bind(log.record_id)
+                .bind(log.user_id)
+                .execute(&mut *tx)
+                .await?;
+                if result.rows_affected() == 0 {
+                    continue;
+                }
+                if let (Some(previous), Some(restored)) = (log.new_timestamp, log.old_timestamp) {
+                    insert_record_revision(
+                        &mut tx,
+                        audit,
+                        log.user_id,
+                        log.record_id,
+                        previous,
+                        restored,
+                    )
+                    .await?;
+                }
+                ("update", log.new_timestamp, log.old_timestamp)
+            }
+            // 削除 → 同じIDで復元
+            _ => {
+                sqlx::query(
+                    "INSERT INTO attendance_records (id, user_id, record_type, timestamp, guild_id)
+                     VALUES ($1, $2, $3, $4, (SELECT guild_id FROM users WHERE id = $2))",
+                )
+                .bind(log.record_id)
+                .bind(log.user_id)
+                .bind(&log.record_type)
+                .bind(log.old_timestamp)
+                .execute(&mut *tx)
+                .await?;
+                ("create", None, log.old_timestamp)
+            }
+        };
+
+        let audit_id = insert_audit_log(
+            &mut tx,
+            audit,
+            AuditEntry {
+                operation_id,
+                action,
+                user_id: log.user_id,
+                record_id: log.record_id,
+                record_type: &log.record_type,
+                old_timestamp,
+                new_timestamp,
+                adjustment_minutes: None,
+            },
+        )
+        .await?;
+        operation_id.get_or_insert(audit_id);
+    }
+
+    let original_operation_id = logs
+        .first()
+        .map(|log| log.operation_id.unwrap_or(log.id))
+        .unwrap_or_default();
+    sqlx::query(
+        "UPDATE audit_log SET undone_at = CURRENT_TIMESTAMP WHERE id = $1 OR operation_id = $1",
+    )
+    .bind(original_operation_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+// Command audit log queries
+pub async fn create_command_audit_log(
+    pool: &DbPool,
+    guild_id: Option<&str>,
+    actor_discord_id: &str,
+    actor_name: &str,
+    command: &str,
+    arguments: &str,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO command_audit_log (guild_id, actor_discord_id, actor_name, command, arguments)
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(guild_id)
+    .bind(actor_discord_id)
+    .bind(actor_name)
+    .bind(command)
+    .bind(arguments)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// 管理者コマンドの実行履歴を新しい順に取得（実行者で絞り込み可能）
+pub async fn get_command_audit_logs(
+    pool: &DbPool,
+    actor_discord_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<CommandAuditLog>> {
+    let rows = sqlx::query(
+        "SELECT id, guild_id, actor_discord_id, actor_name, command, arguments, created_at
+         FROM command_audit_log
+         WHERE $1 IS NULL OR actor_discord_id = $1
+         ORDER BY id DESC
+         LIMIT $2",
+    )
+    .bind(actor_discord_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| CommandAuditLog {
+            id: row.get("id"),
+            guild_id: row.get("guild_id"),
+            actor_discord_id: row.get("actor_discord_id"),
+            actor_name: row.get("actor_name"),
+            command: row.get("command"),
+            arguments: row.get("arguments"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+/// データベースに書き込めるか確認する（変更はロールバックする）
+pub async fn check_database_writable(pool: &DbPool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("INSERT INTO guild_settings (guild_id) VALUES ($1)")
+        .bind("__diagnostics__")
+        .execute(&mut *tx)
+        .await?;
+    tx.rollback().await?;
+    Ok(())
+}
+
+/// データベースに接続できるか確認する（ヘルスチェック用の軽い問い合わせ）
+pub async fn ping_database(pool: &DbPool) -> Result<()> {
+    sqlx::query("SELECT 1").execute(pool).await?;
+    Ok(())
+}
+
+// Guild settings queries
+fn guild_settings_from_row(row: &DbRow) -> GuildSettings {
+    GuildSettings {
+        guild_id: row.get("guild_id"),
+        weekly_digest_channel_id: row.get("weekly_digest_channel_id"),
+        weekly_digest_last_sent: row.get("weekly_digest_last_sent"),
+        timesheet_confirmation_enabled: row.get("timesheet_confirmation_enabled"),
+        standard_daily_minutes: row.get("standard_daily_minutes"),
+        standard_weekly_minutes: row.get("standard_weekly_minutes"),
+        quality_digest_channel_id: row.get("quality_digest_channel_id"),
+        quality_digest_last_sent: row.get("quality_digest_last_sent"),
+        approval_channel_id: row.get("approval_channel_id"),
+        webhook_url: row.get("webhook_url"),
+        event_log_channel_id: row.get("event_log_channel_id"),
+        event_log_last_id: row.get("event_log_last_id"),
+        end_note_prompt_enabled: row.get("end_note_prompt_enabled"),
+        auto_break_after_minutes: row.get("auto_break_after_minutes"),
+        auto_break_minutes: row.get("auto_break_minutes"),
+        dashboard_channel_id: row.get("dashboard_channel_id"),
+        dashboard_message_id: row.get("dashboard_message_id"),
+        resume_window_minutes: row.get("resume_window_minutes"),
+        retention_months: row.get("retention_months"),
+        edit_max_age_days: row.get("edit_max_age_days"),
+        backdate_admin_only: row.get("backdate_admin_only"),
+        delete_requires_approval: row.get("delete_requires_approval"),
+        working_role_id: row.get("working_role_id"),
+        long_session_reminder_hours: row.get("long_session_reminder_hours"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+pub async fn get_guild_settings(pool: &DbPool, guild_id: &str) -> Result<Option<GuildSettings>> {
+    let row = sqlx::query("SELECT * FROM guild_settings WHERE guild_id = $1")
+        .bind(guild_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.as_ref().map(guild_settings_from_row))
+}
+
+pub async fn set_weekly_digest_channel(
+    pool: &DbPool,
+    guild_id: &str,
+    channel_id: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, weekly_digest_channel_id) VALUES ($1, $2)
+         ON CONFLICT(guild_id) DO UPDATE
+         SET weekly_digest_channel_id = excluded.weekly_digest_channel_id, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(channel_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_guilds_with_weekly_digest(pool: &DbPool) -> Result<Vec<GuildSettings>> {
+    let rows =
+        sqlx::query("SELECT * FROM guild_settings WHERE weekly_digest_channel_id IS NOT NULL")
+            .fetch_all(pool)
+            .await?;
+
+    Ok(rows.iter().map(guild_settings_from_row).collect())
+}
+
+pub async fn mark_weekly_digest_sent(
+    pool: &DbPool,
+    guild_id: &str,
+    sent_date: NaiveDate,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE guild_settings
+         SET weekly_digest_last_sent = $1, updated_at = CURRENT_TIMESTAMP
+         WHERE guild_id = $2",
+    )
+    .bind(sent_date)
+    .bind(guild_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn set_quality_digest_channel(
+    pool: &DbPool,
+    guild_id: &str,
+    channel_id: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, quality_digest_channel_id) VALUES ($1, $2)
+         ON CONFLICT(guild_id) DO UPDATE
+         SET quality_digest_channel_id = excluded.quality_digest_channel_id, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(channel_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_guilds_with_quality_digest(pool: &DbPool) -> Result<Vec<GuildSettings>> {
+    let rows =
+        sqlx::query("SELECT * FROM guild_settings WHERE quality_digest_channel_id IS NOT NULL")
+            .fetch_all(pool)
+            .await?;
+
+    Ok(rows.iter().map(guild_settings_from_row).collect())
+}
+
+pub async fn mark_quality_digest_sent(
+    pool: &DbPool,
+    guild_id: &str,
+    sent_date: NaiveDate,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE guild_settings
+         SET quality_digest_last_sent = $1, updated_at = CURRENT_TIMESTAMP
+         WHERE guild_id = $2",
+    )
+    .bind(sent_date)
+    .bind(guild_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn set_approval_channel(
+    pool: &DbPool,
+    guild_id: &str,
+    channel_id: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, approval_channel_id) VALUES ($1, $2)
+         ON CONFLICT(guild_id) DO UPDATE
+         SET approval_channel_id = excluded.approval_channel_id, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(channel_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// 勤怠ログチャンネルを設定（`None` で停止）。設定前の変更履歴は投稿しない
+pub async fn set_event_log_channel(
+    pool: &DbPool,
+    guild_id: &str,
+    channel_id: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, event_log_channel_id, event_log_last_id)
+         VALUES ($1, $2, (SELECT COALESCE(MAX(id), 0) FROM audit_log))
+         ON CONFLICT(guild_id) DO UPDATE
+         SET event_log_channel_id = excluded.event_log_channel_id,
+             event_log_last_id = excluded.event_log_last_id,
+             updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(channel_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_guilds_with_event_log(pool: &DbPool) -> Result<Vec<GuildSettings>> {
+    let rows = sqlx::query("SELECT * FROM guild_settings WHERE event_log_channel_id IS NOT NULL")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.iter().map(guild_settings_from_row).collect())
+}
+
+pub async fn set_event_log_last_id(pool: &DbPool, guild_id: &str, last_id: i64) -> Result<()> {
+    sqlx::query(
+        "UPDATE guild_settings SET event_log_last_id = $1, updated_at = CURRENT_TIMESTAMP
+         WHERE guild_id = $2",
+    )
+    .bind(last_id)
+    .bind(guild_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// ダッシュボードのメッセージを設定（`None` で停止）
+pub async fn set_dashboard_message(
+    pool: &DbPool,
+    guild_id: &str,
+    message: Option<(&str, &str)>,
+) -> Result<()> {
+    let (channel_id, message_id) = message.unzip();
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, dashboard_channel_id, dashboard_message_id)
+         VALUES ($1, $2, $3)
+         ON CONFLICT(guild_id) DO UPDATE
+         SET dashboard_channel_id = excluded.dashboard_channel_id,
+             dashboard_message_id = excluded.dashboard_message_id,
+             updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(channel_id)
+    .bind(message_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_guilds_with_dashboard(pool: &DbPool) -> Result<Vec<GuildSettings>> {
+    let rows = sqlx::query(
+        "SELECT * FROM guild_settings
+         WHERE dashboard_channel_id IS NOT NULL AND dashboard_message_id IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(guild_settings_from_row).collect())
+}
+
+pub async fn set_webhook_url(pool: &DbPool, guild_id: &str, url: Option<&str>) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, webhook_url) VALUES ($1, $2)
+         ON CONFLICT(guild_id) DO UPDATE
+         SET webhook_url = excluded.webhook_url, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(url)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn set_working_role(pool: &DbPool, guild_id: &str, role_id: Option<&str>) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, working_role_id) VALUES ($1, $2)
+         ON CONFLICT(guild_id) DO UPDATE
+         SET working_role_id = excluded.working_role_id, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(role_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn set_long_session_reminder_hours(
+    pool: &DbPool,
+    guild_id: &str,
+    hours: i32,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, long_session_reminder_hours) VALUES ($1, $2)
+         ON CONFLICT(guild_id) DO UPDATE
+         SET long_session_reminder_hours = excluded.long_session_reminder_hours,
+             updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(hours)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn set_timesheet_confirmation_enabled(
+    pool: &DbPool,
+    guild_id: &str,
+    enabled: bool,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, timesheet_confirmation_enabled) VALUES ($1, $2)
+         ON CONFLICT(guild_id) DO UPDATE
+         SET timesheet_confirmation_enabled = excluded.timesheet_confirmation_enabled, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(enabled)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn set_end_note_prompt_enabled(
+    pool: &DbPool,
+    guild_id: &str,
+    enabled: bool,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, end_note_prompt_enabled) VALUES ($1, $2)
+         ON CONFLICT(guild_id) DO UPDATE
+         SET end_note_prompt_enabled = excluded.end_note_prompt_enabled, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(enabled)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn set_resume_window(pool: &DbPool, guild_id: &str, minutes: i32) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, resume_window_minutes) VALUES ($1, $2)
+         ON CONFLICT(guild_id) DO UPDATE
+         SET resume_window_minutes = excluded.resume_window_minutes, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(minutes)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// 記録の編集ルールを保存する
+pub async fn set_edit_policy(
+    pool: &DbPool,
+    guild_id: &str,
+    max_age_days: i32,
+    backdate_admin_only: bool,
+    delete_requires_approval: bool,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, edit_max_age_days, backdate_admin_only, delete_requires_approval)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT(guild_id) DO UPDATE
+         SET edit_max_age_days = excluded.edit_max_age_days,
+             backdate_admin_only = excluded.backdate_admin_only,
+             delete_requires_approval = excluded.delete_requires_approval,
+             updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(max_age_days)
+    .bind(backdate_admin_only)
+    .bind(delete_requires_approval)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// 記録の保持期間を設定（`None` で無期限）
+pub async fn set_retention_months(
+    pool: &DbPool,
+    guild_id: &str,
+    months: Option<i32>,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, retention_months) VALUES ($1, $2)
+         ON CONFLICT(guild_id) DO UPDATE
+         SET retention_months = excluded.retention_months, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(months)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_guilds_with_retention(pool: &DbPool) -> Result<Vec<GuildSettings>> {
+    let rows = sqlx::query("SELECT * FROM guild_settings WHERE retention_months IS NOT NULL")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.iter().map(guild_settings_from_row).collect())
+}
+
+/// サーバーの `cutoff`（JST）より前の勤怠記録と勤務セッション（と集中時間）を削除し、削除した件数を返す
+///
+/// `dry_run` のときは件数だけ数えて変更をロールバックする
+pub async fn purge_records_before(
+    pool: &DbPool,
+    guild_id: &str,
+    cutoff: NaiveDate,
+    dry_run: bool,
+) -> Result<(u64, u64)> {
+    let jst_offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+    let cutoff_time = jst_offset
+        .from_local_datetime(&cutoff.and_hms_opt(0, 0, 0).unwrap())
+        .unwrap()
+        .to_utc();
+
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "DELETE FROM record_revisions WHERE record_id IN (
+             SELECT id FROM attendance_records
+             WHERE user_id IN (SELECT id FROM users WHERE guild_id = $1) AND timestamp < $2
+         )",
+    )
+    .bind(guild_id)
+    .bind(cutoff_time)
+    .execute(&mut *tx)
+    .await?;
+    let records = sqlx::query(
+        "DELETE FROM attendance_records
+         WHERE user_id IN (SELECT id FROM users WHERE guild_id = $1) AND timestamp < $2",
+    )
+    .bind(guild_id)
+    .bind(cutoff_time)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    sqlx::query(
+        "DELETE FROM focus_sessions
+         WHERE user_id IN (SELECT id FROM users WHERE guild_id = $1) AND started_at < $2",
+    )
+    .bind(guild_id)
+    .bind(cutoff_time)
+    .execute(&mut *tx)
+    .await?;
+    let sessions = sqlx::query(
+        "DELETE FROM work_sessions
+         WHERE user_id IN (SELECT id FROM users WHERE guild_id = $1) AND date < $2",
+    )
+    .bind(guild_id)
+    .bind(cutoff)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    if dry_run {
+        tx.rollback().await?;
+    } else {
+        tx.commit().await?;
+    }
+    Ok((records, sessions))
+}
+
+pub async fn set_overtime_thresholds(
+    pool: &DbPool,
+    guild_id: &str,
+    daily_minutes: i32,
+    weekly_minutes: i32,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, standard_daily_minutes, standard_weekly_minutes) VALUES ($1, $2, $3)
+         ON CONFLICT(guild_id) DO UPDATE
+         SET standard_daily_minutes = excluded.standard_daily_minutes,
+             standard_weekly_minutes = excluded.standard_weekly_minutes,
+             updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(daily_minutes)
+    .bind(weekly_minutes)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// 休憩の自動控除ルールを設定（`after_minutes` が `None` で無効）
+pub async fn set_break_rule(
+    pool: &DbPool,
+    guild_id: &str,
+    after_minutes: Option<i32>,
+    break_minutes: i32,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, auto_break_after_minutes, auto_break_minutes) VALUES ($1, $2, $3)
+         ON CONFLICT(guild_id) DO UPDATE
+         SET auto_break_after_minutes = excluded.auto_break_after_minutes,
+             auto_break_minutes = excluded.auto_break_minutes,
+             updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(after_minutes)
+    .bind(break_minutes)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_guilds_with_timesheet_confirmation(pool: &DbPool) -> Result<Vec<GuildSettings>> {
+    let rows =
+        sqlx::query("SELECT * FROM guild_settings WHERE timesheet_confirmation_enabled = TRUE")
+            .fetch_all(pool)
+            .await?;
+
+    Ok(rows.iter().map(guild_settings_from_row).collect())
+}
+
+// Rate queries
+fn rate_from_row(row: &DbRow) -> Rate {
+    Rate {
+        id: row.get("id"),
+        guild_id: row.get("guild_id"),
+        user_id: row.get("user_id"),
+        hourly_rate: row.get("hourly_rate"),
+        overtime_rate: row.get("overtime_rate"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+/// 時給を設定（`user_id` が `None` の場合はギルドの既定値）
+pub async fn set_rate(
+    pool: &DbPool,
+    guild_id: &str,
+    user_id: Option<i64>,
+    hourly_rate: i64,
+    overtime_rate: i64,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    // NULL を含む一意制約は ON CONFLICT で扱えないため、更新できなければ追加する
+    let result = sqlx::query(
+        "UPDATE rates SET hourly_rate = $1, overtime_rate = $2, updated_at = CURRENT_TIMESTAMP
+         WHERE guild_id = $3 AND user_id IS NOT DISTINCT FROM $4",
+    )
+    .bind(hourly_rate)
+    .bind(overtime_rate)
+    .bind(guild_id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        sqlx::query(
+            "INSERT INTO rates (guild_id, user_id, hourly_rate, overtime_rate) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(guild_id)
+        .bind(user_id)
+        .bind(hourly_rate)
+        .bind(overtime_rate)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// ユーザーに適用される時給（個別設定を優先し、無ければギルドの既定値）
+pub async fn get_effective_rate(
+    pool: &DbPool,
+    guild_id: &str,
+    user_id: i64,
+) -> Result<Option<Rate>> {
+    let row = sqlx::query(
+        "SELECT * FROM rates
+         WHERE guild_id = $1 AND (user_id = $2 OR user_id IS NULL)
+         ORDER BY user_id IS NULL
+         LIMIT 1",
+    )
+    .bind(guild_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(rate_from_row))
+}
+
+// Hour cap queries
+fn hour_cap_from_row(row: &DbRow) -> HourCap {
+    HourCap {
+        user_id: row.get("user_id"),
+        guild_id: row.get("guild_id"),
+        monthly_minutes: row.get("monthly_minutes"),
+        notify_channel_id: row.get("notify_channel_id"),
+        warned_month: row.get("warned_month"),
+        warned_level: row.get("warned_level"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+/// 月間の上限を設定（変更時はその月の通知状況もリセットする）
+pub async fn set_hour_cap(
+    pool: &DbPool,
+    user_id: i64,
+    guild_id: &str,
+    monthly_minutes: i32,
+    notify_channel_id: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO hour_caps (user_id, guild_id, monthly_minutes, notify_channel_id) VALUES ($1, $2, $3, $4)
+         ON CONFLICT(user_id, guild_id) DO UPDATE
+         SET monthly_minutes = excluded.monthly_minutes, notify_channel_id = excluded.notify_channel_id,
+             warned_month = NULL, warned_level = 0, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(user_id)
+    .bind(guild_id)
+    .bind(monthly_minutes)
+    .bind(notify_channel_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// 月間の上限を解除（設定が無かった場合は false）
+pub async fn delete_hour_cap(pool: &DbPool, user_id: i64, guild_id: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM hour_caps WHERE user_id = $1 AND guild_id = $2")
+        .bind(user_id)
+        .bind(guild_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get_hour_cap(pool: &DbPool, user_id: i64, guild_id: &str) -> Result<Option<HourCap>> {
+    let row = sqlx::query("SELECT * FROM hour_caps WHERE user_id = $1 AND guild_id = $2")
+        .bind(user_id)
+        .bind(guild_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.as_ref().map(hour_cap_from_row))
+}
+
+/// 管理者へ知らせた月と段階を記録
+pub async fn set_hour_cap_warning(
+    pool: &DbPool,
+    user_id: i64,
+    guild_id: &str,
+    month: &str,
+    level: i32,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE hour_caps SET warned_month = $1, warned_level = $2, updated_at = CURRENT_TIMESTAMP
+         WHERE user_id = $3 AND guild_id = $4",
+    )
+    .bind(month)
+    .bind(level)
+    .bind(user_id)
+    .bind(guild_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+// Project queries
+pub async fn get_projects(pool: &DbPool, guild_id: &str) -> Result<Vec<Project>> {
+    let rows = sqlx::query("SELECT * FROM projects WHERE guild_id = $1 ORDER BY name")
+        .bind(guild_id)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.iter().map(project_from_row).collect())
+}
+
+pub async fn get_project(pool: &DbPool, project_id: i64) -> Result<Option<Project>> {
+    let row = sqlx::query("SELECT * FROM projects WHERE id = $1")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.as_ref().map(project_from_row))
+}
+
+fn project_from_row(row: &DbRow) -> Project {
+    Project {
+        id: row.get("id"),
+        guild_id: row.get("guild_id"),
+        name: row.get("name"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// プロジェクトのIDを取得（未登録なら作成する）
+pub async fn get_or_create_project(pool: &DbPool, guild_id: &str, name: &str) -> Result<i64> {
+    sqlx::query(
+        "INSERT INTO projects (guild_id, name) VALUES ($1, $2)
+         ON CONFLICT(guild_id, name) DO NOTHING",
+    )
+    .bind(guild_id)
+    .bind(name)
+    .execute(pool)
+    .await?;
+
+    let row = sqlx::query("SELECT id FROM projects WHERE guild_id = $1 AND name = $2")
+        .bind(guild_id)
+        .bind(name)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(row.get("id"))
+}
+
+/// 開始記録から作られたセッションにプロジェクトを設定
+pub async fn set_session_project(
+    pool: &DbPool,
+    start_record_id: i64,
+    project_id: Option<i64>,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE work_sessions SET project_id = $1, updated_at = CURRENT_TIMESTAMP
+         WHERE start_record_id = $2",
+    )
+    .bind(project_id)
+    .bind(start_record_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+// Project preference queries
+pub async fn get_project_preference(
+    pool: &DbPool,
+    user_id: i64,
+    guild_id: &str,
+) -> Result<ProjectPreference> {
+    let row = sqlx::query(
+        "SELECT pp.default_project_id, dp.name AS default_project_name,
+                pp.last_project_id, lp.name AS last_project_name
+         FROM project_preferences pp
+         LEFT JOIN projects dp ON dp.id = pp.default_project_id
+         LEFT JOIN projects lp ON lp.id = pp.last_project_id
+         WHERE pp.user_id = $1 AND pp.guild_id = $2",
+    )
+    .bind(user_id)
+    .bind(guild_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row
+        .map(|row| ProjectPreference {
+            default_project: row
+                .get::<Option<i64>, _>("default_project_id")
+                .zip(row.get::<Option<String>, _>("default_project_name")),
+            last_project: row
+                .get::<Option<i64>, _>("last_project_id")
+                .zip(row.get::<Option<String>, _>("last_project_name")),
+        })
+        .unwrap_or_default())
+}
+
+/// 既定のプロジェクトを設定（`None` で解除）
+pub async fn set_default_project(
+    pool: &DbPool,
+    user_id: i64,
+    guild_id: &str,
+    project_id: Option<i64>,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO project_preferences (user_id, guild_id, default_project_id) VALUES ($1, $2, $3)
+         ON CONFLICT(user_id, guild_id) DO UPDATE
+         SET default_project_id = excluded.default_project_id, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(user_id)
+    .bind(guild_id)
+    .bind(project_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn set_last_project(
+    pool: &DbPool,
+    user_id: i64,
+    guild_id: &str,
+    project_id: i64,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO project_preferences (user_id, guild_id, last_project_id) VALUES ($1, $2, $3)
+         ON CONFLICT(user_id, guild_id) DO UPDATE
+         SET last_project_id = excluded.last_project_id, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(user_id)
+    .bind(guild_id)
+    .bind(project_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// セッションにメモを設定（`None` で削除）。対象のセッションが無い場合は false を返す
+pub async fn set_session_note(pool: &DbPool, session_id: i64, note: Option<&str>) -> Result<bool> {
+    let result = sqlx::query(
+        "UPDATE work_sessions SET note = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
+    )
+    .bind(note)
+    .bind(session_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// Shortcut queries
+fn shortcut_from_row(row: &DbRow) -> Shortcut {
+    Shortcut {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        name: row.get("name"),
+        action: row.get("action"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// ショートカットを登録（同名のものがあれば上書き）
+pub async fn upsert_shortcut(pool: &DbPool, user_id: i64, name: &str, action: &str) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO shortcuts (user_id, name, action) VALUES ($1, $2, $3)
+         ON CONFLICT(user_id, name) DO UPDATE SET action = excluded.action",
+    )
+    .bind(user_id)
+    .bind(name)
+    .bind(action)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_shortcuts(pool: &DbPool, user_id: i64) -> Result<Vec<Shortcut>> {
+    let rows = sqlx::query("SELECT * FROM shortcuts WHERE user_id = $1 ORDER BY name")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.iter().map(shortcut_from_row).collect())
+}
+
+pub async fn get_shortcut(pool: &DbPool, user_id: i64, name: &str) -> Result<Option<Shortcut>> {
+    let row = sqlx::query("SELECT * FROM shortcuts WHERE user_id = $1 AND name = $2")
+        .bind(user_id)
+        .bind(name)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.as_ref().map(shortcut_from_row))
+}
+
+/// ショートカットを削除。削除した場合は true を返す
+pub async fn delete_shortcut(pool: &DbPool, user_id: i64, name: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM shortcuts WHERE user_id = $1 AND name = $2")
+        .bind(user_id)
+        .bind(name)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// User settings queries
+
+/// チュートリアルを表示済みにする。今回初めて表示済みになった場合は true を返す
+pub async fn mark_tutorial_completed(pool: &DbPool, user_id: i64) -> Result<bool> {
+    let result = sqlx::query(
+        "INSERT INTO user_settings (user_id, tutorial_completed_at) VALUES ($1, CURRENT_TIMESTAMP)
+         ON CONFLICT(user_id) DO UPDATE
+         SET tutorial_completed_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+         WHERE user_settings.tutorial_completed_at IS NULL",
+    )
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 週の目標勤務時間（分）を設定（`None` で解除）
+pub async fn set_weekly_goal(pool: &DbPool, user_id: i64, minutes: Option<i32>) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO user_settings (user_id, weekly_goal_minutes) VALUES ($1, $2)
+         ON CONFLICT(user_id) DO UPDATE
+         SET weekly_goal_minutes = excluded.weekly_goal_minutes, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(user_id)
+    .bind(minutes)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// 週の目標勤務時間（分）。未設定なら `None`
+pub async fn get_weekly_goal(pool: &DbPool, user_id: i64) -> Result<Option<i32>> {
+    let minutes: Option<Option<i32>> =
+        sqlx::query_scalar("SELECT weekly_goal_minutes FROM user_settings WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+
+    Ok(minutes.flatten())
+}
+
+/// 打刻の丸め単位（分）を設定（`None` で丸めない）
+pub async fn set_rounding_minutes(pool: &DbPool, user_id: i64, minutes: Option<i32>) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO user_settings (user_id, rounding_minutes) VALUES ($1, $2)
+         ON CONFLICT(user_id) DO UPDATE
+         SET rounding_minutes = excluded.rounding_minutes, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(user_id)
+    .bind(minutes)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// 打刻の丸め単位（分）。未設定なら `None`
+pub async fn get_rounding_minutes(pool: &DbPool, user_id: i64) -> Result<Option<i32>> {
+    let minutes: Option<Option<i32>> =
+        sqlx::query_scalar("SELECT rounding_minutes FROM user_settings WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+
+    Ok(minutes.flatten())
+}
+
+/// 表示言語を設定（`None` でサーバーの設定・Discordのロケールに従う）
+pub async fn set_user_language(pool: &DbPool, user_id: i64, language: Option<&str>) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO user_settings (user_id, language) VALUES ($1, $2)
+         ON CONFLICT(user_id) DO UPDATE
+         SET language = excluded.language, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(user_id)
+    .bind(language)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// サーバーの既定の表示言語を設定（`None` でDiscordのロケールに従う）
+pub async fn set_guild_language(
+    pool: &DbPool,
+    guild_id: &str,
+    language: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, language) VALUES ($1, $2)
+         ON CONFLICT(guild_id) DO UPDATE
+         SET language = excluded.language, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(language)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// ユーザーの個人設定とサーバーの既定の表示言語（DMではサーバーの設定は無い）
+pub async fn get_user_preferences(
+    pool: &DbPool,
+    discord_id: &str,
+    guild_id: Option<impl ToString>,
+) -> Result<UserPreferences> {
+    let row = sqlx::query(
+        "SELECT user_settings.language, user_settings.ephemeral_responses,
+                user_settings.utc_offset_minutes, user_settings.clock_12h,
+                user_settings.private_reports,
+                (SELECT language FROM guild_settings WHERE guild_id = $2) AS guild_language
+         FROM (SELECT 1 AS one) AS base
+         LEFT JOIN users ON users.discord_id = $1 AND users.guild_id = $2
+         LEFT JOIN user_settings ON user_settings.user_id = users.id",
+    )
+    .bind(discord_id)
+    .bind(guild_key(guild_id))
+    .fetch_one(pool)
+    .await?;
+
+    Ok(UserPreferences {
+        language: row.get("language"),
+        guild_language: row.get("guild_language"),
+        ephemeral_responses: row
+            .get::<Option<bool>, _>("ephemeral_responses")
+            .unwrap_or_default(),
+        utc_offset_minutes: row.get("utc_offset_minutes"),
+        clock_12h: row.get::<Option<bool>, _>("clock_12h").unwrap_or_default(),
+        private_reports: row
+            .get::<Option<bool>, _>("private_reports")
+            .unwrap_or_default(),
+    })
+}
+
+/// 個人設定を保存（`guild_language` は保存しない）
+pub async fn set_user_preferences(
+    pool: &DbPool,
+    user_id: i64,
+    preferences: &UserPreferences,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO user_settings
+             (user_id, language, ephemeral_responses, utc_offset_minutes, clock_12h, private_reports)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         ON CONFLICT(user_id) DO UPDATE
+         SET language = excluded.language,
+             ephemeral_responses = excluded.ephemeral_responses,
+             utc_offset_minutes = excluded.utc_offset_minutes,
+             clock_12h = excluded.clock_12h,
+             private_reports = excluded.private_reports,
+             updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(user_id)
+    .bind(&preferences.language)
+    .bind(preferences.ephemeral_responses)
+    .bind(preferences.utc_offset_minutes)
+    .bind(preferences.clock_12h)
+    .bind(preferences.private_reports)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+// Work schedule queries
+
+/// 指定した曜日の開始予定時刻を登録（登録済みの曜日は上書き）
+pub async fn set_work_schedule(
+    pool: &DbPool,
+    user_id: i64,
+    weekdays: &[i32],
+    start_minutes: i32,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    for weekday in weekdays {
+        sqlx::query(
+            "INSERT INTO work_schedules (user_id, weekday, start_minutes) VALUES ($1, $2, $3)
+             ON CONFLICT(user_id, weekday) DO UPDATE
+             SET start_minutes = excluded.start_minutes, updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(user_id)
+        .bind(weekday)
+        .bind(start_minutes)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// 指定した曜日の開始予定時刻を削除し、削除した件数を返す
+pub async fn delete_work_schedule(pool: &DbPool, user_id: i64, weekdays: &[i32]) -> Result<u64> {
+    let mut deleted = 0;
+    for weekday in weekdays {
+        deleted += sqlx::query("DELETE FROM work_schedules WHERE user_id = $1 AND weekday = $2")
+            .bind(user_id)
+            .bind(weekday)
+            .execute(pool)
+            .await?
+            .rows_affected();
+    }
+
+    Ok(deleted)
+}
+
+/// ユーザーの開始予定時刻（曜日順）
+pub async fn get_work_schedules(pool: &DbPool, user_id: i64) -> Result<Vec<WorkSchedule>> {
+    let rows = sqlx::query(
+        "SELECT user_id, weekday, start_minutes FROM work_schedules
+         WHERE user_id = $1 ORDER BY weekday ASC",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| WorkSchedule {
+            user_id: row.get("user_id"),
+            weekday: row.get("weekday"),
+            start_minutes: row.get("start_minutes"),
+        })
+        .collect())
+}
+
+pub async fn set_schedule_nudge_enabled(pool: &DbPool, user_id: i64, enabled: bool) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO user_settings (user_id, schedule_nudge_enabled) VALUES ($1, $2)
+         ON CONFLICT(user_id) DO UPDATE
+         SET schedule_nudge_enabled = excluded.schedule_nudge_enabled, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(user_id)
+    .bind(enabled)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// 通知を有効にしていて、`weekday` の開始予定時刻が `from_minutes`〜`to_minutes` にあり、
+/// `today` にまだ通知していないユーザーと開始予定時刻
+pub async fn get_due_schedule_nudges(
+    pool: &DbPool,
+    weekday: i32,
+    from_minutes: i32,
+    to_minutes: i32,
+    today: NaiveDate,
+) -> Result<Vec<(User, i32)>> {
+    let rows = sqlx::query(
+        "SELECT users.id, users.discord_id, users.guild_id, users.username, users.created_at,
+                work_schedules.start_minutes
+         FROM work_schedules
+         JOIN users ON users.id = work_schedules.user_id
+         JOIN user_settings ON user_settings.user_id = work_schedules.user_id
+         WHERE user_settings.schedule_nudge_enabled = TRUE
+           AND work_schedules.weekday = $1
+           AND work_schedules.start_minutes >= $2
+           AND work_schedules.start_minutes <= $3
+           AND (user_settings.schedule_nudge_last_sent IS NULL
+             OR user_settings.schedule_nudge_last_sent < $4)",
+    )
+    .bind(weekday)
+    .bind(from_minutes)
+    .bind(to_minutes)
+    .bind(today)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| (user_from_row(row), row.get("start_minutes")))
+        .collect())
+}
+
+/// 出勤の通知を `date` に送った（または不要だった）ことを記録する
+pub async fn mark_schedule_nudge_sent(pool: &DbPool, user_id: i64, date: NaiveDate) -> Result<()> {
+    sqlx::query(
+        "UPDATE user_settings SET schedule_nudge_last_sent = $1, updated_at = CURRENT_TIMESTAMP
+         WHERE user_id = $2",
+    )
+    .bind(date)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// 出勤リマインダーの時刻を設定する（`None` で停止）
+pub async fn set_checkin_reminder(pool: &DbPool, user_id: i64, minutes: Option<i32>) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO user_settings (user_id, checkin_reminder_minutes) VALUES ($1, $2)
+         ON CONFLICT(user_id) DO UPDATE
+         SET checkin_reminder_minutes = excluded.checkin_reminder_minutes, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(user_id)
+    .bind(minutes)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// 出勤リマインダーの時刻が `from_minutes`〜`to_minutes` にあり、`weekday` が勤務日で、
+/// `today` にまだ送っていないユーザーとリマインダーの時刻。
+/// 勤務日は開始予定時刻を登録した曜日（登録がなければ月〜金）
+pub async fn get_due_checkin_reminders(
+    pool: &DbPool,
+    weekday: i32,
+    from_minutes: i32,
+    to_minutes: i32,
+    today: NaiveDate,
+) -> Result<Vec<(User, i32)>> {
+    let rows = sqlx::query(
+        "SELECT users.id, users.discord_id, users.guild_id, users.username, users.created_at,
+                user_settings.checkin_reminder_minutes
+         FROM user_settings
+         JOIN users ON users.id = user_settings.user_id
+         WHERE user_settings.checkin_reminder_minutes >= $2
+           AND user_settings.checkin_reminder_minutes <= $3
+           AND (user_settings.checkin_reminder_last_sent IS NULL
+             OR user_settings.checkin_reminder_last_sent < $4)
+           AND (EXISTS (SELECT 1 FROM work_schedules
+                        WHERE work_schedules.user_id = users.id AND work_schedules.weekday = $1)
+             OR (NOT EXISTS (SELECT 1 FROM work_schedules WHERE work_schedules.user_id = users.id)
+                 AND $1 < 5))",
+    )
+    .bind(weekday)
+    .bind(from_minutes)
+    .bind(to_minutes)
+    .bind(today)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| (user_from_row(row), row.get("checkin_reminder_minutes")))
+        .collect())
+}
+
+/// 出勤リマインダーを `date` に送った（または不要だった）ことを記録する
+pub async fn mark_checkin_reminder_sent(
+    pool: &DbPool,
+    user_id: i64,
+    date: NaiveDate,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE user_settings SET checkin_reminder_last_sent = $1, updated_at = CURRENT_TIMESTAMP
+         WHERE user_id = $2",
+    )
+    .bind(date)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+// Shift queries
+const SHIFT_COLUMNS: &str =
+    "id, user_id, guild_id, start_time, end_time, created_by, reminded, created_at";
+
+fn shift_from_row(row: &DbRow) -> Shift {
+    Shift {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        guild_id: row.get("guild_id"),
+        start_time: row.get("start_time"),
+        end_time: row.get("end_time"),
+        created_by: row.get("created_by"),
+        reminded: row.get("reminded"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// シフトを追加
+pub async fn create_shift(
+    pool: &DbPool,
+    user_id: i64,
+    guild_id: Option<&str>,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    created_by: &str,
+) -> Result<Shift> {
+    let row = sqlx::query(&format!(
+        "INSERT INTO shifts (user_id, guild_id, start_time, end_time, created_by)
+         VALUES ($1, $2, $3, $4, $5)
+         RETURNING {}",
+        SHIFT_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(guild_id)
+    .bind(start_time)
+    .bind(end_time)
+    .bind(created_by)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(shift_from_row(&row))
+}
+
+/// `from` から `to` までの間に一部でも重なるシフトを開始順に取得
+pub async fn get_shifts_in_range(
+    pool: &DbPool,
+    user_id: i64,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<Shift>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM shifts
+         WHERE user_id = $1 AND start_time < $2 AND end_time > $3
+         ORDER BY start_time ASC",
+        SHIFT_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(to)
+    .bind(from)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(shift_from_row).collect())
+}
+
+pub async fn get_shift(pool: &DbPool, id: i64) -> Result<Option<Shift>> {
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM shifts WHERE id = $1",
+        SHIFT_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(shift_from_row))
+}
+
+pub async fn delete_shift(pool: &DbPool, id: i64) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM shifts WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 開始時刻が `from` から `to` の間で、まだ出勤の通知を確認していないシフト
+/// （`/schedule nudge` を有効にしているユーザーのみ）
+pub async fn get_due_shift_reminders(
+    pool: &DbPool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<(User, Shift)>> {
+    let rows = sqlx::query(
+        "SELECT users.discord_id, users.guild_id AS user_guild_id, users.username,
+                users.created_at AS user_created_at,
+                shifts.id, shifts.user_id, shifts.guild_id, shifts.start_time, shifts.end_time,
+                shifts.created_by, shifts.reminded, shifts.created_at
+         FROM shifts
+         JOIN users ON users.id = shifts.user_id
+         JOIN user_settings ON user_settings.user_id = shifts.user_id
+         WHERE user_settings.schedule_nudge_enabled = TRUE
+           AND shifts.reminded = FALSE
+           AND shifts.start_time >= $1
+           AND shifts.start_time <= $2",
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let shift = shift_from_row(row);
+            (
+                User {
+                    id: shift.user_id,
+                    discord_id: row.get("discord_id"),
+                    guild_id: row.get("user_guild_id"),
+                    username: row.get("username"),
+                    created_at: row.get("user_created_at"),
+                },
+                shift,
+            )
+        })
+        .collect())
+}
+
+/// シフトの出勤の通知を送った（または不要だった）ことを記録する
+pub async fn mark_shift_reminded(pool: &DbPool, id: i64) -> Result<()> {
+    sqlx::query("UPDATE shifts SET reminded = TRUE WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+// Shift template queries
+const SHIFT_TEMPLATE_COLUMNS: &str = "id, guild_id, name, start_minutes, end_minutes";
+
+fn shift_template_from_row(row: &DbRow) -> ShiftTemplate {
+    ShiftTemplate {
+        id: row.get("id"),
+        guild_id: row.get("guild_id"),
+        name: row.get("name"),
+        start_minutes: row.get("start_minutes"),
+        end_minutes: row.get("end_minutes"),
+    }
+}
+
+/// シフトのひな形を登録（同じ名前があれば時間を上書き）
+pub async fn upsert_shift_template(
+    pool: &DbPool,
+    guild_id: &str,
+    name: &str,
+    start_minutes: i32,
+    end_minutes: i32,
+    created_by: &str,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO shift_templates (guild_id, name, start_minutes, end_minutes, created_by)
+         VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT(guild_id, name) DO UPDATE
+         SET start_minutes = excluded.start_minutes, end_minutes = excluded.end_minutes,
+             updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(guild_id)
+    .bind(name)
+    .bind(start_minutes)
+    .bind(end_minutes)
+    .bind(created_by)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_shift_templates(pool: &DbPool, guild_id: &str) -> Result<Vec<ShiftTemplate>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM shift_templates WHERE guild_id = $1 ORDER BY start_minutes ASC, name ASC",
+        SHIFT_TEMPLATE_COLUMNS
+    ))
+    .bind(guild_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(shift_template_from_row).collect())
+}
+
+pub async fn get_shift_template(
+    pool: &DbPool,
+    guild_id: &str,
+    name: &str,
+) -> Result<Option<ShiftTemplate>> {
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM shift_templates WHERE guild_id = $1 AND name = $2",
+        SHIFT_TEMPLATE_COLUMNS
+    ))
+    .bind(guild_id)
+    .bind(name)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(shift_template_from_row))
+}
+
+pub async fn delete_shift_template(pool: &DbPool, guild_id: &str, name: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM shift_templates WHERE guild_id = $1 AND name = $2")
+        .bind(guild_id)
+        .bind(name)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// Focus session queries
+const FOCUS_SESSION_COLUMNS: &str =
+    "id, user_id, channel_id, started_at, ends_at, ended_at, completed, created_at";
+
+fn focus_session_from_row(row: &DbRow) -> FocusSession {
+    FocusSession {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        channel_id: row.get("channel_id"),
+        started_at: row.get("started_at"),
+        ends_at: row.get("ends_at"),
+        ended_at: row.get("ended_at"),
+        completed: row.get("completed"),
+        created_at: row.get("created_at"),
+    }
+}
+
+pub async fn create_focus_session(
+    pool: &DbPool,
+    user_id: i64,
+    channel_id: &str,
+    started_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+) -> Result<FocusSession> {
+    let row = sqlx::query(&format!(
+        "INSERT INTO focus_sessions (user_id, channel_id, started_at, ends_at)
+         VALUES ($1, $2, $3, $4)
+         RETURNING {}",
+        FOCUS_SESSION_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(channel_id)
+    .bind(started_at)
+    .bind(ends_at)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(focus_session_from_row(&row))
+}
+
+/// 終了していない集中時間（あれば1件）
+pub async fn get_running_focus_session(
+    pool: &DbPool,
+    user_id: i64,
+) -> Result<Option<FocusSession>> {
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM focus_sessions
+         WHERE user_id = $1 AND ended_at IS NULL
+         ORDER BY started_at DESC
+         LIMIT 1",
+        FOCUS_SESSION_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(focus_session_from_row))
+}
+
+/// 集中時間を終了する（既に終了していれば `false`。終了の通知と中断が重なっても一度だけ終わる）
+pub async fn finish_focus_session(
+    pool: &DbPool,
+    id: i64,
+    ended_at: DateTime<Utc>,
+    completed: bool,
+) -> Result<bool> {
+    let result = sqlx::query(
+        "UPDATE focus_sessions SET ended_at = $1, completed = $2
+         WHERE id = $3 AND ended_at IS NULL",
+    )
+    .bind(ended_at)
+    .bind(completed)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 予定の終了時刻が `now` までに来た、終了していない集中時間とユーザーのDiscord ID
+pub async fn get_due_focus_sessions(
+    pool: &DbPool,
+    now: DateTime<Utc>,
+) -> Result<Vec<(String, FocusSession)>> {
+    let rows = sqlx::query(
+        "SELECT users.discord_id, focus_sessions.id, focus_sessions.user_id,
+                focus_sessions.channel_id, focus_sessions.started_at, focus_sessions.ends_at,
+                focus_sessions.ended_at, focus_sessions.completed, focus_sessions.created_at
+         FROM focus_sessions
+         JOIN users ON users.id = focus_sessions.user_id
+         WHERE focus_sessions.ended_at IS NULL AND focus_sessions.ends_at <= $1
+         ORDER BY focus_sessions.ends_at ASC",
+    )
+    .bind(now)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| (row.get("discord_id"), focus_session_from_row(row)))
+        .collect())
+}
+
+/// `from` から `to` までに始めた集中時間を開始順に取得
+pub async fn get_focus_sessions_in_range(
+    pool: &DbPool,
+    user_id: i64,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<FocusSession>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM focus_sessions
+         WHERE user_id = $1 AND started_at >= $2 AND started_at < $3
+         ORDER BY started_at ASC",
+        FOCUS_SESSION_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(focus_session_from_row).collect())
+}
+
+// Timesheet confirmation queries
+fn timesheet_confirmation_from_row(row: &DbRow) -> TimesheetConfirmation {
+    TimesheetConfirmation {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        week_start: row.get("week_start"),
+        total_minutes: row.get("total_minutes"),
+        confirmed_at: row.get("confirmed_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// 確認依頼を作成（既に作成済みの週は何もしない）。新規作成した場合は true を返す
+pub async fn create_timesheet_confirmation(
+    pool: &DbPool,
+    user_id: i64,
+    week_start: NaiveDate,
+    total_minutes: i32,
+) -> Result<bool> {
+    let result = sqlx::query(
+        "INSERT INTO timesheet_confirmations (user_id, week_start, total_minutes) VALUES ($1, $2, $3)
+         ON CONFLICT(user_id, week_start) DO NOTHING",
+    )
+    .bind(user_id)
+    .bind(week_start)
+    .bind(total_minutes)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 未確認の確認依頼を確認済みにする。更新した場合は true を返す
+pub async fn confirm_timesheet(pool: &DbPool, user_id: i64, week_start: NaiveDate) -> Result<bool> {
+    let result = sqlx::query(
+        "UPDATE timesheet_confirmations
+         SET confirmed_at = CURRENT_TIMESTAMP
+         WHERE user_id = $1 AND week_start = $2 AND confirmed_at IS NULL",
+    )
+    .bind(user_id)
+    .bind(week_start)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get_timesheet_confirmations_by_date_range(
+    pool: &DbPool,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<TimesheetConfirmation>> {
+    let rows = sqlx::query(
+        "SELECT id, user_id, week_start, total_minutes, confirmed_at, created_at
+         FROM timesheet_confirmations
+         WHERE week_start >= $1 AND week_start <= $2
+         ORDER BY user_id ASC, week_start ASC",
+    )
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(timesheet_confirmation_from_row).collect())
+}
+
+// Validation rejection metrics queries
+
+/// ギルドの記録バリデーションエラーの件数を種類ごとに1増やす
+pub async fn increment_validation_rejection(
+    pool: &DbPool,
+    guild_id: &str,
+    kind: &str,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO validation_rejections (guild_id, kind, count, last_rejected_at)
+         VALUES ($1, $2, 1, CURRENT_TIMESTAMP)
+         ON CONFLICT(guild_id, kind) DO UPDATE
+         SET count = validation_rejections.count + 1, last_rejected_at = excluded.last_rejected_at",
+    )
+    .bind(guild_id)
+    .bind(kind)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// ギルドの記録バリデーションエラーの件数（種類, 件数）を件数の多い順に取得
+pub async fn get_validation_rejections(
+    pool: &DbPool,
+    guild_id: &str,
+) -> Result<Vec<(String, i64)>> {
+    let rows = sqlx::query(
+        "SELECT kind, count FROM validation_rejections
+         WHERE guild_id = $1
+         ORDER BY count DESC, kind ASC",
+    )
+    .bind(guild_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| (row.get("kind"), row.get("count")))
+        .collect())
+}
+
+// Monthly close queries
+
+/// サーバーの月を締め、その月（JST）のサーバーのユーザーの記録をロックする。ロックした記録数を返す
+pub async fn close_month(
+    pool: &DbPool,
+    guild_id: &str,
+    month_start: NaiveDate,
+    closed_by: &str,
+) -> Result<u64> {
+    let next_month = month_start
+        .checked_add_months(chrono::Months::new(1))
+        .ok_or_else(|| anyhow::anyhow!("invalid month: {}", month_start))?;
+    let (start, _) = jst_day_range_utc(month_start);
+    let (end, _) = jst_day_range_utc(next_month);
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "INSERT INTO closed_months (guild_id, month, closed_by) VALUES ($1, $2, $3)
+         ON CONFLICT(guild_id, month) DO NOTHING",
+    )
+    .bind(guild_id)
+    .bind(month_start)
+    .bind(closed_by)
+    .execute(&mut *tx)
+    .await?;
+
+    let result = sqlx::query(
+        "UPDATE attendance_records SET locked = TRUE
+         WHERE timestamp >= $1 AND timestamp < $2 AND locked = FALSE
+           AND user_id IN (SELECT id FROM users WHERE guild_id = $3)",
+    )
+    .bind(start)
+    .bind(end)
+    .bind(guild_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
+/// サーバーの月が締め済みか
+pub async fn is_month_closed(
+    pool: &DbPool,
+    guild_id: &str,
+    month_start: NaiveDate,
+) -> Result<bool> {
+    let count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM closed_months WHERE guild_id = $1 AND month = $2")
+            .bind(guild_id)
+            .bind(month_start)
+            .fetch_one(pool)
+            .await?;
+
+    Ok(count > 0)
+}
+
+// Pending change queries
+fn pending_change_from_row(row: &DbRow) -> Result<PendingChange> {
+    Ok(PendingChange {
+        id: row.get("id"),
+        guild_id: row.get("guild_id"),
+        user_id: row.get("user_id"),
+        requested_by: row.get("requested_by"),
+        action: row.get("action"),
+        record_id: row.get("record_id"),
+        record_type: row.try_get("record_type")?,
+        old_timestamp: row.get("old_timestamp"),
+        new_timestamp: row.get("new_timestamp"),
+        source: row.get("source"),
+        reason: row.get("reason"),
+        status: row.get("status"),
+        reviewed_by: row.get("reviewed_by"),
+        reviewed_at: row.get("reviewed_at"),
+        created_at: row.get("created_at"),
+    })
+}
+
+/// 承認待ちの記録変更を作成し、作成した変更を返す
+#[allow(clippy::too_many_arguments)]
+pub async fn create_pending_change(
+    pool: &DbPool,
+    guild_id: &str,
+    user_id: i64,
+    action: &str,
+    record_id: Option<i64>,
+    record_type: RecordType,
+    old_timestamp: Option<DateTime<Utc>>,
+    new_timestamp: DateTime<Utc>,
+    audit: &AuditSource,
+) -> Result<PendingChange> {
+    let row = sqlx::query(
+        "INSERT INTO pending_changes
+         (guild_id, user_id, requested_by, action, record_id, record_type, old_timestamp, new_timestamp, source, reason)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+         RETURNING *",
+    )
+    .bind(guild_id)
+    .bind(user_id)
+    .bind(&audit.actor_discord_id)
+    .bind(action)
+    .bind(record_id)
+    .bind(record_type.as_str())
+    .bind(old_timestamp)
+    .bind(new_timestamp)
+    .bind(&audit.source)
+    .bind(&audit.reason)
+    .fetch_one(pool)
+    .await?;
+
+    pending_change_from_row(&row)
+}
+
+pub async fn get_pending_change(pool: &DbPool, id: i64) -> Result<Option<PendingChange>> {
+    let row = sqlx::query("SELECT * FROM pending_changes WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    row.as_ref().map(pending_change_from_row).transpose()
+}
+
+/// 承認待ちの変更を承認・却下済みにする。既に処理済みの場合は false を返す
+pub async fn resolve_pending_change(
+    pool: &DbPool,
+    id: i64,
+    status: &str,
+    reviewed_by: &str,
+) -> Result<bool> {
+    let result = sqlx::query(
+        "UPDATE pending_changes
+         SET status = $1, reviewed_by = $2, reviewed_at = CURRENT_TIMESTAMP
+         WHERE id = $3 AND status = 'pending'",
+    )
+    .bind(status)
+    .bind(reviewed_by)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 反映に失敗した変更を承認待ちに戻す
+pub async fn reopen_pending_change(pool: &DbPool, id: i64) -> Result<()> {
+    sqlx::query(
+        "UPDATE pending_changes SET status = 'pending', reviewed_by = NULL, reviewed_at = NULL
+         WHERE id = $1",
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_pending_change(pool: &DbPool, id: i64) -> Result<()> {
+    sqlx::query("DELETE FROM pending_changes WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+// Pending interaction queries
+/// 操作の途中状態を保存してIDを返す（`expires_before` より古いものは削除する）
+pub async fn create_pending_interaction(
+    pool: &DbPool,
+    discord_user_id: &str,
+    action: &str,
+    payload: &str,
+    expires_before: DateTime<Utc>,
+) -> Result<i64> {
+    sqlx::query("DELETE FROM pending_interactions WHERE created_at < $1")
+        .bind(expires_before)
+        .execute(pool)
+        .await?;
+
+    let row = sqlx::query(
+        "INSERT INTO pending_interactions (discord_user_id, action, payload, created_at)
+         VALUES ($1, $2, $3, $4)
+         RETURNING id",
+    )
+    .bind(discord_user_id)
+    .bind(action)
+    .bind(payload)
+    .bind(Utc::now())
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.get("id"))
+}
+
+/// 本人の期限内の途中状態を取得
+pub async fn get_pending_interaction(
+    pool: &DbPool,
+    id: i64,
+    discord_user_id: &str,
+    expires_before: DateTime<Utc>,
+) -> Result<Option<PendingInteraction>> {
+    let row = sqlx::query(
+        "SELECT id, discord_user_id, action, payload, created_at
+         FROM pending_interactions
+         WHERE id = $1 AND discord_user_id = $2 AND created_at >= $3",
+    )
+    .bind(id)
+    .bind(discord_user_id)
+    .bind(expires_before)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| PendingInteraction {
+        id: row.get("id"),
+        discord_user_id: row.get("discord_user_id"),
+        action: row.get("action"),
+        payload: row.get("payload"),
+        created_at: row.get("created_at"),
+    }))
+}
+
+pub async fn delete_pending_interaction(pool: &DbPool, id: i64) -> Result<()> {
+    sqlx::query("DELETE FROM pending_interactions WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// サーバーのデータをまとめて削除し、削除した打刻記録と勤務セッションの件数を返す
+///
+/// `user_ids` の勤務データ（打刻・セッション・勤務時間の調整・監査ログ・週次確認・承認依頼・集中時間）と、
+/// サーバー単位の設定（設定・時給・プロジェクトとその既定値・シフトとそのひな形・検証エラー件数・月次締め）を1トランザクションで削除する。
+/// ユーザー本人の設定（ショートカットなど）とコマンド監査ログは残す
+pub async fn reset_guild_data(
+    pool: &DbPool,
+    guild_id: &str,
+    user_ids: &[i64],
+) -> Result<(u64, u64)> {
+    let mut tx = pool.begin().await?;
+    let mut records = 0;
+    let mut sessions = 0;
+
+    for user_id in user_ids {
+        for table in [
+            "audit_log",
+            "record_revisions",
+            "timesheet_confirmations",
+            "pending_changes",
+            "time_adjustments",
+            "focus_sessions",
+        ] {
+            sqlx::query(&format!("DELETE FROM {} WHERE user_id = $1", table))
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        sessions += sqlx::query("DELETE FROM work_sessions WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        records += sqlx::query("DELETE FROM attendance_records WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    }
+
+    // 削除対象外のユーザーのセッションからこのサーバーのプロジェクトへの参照を外す
+    sqlx::query(
+        "UPDATE work_sessions SET project_id = NULL
+         WHERE project_id IN (SELECT id FROM projects WHERE guild_id = $1)",
+    )
+    .bind(guild_id)
+    .execute(&mut *tx)
+    .await?;
+    for table in [
+        "pending_changes",
+        "rates",
+        "hour_caps",
+        "project_preferences",
+        "projects",
+        "shifts",
+        "shift_templates",
+        "validation_rejections",
+        "closed_months",
+        "guild_settings",
+    ] {
+        sqlx::query(&format!("DELETE FROM {} WHERE guild_id = $1", table))
+            .bind(guild_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+    Ok((records, sessions))
+}
@@ -0,0 +1,11 @@
+// The attendance engine as a library. `config`, `database` (models, queries, migrations) and
+// `utils` (session recalculation, record validation, formatting, stats) don't depend on a
+// Discord client, so they can be unit-tested and reused by other front ends; `bot` (the Discord
+// client) and `api` (the REST API it spawns) are built on top of them. `main.rs` only sets up
+// logging and starts the bot.
+
+pub mod api;
+pub mod bot;
+pub mod config;
+pub mod database;
+pub mod utils;
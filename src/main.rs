@@ -1,10 +1,6 @@
-mod bot;
-mod config;
-mod database;
-mod utils;
-
 use anyhow::Result;
-use config::Config;
+use discord_kintai::bot;
+use discord_kintai::config::Config;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
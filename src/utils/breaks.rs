@@ -0,0 +1,280 @@
+// Break handling for reports. Recorded breaks (`break_start` / `break_end` punches inside a
+// session) are subtracted from the session they fall in, and the automatic deduction set with
+// `/admin break_rule` tops up a day's breaks to the required length. The stored session totals
+// stay as recorded; both are applied when a report adds up the sessions, and each deducted day
+// is listed in the report.
+
+use crate::database::models::{AttendanceRecord, GuildSettings, RecordType, WorkSession};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::BTreeMap;
+
+/// 休憩の自動控除ルール
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BreakPolicy {
+    /// 1日の勤務時間がこの分数を超えたら控除の対象にする
+    pub after_minutes: i32,
+    /// 必要な休憩時間（分）。記録された休憩が足りない分だけ控除する
+    pub break_minutes: i32,
+}
+
+impl BreakPolicy {
+    /// ギルドに控除ルールが設定されていれば返す
+    pub fn from_settings(settings: &GuildSettings) -> Option<Self> {
+        Some(Self {
+            after_minutes: settings.auto_break_after_minutes?,
+            break_minutes: settings.auto_break_minutes,
+        })
+    }
+}
+
+/// 1日分の控除の内訳
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreakDeduction {
+    pub date: NaiveDate,
+    /// 控除前の勤務時間（記録された休憩を除く、分）
+    pub worked_minutes: i32,
+    /// 記録された休憩（同じ日のセッションの間隔と休憩の記録の合計、分）
+    pub recorded_break_minutes: i32,
+    pub deducted_minutes: i32,
+}
+
+/// 休憩開始から休憩終了までの時間帯（終了せずに勤務を終えた休憩は勤務の終了まで）
+pub fn break_intervals(records: &[AttendanceRecord]) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut sorted: Vec<&AttendanceRecord> = records.iter().collect();
+    sorted.sort_by_key(|record| record.timestamp);
+
+    let mut intervals = Vec::new();
+    let mut break_start = None;
+    for record in sorted {
+        match record.record_type {
+            RecordType::BreakStart => {
+                break_start.get_or_insert(record.timestamp);
+            }
+            RecordType::BreakEnd | RecordType::End => {
+                if let Some(start) = break_start.take() {
+                    intervals.push((start, record.timestamp));
+                }
+            }
+            RecordType::Start | RecordType::Away => {}
+        }
+    }
+    intervals
+}
+
+/// セッション中に記録された休憩の合計（分）
+fn break_minutes_within(
+    intervals: &[(DateTime<Utc>, DateTime<Utc>)],
+    session: &WorkSession,
+) -> i32 {
+    let Some(end_time) = session.end_time else {
+        return 0;
+    };
+    intervals
+        .iter()
+        .map(|&(start, end)| {
+            (end.min(end_time) - start.max(session.start_time))
+                .num_minutes()
+                .max(0) as i32
+        })
+        .sum()
+}
+
+/// 記録された休憩をセッションから差し引き、控除ルールがあれば足りない休憩を控除する
+///
+/// `records` は期間内の打刻（休憩の記録を読む）。控除はその日の一番長いセッションから差し引く。
+/// 勤務中のセッションは対象外
+pub fn apply_break_deductions(
+    sessions: &[WorkSession],
+    records: &[AttendanceRecord],
+    policy: Option<BreakPolicy>,
+) -> (Vec<WorkSession>, Vec<BreakDeduction>) {
+    let intervals = break_intervals(records);
+    let mut adjusted = sessions.to_vec();
+    let mut taken = vec![0; sessions.len()];
+    let mut by_date: BTreeMap<NaiveDate, Vec<usize>> = BTreeMap::new();
+    for (index, session) in sessions.iter().enumerate() {
+        if !session.is_completed {
+            continue;
+        }
+        taken[index] = break_minutes_within(&intervals, session);
+        adjusted[index].total_minutes = session
+            .total_minutes
+            .map(|minutes| (minutes - taken[index]).max(0));
+        by_date.entry(session.date).or_default().push(index);
+    }
+
+    let Some(policy) = policy else {
+        return (adjusted, Vec::new());
+    };
+
+    let mut deductions = Vec::new();
+    for (date, mut indices) in by_date {
+        indices.sort_by_key(|&index| sessions[index].start_time);
+        let worked_minutes: i32 = indices
+            .iter()
+            .filter_map(|&index| adjusted[index].total_minutes)
+            .sum();
+        if worked_minutes <= policy.after_minutes {
+            continue;
+        }
+
+        let gap_minutes: i32 = indices
+            .windows(2)
+            .filter_map(|pair| {
+                let previous_end = sessions[pair[0]].end_time?;
+                Some((sessions[pair[1]].start_time - previous_end).num_minutes() as i32)
+            })
+            .map(|gap| gap.max(0))
+            .sum();
+        let recorded_break_minutes =
+            gap_minutes + indices.iter().map(|&index| taken[index]).sum::<i32>();
+        let deducted_minutes = policy.break_minutes - recorded_break_minutes;
+        if deducted_minutes <= 0 {
+            continue;
+        }
+
+        if let Some(&longest) = indices
+            .iter()
+            .max_by_key(|&&index| adjusted[index].total_minutes)
+        {
+            let session = &mut adjusted[longest];
+            session.total_minutes = session
+                .total_minutes
+                .map(|minutes| (minutes - deducted_minutes).max(0));
+        }
+        deductions.push(BreakDeduction {
+            date,
+            worked_minutes,
+            recorded_break_minutes,
+            deducted_minutes,
+        });
+    }
+
+    (adjusted, deductions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 12, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn record(record_type: RecordType, timestamp: DateTime<Utc>) -> AttendanceRecord {
+        AttendanceRecord {
+            id: 0,
+            user_id: 1,
+            record_type,
+            timestamp,
+            is_modified: false,
+            original_timestamp: None,
+            is_imported: false,
+            source: None,
+            locked: false,
+            session_id: None,
+            created_at: timestamp,
+            updated_at: timestamp,
+        }
+    }
+
+    fn session(start: DateTime<Utc>, end: DateTime<Utc>) -> WorkSession {
+        WorkSession {
+            id: 0,
+            user_id: 1,
+            start_time: start,
+            end_time: Some(end),
+            total_minutes: Some((end - start).num_minutes() as i32),
+            date: start.date_naive(),
+            is_completed: true,
+            note: None,
+            created_at: start,
+            updated_at: start,
+        }
+    }
+
+    #[test]
+    fn test_apply_break_deductions() {
+        let policy = BreakPolicy {
+            after_minutes: 6 * 60,
+            break_minutes: 60,
+        };
+        let sessions = vec![
+            // 11日: 休憩なしで8時間 → 60分控除
+            session(at(11, 0, 0), at(11, 8, 0)),
+            // 12日: 30分の休憩を挟んで計7時間 → 足りない30分を控除（長い方から）
+            session(at(12, 0, 0), at(12, 2, 0)),
+            session(at(12, 2, 30), at(12, 7, 30)),
+            // 13日: 1時間の休憩を記録済み → 控除なし
+            session(at(13, 0, 0), at(13, 4, 0)),
+            session(at(13, 5, 0), at(13, 9, 0)),
+            // 14日: 6時間ちょうど → 対象外
+            session(at(14, 0, 0), at(14, 6, 0)),
+        ];
+
+        let (adjusted, deductions) = apply_break_deductions(&sessions, &[], Some(policy));
+
+        let totals: Vec<_> = adjusted.iter().map(|s| s.total_minutes.unwrap()).collect();
+        assert_eq!(totals, vec![420, 120, 270, 240, 240, 360]);
+        assert_eq!(
+            deductions,
+            vec![
+                BreakDeduction {
+                    date: NaiveDate::from_ymd_opt(2023, 12, 11).unwrap(),
+                    worked_minutes: 480,
+                    recorded_break_minutes: 0,
+                    deducted_minutes: 60,
+                },
+                BreakDeduction {
+                    date: NaiveDate::from_ymd_opt(2023, 12, 12).unwrap(),
+                    worked_minutes: 420,
+                    recorded_break_minutes: 30,
+                    deducted_minutes: 30,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recorded_break_inside_session() {
+        let policy = BreakPolicy {
+            after_minutes: 6 * 60,
+            break_minutes: 60,
+        };
+        // 1つのセッションの中で休憩を記録した日
+        let sessions = vec![
+            session(at(11, 0, 0), at(11, 9, 0)),
+            session(at(12, 0, 0), at(12, 9, 0)),
+        ];
+        let records = vec![
+            // 11日: 1時間の休憩 → 勤務8時間、控除なし
+            record(RecordType::BreakStart, at(11, 3, 0)),
+            record(RecordType::BreakEnd, at(11, 4, 0)),
+            // 12日: 45分の休憩 → 勤務8時間15分、足りない15分だけ控除
+            record(RecordType::BreakStart, at(12, 3, 0)),
+            record(RecordType::BreakEnd, at(12, 3, 45)),
+        ];
+
+        let (adjusted, deductions) = apply_break_deductions(&sessions, &records, Some(policy));
+
+        let totals: Vec<_> = adjusted.iter().map(|s| s.total_minutes.unwrap()).collect();
+        assert_eq!(totals, vec![480, 480]);
+        assert_eq!(
+            deductions,
+            vec![BreakDeduction {
+                date: NaiveDate::from_ymd_opt(2023, 12, 12).unwrap(),
+                worked_minutes: 495,
+                recorded_break_minutes: 45,
+                deducted_minutes: 15,
+            }]
+        );
+
+        // 控除ルールが無くても記録された休憩は差し引く
+        let (adjusted, deductions) = apply_break_deductions(&sessions, &records, None);
+        let totals: Vec<_> = adjusted.iter().map(|s| s.total_minutes.unwrap()).collect();
+        assert_eq!(totals, vec![480, 495]);
+        assert!(deductions.is_empty());
+    }
+}
@@ -0,0 +1,276 @@
+// Bar chart of daily working hours attached to `/monthly`. There is no plotting or font
+// dependency: bars, grid lines and a 3x5 pixel digit font are drawn straight into an RGB
+// buffer, which is then encoded as a PNG with flate2 and crc32fast.
+
+use crate::database::models::WorkSession;
+use crate::utils::time::get_month_range;
+use anyhow::Result;
+use chrono::{Datelike, NaiveDate, Weekday};
+use flate2::Compression;
+use flate2::write::ZlibEncoder;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+const WIDTH: usize = 640;
+const HEIGHT: usize = 320;
+const MARGIN_LEFT: usize = 36;
+const MARGIN_RIGHT: usize = 12;
+const MARGIN_TOP: usize = 12;
+const MARGIN_BOTTOM: usize = 28;
+/// 3x5ピクセルの数字を何倍で描くか
+const FONT_SCALE: usize = 2;
+
+type Rgb = [u8; 3];
+
+const BACKGROUND: Rgb = [0xff, 0xff, 0xff];
+const GRID: Rgb = [0xdd, 0xdd, 0xdd];
+const AXIS: Rgb = [0x55, 0x55, 0x55];
+const WEEKDAY_BAR: Rgb = [0x34, 0x98, 0xdb]; // 情報の埋め込みと同じ青
+const WEEKEND_BAR: Rgb = [0x95, 0xa5, 0xa6];
+const TARGET_LINE: Rgb = [0xe7, 0x4c, 0x3c];
+
+/// 0〜9 の3x5ピクセルのフォント（各行の下位3ビット）
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// 月の各日の勤務時間の棒グラフ（PNG）。所定労働時間の位置に赤い破線を引き、土日の棒は灰色にする
+pub fn render_daily_hours_chart(
+    month: NaiveDate,
+    sessions: &[WorkSession],
+    daily_target_minutes: i32,
+) -> Result<Vec<u8>> {
+    let (start_of_month, end_of_month) = get_month_range(month);
+    let mut minutes_by_date: BTreeMap<NaiveDate, i32> = BTreeMap::new();
+    for session in sessions {
+        *minutes_by_date.entry(session.date).or_default() += session.total_minutes.unwrap_or(0);
+    }
+
+    // 縦軸は2時間刻みで、最低でも8時間まで表示する
+    let max_minutes = minutes_by_date
+        .values()
+        .copied()
+        .chain([daily_target_minutes, 8 * 60])
+        .max()
+        .unwrap_or(8 * 60);
+    let max_hours = ((max_minutes + 59) / 60 + 1) / 2 * 2;
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let y_of = |minutes: i32| {
+        let height = minutes.clamp(0, max_hours * 60) as usize * plot_height;
+        MARGIN_TOP + plot_height - height / (max_hours as usize * 60)
+    };
+
+    let mut canvas = Canvas::new();
+    for hours in (0..=max_hours).step_by(2) {
+        let y = y_of(hours * 60);
+        canvas.fill_rect(MARGIN_LEFT, y, plot_width, 1, GRID);
+        let label_x = MARGIN_LEFT - 4 - number_width(hours as u32);
+        canvas.draw_number(
+            label_x,
+            y.saturating_sub(5 * FONT_SCALE / 2),
+            hours as u32,
+            AXIS,
+        );
+    }
+
+    let days = end_of_month.day() as usize;
+    let slot = plot_width / days;
+    let bar_width = (slot * 2 / 3).max(1);
+    for date in start_of_month.iter_days().take(days) {
+        let slot_x = MARGIN_LEFT + date.day0() as usize * slot;
+        if let Some(&minutes) = minutes_by_date.get(&date) {
+            let y = y_of(minutes);
+            let color = match date.weekday() {
+                Weekday::Sat | Weekday::Sun => WEEKEND_BAR,
+                _ => WEEKDAY_BAR,
+            };
+            canvas.fill_rect(
+                slot_x + (slot - bar_width) / 2,
+                y,
+                bar_width,
+                MARGIN_TOP + plot_height - y,
+                color,
+            );
+        }
+        if date.day() == 1 || date.day() % 5 == 0 {
+            let width = number_width(date.day());
+            canvas.draw_number(
+                (slot_x + slot / 2).saturating_sub(width / 2),
+                HEIGHT - MARGIN_BOTTOM + 8,
+                date.day(),
+                AXIS,
+            );
+        }
+    }
+
+    let target_y = y_of(daily_target_minutes);
+    for x in (MARGIN_LEFT..WIDTH - MARGIN_RIGHT).step_by(12) {
+        canvas.fill_rect(x, target_y.saturating_sub(1), 6, 2, TARGET_LINE);
+    }
+    canvas.fill_rect(MARGIN_LEFT, MARGIN_TOP, 1, plot_height + 1, AXIS);
+    canvas.fill_rect(MARGIN_LEFT, MARGIN_TOP + plot_height, plot_width, 1, AXIS);
+
+    encode_png(WIDTH, HEIGHT, &canvas.pixels)
+}
+
+fn number_width(number: u32) -> usize {
+    let digits = number.to_string().len();
+    digits * 4 * FONT_SCALE - FONT_SCALE
+}
+
+struct Canvas {
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new() -> Self {
+        Self {
+            pixels: BACKGROUND.repeat(WIDTH * HEIGHT),
+        }
+    }
+
+    /// 範囲外にはみ出した部分は描かない
+    fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Rgb) {
+        for row in y..(y + height).min(HEIGHT) {
+            for column in x..(x + width).min(WIDTH) {
+                let offset = (row * WIDTH + column) * 3;
+                self.pixels[offset..offset + 3].copy_from_slice(&color);
+            }
+        }
+    }
+
+    /// (x, y) を左上として数字を描く
+    fn draw_number(&mut self, x: usize, y: usize, number: u32, color: Rgb) {
+        for (index, digit) in number.to_string().bytes().enumerate() {
+            let glyph = DIGITS[(digit - b'0') as usize];
+            let glyph_x = x + index * 4 * FONT_SCALE;
+            for (row, bits) in glyph.iter().enumerate() {
+                for column in 0..3 {
+                    if bits & (0b100 >> column) != 0 {
+                        self.fill_rect(
+                            glyph_x + column * FONT_SCALE,
+                            y + row * FONT_SCALE,
+                            FONT_SCALE,
+                            FONT_SCALE,
+                            color,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 8ビットRGBのPNGとして出力する（各行のフィルタは無し）
+fn encode_png(width: usize, height: usize, pixels: &[u8]) -> Result<Vec<u8>> {
+    let mut raw = Vec::with_capacity((width * 3 + 1) * height);
+    for row in pixels.chunks(width * 3) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&raw)?;
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &encoder.finish()?);
+    write_chunk(&mut png, b"IEND", &[]);
+    Ok(png)
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(kind);
+    hasher.update(data);
+    png.extend_from_slice(&hasher.finalize().to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use flate2::read::ZlibDecoder;
+    use std::io::Read;
+
+    fn session(day: u32, minutes: i32) -> WorkSession {
+        let start = Utc.with_ymd_and_hms(2023, 12, day, 0, 0, 0).unwrap();
+        WorkSession {
+            id: day as i64,
+            user_id: 1,
+            start_time: start,
+            end_time: Some(start + chrono::Duration::minutes(minutes as i64)),
+            total_minutes: Some(minutes),
+            date: NaiveDate::from_ymd_opt(2023, 12, day).unwrap(),
+            is_completed: true,
+            note: None,
+            created_at: start,
+            updated_at: start,
+        }
+    }
+
+    /// PNGを読み戻して (x, y) の色を返す
+    fn pixel_at(png: &[u8], x: usize, y: usize) -> Rgb {
+        let mut offset = 8;
+        let mut compressed = Vec::new();
+        while offset < png.len() {
+            let length = u32::from_be_bytes(png[offset..offset + 4].try_into().unwrap()) as usize;
+            if &png[offset + 4..offset + 8] == b"IDAT" {
+                compressed.extend_from_slice(&png[offset + 8..offset + 8 + length]);
+            }
+            offset += length + 12;
+        }
+        let mut raw = Vec::new();
+        ZlibDecoder::new(&compressed[..])
+            .read_to_end(&mut raw)
+            .unwrap();
+        assert_eq!(raw.len(), (WIDTH * 3 + 1) * HEIGHT);
+
+        let start = y * (WIDTH * 3 + 1) + 1 + x * 3;
+        raw[start..start + 3].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_render_daily_hours_chart() {
+        // 2023-12-01 は金曜日、12-02 は土曜日
+        let sessions = vec![session(1, 8 * 60), session(1, 60), session(2, 4 * 60)];
+        let png = render_daily_hours_chart(
+            NaiveDate::from_ymd_opt(2023, 12, 15).unwrap(),
+            &sessions,
+            8 * 60,
+        )
+        .unwrap();
+
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], [0, 0, 2, 128, 0, 0, 1, 64]);
+
+        let slot = (WIDTH - MARGIN_LEFT - MARGIN_RIGHT) / 31;
+        let bottom = HEIGHT - MARGIN_BOTTOM - 2;
+        let center = |day: usize| MARGIN_LEFT + (day - 1) * slot + slot / 2;
+        assert_eq!(pixel_at(&png, center(1), bottom), WEEKDAY_BAR);
+        assert_eq!(pixel_at(&png, center(2), bottom), WEEKEND_BAR);
+        assert_eq!(pixel_at(&png, center(3), bottom), BACKGROUND);
+
+        // 縦軸は10時間まで。1日の棒（9時間）の上端より上は背景のまま
+        let top_of_day1 = MARGIN_TOP + (HEIGHT - MARGIN_TOP - MARGIN_BOTTOM) / 10;
+        assert_eq!(pixel_at(&png, center(1), top_of_day1 - 1), BACKGROUND);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::database::models::{AttendanceRecord, RecordType};
+use crate::utils::record_validator::{RecordValidator, ValidationError};
+use crate::utils::time::combine_date_time_jst_with_day_offset;
+use crate::utils::validation::{validate_date_format, validate_time_format_with_day_info};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// 1回の取り込みで受け付ける行数の上限
+pub const MAX_IMPORT_ROWS: usize = 1000;
+
+/// 取り込みCSVの1行（終了時刻は 25:30 のような翌日表記も可）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRow {
+    /// CSV上の行番号（1始まり、エラー表示用）
+    pub line: usize,
+    pub date: NaiveDate,
+    pub start: DateTime<Utc>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+/// 行ごとの読み込み結果（読めない行があっても他の行は取り込めるようにする）
+#[derive(Debug, Default)]
+pub struct ParsedImport {
+    pub rows: Vec<ImportRow>,
+    pub errors: Vec<(usize, String)>,
+}
+
+/// `date,start,end` 形式のCSVを読み込む（先頭の見出し行と空行は無視し、end は空欄可）
+pub fn parse_attendance_csv(content: &str) -> Result<ParsedImport> {
+    let mut parsed = ParsedImport::default();
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || (index == 0 && line.starts_with("date")) {
+            continue;
+        }
+        if parsed.rows.len() + parsed.errors.len() >= MAX_IMPORT_ROWS {
+            return Err(anyhow!("一度に取り込めるのは{}行までです", MAX_IMPORT_ROWS));
+        }
+
+        match parse_row(line_number, line) {
+            Ok(row) => parsed.rows.push(row),
+            Err(e) => parsed.errors.push((line_number, e.to_string())),
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_row(line: usize, content: &str) -> Result<ImportRow> {
+    let fields: Vec<&str> = content.split(',').map(str::trim).collect();
+    let [date, start, end] = fields[..] else {
+        return Err(anyhow!("列は date,start,end の3つにしてください"));
+    };
+
+    let date = validate_date_format(date)?;
+    let (start_time, start_next_day) = validate_time_format_with_day_info(start)
+        .map_err(|_| anyhow!("開始時刻は HH:MM 形式で入力してください"))?;
+    let start = combine_date_time_jst_with_day_offset(date, start_time, start_next_day);
+
+    let end = if end.is_empty() {
+        None
+    } else {
+        let (end_time, end_next_day) = validate_time_format_with_day_info(end)
+            .map_err(|_| anyhow!("終了時刻は HH:MM 形式で入力してください"))?;
+        let end = combine_date_time_jst_with_day_offset(date, end_time, end_next_day);
+        if end <= start {
+            return Err(anyhow!("終了時刻は開始時刻より後にしてください"));
+        }
+        Some(end)
+    };
+
+    Ok(ImportRow {
+        line,
+        date,
+        start,
+        end,
+    })
+}
+
+/// 既存の記録に対して1行分（開始と終了）をまとめて検証する
+pub fn validate_import_row(
+    existing_records: &[AttendanceRecord],
+    row: &ImportRow,
+) -> Result<(), ValidationError> {
+    RecordValidator::validate_imported_record(
+        existing_records,
+        RecordType::Start,
+        row.start,
+        row.date,
+    )?;
+
+    if let Some(end) = row.end {
+        // 同じ行の開始記録を追加した状態で終了記録を検証する
+        let mut records = existing_records.to_vec();
+        records.push(AttendanceRecord {
+            id: 0,
+            user_id: 0,
+            record_type: RecordType::Start,
+            timestamp: row.start,
+            is_modified: false,
+            original_timestamp: None,
+            is_imported: true,
+            source: None,
+            locked: false,
+            session_id: None,
+            created_at: row.start,
+            updated_at: row.start,
+        });
+        RecordValidator::validate_imported_record(&records, RecordType::End, end, row.date)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_attendance_csv() {
+        let parsed = parse_attendance_csv(
+            "date,start,end\n\
+             2023-12-15,09:00,18:00\n\
+             \n\
+             2023-12-16,22:00,25:30\n\
+             2023-12-17,10:00,\n\
+             2023-12-18,18:00,09:00\n\
+             2023-12-32,09:00,18:00\n\
+             2023-12-20,09:00\n",
+        )
+        .unwrap();
+
+        assert_eq!(parsed.rows.len(), 3);
+        assert_eq!(parsed.rows[0].line, 2);
+        assert_eq!(
+            parsed.rows[1].end.unwrap() - parsed.rows[1].start,
+            chrono::Duration::minutes(210)
+        );
+        assert_eq!(parsed.rows[2].end, None);
+        assert_eq!(
+            parsed
+                .errors
+                .iter()
+                .map(|(line, _)| *line)
+                .collect::<Vec<_>>(),
+            vec![6, 7, 8]
+        );
+    }
+
+    #[test]
+    fn test_validate_import_row_allows_old_dates() {
+        let parsed = parse_attendance_csv("2020-01-06,09:00,18:00\n").unwrap();
+        let row = &parsed.rows[0];
+
+        assert!(validate_import_row(&[], row).is_ok());
+
+        let existing = AttendanceRecord {
+            id: 1,
+            user_id: 1,
+            record_type: RecordType::Start,
+            timestamp: row.start,
+            is_modified: false,
+            original_timestamp: None,
+            is_imported: false,
+            source: None,
+            locked: false,
+            session_id: None,
+            created_at: row.start,
+            updated_at: row.start,
+        };
+        assert!(matches!(
+            validate_import_row(&[existing], row),
+            Err(ValidationError::DuplicateTime { .. })
+        ));
+    }
+}
@@ -0,0 +1,121 @@
+use crate::database::models::{AttendanceRecord, RecordType};
+use chrono::{DateTime, Utc};
+
+/// 長時間勤務として報告する1日の勤務時間（分）
+pub const LONG_DAY_MINUTES: i32 = 16 * 60;
+
+/// 1日の記録に見つかった要確認の項目
+#[derive(Debug, Clone, PartialEq)]
+pub enum Anomaly {
+    /// 終了記録の無い開始記録
+    OpenSession { start: DateTime<Utc> },
+    /// 開始記録の無い終了記録
+    UnpairedEnd { end: DateTime<Utc> },
+    /// 1日の勤務時間が `LONG_DAY_MINUTES` 以上
+    LongDay { minutes: i32 },
+    /// 時刻が修正された記録
+    Modified { count: usize },
+}
+
+/// ユーザーの1日分の記録から要確認の項目を検出する
+pub fn detect_anomalies(records: &[AttendanceRecord]) -> Vec<Anomaly> {
+    let mut sorted = records.to_vec();
+    sorted.sort_by_key(|record| record.timestamp);
+
+    let mut anomalies = Vec::new();
+    let mut open_start: Option<DateTime<Utc>> = None;
+    let mut total_minutes = 0;
+
+    for record in &sorted {
+        match record.record_type {
+            RecordType::Start => {
+                if let Some(start) = open_start.replace(record.timestamp) {
+                    anomalies.push(Anomaly::OpenSession { start });
+                }
+            }
+            RecordType::End => match open_start.take() {
+                Some(start) => total_minutes += (record.timestamp - start).num_minutes() as i32,
+                None => anomalies.push(Anomaly::UnpairedEnd {
+                    end: record.timestamp,
+                }),
+            },
+            // 休憩・離席の順序は記録時に検証している
+            RecordType::BreakStart | RecordType::BreakEnd | RecordType::Away => {}
+        }
+    }
+    if let Some(start) = open_start {
+        anomalies.push(Anomaly::OpenSession { start });
+    }
+
+    if total_minutes >= LONG_DAY_MINUTES {
+        anomalies.push(Anomaly::LongDay {
+            minutes: total_minutes,
+        });
+    }
+
+    let modified = sorted.iter().filter(|record| record.is_modified).count();
+    if modified > 0 {
+        anomalies.push(Anomaly::Modified { count: modified });
+    }
+
+    anomalies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn jst(hour: u32, minute: u32) -> DateTime<Utc> {
+        chrono::FixedOffset::east_opt(9 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2023, 12, 15, hour, minute, 0)
+            .unwrap()
+            .to_utc()
+    }
+
+    fn record(record_type: &str, timestamp: DateTime<Utc>) -> AttendanceRecord {
+        AttendanceRecord {
+            id: 0,
+            user_id: 1,
+            record_type: record_type.parse().unwrap(),
+            timestamp,
+            is_modified: false,
+            original_timestamp: None,
+            is_imported: false,
+            source: None,
+            locked: false,
+            session_id: None,
+            created_at: timestamp,
+            updated_at: timestamp,
+        }
+    }
+
+    #[test]
+    fn test_detect_anomalies() {
+        assert!(
+            detect_anomalies(&[record("start", jst(9, 0)), record("end", jst(18, 0))]).is_empty()
+        );
+
+        let mut modified = record("end", jst(23, 30));
+        modified.is_modified = true;
+        let records = vec![
+            record("end", jst(6, 0)),
+            record("start", jst(7, 0)),
+            modified,
+            record("start", jst(23, 45)),
+        ];
+
+        assert_eq!(
+            detect_anomalies(&records),
+            vec![
+                Anomaly::UnpairedEnd { end: jst(6, 0) },
+                Anomaly::OpenSession { start: jst(23, 45) },
+                Anomaly::LongDay {
+                    minutes: 16 * 60 + 30
+                },
+                Anomaly::Modified { count: 1 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,548 @@
+use crate::database::models::{AttendanceRecord, TimeAdjustment, User, WorkSession};
+use crate::utils::stats::{MemberMonthSummary, SessionStats, over_cap_minutes};
+use crate::utils::time::{format_decimal_hours, format_hhmm, format_time_jst};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// エクスポートファイル末尾に付与する署名行のプレフィックス
+pub const SIGNATURE_PREFIX: &str = "# kintai-signature: hmac-sha256:";
+
+#[derive(Debug, PartialEq)]
+pub enum ExportVerification {
+    /// 署名が一致（生成後に改変されていない）
+    Valid,
+    /// 署名が一致しない（改変されている、または別の鍵で署名されている）
+    Invalid,
+    /// 署名行が見つからない
+    Unsigned,
+}
+
+/// セッションCSVのヘッダー行
+const SESSIONS_CSV_HEADER: &str = "date,start_time,end_time,total_minutes,is_completed";
+
+/// セッションCSVの1行
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportRow {
+    pub date: String,
+    pub start_time: String,
+    pub end_time: String,
+    pub total_minutes: String,
+    pub is_completed: String,
+}
+
+/// 2つのセッションCSVの差分（日付と開始時刻が同じ行を同じセッションとみなす）
+#[derive(Debug, Default, PartialEq)]
+pub struct ExportDiff {
+    pub added: Vec<ExportRow>,
+    pub changed: Vec<(ExportRow, ExportRow)>,
+    pub deleted: Vec<ExportRow>,
+}
+
+impl ExportDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.deleted.is_empty()
+    }
+}
+
+/// 給与計算用テンプレートで追加する列
+const PAYROLL_CSV_COLUMNS: &str =
+    "duration_hhmm,duration_hours,break_minutes,break_hhmm,break_hours";
+
+/// セッションCSVの列構成
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, poise::ChoiceParameter)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportTemplate {
+    /// 基本の列のみ
+    #[default]
+    #[name = "標準"]
+    Standard,
+    /// 勤務時間と休憩時間（同じ日の直前のセッションからの間隔）を HH:MM と時間単位の小数でも出力
+    #[name = "給与計算用（時間・休憩の列を追加）"]
+    Payroll,
+    /// 基本の列に加えて、セッションのメモ（`/end` の「今日やったこと」など）を出力
+    #[name = "日報付き（メモの列を追加）"]
+    DailyReport,
+}
+
+/// 日報付きテンプレートで追加する列
+const NOTE_CSV_COLUMN: &str = "note";
+
+/// 月間上限が設定されているときに追加する列
+const OVER_CAP_CSV_COLUMN: &str = "over_cap_minutes";
+
+/// `monthly_cap_minutes` を指定すると、月間上限を超えた分を最後の列に出力する
+pub fn format_sessions_csv(
+    sessions: &[WorkSession],
+    template: ExportTemplate,
+    monthly_cap_minutes: Option<i32>,
+) -> String {
+    let mut csv = String::from(SESSIONS_CSV_HEADER);
+    if template == ExportTemplate::Payroll {
+        csv.push(',');
+        csv.push_str(PAYROLL_CSV_COLUMNS);
+    }
+    if template == ExportTemplate::DailyReport {
+        csv.push(',');
+        csv.push_str(NOTE_CSV_COLUMN);
+    }
+    if monthly_cap_minutes.is_some() {
+        csv.push(',');
+        csv.push_str(OVER_CAP_CSV_COLUMN);
+    }
+    csv.push('\n');
+
+    let over_cap = monthly_cap_minutes.map(|cap| over_cap_minutes(sessions, cap));
+    let mut previous: Option<&WorkSession> = None;
+    for (index, session) in sessions.iter().enumerate() {
+        csv.push_str(&format!(
+            "{},{},{},{},{}",
+            session.date.format("%Y-%m-%d"),
+            format_time_jst(session.start_time),
+            session.end_time.map(format_time_jst).unwrap_or_default(),
+            session
+                .total_minutes
+                .map(|minutes| minutes.to_string())
+                .unwrap_or_default(),
+            session.is_completed
+        ));
+
+        if template == ExportTemplate::Payroll {
+            let break_minutes = previous
+                .filter(|previous| previous.date == session.date)
+                .and_then(|previous| previous.end_time)
+                .map(|end| (session.start_time - end).num_minutes().max(0) as i32);
+            csv.push_str(&format!(
+                ",{},{},{},{},{}",
+                session.total_minutes.map(format_hhmm).unwrap_or_default(),
+                session
+                    .total_minutes
+                    .map(format_decimal_hours)
+                    .unwrap_or_default(),
+                break_minutes
+                    .map(|minutes| minutes.to_string())
+                    .unwrap_or_default(),
+                break_minutes.map(format_hhmm).unwrap_or_default(),
+                break_minutes.map(format_decimal_hours).unwrap_or_default()
+            ));
+        }
+        if template == ExportTemplate::DailyReport {
+            csv.push(',');
+            csv.push_str(&csv_field(session.note.as_deref().unwrap_or_default()));
+        }
+        if let Some(over_cap) = &over_cap {
+            csv.push_str(&format!(",{}", over_cap[index]));
+        }
+
+        csv.push('\n');
+        previous = Some(session);
+    }
+
+    csv
+}
+
+/// カンマ・引用符を含む値を引用符で囲む（行単位で読み込めるよう改行は空白にする）
+fn csv_field(value: &str) -> String {
+    let value = value.replace(['\r', '\n'], " ");
+    if value.contains([',', '"']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// チームレポートのメンバー別集計CSV
+pub fn format_team_report_csv(members: &[(User, Vec<WorkSession>)]) -> String {
+    let mut csv = String::from(
+        "username,discord_id,total_minutes,total_hours,working_days,average_minutes_per_day,average_hours_per_day,session_count,incomplete_sessions\n",
+    );
+
+    for (user, sessions) in members {
+        let stats = SessionStats::from_sessions(sessions);
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{}\n",
+            user.username,
+            user.discord_id,
+            stats.total_minutes,
+            format_decimal_hours(stats.total_minutes),
+            stats.working_days,
+            stats.average_minutes_per_day(),
+            format_decimal_hours(stats.average_minutes_per_day()),
+            stats.session_count,
+            stats.incomplete_count
+        ));
+    }
+
+    csv
+}
+
+/// `/admin monthly` のCSV（1行1メンバー）
+pub fn format_guild_monthly_csv(summaries: &[MemberMonthSummary]) -> String {
+    let mut csv = String::from(
+        "username,discord_id,total_minutes,total_hours,overtime_minutes,overtime_hours,working_days,incomplete_sessions\n",
+    );
+
+    for summary in summaries {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            summary.user.username,
+            summary.user.discord_id,
+            summary.total_minutes,
+            format_decimal_hours(summary.total_minutes),
+            summary.overtime_minutes,
+            format_decimal_hours(summary.overtime_minutes),
+            summary.working_days,
+            summary.incomplete_sessions
+        ));
+    }
+
+    csv
+}
+
+/// `/export json` で出力する個人データ一式
+#[derive(Debug, Serialize)]
+pub struct PersonalDataExport<'a> {
+    pub exported_at: DateTime<Utc>,
+    pub user: &'a User,
+    pub attendance_records: &'a [AttendanceRecord],
+    pub work_sessions: &'a [WorkSession],
+    pub time_adjustments: &'a [TimeAdjustment],
+}
+
+/// 個人データ一式を整形済みJSONにする（JSONとして読めるよう署名は付けない）
+pub fn format_personal_data_json(data: &PersonalDataExport) -> Result<String> {
+    Ok(serde_json::to_string_pretty(data)?)
+}
+
+/// `/admin reset-guild` の削除前に出力するサーバーのデータ一式
+#[derive(Debug, Serialize)]
+pub struct GuildDataExport<'a> {
+    pub exported_at: DateTime<Utc>,
+    pub guild_id: &'a str,
+    pub members: Vec<PersonalDataExport<'a>>,
+}
+
+/// サーバーのデータ一式を整形済みJSONにする
+pub fn format_guild_data_json(data: &GuildDataExport) -> Result<String> {
+    Ok(serde_json::to_string_pretty(data)?)
+}
+
+/// `/export csv` の形式のCSVを読み込む（署名行は無視する）
+pub fn parse_sessions_csv(content: &str) -> Result<Vec<ExportRow>> {
+    let (content, _) = split_signature(content);
+    let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+
+    // テンプレートや月間上限による追加列は比較に使わない
+    let header = lines.next().map(str::trim).unwrap_or_default();
+    let header = [OVER_CAP_CSV_COLUMN, NOTE_CSV_COLUMN]
+        .iter()
+        .fold(header, |header, column| {
+            header
+                .strip_suffix(column)
+                .and_then(|header| header.strip_suffix(','))
+                .unwrap_or(header)
+        });
+    if header != SESSIONS_CSV_HEADER
+        && header != format!("{},{}", SESSIONS_CSV_HEADER, PAYROLL_CSV_COLUMNS)
+    {
+        return Err(anyhow!("`/export csv` で出力したファイルではありません"));
+    }
+
+    lines
+        .enumerate()
+        .map(|(index, line)| {
+            let fields: Vec<&str> = line.trim().split(',').collect();
+            let [date, start_time, end_time, total_minutes, is_completed, ..] = fields[..] else {
+                return Err(anyhow!("{}行目の形式が正しくありません", index + 2));
+            };
+            Ok(ExportRow {
+                date: date.to_string(),
+                start_time: start_time.to_string(),
+                end_time: end_time.to_string(),
+                total_minutes: total_minutes.to_string(),
+                is_completed: is_completed.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// 以前のエクスポートから現在の内容への差分
+pub fn diff_sessions(previous: &[ExportRow], current: &[ExportRow]) -> ExportDiff {
+    let key = |row: &ExportRow| (row.date.clone(), row.start_time.clone());
+    let previous_by_key: BTreeMap<_, _> = previous.iter().map(|row| (key(row), row)).collect();
+    let current_by_key: BTreeMap<_, _> = current.iter().map(|row| (key(row), row)).collect();
+
+    let mut diff = ExportDiff::default();
+    for (key, row) in &current_by_key {
+        match previous_by_key.get(key) {
+            None => diff.added.push((*row).clone()),
+            Some(old) if old != row => diff.changed.push(((*old).clone(), (*row).clone())),
+            Some(_) => {}
+        }
+    }
+    diff.deleted = previous_by_key
+        .iter()
+        .filter(|(key, _)| !current_by_key.contains_key(*key))
+        .map(|(_, row)| (*row).clone())
+        .collect();
+    diff
+}
+
+/// 署名行の前の本文と署名行に分ける（署名行が無ければ全体が本文）
+fn split_signature(signed_content: &str) -> (&str, Option<&str>) {
+    match signed_content.rfind(SIGNATURE_PREFIX) {
+        Some(signature_start) => {
+            let (content, signature_line) = signed_content.split_at(signature_start);
+            (content, Some(signature_line))
+        }
+        None => (signed_content, None),
+    }
+}
+
+fn compute_signature(content: &str, key: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(content.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// エクスポート内容の末尾に署名行を付与
+pub fn sign_export(content: &str, key: &[u8]) -> String {
+    format!(
+        "{}{}{}\n",
+        content,
+        SIGNATURE_PREFIX,
+        compute_signature(content, key)
+    )
+}
+
+/// 署名付きエクスポートが生成後に改変されていないか検証
+pub fn verify_export(signed_content: &str, key: &[u8]) -> ExportVerification {
+    let (content, Some(signature_line)) = split_signature(signed_content) else {
+        return ExportVerification::Unsigned;
+    };
+    let Ok(signature) = hex::decode(signature_line[SIGNATURE_PREFIX.len()..].trim()) else {
+        return ExportVerification::Invalid;
+    };
+
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(content.as_bytes());
+
+    // verify_slice は定数時間で比較する
+    if mac.verify_slice(&signature).is_ok() {
+        ExportVerification::Valid
+    } else {
+        ExportVerification::Invalid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::models::RecordType;
+    use chrono::TimeZone;
+
+    const KEY: &[u8] = b"test-key";
+
+    #[test]
+    fn test_sign_and_verify_export() {
+        let signed = sign_export("date,start_time\n2023-12-15,09:00\n", KEY);
+
+        assert!(signed.starts_with("date,start_time\n2023-12-15,09:00\n"));
+        assert!(signed.contains(SIGNATURE_PREFIX));
+        assert_eq!(verify_export(&signed, KEY), ExportVerification::Valid);
+    }
+
+    #[test]
+    fn test_verify_export_detects_modification() {
+        let signed = sign_export("date,start_time\n2023-12-15,09:00\n", KEY);
+        let tampered = signed.replace("09:00", "08:00");
+
+        assert_eq!(verify_export(&tampered, KEY), ExportVerification::Invalid);
+        assert_eq!(
+            verify_export(&signed, b"other-key"),
+            ExportVerification::Invalid
+        );
+    }
+
+    #[test]
+    fn test_diff_sessions() {
+        let previous = sign_export(
+            "date,start_time,end_time,total_minutes,is_completed\n\
+             2023-12-15,09:00,17:00,480,true\n\
+             2023-12-16,09:00,,,false\n\
+             2023-12-17,10:00,12:00,120,true\n",
+            KEY,
+        );
+        let current = "date,start_time,end_time,total_minutes,is_completed\n\
+                       2023-12-15,09:00,17:00,480,true\n\
+                       2023-12-16,09:00,18:00,540,true\n\
+                       2023-12-18,13:00,15:00,120,true\n";
+
+        let diff = diff_sessions(
+            &parse_sessions_csv(&previous).unwrap(),
+            &parse_sessions_csv(current).unwrap(),
+        );
+
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].date, "2023-12-18");
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].0.end_time, "");
+        assert_eq!(diff.changed[0].1.end_time, "18:00");
+        assert_eq!(diff.deleted.len(), 1);
+        assert_eq!(diff.deleted[0].date, "2023-12-17");
+
+        assert!(parse_sessions_csv("username,discord_id\n").is_err());
+    }
+
+    #[test]
+    fn test_format_personal_data_json() {
+        let timestamp = DateTime::parse_from_rfc3339("2023-12-15T00:00:00Z")
+            .unwrap()
+            .to_utc();
+        let user = User {
+            id: 1,
+            discord_id: "1234".to_string(),
+            guild_id: String::new(),
+            username: "taro".to_string(),
+            created_at: timestamp,
+        };
+        let records = vec![AttendanceRecord {
+            id: 10,
+            user_id: 1,
+            record_type: RecordType::Start,
+            timestamp,
+            is_modified: false,
+            original_timestamp: None,
+            is_imported: false,
+            source: None,
+            locked: false,
+            session_id: None,
+            created_at: timestamp,
+            updated_at: timestamp,
+        }];
+        let sessions = vec![WorkSession {
+            id: 20,
+            user_id: 1,
+            start_time: timestamp,
+            end_time: None,
+            total_minutes: None,
+            date: timestamp.date_naive(),
+            is_completed: false,
+            note: Some("メモ".to_string()),
+            created_at: timestamp,
+            updated_at: timestamp,
+        }];
+
+        let json = format_personal_data_json(&PersonalDataExport {
+            exported_at: timestamp,
+            user: &user,
+            attendance_records: &records,
+            work_sessions: &sessions,
+            time_adjustments: &[],
+        })
+        .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["user"]["discord_id"], "1234");
+        assert_eq!(value["attendance_records"][0]["record_type"], "start");
+        assert_eq!(value["work_sessions"][0]["date"], "2023-12-15");
+        assert_eq!(value["work_sessions"][0]["note"], "メモ");
+    }
+
+    #[test]
+    fn test_payroll_template_adds_duration_and_break_columns() {
+        let jst = |hour: u32, minute: u32| {
+            chrono::FixedOffset::east_opt(9 * 3600)
+                .unwrap()
+                .with_ymd_and_hms(2023, 12, 15, hour, minute, 0)
+                .unwrap()
+                .to_utc()
+        };
+        let session = |id: i64, start: DateTime<Utc>, end: DateTime<Utc>| WorkSession {
+            id,
+            user_id: 1,
+            start_time: start,
+            end_time: Some(end),
+            total_minutes: Some((end - start).num_minutes() as i32),
+            date: chrono::NaiveDate::from_ymd_opt(2023, 12, 15).unwrap(),
+            is_completed: true,
+            note: None,
+            created_at: start,
+            updated_at: start,
+        };
+        let sessions = vec![
+            session(1, jst(9, 0), jst(12, 0)),
+            session(2, jst(12, 45), jst(18, 15)),
+        ];
+
+        let csv = format_sessions_csv(&sessions, ExportTemplate::Payroll, None);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "date,start_time,end_time,total_minutes,is_completed,duration_hhmm,duration_hours,break_minutes,break_hhmm,break_hours"
+        );
+        assert_eq!(lines[1], "2023-12-15,09:00,12:00,180,true,3:00,3,,,");
+        assert_eq!(
+            lines[2],
+            "2023-12-15,12:45,18:15,330,true,5:30,5.5,45,0:45,0.75"
+        );
+
+        // 追加列があっても差分の比較に使える
+        assert_eq!(
+            parse_sessions_csv(&csv).unwrap(),
+            parse_sessions_csv(&format_sessions_csv(
+                &sessions,
+                ExportTemplate::Standard,
+                None
+            ))
+            .unwrap()
+        );
+
+        // 日報付きテンプレートはメモを引用符付きで出力する
+        let mut noted = sessions.clone();
+        noted[1].note = Some("資料作成, \"A社\"\nレビュー".to_string());
+        let report = format_sessions_csv(&noted, ExportTemplate::DailyReport, None);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(
+            lines[0],
+            "date,start_time,end_time,total_minutes,is_completed,note"
+        );
+        assert_eq!(lines[1], "2023-12-15,09:00,12:00,180,true,");
+        assert_eq!(
+            lines[2],
+            "2023-12-15,12:45,18:15,330,true,\"資料作成, \"\"A社\"\" レビュー\""
+        );
+        assert_eq!(
+            parse_sessions_csv(&report).unwrap(),
+            parse_sessions_csv(&csv).unwrap()
+        );
+
+        // 月間上限（400分）を超えた分は最後の列に出力する
+        let capped = format_sessions_csv(&sessions, ExportTemplate::Standard, Some(400));
+        let lines: Vec<&str> = capped.lines().collect();
+        assert_eq!(
+            lines[0],
+            "date,start_time,end_time,total_minutes,is_completed,over_cap_minutes"
+        );
+        assert_eq!(lines[1], "2023-12-15,09:00,12:00,180,true,0");
+        assert_eq!(lines[2], "2023-12-15,12:45,18:15,330,true,110");
+        assert_eq!(
+            parse_sessions_csv(&capped).unwrap(),
+            parse_sessions_csv(&csv).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_verify_export_unsigned() {
+        assert_eq!(
+            verify_export("date,start_time\n", KEY),
+            ExportVerification::Unsigned
+        );
+    }
+}
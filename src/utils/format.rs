@@ -1,95 +1,248 @@
-use crate::database::models::{AttendanceRecord, WorkSession};
-use crate::utils::time::{format_duration_minutes, format_time_jst};
-use chrono::{DateTime, Utc};
+use crate::database::models::{
+    AttendanceRecord, AuditLog, CommandAuditLog, FocusSession, PendingChange, Rate, RecordRevision,
+    RecordSource, RecordType, Shift, ShiftTemplate, TimeAdjustment, TimesheetConfirmation, User,
+    WorkSchedule, WorkSession,
+};
+use crate::t;
+use crate::utils::breaks::BreakDeduction;
+use crate::utils::data_quality::Anomaly;
+use crate::utils::export::{ExportDiff, ExportRow};
+use crate::utils::i18n::Lang;
+use crate::utils::overtime::OvertimeSplit;
+use crate::utils::record_validator::ValidationError;
+use crate::utils::schedule::{LateStart, WEEKDAY_NAMES, match_shift_records, match_shift_sessions};
+use crate::utils::stats::{
+    CapLevel, MemberMonthSummary, MonthForecast, PayEstimate, SessionStats, WorkPatternStats,
+};
+use crate::utils::time::{
+    TimeDisplay, format_datetime_jst, format_duration, format_duration_minutes, format_time_jst,
+    get_date_from_utc_timestamp, get_month_range,
+};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use poise::serenity_prelude as serenity;
 
-pub fn format_attendance_status(records: &[AttendanceRecord]) -> String {
+pub fn format_attendance_status(
+    records: &[AttendanceRecord],
+    lang: Lang,
+    clock: TimeDisplay,
+) -> String {
     if records.is_empty() {
-        return "今日はまだ勤務記録がありません".to_string();
+        return t!(lang, "status.no_records");
     }
 
+    let duration = |minutes: i32| format_duration(minutes, lang.duration_format());
     let mut status = String::new();
+    let mut break_start: Option<DateTime<Utc>> = None;
+    // 今のセッション中に記録された休憩（勤務時間から差し引く）
+    let mut break_minutes = 0i32;
     let mut start_time: Option<DateTime<Utc>> = None;
     let mut total_minutes = 0i32;
     let mut session_count = 0;
 
-    status.push_str("**本日の勤務記録:**\n");
+    status.push_str(&t!(lang, "status.heading"));
+    status.push('\n');
 
     for record in records {
-        match record.record_type.as_str() {
-            "start" => {
+        match record.record_type {
+            RecordType::Start => {
                 if start_time.is_some() {
                     // 前のセッションが未終了
-                    status.push_str("  ⚠️ 前回の終了記録なし\n");
+                    status.push_str(&t!(lang, "status.missing_end"));
+                    status.push('\n');
                 }
                 session_count += 1;
-                status.push_str(&format!(
-                    "#{} 🟢 **開始**: {} {}\n",
-                    session_count,
-                    format_time_jst(record.timestamp),
-                    if record.is_modified {
-                        "(修正済み)"
-                    } else {
-                        ""
-                    }
+                status.push_str(&t!(
+                    lang,
+                    "status.start",
+                    n = session_count,
+                    time = record_time(record, clock),
+                    marker = record_marker(record, lang)
                 ));
+                status.push('\n');
                 start_time = Some(record.timestamp);
+                break_start = None;
+                break_minutes = 0;
             }
-            "end" => {
-                status.push_str(&format!(
-                    "#{} 🔴 **終了**: {} {}\n",
-                    session_count,
-                    format_time_jst(record.timestamp),
-                    if record.is_modified {
-                        "(修正済み)"
-                    } else {
-                        ""
-                    }
+            RecordType::End => {
+                status.push_str(&t!(
+                    lang,
+                    "status.end",
+                    n = session_count,
+                    time = record_time(record, clock),
+                    marker = record_marker(record, lang)
                 ));
+                status.push('\n');
 
                 if let Some(start) = start_time {
-                    let duration =
-                        record.timestamp.signed_duration_since(start).num_minutes() as i32;
-                    total_minutes += duration;
-                    status.push_str(&format!(
-                        "#{} ⏱️ 勤務時間: {}\n",
-                        session_count,
-                        format_duration_minutes(duration)
+                    // 休憩を終えずに終了した場合は終了までを休憩とする
+                    if let Some(break_started) = break_start {
+                        break_minutes += record
+                            .timestamp
+                            .signed_duration_since(break_started)
+                            .num_minutes() as i32;
+                    }
+                    let minutes = (record.timestamp.signed_duration_since(start).num_minutes()
+                        as i32
+                        - break_minutes)
+                        .max(0);
+                    total_minutes += minutes;
+                    status.push_str(&t!(
+                        lang,
+                        "status.duration",
+                        n = session_count,
+                        duration = duration(minutes)
                     ));
                 } else {
-                    status.push_str(&format!("#{} ⚠️ 対応する開始記録なし\n", session_count));
+                    status.push_str(&t!(lang, "status.end_without_start", n = session_count));
                 }
+                status.push('\n');
                 start_time = None;
+                break_start = None;
+                break_minutes = 0;
+                status.push('\n');
+            }
+            RecordType::BreakStart => {
+                status.push_str(&t!(
+                    lang,
+                    "status.break_start",
+                    time = record_time(record, clock),
+                    marker = record_marker(record, lang)
+                ));
+                status.push('\n');
+                break_start = Some(record.timestamp);
+            }
+            RecordType::BreakEnd => {
+                status.push_str(&t!(
+                    lang,
+                    "status.break_end",
+                    time = record_time(record, clock),
+                    marker = record_marker(record, lang)
+                ));
+                status.push('\n');
+                if let Some(start) = break_start.take() {
+                    let minutes =
+                        record.timestamp.signed_duration_since(start).num_minutes() as i32;
+                    break_minutes += minutes;
+                    status.push_str(&t!(
+                        lang,
+                        "status.break_duration",
+                        duration = duration(minutes)
+                    ));
+                    status.push('\n');
+                }
+            }
+            RecordType::Away => {
+                status.push_str(&t!(
+                    lang,
+                    "status.away",
+                    time = record_time(record, clock),
+                    marker = record_marker(record, lang)
+                ));
                 status.push('\n');
             }
-            _ => {}
         }
     }
 
     // If still working
     if start_time.is_some() {
-        status.push_str(&format!("#{} ⚠️ **現在勤務中**\n\n", session_count));
+        status.push_str(&t!(lang, "status.working", n = session_count));
+        status.push_str("\n\n");
     }
 
     if total_minutes > 0 {
-        status.push_str(&format!(
-            "📊 **本日の合計勤務時間**: {}",
-            format_duration_minutes(total_minutes)
+        status.push_str(&t!(
+            lang,
+            "status.total",
+            duration = duration(total_minutes)
         ));
     }
 
     if session_count > 1 {
-        status.push_str(&format!("\n🔄 **セッション数**: {}", session_count));
+        status.push('\n');
+        status.push_str(&t!(lang, "status.sessions", count = session_count));
     }
 
     status
 }
 
-pub fn format_work_sessions_summary(sessions: &[WorkSession]) -> String {
+/// 記録の時刻（修正された記録は元の時刻からの変化を "09:00 → 09:30" のように表示）
+fn record_time(record: &AttendanceRecord, clock: TimeDisplay) -> String {
+    match record.original_timestamp {
+        Some(original) if record.is_modified && original != record.timestamp => format!(
+            "{} → {}",
+            clock.time(original),
+            clock.time(record.timestamp)
+        ),
+        _ => clock.time(record.timestamp),
+    }
+}
+
+fn record_marker(record: &AttendanceRecord, lang: Lang) -> String {
+    let state = if record.locked {
+        t!(lang, "record.locked")
+    } else if record.is_modified {
+        t!(lang, "record.modified")
+    } else if record.is_imported {
+        t!(lang, "record.imported")
+    } else {
+        String::new()
+    };
+
+    // コマンド以外（ボタン・フォーム入力・API・管理者）で追加された記録は経路も表示する
+    match record.source.as_deref().and_then(RecordSource::parse) {
+        Some(
+            source @ (RecordSource::Button
+            | RecordSource::Modal
+            | RecordSource::Api
+            | RecordSource::Admin),
+        ) => {
+            let via = t!(
+                lang,
+                "record.via",
+                source = record_source_label(source, lang)
+            );
+            if state.is_empty() {
+                via
+            } else {
+                format!("{} {}", state, via)
+            }
+        }
+        _ => state,
+    }
+}
+
+/// 記録を追加した経路の表示名
+pub fn record_type_label(record_type: RecordType, lang: Lang) -> String {
+    match record_type {
+        RecordType::Start => t!(lang, "record.start"),
+        RecordType::End => t!(lang, "record.end"),
+        RecordType::BreakStart => t!(lang, "record.break_start"),
+        RecordType::BreakEnd => t!(lang, "record.break_end"),
+        RecordType::Away => t!(lang, "record.away"),
+    }
+}
+
+pub fn record_source_label(source: RecordSource, lang: Lang) -> String {
+    match source {
+        RecordSource::SlashCommand => t!(lang, "record.source.slash_command"),
+        RecordSource::Button => t!(lang, "record.source.button"),
+        RecordSource::Modal => t!(lang, "record.source.modal"),
+        RecordSource::Api => t!(lang, "record.source.api"),
+        RecordSource::Import => t!(lang, "record.source.import"),
+        RecordSource::Admin => t!(lang, "record.source.admin"),
+    }
+}
+
+pub fn format_work_sessions_summary(
+    sessions: &[WorkSession],
+    lang: Lang,
+    clock: TimeDisplay,
+) -> String {
     if sessions.is_empty() {
-        return "指定期間に勤務記録がありません".to_string();
+        return t!(lang, "report.no_sessions");
     }
 
+    let duration = |minutes: i32| format_duration(minutes, lang.duration_format());
     let mut summary = String::new();
     let mut total_minutes = 0i32;
     let mut current_date: Option<chrono::NaiveDate> = None;
@@ -101,11 +254,13 @@ pub fn format_work_sessions_summary(sessions: &[WorkSession]) -> String {
             // 前の日の合計を表示
             if let Some(prev_date) = current_date {
                 if daily_minutes > 0 {
-                    summary.push_str(&format!(
-                        "   📊 **{}合計**: {}\n\n",
-                        prev_date.format("%m/%d"),
-                        format_duration_minutes(daily_minutes)
+                    summary.push_str(&t!(
+                        lang,
+                        "report.day_total",
+                        date = prev_date.format("%m/%d"),
+                        duration = duration(daily_minutes)
                     ));
+                    summary.push_str("\n\n");
                 }
             }
 
@@ -118,48 +273,1175 @@ pub fn format_work_sessions_summary(sessions: &[WorkSession]) -> String {
             ));
         }
 
-        summary.push_str(&format!(
-            "   🟢 開始: {}",
-            format_time_jst(session.start_time)
+        summary.push_str(&t!(
+            lang,
+            "report.start",
+            time = clock.time(session.start_time)
         ));
 
         if let Some(end_time) = session.end_time {
-            summary.push_str(&format!(" → 🔴 終了: {}", format_time_jst(end_time)));
+            summary.push_str(&t!(lang, "report.end", time = clock.time(end_time)));
 
             if let Some(minutes) = session.total_minutes {
-                summary.push_str(&format!(" ({})", format_duration_minutes(minutes)));
+                summary.push_str(&format!(" ({})", duration(minutes)));
                 total_minutes += minutes;
                 daily_minutes += minutes;
             }
-            summary.push('\n');
         } else {
-            summary.push_str(" → ⚠️ **未終了**\n");
+            summary.push_str(&t!(lang, "report.not_ended"));
+        }
+        summary.push('\n');
+
+        if let Some(note) = &session.note {
+            summary.push_str(&format!("      📝 {}\n", note));
         }
     }
 
     // 最後の日の合計を表示
     if let Some(last_date) = current_date {
         if daily_minutes > 0 {
-            summary.push_str(&format!(
-                "   📊 **{}合計**: {}\n\n",
-                last_date.format("%m/%d"),
-                format_duration_minutes(daily_minutes)
+            summary.push_str(&t!(
+                lang,
+                "report.day_total",
+                date = last_date.format("%m/%d"),
+                duration = duration(daily_minutes)
             ));
+            summary.push_str("\n\n");
         }
     }
 
     if total_minutes > 0 {
+        summary.push_str(&t!(
+            lang,
+            "report.total",
+            duration = duration(total_minutes)
+        ));
+    }
+
+    summary
+}
+
+pub fn format_weekly_digest(members: &[(User, Vec<WorkSession>)]) -> String {
+    if members.is_empty() {
+        return "先週の勤務記録はありません".to_string();
+    }
+
+    let mut digest = String::new();
+    let mut guild_total_minutes = 0i32;
+
+    for (user, sessions) in members {
+        let total_minutes: i32 = sessions.iter().filter_map(|s| s.total_minutes).sum();
+        let incomplete_count = sessions.iter().filter(|s| !s.is_completed).count();
+        guild_total_minutes += total_minutes;
+
+        digest.push_str(&format!(
+            "👤 **{}**: {} (セッション数: {})",
+            user.username,
+            format_duration_minutes(total_minutes),
+            sessions.len()
+        ));
+        if incomplete_count > 0 {
+            digest.push_str(&format!(" ⚠️ 未終了: {}", incomplete_count));
+        }
+        digest.push('\n');
+    }
+
+    digest.push_str(&format!(
+        "\n🎯 **全体合計**: {} ({}人)",
+        format_duration_minutes(guild_total_minutes),
+        members.len()
+    ));
+
+    digest
+}
+
+/// 朝会用のまとめ。各メンバーの今日の出勤状況と、前日の勤務時間・メモ
+///
+/// `members` は (ユーザー, 前日のセッション, 今日の打刻記録)。勤務中・退勤済み・未出勤の順に並べる
+pub fn format_standup(members: &[(User, Vec<WorkSession>, Vec<AttendanceRecord>)]) -> String {
+    if members.is_empty() {
+        return "対象のメンバーがいません".to_string();
+    }
+
+    let mut entries: Vec<_> = members
+        .iter()
+        .map(|(user, yesterday, today)| {
+            let last = today
+                .iter()
+                .filter(|record| record.record_type.is_session_boundary())
+                .max_by_key(|record| record.timestamp);
+            let (order, status) = match last {
+                Some(record) if record.record_type == RecordType::Start => {
+                    let first = today.iter().map(|record| record.timestamp).min();
+                    (
+                        0,
+                        format!(
+                            "🟢 勤務中（{}〜）",
+                            first.map(format_time_jst).unwrap_or_default()
+                        ),
+                    )
+                }
+                Some(record) => (
+                    1,
+                    format!("⚪ 退勤済み（{}）", format_time_jst(record.timestamp)),
+                ),
+                None => (2, "➖ 未出勤".to_string()),
+            };
+
+            let total_minutes: i32 = yesterday.iter().filter_map(|s| s.total_minutes).sum();
+            let mut summary = if yesterday.is_empty() {
+                "休み".to_string()
+            } else {
+                format_duration_minutes(total_minutes)
+            };
+            let notes: Vec<&str> = yesterday.iter().filter_map(|s| s.note.as_deref()).collect();
+            if !notes.is_empty() {
+                summary.push_str(&format!(" 📝 {}", notes.join(" / ")));
+            }
+
+            (
+                order,
+                user.username.as_str(),
+                format!("<@{}> {}\n　前日: {}", user.discord_id, status, summary),
+            )
+        })
+        .collect();
+    entries.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+
+    let working = entries.iter().filter(|(order, _, _)| *order == 0).count();
+    let mut text = entries
+        .into_iter()
+        .map(|(_, _, entry)| entry)
+        .collect::<Vec<_>>()
+        .join("\n");
+    text.push_str(&format!(
+        "\n\n🟢 勤務中: {}人 / {}人",
+        working,
+        members.len()
+    ));
+    text
+}
+
+/// ダッシュボードの本文。勤務中のメンバーと今日の勤務時間
+///
+/// `members` は (ユーザー, 前日以降のセッション)。日付をまたいで勤務中のメンバーも勤務中に含め、
+/// 今日の勤務時間には今日のセッション（勤務中なら `now` までの経過時間）を数える
+pub fn format_dashboard(
+    members: &[(User, Vec<WorkSession>)],
+    today: NaiveDate,
+    now: DateTime<Utc>,
+) -> String {
+    let mut working = Vec::new();
+    let mut totals = Vec::new();
+    for (user, sessions) in members {
+        if let Some(active) = sessions
+            .iter()
+            .filter(|session| !session.is_completed)
+            .max_by_key(|session| session.start_time)
+        {
+            working.push((active.start_time, user));
+        }
+
+        let minutes: i32 = sessions
+            .iter()
+            .filter(|session| session.date == today)
+            .map(|session| {
+                if session.is_completed {
+                    session.total_minutes.unwrap_or(0)
+                } else {
+                    (now - session.start_time).num_minutes().max(0) as i32
+                }
+            })
+            .sum();
+        if minutes > 0 {
+            totals.push((minutes, user));
+        }
+    }
+    working.sort_by(|a, b| (a.0, &a.1.username).cmp(&(b.0, &b.1.username)));
+    totals.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.username.cmp(&b.1.username)));
+
+    let mut text = format!("🟢 **勤務中（{}人）**\n", working.len());
+    if working.is_empty() {
+        text.push_str("勤務中のメンバーはいません\n");
+    }
+    for (start_time, user) in &working {
+        text.push_str(&format!(
+            "<@{}> {}〜\n",
+            user.discord_id,
+            format_time_jst(*start_time)
+        ));
+    }
+
+    text.push_str("\n⏱️ **今日の勤務時間**\n");
+    if totals.is_empty() {
+        text.push_str("今日の勤務記録はまだありません");
+        return text;
+    }
+    for (minutes, user) in &totals {
+        text.push_str(&format!(
+            "<@{}> {}\n",
+            user.discord_id,
+            format_duration_minutes(*minutes)
+        ));
+    }
+    let total: i32 = totals.iter().map(|(minutes, _)| minutes).sum();
+    text.push_str(&format!("合計: {}", format_duration_minutes(total)));
+    text
+}
+
+/// 勤務中のメンバーの一覧（開始時刻と経過時間）。同じメンバーの未完了のセッションは最新のみ表示
+pub fn format_working_members(
+    active: &[(User, WorkSession)],
+    today: NaiveDate,
+    now: DateTime<Utc>,
+) -> String {
+    let mut latest: Vec<&(User, WorkSession)> = Vec::new();
+    for entry in active {
+        match latest.iter_mut().find(|(user, _)| user.id == entry.0.id) {
+            Some(existing) if existing.1.start_time < entry.1.start_time => *existing = entry,
+            Some(_) => {}
+            None => latest.push(entry),
+        }
+    }
+    if latest.is_empty() {
+        return "勤務中のメンバーはいません".to_string();
+    }
+    latest.sort_by(|a, b| (a.1.start_time, &a.0.username).cmp(&(b.1.start_time, &b.0.username)));
+
+    let mut text = String::new();
+    for (user, session) in &latest {
+        // 日付をまたいで勤務している場合は開始日も表示する
+        let started = if session.date == today {
+            format_time_jst(session.start_time)
+        } else {
+            format!(
+                "{} {}",
+                session.date.format("%m/%d"),
+                format_time_jst(session.start_time)
+            )
+        };
+        let elapsed = (now - session.start_time).num_minutes().max(0) as i32;
+        text.push_str(&format!(
+            "<@{}> {}〜（{}）\n",
+            user.discord_id,
+            started,
+            format_duration_minutes(elapsed)
+        ));
+    }
+    text.push_str(&format!("\n合計: {}人", latest.len()));
+    text
+}
+
+pub fn format_team_report(members: &[(User, Vec<WorkSession>)]) -> String {
+    if members.is_empty() {
+        return "対象ロールのメンバーがいません".to_string();
+    }
+
+    let mut report = String::new();
+    let mut team_total_minutes = 0i32;
+
+    for (user, sessions) in members {
+        let stats = SessionStats::from_sessions(sessions);
+        team_total_minutes += stats.total_minutes;
+
+        report.push_str(&format!(
+            "👤 **{}**: {} ({}日 / 1日平均 {})",
+            user.username,
+            format_duration_minutes(stats.total_minutes),
+            stats.working_days,
+            format_duration_minutes(stats.average_minutes_per_day())
+        ));
+        if stats.incomplete_count > 0 {
+            report.push_str(&format!(" ⚠️ 未終了: {}", stats.incomplete_count));
+        }
+        report.push('\n');
+    }
+
+    report.push_str(&format!(
+        "\n🎯 **チーム合計**: {} ({}人)\n📊 **1人あたり平均**: {}",
+        format_duration_minutes(team_total_minutes),
+        members.len(),
+        format_duration_minutes(team_total_minutes / members.len() as i32)
+    ));
+
+    report
+}
+
+/// `/admin monthly` のメンバーごとの合計・時間外・未終了セッション
+pub fn format_guild_monthly(summaries: &[MemberMonthSummary]) -> String {
+    if summaries.is_empty() {
+        return "この月の勤務記録はありません".to_string();
+    }
+
+    let mut report = String::new();
+    for summary in summaries {
+        report.push_str(&format!(
+            "👤 **{}**: {} ({}日 / 時間外 {})",
+            summary.user.username,
+            format_duration_minutes(summary.total_minutes),
+            summary.working_days,
+            format_duration_minutes(summary.overtime_minutes)
+        ));
+        if summary.incomplete_sessions > 0 {
+            report.push_str(&format!(" ⚠️ 未終了: {}", summary.incomplete_sessions));
+        }
+        report.push('\n');
+    }
+
+    let total_minutes: i32 = summaries.iter().map(|s| s.total_minutes).sum();
+    let overtime_minutes: i32 = summaries.iter().map(|s| s.overtime_minutes).sum();
+    let incomplete_sessions: i32 = summaries.iter().map(|s| s.incomplete_sessions).sum();
+    report.push_str(&format!(
+        "\n🎯 **サーバー合計**: {} ({}人)\n⏰ **時間外合計**: {}",
+        format_duration_minutes(total_minutes),
+        summaries.len(),
+        format_duration_minutes(overtime_minutes)
+    ));
+    if incomplete_sessions > 0 {
+        report.push_str(&format!(
+            "\n⚠️ **未終了のセッション**: {}件",
+            incomplete_sessions
+        ));
+    }
+
+    report
+}
+
+/// 表示する上位の人数
+const LEADERBOARD_SIZE: usize = 10;
+
+pub fn format_leaderboard(ranking: &[(User, i64)], caller_discord_id: &str) -> String {
+    if ranking.is_empty() {
+        return "今月の勤務記録はまだありません".to_string();
+    }
+
+    let mut leaderboard = String::new();
+    for (index, (user, total_minutes)) in ranking.iter().take(LEADERBOARD_SIZE).enumerate() {
+        let rank = match index {
+            0 => "🥇".to_string(),
+            1 => "🥈".to_string(),
+            2 => "🥉".to_string(),
+            _ => format!("{}.", index + 1),
+        };
+        leaderboard.push_str(&format!(
+            "{} **{}**: {}\n",
+            rank,
+            user.username,
+            format_duration_minutes(*total_minutes as i32)
+        ));
+    }
+
+    match ranking
+        .iter()
+        .position(|(user, _)| user.discord_id == caller_discord_id)
+    {
+        Some(index) => leaderboard.push_str(&format!(
+            "\n👤 **あなたの順位**: {}位 / {}人 ({})",
+            index + 1,
+            ranking.len(),
+            format_duration_minutes(ranking[index].1 as i32)
+        )),
+        None => leaderboard.push_str("\n👤 **あなたの順位**: 今月の勤務記録がありません"),
+    }
+
+    leaderboard
+}
+
+pub fn format_overtime_split(split: &OvertimeSplit) -> String {
+    format!(
+        "🕘 通常: {}\n⏰ 時間外: {}",
+        format_duration_minutes(split.regular_minutes),
+        format_duration_minutes(split.overtime_minutes)
+    )
+}
+
+/// プロジェクト別の勤務時間（プロジェクト未設定の分は「未設定」）
+pub fn format_project_breakdown(projects: &[(Option<String>, i64)]) -> String {
+    projects
+        .iter()
+        .map(|(name, minutes)| {
+            format!(
+                "📁 **{}**: {}",
+                name.as_deref().unwrap_or("未設定"),
+                format_duration_minutes(*minutes as i32)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 終了した集中時間の回数と合計（中断した回があればその回数も）
+pub fn format_focus_summary(focus_sessions: &[FocusSession]) -> String {
+    let finished: Vec<(&FocusSession, i64)> = focus_sessions
+        .iter()
+        .filter_map(|focus| {
+            let ended_at = focus.ended_at?;
+            Some((focus, (ended_at - focus.started_at).num_minutes()))
+        })
+        .collect();
+    let total_minutes: i64 = finished.iter().map(|(_, minutes)| minutes).sum();
+    let interrupted = finished
+        .iter()
+        .filter(|(focus, _)| !focus.completed)
+        .count();
+
+    let mut summary = format!(
+        "🍅 {}回・合計 {}",
+        finished.len(),
+        format_duration_minutes(total_minutes as i32)
+    );
+    if interrupted > 0 {
+        summary.push_str(&format!("（うち中断 {}回）", interrupted));
+    }
+    summary
+}
+
+/// 前日の要確認項目をユーザーごとに一覧にする
+pub fn format_quality_digest(members: &[(User, Vec<Anomaly>)]) -> String {
+    if members.is_empty() {
+        return "✅ 昨日の記録に要確認の項目はありません".to_string();
+    }
+
+    let mut digest = String::new();
+    for (user, anomalies) in members {
+        digest.push_str(&format!(
+            "👤 **{}** (<@{}>)\n",
+            user.username, user.discord_id
+        ));
+        for anomaly in anomalies {
+            let line = match anomaly {
+                Anomaly::OpenSession { start } => {
+                    format!("⚠️ 未終了のセッション（開始 {}）", format_time_jst(*start))
+                }
+                Anomaly::UnpairedEnd { end } => {
+                    format!("⚠️ 開始記録の無い終了（{}）", format_time_jst(*end))
+                }
+                Anomaly::LongDay { minutes } => {
+                    format!("⏰ 長時間勤務（{}）", format_duration_minutes(*minutes))
+                }
+                Anomaly::Modified { count } => format!("✏️ 修正された記録 {}件", count),
+            };
+            digest.push_str(&format!("   {}\n", line));
+        }
+    }
+    digest.push_str("\n下のボタンから本人が記録を確認・修正できます");
+    digest
+}
+
+/// 記録バリデーションエラーの種類別件数（件数の多い順に渡す）
+pub fn format_validation_rejections(rejections: &[(String, i64)]) -> String {
+    if rejections.is_empty() {
+        return "記録のバリデーションエラーはまだありません".to_string();
+    }
+
+    let total: i64 = rejections.iter().map(|(_, count)| count).sum();
+    let mut lines: Vec<String> = rejections
+        .iter()
+        .map(|(kind, count)| {
+            format!(
+                "• **{}**: {}件 ({}%)",
+                ValidationError::kind_label(kind),
+                count,
+                count * 100 / total
+            )
+        })
+        .collect();
+    lines.push(format!("\n合計: {}件", total));
+    lines.join("\n")
+}
+
+/// 金額を3桁区切りの円表記にする
+pub fn format_yen(amount: i64) -> String {
+    let digits = amount.unsigned_abs().to_string();
+    let mut grouped = String::new();
+    for (i, ch) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(ch);
+    }
+    let sign = if amount < 0 { "-" } else { "" };
+    format!("{}¥{}", sign, grouped)
+}
+
+pub fn format_pay_estimate(estimate: &PayEstimate, rate: &Rate) -> String {
+    format!(
+        "🕘 通常: {} × {}/時 = {}\n\
+         ⏰ 時間外: {} × {}/時 = {}\n\
+         💴 **合計: {}**",
+        format_duration_minutes(estimate.regular_minutes),
+        format_yen(rate.hourly_rate),
+        format_yen(estimate.regular_pay),
+        format_duration_minutes(estimate.overtime_minutes),
+        format_yen(rate.overtime_rate),
+        format_yen(estimate.overtime_pay),
+        format_yen(estimate.total_pay())
+    )
+}
+
+pub fn format_forecast(forecast: &MonthForecast) -> String {
+    let mut text = format!(
+        "📊 ここまで: {}（{}営業日・1日平均 {}）\n\
+         🔮 月末の見込み: **{}**（残り{}営業日）\n\
+         🎯 目標: {}\n",
+        format_duration_minutes(forecast.worked_minutes),
+        forecast.elapsed_days,
+        format_duration_minutes(forecast.average_minutes_per_day()),
+        format_duration_minutes(forecast.projected_minutes()),
+        forecast.remaining_days,
+        format_duration_minutes(forecast.target_minutes)
+    );
+
+    let difference = forecast.projected_minutes() - forecast.target_minutes;
+    if difference >= 0 {
+        text.push_str(&format!(
+            "✅ 目標を {} 上回る見込みです",
+            format_duration_minutes(difference)
+        ));
+    } else {
+        text.push_str(&format!(
+            "⚠️ 目標に {} 届かない見込みです",
+            format_duration_minutes(-difference)
+        ));
+    }
+    if forecast.worked_minutes >= forecast.target_minutes {
+        text.push_str("\n目標時間に達しています");
+    } else if let Some(required) = forecast.required_minutes_per_day() {
+        text.push_str(&format!(
+            "\n目標に届くには残りの営業日に1日あたり {} 必要です",
+            format_duration_minutes(required)
+        ));
+    }
+    text
+}
+
+/// 月間上限に対する実績と見込み（`forecast` は上限を目標として計算したもの）
+pub fn format_hour_cap(forecast: &MonthForecast) -> String {
+    let cap = forecast.target_minutes;
+    let worked = if forecast.worked_minutes > cap {
+        format!(
+            "⛔ {}（超過 {}）",
+            format_duration_minutes(forecast.worked_minutes),
+            format_duration_minutes(forecast.worked_minutes - cap)
+        )
+    } else {
+        format!(
+            "{}（残り {}）",
+            format_duration_minutes(forecast.worked_minutes),
+            format_duration_minutes(cap - forecast.worked_minutes)
+        )
+    };
+    format!(
+        "上限: {}\n実績: {}\n月末の見込み: {}",
+        format_duration_minutes(cap),
+        worked,
+        format_duration_minutes(forecast.projected_minutes())
+    )
+}
+
+/// 調整した分数を符号付きで表示する（例: +30分、-1時間15分）
+pub fn format_adjustment_minutes(minutes: i32) -> String {
+    format!(
+        "{}{}",
+        if minutes < 0 { "-" } else { "+" },
+        format_duration_minutes(minutes.abs())
+    )
+}
+
+/// 勤務時間の調整の一覧と、セッションの合計に調整を加えた合計
+pub fn format_time_adjustments(adjustments: &[TimeAdjustment], session_minutes: i32) -> String {
+    const MAX_LINES: usize = 10;
+    let mut lines: Vec<String> = adjustments
+        .iter()
+        .take(MAX_LINES)
+        .map(|adjustment| {
+            let mut line = format!(
+                "{} **{}**",
+                adjustment.date.format("%m/%d"),
+                format_adjustment_minutes(adjustment.minutes)
+            );
+            if let Some(reason) = &adjustment.reason {
+                line.push_str(&format!(" {}", reason));
+            }
+            line
+        })
+        .collect();
+    if adjustments.len() > MAX_LINES {
+        lines.push(format!("…ほか {}件", adjustments.len() - MAX_LINES));
+    }
+    let adjustment_minutes: i32 = adjustments.iter().map(|a| a.minutes).sum();
+    lines.push(format!(
+        "打刻の合計 {} {} = **{}**",
+        format_duration_minutes(session_minutes),
+        format_adjustment_minutes(adjustment_minutes),
+        format_duration_minutes(session_minutes + adjustment_minutes)
+    ));
+    lines.join("\n")
+}
+
+/// 休憩の自動控除を日ごとに並べる（多い場合は先頭の10日と残りの日数）
+pub fn format_break_deductions(deductions: &[BreakDeduction]) -> String {
+    const MAX_LINES: usize = 10;
+    let mut lines: Vec<String> = deductions
+        .iter()
+        .take(MAX_LINES)
+        .map(|deduction| {
+            format!(
+                "{} 勤務 {}・休憩 {} → {} 控除",
+                deduction.date.format("%m/%d"),
+                format_duration_minutes(deduction.worked_minutes),
+                format_duration_minutes(deduction.recorded_break_minutes),
+                format_duration_minutes(deduction.deducted_minutes)
+            )
+        })
+        .collect();
+    if deductions.len() > MAX_LINES {
+        lines.push(format!("…ほか {}日", deductions.len() - MAX_LINES));
+    }
+    let total: i32 = deductions.iter().map(|d| d.deducted_minutes).sum();
+    lines.push(format!(
+        "合計 {} を勤務時間から差し引いています",
+        format_duration_minutes(total)
+    ));
+    lines.join("\n")
+}
+
+/// 週の目標に対する進捗（10マスのバーと残り時間）
+pub fn format_goal_progress(worked_minutes: i32, goal_minutes: i32, lang: Lang) -> String {
+    let percent = if goal_minutes > 0 {
+        worked_minutes * 100 / goal_minutes
+    } else {
+        100
+    };
+    let duration = |minutes: i32| format_duration(minutes, lang.duration_format());
+    let filled = (percent / 10).clamp(0, 10) as usize;
+    let bar = format!("{}{}", "▓".repeat(filled), "░".repeat(10 - filled));
+    let remaining = if worked_minutes >= goal_minutes {
+        t!(lang, "goal.achieved")
+    } else {
+        t!(
+            lang,
+            "goal.remaining",
+            duration = duration(goal_minutes - worked_minutes)
+        )
+    };
+    format!(
+        "{} {}%\n{} / {}（{}）",
+        bar,
+        percent,
+        duration(worked_minutes),
+        duration(goal_minutes),
+        remaining
+    )
+}
+
+/// 月間上限の警告文（上限内なら `None`）
+pub fn format_cap_warning(level: CapLevel, forecast: &MonthForecast) -> Option<String> {
+    let cap = format_duration_minutes(forecast.target_minutes);
+    match level {
+        CapLevel::Within => None,
+        CapLevel::Approaching => Some(format!(
+            "⚠️ 今月の勤務時間が上限（{}）に近づいています（実績 {}・月末の見込み {}）",
+            cap,
+            format_duration_minutes(forecast.worked_minutes),
+            format_duration_minutes(forecast.projected_minutes())
+        )),
+        CapLevel::Exceeded => Some(format!(
+            "⛔ 今月の勤務時間が上限（{}）を {} 超えています",
+            cap,
+            format_duration_minutes(forecast.worked_minutes - forecast.target_minutes)
+        )),
+    }
+}
+
+/// 曜日ごとの開始予定時刻の一覧
+pub fn format_work_schedules(schedules: &[WorkSchedule]) -> String {
+    if schedules.is_empty() {
+        return "開始予定時刻は登録されていません".to_string();
+    }
+    schedules
+        .iter()
+        .map(|schedule| {
+            format!(
+                "{}曜日: {:02}:{:02}",
+                WEEKDAY_NAMES[schedule.weekday as usize % 7],
+                schedule.start_minutes / 60,
+                schedule.start_minutes % 60
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 予定より遅く始めた日を並べる（多い場合は先頭の10日と残りの日数）
+pub fn format_late_starts(late_starts: &[LateStart]) -> String {
+    const MAX_LINES: usize = 10;
+    let clock = |minutes: i32| format!("{:02}:{:02}", minutes / 60, minutes % 60);
+    let mut lines: Vec<String> = late_starts
+        .iter()
+        .take(MAX_LINES)
+        .map(|late| {
+            format!(
+                "⚠️ {}（{}） {} 開始（予定 {}・{}遅れ）",
+                late.date.format("%m/%d"),
+                WEEKDAY_NAMES[late.date.weekday().num_days_from_monday() as usize],
+                clock(late.actual_minutes),
+                clock(late.expected_minutes),
+                format_duration_minutes(late.late_minutes())
+            )
+        })
+        .collect();
+    if late_starts.len() > MAX_LINES {
+        lines.push(format!("…ほか {}日", late_starts.len() - MAX_LINES));
+    }
+    lines.join("\n")
+}
+
+/// シフトの一覧（IDと日付・時間帯）
+pub fn format_shifts(shifts: &[Shift]) -> String {
+    if shifts.is_empty() {
+        return "予定しているシフトはありません".to_string();
+    }
+    shifts
+        .iter()
+        .map(|shift| {
+            let date = get_date_from_utc_timestamp(shift.start_time);
+            format!(
+                "`#{}` {}（{}） {}〜{}",
+                shift.id,
+                date.format("%m/%d"),
+                WEEKDAY_NAMES[date.weekday().num_days_from_monday() as usize],
+                format_time_jst(shift.start_time),
+                format_time_jst(shift.end_time)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// シフトの予定と実際の打刻を並べる（開始時刻を過ぎても出勤がないシフトは ⚠️）
+pub fn format_shift_comparison(
+    shifts: &[Shift],
+    records: &[AttendanceRecord],
+    now: DateTime<Utc>,
+    lang: Lang,
+    clock: TimeDisplay,
+) -> String {
+    shifts
+        .iter()
+        .map(|shift| {
+            let attendance = match_shift_records(shift, records);
+            let actual = match attendance.actual_start {
+                Some(start) => {
+                    let mut actual = t!(lang, "shift.actual_start", time = clock.time(start));
+                    if let Some(late) = attendance.late_minutes(shift) {
+                        actual.push_str(&t!(
+                            lang,
+                            "shift.late",
+                            duration = format_duration(late as i32, lang.duration_format())
+                        ));
+                    }
+                    if let Some(end) = attendance.actual_end {
+                        actual.push_str(&t!(lang, "shift.actual_end", time = clock.time(end)));
+                    }
+                    actual
+                }
+                None if now >= shift.start_time => t!(lang, "shift.no_clock_in"),
+                None => t!(lang, "shift.upcoming"),
+            };
+            format!(
+                "{}〜{} → {}",
+                clock.time(shift.start_time),
+                clock.time(shift.end_time),
+                actual
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// シフトのひな形の一覧（終了が開始より前なら「翌」を付ける）
+pub fn format_shift_templates(templates: &[ShiftTemplate]) -> String {
+    if templates.is_empty() {
+        return "シフトのひな形は登録されていません".to_string();
+    }
+    let clock = |minutes: i32| format!("{:02}:{:02}", minutes / 60, minutes % 60);
+    templates
+        .iter()
+        .map(|template| {
+            format!(
+                "{}: {}〜{}{}",
+                template.name,
+                clock(template.start_minutes),
+                if template.end_minutes <= template.start_minutes {
+                    "翌"
+                } else {
+                    ""
+                },
+                clock(template.end_minutes)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 期間内のシフトと実際の勤務の比較（件数の内訳と、遅れ・未出勤のシフト。多い場合は先頭の10件）
+pub fn format_shift_report(
+    shifts: &[Shift],
+    sessions: &[WorkSession],
+    now: DateTime<Utc>,
+    clock: TimeDisplay,
+) -> String {
+    const MAX_LINES: usize = 10;
+    let started: Vec<_> = shifts
+        .iter()
+        .filter(|shift| shift.start_time <= now)
+        .collect();
+    let mut attended = 0;
+    let mut late = 0;
+    let mut issues = Vec::new();
+    for shift in &started {
+        let attendance = match_shift_sessions(shift, sessions);
+        let date = get_date_from_utc_timestamp(shift.start_time);
+        let planned = format!(
+            "{}（{}） {}〜{}",
+            date.format("%m/%d"),
+            WEEKDAY_NAMES[date.weekday().num_days_from_monday() as usize],
+            clock.time(shift.start_time),
+            clock.time(shift.end_time)
+        );
+        match (attendance.actual_start, attendance.late_minutes(shift)) {
+            (Some(start), Some(minutes)) => {
+                attended += 1;
+                late += 1;
+                issues.push(format!(
+                    "⚠️ {} → {} 開始（{}遅れ）",
+                    planned,
+                    clock.time(start),
+                    format_duration_minutes(minutes as i32)
+                ));
+            }
+            (Some(_), None) => attended += 1,
+            (None, _) => issues.push(format!("⚠️ {} → 出勤なし", planned)),
+        }
+    }
+
+    let mut lines = vec![format!(
+        "予定 {}件: 出勤 {}件（うち遅れ {}件）・未出勤 {}件",
+        started.len(),
+        attended,
+        late,
+        started.len() - attended
+    )];
+    if shifts.len() > started.len() {
+        lines[0].push_str(&format!(
+            "（これからのシフト {}件）",
+            shifts.len() - started.len()
+        ));
+    }
+    let issue_count = issues.len();
+    lines.extend(issues.into_iter().take(MAX_LINES));
+    if issue_count > MAX_LINES {
+        lines.push(format!("…ほか {}件", issue_count - MAX_LINES));
+    }
+    lines.join("\n")
+}
+
+pub fn format_work_pattern_stats(stats: &WorkPatternStats) -> String {
+    if stats.aggregates.working_days == 0 {
+        return "この期間の完了した勤務はありません".to_string();
+    }
+    // 日付をまたいだ終了は 25:00 のように24時以降で表す
+    let clock = |minutes: Option<i32>| {
+        minutes
+            .map(|minutes| format!("{:02}:{:02}", minutes / 60, minutes % 60))
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    format!(
+        "📅 勤務日数: {}日（セッション {}件）\n\
+         🕘 平均開始時刻: {}\n\
+         🕕 平均終了時刻: {}\n\
+         ⏱️ 1日平均: {}\n\
+         🏆 最長セッション: {}\n\
+         ⏰ 遅い開始（{}より後）: {}日",
+        stats.aggregates.working_days,
+        stats.aggregates.session_count,
+        clock(stats.average_start_minutes),
+        clock(stats.average_end_minutes),
+        format_duration_minutes(stats.average_minutes_per_day()),
+        format_duration_minutes(stats.aggregates.longest_minutes as i32),
+        stats.late_after.format("%H:%M"),
+        stats.late_starts
+    )
+}
+
+/// 月のカレンダー（1行が月曜日〜日曜日の1週間）
+///
+/// 🟩 勤務日、🟨 未終了のセッションがある日、⬜ 休み、➖ `today` より後の日、⬛ 月の範囲外
+pub fn format_calendar(month: NaiveDate, sessions: &[WorkSession], today: NaiveDate) -> String {
+    let (start_of_month, end_of_month) = get_month_range(month);
+    let mut incomplete_dates = Vec::new();
+    let mut worked_days = 0;
+
+    let mut text = String::new();
+    let first_monday = start_of_month
+        - chrono::Duration::days(start_of_month.weekday().num_days_from_monday() as i64);
+    for week_start in first_monday
+        .iter_weeks()
+        .take_while(|date| *date <= end_of_month)
+    {
+        text.push_str(&format!(
+            "`{}` ",
+            week_start.max(start_of_month).format("%m/%d")
+        ));
+        for date in week_start.iter_days().take(7) {
+            let day_sessions: Vec<_> = sessions.iter().filter(|s| s.date == date).collect();
+            let cell = if date < start_of_month || date > end_of_month {
+                "⬛"
+            } else if day_sessions.iter().any(|s| !s.is_completed) {
+                incomplete_dates.push(date);
+                "🟨"
+            } else if !day_sessions.is_empty() {
+                worked_days += 1;
+                "🟩"
+            } else if date > today {
+                "➖"
+            } else {
+                "⬜"
+            };
+            text.push_str(cell);
+        }
+        text.push('\n');
+    }
+
+    text.push_str(&format!(
+        "\n勤務日: {}日",
+        worked_days + incomplete_dates.len()
+    ));
+    if !incomplete_dates.is_empty() {
+        let dates: Vec<_> = incomplete_dates
+            .iter()
+            .map(|date| date.format("%m/%d").to_string())
+            .collect();
+        text.push_str(&format!("\n未終了: {}", dates.join(", ")));
+    }
+    text
+}
+
+pub fn format_timesheet_confirmations(members: &[(User, Vec<TimesheetConfirmation>)]) -> String {
+    if members.is_empty() {
+        return "指定期間にタイムシート確認依頼はありません".to_string();
+    }
+
+    let mut summary = String::new();
+
+    for (user, confirmations) in members {
+        let pending: Vec<String> = confirmations
+            .iter()
+            .filter(|c| c.confirmed_at.is_none())
+            .map(|c| format!("{}週", c.week_start.format("%m/%d")))
+            .collect();
+        let confirmed_count = confirmations.len() - pending.len();
+
         summary.push_str(&format!(
-            "🎯 **総合計勤務時間**: {}",
-            format_duration_minutes(total_minutes)
+            "{} **{}**: {}/{}週 確認済み",
+            if pending.is_empty() { "✅" } else { "⚠️" },
+            user.username,
+            confirmed_count,
+            confirmations.len()
         ));
+        if !pending.is_empty() {
+            summary.push_str(&format!(" (未確認: {})", pending.join(", ")));
+        }
+        summary.push('\n');
     }
 
     summary
 }
 
-pub fn format_error_message(error: &str) -> String {
-    format!("❌ **エラー**: {}", error)
+pub fn format_audit_logs(logs: &[AuditLog], lang: Lang, clock: TimeDisplay) -> String {
+    if logs.is_empty() {
+        return t!(lang, "audit.empty");
+    }
+
+    let mut trail = String::new();
+
+    for log in logs {
+        let record_label = match log.record_type.parse() {
+            Ok(record_type) => record_type_label(record_type, lang),
+            Err(_) => log.record_type.clone(),
+        };
+        let change = match log.action.as_str() {
+            _ if log.record_type == "adjustment" => {
+                let minutes = log.adjustment_minutes.unwrap_or_default();
+                t!(
+                    lang,
+                    "audit.adjusted",
+                    minutes = format!(
+                        "{}{}",
+                        if minutes < 0 { "-" } else { "+" },
+                        format_duration(minutes.abs(), lang.duration_format())
+                    )
+                )
+            }
+            "create" => t!(
+                lang,
+                "audit.created",
+                record = record_label,
+                time = log
+                    .new_timestamp
+                    .map(|timestamp| clock.time(timestamp))
+                    .unwrap_or_default()
+            ),
+            "update" => t!(
+                lang,
+                "audit.updated",
+                record = record_label,
+                old = log
+                    .old_timestamp
+                    .map(|timestamp| clock.time(timestamp))
+                    .unwrap_or_default(),
+                new = log
+                    .new_timestamp
+                    .map(|timestamp| clock.time(timestamp))
+                    .unwrap_or_default()
+            ),
+            _ => t!(
+                lang,
+                "audit.deleted",
+                record = record_label,
+                time = log
+                    .old_timestamp
+                    .map(|timestamp| clock.time(timestamp))
+                    .unwrap_or_default()
+            ),
+        };
+
+        trail.push_str(&format!(
+            "`{}` {} (<@{}> / {})\n",
+            clock.datetime(log.created_at),
+            change,
+            log.actor_discord_id,
+            log.source
+        ));
+    }
+
+    trail
+}
+
+/// 1件の記録の修正履歴（修正前 → 修正後、修正者・操作元・理由）
+pub fn format_record_revisions(
+    record: &AttendanceRecord,
+    revisions: &[RecordRevision],
+    clock: TimeDisplay,
+) -> String {
+    let record_label = record_type_label(record.record_type, Lang::Ja);
+    let mut text = format!(
+        "{} {} の記録（現在: **{}**）\n\n",
+        get_date_from_utc_timestamp(record.timestamp).format("%Y/%m/%d"),
+        record_label,
+        clock.time(record.timestamp)
+    );
+    if revisions.is_empty() {
+        text.push_str("修正履歴はありません");
+        return text;
+    }
+
+    for (index, revision) in revisions.iter().enumerate() {
+        text.push_str(&format!(
+            "{}. `{}` {} → **{}** (<@{}> / {})\n",
+            index + 1,
+            clock.datetime(revision.created_at),
+            clock.time(revision.previous_timestamp),
+            clock.time(revision.new_timestamp),
+            revision.editor_discord_id,
+            revision.source
+        ));
+        if let Some(reason) = &revision.reason {
+            text.push_str(&format!("　理由: {}\n", reason));
+        }
+    }
+    text.trim_end().to_string()
+}
+
+/// 勤怠ログチャンネルに投稿する1件分の本文
+pub fn format_event_log_entry(log: &AuditLog, discord_id: &str) -> String {
+    let at = |timestamp: Option<DateTime<Utc>>| {
+        timestamp
+            .map(|timestamp| {
+                format!(
+                    "{} {}",
+                    get_date_from_utc_timestamp(timestamp).format("%m/%d"),
+                    format_time_jst(timestamp)
+                )
+            })
+            .unwrap_or_default()
+    };
+    let record_label = match log.record_type.parse() {
+        Ok(record_type) => record_type_label(record_type, Lang::Ja),
+        Err(_) => log.record_type.clone(),
+    };
+
+    let mut text = format!(
+        "👤 <@{}>
+",
+        discord_id
+    );
+    text.push_str(&match log.action.as_str() {
+        _ if log.record_type == "adjustment" => format!(
+            "調整: **{} {}**",
+            log.new_timestamp
+                .map(|timestamp| get_date_from_utc_timestamp(timestamp)
+                    .format("%m/%d")
+                    .to_string())
+                .unwrap_or_default(),
+            format_adjustment_minutes(log.adjustment_minutes.unwrap_or_default())
+        ),
+        "create" => format!("{}: **{}**", record_label, at(log.new_timestamp)),
+        "update" => format!(
+            "{}: {} → **{}**",
+            record_label,
+            at(log.old_timestamp),
+            at(log.new_timestamp)
+        ),
+        _ => format!("{}: ~~{}~~", record_label, at(log.old_timestamp)),
+    });
+    if log.actor_discord_id == discord_id {
+        text.push_str(&format!("\n操作元: {}", log.source));
+    } else {
+        text.push_str(&format!(
+            "\n操作元: {}（<@{}> が操作）",
+            log.source, log.actor_discord_id
+        ));
+    }
+    text
+}
+
+pub fn format_command_audit_logs(logs: &[CommandAuditLog]) -> String {
+    if logs.is_empty() {
+        return "管理者コマンドの実行履歴はありません".to_string();
+    }
+
+    logs.iter()
+        .map(|log| {
+            let command = if log.arguments.is_empty() {
+                log.command.clone()
+            } else {
+                format!("{} {}", log.command, log.arguments)
+            };
+            format!(
+                "`{}` <@{}> `{}`\n",
+                format_datetime_jst(log.created_at),
+                log.actor_discord_id,
+                command
+            )
+        })
+        .collect()
+}
+
+pub fn format_error_message(error: &str, lang: Lang) -> String {
+    t!(lang, "error.prefix", message = error)
 }
 
 pub fn format_success_message(message: &str) -> String {
@@ -170,7 +1452,118 @@ pub fn format_info_message(message: &str) -> String {
     format!("ℹ️ {}", message)
 }
 
+/// 差分の各区分で表示する行数の上限（埋め込みの文字数制限対策）
+const MAX_EXPORT_DIFF_LINES: usize = 15;
+
+fn format_export_row(row: &ExportRow) -> String {
+    let end_time = if row.end_time.is_empty() {
+        "未終了"
+    } else {
+        &row.end_time
+    };
+    match row.total_minutes.parse::<i32>() {
+        Ok(minutes) => format!(
+            "{} {}～{} ({})",
+            row.date,
+            row.start_time,
+            end_time,
+            format_duration_minutes(minutes)
+        ),
+        Err(_) => format!("{} {}～{}", row.date, row.start_time, end_time),
+    }
+}
+
+/// エクスポートの差分（追加・変更・削除されたセッション）
+pub fn format_export_diff(diff: &ExportDiff) -> String {
+    if diff.is_empty() {
+        return "✅ エクスポート後に変更された記録はありません".to_string();
+    }
+
+    let section = |title: &str, lines: Vec<String>| {
+        if lines.is_empty() {
+            return String::new();
+        }
+        let mut text = format!("**{} ({}件)**\n", title, lines.len());
+        for line in lines.iter().take(MAX_EXPORT_DIFF_LINES) {
+            text.push_str(&format!("   {}\n", line));
+        }
+        if lines.len() > MAX_EXPORT_DIFF_LINES {
+            text.push_str(&format!(
+                "   …他{}件\n",
+                lines.len() - MAX_EXPORT_DIFF_LINES
+            ));
+        }
+        text.push('\n');
+        text
+    };
+
+    let mut text = String::new();
+    text.push_str(&section(
+        "➕ 追加",
+        diff.added.iter().map(format_export_row).collect(),
+    ));
+    text.push_str(&section(
+        "✏️ 変更",
+        diff.changed
+            .iter()
+            .map(|(old, new)| format!("{} → {}", format_export_row(old), format_export_row(new)))
+            .collect(),
+    ));
+    text.push_str(&section(
+        "➖ 削除",
+        diff.deleted.iter().map(format_export_row).collect(),
+    ));
+    text.trim_end().to_string()
+}
+
+/// 承認依頼の内容（`discord_id` は依頼したユーザー）
+pub fn format_pending_change(change: &PendingChange, discord_id: &str) -> String {
+    let record_type = record_type_label(change.record_type, Lang::Ja);
+    let mut text = format!(
+        "👤 <@{}>\n{}\n種類: {}\n日付: {}\n",
+        discord_id,
+        match change.action.as_str() {
+            "update" => "✏️ 時間修正",
+            "delete" => "🗑️ 記録削除",
+            _ => "➕ 記録追加",
+        },
+        record_type,
+        get_date_from_utc_timestamp(change.new_timestamp).format("%Y年%m月%d日")
+    );
+    match change.old_timestamp {
+        Some(old) if change.action == "update" => text.push_str(&format!(
+            "時刻: {} → **{}**\n",
+            format_time_jst(old),
+            format_time_jst(change.new_timestamp)
+        )),
+        _ => text.push_str(&format!(
+            "時刻: **{}**\n",
+            format_time_jst(change.new_timestamp)
+        )),
+    }
+    text.push_str(&format!("操作元: {}", change.source));
+    text
+}
+
 // Embed utility functions
+pub fn create_event_log_embed(log: &AuditLog, discord_id: &str) -> serenity::CreateEmbed {
+    let (title, color) = match (log.action.as_str(), log.record_type.as_str()) {
+        ("create", "start") => ("🟢 出勤", 0x00ff00), // Green
+        (_, "adjustment") => ("⏱️ 勤務時間の調整", 0x9b59b6), // Purple
+        ("create", "break_start") => ("☕ 休憩開始", 0xf1c40f), // Yellow
+        ("create", "break_end") => ("▶️ 休憩終了", 0xf1c40f), // Yellow
+        ("create", "away") => ("🚶 離席", 0x95a5a6),  // Gray
+        ("create", _) => ("🔵 退勤", 0x3498db),       // Blue
+        ("update", _) => ("✏️ 記録の修正", 0xffa500), // Orange
+        _ => ("🗑️ 記録の削除", 0xff0000),             // Red
+    };
+    serenity::CreateEmbed::new()
+        .title(title)
+        .description(format_event_log_entry(log, discord_id))
+        .color(color)
+        .timestamp(log.created_at)
+}
+
 pub fn create_success_embed(title: &str, description: &str) -> serenity::CreateEmbed {
     serenity::CreateEmbed::new()
         .title(title)
@@ -199,18 +1592,21 @@ pub fn create_status_embed(
     username: &str,
     date: chrono::NaiveDate,
     records: &[AttendanceRecord],
+    lang: Lang,
+    clock: TimeDisplay,
 ) -> serenity::CreateEmbed {
-    let status_text = format_attendance_status(records);
+    let status_text = format_attendance_status(records, lang, clock);
     serenity::CreateEmbed::new()
-        .title("📊 勤務状況")
+        .title(t!(lang, "status.title"))
         .description(status_text)
         .color(0x3498db) // Blue
-        .author(serenity::CreateEmbedAuthor::new(format!(
-            "{} の勤務状況",
-            username
+        .author(serenity::CreateEmbedAuthor::new(t!(
+            lang,
+            "status.author",
+            name = username
         )))
         .footer(serenity::CreateEmbedFooter::new(
-            date.format("%Y年%m月%d日").to_string(),
+            date.format(&t!(lang, "format.date")).to_string(),
         ))
         .timestamp(chrono::Utc::now())
 }
@@ -220,23 +1616,106 @@ pub fn create_report_embed(
     title: &str,
     date_range: &str,
     sessions: &[WorkSession],
+    lang: Lang,
+    clock: TimeDisplay,
 ) -> serenity::CreateEmbed {
-    let report_text = format_work_sessions_summary(sessions);
+    let report_text = format_work_sessions_summary(sessions, lang, clock);
     serenity::CreateEmbed::new()
         .title(format!("📅 {}", title))
         .description(report_text)
         .color(0x9b59b6) // Purple
-        .author(serenity::CreateEmbedAuthor::new(format!(
-            "{} のレポート",
-            username
+        .author(serenity::CreateEmbedAuthor::new(t!(
+            lang,
+            "report.author",
+            name = username
+        )))
+        .footer(serenity::CreateEmbedFooter::new(date_range))
+        .timestamp(chrono::Utc::now())
+}
+
+pub fn create_weekly_digest_embed(
+    date_range: &str,
+    members: &[(User, Vec<WorkSession>)],
+) -> serenity::CreateEmbed {
+    serenity::CreateEmbed::new()
+        .title("📅 週次ダイジェスト")
+        .description(format_weekly_digest(members))
+        .color(0x9b59b6) // Purple
+        .footer(serenity::CreateEmbedFooter::new(date_range))
+        .timestamp(chrono::Utc::now())
+}
+
+pub fn create_quality_digest_embed(
+    date: chrono::NaiveDate,
+    members: &[(User, Vec<Anomaly>)],
+) -> serenity::CreateEmbed {
+    serenity::CreateEmbed::new()
+        .title("🩺 データ品質ダイジェスト")
+        .description(format_quality_digest(members))
+        .color(if members.is_empty() {
+            0x00ff00
+        } else {
+            0xffa500
+        }) // Green / Orange
+        .footer(serenity::CreateEmbedFooter::new(
+            date.format("%Y年%m月%d日").to_string(),
+        ))
+        .timestamp(chrono::Utc::now())
+}
+
+pub fn create_approval_request_embed(
+    change: &PendingChange,
+    discord_id: &str,
+) -> serenity::CreateEmbed {
+    let (title, color) = match change.status.as_str() {
+        "approved" => ("✅ 記録修正（承認済み）", 0x00ff00), // Green
+        "rejected" => ("🚫 記録修正（却下）", 0x95a5a6),     // Gray
+        _ => ("📝 記録修正の承認依頼", 0xffa500),            // Orange
+    };
+    let mut embed = serenity::CreateEmbed::new()
+        .title(title)
+        .description(format_pending_change(change, discord_id))
+        .color(color)
+        .footer(serenity::CreateEmbedFooter::new(format!(
+            "依頼ID: {}",
+            change.id
         )))
+        .timestamp(change.created_at);
+    if let Some(reviewer) = &change.reviewed_by {
+        embed = embed.field("対応者", format!("<@{}>", reviewer), true);
+    }
+    embed
+}
+
+pub fn create_team_report_embed(
+    role_name: &str,
+    date_range: &str,
+    members: &[(User, Vec<WorkSession>)],
+) -> serenity::CreateEmbed {
+    serenity::CreateEmbed::new()
+        .title(format!("👥 チームレポート: {}", role_name))
+        .description(format_team_report(members))
+        .color(0x9b59b6) // Purple
         .footer(serenity::CreateEmbedFooter::new(date_range))
         .timestamp(chrono::Utc::now())
 }
 
+pub fn create_guild_monthly_embed(
+    month_label: &str,
+    summaries: &[MemberMonthSummary],
+) -> serenity::CreateEmbed {
+    serenity::CreateEmbed::new()
+        .title(format!("🏢 サーバー月次レポート: {}", month_label))
+        .description(format_guild_monthly(summaries))
+        .color(0x9b59b6) // Purple
+        .timestamp(chrono::Utc::now())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::database::models::AuditSource;
+    use crate::utils::overtime::OvertimeThresholds;
     use chrono::{NaiveDate, TimeZone};
 
     fn create_test_record(
@@ -257,10 +1736,14 @@ mod tests {
         AttendanceRecord {
             id,
             user_id: 1,
-            record_type: record_type.to_string(),
+            record_type: record_type.parse().unwrap(),
             timestamp: datetime,
             is_modified,
             original_timestamp: None,
+            is_imported: false,
+            source: None,
+            locked: false,
+            session_id: None,
             created_at: datetime,
             updated_at: datetime,
         }
@@ -304,6 +1787,7 @@ mod tests {
             total_minutes,
             date,
             is_completed,
+            note: None,
             created_at: start_datetime,
             updated_at: start_datetime,
         }
@@ -312,7 +1796,7 @@ mod tests {
     #[test]
     fn test_format_attendance_status_empty() {
         let records = vec![];
-        let result = format_attendance_status(&records);
+        let result = format_attendance_status(&records, Lang::Ja, TimeDisplay::default());
         assert_eq!(result, "今日はまだ勤務記録がありません");
     }
 
@@ -322,7 +1806,7 @@ mod tests {
             create_test_record(1, "start", 9, 0, false),
             create_test_record(2, "end", 17, 30, false),
         ];
-        let result = format_attendance_status(&records);
+        let result = format_attendance_status(&records, Lang::Ja, TimeDisplay::default());
 
         assert!(result.contains("**本日の勤務記録:**"));
         assert!(result.contains("#1 🟢 **開始**: 09:00"));
@@ -330,24 +1814,92 @@ mod tests {
         assert!(result.contains("#1 ⏱️ 勤務時間: 8時間30分"));
         assert!(result.contains("📊 **本日の合計勤務時間**: 8時間30分"));
         assert!(!result.contains("(修正済み)"));
+
+        let result = format_attendance_status(&records, Lang::En, TimeDisplay::default());
+        assert!(result.contains("**Today's records:**"));
+        assert!(result.contains("#1 ⏱️ Worked: 8h 30m"));
+        assert!(result.contains("📊 **Total today**: 8h 30m"));
+
+        let clock = TimeDisplay {
+            utc_offset_minutes: 0,
+            clock_12h: true,
+        };
+        let result = format_attendance_status(&records, Lang::En, clock);
+        assert!(result.contains("#1 🟢 **Start**: 12:00 AM"));
+        assert!(result.contains("#1 🔴 **End**: 8:30 AM"));
     }
 
     #[test]
     fn test_format_attendance_status_modified_records() {
+        let mut start = create_test_record(1, "start", 9, 30, true);
+        start.original_timestamp = Some(create_test_record(1, "start", 9, 0, false).timestamp);
+        let records = vec![start, create_test_record(2, "end", 17, 30, true)];
+        let result = format_attendance_status(&records, Lang::Ja, TimeDisplay::default());
+
+        // 元の時刻が残っている記録は変化を表示する
+        assert!(result.contains("#1 🟢 **開始**: 09:00 → 09:30 (修正済み)"));
+        assert!(result.contains("#1 🔴 **終了**: 17:30 (修正済み)"));
+    }
+
+    #[test]
+    fn test_format_attendance_status_breaks() {
         let records = vec![
-            create_test_record(1, "start", 9, 0, true),
-            create_test_record(2, "end", 17, 30, true),
+            create_test_record(1, "start", 9, 0, false),
+            create_test_record(2, "break_start", 12, 0, false),
+            create_test_record(3, "break_end", 12, 45, false),
+            create_test_record(4, "away", 15, 0, false),
+            create_test_record(5, "end", 18, 0, false),
         ];
-        let result = format_attendance_status(&records);
+        let result = format_attendance_status(&records, Lang::Ja, TimeDisplay::default());
+
+        assert!(result.contains("☕ **休憩開始**: 12:00"));
+        assert!(result.contains("▶️ **休憩終了**: 12:45"));
+        assert!(result.contains("⏱️ 休憩時間: 45分"));
+        assert!(result.contains("🚶 **離席**: 15:00"));
+        // 休憩・離席はセッションを区切らない
+        assert!(result.contains("#1 🔴 **終了**: 18:00"));
+        assert!(!result.contains("#2"));
+        // 記録された休憩は勤務時間から差し引く（離席は差し引かない）
+        assert!(result.contains("#1 ⏱️ 勤務時間: 8時間15分"));
+        assert!(result.contains("📊 **本日の合計勤務時間**: 8時間15分"));
+    }
 
-        assert!(result.contains("#1 🟢 **開始**: 09:00 (修正済み)"));
-        assert!(result.contains("#1 🔴 **終了**: 17:30 (修正済み)"));
+    #[test]
+    fn test_format_attendance_status_record_sources() {
+        let kiosk = AuditSource::new(1, "kiosk").record_source();
+        let modal = AuditSource::new(1, "status:edit_time").record_source();
+        assert_eq!(kiosk, RecordSource::Button);
+        assert_eq!(modal, RecordSource::Modal);
+        assert_eq!(
+            AuditSource::new(1, "/start").record_source(),
+            RecordSource::SlashCommand
+        );
+        assert_eq!(
+            AuditSource::new(1, AuditSource::FORCE_END).record_source(),
+            RecordSource::Admin
+        );
+
+        let mut start = create_test_record(1, "start", 9, 0, false);
+        start.source = Some(kiosk.as_str().to_string());
+        let mut end = create_test_record(2, "end", 17, 30, true);
+        end.source = Some(modal.as_str().to_string());
+        let mut restart = create_test_record(3, "start", 18, 0, false);
+        restart.source = Some(RecordSource::SlashCommand.as_str().to_string());
+        let records = vec![start, end, restart];
+
+        let result = format_attendance_status(&records, Lang::Ja, TimeDisplay::default());
+        assert!(result.contains("#1 🟢 **開始**: 09:00 (ボタン)"));
+        assert!(result.contains("#1 🔴 **終了**: 17:30 (修正済み) (フォーム入力)"));
+        assert!(result.contains("#2 🟢 **開始**: 18:00 \n"));
+
+        let result = format_attendance_status(&records, Lang::En, TimeDisplay::default());
+        assert!(result.contains("09:00 (via button)"));
     }
 
     #[test]
     fn test_format_attendance_status_currently_working() {
         let records = vec![create_test_record(1, "start", 9, 0, false)];
-        let result = format_attendance_status(&records);
+        let result = format_attendance_status(&records, Lang::Ja, TimeDisplay::default());
 
         assert!(result.contains("#1 🟢 **開始**: 09:00"));
         assert!(result.contains("#1 ⚠️ **現在勤務中**"));
@@ -362,7 +1914,7 @@ mod tests {
             create_test_record(3, "start", 13, 0, false),
             create_test_record(4, "end", 17, 30, false),
         ];
-        let result = format_attendance_status(&records);
+        let result = format_attendance_status(&records, Lang::Ja, TimeDisplay::default());
 
         assert!(result.contains("#1 🟢 **開始**: 09:00"));
         assert!(result.contains("#1 🔴 **終了**: 12:00"));
@@ -377,7 +1929,7 @@ mod tests {
     #[test]
     fn test_format_attendance_status_end_without_start() {
         let records = vec![create_test_record(1, "end", 17, 30, false)];
-        let result = format_attendance_status(&records);
+        let result = format_attendance_status(&records, Lang::Ja, TimeDisplay::default());
 
         assert!(result.contains("#0 🔴 **終了**: 17:30"));
         assert!(result.contains("#0 ⚠️ 対応する開始記録なし"));
@@ -389,7 +1941,7 @@ mod tests {
             create_test_record(1, "start", 9, 0, false),
             create_test_record(2, "start", 13, 0, false),
         ];
-        let result = format_attendance_status(&records);
+        let result = format_attendance_status(&records, Lang::Ja, TimeDisplay::default());
 
         assert!(result.contains("#1 🟢 **開始**: 09:00"));
         assert!(result.contains("⚠️ 前回の終了記録なし"));
@@ -400,7 +1952,7 @@ mod tests {
     #[test]
     fn test_format_work_sessions_summary_empty() {
         let sessions = vec![];
-        let result = format_work_sessions_summary(&sessions);
+        let result = format_work_sessions_summary(&sessions, Lang::Ja, TimeDisplay::default());
         assert_eq!(result, "指定期間に勤務記録がありません");
     }
 
@@ -408,12 +1960,17 @@ mod tests {
     fn test_format_work_sessions_summary_single_day() {
         let date = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
         let sessions = vec![create_test_session(1, 9, 0, Some(17), Some(30), date)];
-        let result = format_work_sessions_summary(&sessions);
+        let result = format_work_sessions_summary(&sessions, Lang::Ja, TimeDisplay::default());
 
         assert!(result.contains("📅 **2023-12-15 (Fri)**"));
         assert!(result.contains("🟢 開始: 09:00 → 🔴 終了: 17:30 (8時間30分)"));
         assert!(result.contains("📊 **12/15合計**: 8時間30分"));
         assert!(result.contains("🎯 **総合計勤務時間**: 8時間30分"));
+
+        let result = format_work_sessions_summary(&sessions, Lang::En, TimeDisplay::default());
+        assert!(result.contains("🟢 Start: 09:00 → 🔴 End: 17:30 (8h 30m)"));
+        assert!(result.contains("📊 **12/15 total**: 8h 30m"));
+        assert!(result.contains("🎯 **Total worked**: 8h 30m"));
     }
 
     #[test]
@@ -424,7 +1981,7 @@ mod tests {
             create_test_session(1, 9, 0, Some(17), Some(0), date1),
             create_test_session(2, 10, 0, Some(18), Some(30), date2),
         ];
-        let result = format_work_sessions_summary(&sessions);
+        let result = format_work_sessions_summary(&sessions, Lang::Ja, TimeDisplay::default());
 
         assert!(result.contains("📅 **2023-12-15 (Fri)**"));
         assert!(result.contains("🟢 開始: 09:00 → 🔴 終了: 17:00 (8時間0分)"));
@@ -444,7 +2001,7 @@ mod tests {
             create_test_session(1, 9, 0, Some(12), Some(0), date),
             create_test_session(2, 13, 0, None, None, date),
         ];
-        let result = format_work_sessions_summary(&sessions);
+        let result = format_work_sessions_summary(&sessions, Lang::Ja, TimeDisplay::default());
 
         assert!(result.contains("📅 **2023-12-15 (Fri)**"));
         assert!(result.contains("🟢 開始: 09:00 → 🔴 終了: 12:00 (3時間0分)"));
@@ -460,7 +2017,7 @@ mod tests {
             create_test_session(1, 9, 0, Some(12), Some(0), date),
             create_test_session(2, 13, 0, Some(17), Some(30), date),
         ];
-        let result = format_work_sessions_summary(&sessions);
+        let result = format_work_sessions_summary(&sessions, Lang::Ja, TimeDisplay::default());
 
         assert!(result.contains("📅 **2023-12-15 (Fri)**"));
         assert!(result.contains("🟢 開始: 09:00 → 🔴 終了: 12:00 (3時間0分)"));
@@ -469,10 +2026,499 @@ mod tests {
         assert!(result.contains("🎯 **総合計勤務時間**: 7時間30分"));
     }
 
+    fn create_test_user(id: i64, username: &str) -> User {
+        User {
+            id,
+            discord_id: id.to_string(),
+            guild_id: String::new(),
+            username: username.to_string(),
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_format_work_schedules_and_late_starts() {
+        let schedules = vec![
+            WorkSchedule {
+                user_id: 1,
+                weekday: 0,
+                start_minutes: 9 * 60,
+            },
+            WorkSchedule {
+                user_id: 1,
+                weekday: 4,
+                start_minutes: 10 * 60 + 30,
+            },
+        ];
+        assert_eq!(
+            format_work_schedules(&schedules),
+            "月曜日: 09:00\n金曜日: 10:30"
+        );
+        assert_eq!(
+            format_work_schedules(&[]),
+            "開始予定時刻は登録されていません"
+        );
+
+        let late = LateStart {
+            date: NaiveDate::from_ymd_opt(2023, 12, 11).unwrap(),
+            expected_minutes: 9 * 60,
+            actual_minutes: 9 * 60 + 20,
+        };
+        assert_eq!(
+            format_late_starts(&[late]),
+            "⚠️ 12/11（月） 09:20 開始（予定 09:00・20分遅れ）"
+        );
+    }
+
+    #[test]
+    fn test_format_shift_comparison() {
+        let jst = |hour: u32, minute: u32| {
+            chrono::FixedOffset::east_opt(9 * 3600)
+                .unwrap()
+                .with_ymd_and_hms(2023, 12, 11, hour, minute, 0)
+                .unwrap()
+                .to_utc()
+        };
+        let shift = |id: i64, start: u32, end: u32| Shift {
+            id,
+            user_id: 1,
+            guild_id: None,
+            start_time: jst(start, 0),
+            end_time: jst(end, 0),
+            created_by: "1".to_string(),
+            reminded: false,
+            created_at: jst(0, 0),
+        };
+        let record = |id: i64, record_type: &str, hour: u32, minute: u32| AttendanceRecord {
+            id,
+            user_id: 1,
+            record_type: record_type.parse().unwrap(),
+            timestamp: jst(hour, minute),
+            is_modified: false,
+            original_timestamp: None,
+            is_imported: false,
+            source: None,
+            locked: false,
+            session_id: None,
+            created_at: jst(hour, minute),
+            updated_at: jst(hour, minute),
+        };
+        let shifts = vec![shift(1, 9, 12), shift(2, 13, 17), shift(3, 18, 20)];
+        // 1つ目は12分遅れて開始・終了済み、2つ目は出勤なし、3つ目はまだ始まっていない
+        let records = vec![record(1, "start", 9, 12), record(2, "end", 12, 0)];
+
+        assert_eq!(
+            format_shift_comparison(
+                &shifts,
+                &records,
+                jst(14, 0),
+                Lang::Ja,
+                TimeDisplay::default()
+            ),
+            "09:00〜12:00 → 09:12 開始（⚠️ 12分遅れ）・12:00 終了\n\
+             13:00〜17:00 → ⚠️ 出勤の記録がありません\n\
+             18:00〜20:00 → 予定"
+        );
+        assert_eq!(format_shifts(&shifts[..1]), "`#1` 12/11（月） 09:00〜12:00");
+
+        let session = |hour: u32, minute: u32| WorkSession {
+            id: 1,
+            user_id: 1,
+            start_time: jst(hour, minute),
+            end_time: Some(jst(12, 0)),
+            total_minutes: None,
+            date: NaiveDate::from_ymd_opt(2023, 12, 11).unwrap(),
+            is_completed: true,
+            note: None,
+            created_at: jst(hour, minute),
+            updated_at: jst(hour, minute),
+        };
+        assert_eq!(
+            format_shift_report(
+                &shifts,
+                &[session(9, 12)],
+                jst(14, 0),
+                TimeDisplay::default()
+            ),
+            "予定 2件: 出勤 1件（うち遅れ 1件）・未出勤 1件（これからのシフト 1件）\n\
+             ⚠️ 12/11（月） 09:00〜12:00 → 09:12 開始（12分遅れ）\n\
+             ⚠️ 12/11（月） 13:00〜17:00 → 出勤なし"
+        );
+
+        let template = ShiftTemplate {
+            id: 1,
+            guild_id: "1".to_string(),
+            name: "遅番".to_string(),
+            start_minutes: 22 * 60,
+            end_minutes: 7 * 60,
+        };
+        assert_eq!(format_shift_templates(&[template]), "遅番: 22:00〜翌07:00");
+    }
+
+    #[test]
+    fn test_format_time_adjustments() {
+        let adjustment = |id: i64, day: u32, minutes: i32, reason: Option<&str>| TimeAdjustment {
+            id,
+            user_id: 1,
+            date: NaiveDate::from_ymd_opt(2023, 12, day).unwrap(),
+            minutes,
+            reason: reason.map(str::to_string),
+            guild_id: None,
+            created_by: "123".to_string(),
+            created_at: Utc::now(),
+        };
+        let adjustments = vec![
+            adjustment(1, 11, 90, Some("客先での作業")),
+            adjustment(2, 12, -15, None),
+        ];
+
+        assert_eq!(
+            format_time_adjustments(&adjustments, 16 * 60),
+            "12/11 **+1時間30分** 客先での作業\n\
+             12/12 **-15分**\n\
+             打刻の合計 16時間0分 +1時間15分 = **17時間15分**"
+        );
+    }
+
+    #[test]
+    fn test_format_break_deductions() {
+        let deduction = |day: u32, worked: i32, recorded: i32| BreakDeduction {
+            date: NaiveDate::from_ymd_opt(2023, 12, day).unwrap(),
+            worked_minutes: worked,
+            recorded_break_minutes: recorded,
+            deducted_minutes: 60 - recorded,
+        };
+        assert_eq!(
+            format_break_deductions(&[deduction(11, 480, 0), deduction(12, 420, 30)]),
+            "12/11 勤務 8時間0分・休憩 0分 → 1時間0分 控除\n\
+             12/12 勤務 7時間0分・休憩 30分 → 30分 控除\n\
+             合計 1時間30分 を勤務時間から差し引いています"
+        );
+
+        let many: Vec<_> = (1..=12).map(|day| deduction(day, 480, 0)).collect();
+        let result = format_break_deductions(&many);
+        assert_eq!(result.lines().count(), 12);
+        assert!(result.contains("…ほか 2日\n合計 12時間0分"));
+    }
+
+    #[test]
+    fn test_format_goal_progress() {
+        assert_eq!(
+            format_goal_progress(15 * 60, 40 * 60, Lang::Ja),
+            "▓▓▓░░░░░░░ 37%\n15時間0分 / 40時間0分（残り 25時間0分）"
+        );
+        assert_eq!(
+            format_goal_progress(42 * 60, 40 * 60, Lang::Ja),
+            "▓▓▓▓▓▓▓▓▓▓ 105%\n42時間0分 / 40時間0分（🎉 達成）"
+        );
+    }
+
+    #[test]
+    fn test_format_standup() {
+        let yesterday = NaiveDate::from_ymd_opt(2023, 12, 14).unwrap();
+        let mut noted = create_test_session(1, 9, 0, Some(17), Some(30), yesterday);
+        noted.note = Some("API設計".to_string());
+        let members = vec![
+            (create_test_user(1, "alice"), vec![], vec![]),
+            (
+                create_test_user(2, "bob"),
+                vec![noted],
+                vec![
+                    create_test_record(1, "start", 9, 0, false),
+                    create_test_record(2, "end", 12, 0, false),
+                    create_test_record(3, "start", 13, 0, false),
+                ],
+            ),
+            (
+                create_test_user(3, "carol"),
+                vec![create_test_session(2, 10, 0, Some(12), Some(0), yesterday)],
+                vec![
+                    create_test_record(4, "start", 8, 30, false),
+                    create_test_record(5, "end", 11, 15, false),
+                ],
+            ),
+        ];
+
+        let result = format_standup(&members);
+        assert_eq!(
+            result,
+            "<@2> 🟢 勤務中（09:00〜）\n　前日: 8時間30分 📝 API設計\n\
+             <@3> ⚪ 退勤済み（11:15）\n　前日: 2時間0分\n\
+             <@1> ➖ 未出勤\n　前日: 休み\n\n\
+             🟢 勤務中: 1人 / 3人"
+        );
+        assert_eq!(format_standup(&[]), "対象のメンバーがいません");
+    }
+
+    #[test]
+    fn test_format_dashboard() {
+        let today = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
+        let yesterday = today.pred_opt().unwrap();
+        let now = create_test_session(0, 12, 0, None, None, today).start_time;
+        let members = vec![
+            (create_test_user(1, "alice"), vec![]),
+            (
+                create_test_user(2, "bob"),
+                vec![
+                    create_test_session(1, 8, 0, Some(10), Some(0), today),
+                    create_test_session(2, 11, 0, None, None, today),
+                ],
+            ),
+            (
+                create_test_user(3, "carol"),
+                vec![create_test_session(3, 22, 0, None, None, yesterday)],
+            ),
+            (
+                create_test_user(4, "dave"),
+                vec![create_test_session(4, 9, 0, Some(11), Some(30), today)],
+            ),
+        ];
+
+        assert_eq!(
+            format_dashboard(&members, today, now),
+            "🟢 **勤務中（2人）**\n<@3> 22:00〜\n<@2> 11:00〜\n\n\
+             ⏱️ **今日の勤務時間**\n<@2> 3時間0分\n<@4> 2時間30分\n合計: 5時間30分"
+        );
+        assert_eq!(
+            format_dashboard(&members[..1], today, now),
+            "🟢 **勤務中（0人）**\n勤務中のメンバーはいません\n\n\
+             ⏱️ **今日の勤務時間**\n今日の勤務記録はまだありません"
+        );
+    }
+
+    #[test]
+    fn test_format_working_members() {
+        let today = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
+        let yesterday = today.pred_opt().unwrap();
+        let now = create_test_session(0, 12, 0, None, None, today).start_time;
+        let active = vec![
+            (
+                create_test_user(3, "carol"),
+                create_test_session(3, 22, 0, None, None, yesterday),
+            ),
+            (
+                create_test_user(2, "bob"),
+                create_test_session(1, 8, 0, None, None, today),
+            ),
+            (
+                create_test_user(2, "bob"),
+                create_test_session(2, 11, 15, None, None, today),
+            ),
+        ];
+
+        assert_eq!(
+            format_working_members(&active, today, now),
+            "<@3> 12/14 22:00〜（14時間0分）\n<@2> 11:15〜（45分）\n\n合計: 2人"
+        );
+        assert_eq!(
+            format_working_members(&[], today, now),
+            "勤務中のメンバーはいません"
+        );
+    }
+
+    #[test]
+    fn test_format_weekly_digest_empty() {
+        let result = format_weekly_digest(&[]);
+        assert_eq!(result, "先週の勤務記録はありません");
+    }
+
+    #[test]
+    fn test_format_weekly_digest_multiple_members() {
+        let date1 = NaiveDate::from_ymd_opt(2023, 12, 11).unwrap();
+        let date2 = NaiveDate::from_ymd_opt(2023, 12, 12).unwrap();
+        let members = vec![
+            (
+                create_test_user(1, "alice"),
+                vec![
+                    create_test_session(1, 9, 0, Some(17), Some(0), date1),
+                    create_test_session(2, 9, 0, Some(18), Some(30), date2),
+                ],
+            ),
+            (
+                create_test_user(2, "bob"),
+                vec![
+                    create_test_session(3, 10, 0, Some(12), Some(0), date1),
+                    create_test_session(4, 13, 0, None, None, date1),
+                ],
+            ),
+        ];
+        let result = format_weekly_digest(&members);
+
+        assert!(result.contains("👤 **alice**: 17時間30分 (セッション数: 2)\n"));
+        assert!(result.contains("👤 **bob**: 2時間0分 (セッション数: 2) ⚠️ 未終了: 1"));
+        assert!(result.contains("🎯 **全体合計**: 19時間30分 (2人)"));
+    }
+
+    #[test]
+    fn test_format_timesheet_confirmations() {
+        let confirmation = |week_start: NaiveDate, confirmed: bool| TimesheetConfirmation {
+            id: 1,
+            user_id: 1,
+            week_start,
+            total_minutes: 2400,
+            confirmed_at: confirmed.then(chrono::Utc::now),
+            created_at: chrono::Utc::now(),
+        };
+        let week1 = NaiveDate::from_ymd_opt(2023, 12, 4).unwrap();
+        let week2 = NaiveDate::from_ymd_opt(2023, 12, 11).unwrap();
+        let members = vec![
+            (
+                create_test_user(1, "alice"),
+                vec![confirmation(week1, true), confirmation(week2, true)],
+            ),
+            (
+                create_test_user(2, "bob"),
+                vec![confirmation(week1, true), confirmation(week2, false)],
+            ),
+        ];
+        let result = format_timesheet_confirmations(&members);
+
+        assert!(result.contains("✅ **alice**: 2/2週 確認済み\n"));
+        assert!(result.contains("⚠️ **bob**: 1/2週 確認済み (未確認: 12/11週)"));
+    }
+
+    #[test]
+    fn test_format_audit_logs() {
+        let start = create_test_record(1, "start", 9, 0, false).timestamp;
+        let edited = create_test_record(1, "start", 9, 30, false).timestamp;
+        let log = |action: &str, old, new| AuditLog {
+            id: 1,
+            operation_id: None,
+            user_id: 1,
+            actor_discord_id: "123".to_string(),
+            action: action.to_string(),
+            record_id: 1,
+            record_type: "start".to_string(),
+            old_timestamp: old,
+            new_timestamp: new,
+            source: "status:edit_time".to_string(),
+            undone_at: None,
+            guild_id: None,
+            adjustment_minutes: None,
+            created_at: edited,
+        };
+        let mut adjustment = log("create", None, Some(start));
+        adjustment.record_type = "adjustment".to_string();
+        adjustment.adjustment_minutes = Some(-15);
+        let logs = vec![
+            log("create", None, Some(start)),
+            log("update", Some(start), Some(edited)),
+            log("delete", Some(edited), None),
+            adjustment,
+        ];
+        let result = format_audit_logs(&logs, Lang::Ja, TimeDisplay::default());
+
+        assert!(result.contains("➕ **開始を追加**: 09:00"));
+        assert!(result.contains("✏️ **開始を修正**: 09:00 → 09:30 (<@123> / status:edit_time)"));
+        assert!(result.contains("🗑️ **開始を削除**: 09:30"));
+        assert!(result.contains("⏱️ **勤務時間を調整**: -15分"));
+        assert_eq!(
+            format_audit_logs(&[], Lang::Ja, TimeDisplay::default()),
+            "指定日の記録に対する変更履歴はありません"
+        );
+    }
+
+    #[test]
+    fn test_format_record_revisions() {
+        let record = create_test_record(1, "start", 10, 0, true);
+        let revision = |previous: u32, new: u32, reason: Option<&str>| RecordRevision {
+            id: 1,
+            record_id: 1,
+            user_id: 1,
+            previous_timestamp: create_test_record(1, "start", previous, 0, false).timestamp,
+            new_timestamp: create_test_record(1, "start", new, 0, false).timestamp,
+            editor_discord_id: "123".to_string(),
+            source: "status:edit_time".to_string(),
+            reason: reason.map(str::to_string),
+            created_at: record.created_at,
+        };
+        let revisions = vec![revision(9, 11, None), revision(11, 10, Some("打刻ミス"))];
+        let result = format_record_revisions(&record, &revisions, TimeDisplay::default());
+
+        // 最初の時刻だけでなく途中の修正もすべて残る
+        assert!(result.contains("1. "));
+        assert!(result.contains("09:00 → **11:00** (<@123> / status:edit_time)"));
+        assert!(result.contains("11:00 → **10:00**"));
+        assert!(result.contains("理由: 打刻ミス"));
+        assert!(
+            format_record_revisions(&record, &[], TimeDisplay::default())
+                .ends_with("修正履歴はありません")
+        );
+    }
+
+    #[test]
+    fn test_format_event_log_entry() {
+        let start = create_test_record(1, "start", 9, 0, false).timestamp;
+        let edited = create_test_record(1, "start", 9, 30, false).timestamp;
+        let mut log = AuditLog {
+            id: 1,
+            operation_id: None,
+            user_id: 1,
+            actor_discord_id: "123".to_string(),
+            action: "create".to_string(),
+            record_id: 1,
+            record_type: "start".to_string(),
+            old_timestamp: None,
+            new_timestamp: Some(start),
+            source: "/start".to_string(),
+            undone_at: None,
+            guild_id: Some("1".to_string()),
+            adjustment_minutes: None,
+            created_at: start,
+        };
+        let date = get_date_from_utc_timestamp(start).format("%m/%d");
+
+        assert_eq!(
+            format_event_log_entry(&log, "123"),
+            format!("👤 <@123>\n開始: **{} 09:00**\n操作元: /start", date)
+        );
+
+        log.action = "update".to_string();
+        log.old_timestamp = Some(start);
+        log.new_timestamp = Some(edited);
+        log.source = "approval".to_string();
+        assert!(format_event_log_entry(&log, "456").ends_with(&format!(
+            "開始: {} 09:00 → **{} 09:30**\n操作元: approval（<@123> が操作）",
+            date, date
+        )));
+
+        log.record_type = "adjustment".to_string();
+        log.old_timestamp = None;
+        log.adjustment_minutes = Some(30);
+        log.source = "/admin adjust".to_string();
+        assert!(format_event_log_entry(&log, "456").contains(&format!("調整: **{} +30分**", date)));
+    }
+
+    #[test]
+    fn test_format_command_audit_logs() {
+        let log = |command: &str, arguments: &str| CommandAuditLog {
+            id: 1,
+            guild_id: Some("1".to_string()),
+            actor_discord_id: "123".to_string(),
+            actor_name: "admin".to_string(),
+            command: command.to_string(),
+            arguments: arguments.to_string(),
+            created_at: chrono::Utc
+                .with_ymd_and_hms(2023, 12, 15, 0, 30, 0)
+                .unwrap(),
+        };
+        let result = format_command_audit_logs(&[
+            log("/admin timesheet", "enabled=true"),
+            log("/admin confirmations", ""),
+        ]);
+
+        assert!(
+            result.contains("`2023-12-15 09:30:00 JST` <@123> `/admin timesheet enabled=true`\n")
+        );
+        assert!(result.contains("<@123> `/admin confirmations`\n"));
+    }
+
     #[test]
     fn test_format_error_message() {
-        let result = format_error_message("テストエラー");
+        let result = format_error_message("テストエラー", Lang::Ja);
         assert_eq!(result, "❌ **エラー**: テストエラー");
+        let result = format_error_message("Something failed", Lang::En);
+        assert_eq!(result, "❌ **Error**: Something failed");
     }
 
     #[test]
@@ -516,7 +2562,13 @@ mod tests {
             create_test_record(1, "start", 9, 0, false),
             create_test_record(2, "end", 17, 30, false),
         ];
-        let _embed = create_status_embed("テストユーザー", date, &records);
+        let _embed = create_status_embed(
+            "テストユーザー",
+            date,
+            &records,
+            Lang::Ja,
+            TimeDisplay::default(),
+        );
         // Embed creation successful (no panic)
     }
 
@@ -524,7 +2576,393 @@ mod tests {
     fn test_create_report_embed() {
         let date = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
         let sessions = vec![create_test_session(1, 9, 0, Some(17), Some(30), date)];
-        let _embed = create_report_embed("テストユーザー", "日次レポート", "2023-12-15", &sessions);
+        let _embed = create_report_embed(
+            "テストユーザー",
+            "日次レポート",
+            "2023-12-15",
+            &sessions,
+            Lang::Ja,
+            TimeDisplay::default(),
+        );
         // Embed creation successful (no panic)
     }
+
+    fn create_overnight_session(
+        id: i64,
+        date: NaiveDate,
+        start_hour: u32,
+        minutes: i32,
+    ) -> WorkSession {
+        let mut session = create_test_session(id, start_hour, 0, None, None, date);
+        session.end_time = Some(session.start_time + chrono::Duration::minutes(minutes as i64));
+        session.total_minutes = Some(minutes);
+        session.is_completed = true;
+        session
+    }
+
+    #[test]
+    fn snapshot_attendance_status_multiple_sessions() {
+        let records = vec![
+            create_test_record(1, "start", 9, 0, false),
+            create_test_record(2, "end", 12, 0, true),
+            create_test_record(3, "start", 13, 0, false),
+            create_test_record(4, "end", 18, 15, false),
+        ];
+        insta::assert_snapshot!(format_attendance_status(
+            &records,
+            Lang::Ja,
+            TimeDisplay::default()
+        ));
+    }
+
+    #[test]
+    fn snapshot_attendance_status_unpaired_records() {
+        let mut imported = create_test_record(3, "start", 13, 0, false);
+        imported.is_imported = true;
+        let records = vec![
+            create_test_record(1, "end", 8, 30, false),
+            create_test_record(2, "start", 9, 0, false),
+            imported,
+        ];
+        insta::assert_snapshot!(format_attendance_status(
+            &records,
+            Lang::Ja,
+            TimeDisplay::default()
+        ));
+    }
+
+    #[test]
+    fn snapshot_attendance_status_night_shift() {
+        // 前日22:00開始のセッションを当日06:00に終了
+        let mut start = create_test_record(1, "start", 22, 0, false);
+        start.timestamp -= chrono::Duration::days(1);
+        let records = vec![start, create_test_record(2, "end", 6, 0, false)];
+        insta::assert_snapshot!(format_attendance_status(
+            &records,
+            Lang::Ja,
+            TimeDisplay::default()
+        ));
+    }
+
+    #[test]
+    fn snapshot_attendance_status_long_day() {
+        let records = vec![
+            create_test_record(1, "start", 6, 0, false),
+            create_test_record(2, "end", 23, 45, false),
+        ];
+        insta::assert_snapshot!(format_attendance_status(
+            &records,
+            Lang::Ja,
+            TimeDisplay::default()
+        ));
+    }
+
+    #[test]
+    fn snapshot_work_sessions_summary_multiple_days() {
+        let day1 = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
+        let day2 = NaiveDate::from_ymd_opt(2023, 12, 16).unwrap();
+        let sessions = vec![
+            create_test_session(1, 9, 0, Some(12), Some(0), day1),
+            create_test_session(2, 13, 0, Some(18), Some(30), day1),
+            create_test_session(3, 10, 0, None, None, day2),
+        ];
+        insta::assert_snapshot!(format_work_sessions_summary(
+            &sessions,
+            Lang::Ja,
+            TimeDisplay::default()
+        ));
+    }
+
+    #[test]
+    fn snapshot_work_sessions_summary_with_notes() {
+        let date = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
+        let mut sessions = vec![
+            create_test_session(1, 9, 0, Some(12), Some(0), date),
+            create_test_session(2, 13, 0, Some(18), Some(0), date),
+        ];
+        sessions[0].note = Some("見積書の作成".to_string());
+        insta::assert_snapshot!(format_work_sessions_summary(
+            &sessions,
+            Lang::Ja,
+            TimeDisplay::default()
+        ));
+    }
+
+    #[test]
+    fn snapshot_work_sessions_summary_night_shift() {
+        let date = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
+        let sessions = vec![create_overnight_session(1, date, 22, 8 * 60)];
+        insta::assert_snapshot!(format_work_sessions_summary(
+            &sessions,
+            Lang::Ja,
+            TimeDisplay::default()
+        ));
+    }
+
+    #[test]
+    fn snapshot_work_sessions_summary_long_day() {
+        let date = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
+        let sessions = vec![create_test_session(1, 5, 30, Some(23), Some(59), date)];
+        insta::assert_snapshot!(format_work_sessions_summary(
+            &sessions,
+            Lang::Ja,
+            TimeDisplay::default()
+        ));
+    }
+
+    #[test]
+    fn snapshot_weekly_digest() {
+        let date = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
+        let members = vec![
+            (
+                create_test_user(1, "alice"),
+                vec![
+                    create_test_session(1, 9, 0, Some(18), Some(0), date),
+                    create_overnight_session(2, date, 22, 7 * 60),
+                ],
+            ),
+            (
+                create_test_user(2, "bob"),
+                vec![create_test_session(3, 10, 0, None, None, date)],
+            ),
+        ];
+        insta::assert_snapshot!(format_weekly_digest(&members));
+    }
+
+    #[test]
+    fn snapshot_team_report() {
+        let day1 = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
+        let day2 = NaiveDate::from_ymd_opt(2023, 12, 16).unwrap();
+        let members = vec![
+            (
+                create_test_user(1, "alice"),
+                vec![
+                    create_test_session(1, 9, 0, Some(12), Some(0), day1),
+                    create_test_session(2, 13, 0, Some(18), Some(0), day1),
+                    create_test_session(3, 10, 0, Some(16), Some(0), day2),
+                ],
+            ),
+            (
+                create_test_user(2, "bob"),
+                vec![create_test_session(4, 10, 0, None, None, day1)],
+            ),
+            (create_test_user(3, "carol"), vec![]),
+        ];
+        insta::assert_snapshot!(format_team_report(&members));
+    }
+
+    #[test]
+    fn snapshot_leaderboard() {
+        let ranking: Vec<(User, i64)> = (1..=12)
+            .map(|id| {
+                (
+                    create_test_user(id, &format!("user{}", id)),
+                    6000 - id * 300,
+                )
+            })
+            .collect();
+        insta::assert_snapshot!(format_leaderboard(&ranking, "12"));
+    }
+
+    #[test]
+    fn test_format_yen() {
+        assert_eq!(format_yen(0), "¥0");
+        assert_eq!(format_yen(999), "¥999");
+        assert_eq!(format_yen(1000), "¥1,000");
+        assert_eq!(format_yen(1234567), "¥1,234,567");
+        assert_eq!(format_yen(-1500), "-¥1,500");
+    }
+
+    #[test]
+    fn snapshot_overtime_split() {
+        let split = OvertimeSplit {
+            regular_minutes: 2400,
+            overtime_minutes: 135,
+        };
+        insta::assert_snapshot!(format_overtime_split(&split));
+    }
+
+    #[test]
+    fn snapshot_quality_digest() {
+        let jst = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+        let at = |hour, minute| {
+            jst.with_ymd_and_hms(2023, 12, 15, hour, minute, 0)
+                .unwrap()
+                .to_utc()
+        };
+        let members = vec![
+            (
+                create_test_user(1, "alice"),
+                vec![
+                    Anomaly::OpenSession { start: at(9, 0) },
+                    Anomaly::Modified { count: 2 },
+                ],
+            ),
+            (
+                create_test_user(2, "bob"),
+                vec![
+                    Anomaly::UnpairedEnd { end: at(6, 30) },
+                    Anomaly::LongDay { minutes: 17 * 60 },
+                ],
+            ),
+        ];
+        insta::assert_snapshot!(format_quality_digest(&members));
+    }
+
+    #[test]
+    fn snapshot_export_diff() {
+        let row = |date: &str, start: &str, end: &str, minutes: &str| ExportRow {
+            date: date.to_string(),
+            start_time: start.to_string(),
+            end_time: end.to_string(),
+            total_minutes: minutes.to_string(),
+            is_completed: (!end.is_empty()).to_string(),
+        };
+        let diff = ExportDiff {
+            added: vec![row("2023-12-18", "13:00", "15:00", "120")],
+            changed: vec![(
+                row("2023-12-16", "09:00", "", ""),
+                row("2023-12-16", "09:00", "18:00", "540"),
+            )],
+            deleted: vec![row("2023-12-17", "10:00", "12:00", "120")],
+        };
+        insta::assert_snapshot!(format_export_diff(&diff));
+    }
+
+    #[test]
+    fn snapshot_pending_change() {
+        let jst = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+        let at = |hour, minute| {
+            jst.with_ymd_and_hms(2023, 12, 15, hour, minute, 0)
+                .unwrap()
+                .to_utc()
+        };
+        let mut change = PendingChange {
+            id: 1,
+            guild_id: "1".to_string(),
+            user_id: 1,
+            requested_by: "100".to_string(),
+            action: "update".to_string(),
+            record_id: Some(10),
+            record_type: RecordType::Start,
+            old_timestamp: Some(at(9, 0)),
+            new_timestamp: at(8, 30),
+            source: "status:edit_time".to_string(),
+            reason: None,
+            status: "pending".to_string(),
+            reviewed_by: None,
+            reviewed_at: None,
+            created_at: at(12, 0),
+        };
+        let update = format_pending_change(&change, "100");
+
+        change.action = "create".to_string();
+        change.record_id = None;
+        change.record_type = RecordType::End;
+        change.old_timestamp = None;
+        change.new_timestamp = at(18, 0);
+        change.source = "status:add_end".to_string();
+        let create = format_pending_change(&change, "100");
+
+        insta::assert_snapshot!(format!("{}\n---\n{}", update, create));
+
+        // 削除は削除する記録の時刻だけを表示する
+        change.action = "delete".to_string();
+        change.record_id = Some(11);
+        change.old_timestamp = Some(at(18, 0));
+        change.source = "status:delete_single".to_string();
+        let delete = format_pending_change(&change, "100");
+        assert!(delete.contains("🗑️ 記録削除"));
+        assert!(delete.contains(&format!("時刻: **{}**", format_time_jst(at(18, 0)))));
+    }
+
+    #[test]
+    fn snapshot_validation_rejections() {
+        let rejections = vec![
+            ("future_time".to_string(), 12),
+            ("duplicate_time".to_string(), 5),
+            ("end_without_start".to_string(), 3),
+        ];
+        insta::assert_snapshot!(format_validation_rejections(&rejections));
+    }
+
+    #[test]
+    fn snapshot_project_breakdown() {
+        let projects = vec![
+            (Some("kintai-bot".to_string()), 1230),
+            (None, 95),
+            (Some("社内ツール".to_string()), 60),
+        ];
+        insta::assert_snapshot!(format_project_breakdown(&projects));
+    }
+
+    #[test]
+    fn test_format_focus_summary() {
+        let start = Utc::now() - chrono::Duration::hours(3);
+        let focus = |offset: i64, minutes: Option<i64>, completed: bool| FocusSession {
+            id: offset,
+            user_id: 1,
+            channel_id: "1".to_string(),
+            started_at: start + chrono::Duration::minutes(offset),
+            ends_at: start + chrono::Duration::minutes(offset + 25),
+            ended_at: minutes.map(|m| start + chrono::Duration::minutes(offset + m)),
+            completed,
+            created_at: start,
+        };
+
+        // 集中中の回は数えない
+        let sessions = vec![
+            focus(0, Some(25), true),
+            focus(30, Some(25), true),
+            focus(60, Some(10), false),
+            focus(90, None, false),
+        ];
+        assert_eq!(
+            format_focus_summary(&sessions),
+            "🍅 3回・合計 1時間0分（うち中断 1回）"
+        );
+        assert_eq!(format_focus_summary(&sessions[..1]), "🍅 1回・合計 25分");
+    }
+
+    #[test]
+    fn snapshot_pay_estimate() {
+        let day1 = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
+        let day2 = NaiveDate::from_ymd_opt(2023, 12, 16).unwrap();
+        let sessions = vec![
+            create_test_session(1, 8, 0, Some(19), Some(30), day1),
+            create_test_session(2, 9, 0, Some(17), Some(0), day2),
+        ];
+        let rate = Rate {
+            id: 1,
+            guild_id: "1".to_string(),
+            user_id: None,
+            hourly_rate: 1200,
+            overtime_rate: 1500,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let estimate = PayEstimate::calculate(&sessions, &rate, OvertimeThresholds::default());
+        insta::assert_snapshot!(format_pay_estimate(&estimate, &rate));
+    }
+
+    #[test]
+    fn test_format_calendar() {
+        // 2023-12-01 は金曜日
+        let date = |day| NaiveDate::from_ymd_opt(2023, 12, day).unwrap();
+        let sessions = vec![
+            create_test_session(1, 9, 0, Some(17), Some(0), date(1)),
+            create_test_session(2, 9, 0, Some(12), Some(0), date(4)),
+            create_test_session(3, 13, 0, None, None, date(4)),
+            create_test_session(4, 9, 0, Some(17), Some(0), date(5)),
+        ];
+
+        let result = format_calendar(date(1), &sessions, date(6));
+        let lines: Vec<&str> = result.lines().collect();
+
+        assert_eq!(lines[0], "`12/01` ⬛⬛⬛⬛🟩⬜⬜");
+        assert_eq!(lines[1], "`12/04` 🟨🟩⬜➖➖➖➖");
+        assert_eq!(lines[4], "`12/25` ➖➖➖➖➖➖➖");
+        assert_eq!(lines.len(), 8);
+        assert!(result.ends_with("勤務日: 3日\n未終了: 12/04"));
+    }
 }
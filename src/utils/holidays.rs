@@ -0,0 +1,92 @@
+// Japanese national holidays, computed from the rules of the current holiday law (so dates
+// before the 2020 changes, such as the old 体育の日 or the one-off holidays of 2019-2021, are
+// not reproduced). The equinox days use the usual approximation, valid from 1980 to 2099.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+
+/// `date` が祝日（振替休日・国民の休日を含む）なら、その名前
+pub fn holiday_name(date: NaiveDate) -> Option<&'static str> {
+    if let Some(name) = base_holiday(date) {
+        return Some(name);
+    }
+    // 祝日が日曜日のときは、その後の最初の祝日でない日が振替休日
+    let mut previous = date.pred_opt()?;
+    while base_holiday(previous).is_some() {
+        if previous.weekday() == Weekday::Sun {
+            return Some("振替休日");
+        }
+        previous = previous.pred_opt()?;
+    }
+    // 前日と翌日が祝日の日は国民の休日
+    if base_holiday(date.pred_opt()?).is_some() && base_holiday(date.succ_opt()?).is_some() {
+        return Some("国民の休日");
+    }
+    None
+}
+
+/// 日付が法律で決まっている祝日
+fn base_holiday(date: NaiveDate) -> Option<&'static str> {
+    let year = date.year();
+    let name = match (date.month(), date.day()) {
+        (1, 1) => "元日",
+        (1, _) if is_nth_monday(date, 2) => "成人の日",
+        (2, 11) => "建国記念の日",
+        (2, 23) => "天皇誕生日",
+        (3, day) if day == equinox_day(year, 20.8431) => "春分の日",
+        (4, 29) => "昭和の日",
+        (5, 3) => "憲法記念日",
+        (5, 4) => "みどりの日",
+        (5, 5) => "こどもの日",
+        (7, _) if is_nth_monday(date, 3) => "海の日",
+        (8, 11) => "山の日",
+        (9, _) if is_nth_monday(date, 3) => "敬老の日",
+        (9, day) if day == equinox_day(year, 23.2488) => "秋分の日",
+        (10, _) if is_nth_monday(date, 2) => "スポーツの日",
+        (11, 3) => "文化の日",
+        (11, 23) => "勤労感謝の日",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// 月の第 `n` 月曜日か
+fn is_nth_monday(date: NaiveDate, n: u32) -> bool {
+    date.weekday() == Weekday::Mon && (date.day() - 1) / 7 + 1 == n
+}
+
+/// 春分・秋分の日（`base` は1980年の基準日）
+fn equinox_day(year: i32, base: f64) -> u32 {
+    let years = (year - 1980) as f64;
+    (base + 0.242194 * years - (years / 4.0).floor()).floor() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_holiday_name() {
+        assert_eq!(holiday_name(date(2025, 1, 1)), Some("元日"));
+        assert_eq!(holiday_name(date(2025, 1, 13)), Some("成人の日"));
+        assert_eq!(holiday_name(date(2025, 3, 20)), Some("春分の日"));
+        assert_eq!(holiday_name(date(2025, 9, 23)), Some("秋分の日"));
+        assert_eq!(holiday_name(date(2025, 10, 13)), Some("スポーツの日"));
+        assert_eq!(holiday_name(date(2025, 1, 14)), None);
+        assert_eq!(holiday_name(date(2025, 3, 21)), None);
+    }
+
+    #[test]
+    fn test_substitute_and_sandwiched_holidays() {
+        // 2025-05-04（みどりの日）は日曜日なので、こどもの日の翌日が振替休日
+        assert_eq!(holiday_name(date(2025, 5, 6)), Some("振替休日"));
+        assert_eq!(holiday_name(date(2025, 5, 7)), None);
+        // 2026年は敬老の日（21日）と秋分の日（23日）に挟まれた22日が国民の休日
+        assert_eq!(holiday_name(date(2026, 9, 21)), Some("敬老の日"));
+        assert_eq!(holiday_name(date(2026, 9, 22)), Some("国民の休日"));
+        assert_eq!(holiday_name(date(2026, 9, 23)), Some("秋分の日"));
+    }
+}
@@ -0,0 +1,141 @@
+// Message catalog for user-facing text. Each locale is a flat JSON object in `locales/` keyed by
+// message id, with `{name}` placeholders filled in by the `t!` macro. Japanese is the source
+// catalog: a key missing from another locale falls back to Japanese, and modules that have not
+// been moved to the catalog yet still use their hardcoded Japanese strings.
+
+use crate::utils::time::DurationFormat;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::LazyLock;
+
+/// 表示言語
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    #[default]
+    Ja,
+    En,
+}
+
+impl Lang {
+    /// 設定に保存するコード
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::Ja => "ja",
+            Lang::En => "en",
+        }
+    }
+
+    /// 保存されたコードから（不明なコードは `None`）
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "ja" => Some(Lang::Ja),
+            "en" => Some(Lang::En),
+            _ => None,
+        }
+    }
+
+    /// Discordのロケールから決める（日本語以外は英語）
+    pub fn from_locale(locale: Option<&str>) -> Self {
+        match locale {
+            Some(locale) if !locale.starts_with("ja") => Lang::En,
+            _ => Lang::Ja,
+        }
+    }
+
+    /// その言語での言語名
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Lang::Ja => "日本語",
+            Lang::En => "English",
+        }
+    }
+
+    /// 勤務時間の表示形式
+    pub fn duration_format(self) -> DurationFormat {
+        match self {
+            Lang::Ja => DurationFormat::Japanese,
+            Lang::En => DurationFormat::Compact,
+        }
+    }
+
+    fn catalog(self) -> &'static HashMap<String, String> {
+        match self {
+            Lang::Ja => &JA,
+            Lang::En => &EN,
+        }
+    }
+}
+
+static JA: LazyLock<HashMap<String, String>> =
+    LazyLock::new(|| parse_catalog(include_str!("../../locales/ja.json")));
+static EN: LazyLock<HashMap<String, String>> =
+    LazyLock::new(|| parse_catalog(include_str!("../../locales/en.json")));
+
+fn parse_catalog(json: &str) -> HashMap<String, String> {
+    serde_json::from_str(json).expect("locale catalog must be a flat JSON object of strings")
+}
+
+/// メッセージを取得し `{name}` を置き換える（無いキーは日本語、それも無ければキーそのもの）
+pub fn message(lang: Lang, key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let template = lang
+        .catalog()
+        .get(key)
+        .or_else(|| Lang::Ja.catalog().get(key))
+        .map(String::as_str)
+        .unwrap_or(key);
+
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), &value.to_string())
+        })
+}
+
+/// `t!(lang, "key", name = value, ...)` でカタログのメッセージを取得する
+#[macro_export]
+macro_rules! t {
+    ($lang:expr, $key:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::utils::i18n::message(
+            $lang,
+            $key,
+            &[$((stringify!($name), &$value as &dyn std::fmt::Display)),*],
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn placeholders(text: &str) -> BTreeSet<&str> {
+        text.split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect()
+    }
+
+    #[test]
+    fn test_catalogs_have_same_keys_and_placeholders() {
+        let ja: BTreeSet<_> = JA.keys().collect();
+        let en: BTreeSet<_> = EN.keys().collect();
+        assert_eq!(ja, en);
+        for (key, text) in JA.iter() {
+            assert_eq!(placeholders(text), placeholders(&EN[key]), "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_message() {
+        assert_eq!(
+            t!(Lang::Ja, "error.user_lookup"),
+            "ユーザー情報の取得に失敗しました"
+        );
+        assert_eq!(
+            t!(Lang::En, "error.user_lookup"),
+            "Failed to load your user information"
+        );
+        assert_eq!(message(Lang::En, "no.such.key", &[]), "no.such.key");
+        assert_eq!(Lang::from_locale(Some("en-US")), Lang::En);
+        assert_eq!(Lang::from_locale(None), Lang::Ja);
+    }
+}
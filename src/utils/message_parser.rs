@@ -0,0 +1,40 @@
+use crate::database::models::RecordType;
+
+const START_KEYWORDS: &[&str] = &["出勤", "しゅっきん"];
+const END_KEYWORDS: &[&str] = &["退勤", "たいきん"];
+
+/// 「出勤」「退勤」で始まるチャットメッセージから記録種別を判定する
+///
+/// 会話中の言及（例: 「明日は出勤します？」）を拾わないよう、キーワードで始まるメッセージのみ対象とする
+pub fn parse_attendance_message(content: &str) -> Option<RecordType> {
+    let content = content.trim();
+
+    if START_KEYWORDS.iter().any(|k| content.starts_with(k)) {
+        Some(RecordType::Start)
+    } else if END_KEYWORDS.iter().any(|k| content.starts_with(k)) {
+        Some(RecordType::End)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_attendance_message() {
+        assert_eq!(parse_attendance_message("出勤"), Some(RecordType::Start));
+        assert_eq!(
+            parse_attendance_message("  出勤しました！"),
+            Some(RecordType::Start)
+        );
+        assert_eq!(
+            parse_attendance_message("退勤します"),
+            Some(RecordType::End)
+        );
+        assert_eq!(parse_attendance_message("たいきん"), Some(RecordType::End));
+        assert_eq!(parse_attendance_message("明日は出勤します"), None);
+        assert_eq!(parse_attendance_message("おはようございます"), None);
+    }
+}
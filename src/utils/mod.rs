@@ -1,6 +1,18 @@
+pub mod breaks;
+pub mod chart;
+pub mod csv_import;
+pub mod data_quality;
+pub mod export;
 pub mod format;
+pub mod holidays;
+pub mod i18n;
+pub mod message_parser;
+pub mod overtime;
 pub mod record_selector;
 pub mod record_validator;
+pub mod schedule;
 pub mod session_manager;
+pub mod shortcut;
+pub mod stats;
 pub mod time;
 pub mod validation;
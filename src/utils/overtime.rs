@@ -0,0 +1,154 @@
+use crate::database::models::{GuildSettings, WorkSession};
+use crate::utils::time::get_week_start;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// 時間外勤務の判定基準となる所定労働時間（分）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OvertimeThresholds {
+    pub daily_minutes: i32,
+    pub weekly_minutes: i32,
+}
+
+impl Default for OvertimeThresholds {
+    /// 労働基準法の法定労働時間（1日8時間・週40時間）
+    fn default() -> Self {
+        Self {
+            daily_minutes: 8 * 60,
+            weekly_minutes: 40 * 60,
+        }
+    }
+}
+
+impl From<&GuildSettings> for OvertimeThresholds {
+    fn from(settings: &GuildSettings) -> Self {
+        Self {
+            daily_minutes: settings.standard_daily_minutes,
+            weekly_minutes: settings.standard_weekly_minutes,
+        }
+    }
+}
+
+/// 勤務時間の通常・時間外の内訳（分）
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct OvertimeSplit {
+    pub regular_minutes: i32,
+    pub overtime_minutes: i32,
+}
+
+/// 1日の所定時間を超えた分と、週（月曜始まり）の所定時間を超えた分を時間外として集計する
+///
+/// 1日単位で時間外となった分は週の集計に含めない（二重計上しない）
+pub fn split_overtime(sessions: &[WorkSession], thresholds: OvertimeThresholds) -> OvertimeSplit {
+    let mut daily_minutes: BTreeMap<_, i32> = BTreeMap::new();
+    for session in sessions {
+        *daily_minutes.entry(session.date).or_default() += session.total_minutes.unwrap_or(0);
+    }
+
+    split_daily_overtime(&daily_minutes, thresholds)
+}
+
+/// 日ごとの勤務時間（分）を `split_overtime` と同じ基準で通常・時間外に分ける
+pub fn split_daily_overtime(
+    daily_minutes: &BTreeMap<NaiveDate, i32>,
+    thresholds: OvertimeThresholds,
+) -> OvertimeSplit {
+    let mut split = OvertimeSplit::default();
+    let mut current_week = None;
+    let mut weekly_regular = 0;
+
+    for (&date, &minutes) in daily_minutes {
+        let week_start = get_week_start(date);
+        if current_week != Some(week_start) {
+            current_week = Some(week_start);
+            weekly_regular = 0;
+        }
+
+        let mut regular = minutes.min(thresholds.daily_minutes);
+        let mut overtime = minutes - regular;
+
+        let weekly_excess = (weekly_regular + regular - thresholds.weekly_minutes).max(0);
+        regular -= weekly_excess;
+        overtime += weekly_excess;
+        weekly_regular += regular;
+
+        split.regular_minutes += regular;
+        split.overtime_minutes += overtime;
+    }
+
+    split
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Days, NaiveDate, TimeZone, Utc};
+
+    fn session(date: NaiveDate, minutes: i32) -> WorkSession {
+        let start = Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap());
+        WorkSession {
+            id: 0,
+            user_id: 1,
+            start_time: start,
+            end_time: Some(start + chrono::Duration::minutes(minutes as i64)),
+            total_minutes: Some(minutes),
+            date,
+            is_completed: true,
+            note: None,
+            created_at: start,
+            updated_at: start,
+        }
+    }
+
+    #[test]
+    fn test_split_overtime_daily() {
+        // 2023-12-11 は月曜日
+        let monday = NaiveDate::from_ymd_opt(2023, 12, 11).unwrap();
+        let sessions = vec![
+            session(monday, 300),
+            session(monday, 300),
+            session(monday + Days::new(1), 420),
+        ];
+
+        let split = split_overtime(&sessions, OvertimeThresholds::default());
+
+        assert_eq!(split.regular_minutes, 480 + 420);
+        assert_eq!(split.overtime_minutes, 120);
+    }
+
+    #[test]
+    fn test_split_overtime_weekly() {
+        let monday = NaiveDate::from_ymd_opt(2023, 12, 11).unwrap();
+        // 月〜土に8時間ずつ勤務すると6日目の8時間が週の時間外になる
+        let mut sessions: Vec<_> = (0..6)
+            .map(|day| session(monday + Days::new(day), 480))
+            .collect();
+        // 翌週は週の集計がリセットされる
+        sessions.push(session(monday + Days::new(7), 540));
+
+        let split = split_overtime(&sessions, OvertimeThresholds::default());
+
+        assert_eq!(split.regular_minutes, 2400 + 480);
+        assert_eq!(split.overtime_minutes, 480 + 60);
+        assert_eq!(
+            split.regular_minutes + split.overtime_minutes,
+            6 * 480 + 540
+        );
+    }
+
+    #[test]
+    fn test_split_overtime_custom_thresholds() {
+        let monday = NaiveDate::from_ymd_opt(2023, 12, 11).unwrap();
+        let thresholds = OvertimeThresholds {
+            daily_minutes: 360,
+            weekly_minutes: 600,
+        };
+        let sessions = vec![session(monday, 420), session(monday + Days::new(1), 360)];
+
+        let split = split_overtime(&sessions, thresholds);
+
+        // 1日目: 通常360 + 時間外60、2日目: 週の残り240が通常、120が時間外
+        assert_eq!(split.regular_minutes, 600);
+        assert_eq!(split.overtime_minutes, 180);
+    }
+}
@@ -1,36 +1,135 @@
-use crate::database::models::AttendanceRecord;
+use crate::database::models::{AttendanceRecord, RecordSource};
+use crate::utils::format::{record_source_label, record_type_label};
+use crate::utils::i18n::Lang;
 use crate::utils::time::format_time_jst;
 use poise::serenity_prelude as serenity;
 
+/// 1つのセレクトメニューに表示できる選択肢の上限（Discord の制限）
+pub const MAX_SELECT_OPTIONS: usize = 25;
+/// ページ移動の選択肢の値（"page:N"）のプレフィックス
+const PAGE_OPTION_PREFIX: &str = "page:";
+
 pub struct RecordSelector {
     records: Vec<AttendanceRecord>,
+    page_size: usize,
+}
+
+/// 1ページに表示する記録の範囲
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PageWindow {
+    page: usize,
+    start: usize,
+    end: usize,
+    page_count: usize,
 }
 
 impl RecordSelector {
     pub fn new(mut records: Vec<AttendanceRecord>) -> Self {
         // Sort by timestamp for chronological order
         records.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
-        Self { records }
+        Self {
+            records,
+            page_size: MAX_SELECT_OPTIONS,
+        }
+    }
+
+    /// 1ページの選択肢数を変更する（ページ移動の選択肢を含む。4～25に丸める）
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.clamp(4, MAX_SELECT_OPTIONS);
+        self
+    }
+
+    /// ページ移動の選択肢の値ならページ番号を返す
+    pub fn parse_page_option(value: &str) -> Option<usize> {
+        value.strip_prefix(PAGE_OPTION_PREFIX)?.parse().ok()
+    }
+
+    /// 複数ページに分かれる場合の表示位置（例: "全30件中 1～23件目"）
+    pub fn page_summary(&self, page: usize, reserved: usize) -> Option<String> {
+        let window = self.window(page, reserved);
+        (window.page_count > 1).then(|| {
+            format!(
+                "全{}件中 {}～{}件目",
+                self.records.len(),
+                window.start + 1,
+                window.end
+            )
+        })
+    }
+
+    /// 削除メニューで「全て削除」に使う選択肢の数
+    pub fn delete_reserved_options(&self) -> usize {
+        usize::from(self.records.len() > 1)
+    }
+
+    /// `reserved` 個の固定の選択肢を除いて、指定ページに表示する範囲を求める
+    fn window(&self, page: usize, reserved: usize) -> PageWindow {
+        let total = self.records.len();
+        if total + reserved <= self.page_size {
+            return PageWindow {
+                page: 0,
+                start: 0,
+                end: total,
+                page_count: 1,
+            };
+        }
+
+        // 前後のページへ移動する選択肢の分を空ける
+        let per_page = self.page_size - reserved - 2;
+        let page_count = total.div_ceil(per_page);
+        let page = page.min(page_count - 1);
+        PageWindow {
+            page,
+            start: page * per_page,
+            end: ((page + 1) * per_page).min(total),
+            page_count,
+        }
+    }
+
+    /// 前後のページへ移動する選択肢
+    fn page_options(window: PageWindow) -> Vec<serenity::CreateSelectMenuOption> {
+        let mut options = Vec::new();
+        if window.page > 0 {
+            options.push(
+                serenity::CreateSelectMenuOption::new(
+                    "◀ 前のページ",
+                    format!("{}{}", PAGE_OPTION_PREFIX, window.page - 1),
+                )
+                .description(format!("{}/{}ページへ", window.page, window.page_count)),
+            );
+        }
+        if window.page + 1 < window.page_count {
+            options.push(
+                serenity::CreateSelectMenuOption::new(
+                    "次のページ ▶",
+                    format!("{}{}", PAGE_OPTION_PREFIX, window.page + 1),
+                )
+                .description(format!(
+                    "{}/{}ページへ",
+                    window.page + 2,
+                    window.page_count
+                )),
+            );
+        }
+        options
     }
 
     pub fn create_select_menu(
         &self,
         custom_id: &str,
         placeholder: &str,
+        page: usize,
     ) -> Option<serenity::CreateSelectMenu> {
         if self.records.is_empty() {
             return None;
         }
 
+        let window = self.window(page, 0);
         let mut options = Vec::new();
 
-        for record in &self.records {
+        for record in &self.records[window.start..window.end] {
             let time_str = format_time_jst(record.timestamp);
-            let type_str = match record.record_type.as_str() {
-                "start" => "開始",
-                "end" => "終了",
-                _ => "不明",
-            };
+            let type_str = record_type_label(record.record_type, Lang::Ja);
 
             let modified_indicator = if record.is_modified {
                 " (修正済み)"
@@ -46,7 +145,14 @@ impl RecordSelector {
                     "修正済みの記録".to_string()
                 }
             } else {
-                format!("記録ID: {}", record.id)
+                match record.source.as_deref().and_then(RecordSource::parse) {
+                    Some(source) => format!(
+                        "記録ID: {}・{}",
+                        record.id,
+                        record_source_label(source, Lang::Ja)
+                    ),
+                    None => format!("記録ID: {}", record.id),
+                }
             };
 
             options.push(
@@ -55,10 +161,7 @@ impl RecordSelector {
             );
         }
 
-        // Discord select menu limit is 25 options
-        if options.len() > 25 {
-            options.truncate(25);
-        }
+        options.extend(Self::page_options(window));
 
         Some(
             serenity::CreateSelectMenu::new(
@@ -69,21 +172,22 @@ impl RecordSelector {
         )
     }
 
-    pub fn create_delete_select_menu(&self, custom_id: &str) -> Option<serenity::CreateSelectMenu> {
+    pub fn create_delete_select_menu(
+        &self,
+        custom_id: &str,
+        page: usize,
+    ) -> Option<serenity::CreateSelectMenu> {
         if self.records.is_empty() {
             return None;
         }
 
+        let window = self.window(page, self.delete_reserved_options());
         let mut options = Vec::new();
 
         // Add individual record options
-        for record in &self.records {
+        for record in &self.records[window.start..window.end] {
             let time_str = format_time_jst(record.timestamp);
-            let type_str = match record.record_type.as_str() {
-                "start" => "開始",
-                "end" => "終了",
-                _ => "不明",
-            };
+            let type_str = record_type_label(record.record_type, Lang::Ja);
 
             let label = format!("{} {}", time_str, type_str);
             options.push(
@@ -100,10 +204,7 @@ impl RecordSelector {
             );
         }
 
-        // Discord select menu limit is 25 options
-        if options.len() > 25 {
-            options.truncate(25);
-        }
+        options.extend(Self::page_options(window));
 
         Some(
             serenity::CreateSelectMenu::new(
@@ -130,3 +231,88 @@ impl RecordSelector {
         self.records.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::models::RecordType;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn records(count: i64) -> Vec<AttendanceRecord> {
+        let base = Utc.with_ymd_and_hms(2023, 12, 15, 0, 0, 0).unwrap();
+        (1..=count)
+            .map(|id| {
+                let timestamp = base + Duration::minutes(id);
+                AttendanceRecord {
+                    id,
+                    user_id: 1,
+                    record_type: if id % 2 == 1 {
+                        RecordType::Start
+                    } else {
+                        RecordType::End
+                    },
+                    timestamp,
+                    is_modified: false,
+                    original_timestamp: None,
+                    is_imported: false,
+                    source: None,
+                    locked: false,
+                    session_id: None,
+                    created_at: timestamp,
+                    updated_at: timestamp,
+                }
+            })
+            .collect()
+    }
+
+    fn option_values(menu: serenity::CreateSelectMenu) -> Vec<String> {
+        serde_json::to_value(menu).unwrap()["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|option| option["value"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_select_menu_pages_through_all_records() {
+        let selector = RecordSelector::new(records(30));
+
+        // 1ページ目: 23件 + 次のページ
+        let first = option_values(selector.create_select_menu("id", "", 0).unwrap());
+        assert_eq!(first.len(), 24);
+        assert_eq!(first[22], "23");
+        assert_eq!(first[23], "page:1");
+        assert_eq!(
+            selector.page_summary(0, 0).as_deref(),
+            Some("全30件中 1～23件目")
+        );
+
+        // 2ページ目: 残り7件 + 前のページ
+        let second = option_values(selector.create_select_menu("id", "", 1).unwrap());
+        assert_eq!(second.first().map(String::as_str), Some("24"));
+        assert_eq!(second.last().map(String::as_str), Some("page:0"));
+        assert_eq!(second.len(), 8);
+        assert_eq!(RecordSelector::parse_page_option("page:1"), Some(1));
+        assert_eq!(RecordSelector::parse_page_option("24"), None);
+    }
+
+    #[test]
+    fn test_delete_menu_keeps_delete_all_on_every_page() {
+        let selector = RecordSelector::new(records(10)).with_page_size(6);
+
+        // 6件 - 全て削除 - 前後のページ = 1ページ3件
+        let first = option_values(selector.create_delete_select_menu("id", 0).unwrap());
+        assert_eq!(first, vec!["1", "2", "3", "delete_all", "page:1"]);
+        let last = option_values(selector.create_delete_select_menu("id", 9).unwrap());
+        assert_eq!(last, vec!["10", "delete_all", "page:2"]);
+        assert_eq!(
+            selector.page_summary(3, 1).as_deref(),
+            Some("全10件中 10～10件目")
+        );
+
+        // 収まる場合はページに分けない
+        let selector = RecordSelector::new(records(5)).with_page_size(6);
+        assert_eq!(selector.page_summary(0, 1), None);
+    }
+}
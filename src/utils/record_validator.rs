@@ -1,6 +1,228 @@
-use crate::database::models::{AttendanceRecord, RecordType};
-use anyhow::Result;
-use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use crate::database::models::{AttendanceRecord, GuildSettings, RecordType};
+use crate::utils::format::record_type_label;
+use crate::utils::i18n::Lang;
+use crate::utils::time::format_time_jst;
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, Utc};
+use std::fmt;
+
+/// 追加・修正できる記録の最大日数の既定値（今日から遡る日数）
+pub const MAX_RECORD_AGE_DAYS: i64 = 7;
+
+/// サーバーごとの記録の編集ルール（`/admin edit_policy`）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EditPolicy {
+    /// 追加・修正できる記録の最大日数（今日から遡る日数）
+    pub max_age_days: i64,
+    /// 今日より前の日付の記録を追加・修正できるのは管理者のみ
+    pub backdate_admin_only: bool,
+    /// 記録の削除にも承認が必要（承認チャンネルが設定されている場合のみ）
+    pub delete_requires_approval: bool,
+}
+
+impl Default for EditPolicy {
+    fn default() -> Self {
+        Self {
+            max_age_days: MAX_RECORD_AGE_DAYS,
+            backdate_admin_only: false,
+            delete_requires_approval: false,
+        }
+    }
+}
+
+impl From<&GuildSettings> for EditPolicy {
+    fn from(settings: &GuildSettings) -> Self {
+        Self {
+            max_age_days: settings.edit_max_age_days.into(),
+            backdate_admin_only: settings.backdate_admin_only,
+            delete_requires_approval: settings.delete_requires_approval,
+        }
+    }
+}
+
+impl EditPolicy {
+    /// 操作する人に適用するルール（管理者には過去の日付・削除の制限をかけない）
+    pub fn for_actor(self, is_admin: bool) -> Self {
+        if is_admin {
+            Self {
+                backdate_admin_only: false,
+                delete_requires_approval: false,
+                ..self
+            }
+        } else {
+            self
+        }
+    }
+}
+
+/// 記録のバリデーションエラー（修正候補の提示に使う情報を保持する）
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    FutureDate,
+    FutureTime {
+        timestamp: DateTime<Utc>,
+    },
+    TooOld {
+        max_days: i64,
+    },
+    /// 過去の日付の記録は管理者のみ追加・修正できる
+    BackdateNotAllowed,
+    DuplicateTime {
+        timestamp: DateTime<Utc>,
+        existing_record_id: i64,
+    },
+    ConsecutiveRecords {
+        position: usize,
+        record_type: RecordType,
+        count: usize,
+    },
+    /// 開始記録より前に終了記録がある（`conflicting` は順序が崩れる原因となった既存記録）
+    EndWithoutStart {
+        conflicting: Option<(i64, DateTime<Utc>)>,
+    },
+    /// 休憩・離席の記録が勤務中（開始〜終了の間）にない
+    OutsideWorkSession {
+        record_type: RecordType,
+    },
+    /// 休憩開始と休憩終了が交互になっていない（`record_type` は対応が取れなかった側）
+    UnmatchedBreak {
+        record_type: RecordType,
+    },
+    /// 月次締め済みの日の記録
+    Locked,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::FutureDate => write!(f, "未来の日付には記録できません"),
+            ValidationError::FutureTime { .. } => write!(f, "未来の時刻には記録できません"),
+            ValidationError::TooOld { max_days } => {
+                write!(f, "{}日より前の記録は追加・修正できません", max_days)
+            }
+            ValidationError::BackdateNotAllowed => {
+                write!(f, "過去の日付の記録は管理者のみ追加・修正できます")
+            }
+            ValidationError::DuplicateTime { timestamp, .. } => write!(
+                f,
+                "同じ時刻の記録が既に存在します: {}",
+                timestamp.format("%H:%M")
+            ),
+            ValidationError::ConsecutiveRecords {
+                position,
+                record_type,
+                count,
+            } => write!(
+                f,
+                "不正な順序: 位置{}で{}記録が{}回連続しています",
+                position,
+                record_type_label(*record_type, Lang::Ja),
+                count
+            ),
+            ValidationError::EndWithoutStart { .. } => {
+                write!(f, "不正な順序: 開始記録なしに終了記録があります")
+            }
+            ValidationError::OutsideWorkSession { record_type } => write!(
+                f,
+                "不正な順序: {}は勤務中にのみ記録できます",
+                record_type_label(*record_type, Lang::Ja)
+            ),
+            ValidationError::UnmatchedBreak {
+                record_type: RecordType::BreakStart,
+            } => write!(f, "不正な順序: 休憩中に休憩開始記録があります"),
+            ValidationError::UnmatchedBreak { .. } => {
+                write!(f, "不正な順序: 休憩開始記録なしに休憩終了記録があります")
+            }
+            ValidationError::Locked => write!(f, "締め済みの月の記録は変更できません"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// バリデーションエラーに対する修正候補
+#[derive(Debug, Clone, PartialEq)]
+pub enum CorrectionSuggestion {
+    /// 入力した時刻を前日の記録として扱う
+    PreviousDay { timestamp: DateTime<Utc> },
+    /// 順序・重複の原因となっている既存記録を修正する
+    EditRecord {
+        record_id: i64,
+        timestamp: DateTime<Utc>,
+    },
+}
+
+impl CorrectionSuggestion {
+    pub fn label(&self) -> String {
+        match self {
+            CorrectionSuggestion::PreviousDay { timestamp } => {
+                format!("{}ではなく前日扱いにする", format_time_jst(*timestamp))
+            }
+            CorrectionSuggestion::EditRecord { timestamp, .. } => {
+                format!("既存の{}記録を修正する", format_time_jst(*timestamp))
+            }
+        }
+    }
+}
+
+impl ValidationError {
+    /// 集計用のエラー種類（`validation_rejections.kind` に保存する）
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ValidationError::FutureDate => "future_date",
+            ValidationError::FutureTime { .. } => "future_time",
+            ValidationError::TooOld { .. } => "too_old",
+            ValidationError::BackdateNotAllowed => "backdate_not_allowed",
+            ValidationError::DuplicateTime { .. } => "duplicate_time",
+            ValidationError::ConsecutiveRecords { .. } => "consecutive_records",
+            ValidationError::EndWithoutStart { .. } => "end_without_start",
+            ValidationError::OutsideWorkSession { .. } => "outside_work_session",
+            ValidationError::UnmatchedBreak { .. } => "unmatched_break",
+            ValidationError::Locked => "locked",
+        }
+    }
+
+    /// `kind()` の表示名（不明な種類はそのまま返す）
+    pub fn kind_label(kind: &str) -> &str {
+        match kind {
+            "future_date" => "未来の日付",
+            "future_time" => "未来の時刻",
+            "too_old" => "記録可能期間外",
+            "backdate_not_allowed" => "過去の日付（管理者のみ）",
+            "duplicate_time" => "同じ時刻の記録",
+            "consecutive_records" => "開始・終了の連続",
+            "end_without_start" => "開始なしの終了",
+            "outside_work_session" => "勤務外の休憩・離席",
+            "unmatched_break" => "休憩の開始・終了の不一致",
+            "locked" => "締め済みの月",
+            _ => kind,
+        }
+    }
+
+    /// エラー内容から実行可能な修正候補を返す
+    pub fn suggestions(&self) -> Vec<CorrectionSuggestion> {
+        match self {
+            ValidationError::FutureTime { timestamp } => timestamp
+                .checked_sub_days(Days::new(1))
+                .map(|timestamp| CorrectionSuggestion::PreviousDay { timestamp })
+                .into_iter()
+                .collect(),
+            ValidationError::DuplicateTime {
+                timestamp,
+                existing_record_id,
+            } => vec![CorrectionSuggestion::EditRecord {
+                record_id: *existing_record_id,
+                timestamp: *timestamp,
+            }],
+            ValidationError::EndWithoutStart {
+                conflicting: Some((record_id, timestamp)),
+            } => vec![CorrectionSuggestion::EditRecord {
+                record_id: *record_id,
+                timestamp: *timestamp,
+            }],
+            _ => Vec::new(),
+        }
+    }
+}
 
 pub struct RecordValidator;
 
@@ -10,7 +232,7 @@ impl RecordValidator {
         existing_records: &[AttendanceRecord],
         new_record_type: RecordType,
         new_timestamp: DateTime<Utc>,
-    ) -> Result<()> {
+    ) -> Result<(), ValidationError> {
         // 時系列順にソート
         let mut sorted_records = existing_records.to_vec();
         sorted_records.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
@@ -23,20 +245,23 @@ impl RecordValidator {
             if !inserted && record.timestamp > new_timestamp {
                 // 新しい記録を挿入
                 all_records.push(MockRecord {
+                    id: None,
                     timestamp: new_timestamp,
                     record_type: new_record_type,
                 });
                 inserted = true;
             }
             all_records.push(MockRecord {
+                id: Some(record.id),
                 timestamp: record.timestamp,
-                record_type: RecordType::from(record.record_type.clone()),
+                record_type: record.record_type,
             });
         }
 
         // 最後に挿入されていない場合は末尾に追加
         if !inserted {
             all_records.push(MockRecord {
+                id: None,
                 timestamp: new_timestamp,
                 record_type: new_record_type,
             });
@@ -48,31 +273,30 @@ impl RecordValidator {
 
     /// 記録シーケンスの妥当性をチェック
     /// 複数の開始・終了記録を許可する柔軟なバリデーション
-    fn validate_sequence(records: &[MockRecord]) -> Result<()> {
+    fn validate_sequence(records: &[MockRecord]) -> Result<(), ValidationError> {
         if records.is_empty() {
             return Ok(()); // 空の記録は有効
         }
 
-        // 連続する同じタイプの記録をチェック
+        // 連続する同じタイプの記録をチェック（休憩・離席は開始・終了の並びに含めない）
         let mut last_record_type: Option<RecordType> = None;
         let mut consecutive_count = 0;
 
-        for (i, record) in records.iter().enumerate() {
+        for (i, record) in records
+            .iter()
+            .enumerate()
+            .filter(|(_, record)| record.record_type.is_session_boundary())
+        {
             match last_record_type {
                 Some(last_type) if last_type == record.record_type => {
                     consecutive_count += 1;
                     // 同じタイプが3回以上連続する場合は警告
                     if consecutive_count >= 3 {
-                        return Err(anyhow::anyhow!(
-                            "不正な順序: 位置{}で{}記録が{}回連続しています",
-                            i + 1,
-                            if record.record_type == RecordType::Start {
-                                "開始"
-                            } else {
-                                "終了"
-                            },
-                            consecutive_count + 1
-                        ));
+                        return Err(ValidationError::ConsecutiveRecords {
+                            position: i + 1,
+                            record_type: record.record_type,
+                            count: consecutive_count + 1,
+                        });
                     }
                 }
                 _ => {
@@ -83,11 +307,50 @@ impl RecordValidator {
         }
 
         // 最初の記録が終了記録の場合は警告
-        if let Some(first_record) = records.first() {
-            if first_record.record_type == RecordType::End {
-                return Err(anyhow::anyhow!(
-                    "不正な順序: 開始記録なしに終了記録があります"
-                ));
+        if let Some(first_record) = records
+            .iter()
+            .find(|record| record.record_type.is_session_boundary())
+            && first_record.record_type == RecordType::End
+        {
+            // 既存記録のうち最も早いものが順序崩れの原因
+            let conflicting = records
+                .iter()
+                .find_map(|record| record.id.map(|id| (id, record.timestamp)));
+            return Err(ValidationError::EndWithoutStart { conflicting });
+        }
+
+        Self::validate_breaks(records)
+    }
+
+    /// 休憩・離席は勤務中にのみ記録でき、休憩開始と休憩終了は交互になる
+    /// （休憩中に終了記録があれば休憩も終わったものとして扱う）
+    fn validate_breaks(records: &[MockRecord]) -> Result<(), ValidationError> {
+        let mut working = false;
+        let mut on_break = false;
+
+        for record in records {
+            let record_type = record.record_type;
+            match record_type {
+                RecordType::Start => {
+                    working = true;
+                    on_break = false;
+                }
+                RecordType::End => {
+                    working = false;
+                    on_break = false;
+                }
+                _ if !working => {
+                    return Err(ValidationError::OutsideWorkSession { record_type });
+                }
+                RecordType::BreakStart if on_break => {
+                    return Err(ValidationError::UnmatchedBreak { record_type });
+                }
+                RecordType::BreakStart => on_break = true,
+                RecordType::BreakEnd if !on_break => {
+                    return Err(ValidationError::UnmatchedBreak { record_type });
+                }
+                RecordType::BreakEnd => on_break = false,
+                RecordType::Away => {}
             }
         }
 
@@ -99,7 +362,7 @@ impl RecordValidator {
         existing_records: &[AttendanceRecord],
         new_timestamp: DateTime<Utc>,
         exclude_record_id: Option<i64>,
-    ) -> Result<()> {
+    ) -> Result<(), ValidationError> {
         for record in existing_records {
             // 修正対象の記録は除外
             if let Some(exclude_id) = exclude_record_id {
@@ -109,17 +372,21 @@ impl RecordValidator {
             }
 
             if record.timestamp == new_timestamp {
-                return Err(anyhow::anyhow!(
-                    "同じ時刻の記録が既に存在します: {}",
-                    new_timestamp.format("%H:%M")
-                ));
+                return Err(ValidationError::DuplicateTime {
+                    timestamp: new_timestamp,
+                    existing_record_id: record.id,
+                });
             }
         }
         Ok(())
     }
 
     /// 時間の妥当性をチェック（未来時刻、過度に古い時刻など）
-    pub fn validate_reasonable_time(new_time: NaiveTime, new_date: NaiveDate) -> Result<()> {
+    pub fn validate_reasonable_time(
+        new_time: NaiveTime,
+        new_date: NaiveDate,
+        policy: &EditPolicy,
+    ) -> Result<(), ValidationError> {
         let now = chrono::Utc::now();
         let jst_offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
         let now_jst = now.with_timezone(&jst_offset);
@@ -127,21 +394,33 @@ impl RecordValidator {
 
         // 未来の日付チェック
         if new_date > today_jst {
-            return Err(anyhow::anyhow!("未来の日付には記録できません"));
+            return Err(ValidationError::FutureDate);
         }
 
         // 今日の場合、未来の時刻チェック
         if new_date == today_jst {
             let current_time = now_jst.time();
             if new_time > current_time {
-                return Err(anyhow::anyhow!("未来の時刻には記録できません"));
+                return Err(ValidationError::FutureTime {
+                    timestamp: new_date
+                        .and_time(new_time)
+                        .and_local_timezone(jst_offset)
+                        .unwrap()
+                        .to_utc(),
+                });
             }
         }
 
-        // 過度に古い記録のチェック（7日以上前）
+        // 過度に古い記録のチェック（サーバーの設定日数より前）
         let days_ago = today_jst.signed_duration_since(new_date).num_days();
-        if days_ago > 7 {
-            return Err(anyhow::anyhow!("7日以上前の記録は追加できません"));
+        if days_ago > policy.max_age_days {
+            return Err(ValidationError::TooOld {
+                max_days: policy.max_age_days,
+            });
+        }
+
+        if days_ago > 0 && policy.backdate_admin_only {
+            return Err(ValidationError::BackdateNotAllowed);
         }
 
         Ok(())
@@ -154,12 +433,18 @@ impl RecordValidator {
         new_timestamp: DateTime<Utc>,
         new_date: NaiveDate,
         exclude_record_id: Option<i64>,
-    ) -> Result<()> {
+        policy: &EditPolicy,
+    ) -> Result<(), ValidationError> {
         let jst_offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
         let new_time_jst = new_timestamp.with_timezone(&jst_offset).time();
 
+        // 0. 締め済みチェック（締めた月の記録はすべてロックされている）
+        if existing_records.iter().any(|record| record.locked) {
+            return Err(ValidationError::Locked);
+        }
+
         // 1. 時間の妥当性チェック
-        Self::validate_reasonable_time(new_time_jst, new_date)?;
+        Self::validate_reasonable_time(new_time_jst, new_date, policy)?;
 
         // 2. 重複時間チェック
         Self::validate_no_duplicate_time(existing_records, new_timestamp, exclude_record_id)?;
@@ -169,10 +454,284 @@ impl RecordValidator {
 
         Ok(())
     }
+
+    /// CSV取り込み用のバリデーション（過去データの移行のため日数制限は適用しない）
+    pub fn validate_imported_record(
+        existing_records: &[AttendanceRecord],
+        new_record_type: RecordType,
+        new_timestamp: DateTime<Utc>,
+        new_date: NaiveDate,
+    ) -> Result<(), ValidationError> {
+        match Self::validate_new_record(
+            existing_records,
+            new_record_type,
+            new_timestamp,
+            new_date,
+            None,
+            &EditPolicy::default(),
+        ) {
+            // 日数制限は締め済み・未来時刻のチェックの後に判定されるので、残りのチェックだけ行う
+            Err(ValidationError::TooOld { .. }) => {
+                Self::validate_no_duplicate_time(existing_records, new_timestamp, None)?;
+                Self::validate_record_order(existing_records, new_record_type, new_timestamp)
+            }
+            result => result,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 struct MockRecord {
+    id: Option<i64>, // 新規・修正中の記録は None
     timestamp: DateTime<Utc>,
     record_type: RecordType,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::time::get_current_date_jst;
+    use chrono::TimeZone;
+
+    fn jst(hour: u32, minute: u32) -> DateTime<Utc> {
+        chrono::FixedOffset::east_opt(9 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2023, 12, 15, hour, minute, 0)
+            .unwrap()
+            .to_utc()
+    }
+
+    fn record(id: i64, record_type: &str, timestamp: DateTime<Utc>) -> AttendanceRecord {
+        AttendanceRecord {
+            id,
+            user_id: 1,
+            record_type: record_type.parse().unwrap(),
+            timestamp,
+            is_modified: false,
+            original_timestamp: None,
+            is_imported: false,
+            source: None,
+            locked: false,
+            session_id: None,
+            created_at: timestamp,
+            updated_at: timestamp,
+        }
+    }
+
+    #[test]
+    fn test_end_before_start_suggests_editing_existing_record() {
+        let records = vec![record(7, "start", jst(9, 0))];
+        let error = RecordValidator::validate_record_order(&records, RecordType::End, jst(8, 0))
+            .unwrap_err();
+
+        assert_eq!(
+            error,
+            ValidationError::EndWithoutStart {
+                conflicting: Some((7, jst(9, 0)))
+            }
+        );
+        let suggestions = error.suggestions();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].label(), "既存の09:00記録を修正する");
+    }
+
+    #[test]
+    fn test_future_time_suggests_previous_day() {
+        let error = ValidationError::FutureTime {
+            timestamp: jst(18, 0),
+        };
+        let suggestions = error.suggestions();
+
+        assert_eq!(
+            suggestions,
+            vec![CorrectionSuggestion::PreviousDay {
+                timestamp: jst(18, 0) - chrono::Duration::days(1)
+            }]
+        );
+        assert_eq!(suggestions[0].label(), "18:00ではなく前日扱いにする");
+    }
+
+    #[test]
+    fn test_duplicate_time_suggests_editing_existing_record() {
+        let records = vec![record(3, "start", jst(9, 0))];
+        let error =
+            RecordValidator::validate_no_duplicate_time(&records, jst(9, 0), None).unwrap_err();
+
+        assert_eq!(
+            error.suggestions(),
+            vec![CorrectionSuggestion::EditRecord {
+                record_id: 3,
+                timestamp: jst(9, 0)
+            }]
+        );
+        assert!(RecordValidator::validate_no_duplicate_time(&records, jst(9, 0), Some(3)).is_ok());
+    }
+
+    #[test]
+    fn test_locked_day_rejects_new_records() {
+        let mut locked = record(5, "start", jst(9, 0));
+        locked.locked = true;
+        let date = chrono::NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
+
+        let error = RecordValidator::validate_new_record(
+            &[locked],
+            RecordType::End,
+            jst(18, 0),
+            date,
+            None,
+            &EditPolicy::default(),
+        )
+        .unwrap_err();
+        assert_eq!(error, ValidationError::Locked);
+        assert!(error.suggestions().is_empty());
+    }
+
+    #[test]
+    fn test_breaks_must_be_inside_a_work_session() {
+        let records = vec![
+            record(1, "start", jst(9, 0)),
+            record(2, "break_start", jst(12, 0)),
+            record(3, "end", jst(18, 0)),
+        ];
+        let order = |record_type, timestamp| {
+            RecordValidator::validate_record_order(&records, record_type, timestamp)
+        };
+
+        assert!(order(RecordType::BreakEnd, jst(13, 0)).is_ok());
+        assert!(order(RecordType::Away, jst(10, 0)).is_ok());
+        assert_eq!(
+            order(RecordType::Away, jst(8, 0)),
+            Err(ValidationError::OutsideWorkSession {
+                record_type: RecordType::Away
+            })
+        );
+        assert_eq!(
+            order(RecordType::BreakStart, jst(19, 0)),
+            Err(ValidationError::OutsideWorkSession {
+                record_type: RecordType::BreakStart
+            })
+        );
+        assert_eq!(
+            order(RecordType::BreakStart, jst(13, 0)),
+            Err(ValidationError::UnmatchedBreak {
+                record_type: RecordType::BreakStart
+            })
+        );
+        assert_eq!(
+            order(RecordType::BreakEnd, jst(11, 0)),
+            Err(ValidationError::UnmatchedBreak {
+                record_type: RecordType::BreakEnd
+            })
+        );
+        // 休憩の記録は開始・終了の連続には数えない
+        assert!(order(RecordType::End, jst(12, 30)).is_ok());
+    }
+
+    #[test]
+    fn test_edit_policy_limits_past_dates() {
+        let today = get_current_date_jst();
+        let time = NaiveTime::from_hms_opt(0, 0, 0).unwrap();
+        let policy = EditPolicy {
+            max_age_days: 3,
+            backdate_admin_only: true,
+            delete_requires_approval: true,
+        };
+
+        assert_eq!(
+            RecordValidator::validate_reasonable_time(time, today - Days::new(4), &policy),
+            Err(ValidationError::TooOld { max_days: 3 })
+        );
+        assert_eq!(
+            RecordValidator::validate_reasonable_time(time, today - Days::new(1), &policy),
+            Err(ValidationError::BackdateNotAllowed)
+        );
+        assert!(RecordValidator::validate_reasonable_time(time, today, &policy).is_ok());
+
+        let admin = policy.for_actor(true);
+        assert!(
+            RecordValidator::validate_reasonable_time(time, today - Days::new(1), &admin).is_ok()
+        );
+        assert!(!admin.delete_requires_approval);
+        assert_eq!(admin.max_age_days, 3);
+    }
+
+    /// 記録を時系列に並べたときの期待結果（同じ種別の4連続、または終了記録から始まる場合はエラー）
+    fn expected_order_ok(types: &[RecordType]) -> bool {
+        let has_long_run = types.windows(4).any(|w| w.iter().all(|t| *t == w[0]));
+        !has_long_run && types.first() != Some(&RecordType::End)
+    }
+
+    fn record_type_strategy() -> impl proptest::strategy::Strategy<Value = RecordType> {
+        proptest::prop_oneof![
+            proptest::strategy::Just(RecordType::Start),
+            proptest::strategy::Just(RecordType::End)
+        ]
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_validate_record_order_matches_sequence_rules(
+            existing in proptest::collection::btree_map(0u32..1440, record_type_strategy(), 0..10),
+            new_minute in 0u32..1440,
+            new_type in record_type_strategy(),
+        ) {
+            let records: Vec<AttendanceRecord> = existing
+                .iter()
+                .enumerate()
+                .map(|(i, (minute, record_type))| {
+                    record(i as i64 + 1, record_type.as_str(), jst(minute / 60, minute % 60))
+                })
+                .collect();
+
+            // 同時刻の既存記録より後ろに挿入される
+            let mut types: Vec<RecordType> = existing
+                .iter()
+                .filter(|(minute, _)| **minute <= new_minute)
+                .map(|(_, record_type)| *record_type)
+                .collect();
+            types.push(new_type);
+            types.extend(
+                existing
+                    .iter()
+                    .filter(|(minute, _)| **minute > new_minute)
+                    .map(|(_, record_type)| *record_type),
+            );
+
+            let result = RecordValidator::validate_record_order(
+                &records,
+                new_type,
+                jst(new_minute / 60, new_minute % 60),
+            );
+            proptest::prop_assert_eq!(result.is_ok(), expected_order_ok(&types));
+
+            if let Err(ValidationError::EndWithoutStart { conflicting }) = result {
+                // 既存記録があれば、最も早い既存記録が修正候補になる
+                let earliest = records.first().map(|r| (r.id, r.timestamp));
+                proptest::prop_assert_eq!(conflicting, earliest);
+            }
+        }
+
+        #[test]
+        fn prop_alternating_records_are_always_valid(
+            count in 0usize..20,
+            gap in 1u32..60,
+        ) {
+            let records: Vec<AttendanceRecord> = (0..count)
+                .map(|i| {
+                    let minute = i as u32 * gap;
+                    let record_type = if i % 2 == 0 { "start" } else { "end" };
+                    record(i as i64 + 1, record_type, jst(minute / 60, minute % 60))
+                })
+                .collect();
+            let next_type = if count % 2 == 0 { RecordType::Start } else { RecordType::End };
+            let next_minute = count as u32 * gap;
+
+            proptest::prop_assert!(RecordValidator::validate_record_order(
+                &records,
+                next_type,
+                jst(next_minute / 60, next_minute % 60),
+            )
+            .is_ok());
+        }
+    }
+}
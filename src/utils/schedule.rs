@@ -0,0 +1,240 @@
+use crate::database::models::{
+    AttendanceRecord, RecordType, Shift, ShiftTemplate, WorkSchedule, WorkSession,
+};
+use crate::utils::time::combine_date_time_jst;
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc};
+use std::collections::BTreeMap;
+
+/// `WorkSchedule::weekday` の曜日名（0 = 月曜）
+pub const WEEKDAY_NAMES: [&str; 7] = ["月", "火", "水", "木", "金", "土", "日"];
+
+/// 開始予定時刻より遅く始めた日
+#[derive(Debug, Clone, PartialEq)]
+pub struct LateStart {
+    pub date: NaiveDate,
+    /// 開始予定時刻（JST 0時からの分数）
+    pub expected_minutes: i32,
+    /// その日の最初のセッションの開始時刻（JST 0時からの分数）
+    pub actual_minutes: i32,
+}
+
+impl LateStart {
+    pub fn late_minutes(&self) -> i32 {
+        self.actual_minutes - self.expected_minutes
+    }
+}
+
+/// 開始予定時刻のある曜日について、その日の最初のセッションが予定より遅く始まった日を返す
+pub fn find_late_starts(sessions: &[WorkSession], schedules: &[WorkSchedule]) -> Vec<LateStart> {
+    let jst_offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+    let mut first_starts: BTreeMap<NaiveDate, i32> = BTreeMap::new();
+    for session in sessions {
+        let start = session.start_time.with_timezone(&jst_offset);
+        let minutes = (start.hour() * 60 + start.minute()) as i32;
+        first_starts
+            .entry(session.date)
+            .and_modify(|first| *first = (*first).min(minutes))
+            .or_insert(minutes);
+    }
+
+    first_starts
+        .into_iter()
+        .filter_map(|(date, actual_minutes)| {
+            let weekday = date.weekday().num_days_from_monday() as i32;
+            let schedule = schedules.iter().find(|s| s.weekday == weekday)?;
+            (actual_minutes > schedule.start_minutes).then_some(LateStart {
+                date,
+                expected_minutes: schedule.start_minutes,
+                actual_minutes,
+            })
+        })
+        .collect()
+}
+
+/// シフト開始のこの分数前からの開始記録をシフトに対応する出勤とみなす
+pub const SHIFT_EARLY_START_MINUTES: i64 = 120;
+
+/// シフトに対する実際の出勤・退勤
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShiftAttendance {
+    pub actual_start: Option<DateTime<Utc>>,
+    pub actual_end: Option<DateTime<Utc>>,
+}
+
+impl ShiftAttendance {
+    /// 開始予定より遅れた分数（遅れていなければ `None`）
+    pub fn late_minutes(&self, shift: &Shift) -> Option<i64> {
+        let late = (self.actual_start? - shift.start_time).num_minutes();
+        (late > 0).then_some(late)
+    }
+}
+
+/// シフトに対応する打刻を探す（開始予定の2時間前からシフト終了までの最初の開始と、その後の最初の終了）
+pub fn match_shift_records(shift: &Shift, records: &[AttendanceRecord]) -> ShiftAttendance {
+    let window_start = shift.start_time - chrono::Duration::minutes(SHIFT_EARLY_START_MINUTES);
+    let mut sorted: Vec<_> = records.iter().collect();
+    sorted.sort_by_key(|record| record.timestamp);
+
+    let actual_start = sorted
+        .iter()
+        .find(|record| {
+            record.record_type == RecordType::Start
+                && record.timestamp >= window_start
+                && record.timestamp < shift.end_time
+        })
+        .map(|record| record.timestamp);
+    let actual_end = actual_start.and_then(|start| {
+        sorted
+            .iter()
+            .find(|record| record.record_type == RecordType::End && record.timestamp > start)
+            .map(|record| record.timestamp)
+    });
+
+    ShiftAttendance {
+        actual_start,
+        actual_end,
+    }
+}
+
+/// 勤務セッションからシフトに対応する出勤・退勤を探す（レポート用。開始予定の2時間前からシフト終了までに始まった最初のセッション）
+pub fn match_shift_sessions(shift: &Shift, sessions: &[WorkSession]) -> ShiftAttendance {
+    let window_start = shift.start_time - chrono::Duration::minutes(SHIFT_EARLY_START_MINUTES);
+    let session = sessions
+        .iter()
+        .filter(|session| session.start_time >= window_start && session.start_time < shift.end_time)
+        .min_by_key(|session| session.start_time);
+
+    ShiftAttendance {
+        actual_start: session.map(|session| session.start_time),
+        actual_end: session.and_then(|session| session.end_time),
+    }
+}
+
+/// ひな形を `from` から `to` まで（両端を含む）の各日に当てはめたシフトの開始・終了
+///
+/// `weekdays_only` なら土日は除く。終了が開始より前のひな形は翌日の終了になる
+pub fn plan_template_shifts(
+    template: &ShiftTemplate,
+    from: NaiveDate,
+    to: NaiveDate,
+    weekdays_only: bool,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let to_time = |minutes: i32| {
+        chrono::NaiveTime::from_hms_opt(minutes as u32 / 60, minutes as u32 % 60, 0)
+            .unwrap_or(chrono::NaiveTime::MIN)
+    };
+    from.iter_days()
+        .take_while(|date| *date <= to)
+        .filter(|date| !weekdays_only || date.weekday().num_days_from_monday() < 5)
+        .map(|date| {
+            let start = combine_date_time_jst(date, to_time(template.start_minutes));
+            let mut end = combine_date_time_jst(date, to_time(template.end_minutes));
+            if end <= start {
+                end += chrono::Duration::days(1);
+            }
+            (start, end)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn session(day: u32, hour: u32, minute: u32) -> WorkSession {
+        let start = chrono::FixedOffset::east_opt(9 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2023, 12, day, hour, minute, 0)
+            .unwrap()
+            .to_utc();
+        WorkSession {
+            id: 0,
+            user_id: 1,
+            start_time: start,
+            end_time: None,
+            total_minutes: None,
+            date: NaiveDate::from_ymd_opt(2023, 12, day).unwrap(),
+            is_completed: false,
+            note: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_find_late_starts() {
+        // 2023-12-11 は月曜日。予定は月〜水の 09:00
+        let schedules: Vec<_> = (0..3)
+            .map(|weekday| WorkSchedule {
+                user_id: 1,
+                weekday,
+                start_minutes: 9 * 60,
+            })
+            .collect();
+        let sessions = vec![
+            // 月曜: 09:20 開始 → 遅れ（後のセッションは見ない）
+            session(11, 9, 20),
+            session(11, 13, 0),
+            // 火曜: 最初のセッションは予定ちょうど
+            session(12, 9, 0),
+            session(12, 14, 0),
+            // 水曜: 予定より早い
+            session(13, 8, 45),
+            // 木曜: 予定なし
+            session(14, 11, 0),
+        ];
+
+        let late = find_late_starts(&sessions, &schedules);
+
+        assert_eq!(
+            late,
+            vec![LateStart {
+                date: NaiveDate::from_ymd_opt(2023, 12, 11).unwrap(),
+                expected_minutes: 540,
+                actual_minutes: 560,
+            }]
+        );
+        assert_eq!(late[0].late_minutes(), 20);
+    }
+
+    #[test]
+    fn test_plan_template_shifts() {
+        let template = ShiftTemplate {
+            id: 1,
+            guild_id: "1".to_string(),
+            name: "遅番".to_string(),
+            start_minutes: 22 * 60,
+            end_minutes: 7 * 60,
+        };
+        // 2023-12-08 は金曜日。平日のみなら土日を飛ばして金・月の2日分
+        let shifts = plan_template_shifts(
+            &template,
+            NaiveDate::from_ymd_opt(2023, 12, 8).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 12, 11).unwrap(),
+            true,
+        );
+
+        let jst = |day: u32, hour: u32| {
+            chrono::FixedOffset::east_opt(9 * 3600)
+                .unwrap()
+                .with_ymd_and_hms(2023, 12, day, hour, 0, 0)
+                .unwrap()
+                .to_utc()
+        };
+        assert_eq!(
+            shifts,
+            vec![(jst(8, 22), jst(9, 7)), (jst(11, 22), jst(12, 7))]
+        );
+        assert_eq!(
+            plan_template_shifts(
+                &template,
+                NaiveDate::from_ymd_opt(2023, 12, 8).unwrap(),
+                NaiveDate::from_ymd_opt(2023, 12, 11).unwrap(),
+                false,
+            )
+            .len(),
+            4
+        );
+    }
+}
@@ -1,21 +1,24 @@
+use crate::database::DbPool;
 use crate::database::models::{AttendanceRecord, RecordType};
 use crate::database::queries;
+use crate::utils::time::round_work_duration;
 use anyhow::Result;
 use chrono::{DateTime, NaiveDate, Utc};
-use sqlx::SqlitePool;
+use sqlx::Row;
 
 pub struct SessionManager {
-    pool: SqlitePool,
+    pool: DbPool,
 }
 
 impl SessionManager {
-    pub fn new(pool: SqlitePool) -> Self {
+    pub fn new(pool: DbPool) -> Self {
         Self { pool }
     }
 
     /// 指定ユーザーの指定日のセッションを再計算
     pub async fn recalculate_sessions(&self, user_id: i64, date: NaiveDate) -> Result<()> {
-        // 1. 既存のセッションをすべて削除
+        // 1. 引き継ぐ情報を退避してから既存のセッションをすべて削除
+        let preserved = self.load_session_metadata(user_id, date).await?;
         self.delete_existing_sessions(user_id, date).await?;
 
         // 2. その日の記録を取得（時系列順）
@@ -23,18 +26,62 @@ impl SessionManager {
 
         // 3. 記録からセッションを再構築
         let sessions = self.build_sessions_from_records(records)?;
+        let rounding_minutes = queries::get_rounding_minutes(&self.pool, user_id).await?;
 
         // 4. 新しいセッションをデータベースに保存
         for session_data in sessions {
-            self.create_session(user_id, session_data, date).await?;
+            let metadata = preserved
+                .iter()
+                .find(|metadata| metadata.matches(&session_data))
+                .cloned()
+                .unwrap_or_default();
+            self.create_session(user_id, session_data, &metadata, rounding_minutes, date)
+                .await?;
         }
 
         Ok(())
     }
 
-    /// 既存のセッションを削除
+    /// 再計算後も引き継ぐセッションの情報を取得
+    async fn load_session_metadata(
+        &self,
+        user_id: i64,
+        date: NaiveDate,
+    ) -> Result<Vec<SessionMetadata>> {
+        let rows = sqlx::query(
+            "SELECT start_record_id, start_time, project_id, note, long_session_reminded
+             FROM work_sessions
+             WHERE user_id = $1 AND date = $2
+               AND (project_id IS NOT NULL OR note IS NOT NULL OR long_session_reminded = TRUE)",
+        )
+        .bind(user_id)
+        .bind(date)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| SessionMetadata {
+                start_record_id: row.get("start_record_id"),
+                start_time: row.get("start_time"),
+                project_id: row.get("project_id"),
+                note: row.get("note"),
+                long_session_reminded: row.get("long_session_reminded"),
+            })
+            .collect())
+    }
+
+    /// 既存のセッションを削除（記録からの紐づけも外す）
     async fn delete_existing_sessions(&self, user_id: i64, date: NaiveDate) -> Result<()> {
-        sqlx::query("DELETE FROM work_sessions WHERE user_id = ? AND date = ?")
+        sqlx::query(
+            "UPDATE attendance_records SET session_id = NULL
+             WHERE session_id IN (SELECT id FROM work_sessions WHERE user_id = $1 AND date = $2)",
+        )
+        .bind(user_id)
+        .bind(date)
+        .execute(&self.pool)
+        .await?;
+        sqlx::query("DELETE FROM work_sessions WHERE user_id = $1 AND date = $2")
             .bind(user_id)
             .bind(date)
             .execute(&self.pool)
@@ -49,10 +96,10 @@ impl SessionManager {
         records: Vec<AttendanceRecord>,
     ) -> Result<Vec<SessionData>> {
         let mut sessions = Vec::new();
-        let mut current_start: Option<DateTime<Utc>> = None;
+        let mut current_start: Option<(i64, DateTime<Utc>)> = None;
 
         for record in records {
-            match RecordType::from(record.record_type) {
+            match record.record_type {
                 RecordType::Start => {
                     // 既に開始済みの場合は警告（後で検証機能で対応）
                     if current_start.is_some() {
@@ -62,10 +109,10 @@ impl SessionManager {
                             record.id
                         );
                     }
-                    current_start = Some(record.timestamp);
+                    current_start = Some((record.id, record.timestamp));
                 }
                 RecordType::End => {
-                    if let Some(start_time) = current_start.take() {
+                    if let Some((start_record_id, start_time)) = current_start.take() {
                         // ペア完成
                         let total_minutes = record
                             .timestamp
@@ -73,6 +120,8 @@ impl SessionManager {
                             .num_minutes() as i32;
 
                         sessions.push(SessionData {
+                            start_record_id,
+                            end_record_id: Some(record.id),
                             start_time,
                             end_time: Some(record.timestamp),
                             total_minutes: Some(total_minutes),
@@ -87,12 +136,16 @@ impl SessionManager {
                         );
                     }
                 }
+                // 休憩・離席はセッションを区切らない
+                RecordType::BreakStart | RecordType::BreakEnd | RecordType::Away => {}
             }
         }
 
         // 未完了のセッション（開始のみ）
-        if let Some(start_time) = current_start {
+        if let Some((start_record_id, start_time)) = current_start {
             sessions.push(SessionData {
+                start_record_id,
+                end_record_id: None,
                 start_time,
                 end_time: None,
                 total_minutes: None,
@@ -103,25 +156,47 @@ impl SessionManager {
         Ok(sessions)
     }
 
-    /// セッションをデータベースに作成
+    /// セッションをデータベースに作成し、開始・終了記録から紐づける（丸め単位があれば `total_minutes` は丸めた時間、`raw_minutes` は元の時間）
     async fn create_session(
         &self,
         user_id: i64,
         session_data: SessionData,
+        metadata: &SessionMetadata,
+        rounding_minutes: Option<i32>,
         date: NaiveDate,
     ) -> Result<()> {
-        sqlx::query(
-            "INSERT INTO work_sessions (user_id, start_time, end_time, total_minutes, date, is_completed)
-             VALUES (?, ?, ?, ?, ?, ?)"
+        let total_minutes = match (session_data.end_time, rounding_minutes) {
+            (Some(end_time), Some(unit)) => {
+                Some(round_work_duration(session_data.start_time, end_time, unit))
+            }
+            _ => session_data.total_minutes,
+        };
+        let row = sqlx::query(
+            "INSERT INTO work_sessions (user_id, start_time, end_time, total_minutes, raw_minutes, date, is_completed, start_record_id, project_id, note, long_session_reminded, guild_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, (SELECT guild_id FROM users WHERE id = $1))
+             RETURNING id"
         )
         .bind(user_id)
         .bind(session_data.start_time)
         .bind(session_data.end_time)
+        .bind(total_minutes)
         .bind(session_data.total_minutes)
         .bind(date)
         .bind(session_data.is_completed)
-        .execute(&self.pool)
+        .bind(session_data.start_record_id)
+        .bind(metadata.project_id)
+        .bind(&metadata.note)
+        .bind(metadata.long_session_reminded)
+        .fetch_one(&self.pool)
         .await?;
+        let session_id: i64 = row.get("id");
+
+        sqlx::query("UPDATE attendance_records SET session_id = $1 WHERE id = $2 OR id = $3")
+            .bind(session_id)
+            .bind(session_data.start_record_id)
+            .bind(session_data.end_record_id)
+            .execute(&self.pool)
+            .await?;
 
         Ok(())
     }
@@ -143,8 +218,128 @@ impl SessionManager {
 
 #[derive(Debug)]
 struct SessionData {
+    start_record_id: i64,
+    end_record_id: Option<i64>,
     start_time: DateTime<Utc>,
     end_time: Option<DateTime<Utc>>,
     total_minutes: Option<i32>,
     is_completed: bool,
 }
+
+/// 再計算で作り直すセッションに引き継ぐ情報
+#[derive(Debug, Clone, Default)]
+struct SessionMetadata {
+    start_record_id: Option<i64>,
+    start_time: Option<DateTime<Utc>>,
+    project_id: Option<i64>,
+    note: Option<String>,
+    long_session_reminded: bool,
+}
+
+impl SessionMetadata {
+    /// 開始記録が同じセッションか（開始記録IDが無い旧データは開始時刻で判定）
+    fn matches(&self, session: &SessionData) -> bool {
+        match self.start_record_id {
+            Some(id) => id == session.start_record_id,
+            None => self.start_time == Some(session.start_time),
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "postgres")))]
+mod tests {
+    use super::*;
+    use crate::database::create_test_pool;
+    use crate::database::models::AuditSource;
+    use crate::utils::time::get_date_from_utc_timestamp;
+
+    #[tokio::test]
+    async fn test_recalculation_links_records_to_session() {
+        let pool = create_test_pool().await;
+        let audit = AuditSource::new("1", "/start");
+        let user = queries::create_or_get_user(&pool, "1", None::<&str>, "user")
+            .await
+            .unwrap();
+        let start = chrono::Utc::now() - chrono::Duration::hours(2);
+        let date = get_date_from_utc_timestamp(start);
+        let start_record =
+            queries::create_attendance_record(&pool, user.id, RecordType::Start, start, &audit)
+                .await
+                .unwrap();
+        let end_record = queries::create_attendance_record(
+            &pool,
+            user.id,
+            RecordType::End,
+            start + chrono::Duration::minutes(30),
+            &audit,
+        )
+        .await
+        .unwrap();
+        let manager = SessionManager::new(pool.clone());
+        manager.recalculate_sessions(user.id, date).await.unwrap();
+
+        let session = queries::get_work_session_for_record(&pool, end_record.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.start_time, start);
+        assert_eq!(
+            queries::get_work_session_for_record(&pool, start_record.id)
+                .await
+                .unwrap()
+                .map(|session| session.id),
+            Some(session.id)
+        );
+
+        // 終了記録を消すと、開始記録は作り直した未終了のセッションに付け替わる
+        let audit = AuditSource::new("1", "test");
+        assert!(
+            queries::delete_attendance_record(&pool, user.id, end_record.id, &audit)
+                .await
+                .unwrap()
+        );
+        manager.recalculate_sessions(user.id, date).await.unwrap();
+        let reopened = queries::get_work_session_for_record(&pool, start_record.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!reopened.is_completed);
+    }
+
+    #[tokio::test]
+    async fn test_long_session_reminder_survives_recalculation() {
+        let pool = create_test_pool().await;
+        let audit = AuditSource::new("1", "/start");
+        let user = queries::create_or_get_user(&pool, "1", None::<&str>, "user")
+            .await
+            .unwrap();
+        let now = chrono::Utc::now();
+        let start = now - chrono::Duration::hours(11);
+        let date = get_date_from_utc_timestamp(start);
+        queries::create_attendance_record(&pool, user.id, RecordType::Start, start, &audit)
+            .await
+            .unwrap();
+        let manager = SessionManager::new(pool.clone());
+        manager.recalculate_sessions(user.id, date).await.unwrap();
+
+        let due = queries::get_unreminded_open_sessions(&pool, now - chrono::Duration::days(2), 10)
+            .await
+            .unwrap();
+        assert_eq!(due.len(), 1);
+        let (_, session, hours) = &due[0];
+        assert_eq!(session.start_time, start);
+        assert_eq!(*hours, 10);
+
+        // 通知済みの印は、作り直したセッションにも引き継がれる
+        queries::mark_long_session_reminded(&pool, session.id)
+            .await
+            .unwrap();
+        manager.recalculate_sessions(user.id, date).await.unwrap();
+        assert!(
+            queries::get_unreminded_open_sessions(&pool, now - chrono::Duration::days(2), 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
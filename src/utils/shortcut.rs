@@ -0,0 +1,147 @@
+use crate::utils::validation::normalize_input;
+use anyhow::{Result, bail};
+use chrono::NaiveTime;
+use std::fmt;
+
+/// 1つのショートカットに登録できる手順の上限
+pub const MAX_SHORTCUT_STEPS: usize = 5;
+/// ショートカット名の最大文字数
+pub const MAX_SHORTCUT_NAME_LENGTH: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportKind {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// ショートカットの1手順（時刻未指定の場合は実行時の現在時刻）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShortcutAction {
+    Start(Option<NaiveTime>),
+    End(Option<NaiveTime>),
+    Report(ReportKind),
+}
+
+impl fmt::Display for ShortcutAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShortcutAction::Start(None) => write!(f, "start"),
+            ShortcutAction::Start(Some(time)) => write!(f, "start {}", time.format("%H:%M")),
+            ShortcutAction::End(None) => write!(f, "end"),
+            ShortcutAction::End(Some(time)) => write!(f, "end {}", time.format("%H:%M")),
+            ShortcutAction::Report(ReportKind::Daily) => write!(f, "daily"),
+            ShortcutAction::Report(ReportKind::Weekly) => write!(f, "weekly"),
+            ShortcutAction::Report(ReportKind::Monthly) => write!(f, "monthly"),
+        }
+    }
+}
+
+/// `;` 区切りの手順を解析する（例: `end 18:00; daily`）
+pub fn parse_shortcut(action: &str) -> Result<Vec<ShortcutAction>> {
+    let mut actions = Vec::new();
+
+    for step in action.split(';') {
+        let mut tokens = step.split_whitespace();
+        let Some(command) = tokens.next() else {
+            bail!("空の手順があります");
+        };
+        let argument = tokens.next();
+        if tokens.next().is_some() {
+            bail!("手順「{}」の引数が多すぎます", step.trim());
+        }
+
+        let action = match (command.to_lowercase().as_str(), argument) {
+            ("start", time) => ShortcutAction::Start(time.map(parse_step_time).transpose()?),
+            ("end", time) => ShortcutAction::End(time.map(parse_step_time).transpose()?),
+            ("daily", None) => ShortcutAction::Report(ReportKind::Daily),
+            ("weekly", None) => ShortcutAction::Report(ReportKind::Weekly),
+            ("monthly", None) => ShortcutAction::Report(ReportKind::Monthly),
+            ("daily" | "weekly" | "monthly", Some(_)) => {
+                bail!("手順「{}」は引数を取りません", command)
+            }
+            _ => bail!(
+                "不明な手順「{}」です（start [HH:MM], end [HH:MM], daily, weekly, monthly が使えます）",
+                command
+            ),
+        };
+        actions.push(action);
+    }
+
+    if actions.len() > MAX_SHORTCUT_STEPS {
+        bail!("手順は{}個までです", MAX_SHORTCUT_STEPS);
+    }
+
+    Ok(actions)
+}
+
+/// 手順を保存用の正規化した文字列にする
+pub fn format_shortcut(actions: &[ShortcutAction]) -> String {
+    actions
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// ボタンの custom_id に埋め込めるショートカット名か確認する
+pub fn validate_shortcut_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.chars().count() > MAX_SHORTCUT_NAME_LENGTH {
+        bail!(
+            "ショートカット名は1〜{}文字で指定してください",
+            MAX_SHORTCUT_NAME_LENGTH
+        );
+    }
+    if name.contains(':') || name.chars().any(char::is_whitespace) {
+        bail!("ショートカット名に空白や「:」は使えません");
+    }
+    Ok(())
+}
+
+fn parse_step_time(time: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(&normalize_input(time), "%H:%M")
+        .map_err(|_| anyhow::anyhow!("時刻「{}」は HH:MM 形式で指定してください", time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shortcut() {
+        let actions = parse_shortcut("END 18:00;  daily ").unwrap();
+        assert_eq!(
+            actions,
+            vec![
+                ShortcutAction::End(Some(NaiveTime::from_hms_opt(18, 0, 0).unwrap())),
+                ShortcutAction::Report(ReportKind::Daily),
+            ]
+        );
+        assert_eq!(format_shortcut(&actions), "end 18:00; daily");
+        assert_eq!(
+            parse_shortcut("start").unwrap(),
+            vec![ShortcutAction::Start(None)]
+        );
+    }
+
+    #[test]
+    fn test_parse_shortcut_errors() {
+        assert!(parse_shortcut("").is_err());
+        assert!(parse_shortcut("end;").is_err());
+        assert!(parse_shortcut("end 25:00").is_err());
+        assert!(parse_shortcut("end 18:00 now").is_err());
+        assert!(parse_shortcut("daily 18:00").is_err());
+        assert!(parse_shortcut("lunch").is_err());
+        assert!(parse_shortcut("start; end; start; end; start; end").is_err());
+    }
+
+    #[test]
+    fn test_validate_shortcut_name() {
+        assert!(validate_shortcut_name("退勤").is_ok());
+        assert!(validate_shortcut_name("end-day").is_ok());
+        assert!(validate_shortcut_name("").is_err());
+        assert!(validate_shortcut_name("a:b").is_err());
+        assert!(validate_shortcut_name("a b").is_err());
+        assert!(validate_shortcut_name(&"a".repeat(33)).is_err());
+    }
+}
@@ -0,0 +1,546 @@
+use crate::database::models::{DailySpan, DailyTotal, Rate, SessionAggregates, User, WorkSession};
+use crate::utils::overtime::{OvertimeThresholds, split_daily_overtime, split_overtime};
+use crate::utils::time::{get_date_from_utc_timestamp, get_month_range};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, Timelike, Utc, Weekday};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// 勤務セッション一覧の集計値
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SessionStats {
+    pub total_minutes: i32,
+    pub session_count: usize,
+    pub incomplete_count: usize,
+    pub working_days: usize,
+}
+
+impl SessionStats {
+    pub fn from_sessions(sessions: &[WorkSession]) -> Self {
+        let working_days: BTreeSet<_> = sessions.iter().map(|s| s.date).collect();
+
+        Self {
+            total_minutes: sessions.iter().filter_map(|s| s.total_minutes).sum(),
+            session_count: sessions.len(),
+            incomplete_count: sessions.iter().filter(|s| !s.is_completed).count(),
+            working_days: working_days.len(),
+        }
+    }
+
+    /// 勤務日1日あたりの平均勤務時間（分）
+    pub fn average_minutes_per_day(&self) -> i32 {
+        if self.working_days == 0 {
+            0
+        } else {
+            self.total_minutes / self.working_days as i32
+        }
+    }
+}
+
+/// `/stats` で表示する勤務の傾向
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkPatternStats {
+    pub aggregates: SessionAggregates,
+    /// 1日の最初の開始時刻の平均（JSTの0時からの分）
+    pub average_start_minutes: Option<i32>,
+    /// 1日の最後の終了時刻の平均（勤務日の0時からの分。日付をまたぐと24時間を超える）
+    pub average_end_minutes: Option<i32>,
+    /// 最初の開始が `late_after` より後だった日数
+    pub late_starts: usize,
+    pub late_after: NaiveTime,
+}
+
+impl WorkPatternStats {
+    pub fn calculate(
+        aggregates: SessionAggregates,
+        spans: &[DailySpan],
+        late_after: NaiveTime,
+    ) -> Self {
+        let minutes_of_day = |date: NaiveDate, timestamp: DateTime<Utc>| {
+            let days = (get_date_from_utc_timestamp(timestamp) - date).num_days() as i32;
+            let time = (timestamp + chrono::Duration::hours(9)).time();
+            days * 24 * 60 + (time.hour() * 60 + time.minute()) as i32
+        };
+        let average = |minutes: Vec<i32>| {
+            (!minutes.is_empty()).then(|| minutes.iter().sum::<i32>() / minutes.len() as i32)
+        };
+
+        let starts: Vec<i32> = spans
+            .iter()
+            .map(|span| minutes_of_day(span.date, span.first_start))
+            .collect();
+        let late_after_minutes = (late_after.hour() * 60 + late_after.minute()) as i32;
+
+        Self {
+            aggregates,
+            late_starts: starts
+                .iter()
+                .filter(|minutes| **minutes > late_after_minutes)
+                .count(),
+            average_start_minutes: average(starts),
+            average_end_minutes: average(
+                spans
+                    .iter()
+                    .filter_map(|span| span.last_end.map(|end| minutes_of_day(span.date, end)))
+                    .collect(),
+            ),
+            late_after,
+        }
+    }
+
+    /// 勤務日1日あたりの平均勤務時間（分）
+    pub fn average_minutes_per_day(&self) -> i32 {
+        if self.aggregates.working_days == 0 {
+            0
+        } else {
+            (self.aggregates.total_minutes / self.aggregates.working_days) as i32
+        }
+    }
+}
+
+/// 時給設定に基づく給与見積もり
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PayEstimate {
+    pub regular_minutes: i32,
+    pub overtime_minutes: i32,
+    pub regular_pay: i64,
+    pub overtime_pay: i64,
+}
+
+impl PayEstimate {
+    /// 所定労働時間を超えた分を時間外の時給で計算する（1円未満は切り捨て）
+    pub fn calculate(
+        sessions: &[WorkSession],
+        rate: &Rate,
+        thresholds: OvertimeThresholds,
+    ) -> Self {
+        let split = split_overtime(sessions, thresholds);
+
+        Self {
+            regular_minutes: split.regular_minutes,
+            overtime_minutes: split.overtime_minutes,
+            regular_pay: split.regular_minutes as i64 * rate.hourly_rate / 60,
+            overtime_pay: split.overtime_minutes as i64 * rate.overtime_rate / 60,
+        }
+    }
+
+    pub fn total_pay(&self) -> i64 {
+        self.regular_pay + self.overtime_pay
+    }
+}
+
+/// 今月のここまでのペースから見た月末時点の勤務時間の見込み（分）
+///
+/// 営業日は土日を除いた平日とし、今日は勤務があれば経過日、なければ残りの日として数える
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonthForecast {
+    pub worked_minutes: i32,
+    pub elapsed_days: i32,
+    pub remaining_days: i32,
+    pub target_minutes: i32,
+}
+
+impl MonthForecast {
+    /// `sessions` は今月分（進行中のセッションは含めない）
+    pub fn calculate(sessions: &[WorkSession], today: NaiveDate, target_minutes: i32) -> Self {
+        let (start_of_month, end_of_month) = get_month_range(today);
+        let worked_today = sessions
+            .iter()
+            .any(|session| session.date == today && session.is_completed);
+        let today_is_weekday = is_weekday(today);
+
+        let mut elapsed_days = count_weekdays(start_of_month, today.pred_opt().unwrap_or(today));
+        let mut remaining_days = count_weekdays(today.succ_opt().unwrap_or(today), end_of_month);
+        if worked_today {
+            elapsed_days += 1;
+        } else if today_is_weekday {
+            remaining_days += 1;
+        }
+
+        Self {
+            worked_minutes: sessions.iter().filter_map(|s| s.total_minutes).sum(),
+            elapsed_days,
+            remaining_days,
+            target_minutes,
+        }
+    }
+
+    /// ここまでの営業日1日あたりの平均勤務時間
+    pub fn average_minutes_per_day(&self) -> i32 {
+        if self.elapsed_days == 0 {
+            0
+        } else {
+            self.worked_minutes / self.elapsed_days
+        }
+    }
+
+    /// 今のペースが続いた場合の月末の合計
+    pub fn projected_minutes(&self) -> i32 {
+        self.worked_minutes + self.average_minutes_per_day() * self.remaining_days
+    }
+
+    /// 目標に届くために残りの営業日1日あたりに必要な時間（残りが無い場合は `None`）
+    pub fn required_minutes_per_day(&self) -> Option<i32> {
+        (self.remaining_days > 0)
+            .then(|| (self.target_minutes - self.worked_minutes).max(0) / self.remaining_days)
+    }
+}
+
+/// 所定労働時間 × 月の平日数（`/forecast` の目標の既定値）
+pub fn standard_month_minutes(date: NaiveDate, daily_minutes: i32) -> i32 {
+    let (start_of_month, end_of_month) = get_month_range(date);
+    count_weekdays(start_of_month, end_of_month) * daily_minutes
+}
+
+/// 実績がこの割合（%）に達したら月間上限に近づいているとみなす
+pub const CAP_WARNING_PERCENT: i32 = 90;
+
+/// 月間上限に対する状況（`hour_caps.warned_level` に値を保存する）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CapLevel {
+    Within = 0,
+    /// 実績が上限の90%に達したか、月末の見込みが上限を超える
+    Approaching = 1,
+    /// 実績が上限を超えた
+    Exceeded = 2,
+}
+
+impl CapLevel {
+    /// `forecast` は上限を目標として計算したもの
+    pub fn evaluate(forecast: &MonthForecast) -> Self {
+        let cap = forecast.target_minutes;
+        if forecast.worked_minutes > cap {
+            Self::Exceeded
+        } else if forecast.worked_minutes * 100 >= cap * CAP_WARNING_PERCENT
+            || forecast.projected_minutes() > cap
+        {
+            Self::Approaching
+        } else {
+            Self::Within
+        }
+    }
+}
+
+/// 各セッションのうち月間上限を超えた分（日時順に積み上げ、上限を跨いだセッションは超えた分のみ）
+pub fn over_cap_minutes(sessions: &[WorkSession], cap_minutes: i32) -> Vec<i32> {
+    let mut total = 0;
+    sessions
+        .iter()
+        .map(|session| {
+            let minutes = session.total_minutes.unwrap_or(0);
+            let before = total;
+            total += minutes;
+            (total - cap_minutes.max(before)).clamp(0, minutes)
+        })
+        .collect()
+}
+
+/// `/admin monthly` で表示するメンバーごとの月間集計
+#[derive(Debug, Clone)]
+pub struct MemberMonthSummary {
+    pub user: User,
+    pub total_minutes: i32,
+    pub overtime_minutes: i32,
+    pub working_days: usize,
+    pub incomplete_sessions: i32,
+}
+
+impl MemberMonthSummary {
+    /// `queries::get_guild_daily_totals` の結果（ユーザー順）をメンバーごとにまとめ、勤務時間の多い順に返す
+    pub fn from_daily_totals(
+        totals: &[(User, DailyTotal)],
+        thresholds: OvertimeThresholds,
+    ) -> Vec<Self> {
+        let mut members: Vec<(User, BTreeMap<NaiveDate, i32>, i32)> = Vec::new();
+        for (user, total) in totals {
+            if members.last().is_none_or(|(last, _, _)| last.id != user.id) {
+                members.push((user.clone(), BTreeMap::new(), 0));
+            }
+            let Some((_, daily_minutes, incomplete)) = members.last_mut() else {
+                continue;
+            };
+            daily_minutes.insert(total.date, total.total_minutes as i32);
+            *incomplete += total.incomplete_sessions as i32;
+        }
+
+        let mut summaries: Vec<Self> = members
+            .into_iter()
+            .map(|(user, daily_minutes, incomplete_sessions)| Self {
+                total_minutes: daily_minutes.values().sum(),
+                overtime_minutes: split_daily_overtime(&daily_minutes, thresholds).overtime_minutes,
+                working_days: daily_minutes.len(),
+                incomplete_sessions,
+                user,
+            })
+            .collect();
+        summaries.sort_by(|a, b| {
+            b.total_minutes
+                .cmp(&a.total_minutes)
+                .then(a.user.id.cmp(&b.user.id))
+        });
+        summaries
+    }
+}
+
+fn is_weekday(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// `start` から `end` まで（両端を含む）の平日の数
+fn count_weekdays(start: NaiveDate, end: NaiveDate) -> i32 {
+    start
+        .iter_days()
+        .take_while(|date| *date <= end)
+        .filter(|date| is_weekday(*date))
+        .count() as i32
+}
+
+/// 間隔が `gap_minutes` 未満の同日の完了済みセッションを表示用に1つへまとめる
+///
+/// まとめたセッションの勤務時間は各セッションの合計（間隔は含まない）、メモは " / " でつなぐ。元のデータは変更しない
+pub fn merge_close_sessions(sessions: &[WorkSession], gap_minutes: i64) -> Vec<WorkSession> {
+    let mut merged: Vec<WorkSession> = Vec::new();
+
+    for session in sessions {
+        if let Some(last) = merged.last_mut()
+            && last.date == session.date
+            && let Some(last_end) = last.end_time
+            && session.is_completed
+            && (session.start_time - last_end).num_minutes() < gap_minutes
+        {
+            last.end_time = session.end_time;
+            last.total_minutes = match (last.total_minutes, session.total_minutes) {
+                (Some(a), Some(b)) => Some(a + b),
+                (a, b) => a.or(b),
+            };
+            last.note = match (last.note.take(), &session.note) {
+                (Some(a), Some(b)) => Some(format!("{} / {}", a, b)),
+                (a, b) => a.or_else(|| b.clone()),
+            };
+            continue;
+        }
+        merged.push(session.clone());
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+
+    fn jst(hour: u32, minute: u32) -> DateTime<Utc> {
+        chrono::FixedOffset::east_opt(9 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2023, 12, 15, hour, minute, 0)
+            .unwrap()
+            .to_utc()
+    }
+
+    /// 2023年12月 `day` 日の JST の時刻（24時以降は翌日）
+    fn jst_on(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        jst(0, 0)
+            + chrono::Duration::days(day as i64 - 15)
+            + chrono::Duration::minutes((hour * 60 + minute) as i64)
+    }
+
+    fn session(start: DateTime<Utc>, end: Option<DateTime<Utc>>) -> WorkSession {
+        WorkSession {
+            id: 0,
+            user_id: 1,
+            start_time: start,
+            end_time: end,
+            total_minutes: end.map(|end| (end - start).num_minutes() as i32),
+            date: NaiveDate::from_ymd_opt(2023, 12, 15).unwrap(),
+            is_completed: end.is_some(),
+            note: None,
+            created_at: start,
+            updated_at: start,
+        }
+    }
+
+    #[test]
+    fn test_month_forecast() {
+        // 2023-12-15 は金曜日。12月の平日は21日で、14日までに10日経過している
+        let today = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
+        let mut sessions: Vec<_> = [1, 4, 5, 6, 7, 8, 11, 12, 13, 14]
+            .into_iter()
+            .map(|day| WorkSession {
+                date: NaiveDate::from_ymd_opt(2023, 12, day).unwrap(),
+                ..session(jst(9, 0), Some(jst(17, 0)))
+            })
+            .collect();
+        let forecast = MonthForecast::calculate(&sessions, today, 160 * 60);
+
+        assert_eq!(forecast.elapsed_days, 10);
+        assert_eq!(forecast.remaining_days, 11);
+        assert_eq!(forecast.average_minutes_per_day(), 8 * 60);
+        assert_eq!(forecast.projected_minutes(), 21 * 8 * 60);
+        assert_eq!(forecast.required_minutes_per_day(), Some(80 * 60 / 11));
+        assert_eq!(standard_month_minutes(today, 8 * 60), 21 * 8 * 60);
+
+        // 今日の勤務があれば今日は経過日として数える
+        sessions.push(session(jst(9, 0), Some(jst(13, 0))));
+        let forecast = MonthForecast::calculate(&sessions, today, 160 * 60);
+        assert_eq!((forecast.elapsed_days, forecast.remaining_days), (11, 10));
+        assert_eq!(forecast.average_minutes_per_day(), 84 * 60 / 11);
+    }
+
+    #[test]
+    fn test_cap_level_and_over_cap_minutes() {
+        let today = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
+        let sessions: Vec<_> = [1, 4, 5, 6, 7, 8, 11, 12, 13, 14]
+            .into_iter()
+            .map(|day| WorkSession {
+                date: NaiveDate::from_ymd_opt(2023, 12, day).unwrap(),
+                ..session(jst(9, 0), Some(jst(17, 0)))
+            })
+            .collect();
+
+        // 実績80時間・見込み168時間
+        let level = |cap_hours: i32| {
+            CapLevel::evaluate(&MonthForecast::calculate(&sessions, today, cap_hours * 60))
+        };
+        assert_eq!(level(200), CapLevel::Within);
+        assert_eq!(level(160), CapLevel::Approaching);
+        assert_eq!(level(85), CapLevel::Approaching);
+        assert_eq!(level(75), CapLevel::Exceeded);
+
+        // 上限75時間: 10件目の途中（3時間）から超過する
+        let over = over_cap_minutes(&sessions, 75 * 60);
+        assert_eq!(over[..9], [0; 9]);
+        assert_eq!(over[9], 5 * 60);
+        assert_eq!(over_cap_minutes(&sessions, 70 * 60)[8..], [2 * 60, 8 * 60]);
+    }
+
+    #[test]
+    fn test_work_pattern_stats() {
+        let span = |day: u32, start: (u32, u32), end: (u32, u32)| DailySpan {
+            date: NaiveDate::from_ymd_opt(2023, 12, day).unwrap(),
+            first_start: jst_on(day, start.0, start.1),
+            last_end: Some(jst_on(day, end.0, end.1)),
+        };
+        // 3日目は翌日 1:00 まで勤務
+        let spans = vec![
+            span(1, (9, 0), (18, 0)),
+            span(2, (9, 30), (18, 30)),
+            span(3, (10, 0), (25, 0)),
+        ];
+        let aggregates = SessionAggregates {
+            session_count: 4,
+            working_days: 3,
+            total_minutes: 30 * 60,
+            longest_minutes: 15 * 60,
+        };
+
+        let stats = WorkPatternStats::calculate(
+            aggregates,
+            &spans,
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        );
+
+        assert_eq!(stats.average_start_minutes, Some(9 * 60 + 30));
+        assert_eq!(stats.average_end_minutes, Some(20 * 60 + 30));
+        assert_eq!(stats.late_starts, 2);
+        assert_eq!(stats.average_minutes_per_day(), 10 * 60);
+
+        let empty = WorkPatternStats::calculate(
+            SessionAggregates::default(),
+            &[],
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        );
+        assert_eq!(empty.average_start_minutes, None);
+        assert_eq!(empty.average_minutes_per_day(), 0);
+    }
+
+    #[test]
+    fn test_merge_close_sessions() {
+        let sessions = vec![
+            session(jst(9, 0), Some(jst(10, 0))),
+            session(jst(10, 5), Some(jst(12, 0))),
+            session(jst(13, 0), Some(jst(15, 0))),
+            session(jst(15, 3), None),
+        ];
+
+        let mut sessions = sessions;
+        sessions[0].note = Some("資料作成".to_string());
+        sessions[1].note = Some("レビュー".to_string());
+
+        let merged = merge_close_sessions(&sessions, 10);
+
+        // 5分の間隔はまとめ、60分の昼休憩と未終了セッションはまとめない
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[0].start_time, jst(9, 0));
+        assert_eq!(merged[0].end_time, Some(jst(12, 0)));
+        assert_eq!(merged[0].total_minutes, Some(175));
+        assert_eq!(merged[0].note.as_deref(), Some("資料作成 / レビュー"));
+        assert_eq!(merged[2].end_time, None);
+        assert_eq!(SessionStats::from_sessions(&merged).total_minutes, 295);
+    }
+
+    #[test]
+    fn test_pay_estimate_splits_overtime_per_day() {
+        let rate = Rate {
+            id: 1,
+            guild_id: "1".to_string(),
+            user_id: None,
+            hourly_rate: 1200,
+            overtime_rate: 1500,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let mut next_day = session(jst(9, 0), Some(jst(15, 0)));
+        next_day.date = NaiveDate::from_ymd_opt(2023, 12, 16).unwrap();
+        let sessions = vec![
+            session(jst(8, 0), Some(jst(12, 0))),
+            session(jst(13, 0), Some(jst(19, 30))),
+            next_day,
+        ];
+
+        let estimate = PayEstimate::calculate(&sessions, &rate, OvertimeThresholds::default());
+
+        // 12/15 は10時間30分勤務のうち2時間30分が時間外、12/16 は6時間勤務
+        assert_eq!(estimate.regular_minutes, 14 * 60);
+        assert_eq!(estimate.overtime_minutes, 150);
+        assert_eq!(estimate.regular_pay, 16800);
+        assert_eq!(estimate.overtime_pay, 3750);
+        assert_eq!(estimate.total_pay(), 20550);
+    }
+
+    #[test]
+    fn test_member_month_summary_from_daily_totals() {
+        let user = |id: i64, name: &str| User {
+            id,
+            discord_id: id.to_string(),
+            guild_id: "1".to_string(),
+            username: name.to_string(),
+            created_at: Utc::now(),
+        };
+        let total = |day: u32, minutes: i64, incomplete_sessions: i64| DailyTotal {
+            date: NaiveDate::from_ymd_opt(2023, 12, day).unwrap(),
+            total_minutes: minutes,
+            incomplete_sessions,
+        };
+        let totals = vec![
+            (user(1, "alice"), total(11, 600, 0)),
+            (user(1, "alice"), total(12, 0, 1)),
+            (user(2, "bob"), total(11, 480, 0)),
+            (user(2, "bob"), total(12, 480, 0)),
+            (user(2, "bob"), total(13, 540, 1)),
+        ];
+
+        let summaries =
+            MemberMonthSummary::from_daily_totals(&totals, OvertimeThresholds::default());
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].user.username, "bob");
+        assert_eq!(summaries[0].total_minutes, 1500);
+        assert_eq!(summaries[0].overtime_minutes, 60);
+        assert_eq!(summaries[0].working_days, 3);
+        assert_eq!(summaries[0].incomplete_sessions, 1);
+        assert_eq!(summaries[1].user.username, "alice");
+        assert_eq!(summaries[1].total_minutes, 600);
+        assert_eq!(summaries[1].overtime_minutes, 120);
+        assert_eq!(summaries[1].working_days, 2);
+        assert_eq!(summaries[1].incomplete_sessions, 1);
+    }
+}
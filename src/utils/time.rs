@@ -1,5 +1,5 @@
 use anyhow::Result;
-use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveTime, TimeZone, Utc};
 
 pub fn get_current_date_jst() -> NaiveDate {
     let jst_offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
@@ -12,6 +12,31 @@ pub fn get_current_datetime_jst() -> DateTime<chrono::FixedOffset> {
     Utc::now().with_timezone(&jst_offset)
 }
 
+/// 指定日を含む週の月曜日を返す
+pub fn get_week_start(date: NaiveDate) -> NaiveDate {
+    let days_since_monday = date.weekday().num_days_from_monday() as u64;
+    date.checked_sub_days(Days::new(days_since_monday))
+        .unwrap_or(date)
+}
+
+/// 指定月の初日と末日を返す
+/// 保持期間（月数）の境界日。今月の月初から `months` か月さかのぼった日より前の記録が期間切れ
+pub fn retention_cutoff(today: NaiveDate, months: u32) -> NaiveDate {
+    let first_day = today.with_day(1).unwrap_or(today);
+    first_day
+        .checked_sub_months(chrono::Months::new(months))
+        .unwrap_or(NaiveDate::MIN)
+}
+
+pub fn get_month_range(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let first_day = date.with_day(1).unwrap_or(date);
+    let last_day = first_day
+        .checked_add_months(chrono::Months::new(1))
+        .and_then(|next_month| next_month.pred_opt())
+        .unwrap_or(first_day);
+    (first_day, last_day)
+}
+
 pub fn parse_time_string(time_str: &str) -> Result<NaiveTime> {
     let time_str = time_str.trim();
 
@@ -114,17 +139,69 @@ pub fn calculate_work_duration(start: DateTime<Utc>, end: DateTime<Utc>) -> i32
     duration.num_minutes() as i32
 }
 
+/// 開始を `unit_minutes` 単位で切り上げ、終了を切り捨てた勤務時間（分、0未満にはしない）
+pub fn round_work_duration(start: DateTime<Utc>, end: DateTime<Utc>, unit_minutes: i32) -> i32 {
+    let unit = unit_minutes as i64 * 60;
+    let start = start.timestamp().div_euclid(unit) * unit
+        + if start.timestamp().rem_euclid(unit) > 0 {
+            unit
+        } else {
+            0
+        };
+    let end = end.timestamp().div_euclid(unit) * unit;
+    ((end - start) / 60).max(0) as i32
+}
+
+/// 勤務時間の表示形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DurationFormat {
+    /// 8時間30分
+    #[default]
+    Japanese,
+    /// 8h 30m
+    Compact,
+}
+
+impl DurationFormat {
+    /// Discordのロケールから表示形式を決める（日本語以外は 8h 30m）
+    pub fn from_locale(locale: Option<&str>) -> Self {
+        match locale {
+            Some(locale) if !locale.starts_with("ja") => DurationFormat::Compact,
+            _ => DurationFormat::Japanese,
+        }
+    }
+}
+
 pub fn format_duration_minutes(minutes: i32) -> String {
+    format_duration(minutes, DurationFormat::Japanese)
+}
+
+pub fn format_duration(minutes: i32, format: DurationFormat) -> String {
     let hours = minutes / 60;
     let mins = minutes % 60;
 
-    if hours > 0 {
-        format!("{}時間{}分", hours, mins)
-    } else {
-        format!("{}分", mins)
+    match format {
+        DurationFormat::Japanese if hours > 0 => format!("{}時間{}分", hours, mins),
+        DurationFormat::Japanese => format!("{}分", mins),
+        DurationFormat::Compact if hours > 0 => format!("{}h {}m", hours, mins),
+        DurationFormat::Compact => format!("{}m", mins),
     }
 }
 
+/// 分を H:MM 形式にする（24時間を超えても繰り上げない。例: 510 → "8:30"）
+pub fn format_hhmm(minutes: i32) -> String {
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// 分を時間単位の小数にする（給与計算システム向け。小数第2位まで、末尾の0は省く。例: 510 → "8.5"）
+pub fn format_decimal_hours(minutes: i32) -> String {
+    let hours = format!("{:.2}", minutes as f64 / 60.0);
+    hours
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
 pub fn format_datetime_jst(datetime: DateTime<Utc>) -> String {
     let jst_offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
     let jst_time = datetime.with_timezone(&jst_offset);
@@ -137,6 +214,75 @@ pub fn format_time_jst(datetime: DateTime<Utc>) -> String {
     jst_time.format("%H:%M").to_string()
 }
 
+/// 日本時間のUTCからの差（分）
+pub const JST_OFFSET_MINUTES: i32 = 9 * 60;
+
+/// 時刻の表示方法（`/settings` のタイムゾーンと12時間表示。既定は日本時間の24時間表示）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeDisplay {
+    pub utc_offset_minutes: i32,
+    pub clock_12h: bool,
+}
+
+impl Default for TimeDisplay {
+    fn default() -> Self {
+        Self {
+            utc_offset_minutes: JST_OFFSET_MINUTES,
+            clock_12h: false,
+        }
+    }
+}
+
+impl TimeDisplay {
+    fn local(self, datetime: DateTime<Utc>) -> DateTime<chrono::FixedOffset> {
+        let offset = chrono::FixedOffset::east_opt(self.utc_offset_minutes * 60)
+            .unwrap_or_else(|| chrono::FixedOffset::east_opt(9 * 3600).unwrap());
+        datetime.with_timezone(&offset)
+    }
+
+    /// 時刻（`09:00` / `9:00 AM`）
+    pub fn time(self, datetime: DateTime<Utc>) -> String {
+        let format = if self.clock_12h { "%-I:%M %p" } else { "%H:%M" };
+        self.local(datetime).format(format).to_string()
+    }
+
+    /// 日時とタイムゾーン（`2024-04-01 09:00:00 JST`）
+    pub fn datetime(self, datetime: DateTime<Utc>) -> String {
+        let format = if self.clock_12h {
+            "%Y-%m-%d %-I:%M:%S %p"
+        } else {
+            "%Y-%m-%d %H:%M:%S"
+        };
+        format!(
+            "{} {}",
+            self.local(datetime).format(format),
+            self.zone_name()
+        )
+    }
+
+    /// `now` のこのタイムゾーンでの日付の `time`（打刻で指定された時刻の解釈に使う）
+    pub fn today_at(self, time: NaiveTime, now: DateTime<Utc>) -> DateTime<Utc> {
+        let local = self.local(now);
+        (local.date_naive().and_time(time)
+            - chrono::Duration::seconds(local.offset().local_minus_utc() as i64))
+        .and_utc()
+    }
+
+    /// タイムゾーンの表示名（日本時間は `JST`、それ以外は `UTC+05:30` の形）
+    pub fn zone_name(self) -> String {
+        if self.utc_offset_minutes == JST_OFFSET_MINUTES {
+            return "JST".to_string();
+        }
+        let sign = if self.utc_offset_minutes < 0 {
+            '-'
+        } else {
+            '+'
+        };
+        let minutes = self.utc_offset_minutes.abs();
+        format!("UTC{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+    }
+}
+
 pub fn get_date_from_utc_timestamp(timestamp: DateTime<Utc>) -> NaiveDate {
     let jst_offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
     let jst_time = timestamp.with_timezone(&jst_offset);
@@ -149,3 +295,139 @@ pub fn get_date_from_utc_timestamp(timestamp: DateTime<Utc>) -> NaiveDate {
     );
     date
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_retention_cutoff() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        assert_eq!(
+            retention_cutoff(today, 1),
+            NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()
+        );
+        assert_eq!(
+            retention_cutoff(today, 12),
+            NaiveDate::from_ymd_opt(2023, 3, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_time_display() {
+        let at = Utc.with_ymd_and_hms(2024, 4, 1, 4, 5, 0).unwrap();
+        let jst = TimeDisplay::default();
+        assert_eq!(jst.time(at), format_time_jst(at));
+        assert_eq!(jst.datetime(at), format_datetime_jst(at));
+
+        let new_york = TimeDisplay {
+            utc_offset_minutes: -4 * 60,
+            clock_12h: true,
+        };
+        assert_eq!(new_york.time(at), "12:05 AM");
+        assert_eq!(new_york.datetime(at), "2024-04-01 12:05:00 AM UTC-04:00");
+        assert_eq!(
+            TimeDisplay {
+                utc_offset_minutes: 330,
+                clock_12h: false,
+            }
+            .time(at),
+            "09:35"
+        );
+    }
+
+    #[test]
+    fn test_today_at() {
+        // 2024-04-01 04:05 UTC はニューヨークではまだ3月31日
+        let now = Utc.with_ymd_and_hms(2024, 4, 1, 4, 5, 0).unwrap();
+        let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+        assert_eq!(
+            TimeDisplay::default().today_at(nine, now),
+            Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap()
+        );
+        let new_york = TimeDisplay {
+            utc_offset_minutes: -4 * 60,
+            clock_12h: false,
+        };
+        assert_eq!(
+            new_york.today_at(nine, now),
+            Utc.with_ymd_and_hms(2024, 3, 31, 13, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_round_work_duration() {
+        let at = |hour, minute, second| {
+            Utc.with_ymd_and_hms(2024, 4, 1, hour, minute, second)
+                .unwrap()
+        };
+
+        assert_eq!(round_work_duration(at(0, 7, 0), at(9, 8, 0), 15), 525);
+        assert_eq!(round_work_duration(at(0, 0, 0), at(9, 0, 0), 15), 540);
+        assert_eq!(round_work_duration(at(0, 0, 1), at(9, 0, 0), 15), 525);
+        assert_eq!(round_work_duration(at(0, 5, 0), at(0, 10, 0), 15), 0);
+        assert_eq!(round_work_duration(at(0, 7, 0), at(9, 8, 0), 1), 541);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(510, DurationFormat::Japanese), "8時間30分");
+        assert_eq!(format_duration(45, DurationFormat::Japanese), "45分");
+        assert_eq!(format_duration(510, DurationFormat::Compact), "8h 30m");
+        assert_eq!(format_duration(45, DurationFormat::Compact), "45m");
+        assert_eq!(format_decimal_hours(510), "8.5");
+        assert_eq!(format_decimal_hours(500), "8.33");
+        assert_eq!(format_decimal_hours(480), "8");
+        assert_eq!(format_decimal_hours(0), "0");
+        assert_eq!(format_hhmm(510), "8:30");
+        assert_eq!(format_hhmm(1505), "25:05");
+
+        assert_eq!(
+            DurationFormat::from_locale(Some("ja")),
+            DurationFormat::Japanese
+        );
+        assert_eq!(
+            DurationFormat::from_locale(Some("en-US")),
+            DurationFormat::Compact
+        );
+        assert_eq!(DurationFormat::from_locale(None), DurationFormat::Japanese);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_time_string_accepts_hh_mm(hour in 0u32..48, minute in 0u32..60) {
+            let input = format!(" {:02}:{:02} ", hour, minute);
+            let expected = NaiveTime::from_hms_opt(hour % 24, minute, 0).unwrap();
+            prop_assert_eq!(parse_time_string(&input).unwrap(), expected);
+        }
+
+        #[test]
+        fn prop_parse_time_string_rejects_out_of_range(hour in 48u32..1000, minute in 0u32..1000) {
+            let input = format!("{:02}:{:02}", hour, minute);
+            prop_assert!(parse_time_string(&input).is_err());
+        }
+
+        #[test]
+        fn prop_parse_time_string_rejects_invalid_minutes(hour in 0u32..48, minute in 60u32..1000) {
+            let input = format!("{:02}:{:02}", hour, minute);
+            prop_assert!(parse_time_string(&input).is_err());
+        }
+
+        #[test]
+        fn prop_parse_time_with_day_info_flags_next_day(hour in 0u32..48, minute in 0u32..60) {
+            let input = format!("{:02}:{:02}", hour, minute);
+            let (time, is_next_day) = parse_time_with_day_info(&input).unwrap();
+            prop_assert_eq!(time, NaiveTime::from_hms_opt(hour % 24, minute, 0).unwrap());
+            prop_assert_eq!(is_next_day, hour >= 24);
+        }
+
+        #[test]
+        fn prop_parse_functions_agree_on_arbitrary_input(input in "\\PC{0,12}") {
+            // 任意の入力でパニックせず、両関数の時刻の解釈が一致する
+            let time = parse_time_string(&input).ok();
+            let with_day_info = parse_time_with_day_info(&input).ok().map(|(time, _)| time);
+            prop_assert_eq!(time, with_day_info);
+        }
+    }
+}
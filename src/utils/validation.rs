@@ -2,13 +2,124 @@ use crate::utils::time::{parse_time_string, parse_time_with_day_info};
 use anyhow::Result;
 use chrono::{NaiveDate, NaiveTime};
 
+/// セッションのメモの最大文字数
+pub const MAX_SESSION_NOTE_CHARS: usize = 200;
+
+/// 時刻・日付の入力を半角の `HH:MM`・`YYYY-MM-DD` の形にそろえる
+///
+/// スマートフォンの日本語キーボードで入る全角の数字・記号（`０９：３０`）や、
+/// `9時30分`・`2024年4月1日`・`2024/04/01` のような区切りを受け付けるため、検証の前に通す
+pub fn normalize_input(input: &str) -> String {
+    let mut normalized: String = input
+        .trim()
+        .chars()
+        .filter_map(|c| match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32),
+            '：' | '時' => Some(':'),
+            '／' | '/' | '－' | '−' | 'ー' | '.' | '．' | '年' | '月' => Some('-'),
+            '分' | '秒' | '日' => None,
+            c => Some(c),
+        })
+        .collect();
+    // 「9時」「2024年4月」のように末尾の単位だけで終わる入力
+    if normalized.ends_with(':') {
+        normalized.push_str("00");
+    }
+    normalized.trim_end_matches('-').trim().to_string()
+}
+
 pub fn validate_time_format(time_str: &str) -> Result<NaiveTime> {
-    parse_time_string(time_str)
+    parse_time_string(&normalize_input(time_str))
 }
 
 /// Validate time format and return time with next-day information for night shifts
 pub fn validate_time_format_with_day_info(time_str: &str) -> Result<(NaiveTime, bool)> {
-    parse_time_with_day_info(time_str)
+    parse_time_with_day_info(&normalize_input(time_str))
+}
+
+/// YYYY-MM 形式の月を検証し、その月の1日を返す
+pub fn validate_month_format(month_str: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", normalize_input(month_str)), "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("月は YYYY-MM 形式で入力してください (例: 2024-04)"))
+}
+
+/// YYYY-MM-DD 形式の日付を検証する
+pub fn validate_date_format(date_str: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(&normalize_input(date_str), "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("日付は YYYY-MM-DD 形式で入力してください (例: 2024-04-01)"))
+}
+
+/// プロジェクト名の前後の空白を除き、長さを検証する
+pub fn validate_project_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > 50 {
+        return Err(anyhow::anyhow!(
+            "プロジェクト名は1〜50文字で入力してください"
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// シフトのひな形の名前の前後の空白を除き、長さを検証する
+pub fn validate_shift_template_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > 20 {
+        return Err(anyhow::anyhow!("ひな形の名前は1〜20文字で入力してください"));
+    }
+    Ok(name.to_string())
+}
+
+/// セッションのメモの前後の空白を除き、長さを検証する（空の場合は `None`）
+pub fn validate_session_note(note: &str) -> Result<Option<String>> {
+    let note = note.trim();
+    if note.chars().count() > MAX_SESSION_NOTE_CHARS {
+        return Err(anyhow::anyhow!(
+            "メモは{}文字以内で入力してください",
+            MAX_SESSION_NOTE_CHARS
+        ));
+    }
+    Ok((!note.is_empty()).then(|| note.to_string()))
+}
+
+/// WebhookのURLの前後の空白を除き、http(s) のURLか検証する
+pub fn validate_webhook_url(url: &str) -> Result<String> {
+    let url = url.trim();
+    let has_scheme = url.starts_with("https://") || url.starts_with("http://");
+    if !has_scheme || url.len() > 500 || url.chars().any(char::is_whitespace) {
+        return Err(anyhow::anyhow!(
+            "WebhookのURLは https:// から始まる500文字以内のURLで入力してください"
+        ));
+    }
+    Ok(url.to_string())
+}
+
+/// タイムゾーンの入力（`+9`・`+09:00`・`UTC-5`・`GMT+5:30`・`JST`）を検証し、UTCからの差（分）を返す
+pub fn validate_utc_offset(input: &str) -> Result<i32> {
+    let normalized = normalize_input(input).to_uppercase().replace('＋', "+");
+    if normalized == "JST" {
+        return Ok(crate::utils::time::JST_OFFSET_MINUTES);
+    }
+    let error = || {
+        anyhow::anyhow!("タイムゾーンは UTC からの差で入力してください (例: +09:00, UTC-5, JST)")
+    };
+    let offset = normalized
+        .strip_prefix("UTC")
+        .or_else(|| normalized.strip_prefix("GMT"))
+        .unwrap_or(&normalized)
+        .trim();
+    let (sign, offset) = match offset.chars().next() {
+        Some('+') => (1, &offset[1..]),
+        Some('-') => (-1, &offset[1..]),
+        _ => return Err(error()),
+    };
+    let (hours, minutes) = offset.split_once(':').unwrap_or((offset, "0"));
+    let (Ok(hours), Ok(minutes)) = (hours.parse::<i32>(), minutes.parse::<i32>()) else {
+        return Err(error());
+    };
+    if hours > 14 || !matches!(minutes, 0 | 30 | 45) || (hours == 14 && minutes > 0) {
+        return Err(error());
+    }
+    Ok(sign * (hours * 60 + minutes))
 }
 
 pub fn validate_time_order(start_time: NaiveTime, end_time: NaiveTime) -> Result<()> {
@@ -59,3 +170,50 @@ pub fn validate_reasonable_past_date(date: NaiveDate) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_utc_offset() {
+        assert_eq!(validate_utc_offset("+9").unwrap(), 540);
+        assert_eq!(validate_utc_offset("JST").unwrap(), 540);
+        assert_eq!(validate_utc_offset("utc-05:00").unwrap(), -300);
+        assert_eq!(validate_utc_offset("GMT+5:30").unwrap(), 330);
+        assert_eq!(validate_utc_offset("＋０９：００").unwrap(), 540);
+        for input in ["9", "+15", "+09:20", "Asia/Tokyo", ""] {
+            assert!(validate_utc_offset(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_normalize_full_width_and_japanese_separators() {
+        let time = |input: &str| validate_time_format(input).unwrap();
+        assert_eq!(
+            time("０９：３０"),
+            NaiveTime::from_hms_opt(9, 30, 0).unwrap()
+        );
+        assert_eq!(time("9時30分"), NaiveTime::from_hms_opt(9, 30, 0).unwrap());
+        assert_eq!(time("１８時"), NaiveTime::from_hms_opt(18, 0, 0).unwrap());
+        assert_eq!(
+            validate_time_format_with_day_info("２５：１０").unwrap(),
+            (NaiveTime::from_hms_opt(1, 10, 0).unwrap(), true)
+        );
+
+        let date = NaiveDate::from_ymd_opt(2024, 4, 1).unwrap();
+        for input in [
+            "２０２４－０４－０１",
+            "2024/04/01",
+            "2024年4月1日",
+            "２０２４．４．１",
+        ] {
+            assert_eq!(validate_date_format(input).unwrap(), date, "{}", input);
+        }
+        assert_eq!(validate_month_format("2024年4月").unwrap(), date);
+        assert_eq!(validate_month_format("２０２４／０４").unwrap(), date);
+
+        assert!(validate_time_format("9時半").is_err());
+        assert!(validate_date_format("4月1日").is_err());
+    }
+}